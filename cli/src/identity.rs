@@ -31,7 +31,7 @@ fn store_credential_bundle_in_keystore(
 impl Identity {
     pub(crate) fn new(ciphersuite: Ciphersuite, crypto: &OpenMlsRustCrypto, id: &[u8]) -> Self {
         let credential_bundle = CredentialBundle::new(
-            id.to_vec(),
+            id.to_vec().into(),
             CredentialType::Basic,
             SignatureScheme::from(ciphersuite),
             crypto,
@@ -77,6 +77,6 @@ impl Identity {
 
     /// Get the plain credential as byte vector.
     pub fn credential(&self) -> &[u8] {
-        self.credential.credential().identity()
+        self.credential.credential().identity().as_slice()
     }
 }
@@ -213,7 +213,14 @@ impl User {
         log::trace!("done with messages ...");
 
         for c in self.backend.list_clients()?.drain(..) {
-            if c.id != self.identity.borrow().credential.credential().identity()
+            if c.id
+                != self
+                    .identity
+                    .borrow()
+                    .credential
+                    .credential()
+                    .identity()
+                    .as_slice()
                 && self
                     .contacts
                     .insert(
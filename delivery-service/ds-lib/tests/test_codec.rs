@@ -10,7 +10,7 @@ fn test_client_info() {
     let client_name = "Client1";
     let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
     let credential_bundle = CredentialBundle::new(
-        client_name.as_bytes().to_vec(),
+        client_name.as_bytes().into(),
         CredentialType::Basic,
         SignatureScheme::from(ciphersuite),
         crypto,
@@ -13,7 +13,12 @@ fn generate_credential(
     signature_scheme: SignatureScheme,
     crypto_backend: &impl OpenMlsCryptoProvider,
 ) -> Result<Credential, CredentialError> {
-    let cb = CredentialBundle::new(identity, credential_type, signature_scheme, crypto_backend)?;
+    let cb = CredentialBundle::new(
+        identity.into(),
+        credential_type,
+        signature_scheme,
+        crypto_backend,
+    )?;
     let credential = cb.credential().clone();
     crypto_backend
         .key_store()
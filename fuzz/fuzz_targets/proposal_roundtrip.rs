@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use openmls::prelude::*;
+
+fuzz_target!(|proposal: Proposal| {
+    let serialized = proposal
+        .tls_serialize_detached()
+        .expect("Error serializing proposal.");
+    let deserialized = Proposal::tls_deserialize(&mut serialized.as_slice())
+        .expect("Error deserializing proposal.");
+    assert_eq!(proposal, deserialized);
+});
@@ -394,7 +394,7 @@ impl MlsClient for MlsClientImpl {
 
         let ciphersuite = Ciphersuite::try_from(create_group_request.cipher_suite as u16).unwrap();
         let credential_bundle = CredentialBundle::new(
-            "OpenMLS".bytes().collect(),
+            "OpenMLS".into(),
             CredentialType::Basic,
             SignatureScheme::from(ciphersuite),
             &self.crypto_provider,
@@ -436,7 +436,7 @@ impl MlsClient for MlsClientImpl {
 
         let ciphersuite = *to_ciphersuite(create_kp_request.cipher_suite)?;
         let credential_bundle = CredentialBundle::new(
-            "OpenMLS".bytes().collect(),
+            "OpenMLS".into(),
             CredentialType::Basic,
             ciphersuite.signature_algorithm(),
             &self.crypto_provider,
@@ -556,7 +556,7 @@ impl MlsClient for MlsClientImpl {
         let state_auth_secret = interop_group.group.epoch_authenticator();
 
         Ok(Response::new(StateAuthResponse {
-            state_auth_secret: state_auth_secret.as_slice().to_vec(),
+            state_auth_secret: state_auth_secret.expose().to_vec(),
         }))
     }
 
@@ -578,7 +578,9 @@ impl MlsClient for MlsClientImpl {
                 &export_request.context,
                 export_request.key_length as usize,
             )
-            .map_err(into_status)?;
+            .map_err(into_status)?
+            .expose()
+            .to_vec();
 
         Ok(Response::new(ExportResponse { exported_secret }))
     }
@@ -1,4 +1,4 @@
-use openmls_traits::key_store::{FromKeyStoreValue, OpenMlsKeyStore, ToKeyStoreValue};
+use openmls_traits::key_store::{FromKeyStoreValue, KeyStoreId, OpenMlsKeyStore, ToKeyStoreValue};
 use std::{collections::HashMap, sync::RwLock};
 
 type EpochStoreIndex = (Vec<u8>, Vec<u8>, u64); // Identity, GroupId and GroupEpoch
@@ -104,6 +104,24 @@ impl OpenMlsKeyStore for MemoryKeyStore {
         );
         Ok(())
     }
+
+    fn ids(&self) -> Vec<KeyStoreId> {
+        let values = self.values.read().unwrap();
+        let epoch_store = self.epoch_values.read().unwrap();
+        values
+            .keys()
+            .map(|k| KeyStoreId::Value(k.clone()))
+            .chain(
+                epoch_store
+                    .keys()
+                    .map(|(identity, group_id, epoch)| KeyStoreId::EpochKeys {
+                        identity: identity.clone(),
+                        group_id: group_id.clone(),
+                        epoch: *epoch,
+                    }),
+            )
+            .collect()
+    }
 }
 
 /// Errors thrown by the key store.
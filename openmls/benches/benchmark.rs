@@ -19,7 +19,7 @@ fn criterion_kp_bundle(c: &mut Criterion, backend: &impl OpenMlsCryptoProvider)
                 b.iter_with_setup(
                     || {
                         CredentialBundle::new(
-                            vec![1, 2, 3],
+                            vec![1, 2, 3].into(),
                             CredentialType::Basic,
                             ciphersuite.signature_algorithm(),
                             backend,
@@ -21,6 +21,7 @@ pub(crate) const MIN_TREE_SIZE: u32 = 1;
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct LeafNodeIndex(u32);
 
 impl LeafNodeIndex {
@@ -1,3 +1,5 @@
+use zeroize::Zeroize;
+
 use super::*;
 
 /// The default NONCE size in bytes.
@@ -26,6 +28,11 @@ impl AeadKey {
         }
     }
 
+    /// Overwrite the key with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        self.value.as_mut_slice().zeroize();
+    }
+
     #[cfg(test)]
     /// Generate a random AEAD Key
     pub(crate) fn random(ciphersuite: Ciphersuite, rng: &impl OpenMlsRand) -> Self {
@@ -85,6 +92,11 @@ impl AeadNonce {
         Self(nonce)
     }
 
+    /// Overwrite the nonce with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+
     /// Generate a new random nonce.
     ///
     /// **NOTE: This has to wait until it can acquire the lock to get randomness!**
@@ -0,0 +1,34 @@
+//! # FIPS mode
+//!
+//! With the `fips` feature, OpenMLS only accepts ciphersuites and signature
+//! schemes that are built from FIPS approved algorithms. Creating key
+//! packages, credentials or groups, and joining groups with any other
+//! algorithm fails with a `NotFipsApproved` error.
+//!
+//! Note that this only restricts the algorithm selection of OpenMLS. A FIPS
+//! validated crypto provider, such as `openmls_aws_lc_crypto` with its `fips`
+//! feature, is still required.
+
+use openmls_traits::types::{Ciphersuite, SignatureScheme};
+
+/// The ciphersuites that are approved in FIPS mode.
+pub const FIPS_APPROVED_CIPHERSUITES: &[Ciphersuite] = &[
+    Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+    Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+    Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+];
+
+/// Returns `true` if the `ciphersuite` is approved in FIPS mode.
+pub fn is_approved_ciphersuite(ciphersuite: Ciphersuite) -> bool {
+    FIPS_APPROVED_CIPHERSUITES.contains(&ciphersuite)
+}
+
+/// Returns `true` if the `signature_scheme` is approved in FIPS mode.
+pub fn is_approved_signature_scheme(signature_scheme: SignatureScheme) -> bool {
+    matches!(
+        signature_scheme,
+        SignatureScheme::ECDSA_SECP256R1_SHA256
+            | SignatureScheme::ECDSA_SECP384R1_SHA384
+            | SignatureScheme::ECDSA_SECP521R1_SHA512
+    )
+}
@@ -51,7 +51,9 @@ const PROPOSAL_REF_LABEL: &[u8; 26] = b"MLS 1.0 Proposal Reference";
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct HashReference {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     value: VLBytes,
 }
 
@@ -4,7 +4,9 @@ use super::*;
 ///
 /// opaque MAC<V>;
 #[derive(Debug, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct Mac {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     pub(crate) mac_value: VLBytes,
 }
 
@@ -23,6 +23,8 @@ mod reuse_guard;
 mod secret;
 
 // Public
+#[cfg(feature = "fips")]
+pub mod fips;
 pub mod hash_ref;
 pub mod signable;
 pub mod signature;
@@ -48,7 +50,7 @@ pub type HpkePrivateKey = VLBytes;
 /// Compare two byte slices in a way that's hopefully not optimised out by the
 /// compiler.
 #[inline(never)]
-fn equal_ct(a: &[u8], b: &[u8]) -> bool {
+pub(crate) fn equal_ct(a: &[u8], b: &[u8]) -> bool {
     let mut diff = 0u8;
     for (l, r) in a.iter().zip(b.iter()) {
         diff |= l ^ r;
@@ -1,3 +1,5 @@
+use zeroize::Zeroize;
+
 use super::kdf_label::KdfLabel;
 use super::*;
 
@@ -108,21 +110,19 @@ impl Secret {
         let ikm = ikm_option.into().unwrap_or(&zero_secret);
         log_crypto!(trace, "  ikm:  {:x?}", ikm.value);
 
-        // We don't return an error here to keep the error propagation from
-        // blowing up. If this fails, something in the library is really wrong
-        // and we can't recover from it.
-        assert!(
-            self.mls_version == ikm.mls_version,
-            "{} != {}",
-            self.mls_version,
-            ikm.mls_version
-        );
-        assert!(
-            self.ciphersuite == ikm.ciphersuite,
-            "{} != {}",
-            self.ciphersuite,
-            ikm.ciphersuite
-        );
+        // If this fails, something in the library is really wrong. We still
+        // return an error instead of panicking, since the secrets might
+        // originate from untrusted input.
+        if self.mls_version != ikm.mls_version || self.ciphersuite != ikm.ciphersuite {
+            log::error!(
+                "Incompatible secrets in HKDF extract: {} ({}) != {} ({})",
+                self.ciphersuite,
+                self.mls_version,
+                ikm.ciphersuite,
+                ikm.mls_version
+            );
+            return Err(CryptoError::CryptoLibraryError);
+        }
 
         Ok(Self {
             value: backend.crypto().hkdf_extract(
@@ -213,6 +213,18 @@ impl Secret {
         &self.value
     }
 
+    /// Moves the secret value out of the secret without copying it and leaves
+    /// an empty value behind.
+    pub(crate) fn take_value(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.value)
+    }
+
+    /// Overwrite the secret value with zeros, e.g. when the secret has to be
+    /// destroyed before it is dropped.
+    pub(crate) fn zeroize(&mut self) {
+        self.value.as_mut_slice().zeroize();
+    }
+
     /// Returns the ciphersuite of the secret
     pub(crate) fn ciphersuite(&self) -> Ciphersuite {
         self.ciphersuite
@@ -32,7 +32,10 @@ use openmls_traits::{crypto::OpenMlsCrypto, types::SignatureScheme, OpenMlsCrypt
 use thiserror::Error;
 use tls_codec::Serialize;
 
-use crate::ciphersuite::{SignContent, Signature, SignaturePrivateKey, SignaturePublicKey};
+use crate::{
+    ciphersuite::{SignContent, Signature, SignaturePrivateKey, SignaturePublicKey},
+    error::error_codes,
+};
 
 /// Signature generation and verification errors.
 /// The only information relayed with this error is whether the signature
@@ -47,6 +50,11 @@ pub enum SignatureError {
     SigningError,
 }
 
+error_codes!(SignatureError: 800 {
+    VerificationError = 1,
+    SigningError = 2,
+});
+
 /// This trait must be implemented by all structs that contain a self-signature.
 pub trait SignedStruct<T> {
     /// Build a signed struct version from the payload struct.
@@ -10,7 +10,9 @@ use super::*;
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Signature {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     value: VLBytes,
 }
 
@@ -62,7 +64,9 @@ pub struct SignaturePrivateKey {
 #[derive(
     Eq, PartialEq, Hash, Debug, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SignaturePublicKey {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     pub(in crate::ciphersuite) value: VLBytes,
 }
 
@@ -207,6 +211,30 @@ impl SignatureKeypair {
         })
     }
 
+    /// Imports a [`SignatureKeypair`] from the raw `private_key` of the
+    /// [`SignatureScheme`]. The public key is derived by the backend.
+    #[cfg(feature = "key-formats")]
+    pub(crate) fn import(
+        signature_scheme: SignatureScheme,
+        private_key: &[u8],
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<SignatureKeypair, CryptoError> {
+        let (sk, pk) = backend
+            .crypto()
+            .signature_key_import(signature_scheme, private_key)?;
+
+        Ok(SignatureKeypair {
+            private_key: SignaturePrivateKey {
+                value: sk,
+                signature_scheme,
+            },
+            public_key: OpenMlsSignaturePublicKey {
+                value: pk.into(),
+                signature_scheme,
+            },
+        })
+    }
+
     /// Create a [`SignatureKeypair`] from a public and a private key.
     #[cfg(any(feature = "test-utils", test))]
     pub fn from_parts(
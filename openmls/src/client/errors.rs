@@ -0,0 +1,59 @@
+//! # MlsClient errors
+//!
+//! `MlsClientError` is thrown on errors handling an [`MlsClient`](super::MlsClient).
+
+use thiserror::Error;
+
+use crate::{
+    error::{error_codes, LibraryError},
+    group::errors::{MergeCommitError, NewGroupError, ProcessMessageError, WelcomeError},
+    key_packages::errors::KeyPackageNewError,
+};
+
+/// MlsClient error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum MlsClientError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The client has no credential for the signature scheme of the ciphersuite.
+    #[error("The client has no credential for the signature scheme of the ciphersuite.")]
+    NoMatchingCredential,
+    /// The client is not a member of the group the message was sent in.
+    #[error("The client is not a member of the group the message was sent in.")]
+    UnknownGroup,
+    /// The message is neither a Welcome nor a handshake or application message.
+    #[error("The message is neither a Welcome nor a handshake or application message.")]
+    UnsupportedMessageType,
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+    /// See [`KeyPackageNewError`] for more details.
+    #[error(transparent)]
+    KeyPackageNewError(#[from] KeyPackageNewError<KeyStoreError>),
+    /// See [`NewGroupError`] for more details.
+    #[error(transparent)]
+    NewGroupError(#[from] NewGroupError<KeyStoreError>),
+    /// See [`WelcomeError`] for more details.
+    #[error(transparent)]
+    WelcomeError(#[from] WelcomeError<KeyStoreError>),
+    /// See [`ProcessMessageError`] for more details.
+    #[error(transparent)]
+    ProcessMessageError(#[from] ProcessMessageError),
+    /// See [`MergeCommitError`] for more details.
+    #[error(transparent)]
+    MergeCommitError(#[from] MergeCommitError<KeyStoreError>),
+}
+
+error_codes!(MlsClientError<KeyStoreError>: 6000 {
+    LibraryError = 1,
+    NoMatchingCredential = 2,
+    UnknownGroup = 3,
+    UnsupportedMessageType = 4,
+    KeyStoreError = 5,
+    KeyPackageNewError = 6,
+    NewGroupError = 7,
+    WelcomeError = 8,
+    ProcessMessageError = 9,
+    MergeCommitError = 10,
+});
@@ -0,0 +1,333 @@
+//! # MLS Client
+//!
+//! This module contains the [`MlsClient`], which manages the state of a client
+//! across all of its groups.
+//!
+//! Applications that are members of more than one group usually need a layer
+//! on top of [`MlsGroup`] that keeps track of the credentials of the client
+//! and of its groups, routes incoming messages to the right group and
+//! persists all of it together. The [`MlsClient`] is such a layer.
+
+use std::{
+    collections::HashMap,
+    io::{Error, Read, Write},
+};
+
+use openmls_traits::{
+    key_store::OpenMlsKeyStore,
+    types::{Ciphersuite, SignatureScheme},
+    OpenMlsCryptoProvider,
+};
+use tls_codec::Serialize as TlsSerializeTrait;
+
+use crate::{
+    credentials::{Credential, CredentialBundle},
+    error::LibraryError,
+    framing::{MlsMessageIn, MlsMessageInBody, ProcessedMessage, ProcessedMessageContent},
+    group::{config::CryptoConfig, errors::ProcessMessageError, GroupId, MlsGroup, MlsGroupConfig},
+    key_packages::{KeyPackage, KeyPackageInventory},
+};
+
+// Private
+mod ser;
+
+// Public
+pub mod errors;
+
+// Tests
+#[cfg(test)]
+mod tests;
+
+use errors::*;
+use ser::*;
+
+/// The result of processing an incoming message with
+/// [`MlsClient::process_message()`].
+#[derive(Debug)]
+pub enum MlsClientEvent {
+    /// The client joined the group with the given ID from a Welcome.
+    Joined(GroupId),
+    /// An application message or a proposal was processed. Proposals are
+    /// already stored as pending proposals of the group.
+    Message(ProcessedMessage),
+    /// A commit was merged in the group with the given ID. See
+    /// [`MlsGroup::membership_diff()`] for the changes it made, and
+    /// [`MlsGroup::is_active()`] to find out whether the client was removed.
+    Merged(GroupId),
+}
+
+/// A client that is a member of several [`MlsGroup`]s.
+///
+/// The client holds one [`Credential`] per signature scheme, the
+/// [`MlsGroupConfig`] used for groups it joins, and its groups by
+/// [`GroupId`]. The corresponding [`CredentialBundle`]s, as well as the
+/// private key material of the key packages of the client, are in the key
+/// store of the backend, which is passed to the functions that need it, like
+/// for [`MlsGroup`].
+///
+/// [`MlsClient::process_message()`] handles all messages the client receives
+/// from the Delivery Service: it joins groups from Welcome messages, and
+/// routes handshake and application messages to their group, where proposals
+/// are stored and commits are merged. The own commits of the client have to
+/// be merged with [`MlsGroup::merge_pending_commit()`] instead.
+///
+/// [`MlsClient::save()`] persists the credentials, the configuration and all
+/// groups at once. Like the ones in the [`MlsGroupConfig`], callbacks set on
+/// the groups are not persisted.
+#[derive(Debug)]
+pub struct MlsClient {
+    credentials: HashMap<SignatureScheme, Credential>,
+    mls_group_config: MlsGroupConfig,
+    groups: HashMap<GroupId, MlsGroup>,
+}
+
+impl MlsClient {
+    /// Create a new [`MlsClient`] without credentials and groups. The
+    /// `mls_group_config` is used for the groups the client joins from a
+    /// Welcome.
+    pub fn new(mls_group_config: MlsGroupConfig) -> Self {
+        Self {
+            credentials: HashMap::new(),
+            mls_group_config,
+            groups: HashMap::new(),
+        }
+    }
+
+    // === Credentials ===
+
+    /// Add a credential to the client. The `credential_bundle` is stored in
+    /// the key store of the `backend`, and its credential is used for all
+    /// ciphersuites with the same signature scheme. It replaces a previous
+    /// credential with the same signature scheme.
+    pub fn add_credential<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        credential_bundle: CredentialBundle,
+    ) -> Result<(), MlsClientError<KeyStore::Error>> {
+        let credential = credential_bundle.credential().clone();
+        backend
+            .key_store()
+            .store(
+                &credential
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+                &credential_bundle,
+            )
+            .map_err(MlsClientError::KeyStoreError)?;
+        self.credentials
+            .insert(credential.signature_scheme(), credential);
+        Ok(())
+    }
+
+    /// Returns the credential of the client for the `ciphersuite`, if any.
+    pub fn credential(&self, ciphersuite: Ciphersuite) -> Option<&Credential> {
+        self.credentials.get(&ciphersuite.signature_algorithm())
+    }
+
+    /// Returns an iterator over the credentials of the client.
+    pub fn credentials(&self) -> impl Iterator<Item = &Credential> {
+        self.credentials.values()
+    }
+
+    /// Returns the [`MlsGroupConfig`] used for groups the client joins.
+    pub fn mls_group_config(&self) -> &MlsGroupConfig {
+        &self.mls_group_config
+    }
+
+    // === Key packages ===
+
+    /// Generate `count` new key packages for the `ciphersuite` with the
+    /// credential of the client for the ciphersuite. The key packages are
+    /// stored in the key store of the `backend` until a Welcome consumes
+    /// them, and counted in the [`KeyPackageInventory`].
+    pub fn generate_key_packages<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+        count: usize,
+    ) -> Result<Vec<KeyPackage>, MlsClientError<KeyStore::Error>> {
+        let credential_bundle = self.credential_bundle(backend, ciphersuite)?;
+        let crypto_config = CryptoConfig {
+            ciphersuite,
+            version: self.mls_group_config.crypto_config().version,
+        };
+        (0..count)
+            .map(|_| {
+                KeyPackage::builder()
+                    .build(crypto_config, backend, &credential_bundle)
+                    .map_err(MlsClientError::from)
+            })
+            .collect()
+    }
+
+    /// Returns the [`KeyPackageInventory`] with the number of unused key
+    /// packages of the client per ciphersuite.
+    pub fn key_package_inventory<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> KeyPackageInventory {
+        KeyPackageInventory::load(backend)
+    }
+
+    // === Groups ===
+
+    /// Create a new group with the `mls_group_config` and the credential of
+    /// the client for its ciphersuite. Returns the new group.
+    pub fn create_group<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+    ) -> Result<&mut MlsGroup, MlsClientError<KeyStore::Error>> {
+        let credential = self
+            .credential(mls_group_config.crypto_config().ciphersuite)
+            .ok_or(MlsClientError::NoMatchingCredential)?;
+        let group = MlsGroup::new(backend, mls_group_config, credential.signature_key())?;
+        Ok(self.insert_group(group))
+    }
+
+    /// Returns the group with the given `group_id`, if any.
+    pub fn group(&self, group_id: &GroupId) -> Option<&MlsGroup> {
+        self.groups.get(group_id)
+    }
+
+    /// Returns the group with the given `group_id` as mutable reference, if
+    /// any.
+    pub fn group_mut(&mut self, group_id: &GroupId) -> Option<&mut MlsGroup> {
+        self.groups.get_mut(group_id)
+    }
+
+    /// Returns an iterator over all groups of the client.
+    pub fn groups(&self) -> impl Iterator<Item = &MlsGroup> {
+        self.groups.values()
+    }
+
+    /// Returns an iterator over all groups of the client as mutable
+    /// references.
+    pub fn groups_mut(&mut self) -> impl Iterator<Item = &mut MlsGroup> {
+        self.groups.values_mut()
+    }
+
+    /// Removes the group with the given `group_id` from the client, e.g.
+    /// after the client left or was removed from it, and returns it.
+    pub fn remove_group(&mut self, group_id: &GroupId) -> Option<MlsGroup> {
+        self.groups.remove(group_id)
+    }
+
+    // === Processing ===
+
+    /// Processes an incoming message from the Delivery Service.
+    ///
+    /// - A Welcome is used to join the group. Since the Welcome has to contain
+    ///   the ratchet tree extension, groups must use the
+    ///   `use_ratchet_tree_extension` flag. A group the client was already a
+    ///   member of is replaced by the joined group.
+    /// - A handshake or application message is processed by the group it was
+    ///   sent in, if it uses the protocol version of the group. Proposals are
+    ///   stored as pending proposals and commits are merged.
+    ///
+    /// Returns an [`MlsClientEvent`] describing the result, or an error if
+    /// the message couldn't be processed. In that case, the state of the
+    /// client didn't change.
+    pub fn process_message<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        message: MlsMessageIn,
+    ) -> Result<MlsClientEvent, MlsClientError<KeyStore::Error>> {
+        let version = message.version();
+        let protocol_message = match message.extract() {
+            MlsMessageInBody::Welcome(welcome) => {
+                let group =
+                    MlsGroup::new_from_welcome(backend, &self.mls_group_config, welcome, None)?;
+                let group_id = group.group_id().clone();
+                self.insert_group(group);
+                return Ok(MlsClientEvent::Joined(group_id));
+            }
+            MlsMessageInBody::PublicMessage(message) => message.into(),
+            MlsMessageInBody::PrivateMessage(message) => message.into(),
+            MlsMessageInBody::GroupInfo(_) | MlsMessageInBody::KeyPackage(_) => {
+                return Err(MlsClientError::UnsupportedMessageType)
+            }
+        };
+
+        let group = self
+            .groups
+            .get_mut(protocol_message.group_id())
+            .ok_or(MlsClientError::UnknownGroup)?;
+        if version != group.protocol_version() {
+            return Err(ProcessMessageError::VersionMismatch.into());
+        }
+        let processed_message = group.process_message(backend, protocol_message)?;
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => (),
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                group.store_pending_proposal(*queued_proposal.clone())
+            }
+            ProcessedMessageContent::StagedCommitMessage(_) => {
+                if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+                    processed_message.into_content()
+                {
+                    group.merge_staged_commit(backend, *staged_commit)?;
+                }
+                return Ok(MlsClientEvent::Merged(group.group_id().clone()));
+            }
+        }
+        Ok(MlsClientEvent::Message(processed_message))
+    }
+
+    // === Load & save ===
+
+    /// Loads the client from persisted state.
+    pub fn load<R: Read>(reader: R) -> Result<MlsClient, Error> {
+        let serialized_mls_client: SerializedMlsClient = serde_json::from_reader(reader)?;
+        Ok(serialized_mls_client.into_mls_client())
+    }
+
+    /// Persists the state of the client, including the state of all of its
+    /// groups. The state of the groups is marked as persisted, see
+    /// [`MlsGroup::state_changed()`].
+    pub fn save<W: Write>(&mut self, writer: &mut W) -> Result<(), Error> {
+        let mls_client_state = MlsClientState {
+            credentials: self.credentials.values().collect(),
+            mls_group_config: &self.mls_group_config,
+            groups: self.groups.values().collect(),
+        };
+        let serialized_mls_client = serde_json::to_string_pretty(&mls_client_state)?;
+        writer.write_all(&serialized_mls_client.into_bytes())?;
+        self.groups.values_mut().for_each(MlsGroup::set_persisted);
+        Ok(())
+    }
+
+    // === Helpers ===
+
+    /// Reads the credential bundle of the client for the `ciphersuite` from
+    /// the key store.
+    fn credential_bundle<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+    ) -> Result<CredentialBundle, MlsClientError<KeyStore::Error>> {
+        let credential = self
+            .credential(ciphersuite)
+            .ok_or(MlsClientError::NoMatchingCredential)?;
+        backend
+            .key_store()
+            .read(
+                &credential
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+            )
+            .ok_or(MlsClientError::NoMatchingCredential)
+    }
+
+    /// Inserts the `group`, replacing a group with the same ID, and returns it.
+    fn insert_group(&mut self, group: MlsGroup) -> &mut MlsGroup {
+        let group_id = group.group_id().clone();
+        self.groups.insert(group_id.clone(), group);
+        self.groups
+            .get_mut(&group_id)
+            .expect("The group was inserted above.")
+    }
+}
@@ -0,0 +1,49 @@
+// TODO #245: Remove this once we have a proper serialization format
+#![allow(deprecated)]
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    credentials::Credential,
+    group::{ser::SerializedMlsGroup, MlsGroup, MlsGroupConfig},
+};
+
+use super::MlsClient;
+
+/// Helper struct that contains references to the serializable values of an
+/// [`MlsClient`].
+#[derive(Serialize)]
+pub(super) struct MlsClientState<'a> {
+    pub(super) credentials: Vec<&'a Credential>,
+    pub(super) mls_group_config: &'a MlsGroupConfig,
+    pub(super) groups: Vec<&'a MlsGroup>,
+}
+
+/// Helper struct that contains the deserialized values of an [`MlsClient`].
+#[derive(Deserialize)]
+pub(super) struct SerializedMlsClient {
+    credentials: Vec<Credential>,
+    mls_group_config: MlsGroupConfig,
+    groups: Vec<SerializedMlsGroup>,
+}
+
+impl SerializedMlsClient {
+    pub(super) fn into_mls_client(self) -> MlsClient {
+        MlsClient {
+            credentials: self
+                .credentials
+                .into_iter()
+                .map(|credential| (credential.signature_scheme(), credential))
+                .collect(),
+            mls_group_config: self.mls_group_config,
+            groups: self
+                .groups
+                .into_iter()
+                .map(|serialized_mls_group| {
+                    let group = serialized_mls_group.into_mls_group();
+                    (group.group_id().clone(), group)
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    credentials::CredentialType,
+    framing::*,
+    group::{InnerState, MlsGroupConfig},
+    test_utils::*,
+};
+
+use super::*;
+
+fn new_client(
+    identity: &str,
+    ciphersuite: Ciphersuite,
+    mls_group_config: &MlsGroupConfig,
+    backend: &impl OpenMlsCryptoProvider,
+) -> MlsClient {
+    let credential_bundle = CredentialBundle::new(
+        identity.into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("Could not create credential bundle.");
+    let mut client = MlsClient::new(mls_group_config.clone());
+    client
+        .add_credential(backend, credential_bundle)
+        .expect("Could not add credential.");
+    client
+}
+
+#[apply(ciphersuites_and_backends)]
+fn mls_client(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let mut alice = new_client("Alice", ciphersuite, &mls_group_config, backend);
+    let mut bob = new_client("Bob", ciphersuite, &mls_group_config, backend);
+    assert!(bob.credential(ciphersuite).is_some());
+
+    // Bob publishes key packages.
+    let initial_count = bob.key_package_inventory(backend).count(ciphersuite);
+    let bob_key_packages = bob
+        .generate_key_packages(backend, ciphersuite, 2)
+        .expect("Could not generate key packages.");
+    assert_eq!(
+        bob.key_package_inventory(backend).count(ciphersuite),
+        initial_count + 2
+    );
+
+    // Alice creates a group and adds Bob, who joins from the Welcome.
+    let alice_group = alice
+        .create_group(backend, &mls_group_config)
+        .expect("Could not create group.");
+    let group_id = alice_group.group_id().clone();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &bob_key_packages[..1])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    match bob
+        .process_message(backend, welcome.into())
+        .expect("Could not process Welcome.")
+    {
+        MlsClientEvent::Joined(joined_group_id) => assert_eq!(joined_group_id, group_id),
+        event => panic!("Expected a join, got {:?}", event),
+    }
+
+    // Application messages are routed to the group.
+    let message = alice
+        .group_mut(&group_id)
+        .expect("Alice's group is missing.")
+        .create_message(backend, b"Hello Bob")
+        .expect("Could not create message.");
+    match bob
+        .process_message(backend, message.into())
+        .expect("Could not process message.")
+    {
+        MlsClientEvent::Message(processed_message) => match processed_message.into_content() {
+            ProcessedMessageContent::ApplicationMessage(application_message) => {
+                assert_eq!(application_message.into_bytes(), b"Hello Bob")
+            }
+            _ => panic!("Expected an application message."),
+        },
+        event => panic!("Expected a message, got {:?}", event),
+    }
+
+    // Commits are merged.
+    let (commit, _welcome, _group_info) = alice
+        .group_mut(&group_id)
+        .expect("Alice's group is missing.")
+        .self_update(backend)
+        .expect("Could not update.");
+    alice
+        .group_mut(&group_id)
+        .expect("Alice's group is missing.")
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    match bob
+        .process_message(backend, commit.into())
+        .expect("Could not process commit.")
+    {
+        MlsClientEvent::Merged(merged_group_id) => assert_eq!(merged_group_id, group_id),
+        event => panic!("Expected a merged commit, got {:?}", event),
+    }
+    assert_eq!(
+        bob.group(&group_id).map(|group| group.epoch()),
+        alice.group(&group_id).map(|group| group.epoch())
+    );
+
+    // Messages of unknown groups are rejected.
+    let mut carol = new_client("Carol", ciphersuite, &mls_group_config, backend);
+    let message = alice
+        .group_mut(&group_id)
+        .expect("Alice's group is missing.")
+        .create_message(backend, b"Hello Carol")
+        .expect("Could not create message.");
+    assert_eq!(
+        carol
+            .process_message(backend, message.into())
+            .expect_err("Processed a message of an unknown group."),
+        MlsClientError::UnknownGroup
+    );
+
+    // The client can be saved and loaded.
+    let mut serialized_client = Vec::new();
+    bob.save(&mut serialized_client)
+        .expect("Could not save client.");
+    assert!(bob
+        .groups()
+        .all(|group| group.state_changed() == InnerState::Persisted));
+    let loaded_bob = MlsClient::load(serialized_client.as_slice()).expect("Could not load client.");
+    assert_eq!(
+        loaded_bob.credential(ciphersuite),
+        bob.credential(ciphersuite)
+    );
+    assert_eq!(
+        loaded_bob
+            .group(&group_id)
+            .map(|group| group.export_ratchet_tree()),
+        bob.group(&group_id)
+            .map(|group| group.export_ratchet_tree())
+    );
+}
@@ -9,6 +9,10 @@ impl tls_codec::Size for Credential {
             + match &self.credential {
                 MlsCredentialType::Basic(c) => c.tls_serialized_len(),
                 MlsCredentialType::X509(_) => unimplemented!(),
+                #[cfg(feature = "verifiable-credentials")]
+                MlsCredentialType::VerifiableCredential(c) => c.tls_serialized_len(),
+                #[cfg(feature = "token-bound-credentials")]
+                MlsCredentialType::TokenBoundCredential(c) => c.tls_serialized_len(),
             }
     }
 }
@@ -24,6 +28,20 @@ impl tls_codec::Serialize for Credential {
             MlsCredentialType::X509(_) => Err(tls_codec::Error::EncodingError(
                 "X509 certificates are not yet implemented.".to_string(),
             )),
+            #[cfg(feature = "verifiable-credentials")]
+            MlsCredentialType::VerifiableCredential(verifiable_credential) => {
+                let written = CredentialType::VerifiableCredential.tls_serialize(writer)?;
+                verifiable_credential
+                    .tls_serialize(writer)
+                    .map(|l| l + written)
+            }
+            #[cfg(feature = "token-bound-credentials")]
+            MlsCredentialType::TokenBoundCredential(token_bound_credential) => {
+                let written = CredentialType::TokenBoundCredential.tls_serialize(writer)?;
+                token_bound_credential
+                    .tls_serialize(writer)
+                    .map(|l| l + written)
+            }
         }
     }
 }
@@ -37,6 +55,18 @@ impl tls_codec::Deserialize for Credential {
             CredentialType::Basic => Ok(Credential::from(MlsCredentialType::Basic(
                 BasicCredential::tls_deserialize(bytes)?,
             ))),
+            #[cfg(feature = "verifiable-credentials")]
+            CredentialType::VerifiableCredential => {
+                Ok(Credential::from(MlsCredentialType::VerifiableCredential(
+                    VerifiableCredential::tls_deserialize(bytes)?,
+                )))
+            }
+            #[cfg(feature = "token-bound-credentials")]
+            CredentialType::TokenBoundCredential => {
+                Ok(Credential::from(MlsCredentialType::TokenBoundCredential(
+                    TokenBoundCredential::tls_deserialize(bytes)?,
+                )))
+            }
             _ => Err(tls_codec::Error::DecodingError(format!(
                 "{:?} can not be deserialized.",
                 credential_type
@@ -1,8 +1,11 @@
 //! Credential errors
 //!
-//! This module exposes [`CredentialError`].
+//! This module exposes [`CredentialError`] and [`IdentityError`], as well as
+//! [`KeyFormatError`] with the `key-formats` feature.
 
-use crate::error::LibraryError;
+use crate::error::{error_codes, LibraryError};
+#[cfg(feature = "key-formats")]
+use openmls_traits::types::CryptoError;
 use thiserror::Error;
 
 /// An error that occurs in methods of a [`super::Credential`].
@@ -17,4 +20,63 @@ pub enum CredentialError {
     /// Verifying the signature with this credential failed.
     #[error("Invalid signature.")]
     InvalidSignature,
+    /// The signature scheme is not approved in FIPS mode.
+    #[cfg(feature = "fips")]
+    #[error("The signature scheme is not approved in FIPS mode.")]
+    NotFipsApproved,
 }
+
+error_codes!(CredentialError: 700 {
+    LibraryError = 1,
+    UnsupportedCredentialType = 2,
+    InvalidSignature = 3,
+    #[cfg(feature = "fips")]
+    NotFipsApproved = 4,
+});
+
+/// An error that occurs when creating an [`super::Identity`].
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum IdentityError {
+    /// The identity is not a valid URI.
+    #[error("The identity is not a valid URI.")]
+    InvalidUri,
+}
+
+error_codes!(IdentityError: 750 {
+    InvalidUri = 1,
+});
+
+/// An error that occurs when importing or exporting signature keys in the
+/// PKCS#8, SubjectPublicKeyInfo or JWK formats.
+#[cfg(feature = "key-formats")]
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum KeyFormatError {
+    /// The DER encoding of the key is invalid.
+    #[error("The DER encoding of the key is invalid.")]
+    InvalidDer,
+    /// The PEM encoding of the key is invalid.
+    #[error("The PEM encoding of the key is invalid.")]
+    InvalidPem,
+    /// The JWK is invalid.
+    #[error("The JWK is invalid.")]
+    InvalidJwk,
+    /// The key algorithm or curve is not supported.
+    #[error("The key algorithm or curve is not supported.")]
+    UnsupportedKey,
+    /// The public key of the JWK doesn't match its private key.
+    #[error("The public key of the JWK doesn't match its private key.")]
+    PublicKeyMismatch,
+    /// The backend could not import the private key.
+    #[error("The backend could not import the private key: {0}")]
+    ImportFailed(CryptoError),
+}
+
+#[cfg(feature = "key-formats")]
+error_codes!(KeyFormatError: 6300 {
+    InvalidDer = 1,
+    InvalidPem = 2,
+    InvalidJwk = 3,
+    UnsupportedKey = 4,
+    PublicKeyMismatch = 5,
+    ImportFailed = 6,
+});
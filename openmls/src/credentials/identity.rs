@@ -0,0 +1,246 @@
+//! This module contains the [`Identity`] of a [`BasicCredential`](super::BasicCredential).
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    io::{Read, Write},
+};
+
+use serde::{Deserialize, Serialize};
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, Size};
+
+use super::errors::IdentityError;
+
+/// The number of characters shown at the start and at the end of a redacted
+/// identity.
+const REDACTION_VISIBLE_CHARS: usize = 1;
+
+/// The identity of a client in a [`BasicCredential`](super::BasicCredential).
+///
+/// On the wire, an identity is an opaque byte string. The typed constructors
+/// ensure that an identity is a valid user ID ([`Identity::from_user_id()`]) or
+/// URI ([`Identity::from_uri()`]), but any byte string can be used as an
+/// identity with [`Identity::from_bytes()`].
+///
+/// The [`Display`](fmt::Display) implementation redacts the identity, such
+/// that it can be logged without revealing who the client is, e.g.
+/// `mailto:a***e` for `mailto:alice@example.com`. Use
+/// [`Identity::as_slice()`] or [`Identity::as_str()`] to get the full
+/// identity.
+///
+/// ```text
+/// opaque identity<V>;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Identity(tls_codec::TlsByteVecU16);
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Identity {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::from_bytes(u.arbitrary::<Vec<u8>>()?))
+    }
+}
+
+impl Identity {
+    /// Create an [`Identity`] from a UTF-8 user ID, e.g. a user name.
+    pub fn from_user_id(user_id: &str) -> Self {
+        Self::from_bytes(user_id.as_bytes())
+    }
+
+    /// Create an [`Identity`] from a URI, e.g. `mailto:alice@example.com`.
+    ///
+    /// Returns [`IdentityError::InvalidUri`] if `uri` doesn't start with a
+    /// scheme as defined in RFC 3986 or contains whitespace or control
+    /// characters.
+    pub fn from_uri(uri: &str) -> Result<Self, IdentityError> {
+        if uri_scheme(uri).is_none() || uri.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(IdentityError::InvalidUri);
+        }
+        Ok(Self::from_bytes(uri.as_bytes()))
+    }
+
+    /// Create an [`Identity`] from arbitrary bytes.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        let bytes: Vec<u8> = bytes.into();
+        Self(bytes.into())
+    }
+
+    /// Returns the identity as byte slice.
+    pub fn as_slice(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+
+    /// Returns the identity as string slice, or `None` if it isn't valid
+    /// UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(self.as_slice()).ok()
+    }
+
+    /// Returns the scheme of the identity if it is a URI, e.g. `mailto` for
+    /// `mailto:alice@example.com`.
+    pub fn uri_scheme(&self) -> Option<&str> {
+        self.as_str().and_then(uri_scheme)
+    }
+
+    /// Returns `true` if both identities are equal ignoring the case of ASCII
+    /// letters, e.g. to compare user IDs that are not case-sensitive.
+    pub fn eq_ignore_ascii_case(&self, other: &Identity) -> bool {
+        self.as_slice().eq_ignore_ascii_case(other.as_slice())
+    }
+
+    /// Returns `true` if this identity starts with `prefix`, e.g. to check
+    /// that the identity belongs to a domain.
+    pub fn starts_with(&self, prefix: impl AsRef<[u8]>) -> bool {
+        self.as_slice().starts_with(prefix.as_ref())
+    }
+
+    /// Returns the identity as byte vector.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_slice().to_vec()
+    }
+}
+
+/// Returns the scheme of `uri` as defined in RFC 3986, i.e.
+/// `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )` followed by `:`.
+fn uri_scheme(uri: &str) -> Option<&str> {
+    let (scheme, _) = uri.split_once(':')?;
+    let mut chars = scheme.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() => (),
+        _ => return None,
+    }
+    if chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')) {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+/// Writes `value` with all but the first and last characters masked.
+fn write_redacted(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    let count = value.chars().count();
+    if count <= 2 * REDACTION_VISIBLE_CHARS + 1 {
+        return write!(f, "***");
+    }
+    let start: String = value.chars().take(REDACTION_VISIBLE_CHARS).collect();
+    let end: String = value
+        .chars()
+        .skip(count - REDACTION_VISIBLE_CHARS)
+        .collect();
+    write!(f, "{}***{}", start, end)
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_str() {
+            Some(identity) if !identity.chars().any(char::is_control) => {
+                match uri_scheme(identity) {
+                    Some(scheme) => {
+                        write!(f, "{}:", scheme)?;
+                        write_redacted(f, &identity[scheme.len() + 1..])
+                    }
+                    None => write_redacted(f, identity),
+                }
+            }
+            _ => write!(f, "<{} bytes>", self.as_slice().len()),
+        }
+    }
+}
+
+impl Hash for Identity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl AsRef<[u8]> for Identity {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl From<Vec<u8>> for Identity {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for Identity {
+    fn from(bytes: &[u8]) -> Self {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl From<&str> for Identity {
+    fn from(user_id: &str) -> Self {
+        Self::from_user_id(user_id)
+    }
+}
+
+impl From<String> for Identity {
+    fn from(user_id: String) -> Self {
+        Self::from_bytes(user_id.into_bytes())
+    }
+}
+
+impl PartialEq<[u8]> for Identity {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl PartialEq<&[u8]> for Identity {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for Identity {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for Identity {
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        self.as_slice() == *other
+    }
+}
+
+impl PartialEq<Vec<u8>> for Identity {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl PartialEq<str> for Identity {
+    fn eq(&self, other: &str) -> bool {
+        self.as_slice() == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for Identity {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_slice() == other.as_bytes()
+    }
+}
+
+impl Size for Identity {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        self.0.tls_serialized_len()
+    }
+}
+
+impl TlsSerializeTrait for Identity {
+    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        self.0.tls_serialize(writer)
+    }
+}
+
+impl TlsDeserializeTrait for Identity {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
+        tls_codec::TlsByteVecU16::tls_deserialize(bytes).map(Self)
+    }
+}
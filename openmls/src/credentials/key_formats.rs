@@ -0,0 +1,302 @@
+//! This module contains the import of signature keys from the PKCS#8 and JWK
+//! formats into a [`CredentialBundle`] and the export of their public keys in
+//! the SubjectPublicKeyInfo and JWK formats, such that MLS identities can
+//! reuse keys provisioned by existing PKI and identity tooling.
+//!
+//! Ed25519 and ECDSA P-256 keys are supported. The private keys are imported
+//! with [`OpenMlsCrypto::signature_key_import()`].
+//!
+//! This module is only available with the `key-formats` feature.
+//!
+//! [`OpenMlsCrypto::signature_key_import()`]: openmls_traits::crypto::OpenMlsCrypto::signature_key_import()
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use openmls_traits::{types::SignatureScheme, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+
+use super::{errors::KeyFormatError, CredentialBundle, Identity};
+use crate::ciphersuite::SignatureKeypair;
+
+// DER tags
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const SEQUENCE: u8 = 0x30;
+
+/// The object identifier of Ed25519 keys (1.3.101.112).
+const ED25519_OID: &[u8] = &[0x2b, 0x65, 0x70];
+/// The object identifier of elliptic curve public keys (1.2.840.10045.2.1).
+const EC_PUBLIC_KEY_OID: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+/// The object identifier of the P-256 curve (1.2.840.10045.3.1.7).
+const P256_OID: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+const PRIVATE_KEY_PEM_LABEL: &str = "PRIVATE KEY";
+const PUBLIC_KEY_PEM_LABEL: &str = "PUBLIC KEY";
+
+/// A JSON Web Key (RFC 7517) of an Ed25519 (RFC 8037) or ECDSA P-256
+/// (RFC 7518) signature key.
+#[derive(Serialize, Deserialize)]
+struct Jwk {
+    kty: String,
+    crv: String,
+    x: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    y: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    d: Option<String>,
+}
+
+impl CredentialBundle {
+    /// Creates a new [`CredentialBundle`] with a [`BasicCredential`] from an
+    /// identity and the DER encoding of a PKCS#8 private key (RFC 5208 and
+    /// RFC 5958). The public key is derived from the private key.
+    ///
+    /// Returns an error if the encoding is invalid, the key is neither an
+    /// Ed25519 nor an ECDSA P-256 key, or the backend can't import it.
+    ///
+    /// [`BasicCredential`]: super::BasicCredential
+    pub fn from_pkcs8_der(
+        identity: Identity,
+        der: &[u8],
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Self, KeyFormatError> {
+        let (signature_scheme, private_key) = parse_pkcs8(der)?;
+        let keypair = SignatureKeypair::import(signature_scheme, private_key, backend)
+            .map_err(KeyFormatError::ImportFailed)?;
+        Ok(Self::from_parts(identity, keypair))
+    }
+
+    /// Creates a new [`CredentialBundle`] with a [`BasicCredential`] from an
+    /// identity and the PEM encoding of a PKCS#8 private key, i.e. a
+    /// `PRIVATE KEY` PEM block. See [`CredentialBundle::from_pkcs8_der()`].
+    ///
+    /// [`BasicCredential`]: super::BasicCredential
+    pub fn from_pkcs8_pem(
+        identity: Identity,
+        pem: &str,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Self, KeyFormatError> {
+        Self::from_pkcs8_der(identity, &pem_decode(pem, PRIVATE_KEY_PEM_LABEL)?, backend)
+    }
+
+    /// Creates a new [`CredentialBundle`] with a [`BasicCredential`] from an
+    /// identity and a JSON Web Key with a private key (`d`), i.e. an `OKP`
+    /// key on the `Ed25519` curve or an `EC` key on the `P-256` curve.
+    ///
+    /// Returns an error if the JWK is invalid or unsupported, its public key
+    /// doesn't match its private key, or the backend can't import it.
+    ///
+    /// [`BasicCredential`]: super::BasicCredential
+    pub fn from_jwk(
+        identity: Identity,
+        jwk: &str,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Self, KeyFormatError> {
+        let jwk: Jwk = serde_json::from_str(jwk).map_err(|_| KeyFormatError::InvalidJwk)?;
+        let signature_scheme = match (jwk.kty.as_str(), jwk.crv.as_str()) {
+            ("OKP", "Ed25519") => SignatureScheme::ED25519,
+            ("EC", "P-256") => SignatureScheme::ECDSA_SECP256R1_SHA256,
+            _ => return Err(KeyFormatError::UnsupportedKey),
+        };
+        let private_key = base64url_decode(jwk.d.as_deref().ok_or(KeyFormatError::InvalidJwk)?)?;
+        let keypair = SignatureKeypair::import(signature_scheme, &private_key, backend)
+            .map_err(KeyFormatError::ImportFailed)?;
+        let credential_bundle = Self::from_parts(identity, keypair);
+
+        let public_key = credential_bundle.public_key_jwk()?;
+        let public_key: Jwk =
+            serde_json::from_str(&public_key).map_err(|_| KeyFormatError::InvalidJwk)?;
+        if base64url_decode(&public_key.x)? != base64url_decode(&jwk.x)?
+            || public_key.y.as_deref().map(base64url_decode).transpose()?
+                != jwk.y.as_deref().map(base64url_decode).transpose()?
+        {
+            return Err(KeyFormatError::PublicKeyMismatch);
+        }
+        Ok(credential_bundle)
+    }
+
+    /// Returns the DER encoding of the SubjectPublicKeyInfo (RFC 5280) of the
+    /// signature public key.
+    ///
+    /// Returns an error if the signature scheme is neither Ed25519 nor ECDSA
+    /// P-256.
+    pub fn public_key_der(&self) -> Result<Vec<u8>, KeyFormatError> {
+        let public_key = self.credential().signature_key().as_slice();
+        let algorithm = match self.credential().signature_scheme() {
+            SignatureScheme::ED25519 => der_encode(OBJECT_IDENTIFIER, ED25519_OID),
+            SignatureScheme::ECDSA_SECP256R1_SHA256 => [
+                der_encode(OBJECT_IDENTIFIER, EC_PUBLIC_KEY_OID),
+                der_encode(OBJECT_IDENTIFIER, P256_OID),
+            ]
+            .concat(),
+            _ => return Err(KeyFormatError::UnsupportedKey),
+        };
+        // The bit string has no unused bits.
+        let public_key = [&[0x00][..], public_key].concat();
+        Ok(der_encode(
+            SEQUENCE,
+            &[
+                der_encode(SEQUENCE, &algorithm),
+                der_encode(BIT_STRING, &public_key),
+            ]
+            .concat(),
+        ))
+    }
+
+    /// Returns the PEM encoding of the SubjectPublicKeyInfo of the signature
+    /// public key, i.e. a `PUBLIC KEY` PEM block. See
+    /// [`CredentialBundle::public_key_der()`].
+    pub fn public_key_pem(&self) -> Result<String, KeyFormatError> {
+        Ok(pem_encode(&self.public_key_der()?, PUBLIC_KEY_PEM_LABEL))
+    }
+
+    /// Returns the signature public key as JSON Web Key.
+    ///
+    /// Returns an error if the signature scheme is neither Ed25519 nor ECDSA
+    /// P-256.
+    pub fn public_key_jwk(&self) -> Result<String, KeyFormatError> {
+        let public_key = self.credential().signature_key().as_slice();
+        let jwk = match self.credential().signature_scheme() {
+            SignatureScheme::ED25519 => Jwk {
+                kty: "OKP".into(),
+                crv: "Ed25519".into(),
+                x: URL_SAFE_NO_PAD.encode(public_key),
+                y: None,
+                d: None,
+            },
+            SignatureScheme::ECDSA_SECP256R1_SHA256 => {
+                // The public key is an uncompressed point.
+                if public_key.len() != 65 || public_key[0] != 0x04 {
+                    return Err(KeyFormatError::UnsupportedKey);
+                }
+                Jwk {
+                    kty: "EC".into(),
+                    crv: "P-256".into(),
+                    x: URL_SAFE_NO_PAD.encode(&public_key[1..33]),
+                    y: Some(URL_SAFE_NO_PAD.encode(&public_key[33..])),
+                    d: None,
+                }
+            }
+            _ => return Err(KeyFormatError::UnsupportedKey),
+        };
+        serde_json::to_string(&jwk).map_err(|_| KeyFormatError::InvalidJwk)
+    }
+}
+
+/// Parses a PKCS#8 private key and returns its signature scheme and the raw
+/// private key.
+fn parse_pkcs8(der: &[u8]) -> Result<(SignatureScheme, &[u8]), KeyFormatError> {
+    let (private_key_info, rest) = der_decode(SEQUENCE, der)?;
+    if !rest.is_empty() {
+        return Err(KeyFormatError::InvalidDer);
+    }
+    // Version 1 (0) or version 2 (1), which may contain the public key.
+    let (version, private_key_info) = der_decode(INTEGER, private_key_info)?;
+    if version != [0] && version != [1] {
+        return Err(KeyFormatError::InvalidDer);
+    }
+    let (algorithm, private_key_info) = der_decode(SEQUENCE, private_key_info)?;
+    // The optional attributes and public key are ignored.
+    let (private_key, _) = der_decode(OCTET_STRING, private_key_info)?;
+    let (oid, parameters) = der_decode(OBJECT_IDENTIFIER, algorithm)?;
+
+    let (signature_scheme, private_key) = match oid {
+        ED25519_OID => {
+            // CurvePrivateKey ::= OCTET STRING
+            let (private_key, _) = der_decode(OCTET_STRING, private_key)?;
+            (SignatureScheme::ED25519, private_key)
+        }
+        EC_PUBLIC_KEY_OID => {
+            let (curve, _) = der_decode(OBJECT_IDENTIFIER, parameters)?;
+            if curve != P256_OID {
+                return Err(KeyFormatError::UnsupportedKey);
+            }
+            // ECPrivateKey ::= SEQUENCE { version, privateKey OCTET STRING, ... }
+            let (ec_private_key, _) = der_decode(SEQUENCE, private_key)?;
+            let (_, ec_private_key) = der_decode(INTEGER, ec_private_key)?;
+            let (private_key, _) = der_decode(OCTET_STRING, ec_private_key)?;
+            (SignatureScheme::ECDSA_SECP256R1_SHA256, private_key)
+        }
+        _ => return Err(KeyFormatError::UnsupportedKey),
+    };
+    if private_key.len() != 32 {
+        return Err(KeyFormatError::InvalidDer);
+    }
+    Ok((signature_scheme, private_key))
+}
+
+/// Decodes the DER element with the given `tag` at the start of `bytes`.
+/// Returns the content of the element and the remaining bytes.
+fn der_decode(tag: u8, bytes: &[u8]) -> Result<(&[u8], &[u8]), KeyFormatError> {
+    let (&element_tag, bytes) = bytes.split_first().ok_or(KeyFormatError::InvalidDer)?;
+    let (&length, mut bytes) = bytes.split_first().ok_or(KeyFormatError::InvalidDer)?;
+    if element_tag != tag {
+        return Err(KeyFormatError::InvalidDer);
+    }
+    let length = if length < 0x80 {
+        length as usize
+    } else {
+        // Long form with up to two length bytes.
+        let length_bytes = (length & 0x7f) as usize;
+        if length_bytes == 0 || length_bytes > 2 || bytes.len() < length_bytes {
+            return Err(KeyFormatError::InvalidDer);
+        }
+        let (length, rest) = bytes.split_at(length_bytes);
+        bytes = rest;
+        length
+            .iter()
+            .fold(0usize, |length, &byte| (length << 8) | byte as usize)
+    };
+    if bytes.len() < length {
+        return Err(KeyFormatError::InvalidDer);
+    }
+    Ok(bytes.split_at(length))
+}
+
+/// Encodes the `content` as DER element with the given `tag`.
+fn der_encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    match content.len() {
+        length if length < 0x80 => out.push(length as u8),
+        length if length <= 0xff => out.extend_from_slice(&[0x81, length as u8]),
+        length => out.extend_from_slice(&[0x82, (length >> 8) as u8, length as u8]),
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// Decodes the PEM block with the given `label`.
+fn pem_decode(pem: &str, label: &str) -> Result<Vec<u8>, KeyFormatError> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let (_, pem) = pem.split_once(&begin).ok_or(KeyFormatError::InvalidPem)?;
+    let (pem, _) = pem.split_once(&end).ok_or(KeyFormatError::InvalidPem)?;
+    let base64: String = pem.split_whitespace().collect();
+    STANDARD
+        .decode(base64)
+        .map_err(|_| KeyFormatError::InvalidPem)
+}
+
+/// Encodes the `der` bytes as PEM block with the given `label`.
+fn pem_encode(der: &[u8], label: &str) -> String {
+    let base64 = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in base64.as_bytes().chunks(64) {
+        // The base64 alphabet is ASCII.
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// Decodes base64url without padding, as used in JWKs.
+fn base64url_decode(value: &str) -> Result<Vec<u8>, KeyFormatError> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|_| KeyFormatError::InvalidJwk)
+}
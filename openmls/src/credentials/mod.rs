@@ -28,25 +28,41 @@
 //! different signature schemes in different groups.
 //!
 //! There are multiple [`CredentialType`]s, although OpenMLS currently only
-//! supports the [`BasicCredential`].
+//! supports the [`BasicCredential`], with the `verifiable-credentials`
+//! feature the [`VerifiableCredential`] and with the `token-bound-credentials`
+//! feature the [`TokenBoundCredential`].
 
 use openmls_traits::{types::SignatureScheme, OpenMlsCryptoProvider};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 #[cfg(test)]
 use tls_codec::Serialize as TlsSerializeTrait;
-use tls_codec::{TlsByteVecU16, TlsDeserialize, TlsSerialize, TlsSize};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 
 use crate::{ciphersuite::*, error::LibraryError};
 
 // Private
 mod codec;
+mod identity;
+#[cfg(feature = "key-formats")]
+mod key_formats;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "token-bound-credentials")]
+mod token_bound_credential;
+#[cfg(feature = "verifiable-credentials")]
+mod verifiable_credential;
 use errors::*;
 
 // Public
 pub mod errors;
+pub use identity::Identity;
+#[cfg(feature = "token-bound-credentials")]
+pub use token_bound_credential::{
+    TokenBinding, TokenBindingType, TokenBindingVerifier, TokenBoundCredential,
+};
+#[cfg(feature = "verifiable-credentials")]
+pub use verifiable_credential::{VerifiableCredential, VerifiableCredentialVerifier};
 
 /// CredentialType.
 ///
@@ -66,11 +82,18 @@ pub mod errors;
     TlsSize,
 )]
 #[repr(u16)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum CredentialType {
     /// A [`BasicCredential`]
     Basic = 1,
     /// An X.509 [`Certificate`]
     X509 = 2,
+    /// A [`VerifiableCredential`], from the private use range
+    #[cfg(feature = "verifiable-credentials")]
+    VerifiableCredential = 0xF000,
+    /// A [`TokenBoundCredential`], from the private use range
+    #[cfg(feature = "token-bound-credentials")]
+    TokenBoundCredential = 0xF001,
 }
 
 impl TryFrom<u16> for CredentialType {
@@ -80,6 +103,10 @@ impl TryFrom<u16> for CredentialType {
         match value {
             1 => Ok(CredentialType::Basic),
             2 => Ok(CredentialType::X509),
+            #[cfg(feature = "verifiable-credentials")]
+            0xF000 => Ok(CredentialType::VerifiableCredential),
+            #[cfg(feature = "token-bound-credentials")]
+            0xF001 => Ok(CredentialType::TokenBoundCredential),
             _ => Err("Undefined CredentialType"),
         }
     }
@@ -90,6 +117,7 @@ impl TryFrom<u16> for CredentialType {
 /// This struct contains an X.509 certificate chain.  Note that X.509
 /// certificates are not yet supported by OpenMLS.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Certificate {
     cert_data: Vec<u8>,
 }
@@ -103,6 +131,12 @@ pub enum MlsCredentialType {
     Basic(BasicCredential),
     /// An X.509 [`Certificate`]
     X509(Certificate),
+    /// A [`VerifiableCredential`]
+    #[cfg(feature = "verifiable-credentials")]
+    VerifiableCredential(VerifiableCredential),
+    /// A [`TokenBoundCredential`]
+    #[cfg(feature = "token-bound-credentials")]
+    TokenBoundCredential(TokenBoundCredential),
 }
 
 /// Credential.
@@ -115,13 +149,39 @@ pub struct Credential {
     credential: MlsCredentialType,
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Credential {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // The credential type always matches the credential.
+        let credential = if u.arbitrary()? {
+            MlsCredentialType::Basic(u.arbitrary()?)
+        } else {
+            MlsCredentialType::X509(u.arbitrary()?)
+        };
+        Ok(credential.into())
+    }
+}
+
 impl Credential {
+    /// Returns the type of the credential.
+    pub(crate) fn credential_type(&self) -> CredentialType {
+        self.credential_type
+    }
+
     /// Returns the identity of a given credential.
-    pub fn identity(&self) -> &[u8] {
+    pub fn identity(&self) -> &Identity {
         match &self.credential {
-            MlsCredentialType::Basic(basic_credential) => basic_credential.identity.as_slice(),
+            MlsCredentialType::Basic(basic_credential) => &basic_credential.identity,
             // TODO: implement getter for identity for X509 certificates. See issue #134.
             MlsCredentialType::X509(_) => panic!("X509 certificates are not yet implemented."),
+            #[cfg(feature = "verifiable-credentials")]
+            MlsCredentialType::VerifiableCredential(verifiable_credential) => {
+                verifiable_credential.identity()
+            }
+            #[cfg(feature = "token-bound-credentials")]
+            MlsCredentialType::TokenBoundCredential(token_bound_credential) => {
+                token_bound_credential.identity()
+            }
         }
     }
 
@@ -131,6 +191,14 @@ impl Credential {
             MlsCredentialType::Basic(basic_credential) => basic_credential.signature_scheme,
             // TODO: implement getter for signature scheme for X509 certificates. See issue #134.
             MlsCredentialType::X509(_) => panic!("X509 certificates are not yet implemented."),
+            #[cfg(feature = "verifiable-credentials")]
+            MlsCredentialType::VerifiableCredential(verifiable_credential) => {
+                verifiable_credential.signature_scheme()
+            }
+            #[cfg(feature = "token-bound-credentials")]
+            MlsCredentialType::TokenBoundCredential(token_bound_credential) => {
+                token_bound_credential.signature_scheme()
+            }
         }
     }
 
@@ -139,6 +207,38 @@ impl Credential {
         match &self.credential {
             MlsCredentialType::Basic(basic_credential) => &basic_credential.public_key,
             MlsCredentialType::X509(_) => panic!("X509 certificates are not yet implemented."),
+            #[cfg(feature = "verifiable-credentials")]
+            MlsCredentialType::VerifiableCredential(verifiable_credential) => {
+                verifiable_credential.signature_key()
+            }
+            #[cfg(feature = "token-bound-credentials")]
+            MlsCredentialType::TokenBoundCredential(token_bound_credential) => {
+                token_bound_credential.signature_key()
+            }
+        }
+    }
+
+    /// Returns the [`VerifiableCredential`] if this is a verifiable
+    /// credential.
+    #[cfg(feature = "verifiable-credentials")]
+    pub fn verifiable_credential(&self) -> Option<&VerifiableCredential> {
+        match &self.credential {
+            MlsCredentialType::VerifiableCredential(verifiable_credential) => {
+                Some(verifiable_credential)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the [`TokenBoundCredential`] if this is a token-bound
+    /// credential.
+    #[cfg(feature = "token-bound-credentials")]
+    pub fn token_bound_credential(&self) -> Option<&TokenBoundCredential> {
+        match &self.credential {
+            MlsCredentialType::TokenBoundCredential(token_bound_credential) => {
+                Some(token_bound_credential)
+            }
+            _ => None,
         }
     }
 }
@@ -149,6 +249,10 @@ impl From<MlsCredentialType> for Credential {
             credential_type: match mls_credential_type {
                 MlsCredentialType::Basic(_) => CredentialType::Basic,
                 MlsCredentialType::X509(_) => CredentialType::X509,
+                #[cfg(feature = "verifiable-credentials")]
+                MlsCredentialType::VerifiableCredential(_) => CredentialType::VerifiableCredential,
+                #[cfg(feature = "token-bound-credentials")]
+                MlsCredentialType::TokenBoundCredential(_) => CredentialType::TokenBoundCredential,
             },
             credential: mls_credential_type,
         }
@@ -161,8 +265,13 @@ impl From<MlsCredentialType> for Credential {
 /// `identity` to represent the client, as well as a signature public key, along
 /// with the corresponding signature scheme.
 #[derive(Debug, Clone, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct BasicCredential {
-    identity: TlsByteVecU16,
+    identity: Identity,
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::utils::arbitrary_signature_scheme)
+    )]
     signature_scheme: SignatureScheme,
     public_key: SignaturePublicKey,
 }
@@ -212,17 +321,21 @@ impl CredentialBundle {
     ///
     /// Returns an error if the given [`CredentialType`] is not supported.
     pub fn new(
-        identity: Vec<u8>,
+        identity: Identity,
         credential_type: CredentialType,
         signature_scheme: SignatureScheme,
         backend: &impl OpenMlsCryptoProvider,
     ) -> Result<Self, CredentialError> {
+        #[cfg(feature = "fips")]
+        if !crate::ciphersuite::fips::is_approved_signature_scheme(signature_scheme) {
+            return Err(CredentialError::NotFipsApproved);
+        }
         let (private_key, public_key) = SignatureKeypair::new(signature_scheme, backend)
             .map_err(LibraryError::unexpected_crypto_error)?
             .into_tuple();
         let mls_credential = match credential_type {
             CredentialType::Basic => BasicCredential {
-                identity: identity.into(),
+                identity,
                 signature_scheme,
                 public_key: public_key.into(),
             },
@@ -241,10 +354,10 @@ impl CredentialBundle {
     /// Creates a new [`CredentialBundle`] from an identity and a
     /// [`SignatureKeypair`]. Note that only [`BasicCredential`] is currently
     /// supported.
-    pub fn from_parts(identity: Vec<u8>, keypair: SignatureKeypair) -> Self {
+    pub fn from_parts(identity: Identity, keypair: SignatureKeypair) -> Self {
         let (signature_private_key, public_key) = keypair.into_tuple();
         let basic_credential = BasicCredential {
-            identity: identity.into(),
+            identity,
             signature_scheme: public_key.signature_scheme(),
             public_key: public_key.into(),
         };
@@ -258,6 +371,58 @@ impl CredentialBundle {
         }
     }
 
+    /// Creates a new [`CredentialBundle`] with a [`VerifiableCredential`]
+    /// from an identity, e.g. a DID, an encoded verifiable credential or
+    /// presentation and a [`SignatureKeypair`]. The presentation has to bind
+    /// the public key of the `keypair`, which therefore has to be generated
+    /// before the presentation is issued.
+    #[cfg(feature = "verifiable-credentials")]
+    pub fn from_verifiable_credential(
+        identity: Identity,
+        presentation: Vec<u8>,
+        keypair: SignatureKeypair,
+    ) -> Self {
+        let (signature_private_key, public_key) = keypair.into_tuple();
+        let verifiable_credential = VerifiableCredential {
+            identity,
+            presentation: presentation.into(),
+            signature_scheme: public_key.signature_scheme(),
+            public_key: public_key.into(),
+        };
+        Self {
+            credential: Credential::from(MlsCredentialType::VerifiableCredential(
+                verifiable_credential,
+            )),
+            signature_private_key,
+        }
+    }
+
+    /// Creates a new [`CredentialBundle`] with a [`TokenBoundCredential`]
+    /// from an identity, e.g. the subject of an OIDC ID token, a
+    /// [`TokenBinding`] to the token and a [`SignatureKeypair`]. If the token
+    /// binds the public key of the `keypair`, e.g. through its nonce, the key
+    /// pair has to be generated before the token is requested.
+    #[cfg(feature = "token-bound-credentials")]
+    pub fn from_token_binding(
+        identity: Identity,
+        binding: TokenBinding,
+        keypair: SignatureKeypair,
+    ) -> Self {
+        let (signature_private_key, public_key) = keypair.into_tuple();
+        let token_bound_credential = TokenBoundCredential {
+            identity,
+            binding,
+            signature_scheme: public_key.signature_scheme(),
+            public_key: public_key.into(),
+        };
+        Self {
+            credential: Credential::from(MlsCredentialType::TokenBoundCredential(
+                token_bound_credential,
+            )),
+            signature_private_key,
+        }
+    }
+
     /// Returns a reference to the [`Credential`].
     pub fn credential(&self) -> &Credential {
         &self.credential
@@ -0,0 +1,178 @@
+//! This module contains the [`TokenBoundCredential`], a credential that binds
+//! a client to an external identity assertion, e.g. an OIDC ID token.
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{CryptoError, HashType, SignatureScheme},
+    OpenMlsCryptoProvider,
+};
+use serde::{Deserialize, Serialize};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::Identity;
+use crate::ciphersuite::SignaturePublicKey;
+
+/// The type of a [`TokenBinding`].
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    TlsDeserialize,
+    TlsSerialize,
+    TlsSize,
+)]
+#[repr(u8)]
+pub enum TokenBindingType {
+    /// The SHA-256 hash of an identity token, e.g. an OIDC ID token.
+    IdTokenHash = 1,
+    /// A JWT, e.g. an OIDC ID token or an access token.
+    Jwt = 2,
+}
+
+/// Token Binding.
+///
+/// The binding of a [`TokenBoundCredential`] to an identity assertion of an
+/// external identity provider. The binding either carries the assertion
+/// itself, i.e. a JWT, or only its hash, such that the token isn't revealed
+/// to the group.
+///
+/// ```text
+/// struct {
+///     TokenBindingType binding_type;
+///     opaque value<V>;
+/// } TokenBinding;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct TokenBinding {
+    binding_type: TokenBindingType,
+    value: VLBytes,
+}
+
+impl TokenBinding {
+    /// Creates a binding to the SHA-256 hash of the `id_token`.
+    pub fn id_token_hash(
+        backend: &impl OpenMlsCryptoProvider,
+        id_token: &[u8],
+    ) -> Result<Self, CryptoError> {
+        Ok(Self {
+            binding_type: TokenBindingType::IdTokenHash,
+            value: backend.crypto().hash(HashType::Sha2_256, id_token)?.into(),
+        })
+    }
+
+    /// Creates a binding that carries the `jwt`.
+    pub fn jwt(jwt: Vec<u8>) -> Self {
+        Self {
+            binding_type: TokenBindingType::Jwt,
+            value: jwt.into(),
+        }
+    }
+
+    /// Returns the type of the binding.
+    pub fn binding_type(&self) -> TokenBindingType {
+        self.binding_type
+    }
+
+    /// Returns the hash of the ID token or the JWT, depending on the type of
+    /// the binding.
+    pub fn value(&self) -> &[u8] {
+        self.value.as_slice()
+    }
+
+    /// Returns `true` if the binding is for the `token`, i.e. if the token
+    /// is the JWT of the binding or if its hash matches the hash of the
+    /// binding.
+    pub fn binds(&self, backend: &impl OpenMlsCryptoProvider, token: &[u8]) -> bool {
+        match self.binding_type {
+            TokenBindingType::IdTokenHash => backend
+                .crypto()
+                .hash(HashType::Sha2_256, token)
+                .map_or(false, |hash| hash == self.value()),
+            TokenBindingType::Jwt => token == self.value(),
+        }
+    }
+}
+
+/// Token-bound Credential.
+///
+/// A credential that binds a client to an identity assertion of an external
+/// identity provider, e.g. for "login with SSO" deployments. Next to the
+/// [`TokenBinding`], it contains the `identity` of the client, e.g. the
+/// subject of the ID token, as well as the signature public key and the
+/// corresponding signature scheme.
+///
+/// OpenMLS doesn't interpret the binding. It has to be checked by a
+/// [`TokenBindingVerifier`] of the application, which has to ensure that the
+/// token is valid and was issued for the `identity`. Tokens that carry a
+/// nonce or a claim for the signature public key also bind the key.
+///
+/// ```text
+/// struct {
+///     opaque identity<V>;
+///     TokenBinding binding;
+///     SignatureScheme signature_scheme;
+///     SignaturePublicKey public_key;
+/// } TokenBoundCredential;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct TokenBoundCredential {
+    pub(super) identity: Identity,
+    pub(super) binding: TokenBinding,
+    pub(super) signature_scheme: SignatureScheme,
+    pub(super) public_key: SignaturePublicKey,
+}
+
+impl TokenBoundCredential {
+    /// Returns the identity of the client, e.g. the subject of the token.
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+
+    /// Returns the binding to the identity assertion.
+    pub fn binding(&self) -> &TokenBinding {
+        &self.binding
+    }
+
+    /// Returns the signature scheme of the signature public key.
+    pub fn signature_scheme(&self) -> SignatureScheme {
+        self.signature_scheme
+    }
+
+    /// Returns the signature public key.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
+        &self.public_key
+    }
+}
+
+/// Verifies the [`TokenBoundCredential`]s of members.
+///
+/// The verifier is called when a leaf with a [`TokenBoundCredential`] enters
+/// the group, e.g. through an Add or Update proposal, the path of a commit or
+/// the ratchet tree of a Welcome. It returns `true` if the binding is valid,
+/// i.e. if the token is valid and was issued for the identity of the
+/// credential. For hash bindings, the verifier has to obtain the token, e.g.
+/// from the identity provider, and check it with [`TokenBinding::binds()`].
+///
+/// Closures `Fn(&TokenBoundCredential) -> bool` implement this trait.
+pub trait TokenBindingVerifier: Send + Sync {
+    /// Returns `true` if the binding of the `credential` is valid.
+    fn verify(&self, credential: &TokenBoundCredential) -> bool;
+}
+
+impl<F> TokenBindingVerifier for F
+where
+    F: Fn(&TokenBoundCredential) -> bool + Send + Sync,
+{
+    fn verify(&self, credential: &TokenBoundCredential) -> bool {
+        self(credential)
+    }
+}
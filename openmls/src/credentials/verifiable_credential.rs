@@ -0,0 +1,86 @@
+//! This module contains the [`VerifiableCredential`], a credential that binds
+//! a client to a W3C verifiable credential or presentation.
+
+use openmls_traits::types::SignatureScheme;
+use serde::{Deserialize, Serialize};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::Identity;
+use crate::ciphersuite::SignaturePublicKey;
+
+/// Verifiable Credential.
+///
+/// A credential that carries a W3C verifiable credential or presentation,
+/// e.g. a JSON-LD document or a JWT, for deployments that bind MLS leaves to
+/// decentralized identities. Next to the presentation, it contains the
+/// `identity` of the client, e.g. the DID of the subject, as well as the
+/// signature public key and the corresponding signature scheme.
+///
+/// OpenMLS doesn't interpret the presentation. It has to be checked by a
+/// [`VerifiableCredentialVerifier`] of the application, which has to ensure
+/// that the presentation is valid, that it was issued for the `identity` and
+/// that it binds the signature public key.
+///
+/// ```text
+/// struct {
+///     opaque identity<V>;
+///     opaque presentation<V>;
+///     SignatureScheme signature_scheme;
+///     SignaturePublicKey public_key;
+/// } VerifiableCredential;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct VerifiableCredential {
+    pub(super) identity: Identity,
+    pub(super) presentation: VLBytes,
+    pub(super) signature_scheme: SignatureScheme,
+    pub(super) public_key: SignaturePublicKey,
+}
+
+impl VerifiableCredential {
+    /// Returns the identity of the client, e.g. the DID of the subject.
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+
+    /// Returns the encoded verifiable credential or presentation.
+    pub fn presentation(&self) -> &[u8] {
+        self.presentation.as_slice()
+    }
+
+    /// Returns the signature scheme of the signature public key.
+    pub fn signature_scheme(&self) -> SignatureScheme {
+        self.signature_scheme
+    }
+
+    /// Returns the signature public key.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
+        &self.public_key
+    }
+}
+
+/// Verifies the [`VerifiableCredential`]s of members.
+///
+/// The verifier is called when a leaf with a [`VerifiableCredential`] enters
+/// the group, e.g. through an Add or Update proposal, the path of a commit or
+/// the ratchet tree of a Welcome. It returns `true` if the credential is
+/// valid, i.e. if the proof of the presentation is valid, the presentation
+/// was issued for the identity of the credential and it binds the signature
+/// public key.
+///
+/// Closures `Fn(&VerifiableCredential) -> bool` implement this trait.
+pub trait VerifiableCredentialVerifier: Send + Sync {
+    /// Returns `true` if the `credential` is valid.
+    fn verify(&self, credential: &VerifiableCredential) -> bool;
+}
+
+impl<F> VerifiableCredentialVerifier for F
+where
+    F: Fn(&VerifiableCredential) -> bool + Send + Sync,
+{
+    fn verify(&self, credential: &VerifiableCredential) -> bool {
+        self(credential)
+    }
+}
@@ -0,0 +1,476 @@
+//! # Debug representation of protocol objects
+//!
+//! This module renders protocol objects, i.e. [`MlsMessageIn`], `Commit`,
+//! [`Welcome`], [`GroupInfo`] and [`KeyPackage`], into a structured,
+//! human-readable [`DebugTree`], such that captured traffic can be inspected
+//! without a debugger.
+//!
+//! The representation is redacted: it contains public values like group IDs,
+//! epochs, leaf indices and public keys, but never the content of
+//! ciphertexts, application messages, authenticated data or signatures. For
+//! those, only the length is shown. Public keys and hashes longer than
+//! [`MAX_DISPLAYED_BYTES`] are truncated.
+//!
+//! ```text
+//! MLSMessage
+//!   version: Mls10
+//!   wire_format: PublicMessage
+//!   PublicMessage
+//!     group_id: 0x3f1a… (16 bytes)
+//!     epoch: 1
+//!     sender: member 0
+//!     ...
+//! ```
+//!
+//! The tree can be printed with its [`Display`](fmt::Display) implementation
+//! or inspected with [`DebugTree::get()`]. The format is meant for humans and
+//! may change between releases.
+
+use std::fmt;
+
+use crate::{
+    ciphersuite::signable::Verifiable,
+    credentials::{Credential, CredentialType},
+    extensions::Extensions,
+    framing::{
+        mls_content::FramedContentBody, MlsMessageIn, MlsMessageInBody, PrivateMessage,
+        PublicMessage, Sender,
+    },
+    group::GroupContext,
+    key_packages::KeyPackage,
+    messages::{
+        proposals::{Proposal, ProposalOrRef},
+        Commit, GroupInfo, VerifiableGroupInfo, Welcome,
+    },
+    schedule::psk::{PreSharedKeyId, Psk},
+    treesync::{treekem::UpdatePath, LeafNode},
+};
+
+/// Byte strings longer than this are truncated in the debug representation.
+pub const MAX_DISPLAYED_BYTES: usize = 32;
+
+/// A structured, redacted representation of a protocol object.
+///
+/// Every node has a label, an optional value and a list of children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugTree {
+    label: String,
+    value: Option<String>,
+    children: Vec<DebugTree>,
+}
+
+impl DebugTree {
+    /// Creates a node without a value.
+    fn node(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Creates a node with a value.
+    fn leaf(label: impl Into<String>, value: impl fmt::Display) -> Self {
+        Self {
+            label: label.into(),
+            value: Some(value.to_string()),
+            children: Vec::new(),
+        }
+    }
+
+    /// Adds a child to the node.
+    fn child(mut self, child: DebugTree) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Adds a child with a value to the node.
+    fn field(self, label: &str, value: impl fmt::Display) -> Self {
+        self.child(Self::leaf(label, value))
+    }
+
+    /// Adds a child with the `Debug` representation of `value` to the node.
+    fn debug_field(self, label: &str, value: impl fmt::Debug) -> Self {
+        self.field(label, format!("{:?}", value))
+    }
+
+    /// Adds a child with a public byte string to the node.
+    fn bytes_field(self, label: &str, bytes: &[u8]) -> Self {
+        self.field(label, PublicBytes(bytes))
+    }
+
+    /// Adds a child with a redacted byte string to the node.
+    fn redacted_field(self, label: &str, bytes: &[u8]) -> Self {
+        self.field(label, Redacted(bytes.len()))
+    }
+
+    /// Returns the label of the node.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the value of the node, if it has one.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Returns the children of the node.
+    pub fn children(&self) -> &[DebugTree] {
+        &self.children
+    }
+
+    /// Returns the first child with the given label.
+    pub fn get(&self, label: &str) -> Option<&DebugTree> {
+        self.children.iter().find(|child| child.label == label)
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.label, indent = 2 * depth)?;
+        if let Some(value) = &self.value {
+            write!(f, ": {}", value)?;
+        }
+        writeln!(f)?;
+        self.children
+            .iter()
+            .try_for_each(|child| child.fmt_indented(f, depth + 1))
+    }
+}
+
+impl fmt::Display for DebugTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// A protocol object that can be rendered into a [`DebugTree`].
+pub trait Inspect {
+    /// Returns the structured, redacted representation of the object.
+    fn inspect(&self) -> DebugTree;
+}
+
+/// A public byte string, shown as (truncated) hex.
+struct PublicBytes<'a>(&'a [u8]);
+
+impl fmt::Display for PublicBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "(empty)");
+        }
+        write!(f, "0x")?;
+        for byte in self.0.iter().take(MAX_DISPLAYED_BYTES) {
+            write!(f, "{:02x}", byte)?;
+        }
+        if self.0.len() > MAX_DISPLAYED_BYTES {
+            write!(f, "…")?;
+        }
+        write!(f, " ({} bytes)", self.0.len())
+    }
+}
+
+/// A redacted byte string, of which only the length is shown.
+struct Redacted(usize);
+
+impl fmt::Display for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted, {} bytes>", self.0)
+    }
+}
+
+fn sender(sender: &Sender) -> String {
+    match sender {
+        Sender::Member(leaf_index) => format!("member {}", leaf_index.u32()),
+        Sender::External(sender_index) => format!("external {}", sender_index.u32()),
+        Sender::NewMemberProposal => "new member (proposal)".to_owned(),
+        Sender::NewMemberCommit => "new member (commit)".to_owned(),
+    }
+}
+
+fn extensions(label: &str, extensions: &Extensions) -> DebugTree {
+    extensions
+        .iter()
+        .fold(DebugTree::node(label), |tree, extension| {
+            tree.child(DebugTree::node(format!("{:?}", extension.extension_type())))
+        })
+}
+
+fn credential(credential: &Credential) -> DebugTree {
+    let tree = DebugTree::node("Credential").debug_field("type", credential.credential_type());
+    match credential.credential_type() {
+        CredentialType::Basic => tree.field("identity", Identity(credential.identity().as_slice())),
+        #[cfg(feature = "verifiable-credentials")]
+        CredentialType::VerifiableCredential => {
+            let tree = tree.field("identity", Identity(credential.identity().as_slice()));
+            match credential.verifiable_credential() {
+                Some(verifiable_credential) => {
+                    tree.redacted_field("presentation", verifiable_credential.presentation())
+                }
+                None => tree,
+            }
+        }
+        #[cfg(feature = "token-bound-credentials")]
+        CredentialType::TokenBoundCredential => {
+            let tree = tree.field("identity", Identity(credential.identity().as_slice()));
+            match credential.token_bound_credential() {
+                Some(token_bound_credential) => tree
+                    .debug_field(
+                        "binding_type",
+                        token_bound_credential.binding().binding_type(),
+                    )
+                    .redacted_field("binding", token_bound_credential.binding().value()),
+                None => tree,
+            }
+        }
+        // Other credentials can't be decoded yet.
+        CredentialType::X509 => tree,
+    }
+}
+
+/// An identity, shown as text if it is printable and as hex otherwise.
+struct Identity<'a>(&'a [u8]);
+
+impl fmt::Display for Identity<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match std::str::from_utf8(self.0) {
+            Ok(identity) if !identity.chars().any(char::is_control) => {
+                write!(f, "{:?}", identity)
+            }
+            _ => fmt::Display::fmt(&PublicBytes(self.0), f),
+        }
+    }
+}
+
+fn psk(psk_id: &PreSharedKeyId) -> DebugTree {
+    let tree = match psk_id.psk() {
+        Psk::External(external_psk) => {
+            DebugTree::node("ExternalPsk").bytes_field("psk_id", external_psk.psk_id())
+        }
+        Psk::Resumption(resumption_psk) => DebugTree::node("ResumptionPsk")
+            .debug_field("usage", resumption_psk.usage())
+            .bytes_field("group_id", resumption_psk.psk_group_id().as_slice())
+            .field("epoch", resumption_psk.psk_epoch().as_u64()),
+    };
+    tree.bytes_field("psk_nonce", psk_id.psk_nonce())
+}
+
+fn update_path(path: &UpdatePath) -> DebugTree {
+    path.nodes().iter().fold(
+        DebugTree::node("UpdatePath").child(path.leaf_node().inspect()),
+        |tree, node| {
+            let node = node.ciphertexts().iter().fold(
+                DebugTree::node("UpdatePathNode")
+                    .bytes_field("encryption_key", node.public_key().as_slice()),
+                |tree, ciphertext| {
+                    tree.field(
+                        "encrypted_path_secret",
+                        Redacted(ciphertext.kem_output.len() + ciphertext.ciphertext.len()),
+                    )
+                },
+            );
+            tree.child(node)
+        },
+    )
+}
+
+fn group_context(group_context: &GroupContext) -> DebugTree {
+    DebugTree::node("GroupContext")
+        .debug_field("version", group_context.protocol_version())
+        .debug_field("ciphersuite", group_context.ciphersuite())
+        .bytes_field("group_id", group_context.group_id().as_slice())
+        .field("epoch", group_context.epoch().as_u64())
+        .bytes_field("tree_hash", group_context.tree_hash())
+        .bytes_field(
+            "confirmed_transcript_hash",
+            group_context.confirmed_transcript_hash(),
+        )
+        .child(extensions("extensions", group_context.extensions()))
+}
+
+impl Inspect for MlsMessageIn {
+    fn inspect(&self) -> DebugTree {
+        let body = match &self.body {
+            MlsMessageInBody::PublicMessage(message) => message.inspect(),
+            MlsMessageInBody::PrivateMessage(message) => message.inspect(),
+            MlsMessageInBody::Welcome(welcome) => welcome.inspect(),
+            MlsMessageInBody::GroupInfo(group_info) => group_info.inspect(),
+            MlsMessageInBody::KeyPackage(key_package) => key_package.inspect(),
+        };
+        DebugTree::node("MLSMessage")
+            .debug_field("version", self.version)
+            .debug_field("wire_format", self.wire_format())
+            .child(body)
+    }
+}
+
+impl Inspect for PublicMessage {
+    fn inspect(&self) -> DebugTree {
+        let content = match self.content() {
+            FramedContentBody::Application(application_data) => {
+                DebugTree::leaf("Application", Redacted(application_data.as_slice().len()))
+            }
+            FramedContentBody::Proposal(proposal) => proposal.inspect(),
+            FramedContentBody::Commit(commit) => commit.inspect(),
+        };
+        let tree = DebugTree::node("PublicMessage")
+            .bytes_field("group_id", self.group_id().as_slice())
+            .field("epoch", self.epoch().as_u64())
+            .field("sender", sender(self.sender()))
+            .redacted_field("authenticated_data", self.authenticated_data())
+            .child(content)
+            .redacted_field("signature", self.signature().as_slice());
+        let tree = match self.confirmation_tag() {
+            Some(confirmation_tag) => {
+                tree.bytes_field("confirmation_tag", confirmation_tag.0.mac_value.as_slice())
+            }
+            None => tree,
+        };
+        tree.field("membership_tag", self.has_membership_tag())
+    }
+}
+
+impl Inspect for PrivateMessage {
+    fn inspect(&self) -> DebugTree {
+        DebugTree::node("PrivateMessage")
+            .bytes_field("group_id", self.group_id().as_slice())
+            .field("epoch", self.epoch().as_u64())
+            .debug_field("content_type", self.content_type())
+            .redacted_field("authenticated_data", self.authenticated_data())
+            .redacted_field("encrypted_sender_data", self.encrypted_sender_data())
+            .redacted_field("ciphertext", self.ciphertext())
+    }
+}
+
+impl Inspect for Proposal {
+    fn inspect(&self) -> DebugTree {
+        match self {
+            Proposal::Add(add) => DebugTree::node("Add").child(add.key_package().inspect()),
+            Proposal::Update(update) => {
+                DebugTree::node("Update").child(update.leaf_node().inspect())
+            }
+            Proposal::Remove(remove) => {
+                DebugTree::node("Remove").field("removed", remove.removed().u32())
+            }
+            Proposal::PreSharedKey(psk_proposal) => {
+                DebugTree::node("PreSharedKey").child(psk(psk_proposal.psk()))
+            }
+            Proposal::ReInit(reinit) => DebugTree::node("ReInit")
+                .bytes_field("group_id", reinit.group_id().as_slice())
+                .debug_field("version", reinit.version())
+                .debug_field("ciphersuite", reinit.ciphersuite())
+                .child(extensions("extensions", reinit.extensions())),
+            Proposal::ExternalInit(external_init) => DebugTree::node("ExternalInit")
+                .redacted_field("kem_output", external_init.kem_output()),
+            Proposal::AppAck(_) => DebugTree::node("AppAck"),
+            Proposal::GroupContextExtensions(gce) => DebugTree::node("GroupContextExtensions")
+                .child(extensions("extensions", gce.extensions())),
+        }
+    }
+}
+
+impl Inspect for Commit {
+    fn inspect(&self) -> DebugTree {
+        let proposals =
+            self.proposals
+                .iter()
+                .fold(DebugTree::node("proposals"), |tree, proposal| {
+                    tree.child(match proposal {
+                        ProposalOrRef::Proposal(proposal) => proposal.inspect(),
+                        ProposalOrRef::Reference(reference) => {
+                            DebugTree::leaf("Reference", PublicBytes(reference.as_slice()))
+                        }
+                    })
+                });
+        let tree = DebugTree::node("Commit").child(proposals);
+        match self.path() {
+            Some(path) => tree.child(update_path(path)),
+            None => tree.field("path", "none"),
+        }
+    }
+}
+
+impl Inspect for Welcome {
+    fn inspect(&self) -> DebugTree {
+        let secrets = self
+            .secrets()
+            .iter()
+            .fold(DebugTree::node("secrets"), |tree, secrets| {
+                let ciphertext = secrets.encrypted_group_secrets();
+                tree.child(
+                    DebugTree::node("EncryptedGroupSecrets")
+                        .bytes_field("new_member", secrets.new_member().as_slice())
+                        .field(
+                            "encrypted_group_secrets",
+                            Redacted(ciphertext.kem_output.len() + ciphertext.ciphertext.len()),
+                        ),
+                )
+            });
+        DebugTree::node("Welcome")
+            .debug_field("version", self.version())
+            .debug_field("ciphersuite", self.ciphersuite())
+            .child(secrets)
+            .redacted_field("encrypted_group_info", self.encrypted_group_info())
+    }
+}
+
+impl Inspect for GroupInfo {
+    fn inspect(&self) -> DebugTree {
+        DebugTree::node("GroupInfo")
+            .child(group_context(self.group_context()))
+            .child(extensions("extensions", self.extensions()))
+            .bytes_field(
+                "confirmation_tag",
+                self.confirmation_tag().0.mac_value.as_slice(),
+            )
+            .field("signer", self.signer().u32())
+            .redacted_field("signature", self.signature().as_slice())
+    }
+}
+
+impl Inspect for VerifiableGroupInfo {
+    fn inspect(&self) -> DebugTree {
+        DebugTree::node("GroupInfo (unverified)")
+            .child(group_context(self.group_context()))
+            .child(extensions("extensions", self.extensions()))
+            .bytes_field(
+                "confirmation_tag",
+                self.confirmation_tag().0.mac_value.as_slice(),
+            )
+            .field("signer", self.signer().u32())
+            .redacted_field("signature", self.signature().as_slice())
+    }
+}
+
+impl Inspect for KeyPackage {
+    fn inspect(&self) -> DebugTree {
+        DebugTree::node("KeyPackage")
+            .debug_field("version", self.protocol_version())
+            .debug_field("ciphersuite", self.ciphersuite())
+            .bytes_field("init_key", self.hpke_init_key().as_slice())
+            .child(self.leaf_node().inspect())
+            .child(extensions("extensions", self.extensions()))
+    }
+}
+
+impl Inspect for LeafNode {
+    fn inspect(&self) -> DebugTree {
+        let capabilities = self.capabilities();
+        let tree = DebugTree::node("LeafNode")
+            .child(credential(self.credential()))
+            .bytes_field("signature_key", self.signature_key().as_slice())
+            .bytes_field("encryption_key", self.encryption_key().as_slice())
+            .child(
+                DebugTree::node("capabilities")
+                    .debug_field("versions", capabilities.versions())
+                    .debug_field("ciphersuites", capabilities.ciphersuites())
+                    .debug_field("extensions", capabilities.extensions())
+                    .debug_field("proposals", capabilities.proposals())
+                    .debug_field("credentials", capabilities.credentials()),
+            );
+        let tree = match (self.life_time(), self.parent_hash()) {
+            (Some(lifetime), _) => tree.debug_field("lifetime", lifetime),
+            (None, Some(parent_hash)) => tree.bytes_field("parent_hash", parent_hash),
+            (None, None) => tree,
+        };
+        tree.child(extensions("extensions", self.extensions()))
+            .redacted_field("signature", self.signature().as_slice())
+    }
+}
@@ -35,13 +35,134 @@
 //!
 //! All errors derive [`thiserror::Error`](https://docs.rs/thiserror/latest/thiserror/) as well as
 //! [`Debug`](`std::fmt::Debug`), [`PartialEq`](`std::cmp::PartialEq`), and [`Clone`](`std::clone::Clone`).
+//!
+//! ### Error codes
+//!
+//! Every public error implements [`ErrorCode`], which maps each variant to a
+//! stable numeric code and a stable string code. FFI consumers and telemetry
+//! pipelines should use these codes instead of matching on error messages.
+//! The codes of existing variants never change; new variants get new codes.
+//!
+//! ### Error context
+//!
+//! The errors returned by OpenMLS don't carry the group, epoch or leaf they
+//! occurred in. Applications that report errors, e.g. to a telemetry
+//! pipeline, can attach this information themselves with an [`ErrorContext`],
+//! which turns the error into a [`ContextualError`]. The context of a group
+//! is returned by [`MlsGroup::error_context()`](crate::group::MlsGroup::error_context).
+//! Since failing functions of a group don't change its state, the context can
+//! be taken after the error was returned, e.g. with
+//! `.map_err(|error| group.error_context().wrap(error))`.
 
 use backtrace::Backtrace;
 use openmls_traits::types::CryptoError;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use thiserror::Error;
 use tls_codec::Error as TlsCodecError;
 
+use crate::{
+    binary_tree::LeafNodeIndex,
+    group::{GroupEpoch, GroupId},
+};
+
+/// Stable, machine-readable identification of error variants.
+///
+/// Numeric codes are grouped by error type: each error type owns a block of
+/// 100 codes and the variants are numbered within that block. The string code
+/// has the form `"ErrorType::Variant"`.
+pub trait ErrorCode {
+    /// Returns the stable numeric code of this error variant.
+    fn error_code(&self) -> u32;
+
+    /// Returns the stable string code of this error variant.
+    fn error_code_str(&self) -> &'static str;
+}
+
+/// Implements [`ErrorCode`] for an error enum from a table of variants and
+/// their codes relative to the base code of the enum. Variants that only exist
+/// with a feature carry the same `#[cfg]` attribute in the table.
+macro_rules! error_codes {
+    ($name:ident $(<$generic:ident>)? : $base:literal { $($(#[$attr:meta])* $variant:ident = $code:literal),* $(,)? }) => {
+        impl$(<$generic>)? $crate::error::ErrorCode for $name$(<$generic>)? {
+            fn error_code(&self) -> u32 {
+                match self {
+                    $($(#[$attr])* Self::$variant { .. } => $base + $code,)*
+                }
+            }
+
+            fn error_code_str(&self) -> &'static str {
+                match self {
+                    $($(#[$attr])* Self::$variant { .. } => concat!(stringify!($name), "::", stringify!($variant)),)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use error_codes;
+
+/// Structured context describing where an error occurred.
+///
+/// The context is not part of the errors returned by OpenMLS, but attached by
+/// the application with [`ErrorContext::wrap()`]. All fields are optional,
+/// since not every error can be attributed to a group, an epoch or a leaf.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorContext {
+    /// The ID of the group the error occurred in.
+    pub group_id: Option<GroupId>,
+    /// The epoch of the group when the error occurred.
+    pub epoch: Option<GroupEpoch>,
+    /// The leaf index the error relates to.
+    pub leaf_index: Option<LeafNodeIndex>,
+}
+
+impl ErrorContext {
+    /// Create a new [`ErrorContext`] for the given group and epoch.
+    pub fn new(group_id: GroupId, epoch: GroupEpoch) -> Self {
+        Self {
+            group_id: Some(group_id),
+            epoch: Some(epoch),
+            leaf_index: None,
+        }
+    }
+
+    /// Set the leaf index the error relates to.
+    pub fn with_leaf_index(mut self, leaf_index: LeafNodeIndex) -> Self {
+        self.leaf_index = Some(leaf_index);
+        self
+    }
+
+    /// Attach this context to the given error.
+    pub fn wrap<E>(self, error: E) -> ContextualError<E> {
+        ContextualError {
+            error,
+            context: self,
+        }
+    }
+}
+
+/// An error together with the [`ErrorContext`] it occurred in.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{error}")]
+pub struct ContextualError<E: std::error::Error + 'static> {
+    /// The error.
+    #[source]
+    pub error: E,
+    /// The context the error occurred in.
+    pub context: ErrorContext,
+}
+
+impl<E: std::error::Error + ErrorCode + 'static> ErrorCode for ContextualError<E> {
+    fn error_code(&self) -> u32 {
+        self.error.error_code()
+    }
+
+    fn error_code_str(&self) -> &'static str {
+        self.error.error_code_str()
+    }
+}
+
 /// Generic error type that indicates unrecoverable errors in the library.
 ///
 /// This error has 3 subtypes:
@@ -97,6 +218,24 @@ impl LibraryError {
     }
 }
 
+impl ErrorCode for LibraryError {
+    fn error_code(&self) -> u32 {
+        match self.internal {
+            InternalLibraryError::MissingBoundsCheck(_) => 1,
+            InternalLibraryError::CryptoError(_) => 2,
+            InternalLibraryError::Custom(_) => 3,
+        }
+    }
+
+    fn error_code_str(&self) -> &'static str {
+        match self.internal {
+            InternalLibraryError::MissingBoundsCheck(_) => "LibraryError::MissingBoundsCheck",
+            InternalLibraryError::CryptoError(_) => "LibraryError::CryptoError",
+            InternalLibraryError::Custom(_) => "LibraryError::Custom",
+        }
+    }
+}
+
 impl Display for LibraryError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self.internal)
@@ -25,6 +25,13 @@ pub struct ApplicationIdExtension {
     key_id: TlsByteVecU16,
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ApplicationIdExtension {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.arbitrary::<Vec<u8>>()?.as_slice()))
+    }
+}
+
 impl ApplicationIdExtension {
     /// Create a new key identifier extension from a byte slice.
     pub fn new(id: &[u8]) -> Self {
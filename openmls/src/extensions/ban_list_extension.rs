@@ -0,0 +1,92 @@
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{Deserialize, Serialize};
+use crate::{
+    ciphersuite::SignaturePublicKey,
+    credentials::{Credential, Identity},
+};
+
+/// # Ban List
+///
+/// A GroupContext extension that lists the signature keys and identities of
+/// parties that are banned from the group. Since it is part of the group
+/// context, all members agree on it. Every member rejects commits that add a
+/// banned party, either through an Add proposal or an External Commit,
+/// regardless of who created the commit.
+///
+/// ```text
+/// struct {
+///     SignaturePublicKey signature_keys<V>;
+///     opaque identities<V><V>;
+/// } BanList;
+/// ```
+#[derive(
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct BanListExtension {
+    signature_keys: Vec<SignaturePublicKey>,
+    identities: Vec<Identity>,
+}
+
+impl BanListExtension {
+    /// Creates a new ban list with the banned `signature_keys` and
+    /// `identities`.
+    pub fn new(signature_keys: Vec<SignaturePublicKey>, identities: Vec<Identity>) -> Self {
+        Self {
+            signature_keys,
+            identities,
+        }
+    }
+
+    /// Returns the banned signature keys.
+    pub fn signature_keys(&self) -> &[SignaturePublicKey] {
+        &self.signature_keys
+    }
+
+    /// Returns the banned identities.
+    pub fn identities(&self) -> &[Identity] {
+        &self.identities
+    }
+
+    /// Returns `true` if neither signature keys nor identities are banned.
+    pub fn is_empty(&self) -> bool {
+        self.signature_keys.is_empty() && self.identities.is_empty()
+    }
+
+    /// Bans the signature key and the identity of the `credential`. Entries
+    /// that are already banned are not added again.
+    pub fn ban(&mut self, credential: &Credential) {
+        if !self.signature_keys.contains(credential.signature_key()) {
+            self.signature_keys.push(credential.signature_key().clone());
+        }
+        if !self.identities.contains(credential.identity()) {
+            self.identities.push(credential.identity().clone());
+        }
+    }
+
+    /// Lifts the ban of the signature key and the identity of the
+    /// `credential`.
+    pub fn unban(&mut self, credential: &Credential) {
+        self.signature_keys
+            .retain(|signature_key| signature_key != credential.signature_key());
+        self.identities
+            .retain(|identity| identity != credential.identity());
+    }
+
+    /// Returns `true` if the signature key or the identity of the
+    /// `credential` is banned.
+    pub fn bans(&self, credential: &Credential) -> bool {
+        self.signature_keys.contains(credential.signature_key())
+            || self.identities.contains(credential.identity())
+    }
+}
@@ -3,8 +3,9 @@ use std::io::{Read, Write};
 use tls_codec::{Deserialize, Serialize, Size, TlsByteVecU32, TlsSliceU32};
 
 use crate::extensions::{
-    ApplicationIdExtension, Extension, ExtensionType, ExternalPubExtension,
-    ExternalSendersExtension, RatchetTreeExtension, RequiredCapabilitiesExtension,
+    ApplicationIdExtension, BanListExtension, Extension, ExtensionType, ExternalPubExtension,
+    ExternalSendersExtension, MaxMembersExtension, RatchetTreeExtension,
+    RequiredCapabilitiesExtension, UnknownExtension,
 };
 
 impl Size for Extension {
@@ -18,6 +19,9 @@ impl Size for Extension {
                 Extension::RequiredCapabilities(e) => e.tls_serialized_len(),
                 Extension::ExternalPub(e) => e.tls_serialized_len(),
                 Extension::ExternalSenders(e) => e.tls_serialized_len(),
+                Extension::BanList(e) => e.tls_serialized_len(),
+                Extension::MaxMembers(e) => e.tls_serialized_len(),
+                Extension::Unknown(_, e) => e.0.len(),
             }
     }
 }
@@ -44,6 +48,12 @@ impl Serialize for Extension {
             Extension::RequiredCapabilities(e) => e.tls_serialize(&mut extension_data),
             Extension::ExternalPub(e) => e.tls_serialize(&mut extension_data),
             Extension::ExternalSenders(e) => e.tls_serialize(&mut extension_data),
+            Extension::BanList(e) => e.tls_serialize(&mut extension_data),
+            Extension::MaxMembers(e) => e.tls_serialize(&mut extension_data),
+            Extension::Unknown(_, e) => {
+                extension_data.extend_from_slice(&e.0);
+                Ok(e.0.len())
+            }
         }?;
         debug_assert_eq!(extension_data_written, extension_data_len);
         debug_assert_eq!(extension_data_written, extension_data.len());
@@ -85,6 +95,40 @@ impl Deserialize for Extension {
             ExtensionType::ExternalSenders => Extension::ExternalSenders(
                 ExternalSendersExtension::tls_deserialize(&mut extension_data)?,
             ),
+            ExtensionType::BanList => {
+                Extension::BanList(BanListExtension::tls_deserialize(&mut extension_data)?)
+            }
+            ExtensionType::MaxMembers => {
+                Extension::MaxMembers(MaxMembersExtension::tls_deserialize(&mut extension_data)?)
+            }
+            ExtensionType::DeflateCompression => {
+                return Err(tls_codec::Error::DecodingError(
+                    "The deflate compression extension type can't be used as an extension"
+                        .to_string(),
+                ))
+            }
+            ExtensionType::Unknown(extension_type) => {
+                Extension::Unknown(extension_type, UnknownExtension(extension_data.to_vec()))
+            }
         })
     }
 }
+
+impl Size for ExtensionType {
+    #[inline]
+    fn tls_serialized_len(&self) -> usize {
+        2
+    }
+}
+
+impl Serialize for ExtensionType {
+    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        u16::from(*self).tls_serialize(writer)
+    }
+}
+
+impl Deserialize for ExtensionType {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
+        u16::tls_deserialize(bytes).map(ExtensionType::from)
+    }
+}
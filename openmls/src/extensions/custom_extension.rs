@@ -0,0 +1,107 @@
+use super::{errors::ExtensionError, Extension, ExtensionType, Extensions, UnknownExtension};
+
+/// # Custom Extensions
+///
+/// Applications can define their own extensions by implementing this trait
+/// for the type that holds the extension data. The trait registers the
+/// extension type together with the functions that encode and decode the
+/// extension data.
+///
+/// OpenMLS preserves extensions of types it doesn't know as
+/// [`UnknownExtension`]s. The functions of this trait convert between the
+/// typed value and the extension, see [`Extension::custom()`],
+/// [`Extension::as_custom_extension()`] and [`Extensions::custom()`]. Clients
+/// advertise support for the extension type in the capabilities of their leaf
+/// nodes with [`CapabilitiesBuilder::custom_extension()`].
+///
+/// ```
+/// use openmls::prelude::*;
+///
+/// struct Room(Vec<u8>);
+///
+/// impl CustomExtension for Room {
+///     const EXTENSION_TYPE: u16 = 0xff01;
+///
+///     fn encode(&self) -> Result<Vec<u8>, tls_codec::Error> {
+///         Ok(self.0.clone())
+///     }
+///
+///     fn decode(extension_data: &[u8]) -> Result<Self, tls_codec::Error> {
+///         Ok(Room(extension_data.to_vec()))
+///     }
+/// }
+///
+/// let extensions = Extensions::single(Extension::custom(&Room(b"lobby".to_vec())).unwrap());
+/// let room: Room = extensions.custom().unwrap().unwrap();
+/// assert_eq!(room.0, b"lobby");
+/// ```
+///
+/// [`CapabilitiesBuilder::custom_extension()`]: crate::prelude::CapabilitiesBuilder::custom_extension()
+pub trait CustomExtension: Sized {
+    /// The extension type. It must not be the type of an extension that
+    /// OpenMLS knows, see [`ExtensionType`].
+    const EXTENSION_TYPE: u16;
+
+    /// Encode the extension data.
+    fn encode(&self) -> Result<Vec<u8>, tls_codec::Error>;
+
+    /// Decode the extension data.
+    fn decode(extension_data: &[u8]) -> Result<Self, tls_codec::Error>;
+}
+
+impl ExtensionType {
+    /// Returns the [`ExtensionType`] of the [`CustomExtension`] `T`.
+    pub fn custom<T: CustomExtension>() -> Self {
+        ExtensionType::from(T::EXTENSION_TYPE)
+    }
+}
+
+impl Extension {
+    /// Create an [`Extension`] from the [`CustomExtension`] `extension`.
+    ///
+    /// Returns an [`ExtensionError::InvalidExtensionType`] error if the
+    /// extension type is the type of an extension that OpenMLS knows and an
+    /// [`ExtensionError::CustomExtensionCodec`] error if the extension data
+    /// can't be encoded.
+    pub fn custom<T: CustomExtension>(extension: &T) -> Result<Self, ExtensionError> {
+        if !matches!(ExtensionType::custom::<T>(), ExtensionType::Unknown(_)) {
+            return Err(ExtensionError::InvalidExtensionType(
+                "The extension type of a custom extension must not be known to OpenMLS".into(),
+            ));
+        }
+        Ok(Extension::Unknown(
+            T::EXTENSION_TYPE,
+            UnknownExtension(extension.encode()?),
+        ))
+    }
+
+    /// Decode this extension as the [`CustomExtension`] `T`.
+    ///
+    /// Returns an [`ExtensionError::InvalidExtensionType`] error if called on
+    /// an [`Extension`] of another type and an
+    /// [`ExtensionError::CustomExtensionCodec`] error if the extension data
+    /// can't be decoded.
+    pub fn as_custom_extension<T: CustomExtension>(&self) -> Result<T, ExtensionError> {
+        match self {
+            Self::Unknown(extension_type, e) if *extension_type == T::EXTENSION_TYPE => {
+                Ok(T::decode(&e.0)?)
+            }
+            _ => Err(ExtensionError::InvalidExtensionType(
+                "This is not the requested custom extension".into(),
+            )),
+        }
+    }
+}
+
+impl Extensions {
+    /// Decode the [`CustomExtension`] `T` if there is any.
+    ///
+    /// Returns an [`ExtensionError::CustomExtensionCodec`] error if the
+    /// extension data can't be decoded.
+    pub fn custom<T: CustomExtension>(&self) -> Result<Option<T>, ExtensionError> {
+        self.iter()
+            .find(|e| e.extension_type() == ExtensionType::custom::<T>())
+            .map(|e| e.as_custom_extension())
+            .transpose()
+    }
+}
@@ -9,7 +9,7 @@
 //! * `ParentHashError`
 //! * `RatchetTreeError`
 
-use crate::error::{ErrorString, LibraryError};
+use crate::error::{error_codes, ErrorString, LibraryError};
 
 use thiserror::Error;
 
@@ -43,8 +43,25 @@ pub enum ExtensionError {
     /// See [`InvalidExtensionError`] for more details.
     #[error(transparent)]
     InvalidExtension(#[from] InvalidExtensionError),
+    /// The data of a custom extension could not be encoded or decoded. See
+    /// [`tls_codec::Error`] for more details.
+    #[error(transparent)]
+    CustomExtensionCodec(#[from] tls_codec::Error),
 }
 
+error_codes!(ExtensionError: 100 {
+    UnsupportedProposalType = 1,
+    UnsupportedExtensionType = 2,
+    LibraryError = 3,
+    InvalidExtensionType = 4,
+    Capabilities = 5,
+    KeyPackageId = 6,
+    ParentHash = 7,
+    RatchetTree = 8,
+    InvalidExtension = 9,
+    CustomExtensionCodec = 10,
+});
+
 /// Capabilities extension error
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum CapabilitiesExtensionError {
@@ -59,6 +76,12 @@ pub enum CapabilitiesExtensionError {
     UnsupportedCiphersuite,
 }
 
+error_codes!(CapabilitiesExtensionError: 200 {
+    Invalid = 1,
+    EmptyVersionsField = 2,
+    UnsupportedCiphersuite = 3,
+});
+
 /// KeyPackage Id error
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum KeyPackageIdError {
@@ -67,6 +90,10 @@ pub enum KeyPackageIdError {
     Invalid,
 }
 
+error_codes!(KeyPackageIdError: 300 {
+    Invalid = 1,
+});
+
 /// Parent hash error
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum ParentHashError {
@@ -75,6 +102,10 @@ pub enum ParentHashError {
     Invalid,
 }
 
+error_codes!(ParentHashError: 400 {
+    Invalid = 1,
+});
+
 /// Ratchet tree error
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum RatchetTreeError {
@@ -83,6 +114,10 @@ pub enum RatchetTreeError {
     Invalid,
 }
 
+error_codes!(RatchetTreeError: 500 {
+    Invalid = 1,
+});
+
 /// Invalid extension error
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum InvalidExtensionError {
@@ -93,3 +128,8 @@ pub enum InvalidExtensionError {
     #[error("The specified extension could not be found.")]
     NotFound,
 }
+
+error_codes!(InvalidExtensionError: 600 {
+    Duplicate = 1,
+    NotFound = 2,
+});
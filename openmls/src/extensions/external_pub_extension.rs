@@ -12,7 +12,9 @@ use crate::prelude::HpkePublicKey;
 #[derive(
     PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalPubExtension {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     external_pub: HpkePublicKey,
 }
 
@@ -50,7 +52,7 @@ mod test {
             for _ in 0..8 {
                 let hpke_public_key = {
                     let credential_bundle = CredentialBundle::new(
-                        b"Alice".to_vec(),
+                        "Alice".into(),
                         CredentialType::Basic,
                         SignatureScheme::ED25519,
                         &backend,
@@ -16,19 +16,39 @@ use crate::credentials::Credential;
 #[derive(
     Clone, PartialEq, Eq, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalSender {
     signature_key: SignaturePublicKey,
     credential: Credential,
 }
 
+impl ExternalSender {
+    /// Creates a new [`ExternalSender`] from a [`Credential`]. The signature
+    /// key of the external sender is the one of the credential.
+    pub fn new(credential: Credential) -> Self {
+        Self {
+            signature_key: credential.signature_key().clone(),
+            credential,
+        }
+    }
+
+    /// Returns the signature key of the external sender.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
+        &self.signature_key
+    }
+
+    /// Returns the credential of the external sender.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+}
+
 /// ExternalSender (extension data)
 ///
 /// ```c
 /// // draft-ietf-mls-protocol-16
 /// ExternalSender external_senders<V>;
 /// ```
-// TODO(884): Remove `#[allow(unused)]` when #884 is closed.
-#[allow(unused)]
 pub type ExternalSendersExtension = Vec<ExternalSender>;
 
 #[cfg(test)]
@@ -50,7 +70,7 @@ mod test {
 
             for _ in 0..8 {
                 let credential_bundle = CredentialBundle::new(
-                    b"Alice".to_vec(),
+                    "Alice".into(),
                     CredentialType::Basic,
                     SignatureScheme::ED25519,
                     &backend,
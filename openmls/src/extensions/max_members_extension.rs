@@ -0,0 +1,36 @@
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{Deserialize, Serialize};
+
+/// # Max Members
+///
+/// A GroupContext extension that limits the number of members of the group.
+/// Since it is part of the group context, all members agree on the limit.
+/// Every member rejects commits after which the group would have more members
+/// than `max_members`, regardless of who created the commit.
+///
+/// ```text
+/// struct {
+///     uint32 max_members;
+/// } MaxMembers;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MaxMembersExtension {
+    max_members: u32,
+}
+
+impl MaxMembersExtension {
+    /// Creates a new extension that limits the group to `max_members`
+    /// members.
+    pub fn new(max_members: u32) -> Self {
+        Self { max_members }
+    }
+
+    /// Returns the maximum number of members.
+    pub fn max_members(&self) -> u32 {
+        self.max_members
+    }
+}
@@ -18,10 +18,16 @@
 //! - [`RatchetTreeExtension`] (GroupInfo extension)
 //! - [`RequiredCapabilitiesExtension`] (GroupContext extension)
 //! - [`ExternalPubExtension`] (GroupInfo extension)
+//! - [`ExternalSendersExtension`] (GroupContext extension)
+//! - [`BanListExtension`] (GroupContext extension)
+//! - [`MaxMembersExtension`] (GroupContext extension)
+//!
+//! Extensions of other types are preserved as [`UnknownExtension`]s, such
+//! that they are re-serialized byte-exactly. Applications can define their
+//! own extensions with a [`CustomExtension`].
 
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{btree_map::Entry, BTreeMap},
     fmt::Debug,
     io::{Read, Write},
 };
@@ -29,9 +35,12 @@ use tls_codec::*;
 
 // Private
 mod application_id_extension;
+mod ban_list_extension;
 mod codec;
+mod custom_extension;
 mod external_pub_extension;
 mod external_sender_extension;
+mod max_members_extension;
 mod ratchet_tree_extension;
 mod required_capabilities;
 use errors::*;
@@ -41,9 +50,12 @@ pub mod errors;
 
 // Public re-exports
 pub use application_id_extension::ApplicationIdExtension;
+pub use ban_list_extension::BanListExtension;
+pub use custom_extension::CustomExtension;
 pub use external_pub_extension::ExternalPubExtension;
-pub use external_sender_extension::ExternalSendersExtension;
-pub use ratchet_tree_extension::RatchetTreeExtension;
+pub use external_sender_extension::{ExternalSender, ExternalSendersExtension};
+pub use max_members_extension::MaxMembersExtension;
+pub use ratchet_tree_extension::{RatchetTreeExtension, RatchetTreeNodes};
 pub use required_capabilities::RequiredCapabilitiesExtension;
 
 use crate::treesync::node::Node;
@@ -63,68 +75,103 @@ mod test_extensions;
 /// | 0x0003           | required_capabilities    | GC         | Y           | RFC XXXX  |
 /// | 0x0004           | external_pub             | GI         | Y           | RFC XXXX  |
 /// | 0x0005           | external_senders         | GC         | Y           | RFC XXXX  |
+/// | 0xff00           | deflate_compression      | N/A        | N           | OpenMLS   |
+/// | 0xff10           | ban_list                 | GC         | N           | OpenMLS   |
+/// | 0xff11           | max_members              | GC         | N           | OpenMLS   |
 /// | 0xff00  - 0xffff | Reserved for Private Use | N/A        | N/A         | RFC XXXX  |
 ///
 /// Note: OpenMLS does not provide a `Reserved` variant in [ExtensionType].
-#[derive(
-    Debug,
-    Copy,
-    Clone,
-    PartialEq,
-    Eq,
-    Hash,
-    Serialize,
-    Deserialize,
-    Ord,
-    PartialOrd,
-    TlsSerialize,
-    TlsDeserialize,
-    TlsSize,
-)]
-#[repr(u16)]
+/// Extension types that OpenMLS doesn't know are represented by
+/// [`ExtensionType::Unknown`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Ord, PartialOrd)]
 pub enum ExtensionType {
     /// The application id extension allows applications to add an explicit,
     /// application-defined identifier to a KeyPackage.
-    ApplicationId = 1,
+    ApplicationId,
 
     /// The ratchet tree extensions provides the whole public state of the ratchet
     /// tree.
-    RatchetTree = 2,
+    RatchetTree,
 
     /// The required capabilities extension defines the configuration of a group
     /// that imposes certain requirements on clients in the group.
-    RequiredCapabilities = 3,
+    RequiredCapabilities,
 
     /// To join a group via an External Commit, a new member needs a GroupInfo
     /// with an ExternalPub extension present in its extensions field.
-    ExternalPub = 4,
+    ExternalPub,
 
     /// Group context extension that contains the credentials and signature keys
     /// of senders that are permitted to send external proposals to the group.
-    ExternalSenders = 5,
-}
+    ExternalSenders,
+
+    /// Capability indicating support for DEFLATE compressed application
+    /// messages. Groups that require this extension type compress all
+    /// application messages (see [`MlsGroup::uses_application_compression()`]).
+    /// This extension type has no extension data and can't be used as an
+    /// extension.
+    ///
+    /// [`MlsGroup::uses_application_compression()`]: crate::group::MlsGroup::uses_application_compression()
+    DeflateCompression,
+
+    /// Group context extension that lists the signature keys and identities
+    /// of parties that are banned from the group, see [`BanListExtension`].
+    BanList,
 
-impl TryFrom<u16> for ExtensionType {
-    type Error = tls_codec::Error;
+    /// Group context extension that limits the number of members of the
+    /// group, see [`MaxMembersExtension`].
+    MaxMembers,
 
+    /// An extension type that OpenMLS doesn't know. Extensions of this type
+    /// are preserved as [`UnknownExtension`]s. The value must not be the value
+    /// of one of the other extension types.
+    Unknown(u16),
+}
+
+impl From<u16> for ExtensionType {
     /// Get the [`ExtensionType`] from a u16.
-    /// Returns an error if the extension type is not known.
-    /// Note that this returns a [`tls_codec::Error`](`tls_codec::Error`).
-    fn try_from(a: u16) -> Result<Self, Self::Error> {
+    /// Values of extension types that OpenMLS doesn't know are mapped to
+    /// [`ExtensionType::Unknown`].
+    fn from(a: u16) -> Self {
         match a {
-            1 => Ok(ExtensionType::ApplicationId),
-            2 => Ok(ExtensionType::RatchetTree),
-            3 => Ok(ExtensionType::RequiredCapabilities),
-            4 => Ok(ExtensionType::ExternalPub),
-            5 => Ok(ExtensionType::ExternalSenders),
-            _ => Err(tls_codec::Error::DecodingError(format!(
-                "{} is an unkown extension type",
-                a
-            ))),
+            1 => ExtensionType::ApplicationId,
+            2 => ExtensionType::RatchetTree,
+            3 => ExtensionType::RequiredCapabilities,
+            4 => ExtensionType::ExternalPub,
+            5 => ExtensionType::ExternalSenders,
+            0xff00 => ExtensionType::DeflateCompression,
+            0xff10 => ExtensionType::BanList,
+            0xff11 => ExtensionType::MaxMembers,
+            unknown => ExtensionType::Unknown(unknown),
+        }
+    }
+}
+
+impl From<ExtensionType> for u16 {
+    fn from(extension_type: ExtensionType) -> Self {
+        match extension_type {
+            ExtensionType::ApplicationId => 1,
+            ExtensionType::RatchetTree => 2,
+            ExtensionType::RequiredCapabilities => 3,
+            ExtensionType::ExternalPub => 4,
+            ExtensionType::ExternalSenders => 5,
+            ExtensionType::DeflateCompression => 0xff00,
+            ExtensionType::BanList => 0xff10,
+            ExtensionType::MaxMembers => 0xff11,
+            ExtensionType::Unknown(unknown) => unknown,
         }
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ExtensionType {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Going through the `u16` keeps `Unknown` values distinct from the
+        // values of the known extension types.
+        Ok(ExtensionType::from(u.arbitrary::<u16>()?))
+    }
+}
+
 impl ExtensionType {
     /// Check whether an [`ExtensionType`] is supported or not.
     pub fn is_supported(&self) -> bool {
@@ -133,7 +180,11 @@ impl ExtensionType {
             | ExtensionType::RatchetTree
             | ExtensionType::RequiredCapabilities
             | ExtensionType::ExternalPub
-            | ExtensionType::ExternalSenders => true,
+            | ExtensionType::ExternalSenders
+            | ExtensionType::BanList
+            | ExtensionType::MaxMembers => true,
+            ExtensionType::DeflateCompression => cfg!(feature = "compression-deflate"),
+            ExtensionType::Unknown(_) => false,
         }
     }
 }
@@ -168,25 +219,80 @@ pub enum Extension {
 
     /// A [`ExternalPubExtension`]
     ExternalSenders(ExternalSendersExtension),
+
+    /// A [`BanListExtension`]
+    BanList(BanListExtension),
+
+    /// A [`MaxMembersExtension`]
+    MaxMembers(MaxMembersExtension),
+
+    /// An extension of a type that OpenMLS doesn't know, see
+    /// [`UnknownExtension`].
+    Unknown(u16, UnknownExtension),
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Extension {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=7)? {
+            0 => Extension::ApplicationId(u.arbitrary()?),
+            1 => Extension::RatchetTree(u.arbitrary()?),
+            2 => Extension::RequiredCapabilities(u.arbitrary()?),
+            3 => Extension::ExternalPub(u.arbitrary()?),
+            4 => Extension::ExternalSenders(u.arbitrary()?),
+            5 => Extension::BanList(u.arbitrary()?),
+            6 => Extension::MaxMembers(u.arbitrary()?),
+            _ => {
+                let extension_type = match u.arbitrary()? {
+                    ExtensionType::Unknown(extension_type) => extension_type,
+                    // Values of known extension types are shifted into the
+                    // range of unknown ones.
+                    known => u16::from(known).wrapping_add(0x0100),
+                };
+                Extension::Unknown(extension_type, UnknownExtension(u.arbitrary()?))
+            }
+        })
+    }
 }
 
+/// The extension data of an extension whose type OpenMLS doesn't know.
+///
+/// The data is preserved as is, such that the extension can be inspected by
+/// the application and is re-serialized byte-exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownExtension(pub Vec<u8>);
+
 /// A list of extensions with unique extension types.
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// The extensions keep the order in which they were added or deserialized,
+/// such that deserialized extensions are re-serialized byte-exactly. Two lists
+/// are equal if they contain the same extensions, regardless of the order.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Extensions {
-    map: BTreeMap<ExtensionType, Extension>,
+    extensions: Vec<Extension>,
+}
+
+impl PartialEq for Extensions {
+    fn eq(&self, other: &Self) -> bool {
+        self.extensions.len() == other.extensions.len()
+            && self
+                .extensions
+                .iter()
+                .all(|extension| other.extensions.contains(extension))
+    }
 }
 
+impl Eq for Extensions {}
+
 impl Size for Extensions {
     fn tls_serialized_len(&self) -> usize {
-        let out: Vec<&Extension> = self.map.values().collect();
-        out.tls_serialized_len()
+        self.extensions.tls_serialized_len()
     }
 }
 
 impl tls_codec::Serialize for Extensions {
     fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
-        let out: Vec<&Extension> = self.map.values().collect();
-        out.tls_serialize(writer)
+        self.extensions.tls_serialize(writer)
     }
 }
 
@@ -201,18 +307,30 @@ impl tls_codec::Deserialize for Extensions {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Extensions {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Extensions of a type that is already in the list are skipped.
+        let mut extensions = Self::empty();
+        for extension in u.arbitrary_iter::<Extension>()? {
+            let _ = extensions.add(extension?);
+        }
+        Ok(extensions)
+    }
+}
+
 impl Extensions {
     /// Create an empty extension list.
     pub fn empty() -> Self {
         Self {
-            map: BTreeMap::new(),
+            extensions: Vec::new(),
         }
     }
 
     /// Create an extension list with a single extension.
     pub fn single(extension: Extension) -> Self {
         Self {
-            map: BTreeMap::from([(extension.extension_type(), extension)]),
+            extensions: vec![extension],
         }
     }
 
@@ -225,18 +343,18 @@ impl Extensions {
 
     /// Returns an iterator over the extension list.
     pub fn iter(&self) -> impl Iterator<Item = &Extension> {
-        self.map.values()
+        self.extensions.iter()
     }
 
     /// Add an extension to the extension list.
     ///
     /// Returns an error when there already is an extension with the same extension type.
     pub fn add(&mut self, extension: Extension) -> Result<(), InvalidExtensionError> {
-        if let Entry::Vacant(e) = self.map.entry(extension.extension_type()) {
-            e.insert(extension);
-            Ok(())
-        } else {
+        if self.contains(extension.extension_type()) {
             Err(InvalidExtensionError::Duplicate)
+        } else {
+            self.extensions.push(extension);
+            Ok(())
         }
     }
 
@@ -244,14 +362,21 @@ impl Extensions {
     ///
     /// Returns the replaced extension (if any).
     pub fn add_or_replace(&mut self, extension: Extension) -> Option<Extension> {
-        self.map.insert(extension.extension_type(), extension)
+        match self.position(extension.extension_type()) {
+            Some(position) => Some(std::mem::replace(&mut self.extensions[position], extension)),
+            None => {
+                self.extensions.push(extension);
+                None
+            }
+        }
     }
 
     /// Remove an extension from the extension list.
     ///
     /// Returns the removed extension or `None` when there is no extension with the given extension type.
     pub fn remove(&mut self, extension_type: ExtensionType) -> Option<Extension> {
-        self.map.remove(&extension_type)
+        self.position(extension_type)
+            .map(|position| self.extensions.remove(position))
     }
 
     /// Replace an extension in the extension list.
@@ -259,7 +384,7 @@ impl Extensions {
     /// Returns the replaced extension or an error when there is no extension with the given extension type.
     #[cfg(any(feature = "test-utils", test))]
     pub fn replace(&mut self, extension: Extension) -> Result<Extension, InvalidExtensionError> {
-        let got = self.map.remove(&extension.extension_type());
+        let got = self.remove(extension.extension_type());
 
         match got {
             Some(extension) => Ok(extension),
@@ -269,7 +394,22 @@ impl Extensions {
 
     /// Returns `true` iff the extension list contains an extension with the given extension type.
     pub fn contains(&self, extension_type: ExtensionType) -> bool {
-        self.map.contains_key(&extension_type)
+        self.position(extension_type).is_some()
+    }
+
+    /// Returns the position of the extension with the given extension type
+    /// in the list.
+    fn position(&self, extension_type: ExtensionType) -> Option<usize> {
+        self.extensions
+            .iter()
+            .position(|extension| extension.extension_type() == extension_type)
+    }
+
+    /// Returns the extension with the given extension type.
+    fn get(&self, extension_type: ExtensionType) -> Option<&Extension> {
+        self.extensions
+            .iter()
+            .find(|extension| extension.extension_type() == extension_type)
     }
 }
 
@@ -277,23 +417,20 @@ impl TryFrom<Vec<Extension>> for Extensions {
     type Error = InvalidExtensionError;
 
     fn try_from(candidate: Vec<Extension>) -> Result<Self, Self::Error> {
-        let mut map = BTreeMap::new();
+        let mut extensions = Extensions::empty();
 
         for extension in candidate.into_iter() {
-            if map.insert(extension.extension_type(), extension).is_some() {
-                return Err(InvalidExtensionError::Duplicate);
-            }
+            extensions.add(extension)?;
         }
 
-        Ok(Self { map })
+        Ok(extensions)
     }
 }
 
 impl Extensions {
     /// Get a reference to the [`ApplicationIdExtension`] if there is any.
     pub fn application_id(&self) -> Option<&ApplicationIdExtension> {
-        self.map
-            .get(&ExtensionType::ApplicationId)
+        self.get(ExtensionType::ApplicationId)
             .and_then(|e| match e {
                 Extension::ApplicationId(e) => Some(e),
                 _ => None,
@@ -302,18 +439,15 @@ impl Extensions {
 
     /// Get a reference to the [`RatchetTreeExtension`] if there is any.
     pub fn ratchet_tree(&self) -> Option<&RatchetTreeExtension> {
-        self.map
-            .get(&ExtensionType::RatchetTree)
-            .and_then(|e| match e {
-                Extension::RatchetTree(e) => Some(e),
-                _ => None,
-            })
+        self.get(ExtensionType::RatchetTree).and_then(|e| match e {
+            Extension::RatchetTree(e) => Some(e),
+            _ => None,
+        })
     }
 
     /// Get a reference to the [`RequiredCapabilitiesExtension`] if there is any.
     pub fn required_capabilities(&self) -> Option<&RequiredCapabilitiesExtension> {
-        self.map
-            .get(&ExtensionType::RequiredCapabilities)
+        self.get(ExtensionType::RequiredCapabilities)
             .and_then(|e| match e {
                 Extension::RequiredCapabilities(e) => Some(e),
                 _ => None,
@@ -322,23 +456,46 @@ impl Extensions {
 
     /// Get a reference to the [`ExternalPubExtension`] if there is any.
     pub fn external_pub(&self) -> Option<&ExternalPubExtension> {
-        self.map
-            .get(&ExtensionType::ExternalPub)
-            .and_then(|e| match e {
-                Extension::ExternalPub(e) => Some(e),
-                _ => None,
-            })
+        self.get(ExtensionType::ExternalPub).and_then(|e| match e {
+            Extension::ExternalPub(e) => Some(e),
+            _ => None,
+        })
     }
 
     /// Get a reference to the [`ExternalSendersExtension`] if there is any.
     pub fn external_senders(&self) -> Option<&ExternalSendersExtension> {
-        self.map
-            .get(&ExtensionType::ExternalSenders)
+        self.get(ExtensionType::ExternalSenders)
             .and_then(|e| match e {
                 Extension::ExternalSenders(e) => Some(e),
                 _ => None,
             })
     }
+
+    /// Get a reference to the [`BanListExtension`] if there is any.
+    pub fn ban_list(&self) -> Option<&BanListExtension> {
+        self.get(ExtensionType::BanList).and_then(|e| match e {
+            Extension::BanList(e) => Some(e),
+            _ => None,
+        })
+    }
+
+    /// Get a reference to the [`MaxMembersExtension`] if there is any.
+    pub fn max_members(&self) -> Option<&MaxMembersExtension> {
+        self.get(ExtensionType::MaxMembers).and_then(|e| match e {
+            Extension::MaxMembers(e) => Some(e),
+            _ => None,
+        })
+    }
+
+    /// Get a reference to the [`UnknownExtension`] with the given extension
+    /// type if there is any.
+    pub fn unknown(&self, extension_type: u16) -> Option<&UnknownExtension> {
+        self.get(ExtensionType::Unknown(extension_type))
+            .and_then(|e| match e {
+                Extension::Unknown(_, e) => Some(e),
+                _ => None,
+            })
+    }
 }
 
 impl Extension {
@@ -406,6 +563,42 @@ impl Extension {
         }
     }
 
+    /// Get a reference to this extension as [`BanListExtension`].
+    /// Returns an [`ExtensionError::InvalidExtensionType`] error if called on an
+    /// [`Extension`] that's not a [`BanListExtension`].
+    pub fn as_ban_list_extension(&self) -> Result<&BanListExtension, ExtensionError> {
+        match self {
+            Self::BanList(e) => Ok(e),
+            _ => Err(ExtensionError::InvalidExtensionType(
+                "This is not a BanListExtension".into(),
+            )),
+        }
+    }
+
+    /// Get a reference to this extension as [`MaxMembersExtension`].
+    /// Returns an [`ExtensionError::InvalidExtensionType`] error if called on an
+    /// [`Extension`] that's not a [`MaxMembersExtension`].
+    pub fn as_max_members_extension(&self) -> Result<&MaxMembersExtension, ExtensionError> {
+        match self {
+            Self::MaxMembers(e) => Ok(e),
+            _ => Err(ExtensionError::InvalidExtensionType(
+                "This is not a MaxMembersExtension".into(),
+            )),
+        }
+    }
+
+    /// Get a reference to this extension as [`UnknownExtension`].
+    /// Returns an [`ExtensionError::InvalidExtensionType`] error if called on an
+    /// [`Extension`] of a type that OpenMLS knows.
+    pub fn as_unknown_extension(&self) -> Result<&UnknownExtension, ExtensionError> {
+        match self {
+            Self::Unknown(_, e) => Ok(e),
+            _ => Err(ExtensionError::InvalidExtensionType(
+                "This is not an UnknownExtension".into(),
+            )),
+        }
+    }
+
     /// Returns the [`ExtensionType`]
     #[inline]
     pub const fn extension_type(&self) -> ExtensionType {
@@ -415,6 +608,9 @@ impl Extension {
             Extension::RequiredCapabilities(_) => ExtensionType::RequiredCapabilities,
             Extension::ExternalPub(_) => ExtensionType::ExternalPub,
             Extension::ExternalSenders(_) => ExtensionType::ExternalSenders,
+            Extension::BanList(_) => ExtensionType::BanList,
+            Extension::MaxMembers(_) => ExtensionType::MaxMembers,
+            Extension::Unknown(extension_type, _) => ExtensionType::Unknown(*extension_type),
         }
     }
 }
@@ -435,11 +631,15 @@ impl Ord for Extension {
 
 /// This function tries to extract a vector of nodes from the given extensions.
 ///
-/// Returns the vector of nodes if it finds one and `None` otherwise.
+/// Returns the vector of nodes if it finds one and `None` otherwise. Returns
+/// an error if the nodes of the ratchet tree extension can't be deserialized.
 pub(crate) fn try_nodes_from_extensions(
     other_extensions: &Extensions,
-) -> Option<Vec<Option<Node>>> {
-    other_extensions.ratchet_tree().map(|e| e.as_slice().into())
+) -> Result<Option<Vec<Option<Node>>>, tls_codec::Error> {
+    other_extensions
+        .ratchet_tree()
+        .map(|e| e.nodes().collect())
+        .transpose()
 }
 
 #[cfg(test)]
@@ -539,4 +739,31 @@ mod test {
             assert_eq!(candidate, got);
         }
     }
+
+    #[test]
+    fn unknown_extensions() {
+        // An unknown extension before a known one, such that a re-ordering
+        // would change the serialization.
+        let unknown = Extension::Unknown(0xff01, UnknownExtension(vec![1, 2, 3]));
+        let application_id = Extension::ApplicationId(ApplicationIdExtension::new(b"Test"));
+        let extensions = Extensions::from_vec(vec![unknown.clone(), application_id]).unwrap();
+        let bytes = extensions.tls_serialize_detached().unwrap();
+
+        let got = Extensions::tls_deserialize(&mut bytes.as_slice()).unwrap();
+        assert_eq!(got.tls_serialize_detached().unwrap(), bytes);
+        assert_eq!(got.iter().next(), Some(&unknown));
+        assert_eq!(got.unknown(0xff01), Some(&UnknownExtension(vec![1, 2, 3])));
+        assert!(got.unknown(0xff02).is_none());
+        assert!(unknown.as_unknown_extension().is_ok());
+        assert_eq!(unknown.extension_type(), ExtensionType::from(0xff01));
+        assert_eq!(u16::from(unknown.extension_type()), 0xff01);
+        assert!(!unknown.extension_type().is_supported());
+
+        // Unknown extensions of the same type are duplicates.
+        assert!(Extensions::from_vec(vec![
+            unknown.clone(),
+            Extension::Unknown(0xff01, UnknownExtension(vec![]))
+        ])
+        .is_err());
+    }
 }
@@ -1,4 +1,8 @@
-use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+use std::io::{Read, Write};
+
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Error, Serialize as TlsSerializeTrait, Size, VLBytes,
+};
 
 use super::{Deserialize, Serialize};
 use crate::treesync::node::Node;
@@ -7,30 +11,152 @@ use crate::treesync::node::Node;
 ///
 /// The ratchet tree extension contains a list of (optional) [`Node`]s that
 /// represent the public state of the tree in an MLS group.
-#[derive(
-    PartialEq,
-    Eq,
-    Clone,
-    Debug,
-    Default,
-    Serialize,
-    Deserialize,
-    TlsSerialize,
-    TlsDeserialize,
-    TlsSize,
-)]
+///
+/// The ratchet tree of a large group is big, so an extension that was
+/// deserialized, e.g. from the GroupInfo of a Welcome message, keeps its
+/// nodes in their serialized form. The nodes are only deserialized, and thereby
+/// checked, one at a time when iterating over them with
+/// [`RatchetTreeExtension::nodes()`]. This way, the ratchet tree is never held
+/// twice in memory and only parsed once when joining a group.
+#[derive(Clone, Debug)]
 pub struct RatchetTreeExtension {
-    tree: Vec<Option<Node>>,
+    tree: RatchetTree,
+}
+
+/// The nodes of a [`RatchetTreeExtension`], either as they were given to
+/// [`RatchetTreeExtension::new()`] or in their serialized form.
+#[derive(Clone, Debug)]
+enum RatchetTree {
+    Nodes(Vec<Option<Node>>),
+    Serialized(VLBytes),
 }
 
 impl RatchetTreeExtension {
     /// Build a new extension from a vector of [`Node`]s.
     pub fn new(tree: Vec<Option<Node>>) -> Self {
-        RatchetTreeExtension { tree }
+        RatchetTreeExtension {
+            tree: RatchetTree::Nodes(tree),
+        }
+    }
+
+    /// Returns an iterator over the nodes of the tree. If the extension was
+    /// deserialized, every node is deserialized when the iterator reaches it.
+    pub fn nodes(&self) -> RatchetTreeNodes<'_> {
+        let nodes = match &self.tree {
+            RatchetTree::Nodes(nodes) => NodesIter::Nodes(nodes.iter()),
+            RatchetTree::Serialized(bytes) => NodesIter::Serialized(bytes.as_slice()),
+        };
+        RatchetTreeNodes { nodes }
+    }
+}
+
+impl Default for RatchetTreeExtension {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl PartialEq for RatchetTreeExtension {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.tree, &other.tree) {
+            (RatchetTree::Nodes(nodes), RatchetTree::Nodes(other_nodes)) => nodes == other_nodes,
+            (RatchetTree::Serialized(bytes), RatchetTree::Serialized(other_bytes)) => {
+                bytes == other_bytes
+            }
+            // The serialized nodes are compared node by node, such that the
+            // other tree doesn't have to be serialized. A malformed node is
+            // never equal to a node of the other tree.
+            _ => self
+                .nodes()
+                .map(Result::ok)
+                .eq(other.nodes().map(Result::ok)),
+        }
+    }
+}
+
+impl Eq for RatchetTreeExtension {}
+
+/// An iterator over the nodes of a [`RatchetTreeExtension`], see
+/// [`RatchetTreeExtension::nodes()`].
+///
+/// The iterator returns an error and ends if a node can't be deserialized.
+pub struct RatchetTreeNodes<'a> {
+    nodes: NodesIter<'a>,
+}
+
+enum NodesIter<'a> {
+    Nodes(std::slice::Iter<'a, Option<Node>>),
+    Serialized(&'a [u8]),
+}
+
+impl<'a> Iterator for RatchetTreeNodes<'a> {
+    type Item = Result<Option<Node>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.nodes {
+            NodesIter::Nodes(nodes) => nodes.next().cloned().map(Ok),
+            NodesIter::Serialized(bytes) => {
+                if bytes.is_empty() {
+                    return None;
+                }
+                let node = Option::<Node>::tls_deserialize(bytes);
+                if node.is_err() {
+                    *bytes = &[];
+                }
+                Some(node)
+            }
+        }
+    }
+}
+
+impl Size for RatchetTreeExtension {
+    fn tls_serialized_len(&self) -> usize {
+        match &self.tree {
+            RatchetTree::Nodes(nodes) => nodes.tls_serialized_len(),
+            RatchetTree::Serialized(bytes) => bytes.tls_serialized_len(),
+        }
     }
+}
+
+impl TlsSerializeTrait for RatchetTreeExtension {
+    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
+        // The serialized nodes are written with the same length prefix as a
+        // vector of nodes.
+        match &self.tree {
+            RatchetTree::Nodes(nodes) => nodes.tls_serialize(writer),
+            RatchetTree::Serialized(bytes) => bytes.tls_serialize(writer),
+        }
+    }
+}
+
+impl TlsDeserializeTrait for RatchetTreeExtension {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
+        // The nodes are checked when they are deserialized by
+        // `RatchetTreeNodes`.
+        Ok(Self {
+            tree: RatchetTree::Serialized(VLBytes::tls_deserialize(bytes)?),
+        })
+    }
+}
+
+impl Serialize for RatchetTreeExtension {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.nodes()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RatchetTreeExtension {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Option<Node>>::deserialize(deserializer).map(Self::new)
+    }
+}
 
-    /// Get a slice of the nodes in tis tree.
-    pub(crate) fn as_slice(&self) -> &[Option<Node>] {
-        self.tree.as_slice()
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for RatchetTreeExtension {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(u.arbitrary()?))
     }
 }
@@ -39,6 +39,7 @@ use super::{Deserialize, ExtensionError, ExtensionType, Serialize};
     TlsDeserialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RequiredCapabilitiesExtension {
     extension_types: Vec<ExtensionType>,
     proposal_types: Vec<ProposalType>,
@@ -60,18 +61,17 @@ impl RequiredCapabilitiesExtension {
     }
 
     /// Get a slice with the required extension types.
-    pub(crate) fn extension_types(&self) -> &[ExtensionType] {
+    pub fn extension_types(&self) -> &[ExtensionType] {
         self.extension_types.as_slice()
     }
 
     /// Get a slice with the required proposal types.
-    pub(crate) fn proposal_types(&self) -> &[ProposalType] {
+    pub fn proposal_types(&self) -> &[ProposalType] {
         self.proposal_types.as_slice()
     }
 
     /// Get a slice with the required credential types.
-    #[allow(unused)]
-    pub(crate) fn credential_types(&self) -> &[CredentialType] {
+    pub fn credential_types(&self) -> &[CredentialType] {
         self.credential_types.as_slice()
     }
 
@@ -187,6 +187,69 @@ fn ratchet_tree_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
     );
 }
 
+// This tests that a deserialized ratchet tree extension keeps its nodes
+// serialized and deserializes them on demand.
+#[apply(ciphersuites_and_backends)]
+fn ratchet_tree_extension_lazy_nodes(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    let alice_credential_bundle = CredentialBundle::new(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let alice_group = CoreGroup::builder(
+        GroupId::random(backend),
+        config::CryptoConfig::with_default_version(ciphersuite),
+    )
+    .build(&alice_credential_bundle, backend)
+    .expect("Error creating group.");
+    let nodes = alice_group.treesync().export_nodes();
+
+    let extension = RatchetTreeExtension::new(nodes.clone());
+    let serialized = extension
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    // The extension is encoded like a vector of nodes.
+    assert_eq!(
+        serialized,
+        nodes
+            .tls_serialize_detached()
+            .expect("An unexpected error occurred.")
+    );
+
+    let deserialized = RatchetTreeExtension::tls_deserialize(&mut serialized.as_slice())
+        .expect("An unexpected error occurred.");
+    assert_eq!(deserialized, extension);
+    assert_eq!(
+        deserialized
+            .nodes()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("An unexpected error occurred."),
+        nodes
+    );
+    assert_eq!(
+        deserialized
+            .tls_serialize_detached()
+            .expect("An unexpected error occurred."),
+        serialized
+    );
+
+    // Malformed nodes are rejected when they are deserialized.
+    let malformed = tls_codec::VLBytes::from(vec![1u8, 0xff])
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let malformed = RatchetTreeExtension::tls_deserialize(&mut malformed.as_slice())
+        .expect("An unexpected error occurred.");
+    let mut malformed_nodes = malformed.nodes();
+    assert!(matches!(malformed_nodes.next(), Some(Err(_))));
+    assert!(malformed_nodes.next().is_none());
+    assert_ne!(malformed, RatchetTreeExtension::new(vec![None]));
+}
+
 #[test]
 fn required_capabilities() {
     // A required capabilities extension with the default values for openmls (none).
@@ -227,3 +290,93 @@ fn required_capabilities() {
     assert_eq!(ext, ext_decoded);
     assert_eq!(extension_bytes, encoded);
 }
+
+/// A custom extension that holds a room name.
+#[derive(Debug, PartialEq)]
+struct RoomExtension(String);
+
+impl CustomExtension for RoomExtension {
+    const EXTENSION_TYPE: u16 = 0xff01;
+
+    fn encode(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        tls_codec::VLBytes::new(self.0.as_bytes().to_vec()).tls_serialize_detached()
+    }
+
+    fn decode(mut extension_data: &[u8]) -> Result<Self, tls_codec::Error> {
+        let bytes = tls_codec::VLBytes::tls_deserialize(&mut extension_data)?;
+        String::from_utf8(bytes.as_slice().to_vec())
+            .map(RoomExtension)
+            .map_err(|_| tls_codec::Error::DecodingError("Invalid room name".into()))
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn custom_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use crate::treesync::node::leaf_node::Capabilities;
+
+    let credential_bundle = CredentialBundle::new(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let room = RoomExtension("lobby".into());
+    let extensions = Extensions::single(Extension::custom(&room).expect("Could not encode"));
+    let crypto_config = config::CryptoConfig::with_default_version(ciphersuite);
+
+    // The extension type has to be advertised in the capabilities.
+    let key_package = KeyPackage::builder()
+        .key_package_extensions(extensions.clone())
+        .build(crypto_config, backend, &credential_bundle)
+        .expect("An unexpected error occurred.");
+    assert_eq!(
+        key_package.verify(backend),
+        Err(crate::key_packages::errors::KeyPackageVerifyError::UnsupportedExtension)
+    );
+
+    let key_package = KeyPackage::builder()
+        .key_package_extensions(extensions)
+        .leaf_node_capabilities(
+            Capabilities::builder()
+                .custom_extension::<RoomExtension>()
+                .build(),
+        )
+        .build(crypto_config, backend, &credential_bundle)
+        .expect("An unexpected error occurred.");
+    assert!(key_package
+        .leaf_node()
+        .capabilities()
+        .extensions()
+        .contains(&ExtensionType::custom::<RoomExtension>()));
+
+    // The extension survives the serialization and decodes to the typed value.
+    let bytes = key_package
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let key_package =
+        KeyPackage::tls_deserialize(&mut bytes.as_slice()).expect("An unexpected error occurred.");
+    assert!(key_package.verify(backend).is_ok());
+    assert_eq!(
+        key_package.extensions().custom::<RoomExtension>(),
+        Ok(Some(room))
+    );
+
+    // Extension types that OpenMLS knows can't be used for custom extensions.
+    struct InvalidExtension;
+    impl CustomExtension for InvalidExtension {
+        const EXTENSION_TYPE: u16 = 1;
+
+        fn encode(&self) -> Result<Vec<u8>, tls_codec::Error> {
+            Ok(vec![])
+        }
+
+        fn decode(_: &[u8]) -> Result<Self, tls_codec::Error> {
+            Ok(InvalidExtension)
+        }
+    }
+    assert!(matches!(
+        Extension::custom(&InvalidExtension),
+        Err(ExtensionError::InvalidExtensionType(_))
+    ));
+}
@@ -2,7 +2,10 @@
 //!
 //! This module contains errors related to message framing operations.
 
-use crate::error::LibraryError;
+use crate::{
+    error::{error_codes, LibraryError},
+    group::GroupEpoch,
+};
 use thiserror::Error;
 
 // === Public ===
@@ -34,8 +37,58 @@ pub enum MessageDecryptionError {
     /// See [`SenderError`] for more details.
     #[error(transparent)]
     SenderError(#[from] SenderError),
+    /// The generation of the message is older than the out of order tolerance
+    /// of the sender ratchet allows.
+    #[error("The generation {received_generation} of the message is too old, the sender ratchet is at generation {current_generation}.")]
+    GenerationTooOld {
+        /// The generation the sender ratchet is at, i.e. the next generation
+        /// it expects.
+        current_generation: u32,
+        /// The generation of the message.
+        received_generation: u32,
+    },
+    /// The generation of the message is further ahead than the maximum
+    /// forward distance of the sender ratchet allows.
+    #[error("The generation {received_generation} of the message is too far ahead, the sender ratchet is at generation {current_generation}.")]
+    GenerationTooFarAhead {
+        /// The generation the sender ratchet is at, i.e. the next generation
+        /// it expects.
+        current_generation: u32,
+        /// The generation of the message.
+        received_generation: u32,
+    },
+    /// The secret of the generation of the message was deleted, either
+    /// because a message of that generation was already decrypted or because
+    /// it was evicted.
+    #[error("The secret of the generation {received_generation} of the message was deleted.")]
+    GenerationDeleted {
+        /// The generation of the message.
+        received_generation: u32,
+    },
+    /// The message is from an epoch whose secrets are not stored anymore.
+    #[error("The message is from epoch {}, but the secrets of that epoch are not stored anymore. The group is at epoch {}.", .received_epoch.as_u64(), .current_epoch.as_u64())]
+    EpochTooOld {
+        /// The epoch the group is at.
+        current_epoch: GroupEpoch,
+        /// The epoch of the message.
+        received_epoch: GroupEpoch,
+    },
 }
 
+error_codes!(MessageDecryptionError: 900 {
+    LibraryError = 1,
+    GenerationOutOfBound = 2,
+    AeadError = 3,
+    WrongWireFormat = 4,
+    MalformedContent = 5,
+    SecretTreeError = 6,
+    SenderError = 7,
+    GenerationTooOld = 8,
+    GenerationTooFarAhead = 9,
+    GenerationDeleted = 10,
+    EpochTooOld = 11,
+});
+
 /// Message encryption error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub(crate) enum MessageEncryptionError {
@@ -67,6 +120,12 @@ pub enum SenderError {
     UnknownSender,
 }
 
+error_codes!(SenderError: 1000 {
+    LibraryError = 1,
+    NotAMember = 2,
+    UnknownSender = 3,
+});
+
 /// MlsMessage error
 #[derive(Error, Debug, Clone)]
 pub enum MlsMessageError {
@@ -76,4 +135,33 @@ pub enum MlsMessageError {
     /// The message (or one of its parts) is too large to be encoded.
     #[error("The message (or one of its parts) is too large to be encoded.")]
     UnableToEncode,
+    /// The message uses an MLS version that is not supported.
+    #[error("The message uses an MLS version that is not supported.")]
+    UnsupportedMlsVersion,
+}
+
+error_codes!(MlsMessageError: 1100 {
+    UnableToDecode = 1,
+    UnableToEncode = 2,
+    UnsupportedMlsVersion = 3,
+});
+
+/// MlsMessage stream error
+#[derive(Error, Debug, Clone)]
+pub enum MlsMessageStreamError {
+    /// The frame uses a framing version that is not supported.
+    #[error("The frame uses a framing version that is not supported.")]
+    UnsupportedFramingVersion,
+    /// The message exceeds the maximum message length.
+    #[error("The message exceeds the maximum message length.")]
+    MessageTooLong,
+    /// See [`MlsMessageError`] for more details.
+    #[error(transparent)]
+    MlsMessageError(#[from] MlsMessageError),
 }
+
+error_codes!(MlsMessageStreamError: 5200 {
+    UnsupportedFramingVersion = 1,
+    MessageTooLong = 2,
+    MlsMessageError = 3,
+});
@@ -35,6 +35,7 @@ use crate::{key_packages::KeyPackage, versions::ProtocolVersion};
 /// which is commonly returned by functions of the [`MlsGroup`] API.
 #[derive(PartialEq, Debug, Clone, TlsSize, TlsDeserialize)]
 #[cfg_attr(feature = "test-utils", derive(TlsSerialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MlsMessageIn {
     pub(crate) version: ProtocolVersion,
     pub(crate) body: MlsMessageInBody,
@@ -69,6 +70,7 @@ pub struct MlsMessageIn {
 /// ```
 #[derive(Debug, PartialEq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum MlsMessageInBody {
     /// Plaintext message
     #[tls_codec(discriminant = 1)]
@@ -92,6 +94,12 @@ pub enum MlsMessageInBody {
 }
 
 impl MlsMessageIn {
+    /// Returns the MLS version of the message. This can be used to route the
+    /// message to a group running the same version.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
     /// Returns the wire format.
     pub fn wire_format(&self) -> WireFormat {
         match self.body {
@@ -109,9 +117,15 @@ impl MlsMessageIn {
         self.body
     }
 
-    /// Tries to deserialize from a byte slice. Returns [`MlsMessageError::UnableToDecode`] on failure.
+    /// Tries to deserialize from a byte slice. Returns [`MlsMessageError::UnableToDecode`] on failure
+    /// and [`MlsMessageError::UnsupportedMlsVersion`] if the message uses a version that is not supported.
     pub fn try_from_bytes(mut bytes: &[u8]) -> Result<Self, MlsMessageError> {
-        MlsMessageIn::tls_deserialize(&mut bytes).map_err(|_| MlsMessageError::UnableToDecode)
+        let message = MlsMessageIn::tls_deserialize(&mut bytes)
+            .map_err(|_| MlsMessageError::UnableToDecode)?;
+        if !message.version.is_supported() {
+            return Err(MlsMessageError::UnsupportedMlsVersion);
+        }
+        Ok(message)
     }
 
     #[cfg(test)]
@@ -138,21 +152,37 @@ impl MlsMessageIn {
         }
     }
 
-    #[cfg(any(feature = "test-utils", test))]
+    /// Converts the [`MlsMessageIn`] into a [`ProtocolMessage`] that keeps the
+    /// protocol version of the message, such that it is checked by
+    /// [`MlsGroup::process_message()`]. Returns `None` if the message is
+    /// neither a [`PublicMessage`] nor a [`PrivateMessage`].
     pub fn into_protocol_message(self) -> Option<ProtocolMessage> {
-        match self.body {
-            MlsMessageInBody::PublicMessage(m) => Some(m.into()),
-            MlsMessageInBody::PrivateMessage(m) => Some(m.into()),
-            _ => None,
-        }
+        let body = match self.body {
+            MlsMessageInBody::PublicMessage(m) => ProtocolMessageBody::PublicMessage(m),
+            MlsMessageInBody::PrivateMessage(m) => ProtocolMessageBody::PrivateMessage(m),
+            _ => return None,
+        };
+        Some(ProtocolMessage {
+            version: Some(self.version),
+            body,
+        })
     }
 }
 
-/// Enum containing a message for use with `process_message` and an
-/// [`MlsGroup`]. Both [`PublicMessage`] and [`PrivateMessage`] implement
-/// [`Into<ProtocolMessage>`].
+/// A message for use with `process_message` and an [`MlsGroup`]. Both
+/// [`PublicMessage`] and [`PrivateMessage`] implement
+/// [`Into<ProtocolMessage>`]. A [`ProtocolMessage`] converted from an
+/// [`MlsMessageIn`] with [`MlsMessageIn::into_protocol_message()`] also keeps
+/// the protocol version of the message.
 #[derive(Debug, Clone)]
-pub enum ProtocolMessage {
+pub struct ProtocolMessage {
+    version: Option<ProtocolVersion>,
+    body: ProtocolMessageBody,
+}
+
+/// The content of a [`ProtocolMessage`].
+#[derive(Debug, Clone)]
+pub enum ProtocolMessageBody {
     /// A [`ProtocolMessage`] containing a [`PrivateMessage`].
     PrivateMessage(PrivateMessage),
     /// A [`ProtocolMessage`] containing a [`PublicMessage`].
@@ -160,49 +190,65 @@ pub enum ProtocolMessage {
 }
 
 impl ProtocolMessage {
+    /// Returns the protocol version of the message, if it was converted from
+    /// an [`MlsMessageIn`], and `None` otherwise.
+    pub fn version(&self) -> Option<ProtocolVersion> {
+        self.version
+    }
+
+    /// Returns the content of the message.
+    pub fn body(&self) -> &ProtocolMessageBody {
+        &self.body
+    }
+
+    /// Returns the content of the message, dropping the protocol version.
+    pub fn into_body(self) -> ProtocolMessageBody {
+        self.body
+    }
+
     /// Returns the wire format.
     pub fn wire_format(&self) -> WireFormat {
-        match self {
-            ProtocolMessage::PrivateMessage(_) => WireFormat::PrivateMessage,
-            ProtocolMessage::PublicMessage(_) => WireFormat::PublicMessage,
+        match self.body {
+            ProtocolMessageBody::PrivateMessage(_) => WireFormat::PrivateMessage,
+            ProtocolMessageBody::PublicMessage(_) => WireFormat::PublicMessage,
         }
     }
 
     /// Returns the group ID.
     pub fn group_id(&self) -> &GroupId {
-        match self {
-            ProtocolMessage::PrivateMessage(ref m) => m.group_id(),
-            ProtocolMessage::PublicMessage(ref m) => m.group_id(),
+        match self.body {
+            ProtocolMessageBody::PrivateMessage(ref m) => m.group_id(),
+            ProtocolMessageBody::PublicMessage(ref m) => m.group_id(),
         }
     }
 
     /// Returns the epoch.
     pub fn epoch(&self) -> GroupEpoch {
-        match self {
-            ProtocolMessage::PrivateMessage(ref m) => m.epoch(),
-            ProtocolMessage::PublicMessage(ref m) => m.epoch(),
+        match self.body {
+            ProtocolMessageBody::PrivateMessage(ref m) => m.epoch(),
+            ProtocolMessageBody::PublicMessage(ref m) => m.epoch(),
         }
     }
 
     /// Returns the content type.
     pub fn content_type(&self) -> ContentType {
-        match self {
-            ProtocolMessage::PrivateMessage(ref m) => m.content_type(),
-            ProtocolMessage::PublicMessage(ref m) => m.content_type(),
+        match self.body {
+            ProtocolMessageBody::PrivateMessage(ref m) => m.content_type(),
+            ProtocolMessageBody::PublicMessage(ref m) => m.content_type(),
         }
     }
 
     /// Returns `true` if this is either an external proposal or external commit
     pub fn is_external(&self) -> bool {
-        match &self {
-            ProtocolMessage::PublicMessage(p) => {
+        match &self.body {
+            ProtocolMessageBody::PublicMessage(p) => {
                 matches!(
                     p.sender(),
                     Sender::NewMemberProposal | Sender::NewMemberCommit | Sender::External(_)
                 )
             }
             // external message cannot be encrypted
-            ProtocolMessage::PrivateMessage(_) => false,
+            ProtocolMessageBody::PrivateMessage(_) => false,
         }
     }
 
@@ -212,25 +258,30 @@ impl ProtocolMessage {
     }
 }
 
+impl From<ProtocolMessageBody> for ProtocolMessage {
+    fn from(body: ProtocolMessageBody) -> Self {
+        ProtocolMessage {
+            version: None,
+            body,
+        }
+    }
+}
+
 impl From<PrivateMessage> for ProtocolMessage {
     fn from(private_message: PrivateMessage) -> Self {
-        ProtocolMessage::PrivateMessage(private_message)
+        ProtocolMessageBody::PrivateMessage(private_message).into()
     }
 }
 
 impl From<PublicMessage> for ProtocolMessage {
     fn from(public_message: PublicMessage) -> Self {
-        ProtocolMessage::PublicMessage(public_message)
+        ProtocolMessageBody::PublicMessage(public_message).into()
     }
 }
 
 #[cfg(any(feature = "test-utils", test))]
 impl From<MlsMessageIn> for ProtocolMessage {
     fn from(msg: MlsMessageIn) -> Self {
-        match msg.body {
-            MlsMessageInBody::PublicMessage(m) => ProtocolMessage::PublicMessage(m),
-            MlsMessageInBody::PrivateMessage(m) => ProtocolMessage::PrivateMessage(m),
-            _ => panic!("Wrong message type"),
-        }
+        msg.into_protocol_message().expect("Wrong message type")
     }
 }
@@ -138,11 +138,7 @@ impl MlsMessageOut {
     #[cfg(any(feature = "test-utils", test))]
     pub fn into_protocol_message(self) -> Option<ProtocolMessage> {
         let mls_message_in: MlsMessageIn = self.into();
-        match mls_message_in.extract() {
-            MlsMessageInBody::PublicMessage(pm) => Some(pm.into()),
-            MlsMessageInBody::PrivateMessage(pm) => Some(pm.into()),
-            _ => None,
-        }
+        mls_message_in.into_protocol_message()
     }
 
     #[cfg(any(feature = "test-utils", test))]
@@ -10,6 +10,7 @@ use crate::{
     binary_tree::LeafNodeIndex,
     ciphersuite::signable::{Signable, SignedStruct, Verifiable, VerifiedStruct},
     error::LibraryError,
+    extensions::ExternalSendersExtension,
     group::errors::ValidationError,
 };
 
@@ -181,10 +182,10 @@ impl AuthenticatedContent {
     }
 
     /// This constructor builds an `PublicMessage` containing an External Proposal.
-    /// The sender is [Sender::NewMemberProposal].
-    // TODO #151/#106: We don't support preconfigured senders yet
+    /// The sender is either [Sender::NewMemberProposal] or [Sender::External].
     pub(crate) fn new_external_proposal(
         proposal: Proposal,
+        sender: Sender,
         credential_bundle: &CredentialBundle,
         group_id: GroupId,
         epoch: GroupEpoch,
@@ -196,7 +197,7 @@ impl AuthenticatedContent {
             WireFormat::PublicMessage,
             group_id,
             epoch,
-            Sender::NewMemberProposal,
+            sender,
             vec![].into(),
             body,
         );
@@ -350,6 +351,40 @@ impl VerifiableAuthenticatedContent {
         }
     }
 
+    /// Returns the [`Credential`] of the external sender of the
+    /// [`VerifiableAuthenticatedContent`] if the `sender_type` is
+    /// [`Sender::External`] and the sender is listed in `external_senders`.
+    ///
+    /// Returns a [`ValidationError`] if
+    /// * the sender type is not [`Sender::External`],
+    /// * the content is not a proposal that an external sender can send, or
+    /// * the sender is not listed in `external_senders`.
+    pub(crate) fn external_sender_credential(
+        &self,
+        external_senders: Option<&ExternalSendersExtension>,
+    ) -> Result<Credential, ValidationError> {
+        let sender_index = match self.auth_content.tbs.content.sender {
+            Sender::External(sender_index) => sender_index,
+            _ => return Err(ValidationError::UnknownMember),
+        };
+        // External senders don't have a leaf in the tree, so they can't send
+        // Update or ExternalInit proposals.
+        match &self.auth_content.tbs.content.body {
+            FramedContentBody::Proposal(
+                Proposal::Add(_)
+                | Proposal::Remove(_)
+                | Proposal::PreSharedKey(_)
+                | Proposal::ReInit(_)
+                | Proposal::GroupContextExtensions(_),
+            ) => (),
+            _ => return Err(ValidationError::NotAnExternalSenderProposal),
+        }
+        external_senders
+            .and_then(|external_senders| external_senders.get(sender_index.usize()))
+            .map(|external_sender| external_sender.credential().clone())
+            .ok_or(ValidationError::UnknownExternalSender)
+    }
+
     /// Get the wire format.
     pub(crate) fn wire_format(&self) -> WireFormat {
         self.auth_content.tbs.wire_format
@@ -39,10 +39,12 @@ use tls_codec::{
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct FramedContent {
     pub(super) group_id: GroupId,
     pub(super) epoch: GroupEpoch,
     pub(super) sender: Sender,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     pub(super) authenticated_data: VLBytes,
 
     pub(super) body: FramedContentBody,
@@ -83,6 +85,17 @@ pub(crate) enum FramedContentBody {
     Commit(Commit),
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FramedContentBody {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.int_in_range(0..=2)? {
+            0 => FramedContentBody::Application(u.arbitrary::<Vec<u8>>()?.into()),
+            1 => FramedContentBody::Proposal(u.arbitrary()?),
+            _ => FramedContentBody::Commit(u.arbitrary()?),
+        })
+    }
+}
+
 impl FramedContentBody {
     pub(crate) fn content_type(&self) -> ContentType {
         match self {
@@ -131,6 +144,7 @@ impl FramedContentBody {
     PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ContentType {
     Application = 1,
     Proposal = 2,
@@ -53,9 +53,11 @@ pub(crate) mod message_in;
 pub(crate) mod message_out;
 pub(crate) mod mls_auth_content;
 pub(crate) mod mls_content;
+pub(crate) mod pre_validation;
 pub(crate) mod private_message;
 pub(crate) mod public_message;
 pub(crate) mod sender;
+pub(crate) mod stream;
 pub(crate) mod validation;
 pub(crate) use errors::*;
 pub use private_message::*;
@@ -75,7 +77,9 @@ pub mod errors;
 
 pub use message_in::*;
 pub use message_out::*;
+pub use pre_validation::*;
 pub use sender::*;
+pub use stream::*;
 pub use validation::*;
 
 // Tests
@@ -0,0 +1,178 @@
+//! # Pre-validation of protocol messages
+//!
+//! [`ProtocolMessage::pre_validate()`] checks the cleartext framing of a
+//! message against a [`GroupView`] before any signature, membership tag or
+//! AEAD operation is performed. Messages that fail the pre-validation can be
+//! dropped cheaply, e.g. by a Delivery Service that receives junk messages
+//! for a group.
+//!
+//! Passing the pre-validation doesn't mean that a message is valid. Messages
+//! still have to be processed with [`MlsGroup::process_message()`] or
+//! [`PublicGroup::process_message()`], which perform the full validation.
+
+use super::{
+    mls_content::{ContentType, FramedContentBody},
+    *,
+};
+use crate::group::errors::ValidationError;
+
+/// The parameters of a group that are needed to pre-validate messages, see
+/// [`ProtocolMessage::pre_validate()`].
+///
+/// A view of an [`MlsGroup`] is returned by [`MlsGroup::group_view()`].
+/// Parties that don't follow the state of a group, e.g. a Delivery Service
+/// with a [`PublicGroup`], create the view with [`GroupView::new()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupView {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    max_past_epochs: usize,
+    wire_format_policy: IncomingWireFormatPolicy,
+}
+
+impl GroupView {
+    /// Creates a view of the group with the given `group_id` in the given
+    /// `epoch`. Application messages are accepted from up to
+    /// `max_past_epochs` epochs in the past and handshake messages have to
+    /// comply with the `wire_format_policy`.
+    pub fn new(
+        group_id: GroupId,
+        epoch: GroupEpoch,
+        max_past_epochs: usize,
+        wire_format_policy: IncomingWireFormatPolicy,
+    ) -> Self {
+        Self {
+            group_id,
+            epoch,
+            max_past_epochs,
+            wire_format_policy,
+        }
+    }
+
+    /// Returns the group ID.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the current epoch of the group.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the number of past epochs application messages are accepted
+    /// from.
+    pub fn max_past_epochs(&self) -> usize {
+        self.max_past_epochs
+    }
+
+    /// Returns the wire format policy for incoming handshake messages.
+    pub fn wire_format_policy(&self) -> IncomingWireFormatPolicy {
+        self.wire_format_policy
+    }
+}
+
+impl ProtocolMessage {
+    /// Checks the cleartext framing of the message against the `group_view`
+    /// without performing any cryptographic operation.
+    ///
+    /// The following checks are performed:
+    ///  - The group ID matches (ValSem002).
+    ///  - Handshake messages are from the current epoch and application
+    ///    messages are from the current or one of the last
+    ///    `max_past_epochs` epochs (ValSem003).
+    ///  - Handshake messages comply with the wire format policy and
+    ///    application messages are encrypted (ValSem005).
+    ///  - The content of a [`PublicMessage`] is consistent with its sender,
+    ///    i.e. members include a membership tag, new members only send
+    ///    external commits with a path or Add proposals and external senders
+    ///    only send proposals they are allowed to send.
+    ///  - Commits in a [`PublicMessage`] include a confirmation tag (ValSem009).
+    ///
+    /// Returns a [`ValidationError`] if one of the checks fails.
+    pub fn pre_validate(&self, group_view: &GroupView) -> Result<(), ValidationError> {
+        // ValSem002
+        if self.group_id() != group_view.group_id() {
+            return Err(ValidationError::WrongGroupId);
+        }
+
+        // ValSem003
+        let epoch = self.epoch().as_u64();
+        let current_epoch = group_view.epoch().as_u64();
+        if self.content_type() == ContentType::Application {
+            if epoch > current_epoch {
+                return Err(ValidationError::WrongEpoch);
+            }
+            if current_epoch - epoch > group_view.max_past_epochs() as u64 {
+                return Err(ValidationError::NoPastEpochData);
+            }
+        } else if epoch != current_epoch {
+            return Err(ValidationError::WrongEpoch);
+        }
+
+        // ValSem005
+        if self.content_type() == ContentType::Application {
+            if self.wire_format() != WireFormat::PrivateMessage {
+                return Err(ValidationError::UnencryptedApplicationMessage);
+            }
+        } else if !group_view
+            .wire_format_policy()
+            .is_compatible_with(self.wire_format())
+        {
+            return Err(ValidationError::WrongWireFormat);
+        }
+
+        // The sender and the authentication data of private messages are
+        // encrypted.
+        let public_message = match self.body() {
+            ProtocolMessageBody::PublicMessage(public_message) => public_message,
+            ProtocolMessageBody::PrivateMessage(_) => return Ok(()),
+        };
+
+        match public_message.sender() {
+            Sender::Member(_) => {
+                if !public_message.has_membership_tag() {
+                    return Err(ValidationError::MissingMembershipTag);
+                }
+            }
+            Sender::NewMemberCommit => match public_message.content() {
+                FramedContentBody::Commit(commit) => {
+                    if commit.path().is_none() {
+                        return Err(ValidationError::NoPath);
+                    }
+                }
+                _ => return Err(ValidationError::NotACommit),
+            },
+            Sender::NewMemberProposal => {
+                if !matches!(
+                    public_message.content(),
+                    FramedContentBody::Proposal(Proposal::Add(_))
+                ) {
+                    return Err(ValidationError::NotAnExternalAddProposal);
+                }
+            }
+            Sender::External(_) => {
+                if !matches!(
+                    public_message.content(),
+                    FramedContentBody::Proposal(
+                        Proposal::Add(_)
+                            | Proposal::Remove(_)
+                            | Proposal::PreSharedKey(_)
+                            | Proposal::ReInit(_)
+                            | Proposal::GroupContextExtensions(_),
+                    )
+                ) {
+                    return Err(ValidationError::NotAnExternalSenderProposal);
+                }
+            }
+        }
+
+        // ValSem009
+        if public_message.content_type() == ContentType::Commit
+            && public_message.confirmation_tag().is_none()
+        {
+            return Err(ValidationError::MissingConfirmationTag);
+        }
+
+        Ok(())
+    }
+}
@@ -16,7 +16,7 @@ use crate::{
     framing::mls_content::FramedContentTbs,
     tree::{
         index::SecretTreeLeafIndex, secret_tree::SecretType,
-        sender_ratchet::SenderRatchetConfiguration,
+        sender_ratchet::SenderRatchetConfigurations,
     },
 };
 
@@ -38,12 +38,16 @@ use super::*;
 /// } PrivateMessage;
 /// ```
 #[derive(Debug, PartialEq, Eq, Clone, TlsSerialize, TlsSize, TlsDeserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PrivateMessage {
     group_id: GroupId,
     epoch: GroupEpoch,
     content_type: ContentType,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     authenticated_data: VLBytes,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     encrypted_sender_data: VLBytes,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     ciphertext: VLBytes,
 }
 
@@ -334,7 +338,7 @@ impl PrivateMessage {
         backend: &impl OpenMlsCryptoProvider,
         message_secrets: &mut MessageSecrets,
         sender_index: SecretTreeLeafIndex,
-        sender_ratchet_configuration: &SenderRatchetConfiguration,
+        sender_ratchet_configurations: &SenderRatchetConfigurations,
         sender_data: MlsSenderData,
     ) -> Result<VerifiableAuthenticatedContent, MessageDecryptionError> {
         let secret_type = SecretType::from(&self.content_type);
@@ -347,11 +351,35 @@ impl PrivateMessage {
                 sender_index,
                 secret_type,
                 sender_data.generation,
-                sender_ratchet_configuration,
+                sender_ratchet_configurations.for_sender(sender_data.leaf_index),
             )
-            .map_err(|_| {
+            .map_err(|e| {
                 log::error!("  Ciphertext generation out of bounds");
-                MessageDecryptionError::GenerationOutOfBound
+                let current_generation = || {
+                    message_secrets
+                        .secret_tree()
+                        .generation(sender_index, secret_type)
+                };
+                match e {
+                    SecretTreeError::TooDistantInThePast => {
+                        MessageDecryptionError::GenerationTooOld {
+                            current_generation: current_generation(),
+                            received_generation: sender_data.generation,
+                        }
+                    }
+                    SecretTreeError::TooDistantInTheFuture => {
+                        MessageDecryptionError::GenerationTooFarAhead {
+                            current_generation: current_generation(),
+                            received_generation: sender_data.generation,
+                        }
+                    }
+                    SecretTreeError::SecretReuseError => {
+                        MessageDecryptionError::GenerationDeleted {
+                            received_generation: sender_data.generation,
+                        }
+                    }
+                    _ => MessageDecryptionError::GenerationOutOfBound,
+                }
             })?;
         // Prepare the nonce by xoring with the reuse guard.
         let prepared_nonce = ratchet_nonce.xor_with_reuse_guard(&sender_data.reuse_guard);
@@ -431,11 +459,20 @@ impl PrivateMessage {
     }
 
     /// Get the cipher text bytes as slice.
-    #[cfg(test)]
     pub(crate) fn ciphertext(&self) -> &[u8] {
         self.ciphertext.as_slice()
     }
 
+    /// Get the authenticated data bytes as slice.
+    pub(crate) fn authenticated_data(&self) -> &[u8] {
+        self.authenticated_data.as_slice()
+    }
+
+    /// Get the encrypted sender data bytes as slice.
+    pub(crate) fn encrypted_sender_data(&self) -> &[u8] {
+        self.encrypted_sender_data.as_slice()
+    }
+
     /// Get the `epoch` in the `PrivateMessage`.
     pub(crate) fn epoch(&self) -> GroupEpoch {
         self.epoch
@@ -27,6 +27,7 @@ use tls_codec::{
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct MembershipTag(pub(crate) Mac);
 
 /// [`PublicMessage`] is a framing structure for MLS messages. It can contain
@@ -237,6 +238,21 @@ impl PublicMessage {
         &self.content.content.group_id
     }
 
+    /// Get the authenticated data of this message.
+    pub(crate) fn authenticated_data(&self) -> &[u8] {
+        self.content.content.authenticated_data.as_slice()
+    }
+
+    /// Get the signature of this message.
+    pub(crate) fn signature(&self) -> &Signature {
+        &self.auth.signature
+    }
+
+    /// Returns `true` if the message has a membership tag.
+    pub(crate) fn has_membership_tag(&self) -> bool {
+        self.membership_tag.is_some()
+    }
+
     /// Set the context for later verification if applicable. If the sender type
     /// is not [`Sender::Member`] or [`Sender::NewMemberCommit`], this function
     /// will set the context to `None`.
@@ -348,6 +364,38 @@ impl TlsDeserializeTrait for PublicMessage {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PublicMessage {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // As upon deserialization, commits carry a confirmation tag and
+        // messages of members carry a membership tag.
+        let content: FramedContent = u.arbitrary()?;
+        let auth = FramedContentAuthData {
+            signature: u.arbitrary()?,
+            confirmation_tag: if content.body.content_type() == ContentType::Commit {
+                Some(u.arbitrary()?)
+            } else {
+                None
+            },
+        };
+        let membership_tag = if content.sender.is_member() {
+            Some(u.arbitrary()?)
+        } else {
+            None
+        };
+        let tbs = FramedContentTbs::new(
+            WireFormat::PublicMessage,
+            content.group_id,
+            content.epoch,
+            content.sender,
+            content.authenticated_data,
+            content.body,
+        );
+
+        Ok(PublicMessage::new(tbs, auth, membership_tag))
+    }
+}
+
 impl Size for PublicMessage {
     #[inline]
     fn tls_serialized_len(&self) -> usize {
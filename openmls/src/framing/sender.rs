@@ -36,12 +36,14 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Sender {
     /// The sender is a member of the group
     #[tls_codec(discriminant = 1)]
     Member(LeafNodeIndex),
-    /// The sender is not a member of the group and has an external value instead
-    External(TlsByteVecU8),
+    /// The sender is not a member of the group and is listed in the
+    /// external senders extension of the group instead
+    External(SenderExtensionIndex),
     /// The sender is a new member of the group that joins itself through
     /// an [External Add proposal](crate::messages::external_proposals::JoinProposal)
     NewMemberProposal,
@@ -75,3 +77,44 @@ impl Sender {
         }
     }
 }
+
+/// The index of an external sender in the
+/// [`ExternalSendersExtension`](crate::extensions::ExternalSendersExtension)
+/// of a group.
+///
+/// ```c
+/// // draft-ietf-mls-protocol-16
+/// uint32 sender_index;
+/// ```
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsSize,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SenderExtensionIndex(u32);
+
+impl SenderExtensionIndex {
+    /// Create a new `SenderExtensionIndex` from a `u32`.
+    pub fn new(index: u32) -> Self {
+        SenderExtensionIndex(index)
+    }
+
+    /// Return the inner value as `u32`.
+    pub fn u32(&self) -> u32 {
+        self.0
+    }
+
+    /// Return the inner value as `usize`.
+    pub fn usize(&self) -> usize {
+        self.u32() as usize
+    }
+}
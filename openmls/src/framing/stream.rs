@@ -0,0 +1,176 @@
+//! # Stream framing
+//!
+//! This module frames MLS messages for transports that provide a byte stream
+//! instead of individual messages, e.g. TCP or a WebSocket that delivers
+//! partial messages.
+//!
+//! Each message is framed as follows:
+//!
+//! ```text
+//! struct {
+//!     uint8 framing_version;
+//!     opaque mls_message<V32>;
+//! } MlsMessageFrame;
+//! ```
+//!
+//! The [`MlsMessageStreamEncoder`] frames [`MlsMessageOut`]s. The
+//! [`MlsMessageStreamDecoder`] is fed with the bytes read from the stream, in
+//! chunks of arbitrary size, and yields an [`MlsMessageIn`] for every complete
+//! frame.
+
+use super::{errors::MlsMessageStreamError, MlsMessageIn, MlsMessageOut};
+
+/// The framing version written by the [`MlsMessageStreamEncoder`].
+pub const MLS_MESSAGE_FRAMING_VERSION: u8 = 1;
+
+/// The default maximum length of a framed message in bytes.
+pub const DEFAULT_MAX_MESSAGE_LENGTH: usize = 1 << 24;
+
+/// Length of the frame header, i.e. the framing version and the length
+/// prefix.
+const HEADER_LENGTH: usize = 1 + 4;
+
+/// Frames [`MlsMessageOut`]s for a byte stream.
+#[derive(Debug, Clone)]
+pub struct MlsMessageStreamEncoder {
+    max_message_length: usize,
+}
+
+impl Default for MlsMessageStreamEncoder {
+    fn default() -> Self {
+        Self {
+            max_message_length: DEFAULT_MAX_MESSAGE_LENGTH,
+        }
+    }
+}
+
+impl MlsMessageStreamEncoder {
+    /// Creates a new encoder with the [`DEFAULT_MAX_MESSAGE_LENGTH`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum length of a message in bytes. Longer messages are
+    /// rejected with [`MlsMessageStreamError::MessageTooLong`]. The length
+    /// must not exceed the maximum length configured on the receiving
+    /// [`MlsMessageStreamDecoder`].
+    pub fn with_max_message_length(mut self, max_message_length: usize) -> Self {
+        self.max_message_length = max_message_length;
+        self
+    }
+
+    /// Appends the framed `message` to `buffer` and returns the number of
+    /// bytes written.
+    pub fn encode(
+        &self,
+        message: &MlsMessageOut,
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize, MlsMessageStreamError> {
+        let message = message.to_bytes()?;
+        if message.len() > self.max_message_length {
+            return Err(MlsMessageStreamError::MessageTooLong);
+        }
+        let length =
+            u32::try_from(message.len()).map_err(|_| MlsMessageStreamError::MessageTooLong)?;
+
+        buffer.reserve(HEADER_LENGTH + message.len());
+        buffer.push(MLS_MESSAGE_FRAMING_VERSION);
+        buffer.extend_from_slice(&length.to_be_bytes());
+        buffer.extend_from_slice(&message);
+        Ok(HEADER_LENGTH + message.len())
+    }
+
+    /// Returns the framed `message`.
+    pub fn to_frame(&self, message: &MlsMessageOut) -> Result<Vec<u8>, MlsMessageStreamError> {
+        let mut buffer = Vec::new();
+        self.encode(message, &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+/// Incrementally decodes framed [`MlsMessageIn`]s from a byte stream.
+///
+/// ```ignore
+/// let mut decoder = MlsMessageStreamDecoder::new();
+/// loop {
+///     let read = stream.read(&mut read_buffer)?;
+///     decoder.push(&read_buffer[..read]);
+///     while let Some(message) = decoder.next_message()? {
+///         // Process the message
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MlsMessageStreamDecoder {
+    buffer: Vec<u8>,
+    max_message_length: usize,
+}
+
+impl Default for MlsMessageStreamDecoder {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            max_message_length: DEFAULT_MAX_MESSAGE_LENGTH,
+        }
+    }
+}
+
+impl MlsMessageStreamDecoder {
+    /// Creates a new decoder with the [`DEFAULT_MAX_MESSAGE_LENGTH`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum length of a message in bytes. Frames announcing a
+    /// longer message are rejected with
+    /// [`MlsMessageStreamError::MessageTooLong`] before the message is
+    /// buffered.
+    pub fn with_max_message_length(mut self, max_message_length: usize) -> Self {
+        self.max_message_length = max_message_length;
+        self
+    }
+
+    /// Appends bytes read from the stream.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the number of buffered bytes that haven't been decoded yet.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the next message if a complete frame has been pushed, and
+    /// `None` if more bytes are needed.
+    ///
+    /// If the message of a frame can't be decoded, the frame is skipped and
+    /// the error is returned, i.e. the decoder can be used for the following
+    /// frames. An [`MlsMessageStreamError::UnsupportedFramingVersion`] or
+    /// [`MlsMessageStreamError::MessageTooLong`] error means that the stream
+    /// is corrupted and must be closed.
+    pub fn next_message(&mut self) -> Result<Option<MlsMessageIn>, MlsMessageStreamError> {
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+        if self.buffer[0] != MLS_MESSAGE_FRAMING_VERSION {
+            return Err(MlsMessageStreamError::UnsupportedFramingVersion);
+        }
+        if self.buffer.len() < HEADER_LENGTH {
+            return Ok(None);
+        }
+
+        let mut length = [0u8; 4];
+        length.copy_from_slice(&self.buffer[1..HEADER_LENGTH]);
+        let length = u32::from_be_bytes(length) as usize;
+        if length > self.max_message_length {
+            return Err(MlsMessageStreamError::MessageTooLong);
+        }
+        if self.buffer.len() < HEADER_LENGTH + length {
+            return Ok(None);
+        }
+
+        let frame: Vec<u8> = self.buffer.drain(..HEADER_LENGTH + length).collect();
+        let message = MlsMessageIn::try_from_bytes(&frame[HEADER_LENGTH..])?;
+        Ok(Some(message))
+    }
+}
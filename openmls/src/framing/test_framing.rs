@@ -21,7 +21,10 @@ use crate::{
         tests::tree_printing::print_tree,
     },
     key_packages::KeyPackageBundle,
-    tree::{secret_tree::SecretTree, sender_ratchet::SenderRatchetConfiguration},
+    tree::{
+        secret_tree::SecretTree,
+        sender_ratchet::{SenderRatchetConfiguration, SenderRatchetConfigurations},
+    },
     versions::ProtocolVersion,
 };
 
@@ -29,7 +32,7 @@ use crate::{
 #[apply(ciphersuites_and_backends)]
 fn codec_plaintext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     let credential_bundle = CredentialBundle::new(
-        vec![7, 8, 9],
+        vec![7, 8, 9].into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
@@ -37,6 +40,7 @@ fn codec_plaintext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
     .expect("An unexpected error occurred.");
     let sender = Sender::build_member(LeafNodeIndex::new(987543210));
     let group_context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         GroupId::random(backend),
         1,
@@ -84,7 +88,7 @@ fn codec_plaintext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
 #[apply(ciphersuites_and_backends)]
 fn codec_ciphertext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     let credential_bundle = CredentialBundle::new(
-        vec![7, 8, 9],
+        vec![7, 8, 9].into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
@@ -92,6 +96,7 @@ fn codec_ciphertext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
     .expect("An unexpected error occurred.");
     let sender = Sender::build_member(LeafNodeIndex::new(0));
     let group_context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         GroupId::from_slice(&[5, 5, 5]),
         1,
@@ -161,7 +166,7 @@ fn codec_ciphertext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
 /// This tests the correctness of wire format checks
 #[apply(ciphersuites_and_backends)]
 fn wire_format_checks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
-    let configuration = &SenderRatchetConfiguration::default();
+    let configuration = &SenderRatchetConfigurations::from(SenderRatchetConfiguration::default());
     let (plaintext, _credential) = create_content(ciphersuite, WireFormat::PrivateMessage, backend);
 
     let mut message_secrets = MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0));
@@ -295,7 +300,7 @@ fn create_content(
     backend: &impl OpenMlsCryptoProvider,
 ) -> (AuthenticatedContent, Credential) {
     let credential_bundle = CredentialBundle::new(
-        vec![7, 8, 9],
+        vec![7, 8, 9].into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
@@ -303,6 +308,7 @@ fn create_content(
     .expect("An unexpected error occurred.");
     let sender = Sender::build_member(LeafNodeIndex::new(0));
     let group_context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         GroupId::from_slice(&[5, 5, 5]),
         1,
@@ -332,13 +338,14 @@ fn create_content(
 #[apply(ciphersuites_and_backends)]
 fn membership_tag(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     let credential_bundle = CredentialBundle::new(
-        vec![7, 8, 9],
+        vec![7, 8, 9].into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
     )
     .expect("An unexpected error occurred.");
     let group_context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         GroupId::random(backend),
         1,
@@ -917,3 +924,83 @@ fn invalid_plaintext_signature(ciphersuite: Ciphersuite, backend: &impl OpenMlsC
     //     .stage_commit(&decoded_commit, &proposal_store, &[], backend)
     //     .expect("Alice: Error staging commit.");
 }
+
+#[apply(ciphersuites_and_backends)]
+fn stream_codec(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let membership_key = MembershipKey::from_secret(
+        Secret::random(ciphersuite, backend, None /* MLS version */)
+            .expect("Not enough randomness."),
+    );
+    let messages: Vec<MlsMessageOut> = (0..3)
+        .map(|_| {
+            let (content, _credential) =
+                create_content(ciphersuite, WireFormat::PublicMessage, backend);
+            let mut public_message: PublicMessage = content.into();
+            public_message
+                .set_membership_tag(backend, &membership_key)
+                .expect("Error setting membership tag.");
+            public_message.into()
+        })
+        .collect();
+
+    let encoder = MlsMessageStreamEncoder::new();
+    let mut stream = Vec::new();
+    for message in &messages {
+        encoder
+            .encode(message, &mut stream)
+            .expect("Could not encode message.");
+    }
+
+    // Feed the stream in small chunks, as a transport would deliver it.
+    let mut decoder = MlsMessageStreamDecoder::new();
+    let mut decoded = Vec::new();
+    for chunk in stream.chunks(7) {
+        decoder.push(chunk);
+        while let Some(message) = decoder.next_message().expect("Could not decode message.") {
+            decoded.push(message);
+        }
+    }
+    assert_eq!(decoder.buffered_len(), 0);
+    assert_eq!(decoded.len(), messages.len());
+    for (message, decoded) in messages.iter().zip(decoded) {
+        assert_eq!(
+            MlsMessageOut::from(decoded)
+                .to_bytes()
+                .expect("Could not serialize message."),
+            message.to_bytes().expect("Could not serialize message.")
+        );
+    }
+
+    // A frame with an undecodable message is skipped.
+    let mut decoder = MlsMessageStreamDecoder::new();
+    decoder.push(&[MLS_MESSAGE_FRAMING_VERSION, 0, 0, 0, 2, 0xff, 0xff]);
+    decoder.push(&stream);
+    assert!(matches!(
+        decoder.next_message(),
+        Err(MlsMessageStreamError::MlsMessageError(_))
+    ));
+    assert!(decoder
+        .next_message()
+        .expect("Could not decode message.")
+        .is_some());
+
+    // Unsupported framing versions and long messages are rejected.
+    let mut decoder = MlsMessageStreamDecoder::new();
+    decoder.push(&[MLS_MESSAGE_FRAMING_VERSION + 1]);
+    assert!(matches!(
+        decoder.next_message(),
+        Err(MlsMessageStreamError::UnsupportedFramingVersion)
+    ));
+    let mut decoder = MlsMessageStreamDecoder::new().with_max_message_length(16);
+    decoder.push(&stream);
+    assert!(matches!(
+        decoder.next_message(),
+        Err(MlsMessageStreamError::MessageTooLong)
+    ));
+    assert!(matches!(
+        MlsMessageStreamEncoder::new()
+            .with_max_message_length(16)
+            .to_frame(&messages[0]),
+        Err(MlsMessageStreamError::MessageTooLong)
+    ));
+}
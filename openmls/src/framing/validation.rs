@@ -39,13 +39,16 @@
 //! ```
 // TODO #106/#151: Update the above diagram
 
-use crate::{group::errors::ValidationError, tree::index::SecretTreeLeafIndex, treesync::TreeSync};
+use crate::{
+    extensions::ExternalSendersExtension, group::errors::ValidationError,
+    tree::index::SecretTreeLeafIndex, treesync::TreeSync,
+};
 use core_group::{proposals::QueuedProposal, staged_commit::StagedCommit};
 use openmls_traits::OpenMlsCryptoProvider;
 
 use crate::{
     ciphersuite::signable::Verifiable, error::LibraryError,
-    tree::sender_ratchet::SenderRatchetConfiguration,
+    tree::sender_ratchet::SenderRatchetConfigurations,
 };
 
 use super::{
@@ -86,21 +89,36 @@ impl DecryptedMessage {
         Self::from_verifiable_content(verifiable_content)
     }
 
+    /// Constructs a [DecryptedMessage] from a [PublicMessage] without
+    /// verifying the membership tag. This is used by parties that are not
+    /// members of the group and therefore don't know the membership key, e.g.
+    /// the Delivery Service.
+    pub(crate) fn from_inbound_public_message_without_membership_tag(
+        mut public_message: PublicMessage,
+        serialized_context: &[u8],
+    ) -> Result<Self, ValidationError> {
+        // Set the context for verification (happens only for the correct sender types).
+        public_message.set_context(serialized_context);
+
+        let verifiable_content = public_message.into();
+
+        Self::from_verifiable_content(verifiable_content)
+    }
+
     /// Constructs a [DecryptedMessage] from a [PrivateMessage] by attempting to decrypt it
     /// to a [VerifiableAuthenticatedContent] first.
     pub(crate) fn from_inbound_ciphertext(
         ciphertext: PrivateMessage,
         backend: &impl OpenMlsCryptoProvider,
         group: &mut CoreGroup,
-        sender_ratchet_configuration: &SenderRatchetConfiguration,
+        sender_ratchet_configurations: &SenderRatchetConfigurations,
     ) -> Result<Self, ValidationError> {
         // This will be refactored with #265.
         let ciphersuite = group.ciphersuite();
         // TODO: #819 The old leaves should not be needed any more.
         //       Revisit when the transition is further along.
-        let (message_secrets, _old_leaves) = group
-            .message_secrets_and_leaves_mut(ciphertext.epoch())
-            .map_err(|_| MessageDecryptionError::AeadError)?;
+        let (message_secrets, _old_leaves) =
+            group.message_secrets_and_leaves_mut(ciphertext.epoch())?;
         let sender_data = ciphertext.sender_data(message_secrets, backend, ciphersuite)?;
         let sender_index = SecretTreeLeafIndex::from(sender_data.leaf_index);
         let message_secrets = group
@@ -111,7 +129,7 @@ impl DecryptedMessage {
             backend,
             message_secrets,
             sender_index,
-            sender_ratchet_configuration,
+            sender_ratchet_configurations,
             sender_data,
         )?;
         Self::from_verifiable_content(verifiable_content)
@@ -148,10 +166,14 @@ impl DecryptedMessage {
     ///  - ValSem245
     ///  - Prepares ValSem246 by setting the right credential. The remainder
     ///    of ValSem246 is validated as part of ValSem010.
+    ///
+    /// The credential of an external sender is looked up in the
+    /// `external_senders` extension of the group.
     pub(crate) fn credential(
         &self,
         treesync: &TreeSync,
         old_leaves: &[Member],
+        external_senders: Option<&ExternalSendersExtension>,
     ) -> Result<Credential, ValidationError> {
         let sender = self.sender();
         match sender {
@@ -180,8 +202,9 @@ impl DecryptedMessage {
                     }
                 }
             }
-            // External senders are not supported yet #106/#151.
-            Sender::External(_) => unimplemented!(),
+            Sender::External(_) => self
+                .verifiable_content
+                .external_sender_credential(external_senders),
             Sender::NewMemberCommit | Sender::NewMemberProposal => {
                 // Fetch the credential from the message itself.
                 self.verifiable_content.new_member_credential()
@@ -238,8 +261,6 @@ pub(crate) enum UnverifiedContextMessage {
     /// Unverified message from either a `NewMemberProposal` or a `NewMemberCommit`
     NewMember(UnverifiedNewMemberMessage),
     /// Unverified message from an external sender
-    /// TODO: #106
-    #[allow(dead_code)]
     External(UnverifiedExternalMessage),
 }
 
@@ -259,8 +280,14 @@ impl UnverifiedContextMessage {
                         .ok_or_else(|| LibraryError::custom("Expected credential"))?,
                 }))
             }
-            // TODO #151/#106: We don't support external senders yet
-            Sender::External(_) => unimplemented!(),
+            Sender::External(_) => Ok(UnverifiedContextMessage::External(
+                UnverifiedExternalMessage {
+                    verifiable_content,
+                    // If the message type is `External` it always contains credentials
+                    credential: credential_option
+                        .ok_or_else(|| LibraryError::custom("Expected credential"))?,
+                },
+            )),
             Sender::NewMemberProposal | Sender::NewMemberCommit => {
                 Ok(UnverifiedContextMessage::NewMember(
                     UnverifiedNewMemberMessage {
@@ -352,10 +379,39 @@ impl UnverifiedNewMemberMessage {
     }
 }
 
-// TODO #151/#106: We don't support external senders yet
 /// Part of [UnverifiedContextMessage].
 pub(crate) struct UnverifiedExternalMessage {
-    _verifiable_content: VerifiableAuthenticatedContent,
+    verifiable_content: VerifiableAuthenticatedContent,
+    credential: Credential,
+}
+
+impl UnverifiedExternalMessage {
+    /// Verifies the signature of an [UnverifiedExternalMessage] with the
+    /// signature key of the external sender and returns a
+    /// [VerifiedExternalMessage] if the verification is successful.
+    /// This function implements the following checks:
+    /// - ValSem010
+    pub(crate) fn into_verified(
+        self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<VerifiedExternalMessage, ValidationError> {
+        // ValSem010
+        self.verifiable_content
+            .verify(
+                backend,
+                self.credential.signature_key(),
+                self.credential.signature_scheme(),
+            )
+            .map(|authenticated_content| VerifiedExternalMessage {
+                authenticated_content,
+            })
+            .map_err(|_| ValidationError::InvalidSignature)
+    }
+
+    /// Returns the credential.
+    pub(crate) fn credential(&self) -> &Credential {
+        &self.credential
+    }
 }
 
 /// Member message, where all semantic checks on the framing have been successfully performed.
@@ -372,7 +428,6 @@ impl VerifiedMemberMessage {
 }
 
 /// External message, where all semantic checks on the framing have been successfully performed.
-/// Note: External messages are not fully supported yet #106
 pub(crate) struct VerifiedExternalMessage {
     authenticated_content: AuthenticatedContent,
 }
@@ -450,6 +505,11 @@ impl ProcessedMessage {
         self.content
     }
 
+    /// Returns a mutable reference to the content of the message.
+    pub(crate) fn content_mut(&mut self) -> &mut ProcessedMessageContent {
+        &mut self.content
+    }
+
     /// Returns the credential of the message if present.
     pub fn credential(&self) -> Option<&Credential> {
         self.credential.as_ref()
@@ -505,4 +565,9 @@ impl ApplicationMessage {
     pub fn into_bytes(self) -> Vec<u8> {
         self.bytes
     }
+
+    /// Returns a reference to the inner bytes.
+    pub(crate) fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
 }
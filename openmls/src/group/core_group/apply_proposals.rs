@@ -20,6 +20,7 @@ pub(crate) struct ApplyProposalsValues {
     pub(crate) invitation_list: Vec<(LeafNodeIndex, AddProposal)>,
     pub(crate) presharedkeys: Vec<PreSharedKeyId>,
     pub(crate) external_init_secret_option: Option<InitSecret>,
+    pub(crate) extensions: Option<Extensions>,
 }
 
 impl ApplyProposalsValues {
@@ -159,6 +160,20 @@ impl CoreGroup {
             })
             .collect();
 
+        // Process the GroupContextExtensions proposal. The proposal queue
+        // contains at most one.
+        let extensions = proposal_queue
+            .filtered_by_type(ProposalType::GroupContextExtensions)
+            .find_map(|queued_proposal| {
+                if let Proposal::GroupContextExtensions(group_context_extension_proposal) =
+                    queued_proposal.proposal()
+                {
+                    Some(group_context_extension_proposal.extensions().clone())
+                } else {
+                    None
+                }
+            });
+
         let proposals_require_path = proposal_queue
             .queued_proposals()
             .any(|p| p.proposal().is_path_required());
@@ -178,6 +193,7 @@ impl CoreGroup {
             invitation_list,
             presharedkeys,
             external_init_secret_option,
+            extensions,
         })
     }
 }
@@ -11,7 +11,6 @@ use crate::{
         },
         treekem::{PlaintextSecret, UpdatePath},
     },
-    versions::ProtocolVersion,
 };
 
 use super::{
@@ -67,7 +66,7 @@ impl CoreGroup {
             ciphersuite,
             backend,
             sender.clone(),
-            params.proposal_store(),
+            params.proposals(),
             params.inline_proposals(),
             own_leaf_index,
         )
@@ -110,6 +109,10 @@ impl CoreGroup {
         // ValSem107
         // ValSem108
         self.validate_remove_proposals(&proposal_queue)?;
+        self.validate_max_members(
+            &proposal_queue,
+            params.commit_type() == CommitType::External,
+        )?;
         // Validate update proposals for member commits
         if let Sender::Member(sender_index) = &sender {
             // ValSem109
@@ -180,10 +183,18 @@ impl CoreGroup {
                     let own_diff_leaf = diff
                         .own_leaf_mut()
                         .map_err(|_| LibraryError::custom("Unable to get own leaf from diff"))?;
+                    // Replace the capabilities and extensions before the leaf
+                    // is re-signed, if requested.
+                    if let Some((capabilities, extensions)) = params.own_leaf_capabilities() {
+                        own_diff_leaf.set_capabilities_and_extensions(
+                            capabilities.clone(),
+                            extensions.clone(),
+                        );
+                    }
                     let encryption_keypair = own_diff_leaf.rekey(
                         self.group_id(),
                         self.ciphersuite,
-                        ProtocolVersion::default(), // XXX: openmls/openmls#1065
+                        self.version(),
                         params.credential_bundle(),
                         backend,
                     )?;
@@ -269,12 +280,16 @@ impl CoreGroup {
 
         // Calculate group context
         let provisional_group_context = GroupContext::new(
+            self.version(),
             ciphersuite,
             self.group_context.group_id().clone(),
             provisional_epoch,
             tree_hash.clone(),
             confirmed_transcript_hash.clone(),
-            self.group_context.extensions().clone(),
+            apply_proposals_values
+                .extensions
+                .clone()
+                .unwrap_or_else(|| self.group_context.extensions().clone()),
         );
 
         let joiner_secret = JoinerSecret::new(
@@ -346,12 +361,13 @@ impl CoreGroup {
             // Create to-be-signed group info.
             let group_info_tbs = {
                 let group_context = GroupContext::new(
+                    provisional_group_context.protocol_version(),
                     ciphersuite,
                     provisional_group_context.group_id().clone(),
                     provisional_group_context.epoch(),
                     tree_hash,
                     confirmed_transcript_hash.clone(),
-                    self.group_context_extensions().clone(),
+                    provisional_group_context.extensions().clone(),
                 );
 
                 GroupInfoTBS::new(
@@ -393,7 +409,7 @@ impl CoreGroup {
                 .collect();
             // Create welcome message
             let welcome = Welcome::new(
-                ProtocolVersion::Mls10,
+                self.version(),
                 self.ciphersuite,
                 secrets,
                 encrypted_group_info,
@@ -1,6 +1,9 @@
 //! Builder for [CreateCommitParams] that is used in [CoreGroup::create_commit()]
 
-use super::{proposals::ProposalStore, *};
+use super::{
+    proposals::{ProposalStore, QueuedProposal},
+    *,
+};
 
 /// Can be used to denote the type of a commit.
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
@@ -13,9 +16,11 @@ pub(crate) struct CreateCommitParams<'a> {
     framing_parameters: FramingParameters<'a>, // Mandatory
     credential_bundle: &'a CredentialBundle,   // Mandatory
     proposal_store: &'a ProposalStore,         // Mandatory
+    proposal_filter: Option<&'a dyn Fn(&QueuedProposal) -> bool>, // Optional
     inline_proposals: Vec<Proposal>,           // Optional
     force_self_update: bool,                   // Optional
     commit_type: CommitType,                   // Optional (default is `Member`)
+    own_leaf_capabilities: Option<(Capabilities, Extensions)>, // Optional
 }
 
 pub(crate) struct TempBuilderCCPM0 {}
@@ -64,15 +69,26 @@ impl<'a> TempBuilderCCPM2<'a> {
                 framing_parameters: self.framing_parameters,
                 credential_bundle: self.credential_bundle,
                 proposal_store,
+                proposal_filter: None,
                 inline_proposals: vec![],
                 force_self_update: true,
                 commit_type: CommitType::Member,
+                own_leaf_capabilities: None,
             },
         }
     }
 }
 
 impl<'a> CreateCommitParamsBuilder<'a> {
+    /// Only commits the proposals of the proposal store for which
+    /// `proposal_filter` returns `true`.
+    pub(crate) fn proposal_filter(
+        mut self,
+        proposal_filter: &'a dyn Fn(&QueuedProposal) -> bool,
+    ) -> Self {
+        self.ccp.proposal_filter = Some(proposal_filter);
+        self
+    }
     pub(crate) fn inline_proposals(mut self, inline_proposals: Vec<Proposal>) -> Self {
         self.ccp.inline_proposals = inline_proposals;
         self
@@ -86,6 +102,14 @@ impl<'a> CreateCommitParamsBuilder<'a> {
         self.ccp.commit_type = commit_type;
         self
     }
+    pub(crate) fn own_leaf_capabilities(
+        mut self,
+        capabilities: Capabilities,
+        extensions: Extensions,
+    ) -> Self {
+        self.ccp.own_leaf_capabilities = Some((capabilities, extensions));
+        self
+    }
     pub(crate) fn build(self) -> CreateCommitParams<'a> {
         self.ccp
     }
@@ -104,6 +128,15 @@ impl<'a> CreateCommitParams<'a> {
     pub(crate) fn proposal_store(&self) -> &ProposalStore {
         self.proposal_store
     }
+    /// Returns the proposals of the proposal store that are committed.
+    pub(crate) fn proposals(&self) -> impl Iterator<Item = &'a QueuedProposal> + 'a {
+        let proposal_filter = self.proposal_filter;
+        self.proposal_store
+            .proposals()
+            .filter(move |queued_proposal| {
+                proposal_filter.map_or(true, |proposal_filter| proposal_filter(queued_proposal))
+            })
+    }
     pub(crate) fn inline_proposals(&self) -> &[Proposal] {
         &self.inline_proposals
     }
@@ -113,4 +146,7 @@ impl<'a> CreateCommitParams<'a> {
     pub(crate) fn commit_type(&self) -> CommitType {
         self.commit_type
     }
+    pub(crate) fn own_leaf_capabilities(&self) -> Option<&(Capabilities, Extensions)> {
+        self.own_leaf_capabilities.as_ref()
+    }
 }
@@ -7,13 +7,13 @@
 
 // Private
 mod apply_proposals;
-mod new_from_welcome;
 mod validation;
 
 // Crate
 pub(crate) mod create_commit;
 pub(crate) mod create_commit_params;
 pub(crate) mod new_from_external_init;
+pub(crate) mod new_from_welcome;
 pub(crate) mod past_secrets;
 pub(crate) mod process;
 pub(crate) mod proposals;
@@ -49,9 +49,13 @@ use crate::{
     messages::VerifiableGroupInfo,
     messages::{proposals::*, *},
     schedule::{message_secrets::*, psk::*, *},
-    tree::{secret_tree::SecretTreeError, sender_ratchet::SenderRatchetConfiguration},
+    tree::{
+        index::SecretTreeLeafIndex,
+        secret_tree::SecretTreeError,
+        sender_ratchet::{SenderRatchetConfiguration, SenderRatchetConfigurations},
+    },
     treesync::{
-        node::leaf_node::{Capabilities, Lifetime},
+        node::leaf_node::{Capabilities, LeafNodeSource, Lifetime},
         *,
     },
     versions::ProtocolVersion,
@@ -70,8 +74,8 @@ use tls_codec::Serialize as TlsSerializeTrait;
 
 use super::{
     errors::{
-        CoreGroupBuildError, CreateAddProposalError, ExporterError, ProposalValidationError,
-        ValidationError,
+        CoreGroupBuildError, CreateAddProposalError, ExporterError, PinMessageSecretsError,
+        ProposalValidationError, ValidationError,
     },
     group_context::*,
 };
@@ -90,11 +94,16 @@ pub struct Member {
     /// The member's leaf index in the ratchet tree.
     pub index: LeafNodeIndex,
     /// The member's identity from the credential.
-    pub identity: Vec<u8>,
+    pub identity: Identity,
     /// The member's public HPHKE encryption key.
     pub encryption_key: Vec<u8>,
     /// The member's public signature key.
     pub signature_key: Vec<u8>,
+    /// The member's application id from the [`ApplicationIdExtension`] of
+    /// its leaf node, if present.
+    ///
+    /// [`ApplicationIdExtension`]: crate::extensions::ApplicationIdExtension
+    pub application_id: Option<Vec<u8>>,
 }
 
 impl Member {
@@ -103,13 +112,54 @@ impl Member {
         index: LeafNodeIndex,
         encryption_key: Vec<u8>,
         signature_key: Vec<u8>,
-        identity: Vec<u8>,
+        identity: Identity,
+        application_id: Option<Vec<u8>>,
     ) -> Self {
         Self {
             index,
             encryption_key,
             signature_key,
             identity,
+            application_id,
+        }
+    }
+}
+
+/// Details of a member of the group, taken from its leaf node, in addition to
+/// the [`Member`] itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberDetails {
+    /// The member.
+    pub member: Member,
+    /// The type of the member's credential.
+    pub credential_type: CredentialType,
+    /// The [`Capabilities`] of the member's leaf node.
+    pub capabilities: Capabilities,
+    /// The extensions of the member's leaf node.
+    pub extensions: Extensions,
+    /// The [`Lifetime`] of the member's leaf node, if the leaf node is part of
+    /// a key package.
+    pub lifetime: Option<Lifetime>,
+    /// The [`LeafNodeSource`] of the member's leaf node.
+    pub leaf_node_source: LeafNodeSource,
+}
+
+impl MemberDetails {
+    /// Create the details of the member at `index` from its `leaf_node`.
+    pub(crate) fn from_leaf_node(index: LeafNodeIndex, leaf_node: &LeafNode) -> Self {
+        Self {
+            member: Member::new(
+                index,
+                leaf_node.encryption_key().as_slice().to_vec(),
+                leaf_node.signature_key().as_slice().to_vec(),
+                leaf_node.credential().identity().clone(),
+                leaf_node.application_id().map(|id| id.to_vec()),
+            ),
+            credential_type: leaf_node.credential().credential_type(),
+            capabilities: leaf_node.capabilities().clone(),
+            extensions: leaf_node.extensions().clone(),
+            lifetime: leaf_node.life_time().copied(),
+            leaf_node_source: leaf_node.leaf_node_source().clone(),
         }
     }
 }
@@ -135,6 +185,11 @@ pub(crate) struct CoreGroup {
     /// able to decrypt application messages from previous epochs, the size of
     /// the store must be increased through [`max_past_epochs()`].
     message_secrets_store: MessageSecretsStore,
+    // Set to true if new members may have the identity of another member.
+    // This mirrors the `DuplicateIdentityPolicy` of the `MlsGroupConfig` and
+    // is set again when the group is loaded.
+    #[serde(skip)]
+    allow_duplicate_identities: bool,
 }
 
 /// Builder for [`CoreGroup`].
@@ -146,6 +201,7 @@ pub(crate) struct CoreGroupBuilder {
     psk_ids: Vec<PreSharedKeyId>,
     version: Option<ProtocolVersion>,
     required_capabilities: Option<RequiredCapabilitiesExtension>,
+    max_members: Option<MaxMembersExtension>,
     max_past_epochs: usize,
     lifetime: Option<Lifetime>,
 }
@@ -159,6 +215,7 @@ impl CoreGroupBuilder {
             psk_ids: vec![],
             version: None,
             required_capabilities: None,
+            max_members: None,
             max_past_epochs: 0,
             own_leaf_extensions: Extensions::empty(),
             lifetime: None,
@@ -184,6 +241,11 @@ impl CoreGroupBuilder {
         self.required_capabilities = Some(required_capabilities);
         self
     }
+    /// Set the [`MaxMembersExtension`] of the [`CoreGroup`].
+    pub(crate) fn with_max_members(mut self, max_members: Option<MaxMembersExtension>) -> Self {
+        self.max_members = max_members;
+        self
+    }
     /// Set the number of past epochs the group should keep secrets.
     pub fn with_max_past_epoch_secrets(mut self, max_past_epochs: usize) -> Self {
         self.max_past_epochs = max_past_epochs;
@@ -213,6 +275,9 @@ impl CoreGroupBuilder {
             .as_ref()
             .map(|re| re.extension_types());
         let version = self.version.unwrap_or_default();
+        if !version.is_supported() {
+            return Err(CoreGroupBuildError::UnsupportedMlsVersion);
+        }
 
         debug!("Created group {:x?}", self.group_id);
         trace!(" >>> with {:?}, {:?}", ciphersuite, config);
@@ -242,14 +307,18 @@ impl CoreGroupBuilder {
             }
             _ => LibraryError::custom("Unexpected ExtensionError").into(),
         })?;
-        let required_capabilities =
+        let mut group_context_extensions =
             Extensions::single(Extension::RequiredCapabilities(required_capabilities));
+        if let Some(max_members) = self.max_members {
+            group_context_extensions.add_or_replace(Extension::MaxMembers(max_members));
+        }
 
         let group_context = GroupContext::create_initial_group_context(
+            version,
             ciphersuite,
             self.group_id,
             tree.tree_hash().to_vec(),
-            required_capabilities,
+            group_context_extensions,
         );
         // Derive an initial joiner secret based on the commit secret.
         // Derive an epoch secret from the joiner secret.
@@ -294,6 +363,7 @@ impl CoreGroupBuilder {
             use_ratchet_tree_extension: config.add_ratchet_tree_extension,
             mls_version: version,
             message_secrets_store,
+            allow_duplicate_identities: false,
         };
 
         // Store the private key of the own leaf in the key store as an epoch keypair.
@@ -528,7 +598,7 @@ impl CoreGroup {
             backend,
             message_secrets,
             sender_index,
-            sender_ratchet_configuration,
+            &sender_ratchet_configuration.clone().into(),
             sender_data,
         )
     }
@@ -540,7 +610,7 @@ impl CoreGroup {
         label: &str,
         context: &[u8],
         key_length: usize,
-    ) -> Result<Vec<u8>, ExporterError> {
+    ) -> Result<ExportedSecret, ExporterError> {
         if key_length > u16::MAX.into() {
             log::error!("Got a key that is larger than u16::MAX");
             return Err(ExporterError::KeyLengthTooLong);
@@ -558,30 +628,38 @@ impl CoreGroup {
         credential_bundle: &CredentialBundle,
         with_ratchet_tree: bool,
     ) -> Result<GroupInfo, LibraryError> {
-        let extensions = {
-            let ratchet_tree_extension = || {
-                Extension::RatchetTree(RatchetTreeExtension::new(self.treesync().export_nodes()))
-            };
-
-            let external_pub_extension = || {
-                let external_pub = self
-                    .group_epoch_secrets()
-                    .external_secret()
-                    .derive_external_keypair(backend.crypto(), self.ciphersuite)
-                    .public;
-                Extension::ExternalPub(ExternalPubExtension::new(HpkePublicKey::from(external_pub)))
-            };
+        self.export_group_info_with_external_pub(
+            backend,
+            credential_bundle,
+            with_ratchet_tree,
+            true,
+        )
+    }
 
+    /// Exports a group info that only contains the external_pub extension
+    /// if `with_external_pub` is set.
+    pub(crate) fn export_group_info_with_external_pub(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        credential_bundle: &CredentialBundle,
+        with_ratchet_tree: bool,
+        with_external_pub: bool,
+    ) -> Result<GroupInfo, LibraryError> {
+        let extensions = {
+            let mut extensions = Vec::new();
             if with_ratchet_tree {
-                Extensions::from_vec(vec![ratchet_tree_extension(), external_pub_extension()])
-                    .map_err(|_| {
-                        LibraryError::custom(
-                            "There should not have been duplicate extensions here.",
-                        )
-                    })?
-            } else {
-                Extensions::single(external_pub_extension())
+                extensions.push(Extension::RatchetTree(RatchetTreeExtension::new(
+                    self.treesync().export_nodes(),
+                )));
             }
+            if with_external_pub {
+                extensions.push(Extension::ExternalPub(ExternalPubExtension::new(
+                    self.external_pub(backend),
+                )));
+            }
+            Extensions::from_vec(extensions).map_err(|_| {
+                LibraryError::custom("There should not have been duplicate extensions here.")
+            })?
         };
 
         // Create to-be-signed group info.
@@ -601,6 +679,16 @@ impl CoreGroup {
             .map_err(|_| LibraryError::custom("Signing failed"))
     }
 
+    /// Returns the public key of the external key pair of the current epoch.
+    pub(crate) fn external_pub(&self, backend: &impl OpenMlsCryptoProvider) -> HpkePublicKey {
+        HpkePublicKey::from(
+            self.group_epoch_secrets()
+                .external_secret()
+                .derive_external_keypair(backend.crypto(), self.ciphersuite)
+                .public,
+        )
+    }
+
     /// Returns the epoch authenticator
     pub(crate) fn epoch_authenticator(&self) -> &EpochAuthenticator {
         self.group_epoch_secrets().epoch_authenticator()
@@ -639,6 +727,11 @@ impl CoreGroup {
         self.mls_version
     }
 
+    /// Get the interim transcript hash of the current epoch.
+    pub(crate) fn interim_transcript_hash(&self) -> &[u8] {
+        &self.interim_transcript_hash
+    }
+
     /// Get the group context
     pub(crate) fn context(&self) -> &GroupContext {
         &self.group_context
@@ -675,7 +768,7 @@ impl CoreGroup {
     }
 
     /// Get the identity of the client's [`Credential`] owning this group.
-    pub(crate) fn own_identity(&self) -> Option<&[u8]> {
+    pub(crate) fn own_identity(&self) -> Option<&Identity> {
         self.treesync()
             .own_leaf_node()
             .map(|node| node.credential().identity())
@@ -698,6 +791,66 @@ impl CoreGroup {
         self.message_secrets_store.resize(max_past_epochs);
     }
 
+    /// Sets whether Add proposals may introduce members with the identity of
+    /// an existing member or of another added member (ValSem100 and
+    /// ValSem103). Signature keys must always be unique.
+    pub(crate) fn set_allow_duplicate_identities(&mut self, allow_duplicate_identities: bool) {
+        self.allow_duplicate_identities = allow_duplicate_identities;
+    }
+
+    /// Pin the message secrets of an epoch or of a single sender in an epoch,
+    /// such that they are retained when the epoch is evicted from the
+    /// [`MessageSecretsStore`]. The sender ratchets of a pinned sender are
+    /// initialized right away.
+    pub(crate) fn pin_message_secrets(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        pin: MessageSecretsPin,
+    ) -> Result<(), PinMessageSecretsError> {
+        let ciphersuite = self.ciphersuite();
+        let current_epoch = self.context().epoch();
+        if pin.epoch() > current_epoch {
+            return Err(PinMessageSecretsError::UnknownEpoch);
+        }
+        if let Some(sender) = pin.sender() {
+            let is_member = if pin.epoch() < current_epoch {
+                self.message_secrets_store
+                    .epoch_has_leaf(pin.epoch(), sender)
+            } else {
+                self.treesync().leaf(sender).is_some()
+            };
+            let message_secrets = self
+                .message_secrets_mut(pin.epoch())
+                .map_err(|_| PinMessageSecretsError::UnknownEpoch)?;
+            if !is_member {
+                return Err(PinMessageSecretsError::UnknownSender);
+            }
+            message_secrets
+                .secret_tree_mut()
+                .initialize_sender_ratchets(ciphersuite, backend, SecretTreeLeafIndex::from(sender))
+                .map_err(|e| match e {
+                    SecretTreeError::SecretReuseError => PinMessageSecretsError::SecretsDeleted,
+                    _ => LibraryError::custom("Could not initialize the sender ratchets").into(),
+                })?;
+        } else {
+            self.message_secrets_for_epoch(pin.epoch())
+                .map_err(|_| PinMessageSecretsError::UnknownEpoch)?;
+        }
+        self.message_secrets_store.pin(pin);
+        Ok(())
+    }
+
+    /// Remove a pin set with [`Self::pin_message_secrets()`]. Returns `false`
+    /// if the pin didn't exist.
+    pub(crate) fn unpin_message_secrets(&mut self, pin: MessageSecretsPin) -> bool {
+        self.message_secrets_store.unpin(pin)
+    }
+
+    /// Returns the pins set with [`Self::pin_message_secrets()`].
+    pub(crate) fn message_secrets_pins(&self) -> &[MessageSecretsPin] {
+        self.message_secrets_store.pins()
+    }
+
     /// Get the message secrets. Either from the secrets store or from the group.
     pub(crate) fn message_secrets_mut(
         &mut self,
@@ -712,6 +865,16 @@ impl CoreGroup {
         }
     }
 
+    /// Get the message secrets of the current epoch and of all stored past
+    /// epochs together with their epoch, from the current to the oldest epoch.
+    pub(crate) fn all_message_secrets_mut(&mut self) -> Vec<(GroupEpoch, &mut MessageSecrets)> {
+        let epoch = self.context().epoch();
+        let (current, past) = self.message_secrets_store.current_and_past_secrets_mut();
+        std::iter::once((epoch, current))
+            .chain(past.rev())
+            .collect()
+    }
+
     /// Get the message secrets. Either from the secrets store or from the group.
     pub(crate) fn message_secrets_for_epoch(
         &self,
@@ -735,11 +898,13 @@ impl CoreGroup {
         &mut self,
         epoch: GroupEpoch,
     ) -> Result<(&mut MessageSecrets, &[Member]), MessageDecryptionError> {
-        if epoch < self.context().epoch() {
+        let current_epoch = self.context().epoch();
+        if epoch < current_epoch {
             self.message_secrets_store
                 .secrets_and_leaves_for_epoch_mut(epoch)
-                .ok_or({
-                    MessageDecryptionError::SecretTreeError(SecretTreeError::TooDistantInThePast)
+                .ok_or(MessageDecryptionError::EpochTooOld {
+                    current_epoch,
+                    received_epoch: epoch,
                 })
         } else {
             // No need for leaves here. The tree of the current epoch is
@@ -758,7 +923,10 @@ impl CoreGroup {
         keypair_references: &[EncryptionKeyPair],
     ) -> Result<(), KeyStore::Error> {
         // Retrieving our identity should not fail.
-        let own_identity = self.own_identity().unwrap_or_default();
+        let own_identity = self
+            .own_identity()
+            .map(Identity::as_slice)
+            .unwrap_or_default();
         debug_assert_ne!(own_identity, &[0u8; 0]);
         backend.key_store().store_epoch_keys(
             own_identity,
@@ -777,7 +945,10 @@ impl CoreGroup {
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
     ) -> Vec<EncryptionKeyPair> {
         // Retrieving our identity should not fail.
-        let own_identity = self.own_identity().unwrap_or_default();
+        let own_identity = self
+            .own_identity()
+            .map(Identity::as_slice)
+            .unwrap_or_default();
         debug_assert_ne!(own_identity, &[0u8; 0]);
         backend.key_store().read_epoch_keys(
             // Retrieving our identity should not fail.
@@ -796,7 +967,10 @@ impl CoreGroup {
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
     ) -> Result<(), KeyStore::Error> {
         // Retrieving our identity should not fail.
-        let own_identity = self.own_identity().unwrap_or_default();
+        let own_identity = self
+            .own_identity()
+            .map(Identity::as_slice)
+            .unwrap_or_default();
         debug_assert_ne!(own_identity, &[0u8; 0]);
         backend.key_store().delete_epoch_keys(
             // Retrieving our identity should not fail.
@@ -806,6 +980,37 @@ impl CoreGroup {
         )
     }
 
+    /// Delete the [`EncryptionKeyPair`]s of the current [`GroupEpoch`] from
+    /// the `backend`'s key store. Returns the number of deleted keypairs.
+    ///
+    /// Returns an error if access to the key store fails.
+    pub(super) fn delete_epoch_keypairs<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<usize, KeyStore::Error> {
+        let deleted = self.read_epoch_keypairs(backend).len();
+        // Retrieving our identity should not fail.
+        let own_identity = self
+            .own_identity()
+            .map(Identity::as_slice)
+            .unwrap_or_default();
+        debug_assert_ne!(own_identity, &[0u8; 0]);
+        backend.key_store().delete_epoch_keys(
+            own_identity,
+            self.group_id().as_slice(),
+            self.context().epoch().as_u64(),
+        )?;
+        Ok(deleted)
+    }
+
+    /// Overwrite the epoch secrets and the message secrets of the current and
+    /// all past epochs with zeros. Returns the number of epochs whose message
+    /// secrets were overwritten.
+    pub(super) fn zeroize_secrets(&mut self) -> usize {
+        self.group_epoch_secrets.zeroize();
+        self.message_secrets_store.zeroize()
+    }
+
     #[cfg(any(feature = "test-utils", test))]
     pub(crate) fn message_secrets_test_mut(&mut self) -> &mut MessageSecrets {
         self.message_secrets_store.message_secrets_mut()
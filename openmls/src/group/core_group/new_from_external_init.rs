@@ -1,9 +1,15 @@
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
-    ciphersuite::signable::Verifiable,
-    group::errors::ExternalCommitError,
+    ciphersuite::{
+        signable::Verifiable,
+        signature::{OpenMlsSignaturePublicKey, SignaturePublicKey},
+    },
+    group::errors::{CreateCommitError, ExternalCommitError},
     messages::proposals::{ExternalInitProposal, Proposal},
-    treesync::{errors::TreeSyncFromNodesError, node::Node},
+    treesync::{
+        errors::{PublicTreeError, TreeSyncFromNodesError},
+        node::Node,
+    },
 };
 
 use super::{
@@ -21,6 +27,13 @@ impl CoreGroup {
     /// `ExternalInit` proposal and commits it along with the given proposals by
     /// reference and by value.
     ///
+    /// The inline proposals in `params` are appended to the commit after the
+    /// `ExternalInit` proposal. Only `PreSharedKey` proposals are allowed
+    /// there (see ValSem242).
+    ///
+    /// The signature of the GroupInfo has to be valid for the leaf of its
+    /// signer or for one of the `external_group_info_signers`.
+    ///
     /// Returns the new `CoreGroup` object, as well as the `PublicMessage`
     /// containing the commit.
     pub(crate) fn join_by_external_commit(
@@ -28,8 +41,13 @@ impl CoreGroup {
         params: CreateCommitParams,
         tree_option: Option<&[Option<Node>]>,
         verifiable_group_info: VerifiableGroupInfo,
+        external_group_info_signers: &[OpenMlsSignaturePublicKey],
     ) -> Result<ExternalCommitResult, ExternalCommitError> {
         let ciphersuite = verifiable_group_info.ciphersuite();
+        #[cfg(feature = "fips")]
+        if !crate::ciphersuite::fips::is_approved_ciphersuite(ciphersuite) {
+            return Err(ExternalCommitError::NotFipsApproved);
+        }
 
         // Build the ratchet tree
 
@@ -37,7 +55,10 @@ impl CoreGroup {
         // If we got a ratchet tree extension in the welcome, we enable it for
         // this group. Note that this is not strictly necessary. But there's
         // currently no other mechanism to enable the extension.
-        let extension_tree_option = try_nodes_from_extensions(verifiable_group_info.extensions());
+        let extension_tree_option = try_nodes_from_extensions(verifiable_group_info.extensions())
+            .map_err(|_| {
+            ExternalCommitError::PublicTreeError(PublicTreeError::MalformedTree)
+        })?;
         let (nodes, enable_ratchet_tree_extension) = match extension_tree_option {
             Some(nodes) => (nodes, true),
             None => match tree_option {
@@ -64,12 +85,30 @@ impl CoreGroup {
                 .ok_or(ExternalCommitError::UnknownSender)?
                 .credential();
 
+            // A GroupInfo that was re-signed by an external signer, e.g. the
+            // DS, is verified with the key of that signer instead.
+            let (signature_key, signature_scheme) = external_group_info_signers
+                .iter()
+                .map(|signer| {
+                    (
+                        SignaturePublicKey::from(signer.clone()),
+                        signer.signature_scheme(),
+                    )
+                })
+                .find(|(signature_key, signature_scheme)| {
+                    verifiable_group_info
+                        .verify_no_out(backend, signature_key, *signature_scheme)
+                        .is_ok()
+                })
+                .unwrap_or_else(|| {
+                    (
+                        group_info_signer_leaf.signature_key().clone(),
+                        ciphersuite.signature_algorithm(),
+                    )
+                });
+
             verifiable_group_info
-                .verify(
-                    backend,
-                    group_info_signer_leaf.signature_key(),
-                    ciphersuite.signature_algorithm(),
-                )
+                .verify(backend, &signature_key, signature_scheme)
                 .map_err(|_| ExternalCommitError::InvalidGroupInfoSignature)?
         };
 
@@ -77,7 +116,7 @@ impl CoreGroup {
             return Err(ExternalCommitError::TreeHashMismatch);
         }
 
-        if group_info.group_context().protocol_version() != ProtocolVersion::Mls10 {
+        if !group_info.group_context().protocol_version().is_supported() {
             return Err(ExternalCommitError::UnsupportedMlsVersion);
         }
 
@@ -121,8 +160,7 @@ impl CoreGroup {
                     backend,
                     &InterimTranscriptHashInput::from(group_info.confirmation_tag()),
                     group_info.group_context().confirmed_transcript_hash(),
-                )
-                .unwrap()
+                )?
             }
         };
 
@@ -136,6 +174,7 @@ impl CoreGroup {
             mls_version: group_info.group_context().protocol_version(),
             group_epoch_secrets,
             message_secrets_store,
+            allow_duplicate_identities: false,
         };
 
         let external_init_proposal = Proposal::ExternalInit(ExternalInitProposal::from(kem_output));
@@ -148,13 +187,21 @@ impl CoreGroup {
             index, identity, ..
         } in group.treesync().full_leave_members()
         {
-            if identity == params.credential_bundle().credential().identity() {
+            if &identity == params.credential_bundle().credential().identity() {
                 let remove_proposal = Proposal::Remove(RemoveProposal { removed: index });
                 inline_proposals.push(remove_proposal);
                 break;
             };
         }
 
+        // Add the PSK proposals the joiner wants to prove possession of.
+        for proposal in params.inline_proposals() {
+            if !matches!(proposal, Proposal::PreSharedKey(_)) {
+                return Err(ExternalCommitError::InvalidInlineProposal);
+            }
+            inline_proposals.push(proposal.clone());
+        }
+
         let params = CreateCommitParams::builder()
             .framing_parameters(*params.framing_parameters())
             .credential_bundle(params.credential_bundle())
@@ -164,16 +211,16 @@ impl CoreGroup {
             .build();
 
         // Immediately create the commit to add ourselves to the group.
-        let create_commit_result = group.create_commit(params, backend);
-        debug_assert!(
-            create_commit_result.is_ok(),
-            "Error creating commit {:?}",
-            create_commit_result
-        );
+        let create_commit_result = group.create_commit(params, backend).map_err(|e| match e {
+            CreateCommitError::ProposalValidationError(ProposalValidationError::TooManyMembers) => {
+                ExternalCommitError::TooManyMembers
+            }
+            e => {
+                log::error!("Error creating commit {:?}", e);
+                ExternalCommitError::CommitError
+            }
+        })?;
 
-        Ok((
-            group,
-            create_commit_result.map_err(|_| ExternalCommitError::CommitError)?,
-        ))
+        Ok((group, create_commit_result))
     }
 }
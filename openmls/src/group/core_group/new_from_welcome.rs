@@ -2,6 +2,8 @@ use log::debug;
 use openmls_traits::{crypto::OpenMlsCrypto, key_store::OpenMlsKeyStore};
 use tls_codec::Deserialize;
 
+#[cfg(feature = "partial-tree")]
+use crate::treesync::PartialTree;
 use crate::{
     ciphersuite::{hash_ref::HashReference, signable::Verifiable},
     group::{core_group::*, errors::WelcomeError},
@@ -12,6 +14,28 @@ use crate::{
     },
 };
 
+/// A Welcome message of which the group secrets and the GroupInfo were
+/// decrypted, but neither the ratchet tree nor the GroupInfo were validated.
+pub(crate) struct DecryptedWelcome {
+    mls_version: ProtocolVersion,
+    ciphersuite: Ciphersuite,
+    key_package_bundle: KeyPackageBundle,
+    leaf_keypair: EncryptionKeyPair,
+    path_secret: Option<PathSecret>,
+    psks: Vec<PreSharedKeyId>,
+    key_schedule: KeySchedule,
+    verifiable_group_info: VerifiableGroupInfo,
+}
+
+/// A Welcome message of which the ratchet tree and the GroupInfo were
+/// validated. The group is set up, but its key pairs are not yet in the key
+/// store.
+pub(crate) struct ValidatedWelcome {
+    pub(crate) group: CoreGroup,
+    pub(crate) group_keypairs: Vec<EncryptionKeyPair>,
+    pub(crate) signer: LeafNodeIndex,
+}
+
 impl CoreGroup {
     // Join a group from a welcome message
     pub fn new_from_welcome<KeyStore: OpenMlsKeyStore>(
@@ -22,7 +46,26 @@ impl CoreGroup {
     ) -> Result<Self, WelcomeError<KeyStore::Error>> {
         log::debug!("CoreGroup::new_from_welcome_internal");
 
-        // Read the encryption key pair from the key store and delete it there.
+        let validated_welcome = Self::decrypt_welcome(welcome, key_package_bundle, backend)?
+            .validate(nodes_option, backend)?;
+        validated_welcome
+            .group
+            .store_epoch_keypairs(backend, validated_welcome.group_keypairs.as_slice())
+            .map_err(WelcomeError::KeyStoreError)?;
+
+        Ok(validated_welcome.group)
+    }
+
+    /// Decrypts the group secrets and the GroupInfo of the `welcome` with the
+    /// `key_package_bundle` the client was added with.
+    pub(crate) fn decrypt_welcome<KeyStore: OpenMlsKeyStore>(
+        welcome: Welcome,
+        key_package_bundle: KeyPackageBundle,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<DecryptedWelcome, WelcomeError<KeyStore::Error>> {
+        // Read the encryption key pair from the key store. It is deleted
+        // together with the key package by the caller, unless the key package
+        // is kept, e.g. as last resort key package.
         // TODO #1207: Key store access happens as early as possible so it can
         // be pulled up later more easily.
         let leaf_keypair = EncryptionKeyPair::read_from_key_store(
@@ -30,12 +73,13 @@ impl CoreGroup {
             key_package_bundle.key_package.leaf_node().encryption_key(),
         )
         .ok_or(WelcomeError::NoMatchingEncryptionKey)?;
-        leaf_keypair
-            .delete_from_key_store(backend)
-            .map_err(|_| WelcomeError::NoMatchingEncryptionKey)?;
 
+        // The Welcome has to use a supported protocol version, which is the
+        // protocol version of the key package of the new member.
         let mls_version = *welcome.version();
-        if mls_version != ProtocolVersion::Mls10 {
+        if !mls_version.is_supported()
+            || mls_version != key_package_bundle.key_package().protocol_version()
+        {
             return Err(WelcomeError::UnsupportedMlsVersion);
         }
 
@@ -52,6 +96,10 @@ impl CoreGroup {
         } else {
             return Err(WelcomeError::JoinerSecretNotFound);
         };
+        #[cfg(feature = "fips")]
+        if !crate::ciphersuite::fips::is_approved_ciphersuite(ciphersuite) {
+            return Err(WelcomeError::NotFipsApproved);
+        }
         if ciphersuite != key_package_bundle.key_package().ciphersuite() {
             let e = WelcomeError::CiphersuiteMismatch;
             debug!("new_from_welcome {:?}", e);
@@ -118,7 +166,58 @@ impl CoreGroup {
                 .map_err(|_| WelcomeError::UnsupportedCapability)?
         }
 
-        let path_secret_option = group_secrets.path_secret;
+        Ok(DecryptedWelcome {
+            mls_version,
+            ciphersuite,
+            key_package_bundle,
+            leaf_keypair,
+            path_secret: group_secrets.path_secret,
+            psks: group_secrets.psks,
+            key_schedule,
+            verifiable_group_info,
+        })
+    }
+
+    // Helper functions
+
+    pub(crate) fn find_key_package_from_welcome_secrets(
+        hash_ref: HashReference,
+        welcome_secrets: &[EncryptedGroupSecrets],
+    ) -> Option<EncryptedGroupSecrets> {
+        for egs in welcome_secrets {
+            if hash_ref == egs.new_member() {
+                return Some(egs.clone());
+            }
+        }
+        None
+    }
+}
+
+impl DecryptedWelcome {
+    /// Returns the key package the client was added with.
+    pub(crate) fn key_package(&self) -> &KeyPackage {
+        self.key_package_bundle.key_package()
+    }
+
+    /// Returns the GroupInfo of the Welcome. Its signature is not verified yet.
+    pub(crate) fn unverified_group_info(&self) -> &VerifiableGroupInfo {
+        &self.verifiable_group_info
+    }
+
+    /// Returns the PSKs that were injected into the key schedule.
+    pub(crate) fn psks(&self) -> &[PreSharedKeyId] {
+        &self.psks
+    }
+
+    /// Builds the ratchet tree either from the ratchet tree extension in the
+    /// GroupInfo or from the `nodes_option`, verifies the GroupInfo against
+    /// it and computes the epoch secrets of the group.
+    pub(crate) fn validate<KeyStore: OpenMlsKeyStore>(
+        mut self,
+        nodes_option: Option<Vec<Option<Node>>>,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<ValidatedWelcome, WelcomeError<KeyStore::Error>> {
+        let signer = self.verifiable_group_info.signer();
 
         // Build the ratchet tree
 
@@ -126,25 +225,38 @@ impl CoreGroup {
         // If we got a ratchet tree extension in the welcome, we enable it for
         // this group. Note that this is not strictly necessary. But there's
         // currently no other mechanism to enable the extension.
-        let (nodes, enable_ratchet_tree_extension) =
-            match try_nodes_from_extensions(verifiable_group_info.extensions()) {
-                Some(nodes) => (nodes, true),
+        // The nodes of the extension are deserialized one at a time while
+        // the tree is built, such that they are not held in memory twice.
+        let encryption_key = self
+            .key_package_bundle
+            .key_package()
+            .leaf_node()
+            .encryption_key();
+        let (tree, enable_ratchet_tree_extension) =
+            match self.verifiable_group_info.extensions().ratchet_tree() {
+                Some(ratchet_tree) => (
+                    TreeSync::from_nodes(
+                        backend,
+                        self.ciphersuite,
+                        ratchet_tree.nodes(),
+                        encryption_key,
+                    ),
+                    true,
+                ),
                 None => match nodes_option {
-                    Some(n) => (n, false),
+                    Some(nodes) => (
+                        TreeSync::from_nodes(
+                            backend,
+                            self.ciphersuite,
+                            nodes.into_iter().map(Ok::<_, tls_codec::Error>),
+                            encryption_key,
+                        ),
+                        false,
+                    ),
                     None => return Err(WelcomeError::MissingRatchetTree),
                 },
             };
-
-        let tree = TreeSync::from_nodes(
-            backend,
-            ciphersuite,
-            &nodes,
-            key_package_bundle
-                .key_package()
-                .leaf_node()
-                .encryption_key(),
-        )
-        .map_err(|e| match e {
+        let tree = tree.map_err(|e| match e {
             TreeSyncFromNodesError::LibraryError(e) => e.into(),
             TreeSyncFromNodesError::PublicTreeError(e) => WelcomeError::PublicTreeError(e),
         })?;
@@ -152,124 +264,242 @@ impl CoreGroup {
         let diff = tree.empty_diff();
 
         // If we got a path secret, derive the path (which also checks if the
-        // public keys match) and store the derived keys in the key store.
-        let group_keypairs = if let Some(path_secret) = path_secret_option {
+        // public keys match). The derived keys are stored in the key store
+        // together with the group.
+        let group_keypairs = if let Some(path_secret) = self.path_secret {
             let (path_keypairs, _commit_secret) = diff
-                .derive_path_secrets(
-                    backend,
-                    ciphersuite,
-                    path_secret,
-                    verifiable_group_info.signer(),
-                )
+                .derive_path_secrets(backend, self.ciphersuite, path_secret, signer)
                 .map_err(|e| match e {
                     TreeSyncSetPathError::LibraryError(e) => e.into(),
                     TreeSyncSetPathError::PublicKeyMismatch => {
                         WelcomeError::PublicTreeError(PublicTreeError::PublicKeyMismatch)
                     }
                 })?;
-            vec![leaf_keypair]
+            vec![self.leaf_keypair]
                 .into_iter()
                 .chain(path_keypairs.into_iter())
                 .collect()
         } else {
-            vec![leaf_keypair]
+            vec![self.leaf_keypair]
         };
 
         let group_info: GroupInfo = {
             let signer_credential = tree
-                .leaf(verifiable_group_info.signer())
+                .leaf(signer)
                 .ok_or(WelcomeError::UnknownSender)?
                 .credential();
 
-            verifiable_group_info
+            self.verifiable_group_info
                 .verify(
                     backend,
                     signer_credential.signature_key(),
-                    ciphersuite.signature_algorithm(),
+                    self.ciphersuite.signature_algorithm(),
                 )
                 .map_err(|_| WelcomeError::InvalidGroupInfoSignature)?
         };
 
-        // Compute state
-        let group_context = GroupContext::new(
-            ciphersuite,
-            group_info.group_context().group_id().clone(),
-            group_info.group_context().epoch(),
+        let JoinedEpoch {
+            group_context,
+            group_epoch_secrets,
+            message_secrets,
+            interim_transcript_hash,
+        } = join_epoch(
+            self.mls_version,
+            self.ciphersuite,
+            &mut self.key_schedule,
+            &group_info,
             tree.tree_hash().to_vec(),
-            group_info
-                .group_context()
-                .confirmed_transcript_hash()
-                .to_vec(),
-            group_info.group_context().extensions().clone(),
-        );
-
-        let serialized_group_context = group_context
-            .tls_serialize_detached()
-            .map_err(LibraryError::missing_bound_check)?;
-        // TODO #751: Implement PSK
-        key_schedule
-            .add_context(backend, &serialized_group_context)
-            .map_err(|_| LibraryError::custom("Using the key schedule in the wrong state"))?;
-        let epoch_secrets = key_schedule
-            .epoch_secrets(backend)
-            .map_err(|_| LibraryError::custom("Using the key schedule in the wrong state"))?;
-
-        let (group_epoch_secrets, message_secrets) = epoch_secrets.split_secrets(
-            serialized_group_context,
             tree.leaf_count(),
             tree.own_leaf_index(),
-        );
-
-        let confirmation_tag = message_secrets
-            .confirmation_key()
-            .tag(backend, group_context.confirmed_transcript_hash())
-            .map_err(LibraryError::unexpected_crypto_error)?;
-        let interim_transcript_hash = update_interim_transcript_hash(
-            ciphersuite,
             backend,
-            &InterimTranscriptHashInput::from(&confirmation_tag),
-            group_context.confirmed_transcript_hash(),
         )?;
 
-        // Verify confirmation tag
-        if &confirmation_tag != group_info.confirmation_tag() {
-            log::error!("Confirmation tag mismatch");
-            log_crypto!(trace, "  Got:      {:x?}", confirmation_tag);
-            log_crypto!(trace, "  Expected: {:x?}", group_info.confirmation_tag());
-            debug_assert!(false, "Confirmation tag mismatch");
-            Err(WelcomeError::ConfirmationTagMismatch)
-        } else {
-            let message_secrets_store = MessageSecretsStore::new_with_secret(0, message_secrets);
-
-            let group = CoreGroup {
-                ciphersuite,
-                group_context,
-                group_epoch_secrets,
-                tree,
-                interim_transcript_hash,
-                use_ratchet_tree_extension: enable_ratchet_tree_extension,
-                mls_version,
-                message_secrets_store,
-            };
-            group
-                .store_epoch_keypairs(backend, group_keypairs.as_slice())
-                .map_err(WelcomeError::KeyStoreError)?;
+        let message_secrets_store = MessageSecretsStore::new_with_secret(0, message_secrets);
 
-            Ok(group)
-        }
+        let group = CoreGroup {
+            ciphersuite: self.ciphersuite,
+            group_context,
+            group_epoch_secrets,
+            tree,
+            interim_transcript_hash,
+            use_ratchet_tree_extension: enable_ratchet_tree_extension,
+            mls_version: self.mls_version,
+            message_secrets_store,
+            allow_duplicate_identities: false,
+        };
+
+        Ok(ValidatedWelcome {
+            group,
+            group_keypairs,
+            signer,
+        })
     }
 
-    // Helper functions
+    /// Verifies the GroupInfo against the [`PartialTree`] of the client and
+    /// the [`PartialTree`] of the signer of the GroupInfo instead of the full
+    /// ratchet tree, and computes the epoch secrets of the group.
+    ///
+    /// The path secret of the Welcome, if any, is checked against the direct
+    /// path of the client, but the derived key pairs are not kept, since they
+    /// are only needed to process Commits.
+    #[cfg(feature = "partial-tree")]
+    pub(crate) fn validate_partial<KeyStore: OpenMlsKeyStore>(
+        mut self,
+        own_tree: &PartialTree,
+        signer_tree: &PartialTree,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(GroupContext, GroupEpochSecrets), WelcomeError<KeyStore::Error>> {
+        let signer = self.verifiable_group_info.signer();
 
-    pub(crate) fn find_key_package_from_welcome_secrets(
-        hash_ref: HashReference,
-        welcome_secrets: &[EncryptedGroupSecrets],
-    ) -> Option<EncryptedGroupSecrets> {
-        for egs in welcome_secrets {
-            if hash_ref == egs.new_member() {
-                return Some(egs.clone());
+        // The partial tree of the client has to contain the leaf it was
+        // added with.
+        let encryption_key = self
+            .key_package_bundle
+            .key_package()
+            .leaf_node()
+            .encryption_key();
+        if own_tree.leaf_node().map(LeafNode::encryption_key) != Some(encryption_key) {
+            return Err(WelcomeError::PublicTreeError(
+                PublicTreeError::MissingKeyPackage,
+            ));
+        }
+        if signer_tree.leaf_index() == own_tree.leaf_index() || signer_tree.leaf_index() != signer {
+            return Err(WelcomeError::UnknownSender);
+        }
+
+        // Both partial trees have to be part of the ratchet tree of the group.
+        let tree_hash = self
+            .verifiable_group_info
+            .group_context()
+            .tree_hash()
+            .to_vec();
+        own_tree.verify(backend, self.ciphersuite, &tree_hash)?;
+        signer_tree.verify(backend, self.ciphersuite, &tree_hash)?;
+
+        // If we got a path secret, check that it matches the public keys in
+        // the direct path of the client, starting at the lowest common
+        // ancestor with the signer. The verification above ensures that the
+        // common ancestor is in the direct path.
+        if let Some(mut path_secret) = self.path_secret {
+            let own_index = own_tree.leaf_index();
+            let common_ancestor =
+                (u32::BITS - 1 - (own_index.u32() ^ signer.u32()).leading_zeros()) as usize;
+            for parent_node in own_tree.direct_path()[common_ancestor..].iter().flatten() {
+                if parent_node.unmerged_leaves().contains(&own_index) {
+                    continue;
+                }
+                let keypair = path_secret.derive_key_pair(backend, self.ciphersuite)?;
+                if parent_node.encryption_key() != keypair.public_key() {
+                    return Err(WelcomeError::PublicTreeError(
+                        PublicTreeError::PublicKeyMismatch,
+                    ));
+                }
+                path_secret = path_secret.derive_path_secret(backend, self.ciphersuite)?;
             }
         }
-        None
+
+        let group_info: GroupInfo = {
+            let signer_credential = signer_tree
+                .leaf_node()
+                .ok_or(WelcomeError::UnknownSender)?
+                .credential();
+
+            self.verifiable_group_info
+                .verify(
+                    backend,
+                    signer_credential.signature_key(),
+                    self.ciphersuite.signature_algorithm(),
+                )
+                .map_err(|_| WelcomeError::InvalidGroupInfoSignature)?
+        };
+
+        let joined_epoch = join_epoch(
+            self.mls_version,
+            self.ciphersuite,
+            &mut self.key_schedule,
+            &group_info,
+            tree_hash,
+            1 << own_tree.direct_path().len(),
+            own_tree.leaf_index(),
+            backend,
+        )?;
+
+        Ok((joined_epoch.group_context, joined_epoch.group_epoch_secrets))
+    }
+}
+
+/// The state of the epoch a client joins with a Welcome.
+struct JoinedEpoch {
+    group_context: GroupContext,
+    group_epoch_secrets: GroupEpochSecrets,
+    message_secrets: MessageSecrets,
+    interim_transcript_hash: Vec<u8>,
+}
+
+/// Computes the epoch secrets of the group from the `key_schedule` and
+/// verifies the confirmation tag of the `group_info`. The `tree_hash` has to
+/// be the one of the verified ratchet tree.
+#[allow(clippy::too_many_arguments)]
+fn join_epoch<KeyStore: OpenMlsKeyStore>(
+    mls_version: ProtocolVersion,
+    ciphersuite: Ciphersuite,
+    key_schedule: &mut KeySchedule,
+    group_info: &GroupInfo,
+    tree_hash: Vec<u8>,
+    leaf_count: u32,
+    own_leaf_index: LeafNodeIndex,
+    backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+) -> Result<JoinedEpoch, WelcomeError<KeyStore::Error>> {
+    let group_context = GroupContext::new(
+        mls_version,
+        ciphersuite,
+        group_info.group_context().group_id().clone(),
+        group_info.group_context().epoch(),
+        tree_hash,
+        group_info
+            .group_context()
+            .confirmed_transcript_hash()
+            .to_vec(),
+        group_info.group_context().extensions().clone(),
+    );
+
+    let serialized_group_context = group_context
+        .tls_serialize_detached()
+        .map_err(LibraryError::missing_bound_check)?;
+    // TODO #751: Implement PSK
+    key_schedule
+        .add_context(backend, &serialized_group_context)
+        .map_err(|_| LibraryError::custom("Using the key schedule in the wrong state"))?;
+    let epoch_secrets = key_schedule
+        .epoch_secrets(backend)
+        .map_err(|_| LibraryError::custom("Using the key schedule in the wrong state"))?;
+
+    let (group_epoch_secrets, message_secrets) =
+        epoch_secrets.split_secrets(serialized_group_context, leaf_count, own_leaf_index);
+
+    let confirmation_tag = message_secrets
+        .confirmation_key()
+        .tag(backend, group_context.confirmed_transcript_hash())
+        .map_err(LibraryError::unexpected_crypto_error)?;
+    let interim_transcript_hash = update_interim_transcript_hash(
+        ciphersuite,
+        backend,
+        &InterimTranscriptHashInput::from(&confirmation_tag),
+        group_context.confirmed_transcript_hash(),
+    )?;
+
+    // Verify confirmation tag
+    if &confirmation_tag != group_info.confirmation_tag() {
+        log::error!("Confirmation tag mismatch");
+        log_crypto!(trace, "  Got:      {:x?}", confirmation_tag);
+        log_crypto!(trace, "  Expected: {:x?}", group_info.confirmation_tag());
+        return Err(WelcomeError::ConfirmationTagMismatch);
     }
+
+    Ok(JoinedEpoch {
+        group_context,
+        group_epoch_secrets,
+        message_secrets,
+        interim_transcript_hash,
+    })
 }
@@ -1,6 +1,9 @@
 use std::collections::VecDeque;
 
-use crate::schedule::message_secrets::MessageSecrets;
+use crate::{
+    group::mls_group::retention::MessageSecretsPin, schedule::message_secrets::MessageSecrets,
+    tree::index::SecretTreeLeafIndex,
+};
 
 use super::*;
 
@@ -24,6 +27,13 @@ pub(crate) struct MessageSecretsStore {
     past_epoch_trees: VecDeque<EpochTree>,
     // The message secrets of the current epoch.
     message_secrets: MessageSecrets,
+    // Epochs and senders whose message secrets are retained beyond
+    // `max_epochs`.
+    #[serde(default)]
+    pins: Vec<MessageSecretsPin>,
+    // Pinned message secrets that were evicted from `past_epoch_trees`.
+    #[serde(default)]
+    retained_epoch_trees: Vec<EpochTree>,
 }
 
 impl MessageSecretsStore {
@@ -34,17 +44,19 @@ impl MessageSecretsStore {
             max_epochs,
             past_epoch_trees: VecDeque::new(),
             message_secrets,
+            pins: Vec::new(),
+            retained_epoch_trees: Vec::new(),
         }
     }
 
-    /// Resize the store.
+    /// Resize the store. Pinned message secrets of the dropped epochs are
+    /// retained.
     pub(crate) fn resize(&mut self, max_past_epochs: usize) {
-        let old_size = self.max_epochs;
         self.max_epochs = max_past_epochs;
-        if old_size > max_past_epochs {
-            let num_epochs_out = old_size - max_past_epochs;
-            self.past_epoch_trees.rotate_left(num_epochs_out);
-            self.past_epoch_trees.truncate(max_past_epochs);
+        while self.past_epoch_trees.len() > max_past_epochs {
+            if let Some(epoch_tree) = self.past_epoch_trees.pop_front() {
+                self.evict(epoch_tree);
+            }
         }
     }
 
@@ -57,19 +69,22 @@ impl MessageSecretsStore {
         message_secrets: MessageSecrets,
         leaves: Vec<Member>,
     ) {
+        let epoch_tree = EpochTree {
+            epoch: group_epoch.into().as_u64(),
+            message_secrets,
+            leaves,
+        };
         // Don't store the tree if it's not intended
         if self.max_epochs == 0 {
+            self.evict(epoch_tree);
             return;
         }
-        if self.past_epoch_trees.len() >= self.max_epochs {
-            self.past_epoch_trees.rotate_left(1);
-            self.past_epoch_trees.truncate(self.max_epochs - 1);
+        while self.past_epoch_trees.len() >= self.max_epochs {
+            if let Some(oldest) = self.past_epoch_trees.pop_front() {
+                self.evict(oldest);
+            }
         }
-        self.past_epoch_trees.push_back(EpochTree {
-            epoch: group_epoch.into().as_u64(),
-            message_secrets,
-            leaves,
-        });
+        self.past_epoch_trees.push_back(epoch_tree);
         debug_assert!(
             self.max_epochs >= self.past_epoch_trees.len(),
             "Only {} past secrets must be stored but we found {}",
@@ -85,12 +100,9 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<&mut MessageSecrets> {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter_mut() {
-            if epoch_tree.epoch == epoch {
-                return Some(&mut epoch_tree.message_secrets);
-            }
-        }
-        None
+        self.epoch_trees_mut()
+            .find(|epoch_tree| epoch_tree.epoch == epoch)
+            .map(|epoch_tree| &mut epoch_tree.message_secrets)
     }
 
     /// Get a reference to a secret tree for a given epoch `group_epoch`.
@@ -100,12 +112,9 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<&MessageSecrets> {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter() {
-            if epoch_tree.epoch == epoch {
-                return Some(&epoch_tree.message_secrets);
-            }
-        }
-        None
+        self.epoch_trees()
+            .find(|epoch_tree| epoch_tree.epoch == epoch)
+            .map(|epoch_tree| &epoch_tree.message_secrets)
     }
 
     /// Get a mutable reference to a secret tree for a given epoch `group_epoch`.
@@ -116,23 +125,45 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<(&mut MessageSecrets, &[Member])> {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter_mut() {
-            if epoch_tree.epoch == epoch {
-                return Some((&mut epoch_tree.message_secrets, &epoch_tree.leaves));
-            }
-        }
-        None
+        self.epoch_trees_mut()
+            .find(|epoch_tree| epoch_tree.epoch == epoch)
+            .map(|epoch_tree| {
+                (
+                    &mut epoch_tree.message_secrets,
+                    epoch_tree.leaves.as_slice(),
+                )
+            })
+    }
+
+    /// Get mutable references to the message secrets of the current epoch and
+    /// to the message secrets of all past epochs together with their epoch,
+    /// starting with the oldest epoch. Retained pinned epochs come first.
+    pub(crate) fn current_and_past_secrets_mut(
+        &mut self,
+    ) -> (
+        &mut MessageSecrets,
+        impl DoubleEndedIterator<Item = (GroupEpoch, &mut MessageSecrets)>,
+    ) {
+        let past_secrets = self
+            .retained_epoch_trees
+            .iter_mut()
+            .chain(self.past_epoch_trees.iter_mut())
+            .map(|epoch_tree| {
+                (
+                    GroupEpoch::from(epoch_tree.epoch),
+                    &mut epoch_tree.message_secrets,
+                )
+            });
+        (&mut self.message_secrets, past_secrets)
     }
 
     /// Return a slice with the [`Member`]s of the `group_epoch`.
     pub(crate) fn leaves_for_epoch(&self, group_epoch: impl Into<GroupEpoch>) -> &[Member] {
         let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter() {
-            if epoch_tree.epoch == epoch {
-                return &epoch_tree.leaves;
-            }
-        }
-        &[]
+        self.epoch_trees()
+            .find(|epoch_tree| epoch_tree.epoch == epoch)
+            .map(|epoch_tree| epoch_tree.leaves.as_slice())
+            .unwrap_or(&[])
     }
 
     /// Check if the provided epoch contains a leaf index.
@@ -141,7 +172,7 @@ impl MessageSecretsStore {
         group_epoch: GroupEpoch,
         leaf_index: LeafNodeIndex,
     ) -> bool {
-        self.past_epoch_trees.iter().any(|t| {
+        self.epoch_trees().any(|t| {
             t.epoch == group_epoch.0
                 && t.leaves
                     .iter()
@@ -158,4 +189,92 @@ impl MessageSecretsStore {
     pub(crate) fn message_secrets(&self) -> &MessageSecrets {
         &self.message_secrets
     }
+
+    /// Overwrite the message secrets of the current and all past epochs with
+    /// zeros and drop the past epochs and the pins. Returns the number of
+    /// epochs whose secrets were overwritten.
+    pub(crate) fn zeroize(&mut self) -> usize {
+        for epoch_tree in self.epoch_trees_mut() {
+            epoch_tree.message_secrets.zeroize();
+        }
+        self.message_secrets.zeroize();
+        let epochs = self.past_epoch_trees.len() + self.retained_epoch_trees.len() + 1;
+        self.past_epoch_trees.clear();
+        self.retained_epoch_trees.clear();
+        self.pins.clear();
+        epochs
+    }
+
+    /// Pin the message secrets of an epoch, such that they are retained when
+    /// the epoch is evicted from the store.
+    pub(crate) fn pin(&mut self, pin: MessageSecretsPin) {
+        if !self.pins.contains(&pin) {
+            self.pins.push(pin);
+        }
+    }
+
+    /// Remove a pin. If the epoch of the pin was already evicted, its message
+    /// secrets are deleted, or reduced to those of the senders that are still
+    /// pinned. Returns `false` if the pin didn't exist.
+    pub(crate) fn unpin(&mut self, pin: MessageSecretsPin) -> bool {
+        let position = match self.pins.iter().position(|p| *p == pin) {
+            Some(position) => position,
+            None => return false,
+        };
+        self.pins.remove(position);
+        let epoch = pin.epoch().as_u64();
+        if let Some(position) = self
+            .retained_epoch_trees
+            .iter()
+            .position(|epoch_tree| epoch_tree.epoch == epoch)
+        {
+            let epoch_tree = self.retained_epoch_trees.remove(position);
+            self.evict(epoch_tree);
+        }
+        true
+    }
+
+    /// Return the pins.
+    pub(crate) fn pins(&self) -> &[MessageSecretsPin] {
+        &self.pins
+    }
+
+    /// Delete the message secrets of an epoch that is evicted from the store.
+    /// If the epoch is pinned, the message secrets are retained instead. If
+    /// all pins of the epoch name a sender, only the secrets of these senders
+    /// are retained.
+    fn evict(&mut self, mut epoch_tree: EpochTree) {
+        let pins = self
+            .pins
+            .iter()
+            .filter(|pin| pin.epoch().as_u64() == epoch_tree.epoch)
+            .collect::<Vec<_>>();
+        if pins.is_empty() {
+            epoch_tree.message_secrets.zeroize();
+            return;
+        }
+        let senders = pins
+            .iter()
+            .map(|pin| pin.sender().map(SecretTreeLeafIndex::from))
+            .collect::<Option<Vec<_>>>();
+        if let Some(senders) = senders {
+            epoch_tree
+                .message_secrets
+                .secret_tree_mut()
+                .retain_senders(&senders);
+        }
+        self.retained_epoch_trees.push(epoch_tree);
+    }
+
+    fn epoch_trees(&self) -> impl Iterator<Item = &EpochTree> {
+        self.past_epoch_trees
+            .iter()
+            .chain(self.retained_epoch_trees.iter())
+    }
+
+    fn epoch_trees_mut(&mut self) -> impl Iterator<Item = &mut EpochTree> {
+        self.past_epoch_trees
+            .iter_mut()
+            .chain(self.retained_epoch_trees.iter_mut())
+    }
 }
@@ -31,7 +31,7 @@ impl CoreGroup {
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
         message: impl Into<ProtocolMessage>,
-        sender_ratchet_configuration: &SenderRatchetConfiguration,
+        sender_ratchet_configurations: &SenderRatchetConfigurations,
     ) -> Result<UnverifiedMessage, ValidationError> {
         let message: ProtocolMessage = message.into();
         // Checks the following semantic validation:
@@ -44,8 +44,8 @@ impl CoreGroup {
         // Checks the following semantic validation:
         //  - ValSem006
         //  - ValSem007 MembershipTag presence
-        let decrypted_message = match message {
-            ProtocolMessage::PublicMessage(public_message) => {
+        let decrypted_message = match message.into_body() {
+            ProtocolMessageBody::PublicMessage(public_message) => {
                 // If the message is older than the current epoch, we need to fetch the correct secret tree first.
                 let message_secrets =
                     self.message_secrets_for_epoch(epoch).map_err(|e| match e {
@@ -61,13 +61,13 @@ impl CoreGroup {
                     backend,
                 )?
             }
-            ProtocolMessage::PrivateMessage(ciphertext) => {
+            ProtocolMessageBody::PrivateMessage(ciphertext) => {
                 // If the message is older than the current epoch, we need to fetch the correct secret tree first
                 DecryptedMessage::from_inbound_ciphertext(
                     ciphertext,
                     backend,
                     self,
-                    sender_ratchet_configuration,
+                    sender_ratchet_configurations,
                 )?
             }
         };
@@ -84,11 +84,11 @@ impl CoreGroup {
         //  - ValSem245
         //  - Prepares ValSem246 by setting the right credential. The remainder
         //    of ValSem246 is validated as part of ValSem010.
-        // External senders are not supported yet #106/#151.
         let credential = decrypted_message.credential(
             self.treesync(),
             self.message_secrets_store
                 .leaves_for_epoch(decrypted_message.verifiable_content().epoch()),
+            self.group_context_extensions().external_senders(),
         )?;
 
         Ok(UnverifiedMessage::from_decrypted_message(
@@ -97,6 +97,86 @@ impl CoreGroup {
         ))
     }
 
+    /// Validates a message without decrypting it and without touching the
+    /// sender ratchets, e.g. for a relay that filters the traffic of a group.
+    ///
+    /// [PublicMessage]s are fully authenticated. For [PrivateMessage]s, only
+    /// the cleartext framing is checked, since the sender and the content are
+    /// encrypted.
+    /// Checks the following semantic validation:
+    ///  - ValSem002
+    ///  - ValSem003
+    ///  - ValSem004
+    ///  - ValSem005
+    ///  - ValSem007
+    ///  - ValSem008
+    ///  - ValSem009
+    ///  - ValSem010
+    ///  - ValSem112
+    ///  - ValSem245
+    pub(crate) fn validate_message(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: ProtocolMessage,
+    ) -> Result<(), ValidationError> {
+        // Checks the following semantic validation:
+        //  - ValSem002
+        //  - ValSem003
+        self.validate_framing(&message)?;
+
+        let message_secrets =
+            self.message_secrets_for_epoch(message.epoch())
+                .map_err(|e| match e {
+                    SecretTreeError::TooDistantInThePast => ValidationError::NoPastEpochData,
+                    _ => LibraryError::custom(
+                        "Unexpected error while retrieving message secrets for epoch.",
+                    )
+                    .into(),
+                })?;
+        let public_message = match message.into_body() {
+            ProtocolMessageBody::PublicMessage(public_message) => public_message,
+            ProtocolMessageBody::PrivateMessage(_) => return Ok(()),
+        };
+
+        // Checks the following semantic validation:
+        //  - ValSem007
+        //  - ValSem008
+        let decrypted_message = DecryptedMessage::from_inbound_public_message(
+            public_message,
+            message_secrets,
+            backend,
+        )?;
+
+        // Checks the following semantic validation:
+        //  - ValSem004
+        //  - ValSem005
+        //  - ValSem009
+        self.validate_verifiable_content(decrypted_message.verifiable_content())?;
+
+        // Checks the following semantic validation:
+        //  - ValSem112
+        //  - ValSem245
+        let credential = decrypted_message.credential(
+            self.treesync(),
+            self.message_secrets_store
+                .leaves_for_epoch(decrypted_message.verifiable_content().epoch()),
+            self.group_context_extensions().external_senders(),
+        )?;
+        let unverified_message =
+            UnverifiedMessage::from_decrypted_message(decrypted_message, Some(credential));
+
+        // ValSem010
+        match UnverifiedContextMessage::from_unverified_message(unverified_message)? {
+            UnverifiedContextMessage::Group(message) => message.into_verified(backend).map(|_| ()),
+            UnverifiedContextMessage::NewMember(message) => {
+                message.into_verified(backend).map(|_| ())
+            }
+            UnverifiedContextMessage::External(message) => {
+                message.into_verified(backend).map(|_| ())
+            }
+        }
+    }
+
     /// This processing function does most of the semantic verifications.
     /// It returns a [ProcessedMessage] enum.
     /// Checks the following semantic validation:
@@ -208,10 +288,39 @@ impl CoreGroup {
                     Some(credential),
                 ))
             }
-            UnverifiedContextMessage::External(_external_message) => {
-                // We don't support messages from external senders yet
-                // TODO #151/#106
-                todo!()
+            UnverifiedContextMessage::External(unverified_external_message) => {
+                let credential = unverified_external_message.credential().clone();
+                // Signature verification
+                let verified_external_message = unverified_external_message
+                    .into_verified(backend)
+                    .map_err(|_| ProcessMessageError::InvalidSignature)?;
+                let sender = verified_external_message
+                    .authenticated_content()
+                    .sender()
+                    .clone();
+                let authenticated_data = verified_external_message
+                    .authenticated_content()
+                    .authenticated_data()
+                    .to_owned();
+
+                // External senders can only send proposals. This was checked
+                // when the credential was looked up.
+                let content = ProcessedMessageContent::ProposalMessage(Box::new(
+                    QueuedProposal::from_authenticated_content(
+                        self.ciphersuite(),
+                        backend,
+                        verified_external_message.take_authenticated_content(),
+                    )?,
+                ));
+
+                Ok(ProcessedMessage::new(
+                    group_id,
+                    epoch,
+                    sender,
+                    authenticated_data,
+                    content,
+                    Some(credential),
+                ))
             }
             UnverifiedContextMessage::NewMember(unverified_new_member_message) => {
                 let credential = unverified_new_member_message.credential().clone();
@@ -313,12 +422,12 @@ impl CoreGroup {
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
         message: impl Into<ProtocolMessage>,
-        sender_ratchet_configuration: &SenderRatchetConfiguration,
+        sender_ratchet_configurations: &SenderRatchetConfigurations,
         proposal_store: &ProposalStore,
         own_kpbs: &[OpenMlsLeafNode],
     ) -> Result<ProcessedMessage, ProcessMessageError> {
         let unverified_message = self
-            .parse_message(backend, message.into(), sender_ratchet_configuration)
+            .parse_message(backend, message.into(), sender_ratchet_configurations)
             .map_err(ProcessMessageError::from)?;
         self.process_unverified_message(unverified_message, proposal_store, own_kpbs, backend)
     }
@@ -5,7 +5,7 @@ use crate::{
     framing::{
         mls_auth_content::AuthenticatedContent, mls_content::FramedContentBody, Sender, SenderError,
     },
-    group::errors::*,
+    group::{errors::*, GroupEpoch},
     messages::proposals::{
         AddProposal, PreSharedKeyProposal, Proposal, ProposalOrRef, ProposalOrRefType,
         ProposalType, RemoveProposal, UpdateProposal,
@@ -14,29 +14,91 @@ use crate::{
 
 use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
 use serde::{Deserialize, Serialize};
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 /// A [ProposalStore] can store the standalone proposals that are received from the DS
 /// in between two commit messages.
 #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct ProposalStore {
     queued_proposals: Vec<QueuedProposal>,
+    /// The times (in seconds since the UNIX epoch) at which the queued
+    /// proposals were stored, in the same order as the queued proposals.
+    #[serde(default)]
+    stored_at: Vec<u64>,
+}
+
+/// Returns the current time in seconds since the UNIX epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("SystemTime before UNIX EPOCH!")
+        .as_secs()
 }
 
 impl ProposalStore {
     pub(crate) fn new() -> Self {
         Self {
             queued_proposals: Vec::new(),
+            stored_at: Vec::new(),
         }
     }
     #[cfg(any(feature = "test-utils", test))]
     pub(crate) fn from_queued_proposal(queued_proposal: QueuedProposal) -> Self {
         Self {
             queued_proposals: vec![queued_proposal],
+            stored_at: vec![now()],
         }
     }
     pub(crate) fn add(&mut self, queued_proposal: QueuedProposal) {
+        let now = now();
+        // Proposals from a state persisted without storage times count as
+        // stored now.
+        self.stored_at.resize(self.queued_proposals.len(), now);
         self.queued_proposals.push(queued_proposal);
+        self.stored_at.push(now);
+    }
+    /// Removes the proposal with the given reference from the store. Returns
+    /// the removed proposal or `None` if it wasn't stored.
+    pub(crate) fn remove(&mut self, proposal_reference: &ProposalRef) -> Option<QueuedProposal> {
+        let position = self
+            .queued_proposals
+            .iter()
+            .position(|proposal| &proposal.proposal_reference() == proposal_reference)?;
+        if position < self.stored_at.len() {
+            self.stored_at.remove(position);
+        }
+        Some(self.queued_proposals.remove(position))
+    }
+    /// Removes the proposals that were sent in an epoch other than `epoch`
+    /// and, if there is a `max_age`, the proposals that were stored at least
+    /// `max_age` seconds ago. Returns the removed proposals.
+    pub(crate) fn remove_expired(
+        &mut self,
+        epoch: GroupEpoch,
+        max_age: Option<u64>,
+    ) -> Vec<QueuedProposal> {
+        let now = now();
+        self.stored_at.resize(self.queued_proposals.len(), now);
+        let mut expired = Vec::new();
+        let mut index = 0;
+        while index < self.queued_proposals.len() {
+            let from_other_epoch = self.queued_proposals[index]
+                .epoch()
+                .map_or(false, |proposal_epoch| proposal_epoch != epoch);
+            let too_old = max_age.map_or(false, |max_age| {
+                now.saturating_sub(self.stored_at[index]) >= max_age
+            });
+            if from_other_epoch || too_old {
+                expired.push(self.queued_proposals.remove(index));
+                self.stored_at.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        expired
     }
     pub(crate) fn proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
         self.queued_proposals.iter()
@@ -46,6 +108,7 @@ impl ProposalStore {
     }
     pub(crate) fn empty(&mut self) {
         self.queued_proposals = Vec::new();
+        self.stored_at = Vec::new();
     }
 }
 
@@ -57,6 +120,8 @@ pub struct QueuedProposal {
     proposal_reference: ProposalRef,
     sender: Sender,
     proposal_or_ref_type: ProposalOrRefType,
+    #[serde(default)]
+    epoch: Option<GroupEpoch>,
 }
 
 impl QueuedProposal {
@@ -76,6 +141,7 @@ impl QueuedProposal {
             proposal_reference,
             sender: public_message.sender().clone(),
             proposal_or_ref_type: ProposalOrRefType::Reference,
+            epoch: Some(public_message.epoch()),
         })
     }
 
@@ -92,6 +158,7 @@ impl QueuedProposal {
             proposal_reference,
             sender: sender.clone(),
             proposal_or_ref_type: ProposalOrRefType::Proposal,
+            epoch: None,
         })
     }
     /// Returns the `Proposal` as a reference
@@ -99,9 +166,14 @@ impl QueuedProposal {
         &self.proposal
     }
     /// Returns the `ProposalRef`.
-    pub(crate) fn proposal_reference(&self) -> ProposalRef {
+    pub fn proposal_reference(&self) -> ProposalRef {
         self.proposal_reference.clone()
     }
+    /// Returns `true` if the proposal was included in the Commit by
+    /// reference and `false` if it was included inline.
+    pub fn is_by_reference(&self) -> bool {
+        self.proposal_or_ref_type == ProposalOrRefType::Reference
+    }
     /// Returns the `ProposalOrRefType`.
     pub(crate) fn proposal_or_ref_type(&self) -> ProposalOrRefType {
         self.proposal_or_ref_type
@@ -110,8 +182,27 @@ impl QueuedProposal {
     pub fn sender(&self) -> &Sender {
         &self.sender
     }
+    /// Returns the epoch in which the proposal was sent, or `None` if the
+    /// proposal was included inline in a Commit.
+    pub fn epoch(&self) -> Option<GroupEpoch> {
+        self.epoch
+    }
 }
 
+/// The order in which the proposals of a Commit are applied to the group.
+/// This is the order of the MLS specification, where ExternalInit proposals
+/// come first.
+pub(crate) const APPLICATION_ORDER: [ProposalType; 8] = [
+    ProposalType::ExternalInit,
+    ProposalType::GroupContextExtensions,
+    ProposalType::Update,
+    ProposalType::Remove,
+    ProposalType::Add,
+    ProposalType::Presharedkey,
+    ProposalType::Reinit,
+    ProposalType::AppAck,
+];
+
 /// Proposal queue that helps filtering and sorting Proposals received during one
 /// epoch. The Proposals are stored in a `HashMap` which maps Proposal
 /// references to Proposals, such that, given a reference, a proposal can be
@@ -124,9 +215,33 @@ pub(crate) struct ProposalQueue {
     proposal_references: Vec<ProposalRef>,
     /// `queued_proposals` contains the actual proposals in the queue. They are
     /// stored in a `HashMap` to allow for efficient access to the proposals.
+    #[serde(with = "queued_proposals_serde")]
     queued_proposals: HashMap<ProposalRef, QueuedProposal>,
 }
 
+/// (De)serializes the `HashMap` of a [`ProposalQueue`] as a list of pairs,
+/// because `ProposalRef`s can't be used as keys of a JSON object. This allows
+/// the staged commit of a pending commit to be persisted.
+mod queued_proposals_serde {
+    use super::*;
+
+    use serde::{Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        queued_proposals: &HashMap<ProposalRef, QueuedProposal>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(queued_proposals.iter())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<ProposalRef, QueuedProposal>, D::Error> {
+        let queued_proposals = Vec::<(ProposalRef, QueuedProposal)>::deserialize(deserializer)?;
+        Ok(queued_proposals.into_iter().collect())
+    }
+}
+
 impl ProposalQueue {
     /// Returns `true` if the [`ProposalQueue`] is empty. Otherwise returns `false`.
     pub(crate) fn is_empty(&self) -> bool {
@@ -242,6 +357,17 @@ impl ProposalQueue {
             .filter_map(move |reference| self.get(reference))
     }
 
+    /// Returns an iterator over all `QueuedProposal` in the queue in the order
+    /// in which they are applied to the group, see [`APPLICATION_ORDER`].
+    /// Proposals of the same type are in the order of the Commit message.
+    pub(crate) fn queued_proposals_in_application_order(
+        &self,
+    ) -> impl Iterator<Item = &QueuedProposal> {
+        APPLICATION_ORDER
+            .iter()
+            .flat_map(move |&proposal_type| self.filtered_by_type(proposal_type))
+    }
+
     /// Returns an iterator over all Add proposals in the queue
     /// in the order of the the Commit message
     pub(crate) fn add_proposals(&self) -> impl Iterator<Item = QueuedAddProposal> {
@@ -331,7 +457,7 @@ impl ProposalQueue {
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         sender: Sender,
-        proposal_store: &'a ProposalStore,
+        proposals: impl Iterator<Item = &'a QueuedProposal>,
         inline_proposals: &'a [Proposal],
         own_index: LeafNodeIndex,
     ) -> Result<(Self, bool), ProposalQueueError> {
@@ -346,11 +472,12 @@ impl ProposalQueue {
         let mut proposal_pool: HashMap<ProposalRef, QueuedProposal> = HashMap::new();
         let mut contains_own_updates = false;
         let mut contains_external_init = false;
+        let mut contains_reinit = false;
+        let mut contains_group_context_extensions = false;
 
         // Aggregate both proposal types to a common iterator
         // We checked earlier that only proposals can end up here
-        let mut queued_proposal_list: Vec<QueuedProposal> =
-            proposal_store.proposals().cloned().collect();
+        let mut queued_proposal_list: Vec<QueuedProposal> = proposals.cloned().collect();
 
         queued_proposal_list.extend(
             inline_proposals
@@ -408,8 +535,12 @@ impl ProposalQueue {
                     proposal_pool.insert(queued_proposal.proposal_reference(), queued_proposal);
                 }
                 Proposal::ReInit(_) => {
-                    // TODO #751: Only keep one ReInit
-                    proposal_pool.insert(queued_proposal.proposal_reference(), queued_proposal);
+                    // Only use the first ReInit proposal we find.
+                    if !contains_reinit {
+                        valid_proposals.insert(queued_proposal.proposal_reference());
+                        proposal_pool.insert(queued_proposal.proposal_reference(), queued_proposal);
+                        contains_reinit = true;
+                    }
                 }
                 Proposal::ExternalInit(_) => {
                     // Only use the first external init proposal we find.
@@ -421,8 +552,12 @@ impl ProposalQueue {
                 }
                 Proposal::AppAck(_) => unimplemented!("See #291"),
                 Proposal::GroupContextExtensions(_) => {
-                    // TODO: Validate proposal?
-                    proposal_pool.insert(queued_proposal.proposal_reference(), queued_proposal);
+                    // Only use the first GroupContextExtensions proposal we find.
+                    if !contains_group_context_extensions {
+                        valid_proposals.insert(queued_proposal.proposal_reference());
+                        proposal_pool.insert(queued_proposal.proposal_reference(), queued_proposal);
+                        contains_group_context_extensions = true;
+                    }
                 }
             }
         }
@@ -492,7 +627,7 @@ pub struct QueuedAddProposal<'a> {
 
 impl<'a> QueuedAddProposal<'a> {
     /// Returns a reference to the proposal
-    pub fn add_proposal(&self) -> &AddProposal {
+    pub fn add_proposal(&self) -> &'a AddProposal {
         self.add_proposal
     }
 
@@ -137,6 +137,7 @@ impl CoreGroup {
         // ValSem107
         // ValSem108
         self.validate_remove_proposals(&proposal_queue)?;
+        self.validate_max_members(&proposal_queue, *sender == Sender::NewMemberCommit)?;
 
         let public_key_set = match sender {
             Sender::Member(leaf_index) => {
@@ -224,8 +225,7 @@ impl CoreGroup {
                     )
                     .is_err()
                 {
-                    debug_assert!(
-                        false,
+                    log::error!(
                         "Verification failed of leaf node in commit path.\n\
                      Leaf node identity: {:?} ({})",
                         leaf_node.credential().identity(),
@@ -369,12 +369,16 @@ impl CoreGroup {
         )?;
 
         let provisional_group_context = GroupContext::new(
+            self.version(),
             ciphersuite,
             self.group_context.group_id().clone(),
             provisional_epoch,
             diff.compute_tree_hashes(backend, ciphersuite)?,
             confirmed_transcript_hash.clone(),
-            self.group_context.extensions().clone(),
+            apply_proposals_values
+                .extensions
+                .clone()
+                .unwrap_or_else(|| self.group_context.extensions().clone()),
         );
 
         // Prepare the PskSecret
@@ -502,12 +506,15 @@ impl CoreGroup {
                 // Figure out which keys we need in the new epoch.
                 let new_owned_encryption_keys = self.tree.owned_encryption_keys();
                 // From the old and new keys, keep the ones that are still relevant in the new epoch.
-                let epoch_keypairs: Vec<EncryptionKeyPair> = old_epoch_keypairs
-                    .into_iter()
-                    .chain(state.new_keypairs.into_iter())
-                    .chain(leaf_keypair.into_iter())
-                    .filter(|keypair| new_owned_encryption_keys.contains(keypair.public_key()))
-                    .collect();
+                // The other ones can be released.
+                let (epoch_keypairs, released_keypairs): (Vec<EncryptionKeyPair>, Vec<_>) =
+                    old_epoch_keypairs
+                        .into_iter()
+                        .chain(state.new_keypairs.into_iter())
+                        .chain(leaf_keypair.into_iter())
+                        .partition(|keypair| {
+                            new_owned_encryption_keys.contains(keypair.public_key())
+                        });
                 // We should have private keys for all owned encryption keys.
 
                 debug_assert_eq!(new_owned_encryption_keys.len(), epoch_keypairs.len());
@@ -528,6 +535,13 @@ impl CoreGroup {
                         .delete_from_key_store(backend)
                         .map_err(MergeCommitError::KeyStoreError)?;
                 }
+                // Releasing keys is best effort. The group state has already
+                // been updated at this point.
+                for keypair in released_keypairs {
+                    if let Err(e) = keypair.release_private_key(backend) {
+                        log::warn!("Failed to release a private key: {:?}", e);
+                    }
+                }
 
                 Ok(Some(message_secrets))
             }
@@ -547,9 +561,21 @@ pub struct StagedCommit {
     staged_proposal_queue: ProposalQueue,
     state: StagedCommitState,
     commit_update_leaf_node: Option<LeafNode>,
+    // The digest of the PrivateMessage the commit was sent in, used to detect
+    // the commit when it is delivered again after it was merged.
+    #[serde(default)]
+    commit_digest: Option<Vec<u8>>,
 }
 
 impl StagedCommit {
+    /// Overwrite the secrets of the new epoch with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        if let StagedCommitState::GroupMember(state) = &mut self.state {
+            state.group_epoch_secrets.zeroize();
+            state.message_secrets.zeroize();
+        }
+    }
+
     /// Create a new [`StagedCommit`] from the provisional group state created
     /// during the commit process.
     pub(crate) fn new(
@@ -561,9 +587,37 @@ impl StagedCommit {
             staged_proposal_queue,
             state,
             commit_update_leaf_node,
+            commit_digest: None,
         }
     }
 
+    /// Sets the digest of the PrivateMessage the commit was sent in.
+    pub(crate) fn set_commit_digest(&mut self, commit_digest: Vec<u8>) {
+        self.commit_digest = Some(commit_digest);
+    }
+
+    /// Returns the digest of the PrivateMessage the commit was sent in, if it
+    /// was recorded.
+    pub(crate) fn commit_digest(&self) -> Option<&[u8]> {
+        self.commit_digest.as_deref()
+    }
+
+    /// Returns all proposals that are covered by the Commit message, inline
+    /// and by reference, as an iterator over [QueuedProposal] in the order in
+    /// which they are applied to the group: ExternalInit,
+    /// GroupContextExtensions, Update, Remove, Add, PreSharedKey, ReInit and
+    /// AppAck proposals. Proposals of the same type are in the order of the
+    /// Commit message.
+    ///
+    /// Each [QueuedProposal] contains the sender and the [`ProposalRef`] of the
+    /// proposal and whether it was included by reference.
+    ///
+    /// [`ProposalRef`]: crate::ciphersuite::hash_ref::ProposalRef
+    pub fn queued_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
+        self.staged_proposal_queue
+            .queued_proposals_in_application_order()
+    }
+
     /// Returns the Add proposals that are covered by the Commit message as in iterator over [QueuedAddProposal].
     pub fn add_proposals(&self) -> impl Iterator<Item = QueuedAddProposal> {
         self.staged_proposal_queue.add_proposals()
@@ -584,6 +638,17 @@ impl StagedCommit {
         self.staged_proposal_queue.psk_proposals()
     }
 
+    /// Returns the ReInit proposal that is covered by the Commit message, if
+    /// there is one.
+    pub fn reinit_proposal(&self) -> Option<&ReInitProposal> {
+        self.staged_proposal_queue
+            .filtered_by_type(ProposalType::Reinit)
+            .find_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::ReInit(reinit_proposal) => Some(reinit_proposal),
+                _ => None,
+            })
+    }
+
     /// Returns an optional leaf node from the Commit's update path.
     /// A leaf node is returned for full and empty Commits, but not for partial Commits.
     pub fn commit_update_key_package(&self) -> Option<&LeafNode> {
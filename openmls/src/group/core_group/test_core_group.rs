@@ -11,8 +11,8 @@ use crate::{
     framing::*,
     group::{errors::*, *},
     key_packages::*,
-    messages::*,
-    schedule::psk::*,
+    messages::{proposals::*, *},
+    schedule::{errors::PskError, psk::*},
     test_utils::*,
     treesync::errors::ApplyUpdatePathError,
     versions::ProtocolVersion,
@@ -88,6 +88,7 @@ fn test_failed_groupinfo_decryption(
 
     let group_info_tbs = {
         let group_context = GroupContext::new(
+            ProtocolVersion::default(),
             ciphersuite,
             group_id,
             epoch,
@@ -469,6 +470,121 @@ fn test_psks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
         .expect("An unexpected error occurred.");
 }
 
+// Test that external PSKs stored by their PSK ID are injected when committing
+// and when joining from a Welcome message.
+#[apply(ciphersuites_and_backends)]
+fn test_external_psk_store(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let framing_parameters = FramingParameters::new(&[], WireFormat::PublicMessage);
+
+    let alice_credential_bundle = CredentialBundle::new(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential_bundle = CredentialBundle::new(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_key_package_bundle =
+        KeyPackageBundle::new(backend, ciphersuite, &bob_credential_bundle);
+
+    // Store the PSK by its PSK ID only, i.e. independent of the nonce.
+    let external_psk = ExternalPsk::new(vec![4u8, 5, 6]);
+    external_psk
+        .write_to_key_store(backend, &[7u8; 32])
+        .expect("Could not store PSK.");
+    let preshared_key_id = PreSharedKeyId::new(
+        ciphersuite,
+        backend.rand(),
+        Psk::External(external_psk.clone()),
+    )
+    .expect("An unexpected error occured.");
+
+    let mut alice_group = CoreGroup::builder(
+        GroupId::random(backend),
+        config::CryptoConfig::with_default_version(ciphersuite),
+    )
+    .build(&alice_credential_bundle, backend)
+    .expect("Error creating group.");
+
+    // === Alice adds Bob and injects the PSK ===
+    let psk_proposal = alice_group
+        .create_presharedkey_proposal(
+            framing_parameters,
+            &alice_credential_bundle,
+            preshared_key_id.clone(),
+            backend,
+        )
+        .expect("Could not create PSK proposal");
+    let bob_add_proposal = alice_group
+        .create_add_proposal(
+            framing_parameters,
+            &alice_credential_bundle,
+            bob_key_package_bundle.key_package().clone(),
+            backend,
+        )
+        .expect("Could not create proposal");
+    let mut proposal_store = ProposalStore::from_queued_proposal(
+        QueuedProposal::from_authenticated_content(ciphersuite, backend, bob_add_proposal)
+            .expect("Could not create QueuedProposal."),
+    );
+    proposal_store.add(
+        QueuedProposal::from_authenticated_content(ciphersuite, backend, psk_proposal)
+            .expect("Could not create QueuedProposal."),
+    );
+    let params = CreateCommitParams::builder()
+        .framing_parameters(framing_parameters)
+        .credential_bundle(&alice_credential_bundle)
+        .proposal_store(&proposal_store)
+        .force_self_update(false)
+        .build();
+    let create_commit_result = alice_group
+        .create_commit(params, backend)
+        .expect("Error creating commit");
+    alice_group
+        .merge_commit(backend, create_commit_result.staged_commit)
+        .expect("error merging pending commit");
+
+    // === Bob joins with the PSK from the key store ===
+    let group_bob = CoreGroup::new_from_welcome(
+        create_commit_result
+            .welcome_option
+            .expect("An unexpected error occurred."),
+        Some(alice_group.treesync().export_nodes()),
+        bob_key_package_bundle,
+        backend,
+    )
+    .expect("Could not create new group from Welcome");
+    assert_eq!(
+        group_bob.export_secret(backend, "test", &[], 32),
+        alice_group.export_secret(backend, "test", &[], 32)
+    );
+
+    // === The PSK can't be injected anymore once it is deleted ===
+    backend
+        .key_store()
+        .delete_psk(external_psk.psk_id())
+        .expect("Could not delete PSK.");
+    let proposal_store = ProposalStore::new();
+    let params = CreateCommitParams::builder()
+        .framing_parameters(framing_parameters)
+        .credential_bundle(&alice_credential_bundle)
+        .proposal_store(&proposal_store)
+        .inline_proposals(vec![Proposal::PreSharedKey(PreSharedKeyProposal::new(
+            preshared_key_id,
+        ))])
+        .build();
+    assert!(matches!(
+        alice_group.create_commit(params, backend),
+        Err(CreateCommitError::PskError(PskError::KeyNotFound))
+    ));
+}
+
 // Test several scenarios when PSKs are used in a group
 #[apply(ciphersuites_and_backends)]
 fn test_staged_commit_creation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
@@ -11,7 +11,7 @@ fn build_create_commit_params(backend: &impl OpenMlsCryptoProvider) {
     let framing_parameters: FramingParameters =
         FramingParameters::new(&[1, 2, 3], WireFormat::PrivateMessage);
     let credential_bundle: &CredentialBundle = &CredentialBundle::new(
-        vec![4, 5, 6],
+        vec![4, 5, 6].into(),
         CredentialType::Basic,
         SignatureScheme::ED25519,
         backend,
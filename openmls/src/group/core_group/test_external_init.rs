@@ -111,7 +111,7 @@ fn test_external_init(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
         .proposal_store(&proposal_store)
         .build();
     let (mut group_charly, create_commit_result) =
-        CoreGroup::join_by_external_commit(backend, params, None, verifiable_group_info)
+        CoreGroup::join_by_external_commit(backend, params, None, verifiable_group_info, &[])
             .expect("Error initializing group externally.");
 
     // Have alice and bob process the commit resulting from external init.
@@ -188,6 +188,7 @@ fn test_external_init(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
         params,
         Some(&nodes_option),
         verifiable_group_info,
+        &[],
     )
     .expect("Error initializing group externally.");
 
@@ -292,6 +293,7 @@ fn test_external_init_single_member_group(
         params,
         Some(&nodes_option),
         verifiable_group_info,
+        &[],
     )
     .expect("Error initializing group externally.");
 
@@ -410,7 +412,7 @@ fn test_external_init_broken_signature(
         .build();
     assert_eq!(
         ExternalCommitError::InvalidGroupInfoSignature,
-        CoreGroup::join_by_external_commit(backend, params, None, verifiable_group_info)
+        CoreGroup::join_by_external_commit(backend, params, None, verifiable_group_info, &[])
             .expect_err("Signature was corrupted. This should have failed.")
     );
 }
@@ -19,6 +19,7 @@ use crate::{
     },
     key_packages::KeyPackageBundle,
     messages::proposals::{AddProposal, Proposal, ProposalOrRef, ProposalType},
+    versions::ProtocolVersion,
 };
 
 fn setup_client(
@@ -57,6 +58,7 @@ fn proposal_queue_functions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryp
     assert!(alice_update_key_package.verify(backend).is_ok());
 
     let group_context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         GroupId::random(backend),
         0,
@@ -136,7 +138,7 @@ fn proposal_queue_functions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryp
         ciphersuite,
         backend,
         Sender::build_member(LeafNodeIndex::new(1)),
-        &proposal_store,
+        proposal_store.proposals(),
         &[],
         LeafNodeIndex::new(0),
     )
@@ -183,6 +185,7 @@ fn proposal_queue_order(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     assert!(alice_update_key_package.verify(backend).is_ok());
 
     let group_context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         GroupId::random(backend),
         0,
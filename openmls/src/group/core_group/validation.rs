@@ -104,11 +104,18 @@ impl CoreGroup {
     ///  - ValSem103
     ///  - ValSem104
     ///  - ValSem106
+    ///
+    /// ValSem100 and ValSem103 are skipped if the group allows duplicate
+    /// identities, see [`CoreGroup::set_allow_duplicate_identities()`].
+    ///
+    /// Additionally, it checks that the added parties are not banned by the
+    /// [`BanListExtension`](crate::extensions::BanListExtension) of the group.
     pub(crate) fn validate_add_proposals(
         &self,
         proposal_queue: &ProposalQueue,
     ) -> Result<(), ProposalValidationError> {
         let add_proposals = proposal_queue.add_proposals();
+        let ban_list = self.group_context_extensions().ban_list();
 
         let mut identity_set = HashSet::new();
         let mut signature_key_set = HashSet::new();
@@ -121,9 +128,20 @@ impl CoreGroup {
                 .leaf_node()
                 .credential()
                 .identity()
-                .to_vec();
+                .clone();
+            if ban_list.map_or(false, |ban_list| {
+                ban_list.bans(
+                    add_proposal
+                        .add_proposal()
+                        .key_package()
+                        .leaf_node()
+                        .credential(),
+                )
+            }) {
+                return Err(ProposalValidationError::BannedAddProposal);
+            }
             // ValSem100
-            if !identity_set.insert(identity) {
+            if !identity_set.insert(identity) && !self.allow_duplicate_identities {
                 return Err(ProposalValidationError::DuplicateIdentityAddProposal);
             }
             let signature_key = add_proposal
@@ -217,13 +235,17 @@ impl CoreGroup {
             identity,
             encryption_key,
             signature_key,
+            ..
         } in self.treesync().full_leave_members()
         {
             let has_remove_proposal = proposal_queue
                 .remove_proposals()
                 .any(|p| p.remove_proposal().removed == index);
             // ValSem103
-            if identity_set.contains(&identity) && !has_remove_proposal {
+            if identity_set.contains(&identity)
+                && !has_remove_proposal
+                && !self.allow_duplicate_identities
+            {
                 return Err(ProposalValidationError::ExistingIdentityAddProposal);
             }
             // ValSem104
@@ -256,8 +278,9 @@ impl CoreGroup {
                 return Err(ProposalValidationError::DuplicateMemberRemoval);
             }
 
-            // TODO: ValSem108
-            if !self.treesync().is_leaf_in_tree(removed) {
+            // ValSem108
+            if !self.treesync().is_leaf_in_tree(removed) || self.treesync().leaf(removed).is_none()
+            {
                 return Err(ProposalValidationError::UnknownMemberRemoval);
             }
         }
@@ -265,6 +288,28 @@ impl CoreGroup {
         Ok(())
     }
 
+    /// Validate that the group doesn't have more members than allowed by the
+    /// [`MaxMembersExtension`](crate::extensions::MaxMembersExtension) of the
+    /// group after the proposals in the queue were applied. If `new_member_commit`
+    /// is `true`, the committer joins the group with an External Commit.
+    pub(crate) fn validate_max_members(
+        &self,
+        proposal_queue: &ProposalQueue,
+        new_member_commit: bool,
+    ) -> Result<(), ProposalValidationError> {
+        let max_members = match self.group_context_extensions().max_members() {
+            Some(max_members) => max_members.max_members() as usize,
+            None => return Ok(()),
+        };
+        let added = proposal_queue.add_proposals().count() + usize::from(new_member_commit);
+        let removed = proposal_queue.remove_proposals().count();
+        if self.treesync().full_leaves().len() + added > max_members + removed {
+            return Err(ProposalValidationError::TooManyMembers);
+        }
+
+        Ok(())
+    }
+
     /// Validate Update proposals. This function implements the following checks:
     ///  - ValSem109
     ///  - ValSem110
@@ -350,7 +395,7 @@ impl CoreGroup {
         }) = members.find(|Member { index, .. }| index == &sender)
         {
             // ValSem109
-            if leaf_node.credential().identity() != identity {
+            if leaf_node.credential().identity() != &identity {
                 return Err(ProposalValidationError::UpdateProposalIdentityMismatch);
             }
             // ValSem110
@@ -398,6 +443,15 @@ impl CoreGroup {
             return Err(ExternalCommitValidationError::InvalidInlineProposals);
         }
 
+        // The joiner must not be banned from the group.
+        if let (Some(ban_list), Some(new_leaf)) =
+            (self.group_context_extensions().ban_list(), path_leaf_node)
+        {
+            if ban_list.bans(new_leaf.credential()) {
+                return Err(ExternalCommitValidationError::BannedJoiner);
+            }
+        }
+
         let remove_proposals = proposal_queue.filtered_by_type(ProposalType::Remove);
         for proposal in remove_proposals {
             if proposal.proposal_or_ref_type() == ProposalOrRefType::Proposal {
@@ -4,7 +4,7 @@
 
 use crate::{
     ciphersuite::signable::SignatureError,
-    error::LibraryError,
+    error::{error_codes, LibraryError},
     extensions::errors::{ExtensionError, InvalidExtensionError},
     framing::errors::{MessageDecryptionError, SenderError},
     key_packages::errors::{KeyPackageExtensionSupportError, KeyPackageNewError},
@@ -80,8 +80,68 @@ pub enum WelcomeError<KeyStoreError> {
     /// This error indicates the public tree is invalid. See [`PublicTreeError`] for more details.
     #[error(transparent)]
     PublicTreeError(#[from] PublicTreeError),
+    /// The group has more members than the maximum number of members.
+    #[error("The group has more members than the maximum number of members.")]
+    TooManyMembers,
+    /// A verifiable credential of a member could not be verified.
+    #[cfg(feature = "verifiable-credentials")]
+    #[error("A verifiable credential of a member could not be verified.")]
+    InvalidVerifiableCredential,
+    /// The token binding of a member could not be verified.
+    #[cfg(feature = "token-bound-credentials")]
+    #[error("The token binding of a member could not be verified.")]
+    InvalidTokenBinding,
+    /// The ciphersuite of the group is not approved in FIPS mode.
+    #[cfg(feature = "fips")]
+    #[error("The ciphersuite of the group is not approved in FIPS mode.")]
+    NotFipsApproved,
+    /// The ciphersuite of the group is not allowed by the configuration.
+    #[error("The ciphersuite of the group is not allowed by the configuration.")]
+    CiphersuiteNotAllowed,
+    /// The ciphersuite or the protocol version of the group is weaker than
+    /// the configured minimum.
+    #[error("The ciphersuite or the protocol version of the group is weaker than the configured minimum.")]
+    DowngradeDetected,
+    /// See [`PartialTreeError`] for more details.
+    #[cfg(feature = "partial-tree")]
+    #[error(transparent)]
+    PartialTreeError(#[from] PartialTreeError),
 }
 
+error_codes!(WelcomeError<KeyStoreError>: 3300 {
+    LibraryError = 1,
+    CiphersuiteMismatch = 2,
+    GroupInfoCiphersuiteMismatch = 3,
+    JoinerSecretNotFound = 4,
+    MissingRatchetTree = 5,
+    ConfirmationTagMismatch = 6,
+    InvalidGroupInfoSignature = 7,
+    GroupInfoDecryptionFailure = 8,
+    UnsupportedMlsVersion = 9,
+    UnsupportedCapability = 10,
+    UnknownSender = 11,
+    MalformedWelcomeMessage = 12,
+    UnableToDecrypt = 13,
+    UnsupportedExtensions = 14,
+    PskTooManyKeys = 15,
+    PskNotFound = 16,
+    NoMatchingEncryptionKey = 17,
+    NoMatchingKeyPackage = 18,
+    KeyStoreError = 19,
+    PublicTreeError = 20,
+    TooManyMembers = 21,
+    #[cfg(feature = "verifiable-credentials")]
+    InvalidVerifiableCredential = 22,
+    #[cfg(feature = "token-bound-credentials")]
+    InvalidTokenBinding = 23,
+    #[cfg(feature = "fips")]
+    NotFipsApproved = 24,
+    CiphersuiteNotAllowed = 25,
+    DowngradeDetected = 26,
+    #[cfg(feature = "partial-tree")]
+    PartialTreeError = 27,
+});
+
 /// External Commit error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ExternalCommitError {
@@ -112,11 +172,47 @@ pub enum ExternalCommitError {
     /// Error creating external commit.
     #[error("Error creating external commit.")]
     CommitError,
+    /// Only PreSharedKey proposals can be added to an external commit.
+    #[error("Only PreSharedKey proposals can be added to an external commit.")]
+    InvalidInlineProposal,
     /// This error indicates the public tree is invalid. See [`PublicTreeError`] for more details.
     #[error(transparent)]
     PublicTreeError(#[from] PublicTreeError),
+    /// The ciphersuite of the group is not approved in FIPS mode.
+    #[cfg(feature = "fips")]
+    #[error("The ciphersuite of the group is not approved in FIPS mode.")]
+    NotFipsApproved,
+    /// The ciphersuite of the group is not allowed by the configuration.
+    #[error("The ciphersuite of the group is not allowed by the configuration.")]
+    CiphersuiteNotAllowed,
+    /// The ciphersuite or the protocol version of the group is weaker than
+    /// the configured minimum.
+    #[error("The ciphersuite or the protocol version of the group is weaker than the configured minimum.")]
+    DowngradeDetected,
+    /// The group has already reached the maximum number of members.
+    #[error("The group has already reached the maximum number of members.")]
+    TooManyMembers,
 }
 
+error_codes!(ExternalCommitError: 3400 {
+    LibraryError = 1,
+    MissingRatchetTree = 2,
+    MissingExternalPub = 3,
+    TreeHashMismatch = 4,
+    UnsupportedMlsVersion = 5,
+    UnsupportedCiphersuite = 6,
+    UnknownSender = 7,
+    InvalidGroupInfoSignature = 8,
+    CommitError = 9,
+    InvalidInlineProposal = 10,
+    PublicTreeError = 11,
+    #[cfg(feature = "fips")]
+    NotFipsApproved = 12,
+    CiphersuiteNotAllowed = 13,
+    DowngradeDetected = 14,
+    TooManyMembers = 15,
+});
+
 /// Stage Commit error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum StageCommitError {
@@ -182,6 +278,29 @@ pub enum StageCommitError {
     MissingDecryptionKey,
 }
 
+error_codes!(StageCommitError: 3500 {
+    LibraryError = 1,
+    EpochMismatch = 2,
+    OwnCommit = 3,
+    WrongPlaintextContentType = 4,
+    PathLeafNodeVerificationFailure = 5,
+    RequiredPathNotFound = 6,
+    ConfirmationTagMissing = 7,
+    ConfirmationTagMismatch = 8,
+    AttemptedSelfRemoval = 9,
+    MissingProposal = 10,
+    OwnKeyNotFound = 11,
+    InconsistentSenderIndex = 12,
+    SenderTypeExternal = 13,
+    SenderTypeNewMemberProposal = 14,
+    TooManyNewMembers = 15,
+    ProposalValidationError = 16,
+    PskError = 17,
+    ExternalCommitValidation = 18,
+    UpdatePathError = 19,
+    MissingDecryptionKey = 20,
+});
+
 /// Create commit error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum CreateCommitError<KeyStoreError> {
@@ -220,6 +339,20 @@ pub enum CreateCommitError<KeyStoreError> {
     InvalidExtensionError(#[from] InvalidExtensionError),
 }
 
+error_codes!(CreateCommitError<KeyStoreError>: 3600 {
+    LibraryError = 1,
+    OwnKeyNotFound = 2,
+    CannotRemoveSelf = 3,
+    MissingProposal = 4,
+    WrongProposalSenderType = 5,
+    PskError = 6,
+    ProposalValidationError = 7,
+    KeyStoreError = 8,
+    KeyPackageGenerationError = 9,
+    SignatureError = 10,
+    InvalidExtensionError = 11,
+});
+
 /// Validation error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ValidationError {
@@ -271,8 +404,35 @@ pub enum ValidationError {
     /// The message is from an epoch too far in the past.
     #[error("The message is from an epoch too far in the past.")]
     NoPastEpochData,
+    /// The external sender is not listed in the external senders extension of the group.
+    #[error("The external sender is not listed in the external senders extension of the group.")]
+    UnknownExternalSender,
+    /// The PublicMessage is not a proposal an external sender can send despite the sender being of type [External](crate::prelude::Sender::External).
+    #[error("The PublicMessage is not a proposal an external sender can send despite the sender being of type External.")]
+    NotAnExternalSenderProposal,
 }
 
+error_codes!(ValidationError: 3700 {
+    LibraryError = 1,
+    WrongGroupId = 2,
+    WrongEpoch = 3,
+    NotACommit = 4,
+    NotAnExternalAddProposal = 5,
+    NoPath = 6,
+    UnencryptedApplicationMessage = 7,
+    UnknownMember = 8,
+    MissingMembershipTag = 9,
+    InvalidMembershipTag = 10,
+    MissingConfirmationTag = 11,
+    WrongWireFormat = 12,
+    InvalidSignature = 13,
+    NonMemberApplicationMessage = 14,
+    UnableToDecrypt = 15,
+    NoPastEpochData = 16,
+    UnknownExternalSender = 17,
+    NotAnExternalSenderProposal = 18,
+});
+
 /// Proposal validation error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ProposalValidationError {
@@ -327,8 +487,38 @@ pub enum ProposalValidationError {
     /// The capabilities of the add proposal are insufficient for this group.
     #[error("The capabilities of the add proposal are insufficient for this group.")]
     InsufficientCapabilities,
+    /// The signature key or the identity of the add proposal is banned from
+    /// the group.
+    #[error("The signature key or the identity of the add proposal is banned from the group.")]
+    BannedAddProposal,
+    /// The group would have more members than allowed by its max members
+    /// extension.
+    #[error("The group would have more members than allowed by its max members extension.")]
+    TooManyMembers,
 }
 
+error_codes!(ProposalValidationError: 3800 {
+    LibraryError = 1,
+    UnknownMember = 2,
+    DuplicateIdentityAddProposal = 3,
+    DuplicateSignatureKeyAddProposal = 4,
+    DuplicatePublicKeyAddProposal = 5,
+    ExistingIdentityAddProposal = 6,
+    ExistingSignatureKeyAddProposal = 7,
+    ExistingPublicKeyAddProposal = 8,
+    InitEncryptionKeyCollision = 9,
+    UpdateProposalIdentityMismatch = 10,
+    ExistingSignatureKeyUpdateProposal = 11,
+    ExistingPublicKeyUpdateProposal = 12,
+    DuplicateMemberRemoval = 13,
+    UnknownMemberRemoval = 14,
+    UpdateFromNonMember = 15,
+    CommitterIncludedOwnUpdate = 16,
+    InsufficientCapabilities = 17,
+    BannedAddProposal = 18,
+    TooManyMembers = 19,
+});
+
 /// External Commit validaton error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ExternalCommitValidationError {
@@ -361,8 +551,29 @@ pub enum ExternalCommitValidationError {
     /// External commit contains referenced proposal
     #[error("Found a referenced proposal in an External Commit.")]
     ReferencedProposal,
+    /// The group requires an external PSK, but the External Commit doesn't contain one.
+    #[error("The group requires an external PSK, but the External Commit doesn't contain one.")]
+    MissingExternalPsk,
+    /// The signature key or the identity of the joiner is banned from the
+    /// group.
+    #[error("The signature key or the identity of the joiner is banned from the group.")]
+    BannedJoiner,
 }
 
+error_codes!(ExternalCommitValidationError: 3900 {
+    LibraryError = 1,
+    NoExternalInitProposals = 2,
+    MultipleExternalInitProposals = 3,
+    InvalidInlineProposals = 4,
+    MultipleRemoveProposals = 5,
+    InvalidRemoveProposal = 6,
+    NoPath = 7,
+    UnknownMemberRemoval = 8,
+    ReferencedProposal = 9,
+    MissingExternalPsk = 10,
+    BannedJoiner = 11,
+});
+
 // === Crate errors ===
 
 /// Create add proposal error
@@ -449,6 +660,9 @@ pub(crate) enum CoreGroupBuildError<KeyStoreError> {
     /// Unsupported extension type in required capabilities.
     #[error("Unsupported extension type in required capabilities.")]
     UnsupportedExtensionType,
+    /// The requested MLS version is not supported.
+    #[error("The requested MLS version is not supported.")]
+    UnsupportedMlsVersion,
     /// See [`PskError`] for more details.
     #[error(transparent)]
     PskError(#[from] PskError),
@@ -497,3 +711,72 @@ pub enum MergeCommitError<KeyStoreError> {
     #[error("Error accessing the key store.")]
     KeyStoreError(KeyStoreError),
 }
+
+error_codes!(MergeCommitError<KeyStoreError>: 4000 {
+    LibraryError = 1,
+    KeyStoreError = 2,
+});
+
+/// Public group error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum PublicGroupError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// No ratchet tree available to build initial tree.
+    #[error("No ratchet tree available to build initial tree.")]
+    MissingRatchetTree,
+    /// The computed tree hash does not match the one in the GroupInfo.
+    #[error("The computed tree hash does not match the one in the GroupInfo.")]
+    TreeHashMismatch,
+    /// Sender not found in tree.
+    #[error("Sender not found in tree.")]
+    UnknownSender,
+    /// The signature over the given group info is invalid.
+    #[error("The signature over the given group info is invalid.")]
+    InvalidGroupInfoSignature,
+    /// The GroupInfo doesn't belong to the current epoch of the group.
+    #[error("The GroupInfo doesn't belong to the current epoch of the group.")]
+    GroupInfoMismatch,
+    /// This error indicates the public tree is invalid. See [`PublicTreeError`] for more details.
+    #[error(transparent)]
+    PublicTreeError(#[from] PublicTreeError),
+    /// See [`ValidationError`] for more details.
+    #[error(transparent)]
+    ValidationError(#[from] ValidationError),
+    /// See [`ProposalValidationError`] for more details.
+    #[error(transparent)]
+    ProposalValidationError(#[from] ProposalValidationError),
+    /// See [`StageCommitError`] for more details.
+    #[error(transparent)]
+    StageCommitError(#[from] StageCommitError),
+}
+
+error_codes!(PublicGroupError: 5600 {
+    LibraryError = 1,
+    MissingRatchetTree = 2,
+    TreeHashMismatch = 3,
+    UnknownSender = 4,
+    InvalidGroupInfoSignature = 5,
+    GroupInfoMismatch = 6,
+    PublicTreeError = 8,
+    ValidationError = 9,
+    ProposalValidationError = 10,
+    StageCommitError = 11,
+});
+
+/// Group ID error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum GroupIdError {
+    /// The group ID is shorter than `GroupId::MIN_LENGTH`.
+    #[error("The group ID is shorter than `GroupId::MIN_LENGTH`.")]
+    TooShort,
+    /// The group ID is longer than `GroupId::MAX_LENGTH`.
+    #[error("The group ID is longer than `GroupId::MAX_LENGTH`.")]
+    TooLong,
+}
+
+error_codes!(GroupIdError: 7100 {
+    TooShort = 1,
+    TooLong = 2,
+});
@@ -11,12 +11,16 @@ use super::*;
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupContext {
     protocol_version: ProtocolVersion,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_ciphersuite))]
     ciphersuite: Ciphersuite,
     group_id: GroupId,
     epoch: GroupEpoch,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     tree_hash: VLBytes,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     confirmed_transcript_hash: VLBytes,
     extensions: Extensions,
 }
@@ -39,6 +43,7 @@ impl GroupContext {
 impl GroupContext {
     /// Create a new group context
     pub(crate) fn new(
+        protocol_version: ProtocolVersion,
         ciphersuite: Ciphersuite,
         group_id: GroupId,
         epoch: impl Into<GroupEpoch>,
@@ -48,7 +53,7 @@ impl GroupContext {
     ) -> Self {
         GroupContext {
             ciphersuite,
-            protocol_version: ProtocolVersion::Mls10,
+            protocol_version,
             group_id,
             epoch: epoch.into(),
             tree_hash: tree_hash.into(),
@@ -59,12 +64,14 @@ impl GroupContext {
 
     /// Create the `GroupContext` needed upon creation of a new group.
     pub(crate) fn create_initial_group_context(
+        protocol_version: ProtocolVersion,
         ciphersuite: Ciphersuite,
         group_id: GroupId,
         tree_hash: Vec<u8>,
         extensions: Extensions,
     ) -> Self {
         Self::new(
+            protocol_version,
             ciphersuite,
             group_id,
             0,
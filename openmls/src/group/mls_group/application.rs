@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use tls_codec::Serialize;
 
 use super::{errors::CreateMessageError, *};
@@ -6,6 +8,8 @@ impl MlsGroup {
     // === Application messages ===
 
     /// Creates an application message.
+    /// The AAD of the group (see [`MlsGroup::set_aad()`]) is used as the
+    /// message's authenticated data.
     /// Returns `CreateMessageError::MlsGroupStateError::UseAfterEviction`
     /// if the member is no longer part of the group.
     /// Returns `CreateMessageError::MlsGroupStateError::PendingProposal` if pending proposals
@@ -15,6 +19,25 @@ impl MlsGroup {
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
         message: &[u8],
+    ) -> Result<MlsMessageOut, CreateMessageError> {
+        let aad = self.aad.clone();
+        self.create_message_with_aad(backend, message, &aad)
+    }
+
+    /// Creates an application message with the given authenticated data
+    /// `aad`, e.g. a message ID or a reference to the message that is replied
+    /// to. The `aad` is only used for this message and the AAD of the group
+    /// is ignored.
+    ///
+    /// The `aad` is authenticated, but not encrypted. Receivers can read it
+    /// via [`ProcessedMessage::authenticated_data()`].
+    ///
+    /// Returns the same errors as [`MlsGroup::create_message()`].
+    pub fn create_message_with_aad(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &[u8],
+        aad: &[u8],
     ) -> Result<MlsMessageOut, CreateMessageError> {
         if !self.is_active() {
             return Err(CreateMessageError::GroupStateError(
@@ -41,11 +64,17 @@ impl MlsGroup {
             )
             .ok_or(CreateMessageError::NoMatchingCredentialBundle)?;
 
+        let message = if self.uses_application_compression() {
+            Cow::Owned(compression::compress(message)?)
+        } else {
+            Cow::Borrowed(message)
+        };
+
         let ciphertext = self
             .group
             .create_application_message(
-                &self.aad,
-                message,
+                aad,
+                &message,
                 &credential_bundle,
                 self.configuration().padding_size(),
                 backend,
@@ -54,7 +83,7 @@ impl MlsGroup {
             .map_err(|_| LibraryError::custom("Malformed plaintext"))?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::MessageCreated);
 
         Ok(MlsMessageOut::from_private_message(
             ciphertext,
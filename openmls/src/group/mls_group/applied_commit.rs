@@ -0,0 +1,102 @@
+//! Detection of commits that were already merged, see
+//! [`MlsGroupConfig::detect_merged_commits()`] and
+//! [`ProcessMessageError::AlreadyProcessed`].
+
+use openmls_traits::crypto::OpenMlsCrypto;
+use tls_codec::Serialize as TlsSerializeTrait;
+
+use super::*;
+
+impl MlsGroup {
+    /// Returns `true` if the `message` is the commit that led to the current
+    /// epoch and the group detects merged commits.
+    ///
+    /// The confirmation tag of a [`PublicMessage`] is compared to the one of
+    /// the current epoch. The content of a [`PrivateMessage`] can't be
+    /// decrypted again, since its message key was deleted when it was first
+    /// decrypted or encrypted. It is compared to the digest of the
+    /// [`PrivateMessage`] that was recorded when the commit was processed or
+    /// created instead. Other commits of the previous epoch are not detected,
+    /// such that they fail with a `WrongEpoch` error.
+    pub(super) fn is_merged_commit(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &ProtocolMessage,
+    ) -> Result<bool, LibraryError> {
+        if !self.configuration().detect_merged_commits()
+            || message.content_type() != ContentType::Commit
+            || message.group_id() != self.group_id()
+            || message.epoch().as_u64().checked_add(1) != Some(self.epoch().as_u64())
+        {
+            return Ok(false);
+        }
+        match message.body() {
+            ProtocolMessageBody::PublicMessage(public_message) => {
+                let confirmation_tag = self
+                    .group
+                    .message_secrets()
+                    .confirmation_key()
+                    .tag(backend, self.group.context().confirmed_transcript_hash())
+                    .map_err(LibraryError::unexpected_crypto_error)?;
+                Ok(public_message.confirmation_tag() == Some(&confirmation_tag))
+            }
+            ProtocolMessageBody::PrivateMessage(private_message) => {
+                let digest = self.private_message_digest(backend, private_message)?;
+                Ok(self.merged_commit_digest.as_ref() == Some(&digest))
+            }
+        }
+    }
+
+    /// Returns the digest of the `message` if it is a commit sent as a
+    /// [`PrivateMessage`] and the group detects merged commits.
+    pub(super) fn private_commit_digest(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &ProtocolMessage,
+    ) -> Result<Option<Vec<u8>>, LibraryError> {
+        match message.body() {
+            ProtocolMessageBody::PrivateMessage(private_message)
+                if self.configuration().detect_merged_commits()
+                    && private_message.content_type() == ContentType::Commit =>
+            {
+                self.private_message_digest(backend, private_message)
+                    .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Sets the own `staged_commit` as the pending commit. If the commit is
+    /// sent as a [`PrivateMessage`] in the `mls_message`, its digest is
+    /// recorded to detect the commit after it was merged.
+    pub(super) fn set_own_pending_commit(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        mut staged_commit: StagedCommit,
+        mls_message: &MlsMessageOut,
+    ) -> Result<(), LibraryError> {
+        if let MlsMessageOutBody::PrivateMessage(private_message) = &mls_message.body {
+            if self.configuration().detect_merged_commits() {
+                staged_commit
+                    .set_commit_digest(self.private_message_digest(backend, private_message)?);
+            }
+        }
+        self.group_state =
+            MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(staged_commit)));
+        Ok(())
+    }
+
+    fn private_message_digest(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        private_message: &PrivateMessage,
+    ) -> Result<Vec<u8>, LibraryError> {
+        let serialized_message = private_message
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        backend
+            .crypto()
+            .hash(self.ciphersuite().hash_algorithm(), &serialized_message)
+            .map_err(LibraryError::unexpected_crypto_error)
+    }
+}
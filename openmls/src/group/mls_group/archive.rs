@@ -0,0 +1,109 @@
+//! Archiving of groups, see [`MlsGroup::archive()`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::{ExportedSecret, ExporterSecret};
+
+use super::*;
+
+/// The archive of a group, see [`MlsGroup::archive()`].
+///
+/// The archive retains the public state of the group in its final epoch and
+/// its membership history, as well as the exporter secret of the final
+/// epoch, such that keys for stored message history can still be derived
+/// with [`ArchivedGroup::export_secret()`]. It contains no other secrets and
+/// can neither send nor receive messages.
+///
+/// The archive can be persisted with serde.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedGroup {
+    group_id: GroupId,
+    ciphersuite: Ciphersuite,
+    epoch: GroupEpoch,
+    ratchet_tree: Vec<Option<Node>>,
+    members: Vec<Member>,
+    membership_history: Vec<MembershipRecord>,
+    exporter_secret: ExporterSecret,
+}
+
+impl ArchivedGroup {
+    /// Returns the group ID.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the ciphersuite of the group.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+
+    /// Returns the final epoch of the group.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the public ratchet tree of the final epoch.
+    pub fn ratchet_tree(&self) -> &[Option<Node>] {
+        &self.ratchet_tree
+    }
+
+    /// Returns the members of the group in the final epoch.
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// Returns the membership history of the group, see
+    /// [`MlsGroup::membership_history()`].
+    pub fn membership_history(&self) -> &[MembershipRecord] {
+        &self.membership_history
+    }
+
+    /// Exports a secret from the final epoch, like
+    /// [`MlsGroup::export_secret()`] did before the group was archived.
+    /// Returns [`ExportSecretError::KeyLengthTooLong`] if the requested key
+    /// length is too long.
+    pub fn export_secret(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<ExportedSecret, ExportSecretError> {
+        if key_length > u16::MAX.into() {
+            return Err(ExportSecretError::KeyLengthTooLong);
+        }
+        self.exporter_secret
+            .derive_exported_secret(self.ciphersuite, backend, label, context, key_length)
+            .map_err(|e| LibraryError::unexpected_crypto_error(e).into())
+    }
+}
+
+impl MlsGroup {
+    /// Freezes the group into an [`ArchivedGroup`], e.g. to retain the
+    /// history of a conversation that ended for compliance reasons.
+    ///
+    /// The archive retains the public ratchet tree, the members and the
+    /// membership history of the current epoch, as well as its exporter
+    /// secret. Afterwards, all other secrets of the group are destroyed with
+    /// [`MlsGroup::wipe()`], such that the group is inactive and rejects
+    /// further messages. A pending commit is discarded.
+    ///
+    /// Archiving a group that was already wiped yields an archive whose
+    /// exporter secret was overwritten with zeros.
+    pub fn archive<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<ArchivedGroup, WipeError<KeyStore::Error>> {
+        let archive = ArchivedGroup {
+            group_id: self.group_id().clone(),
+            ciphersuite: self.ciphersuite(),
+            epoch: self.epoch(),
+            ratchet_tree: self.export_ratchet_tree(),
+            members: self.members().collect(),
+            membership_history: self.membership_history.clone(),
+            exporter_secret: self.group.group_epoch_secrets().exporter_secret().clone(),
+        };
+        self.wipe(backend)?;
+        Ok(archive)
+    }
+}
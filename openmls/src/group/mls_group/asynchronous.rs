@@ -0,0 +1,590 @@
+//! Async variants of [`MlsGroup`] operations.
+//!
+//! These functions are meant for applications whose key store is
+//! asynchronous, e.g. because it is backed by a remote KMS or by WebCrypto and
+//! IndexedDB. They take an [`AsyncOpenMlsKeyStore`] next to the usual
+//! `backend`, whose crypto and randomness providers are used as is. The key
+//! store of the `backend` is never accessed.
+//!
+//! Every operation is split into three phases:
+//!
+//! 1. All key store values the operation needs are prefetched from the async
+//!    key store into an in-memory buffer.
+//! 2. The synchronous operation runs against the buffer. Writes and deletions
+//!    are recorded.
+//! 3. The recorded writes and deletions are flushed to the async key store in
+//!    the order in which they happened.
+//!
+//! The flush happens even if the operation fails, to mirror the behaviour of
+//! the synchronous functions, which write to the key store as they go.
+//!
+//! PSKs are only prefetched if they are referenced by proposals in the
+//! group's proposal store. Groups that rely on PSKs that are only referenced
+//! inline in a Commit or in a Welcome have to use the synchronous API.
+
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{PoisonError, RwLock},
+};
+
+use openmls_traits::{
+    async_key_store::AsyncOpenMlsKeyStore,
+    key_store::{FromKeyStoreValue, OpenMlsKeyStore, ToKeyStoreValue},
+};
+use tls_codec::Serialize;
+
+use crate::{
+    ciphersuite::signature::SignaturePublicKey,
+    group::errors::{CreateCommitError, MergeCommitError, WelcomeError},
+    key_packages::{last_resort_id, KEY_PACKAGE_INVENTORY_ID},
+    messages::GroupInfo,
+    schedule::psk::Psk,
+};
+
+use super::*;
+
+/// A key store write or deletion that has to be flushed to the async key
+/// store.
+enum KeyStoreOperation {
+    Store(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+    StoreEpochKeys {
+        identity: Vec<u8>,
+        group_id: Vec<u8>,
+        epoch: u64,
+        encryption_keys: Vec<Vec<u8>>,
+    },
+    DeleteEpochKeys {
+        identity: Vec<u8>,
+        group_id: Vec<u8>,
+        epoch: u64,
+    },
+}
+
+type EpochKeysIndex = (Vec<u8>, Vec<u8>, u64);
+
+/// An in-memory [`OpenMlsKeyStore`] that is filled from an
+/// [`AsyncOpenMlsKeyStore`] and records all changes, such that they can be
+/// flushed back later.
+pub(crate) struct BufferedKeyStore<KeyStoreError> {
+    values: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
+    epoch_keys: RwLock<HashMap<EpochKeysIndex, Vec<Vec<u8>>>>,
+    operations: RwLock<Vec<KeyStoreOperation>>,
+    _error: PhantomData<fn() -> KeyStoreError>,
+}
+
+impl<KeyStoreError> BufferedKeyStore<KeyStoreError> {
+    fn new() -> Self {
+        Self {
+            values: RwLock::new(HashMap::new()),
+            epoch_keys: RwLock::new(HashMap::new()),
+            operations: RwLock::new(Vec::new()),
+            _error: PhantomData,
+        }
+    }
+
+    /// Read the value for `k` from the `key_store` into the buffer.
+    ///
+    /// Returns the serialized value if one was found.
+    async fn prefetch<S: AsyncOpenMlsKeyStore<Error = KeyStoreError>>(
+        &self,
+        key_store: &S,
+        k: &[u8],
+    ) -> Option<Vec<u8>> {
+        let value = key_store.read(k).await?;
+        self.values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(k.to_vec(), value.clone());
+        Some(value)
+    }
+
+    /// Read the epoch keys for the given `identity`, `group_id` and `epoch`
+    /// from the `key_store` into the buffer.
+    async fn prefetch_epoch_keys<S: AsyncOpenMlsKeyStore<Error = KeyStoreError>>(
+        &self,
+        key_store: &S,
+        identity: &[u8],
+        group_id: &[u8],
+        epoch: u64,
+    ) {
+        let encryption_keys = key_store.read_epoch_keys(identity, group_id, epoch).await;
+        self.epoch_keys
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                (identity.to_vec(), group_id.to_vec(), epoch),
+                encryption_keys,
+            );
+    }
+
+    /// Write all recorded changes to the `key_store`.
+    ///
+    /// Returns an error if the `key_store` returns an error.
+    async fn flush<S: AsyncOpenMlsKeyStore<Error = KeyStoreError>>(
+        self,
+        key_store: &S,
+    ) -> Result<(), AsyncKeyStoreError<KeyStoreError>> {
+        let operations = self
+            .operations
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        for operation in operations {
+            match operation {
+                KeyStoreOperation::Store(k, v) => key_store.store(&k, v).await,
+                KeyStoreOperation::Delete(k) => key_store.delete(&k).await,
+                KeyStoreOperation::StoreEpochKeys {
+                    identity,
+                    group_id,
+                    epoch,
+                    encryption_keys,
+                } => {
+                    key_store
+                        .store_epoch_keys(&identity, &group_id, epoch, encryption_keys)
+                        .await
+                }
+                KeyStoreOperation::DeleteEpochKeys {
+                    identity,
+                    group_id,
+                    epoch,
+                } => {
+                    key_store
+                        .delete_epoch_keys(&identity, &group_id, epoch)
+                        .await
+                }
+            }
+            .map_err(AsyncKeyStoreError::KeyStoreError)?;
+        }
+        Ok(())
+    }
+
+    fn record(&self, operation: KeyStoreOperation) {
+        self.operations
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(operation);
+    }
+}
+
+impl<KeyStoreError> OpenMlsKeyStore for BufferedKeyStore<KeyStoreError>
+where
+    KeyStoreError: std::error::Error + PartialEq + 'static,
+{
+    type Error = AsyncKeyStoreError<KeyStoreError>;
+
+    fn read_epoch_keys<V: FromKeyStoreValue>(
+        &self,
+        identity: &[u8],
+        group_id: &[u8],
+        epoch: u64,
+    ) -> Vec<V> {
+        self.epoch_keys
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&(identity.to_vec(), group_id.to_vec(), epoch))
+            .map(|encryption_keys| {
+                encryption_keys
+                    .iter()
+                    .filter_map(|value| V::from_key_store_value(value).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn store_epoch_keys<V: ToKeyStoreValue>(
+        &self,
+        identity: &[u8],
+        group_id: &[u8],
+        epoch: u64,
+        encryption_keys: &[V],
+    ) -> Result<(), Self::Error> {
+        let encryption_keys = encryption_keys
+            .iter()
+            .map(|value| value.to_key_store_value())
+            .collect::<Result<Vec<Vec<u8>>, _>>()
+            .map_err(|_| AsyncKeyStoreError::SerializationError)?;
+        self.epoch_keys
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                (identity.to_vec(), group_id.to_vec(), epoch),
+                encryption_keys.clone(),
+            );
+        self.record(KeyStoreOperation::StoreEpochKeys {
+            identity: identity.to_vec(),
+            group_id: group_id.to_vec(),
+            epoch,
+            encryption_keys,
+        });
+        Ok(())
+    }
+
+    fn delete_epoch_keys(
+        &self,
+        identity: &[u8],
+        group_id: &[u8],
+        epoch: u64,
+    ) -> Result<(), Self::Error> {
+        self.epoch_keys
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&(identity.to_vec(), group_id.to_vec(), epoch));
+        self.record(KeyStoreOperation::DeleteEpochKeys {
+            identity: identity.to_vec(),
+            group_id: group_id.to_vec(),
+            epoch,
+        });
+        Ok(())
+    }
+
+    fn store<V: ToKeyStoreValue>(&self, k: &[u8], v: &V) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+    {
+        let value = v
+            .to_key_store_value()
+            .map_err(|_| AsyncKeyStoreError::SerializationError)?;
+        self.values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(k.to_vec(), value.clone());
+        self.record(KeyStoreOperation::Store(k.to_vec(), value));
+        Ok(())
+    }
+
+    fn read<V: FromKeyStoreValue>(&self, k: &[u8]) -> Option<V>
+    where
+        Self: Sized,
+    {
+        self.values
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(k)
+            .and_then(|value| V::from_key_store_value(value).ok())
+    }
+
+    fn delete(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(k);
+        self.record(KeyStoreOperation::Delete(k.to_vec()));
+        Ok(())
+    }
+}
+
+/// A backend that uses the crypto and randomness providers of another backend
+/// together with a [`BufferedKeyStore`].
+pub(crate) struct BufferedBackend<'a, Backend, KeyStoreError> {
+    backend: &'a Backend,
+    key_store: BufferedKeyStore<KeyStoreError>,
+}
+
+impl<'a, Backend, KeyStoreError> BufferedBackend<'a, Backend, KeyStoreError> {
+    pub(crate) fn new(backend: &'a Backend) -> Self {
+        Self {
+            backend,
+            key_store: BufferedKeyStore::new(),
+        }
+    }
+
+    /// Write all changes to the key store of this backend to the
+    /// `key_store`.
+    pub(crate) async fn flush<S: AsyncOpenMlsKeyStore<Error = KeyStoreError>>(
+        self,
+        key_store: &S,
+    ) -> Result<(), AsyncKeyStoreError<KeyStoreError>> {
+        self.key_store.flush(key_store).await
+    }
+}
+
+impl<'a, Backend, KeyStoreError> OpenMlsCryptoProvider
+    for BufferedBackend<'a, Backend, KeyStoreError>
+where
+    Backend: OpenMlsCryptoProvider,
+    KeyStoreError: std::error::Error + PartialEq + 'static,
+{
+    type CryptoProvider = Backend::CryptoProvider;
+    type RandProvider = Backend::RandProvider;
+    type KeyStoreProvider = BufferedKeyStore<KeyStoreError>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.backend.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.backend.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+impl MlsGroup {
+    /// Async variant of [`MlsGroup::new()`] for backends with an
+    /// [`AsyncOpenMlsKeyStore`].
+    pub async fn new_async<Backend: OpenMlsCryptoProvider, S: AsyncOpenMlsKeyStore>(
+        backend: &Backend,
+        key_store: &S,
+        mls_group_config: &MlsGroupConfig,
+        signature_key: &SignaturePublicKey,
+    ) -> Result<Self, NewGroupError<AsyncKeyStoreError<S::Error>>> {
+        let buffered_backend = BufferedBackend::new(backend);
+        if let Ok(k) = signature_key.tls_serialize_detached() {
+            buffered_backend.key_store.prefetch(key_store, &k).await;
+        }
+
+        let result = Self::new(&buffered_backend, mls_group_config, signature_key);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        let group = result?;
+        flushed.map_err(NewGroupError::KeyStoreError)?;
+        Ok(group)
+    }
+
+    /// Async variant of [`MlsGroup::create_message()`] for backends with an
+    /// [`AsyncOpenMlsKeyStore`].
+    ///
+    /// Creating a message does not write to the key store. Errors of the
+    /// async key store are returned as [`CreateMessageError::LibraryError`].
+    pub async fn create_message_async<Backend: OpenMlsCryptoProvider, S: AsyncOpenMlsKeyStore>(
+        &mut self,
+        backend: &Backend,
+        key_store: &S,
+        message: &[u8],
+    ) -> Result<MlsMessageOut, CreateMessageError> {
+        let buffered_backend = BufferedBackend::new(backend);
+        self.prefetch(&buffered_backend.key_store, key_store).await;
+
+        let result = self.create_message(&buffered_backend, message);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        let output = result?;
+        flushed.map_err(|_| {
+            LibraryError::custom("Creating a message should not write to the key store.")
+        })?;
+        Ok(output)
+    }
+
+    /// Async variant of [`MlsGroup::add_members()`] for backends with an
+    /// [`AsyncOpenMlsKeyStore`].
+    ///
+    /// Errors of the async key store are returned as
+    /// [`CreateCommitError::KeyStoreError`].
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub async fn add_members_async<Backend: OpenMlsCryptoProvider, S: AsyncOpenMlsKeyStore>(
+        &mut self,
+        backend: &Backend,
+        key_store: &S,
+        key_packages: &[KeyPackage],
+    ) -> Result<
+        (MlsMessageOut, MlsMessageOut, Option<GroupInfo>),
+        AddMembersError<AsyncKeyStoreError<S::Error>>,
+    > {
+        let buffered_backend = BufferedBackend::new(backend);
+        self.prefetch(&buffered_backend.key_store, key_store).await;
+
+        let result = self.add_members(&buffered_backend, key_packages);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        let output = result?;
+        flushed.map_err(CreateCommitError::KeyStoreError)?;
+        Ok(output)
+    }
+
+    /// Async variant of [`MlsGroup::self_update()`] for backends with an
+    /// [`AsyncOpenMlsKeyStore`].
+    ///
+    /// Errors of the async key store are returned as
+    /// [`CreateCommitError::KeyStoreError`].
+    #[allow(clippy::type_complexity)]
+    pub async fn self_update_async<Backend: OpenMlsCryptoProvider, S: AsyncOpenMlsKeyStore>(
+        &mut self,
+        backend: &Backend,
+        key_store: &S,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        SelfUpdateError<AsyncKeyStoreError<S::Error>>,
+    > {
+        let buffered_backend = BufferedBackend::new(backend);
+        self.prefetch(&buffered_backend.key_store, key_store).await;
+
+        let result = self.self_update(&buffered_backend);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        let output = result?;
+        flushed.map_err(CreateCommitError::KeyStoreError)?;
+        Ok(output)
+    }
+
+    /// Async variant of [`MlsGroup::process_message()`] for backends with an
+    /// [`AsyncOpenMlsKeyStore`].
+    ///
+    /// Errors of [`MlsGroup::process_message()`] are returned as
+    /// [`ProcessMessageAsyncError::ProcessMessageError`] and errors of the
+    /// async key store as [`ProcessMessageAsyncError::KeyStoreError`].
+    pub async fn process_message_async<Backend: OpenMlsCryptoProvider, S: AsyncOpenMlsKeyStore>(
+        &mut self,
+        backend: &Backend,
+        key_store: &S,
+        message: impl Into<ProtocolMessage>,
+    ) -> Result<ProcessedMessage, ProcessMessageAsyncError<AsyncKeyStoreError<S::Error>>> {
+        let buffered_backend = BufferedBackend::new(backend);
+        self.prefetch(&buffered_backend.key_store, key_store).await;
+
+        let result = self.process_message(&buffered_backend, message);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        let output = result?;
+        flushed.map_err(ProcessMessageAsyncError::KeyStoreError)?;
+        Ok(output)
+    }
+
+    /// Async variant of [`MlsGroup::merge_staged_commit()`] for backends with
+    /// an [`AsyncOpenMlsKeyStore`].
+    pub async fn merge_staged_commit_async<
+        Backend: OpenMlsCryptoProvider,
+        S: AsyncOpenMlsKeyStore,
+    >(
+        &mut self,
+        backend: &Backend,
+        key_store: &S,
+        staged_commit: StagedCommit,
+    ) -> Result<(), MergeCommitError<AsyncKeyStoreError<S::Error>>> {
+        let buffered_backend = BufferedBackend::new(backend);
+        self.prefetch(&buffered_backend.key_store, key_store).await;
+
+        let result = self.merge_staged_commit(&buffered_backend, staged_commit);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        result?;
+        flushed.map_err(MergeCommitError::KeyStoreError)
+    }
+
+    /// Async variant of [`MlsGroup::merge_pending_commit()`] for backends with
+    /// an [`AsyncOpenMlsKeyStore`].
+    pub async fn merge_pending_commit_async<
+        Backend: OpenMlsCryptoProvider,
+        S: AsyncOpenMlsKeyStore,
+    >(
+        &mut self,
+        backend: &Backend,
+        key_store: &S,
+    ) -> Result<(), MergePendingCommitError<AsyncKeyStoreError<S::Error>>> {
+        let buffered_backend = BufferedBackend::new(backend);
+        self.prefetch(&buffered_backend.key_store, key_store).await;
+
+        let result = self.merge_pending_commit(&buffered_backend);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        result?;
+        flushed.map_err(MergeCommitError::KeyStoreError)?;
+        Ok(())
+    }
+
+    /// Async variant of [`MlsGroup::new_from_welcome()`] for backends with an
+    /// [`AsyncOpenMlsKeyStore`].
+    pub async fn new_from_welcome_async<Backend: OpenMlsCryptoProvider, S: AsyncOpenMlsKeyStore>(
+        backend: &Backend,
+        key_store: &S,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+        ratchet_tree: Option<Vec<Option<Node>>>,
+    ) -> Result<Self, WelcomeError<AsyncKeyStoreError<S::Error>>> {
+        let buffered_backend = BufferedBackend::new(backend);
+        // Consuming the key package updates the inventory, which has to be
+        // read first, such that the counts of other key packages are kept.
+        buffered_backend
+            .key_store
+            .prefetch(key_store, KEY_PACKAGE_INVENTORY_ID)
+            .await;
+        for egs in welcome.secrets() {
+            let key_package = buffered_backend
+                .key_store
+                .prefetch(key_store, egs.new_member().as_slice())
+                .await
+                .and_then(|value| KeyPackage::from_key_store_value(&value).ok());
+            if let Some(key_package) = key_package {
+                buffered_backend
+                    .key_store
+                    .prefetch(key_store, key_package.hpke_init_key().as_slice())
+                    .await;
+                // A last resort key package is kept after joining.
+                buffered_backend
+                    .key_store
+                    .prefetch(key_store, &last_resort_id(&egs.new_member()))
+                    .await;
+                buffered_backend
+                    .key_store
+                    .prefetch(
+                        key_store,
+                        &key_package
+                            .leaf_node()
+                            .encryption_key()
+                            .to_bytes_with_prefix(),
+                    )
+                    .await;
+                break;
+            }
+        }
+
+        let result =
+            Self::new_from_welcome(&buffered_backend, mls_group_config, welcome, ratchet_tree);
+        let flushed = buffered_backend.key_store.flush(key_store).await;
+        let group = result?;
+        flushed.map_err(WelcomeError::KeyStoreError)?;
+        Ok(group)
+    }
+
+    /// Prefetch everything the group might need from the `key_store`: the own
+    /// credential bundle, the epoch keys of the current epoch, the keys of own
+    /// pending updates and the PSKs referenced in the proposal store.
+    async fn prefetch<S: AsyncOpenMlsKeyStore>(
+        &self,
+        buffer: &BufferedKeyStore<S::Error>,
+        key_store: &S,
+    ) {
+        if let Some(identity) = self.group.own_identity() {
+            buffer
+                .prefetch_epoch_keys(
+                    key_store,
+                    identity.as_slice(),
+                    self.group_id().as_slice(),
+                    self.epoch().as_u64(),
+                )
+                .await;
+        }
+        for k in self.prefetch_keys() {
+            buffer.prefetch(key_store, &k).await;
+        }
+    }
+
+    /// Returns the key store indices of the values [`Self::prefetch()`] has
+    /// to read.
+    ///
+    /// Values whose index cannot be computed are skipped, such that the
+    /// synchronous operation can report the error.
+    fn prefetch_keys(&self) -> Vec<Vec<u8>> {
+        let mut keys = vec![];
+        if let Ok(credential) = self.credential() {
+            if let Ok(k) = credential.signature_key().tls_serialize_detached() {
+                keys.push(k);
+            }
+        }
+        keys.extend(
+            self.own_leaf_nodes
+                .iter()
+                .map(|leaf_node| leaf_node.encryption_key().to_bytes_with_prefix()),
+        );
+        for queued_proposal in self.proposal_store.proposals() {
+            if let Proposal::PreSharedKey(psk_proposal) = queued_proposal.proposal() {
+                let psk_id = psk_proposal.psk();
+                if let Ok(k) = psk_id.tls_serialize_detached() {
+                    keys.push(k);
+                }
+                if let Psk::Resumption(resumption_psk) = psk_id.psk() {
+                    if let Ok(k) = resumption_psk.tls_serialize_detached() {
+                        keys.push(k);
+                    }
+                }
+            }
+        }
+        keys
+    }
+}
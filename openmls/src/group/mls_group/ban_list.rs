@@ -0,0 +1,112 @@
+//! Ban list of an [`MlsGroup`].
+//!
+//! This module contains the functions to update the ban list extension of a
+//! group through a GroupContextExtensions proposal.
+
+use core_group::create_commit_params::CreateCommitParams;
+use tls_codec::Serialize;
+
+use crate::{
+    extensions::{BanListExtension, Extension, ExtensionType},
+    messages::GroupInfo,
+};
+
+use super::{errors::UpdateBanListError, *};
+
+impl MlsGroup {
+    /// Returns the ban list of the group, i.e. the ban list extension in the
+    /// group context, if there is any. Commits that add a banned party,
+    /// either through an Add proposal or an External Commit, are rejected.
+    pub fn ban_list(&self) -> Option<&BanListExtension> {
+        self.group.group_context_extensions().ban_list()
+    }
+
+    /// Bans the signature key and the identity of the `credential` from the
+    /// group.
+    ///
+    /// This creates a Commit that covers a GroupContextExtensions proposal
+    /// with the updated ban list. See
+    /// [`update_ban_list()`](Self::update_ban_list()) for details. A member
+    /// with the credential is not removed from the group by this.
+    ///
+    /// Returns an error if there is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn ban<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        credential: &Credential,
+    ) -> Result<(MlsMessageOut, Option<GroupInfo>), UpdateBanListError<KeyStore::Error>> {
+        let mut ban_list = self.ban_list().cloned().unwrap_or_default();
+        ban_list.ban(credential);
+        self.update_ban_list(backend, ban_list)
+    }
+
+    /// Replaces the ban list extension of the group with `ban_list`. The
+    /// extension is removed from the group context if `ban_list` is empty.
+    ///
+    /// This creates a Commit that only covers a GroupContextExtensions
+    /// proposal with the updated extensions. Pending proposals are not
+    /// included. Once the Commit is merged with
+    /// [`merge_pending_commit()`](Self::merge_pending_commit()), all members
+    /// reject commits that add a party of the new ban list.
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`] (containing the
+    /// commit) and the [GroupInfo]. The [GroupInfo] is [Some] if the group
+    /// has the `use_ratchet_tree_extension` flag set.
+    ///
+    /// Returns an error if there is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn update_ban_list<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ban_list: BanListExtension,
+    ) -> Result<(MlsMessageOut, Option<GroupInfo>), UpdateBanListError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        let credential = self.credential()?;
+        let credential_bundle: CredentialBundle = backend
+            .key_store()
+            .read(
+                &credential
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+            )
+            .ok_or(UpdateBanListError::NoMatchingCredentialBundle)?;
+
+        let mut extensions = self.group.group_context_extensions().clone();
+        if ban_list.is_empty() {
+            extensions.remove(ExtensionType::BanList);
+        } else {
+            extensions.add_or_replace(Extension::BanList(ban_list));
+        }
+        let proposal =
+            Proposal::GroupContextExtensions(GroupContextExtensionProposal::new(extensions));
+
+        // The GroupContextExtensions proposal has to be the only one of its
+        // kind in the Commit, so we don't include pending proposals.
+        let empty_proposal_store = ProposalStore::new();
+        let params = CreateCommitParams::builder()
+            .framing_parameters(self.framing_parameters())
+            .credential_bundle(&credential_bundle)
+            .proposal_store(&empty_proposal_store)
+            .inline_proposals(vec![proposal])
+            .build();
+        let create_commit_result = self.group.create_commit(params, backend)?;
+
+        // Convert PublicMessage messages to MLSMessage and encrypt them if required by
+        // the configuration
+        let mls_message = self.content_to_mls_message(create_commit_result.commit, backend)?;
+
+        // Set the current group state to [`MlsGroupState::PendingCommit`],
+        // storing the current [`StagedCommit`] from the commit results
+        self.set_own_pending_commit(backend, create_commit_result.staged_commit, &mls_message)?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change(StateChangeReason::CommitCreated);
+
+        Ok((mls_message, create_commit_result.group_info))
+    }
+}
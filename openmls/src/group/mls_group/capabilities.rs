@@ -0,0 +1,166 @@
+//! Updates of the capabilities and extensions of the own leaf, e.g. after the
+//! application gained support for a new extension, see
+//! [`MlsGroup::update_capabilities()`].
+
+use core_group::create_commit_params::CreateCommitParams;
+use tls_codec::Serialize;
+
+use crate::{messages::GroupInfo, treesync::node::leaf_node::Capabilities};
+
+use super::*;
+
+impl MlsGroup {
+    /// Updates the [`Capabilities`] and the extensions of the own leaf node
+    /// with a commit. The commit covers all pending proposals.
+    ///
+    /// The new capabilities must support the ciphersuite and the protocol
+    /// version of the group, the required capabilities of the group, and all
+    /// `extensions` of the leaf node. Otherwise, an
+    /// [`UpdateCapabilitiesError`] is returned and the group doesn't change.
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`] (containing the
+    /// commit), an optional [`MlsMessageOut`] (containing the [`Welcome`]) and
+    /// the [GroupInfo], like [`MlsGroup::self_update()`].
+    #[allow(clippy::type_complexity)]
+    pub fn update_capabilities<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        capabilities: Capabilities,
+        extensions: Extensions,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        UpdateCapabilitiesError<KeyStore::Error>,
+    > {
+        self.is_operational()?;
+        self.check_own_capabilities(&capabilities, &extensions)?;
+
+        // Drop expired proposals, such that they are not committed
+        self.expire_pending_proposals();
+
+        let credential_bundle = self.own_credential_bundle(backend)?;
+        let params = CreateCommitParams::builder()
+            .framing_parameters(self.framing_parameters())
+            .credential_bundle(&credential_bundle)
+            .proposal_store(&self.proposal_store)
+            .own_leaf_capabilities(capabilities, extensions)
+            .build();
+        let create_commit_result = self.group.create_commit(params, backend)?;
+
+        let mls_message = self.content_to_mls_message(create_commit_result.commit, backend)?;
+
+        self.set_own_pending_commit(backend, create_commit_result.staged_commit, &mls_message)?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change(StateChangeReason::CommitCreated);
+
+        Ok((
+            mls_message,
+            create_commit_result
+                .welcome_option
+                .map(|w| MlsMessageOut::from_welcome(w, self.group.version())),
+            create_commit_result.group_info,
+        ))
+    }
+
+    /// Creates a proposal to update the [`Capabilities`] and the extensions of
+    /// the own leaf node. The leaf node gets a fresh encryption key.
+    ///
+    /// The new capabilities are validated like in
+    /// [`MlsGroup::update_capabilities()`].
+    pub fn propose_capabilities_update<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        capabilities: Capabilities,
+        extensions: Extensions,
+    ) -> Result<MlsMessageOut, UpdateCapabilitiesError<KeyStore::Error>> {
+        self.is_operational()?;
+        self.check_own_capabilities(&capabilities, &extensions)?;
+
+        let credential_bundle = self.own_credential_bundle(backend)?;
+
+        // Clone the own leaf to update it such that the tree doesn't change
+        // before the proposal is committed.
+        let mut own_leaf = self
+            .group
+            .treesync()
+            .own_leaf_node()
+            .ok_or_else(|| LibraryError::custom("The tree is broken. Couldn't find own leaf."))?
+            .clone();
+        own_leaf.set_capabilities_and_extensions(capabilities, extensions);
+        let keypair = own_leaf.rekey(
+            self.group_id(),
+            self.ciphersuite(),
+            self.group.version(),
+            &credential_bundle,
+            backend,
+        )?;
+        keypair
+            .write_to_key_store(backend)
+            .map_err(UpdateCapabilitiesError::KeyStoreError)?;
+
+        let update_proposal = self.group.create_update_proposal(
+            self.framing_parameters(),
+            &credential_bundle,
+            own_leaf.leaf_node().clone(),
+            backend,
+        )?;
+
+        self.own_leaf_nodes.push(own_leaf);
+        self.proposal_store
+            .add(QueuedProposal::from_authenticated_content(
+                self.ciphersuite(),
+                backend,
+                update_proposal.clone(),
+            )?);
+
+        let mls_message = self.content_to_mls_message(update_proposal, backend)?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change(StateChangeReason::ProposalCreated);
+
+        Ok(mls_message)
+    }
+
+    /// Check that the `capabilities` are valid for the own leaf node in this
+    /// group, together with the `extensions` of the leaf node.
+    fn check_own_capabilities<KeyStoreError>(
+        &self,
+        capabilities: &Capabilities,
+        extensions: &Extensions,
+    ) -> Result<(), UpdateCapabilitiesError<KeyStoreError>> {
+        if !capabilities.ciphersuites().contains(&self.ciphersuite())
+            || !capabilities.versions().contains(&self.group.version())
+        {
+            return Err(UpdateCapabilitiesError::UnsupportedCiphersuiteOrVersion);
+        }
+        if let Some(required_capabilities) = self.required_capabilities() {
+            capabilities.check_required_capabilities(required_capabilities)?;
+        }
+
+        // Extensions of the leaf node must be supported by its capabilities.
+        if extensions
+            .iter()
+            .any(|extension| !capabilities.supports_extension(&extension.extension_type()))
+        {
+            return Err(UpdateCapabilitiesError::UnsupportedExtension);
+        }
+        Ok(())
+    }
+
+    /// Read the [`CredentialBundle`] of the own leaf from the key store.
+    fn own_credential_bundle<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<CredentialBundle, UpdateCapabilitiesError<KeyStore::Error>> {
+        backend
+            .key_store()
+            .read(
+                &self
+                    .credential()?
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+            )
+            .ok_or(UpdateCapabilitiesError::NoMatchingCredentialBundle)
+    }
+}
@@ -0,0 +1,60 @@
+//! Processing of the handshake messages a client missed while it was offline,
+//! see [`MlsGroup::catch_up()`].
+
+use super::*;
+
+impl MlsGroup {
+    /// Processes an ordered sequence of messages from the Delivery Service,
+    /// for example after the client was offline for a while, and returns the
+    /// application messages among them in order.
+    ///
+    /// Proposals are validated and stored as pending proposals, and commits
+    /// are validated and merged, such that every message is processed in the
+    /// epoch it was sent in. This is equivalent to calling
+    /// [`MlsGroup::process_message()`] for every message, followed by
+    /// [`MlsGroup::store_pending_proposal()`] or
+    /// [`MlsGroup::merge_staged_commit()`], but the group state only has to be
+    /// persisted once after this function returned.
+    ///
+    /// The messages must not contain the own commits of this client, which
+    /// have to be merged with [`MlsGroup::merge_pending_commit()`] instead.
+    /// Commits that were already merged, e.g. because the Delivery Service
+    /// delivered them again, are skipped.
+    ///
+    /// Processing stops at the first message that can't be processed or
+    /// merged. The messages before it remain processed, and
+    /// [`MlsGroup::epoch()`] indicates how far the group caught up.
+    pub fn catch_up<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        messages: Vec<ProtocolMessage>,
+    ) -> Result<Vec<ProcessedMessage>, CatchUpError<KeyStore::Error>> {
+        let mut application_messages = Vec::new();
+        for message in messages {
+            let processed_message = match self.process_message(backend, message) {
+                Ok(processed_message) => processed_message,
+                Err(ProcessMessageError::AlreadyProcessed) => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if matches!(
+                processed_message.content(),
+                ProcessedMessageContent::ApplicationMessage(_)
+            ) {
+                application_messages.push(processed_message);
+                continue;
+            }
+            match processed_message.into_content() {
+                ProcessedMessageContent::ProposalMessage(queued_proposal)
+                | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                    self.store_pending_proposal(*queued_proposal)
+                }
+                ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                    self.merge_staged_commit(backend, *staged_commit)?
+                }
+                // Application messages were returned above.
+                ProcessedMessageContent::ApplicationMessage(_) => (),
+            }
+        }
+        Ok(application_messages)
+    }
+}
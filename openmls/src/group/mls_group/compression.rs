@@ -0,0 +1,83 @@
+//! # Application message compression
+//!
+//! Groups can agree on compressing application messages before they are
+//! encrypted. Compression is negotiated through the required capabilities of
+//! the group: if [`ExtensionType::DeflateCompression`] is one of the required
+//! extension types, all members support it and the payloads of all
+//! application messages are compressed with DEFLATE (RFC 1951). Compression
+//! can be enabled when the group is created (see
+//! [`MlsGroupConfigBuilder::required_capabilities()`](super::config::MlsGroupConfigBuilder::required_capabilities()))
+//! or later on with a group context extensions proposal. In both cases,
+//! members that don't support it can't be part of the group.
+//!
+//! Support for compression is enabled with the `compression-deflate` feature.
+//!
+//! **WARNING**
+//!
+//! The size of a compressed message depends on its content. Applications that
+//! mix secret and attacker-controlled data in the same message should not
+//! enable compression.
+
+use super::errors::CompressionError;
+use crate::extensions::{ExtensionType, RequiredCapabilitiesExtension};
+
+/// The maximum size of a decompressed application message in bytes.
+#[cfg(feature = "compression-deflate")]
+const MAX_DECOMPRESSED_SIZE: usize = 1 << 24;
+
+/// Returns `true` if the required capabilities of a group ask for compressed
+/// application messages.
+pub(super) fn is_enabled(required_capabilities: Option<&RequiredCapabilitiesExtension>) -> bool {
+    required_capabilities
+        .map(|required_capabilities| {
+            required_capabilities
+                .extension_types()
+                .contains(&ExtensionType::DeflateCompression)
+        })
+        .unwrap_or(false)
+}
+
+/// Compresses the payload of an application message.
+#[cfg(feature = "compression-deflate")]
+pub(super) fn compress(payload: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    use crate::error::LibraryError;
+    use flate2::{write::DeflateEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(payload)
+        .and_then(|_| encoder.finish())
+        // Writing to a vector can't fail
+        .map_err(|_| LibraryError::custom("Could not compress payload").into())
+}
+
+/// Compresses the payload of an application message.
+#[cfg(not(feature = "compression-deflate"))]
+pub(super) fn compress(_payload: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    Err(CompressionError::CompressionNotSupported)
+}
+
+/// Decompresses the payload of an application message. Payloads that
+/// decompress to more than [`MAX_DECOMPRESSED_SIZE`] bytes are rejected.
+#[cfg(feature = "compression-deflate")]
+pub(super) fn decompress(payload: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    let mut decompressed = Vec::new();
+    DeflateDecoder::new(payload)
+        .take(MAX_DECOMPRESSED_SIZE as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|_| CompressionError::MalformedPayload)?;
+    if decompressed.len() > MAX_DECOMPRESSED_SIZE {
+        return Err(CompressionError::PayloadTooLarge);
+    }
+    Ok(decompressed)
+}
+
+/// Decompresses the payload of an application message.
+#[cfg(not(feature = "compression-deflate"))]
+pub(super) fn decompress(_payload: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    Err(CompressionError::CompressionNotSupported)
+}
@@ -26,10 +26,28 @@
 //!     .wire_format_policy(MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY)
 //!     .build();
 //! ```
+//!
+//! ### Presets
+//! [`MlsGroupConfig::strict()`] and [`MlsGroupConfig::interop()`] bundle
+//! choices for common deployments. A preset can be adjusted by turning it into
+//! a builder.
+//!
+//! ```
+//! use openmls::prelude::*;
+//!
+//! let group_config = MlsGroupConfigBuilder::from(MlsGroupConfig::strict())
+//!     .crypto_config(CryptoConfig::with_default_version(
+//!         Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+//!     ))
+//!     .build();
+//! ```
 
 use super::*;
 use crate::{
-    group::config::CryptoConfig, tree::sender_ratchet::SenderRatchetConfiguration,
+    ciphersuite::signature::{OpenMlsSignaturePublicKey, SignaturePublicKey},
+    credentials::CredentialType,
+    group::config::CryptoConfig,
+    tree::sender_ratchet::SenderRatchetConfiguration,
     treesync::node::leaf_node::Lifetime,
 };
 use serde::{Deserialize, Serialize};
@@ -54,10 +72,79 @@ pub struct MlsGroupConfig {
     pub(crate) required_capabilities: RequiredCapabilitiesExtension,
     /// Sender ratchet configuration
     pub(crate) sender_ratchet_configuration: SenderRatchetConfiguration,
+    /// Maximum number of skipped message keys stored for all senders and
+    /// epochs together
+    #[serde(default)]
+    pub(crate) max_skipped_keys: Option<usize>,
     /// Lifetime of the own leaf node
     pub(crate) lifetime: Lifetime,
     /// Ciphersuite and protocol version
     pub(crate) crypto_config: CryptoConfig,
+    /// Flag to indicate that External Commits must contain an external PSK
+    #[serde(default)]
+    pub(crate) require_external_commit_psk: bool,
+    /// Number of handshake messages remembered to detect replays
+    #[serde(default)]
+    pub(crate) replay_window_size: usize,
+    /// Flag to indicate that redelivered commits that were already merged are
+    /// detected
+    #[serde(default)]
+    pub(crate) detect_merged_commits: bool,
+    /// Validation level for incoming messages
+    #[serde(default)]
+    pub(crate) validation_level: ValidationLevel,
+    /// Maximum number of members of new groups
+    #[serde(default)]
+    pub(crate) max_members: Option<u32>,
+    /// Handling of new members with an existing identity or signature key
+    #[serde(default)]
+    pub(crate) duplicate_identity_policy: DuplicateIdentityPolicy,
+    /// Maximum age of pending proposals in seconds
+    #[serde(default)]
+    pub(crate) max_pending_proposal_age: Option<u64>,
+    /// Flag to indicate that consumed key packages are kept in the key store
+    #[serde(default)]
+    pub(crate) keep_consumed_key_packages: bool,
+    /// Ciphersuites of groups that can be joined
+    #[serde(default)]
+    pub(crate) allowed_ciphersuites: Option<Vec<Ciphersuite>>,
+    /// Minimum security level in bits of the ciphersuites of joined groups
+    #[serde(default)]
+    pub(crate) minimum_security_level: Option<u16>,
+    /// Minimum protocol version of joined groups
+    #[serde(default)]
+    pub(crate) minimum_version: Option<ProtocolVersion>,
+    /// Non-members whose signatures on GroupInfos are accepted for external
+    /// commits
+    #[serde(default)]
+    pub(crate) external_group_info_signers: Vec<OpenMlsSignaturePublicKey>,
+    /// Generation of the group IDs of new groups
+    #[serde(default)]
+    pub(crate) group_id_strategy: GroupIdStrategy,
+    /// Flag to indicate that the key packages of new members must be verified
+    /// by a key transparency verifier
+    #[serde(default)]
+    pub(crate) require_key_transparency: bool,
+    // The application callbacks below can't be persisted and have to be set
+    // again after a group is loaded.
+    /// Notification when joining from a Welcome leaves few key packages
+    #[serde(skip)]
+    pub(crate) key_package_replenishment: Option<KeyPackageReplenishment>,
+    /// Verifier for the key-transparency publications of the key packages of
+    /// new members
+    #[serde(skip)]
+    pub(crate) key_transparency_verifier: Option<TransparencyVerifier>,
+    /// Limiter for proposals from outside of the group
+    #[serde(skip)]
+    pub(crate) external_proposal_limiter: Option<ProposalLimiter>,
+    /// Verifier for the verifiable credentials of members
+    #[cfg(feature = "verifiable-credentials")]
+    #[serde(skip)]
+    pub(crate) verifiable_credential_verifier: Option<CredentialVerifier>,
+    /// Verifier for the token bindings of members
+    #[cfg(feature = "token-bound-credentials")]
+    #[serde(skip)]
+    pub(crate) token_binding_verifier: Option<TokenVerifier>,
 }
 
 impl MlsGroupConfig {
@@ -66,6 +153,61 @@ impl MlsGroupConfig {
         MlsGroupConfigBuilder::new()
     }
 
+    /// Returns a configuration for security-sensitive deployments, in which
+    /// all members use OpenMLS with this configuration:
+    ///
+    /// - Handshake messages are always encrypted
+    ///   ([`PURE_CIPHERTEXT_WIRE_FORMAT_POLICY`]).
+    /// - Application messages can only be decrypted in the epoch they were
+    ///   sent in (`max_past_epochs` is 0) and only a small number of them can
+    ///   arrive out of order.
+    /// - Replayed handshake messages are rejected.
+    /// - All members must support basic credentials
+    ///   ([`RequiredCapabilitiesExtension`]).
+    /// - Incoming messages are validated with [`ValidationLevel::Strict`].
+    ///
+    /// All other values are the defaults.
+    pub fn strict() -> Self {
+        Self::builder()
+            .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+            .max_past_epochs(0)
+            .sender_ratchet_configuration(SenderRatchetConfiguration::new(
+                STRICT_OUT_OF_ORDER_TOLERANCE,
+                STRICT_MAXIMUM_FORWARD_DISTANCE,
+            ))
+            .replay_window_size(STRICT_REPLAY_WINDOW_SIZE)
+            .required_capabilities(RequiredCapabilitiesExtension::new(
+                &[],
+                &[],
+                &[CredentialType::Basic],
+            ))
+            .validation_level(ValidationLevel::Strict)
+            .build()
+    }
+
+    /// Returns a configuration for groups with members that use other MLS
+    /// implementations:
+    ///
+    /// - Handshake messages are encrypted, but unencrypted ones are accepted
+    ///   ([`MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY`]).
+    /// - The ratchet tree is sent in the Welcome message, such that new
+    ///   members don't depend on an out-of-band tree.
+    /// - Application messages can only be decrypted in the epoch they were
+    ///   sent in (`max_past_epochs` is 0).
+    /// - No capabilities are required beyond the ones the MLS protocol
+    ///   requires.
+    ///
+    /// All other values are the defaults.
+    pub fn interop() -> Self {
+        Self::builder()
+            .wire_format_policy(MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY)
+            .use_ratchet_tree_extension(true)
+            .max_past_epochs(0)
+            .required_capabilities(RequiredCapabilitiesExtension::default())
+            .validation_level(ValidationLevel::Standard)
+            .build()
+    }
+
     /// Returns the [`MlsGroupConfig`] wire format policy.
     pub fn wire_format_policy(&self) -> WireFormatPolicy {
         self.wire_format_policy
@@ -91,11 +233,22 @@ impl MlsGroupConfig {
         self.use_ratchet_tree_extension
     }
 
+    /// Returns the [`MlsGroupConfig`] required capabilities.
+    pub fn required_capabilities(&self) -> &RequiredCapabilitiesExtension {
+        &self.required_capabilities
+    }
+
     /// Returns the [`MlsGroupConfig`] sender ratchet configuration.
     pub fn sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
         &self.sender_ratchet_configuration
     }
 
+    /// Returns the [`MlsGroupConfig`] maximum number of skipped message keys
+    /// stored for all senders and epochs together, if there is one.
+    pub fn max_skipped_keys(&self) -> Option<usize> {
+        self.max_skipped_keys
+    }
+
     /// Returns the [`MlsGroupConfig`] lifetime configuration.
     pub fn lifetime(&self) -> &Lifetime {
         &self.lifetime
@@ -106,6 +259,104 @@ impl MlsGroupConfig {
         &self.crypto_config
     }
 
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether
+    /// External Commits have to contain an external PSK.
+    pub fn require_external_commit_psk(&self) -> bool {
+        self.require_external_commit_psk
+    }
+
+    /// Returns the [`MlsGroupConfig`] replay window size.
+    pub fn replay_window_size(&self) -> usize {
+        self.replay_window_size
+    }
+
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether
+    /// redelivered commits that were already merged are detected.
+    pub fn detect_merged_commits(&self) -> bool {
+        self.detect_merged_commits
+    }
+
+    /// Returns the [`MlsGroupConfig`] validation level.
+    pub fn validation_level(&self) -> ValidationLevel {
+        self.validation_level
+    }
+
+    /// Returns the [`MlsGroupConfig`] maximum number of members of new
+    /// groups, if there is one.
+    pub fn max_members(&self) -> Option<u32> {
+        self.max_members
+    }
+
+    /// Returns the [`MlsGroupConfig`] duplicate identity policy.
+    pub fn duplicate_identity_policy(&self) -> DuplicateIdentityPolicy {
+        self.duplicate_identity_policy
+    }
+
+    /// Returns the [`MlsGroupConfig`] maximum age of pending proposals in
+    /// seconds, if there is one.
+    pub fn max_pending_proposal_age(&self) -> Option<u64> {
+        self.max_pending_proposal_age
+    }
+
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether
+    /// consumed key packages are kept in the key store.
+    pub fn keep_consumed_key_packages(&self) -> bool {
+        self.keep_consumed_key_packages
+    }
+
+    /// Returns the [`MlsGroupConfig`] ciphersuites of groups that can be
+    /// joined, if they are restricted.
+    pub fn allowed_ciphersuites(&self) -> Option<&[Ciphersuite]> {
+        self.allowed_ciphersuites.as_deref()
+    }
+
+    /// Returns `true` if groups with the `ciphersuite` can be joined.
+    pub(crate) fn is_ciphersuite_allowed(&self, ciphersuite: Ciphersuite) -> bool {
+        self.allowed_ciphersuites
+            .as_ref()
+            .map_or(true, |ciphersuites| ciphersuites.contains(&ciphersuite))
+    }
+
+    /// Returns the [`MlsGroupConfig`] minimum security level in bits of the
+    /// ciphersuites of joined groups, if there is one.
+    pub fn minimum_security_level(&self) -> Option<u16> {
+        self.minimum_security_level
+    }
+
+    /// Returns the [`MlsGroupConfig`] minimum protocol version of joined
+    /// groups, if there is one.
+    pub fn minimum_version(&self) -> Option<ProtocolVersion> {
+        self.minimum_version
+    }
+
+    /// Returns the [`MlsGroupConfig`] external GroupInfo signers.
+    pub fn external_group_info_signers(&self) -> &[OpenMlsSignaturePublicKey] {
+        &self.external_group_info_signers
+    }
+
+    /// Returns the [`MlsGroupConfig`] strategy for the group IDs of new
+    /// groups.
+    pub fn group_id_strategy(&self) -> &GroupIdStrategy {
+        &self.group_id_strategy
+    }
+
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether the
+    /// key packages of new members must be verified by a key transparency
+    /// verifier.
+    pub fn require_key_transparency(&self) -> bool {
+        self.require_key_transparency
+    }
+
+    /// Returns `true` if a group with the `ciphersuite` and the `version`
+    /// would be weaker than the configured minimums.
+    pub(crate) fn is_downgrade(&self, ciphersuite: Ciphersuite, version: ProtocolVersion) -> bool {
+        self.minimum_security_level
+            .map_or(false, |minimum| ciphersuite.security_level() < minimum)
+            || self
+                .minimum_version
+                .map_or(false, |minimum| !version.is_at_least(minimum))
+    }
+
     #[cfg(any(feature = "test-utils", test))]
     pub fn test_default(ciphersuite: Ciphersuite) -> Self {
         Self::builder()
@@ -118,11 +369,24 @@ impl MlsGroupConfig {
     }
 }
 
+/// Out-of-order tolerance of the sender ratchets in [`MlsGroupConfig::strict()`].
+const STRICT_OUT_OF_ORDER_TOLERANCE: u32 = 2;
+/// Maximum forward distance of the sender ratchets in [`MlsGroupConfig::strict()`].
+const STRICT_MAXIMUM_FORWARD_DISTANCE: u32 = 100;
+/// Replay window size in [`MlsGroupConfig::strict()`].
+const STRICT_REPLAY_WINDOW_SIZE: usize = 32;
+
 /// Builder for an [`MlsGroupConfig`].
 #[derive(Default)]
 pub struct MlsGroupConfigBuilder {
     config: MlsGroupConfig,
 }
+
+impl From<MlsGroupConfig> for MlsGroupConfigBuilder {
+    fn from(config: MlsGroupConfig) -> Self {
+        MlsGroupConfigBuilder { config }
+    }
+}
 impl MlsGroupConfigBuilder {
     /// Creates a new builder with default values.
     pub fn new() -> Self {
@@ -170,6 +434,17 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `required_capabilities` property of the MlsGroupConfig.
+    /// All members of the group must support the required extension and
+    /// proposal types.
+    pub fn required_capabilities(
+        mut self,
+        required_capabilities: RequiredCapabilitiesExtension,
+    ) -> Self {
+        self.config.required_capabilities = required_capabilities;
+        self
+    }
+
     /// Sets the `sender_ratchet_configuration` property of the MlsGroupConfig.
     /// See [`SenderRatchetConfiguration`] for more information.
     pub fn sender_ratchet_configuration(
@@ -180,6 +455,23 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `max_skipped_keys` property of the MlsGroupConfig.
+    ///
+    /// The [`SenderRatchetConfiguration`] limits the number of skipped message
+    /// keys that are stored for each sender and epoch. The `max_skipped_keys`
+    /// limits the number of skipped message keys that are stored for all
+    /// senders and epochs together, such that a single sender can't exhaust
+    /// the memory of the client by skipping generations.
+    ///
+    /// If a processed message exceeds the limit, the oldest skipped keys of
+    /// the sender that stores the most skipped keys are evicted first, see
+    /// [`MlsGroup::take_skipped_keys_evictions()`]. By default, only the
+    /// [`SenderRatchetConfiguration`] limits the number of skipped keys.
+    pub fn max_skipped_keys(mut self, max_skipped_keys: usize) -> Self {
+        self.config.max_skipped_keys = Some(max_skipped_keys);
+        self
+    }
+
     /// Sets the `lifetime` property of the MlsGroupConfig.
     pub fn lifetime(mut self, lifetime: Lifetime) -> Self {
         self.config.lifetime = lifetime;
@@ -192,12 +484,388 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `require_external_commit_psk` property of the MlsGroupConfig.
+    ///
+    /// If set, External Commits are only accepted if they contain a
+    /// PreSharedKey proposal for an external PSK. Since the PSK has to be
+    /// known to all members to process the commit, this allows a group to
+    /// require joiners to prove the possession of an out-of-band secret, e.g.
+    /// an invite code.
+    pub fn require_external_commit_psk(mut self, require_external_commit_psk: bool) -> Self {
+        self.config.require_external_commit_psk = require_external_commit_psk;
+        self
+    }
+
+    /// Sets the `replay_window_size` property of the MlsGroupConfig.
+    ///
+    /// The group remembers the last `replay_window_size` proposals and commits
+    /// it processed as PublicMessage. Processing one of them again fails with
+    /// [`ProcessMessageError::ReplayDetected`]. The default is 0, which
+    /// disables replay detection.
+    pub fn replay_window_size(mut self, replay_window_size: usize) -> Self {
+        self.config.replay_window_size = replay_window_size;
+        self
+    }
+
+    /// Sets the `detect_merged_commits` property of the MlsGroupConfig.
+    ///
+    /// If set, processing the commit that led to the current epoch again fails
+    /// with [`ProcessMessageError::AlreadyProcessed`], such that it can be
+    /// ignored, e.g. when the Delivery Service delivers messages at least
+    /// once. Other commits of the previous epoch still fail with a
+    /// `WrongEpoch` error. The default is `false`.
+    pub fn detect_merged_commits(mut self, detect_merged_commits: bool) -> Self {
+        self.config.detect_merged_commits = detect_merged_commits;
+        self
+    }
+
+    /// Sets the `validation_level` property of the MlsGroupConfig.
+    /// See [`ValidationLevel`] for more information.
+    pub fn validation_level(mut self, validation_level: ValidationLevel) -> Self {
+        self.config.validation_level = validation_level;
+        self
+    }
+
+    /// Sets the `max_members` property of the MlsGroupConfig.
+    ///
+    /// Groups created with [`MlsGroup::new()`] store `max_members` in a
+    /// [`MaxMembersExtension`](crate::extensions::MaxMembersExtension) in
+    /// their group context, such that all members enforce the same limit.
+    /// Adding members beyond it fails, and commits, Add proposals and Welcome
+    /// messages that would exceed it are rejected with a `TooManyMembers`
+    /// error. Groups that are joined use the limit of their group context
+    /// instead. By default, the number of members is not limited.
+    pub fn max_members(mut self, max_members: u32) -> Self {
+        self.config.max_members = Some(max_members);
+        self
+    }
+
+    /// Sets the `duplicate_identity_policy` property of the MlsGroupConfig.
+    /// See [`DuplicateIdentityPolicy`] for more information.
+    pub fn duplicate_identity_policy(
+        mut self,
+        duplicate_identity_policy: DuplicateIdentityPolicy,
+    ) -> Self {
+        self.config.duplicate_identity_policy = duplicate_identity_policy;
+        self
+    }
+
+    /// Sets the `max_pending_proposal_age` property of the MlsGroupConfig.
+    ///
+    /// Pending proposals that were stored at least `max_pending_proposal_age`
+    /// seconds ago are dropped before the group creates a commit, such that
+    /// they are not committed. Note that commits of other members that
+    /// reference a dropped proposal can't be processed anymore. By default,
+    /// pending proposals are only dropped when the epoch changes. See
+    /// [`MlsGroup::take_expired_proposals()`] for more information.
+    pub fn max_pending_proposal_age(mut self, max_pending_proposal_age: u64) -> Self {
+        self.config.max_pending_proposal_age = Some(max_pending_proposal_age);
+        self
+    }
+
+    /// Sets the `keep_consumed_key_packages` property of the MlsGroupConfig.
+    ///
+    /// By default, [`MlsGroup::new_from_welcome()`] deletes the consumed key
+    /// package and its private keys from the key store after joining, unless
+    /// it is a last resort key package (see
+    /// [`KeyPackageBuilder::last_resort()`](crate::key_packages::KeyPackageBuilder::last_resort())).
+    /// If this flag is set, the key package is kept and the application is
+    /// responsible for deleting it with [`KeyPackage::delete()`] to uphold
+    /// forward secrecy.
+    pub fn keep_consumed_key_packages(mut self, keep_consumed_key_packages: bool) -> Self {
+        self.config.keep_consumed_key_packages = keep_consumed_key_packages;
+        self
+    }
+
+    /// Sets the `allowed_ciphersuites` property of the MlsGroupConfig.
+    ///
+    /// Joining a group whose ciphersuite is not in `allowed_ciphersuites`
+    /// fails, both from a Welcome with [`MlsGroup::new_from_welcome()`] and
+    /// with [`MlsGroup::join_by_external_commit()`], before any of its
+    /// secrets are decrypted. By default, groups of all ciphersuites that
+    /// the crypto provider supports can be joined. The ciphersuite of groups
+    /// created with this configuration is set with
+    /// [`MlsGroupConfigBuilder::crypto_config()`] instead.
+    pub fn allowed_ciphersuites(mut self, allowed_ciphersuites: &[Ciphersuite]) -> Self {
+        self.config.allowed_ciphersuites = Some(allowed_ciphersuites.to_vec());
+        self
+    }
+
+    /// Sets the `minimum_security_level` property of the MlsGroupConfig.
+    ///
+    /// Groups whose ciphersuite has a lower security level in bits (see
+    /// [`Ciphersuite::security_level()`]) are rejected with a
+    /// `DowngradeDetected` error when joining them from a Welcome or with an
+    /// external commit, and ReInit proposals and commits that move the group
+    /// to such a ciphersuite are rejected as well. By default, there is no
+    /// minimum.
+    pub fn minimum_security_level(mut self, minimum_security_level: u16) -> Self {
+        self.config.minimum_security_level = Some(minimum_security_level);
+        self
+    }
+
+    /// Sets the `minimum_version` property of the MlsGroupConfig.
+    ///
+    /// Like [`MlsGroupConfigBuilder::minimum_security_level()`], groups on an
+    /// older protocol version are rejected with a `DowngradeDetected` error.
+    /// By default, all supported versions are accepted.
+    pub fn minimum_version(mut self, minimum_version: ProtocolVersion) -> Self {
+        self.config.minimum_version = Some(minimum_version);
+        self
+    }
+
+    /// Adds `signer` to the `external_group_info_signers` property of the
+    /// MlsGroupConfig.
+    ///
+    /// When joining a group with an external commit, a GroupInfo is accepted
+    /// if it is signed by its signer in the group or by one of the external
+    /// GroupInfo signers. This allows a Delivery Service that is not a member
+    /// of the group to re-sign GroupInfos with
+    /// [`VerifiableGroupInfo::resign()`](crate::messages::VerifiableGroupInfo::resign()),
+    /// e.g. after it validated them. By default, only GroupInfos signed by a
+    /// member are accepted.
+    pub fn external_group_info_signer(mut self, signer: OpenMlsSignaturePublicKey) -> Self {
+        self.config.external_group_info_signers.push(signer);
+        self
+    }
+
+    /// Sets the `group_id_strategy` property of the MlsGroupConfig.
+    /// See [`GroupIdStrategy`] for more information.
+    ///
+    /// The strategy determines the group ID of groups created with
+    /// [`MlsGroup::new()`]. It has no effect on groups created with
+    /// [`MlsGroup::new_with_group_id()`].
+    pub fn group_id_strategy(mut self, group_id_strategy: GroupIdStrategy) -> Self {
+        self.config.group_id_strategy = group_id_strategy;
+        self
+    }
+
+    /// Sets the `key_package_replenishment` property of the MlsGroupConfig.
+    ///
+    /// When a group is joined from a Welcome with
+    /// [`MlsGroup::new_from_welcome()`], the consumed key package is removed
+    /// from the [`KeyPackageInventory`]. If fewer than `threshold` unused key
+    /// packages for its ciphersuite remain, the `callback` is called with the
+    /// ciphersuite and the number of remaining key packages. This allows
+    /// clients to upload new key packages in time.
+    ///
+    /// The callback is not persisted with the configuration of a group.
+    pub fn key_package_replenishment(
+        mut self,
+        threshold: usize,
+        callback: impl Fn(Ciphersuite, usize) + Send + Sync + 'static,
+    ) -> Self {
+        self.config.key_package_replenishment =
+            Some(KeyPackageReplenishment::new(threshold, callback));
+        self
+    }
+
+    /// Sets the `key_transparency_verifier` property of the MlsGroupConfig.
+    ///
+    /// The verifier is called with the [`KeyPackagePublication`] of the key
+    /// package of every new member, both when adding members and when
+    /// processing Add proposals and commits, such that key packages are only
+    /// trusted if their publication in a key-transparency log is proven.
+    /// Without a verifier, key packages aren't checked.
+    ///
+    /// The verifier is not persisted with the configuration of a group, but
+    /// the fact that one is required is. A loaded group rejects the key
+    /// packages of all new members with an `UntrustedKeyPackage` error until
+    /// the verifier is set again with
+    /// [`MlsGroup::set_key_transparency_verifier()`].
+    ///
+    /// [`KeyPackagePublication`]: crate::key_packages::KeyPackagePublication
+    pub fn key_transparency_verifier(
+        mut self,
+        verifier: impl crate::key_packages::KeyTransparencyVerifier + 'static,
+    ) -> Self {
+        self.config.key_transparency_verifier = Some(TransparencyVerifier::new(verifier));
+        self.config.require_key_transparency = true;
+        self
+    }
+
+    /// Sets the `external_proposal_limiter` property of the MlsGroupConfig.
+    ///
+    /// The limiter is called for every proposal of a new member or an
+    /// external sender before it is validated, such that applications can
+    /// rate-limit or deny-list their sources. Proposals that the limiter
+    /// doesn't allow are rejected with
+    /// [`ProcessMessageError::ExternalProposalRejected`]. Without a limiter,
+    /// all such proposals are processed.
+    ///
+    /// The limiter is not persisted with the configuration of a group. Set
+    /// it again with [`MlsGroup::set_external_proposal_limiter()`] after
+    /// loading a group.
+    pub fn external_proposal_limiter(
+        mut self,
+        limiter: impl ExternalProposalLimiter + 'static,
+    ) -> Self {
+        self.config.external_proposal_limiter = Some(ProposalLimiter::new(limiter));
+        self
+    }
+
+    /// Sets the `verifiable_credential_verifier` property of the
+    /// MlsGroupConfig.
+    ///
+    /// The verifier checks the [`VerifiableCredential`]s of new members when
+    /// they are added, of updated leaves in proposals and commits, and of all
+    /// members when joining from a Welcome. Without a verifier, members with
+    /// a verifiable credential are rejected.
+    ///
+    /// The verifier is not persisted with the configuration of a group. Set
+    /// it again with [`MlsGroup::set_verifiable_credential_verifier()`] after
+    /// loading a group.
+    ///
+    /// [`VerifiableCredential`]: crate::credentials::VerifiableCredential
+    #[cfg(feature = "verifiable-credentials")]
+    pub fn verifiable_credential_verifier(
+        mut self,
+        verifier: impl crate::credentials::VerifiableCredentialVerifier + 'static,
+    ) -> Self {
+        self.config.verifiable_credential_verifier = Some(CredentialVerifier::new(verifier));
+        self
+    }
+
+    /// Sets the `token_binding_verifier` property of the MlsGroupConfig.
+    ///
+    /// The verifier checks the [`TokenBoundCredential`]s of new members when
+    /// they are added, of updated leaves in proposals and commits, and of all
+    /// members when joining from a Welcome. Without a verifier, members with
+    /// a token-bound credential are rejected.
+    ///
+    /// The verifier is not persisted with the configuration of a group. Set
+    /// it again with [`MlsGroup::set_token_binding_verifier()`] after loading
+    /// a group.
+    ///
+    /// [`TokenBoundCredential`]: crate::credentials::TokenBoundCredential
+    #[cfg(feature = "token-bound-credentials")]
+    pub fn token_binding_verifier(
+        mut self,
+        verifier: impl crate::credentials::TokenBindingVerifier + 'static,
+    ) -> Self {
+        self.config.token_binding_verifier = Some(TokenVerifier::new(verifier));
+        self
+    }
+
     /// Finalizes the builder and retursn an `[MlsGroupConfig`].
     pub fn build(self) -> MlsGroupConfig {
         self.config
     }
 }
 
+/// Defines how thoroughly incoming messages are validated.
+///
+/// With [`ValidationLevel::Standard`], all semantic validation steps listed in
+/// the [User Manual](https://openmls.tech/book/message_validation.html) are
+/// performed. [`ValidationLevel::Strict`] additionally performs checks that are
+/// only partially covered by the standard validation and fails closed where
+/// OpenMLS doesn't support a feature yet:
+///
+/// - ValSem105: The HPKE init keys of Add proposals must not be the encryption
+///   key of an existing member.
+/// - ValSem106: The key packages of Add proposals must be valid, i.e. their
+///   signature, lifetime and extensions are verified.
+/// - ValSem106: The leaf nodes of Update proposals and of the Commit's path
+///   must support the group's ciphersuite, version and required capabilities.
+/// - ValSem012: Messages from external senders are rejected unless they are
+///   listed in the group's external senders extension.
+///
+/// Violations are reported as [`StrictValidationError`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValidationLevel {
+    /// Perform the standard semantic validation.
+    Standard,
+    /// Perform the standard and all additional semantic validation.
+    Strict,
+}
+
+impl Default for ValidationLevel {
+    fn default() -> Self {
+        ValidationLevel::Standard
+    }
+}
+
+/// Defines how new members are handled whose credential has the same identity
+/// as the credential of an existing member, e.g. a second device of a user.
+///
+/// The policy applies to the members added by the own Add proposals and
+/// commits, as well as to incoming Add proposals and commits, including
+/// External Commits. Members that are removed by the same commit don't count
+/// as existing members, such that members can rejoin with an External Commit.
+/// Violations are reported as `DuplicateIdentity` errors.
+///
+/// New members with the signature key of an existing member are always
+/// rejected, since the MLS protocol requires signature keys to be unique.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuplicateIdentityPolicy {
+    /// New members with an existing identity are accepted.
+    Allow,
+    /// New members with an existing identity are rejected.
+    Reject,
+    /// The callback set with [`MlsGroup::set_duplicate_identity_callback()`]
+    /// decides whether a new member with an existing identity is accepted. If
+    /// no callback is set, the new member is rejected.
+    Callback,
+}
+
+impl DuplicateIdentityPolicy {
+    /// Returns `true` if the validation of Add proposals must accept
+    /// duplicate identities, because this policy may accept them.
+    pub(crate) fn allows_duplicate_identities(self) -> bool {
+        self != DuplicateIdentityPolicy::Reject
+    }
+}
+
+impl Default for DuplicateIdentityPolicy {
+    fn default() -> Self {
+        DuplicateIdentityPolicy::Reject
+    }
+}
+
+/// Defines how [`MlsGroup::new()`] chooses the group ID of a new group.
+///
+/// All strategies result in group IDs that are unlikely to collide with the
+/// group IDs of other groups.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupIdStrategy {
+    /// A random group ID of 16 bytes, see [`GroupId::random()`].
+    Random,
+    /// A random group ID in the format of a version 4 UUID, see
+    /// [`GroupId::uuid()`].
+    Uuid,
+    /// A group ID derived from the signature key of the creator and the
+    /// `context`, see [`GroupId::derive()`]. The `context` must be unique
+    /// among the groups of the creator, e.g. the name of a channel.
+    Derived {
+        /// Application-defined context of the group
+        context: Vec<u8>,
+    },
+}
+
+impl GroupIdStrategy {
+    /// Returns a group ID for a group created by the owner of the
+    /// `signature_key`.
+    pub(crate) fn group_id(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        signature_key: &SignaturePublicKey,
+    ) -> Result<GroupId, LibraryError> {
+        match self {
+            GroupIdStrategy::Random => Ok(GroupId::random(backend)),
+            GroupIdStrategy::Uuid => Ok(GroupId::uuid(backend)),
+            GroupIdStrategy::Derived { context } => {
+                GroupId::derive(backend, signature_key.as_slice(), context)
+            }
+        }
+    }
+}
+
+impl Default for GroupIdStrategy {
+    fn default() -> Self {
+        GroupIdStrategy::Random
+    }
+}
+
 /// Defines what wire format is acceptable for incoming handshake messages.
 /// Note that application messages must always be encrypted.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -261,6 +929,15 @@ impl WireFormatPolicy {
     pub fn incoming(&self) -> IncomingWireFormatPolicy {
         self.incoming
     }
+
+    /// Returns a copy of the wire format policy with the outgoing wire format
+    /// policy replaced by `outgoing`.
+    pub(crate) fn with_outgoing(self, outgoing: OutgoingWireFormatPolicy) -> Self {
+        Self {
+            outgoing,
+            incoming: self.incoming,
+        }
+    }
 }
 
 impl Default for WireFormatPolicy {
@@ -4,7 +4,9 @@ use crate::{
         core_group::create_commit_params::CreateCommitParams,
         errors::{CoreGroupBuildError, ExternalCommitError, WelcomeError},
     },
+    key_packages::errors::KeyPackageDeleteError,
     messages::VerifiableGroupInfo,
+    schedule::psk::PreSharedKeyId,
 };
 use tls_codec::Serialize;
 
@@ -13,7 +15,9 @@ use super::*;
 impl MlsGroup {
     // === Group creation ===
 
-    /// Creates a new group with the creator as the only member (and a random group ID).
+    /// Creates a new group with the creator as the only member. The group ID
+    /// is chosen according to the [`GroupIdStrategy`] of the
+    /// `mls_group_config`, which is a random group ID by default.
     ///
     /// This function removes the private key corresponding to the
     /// `key_package` from the key store.
@@ -25,12 +29,10 @@ impl MlsGroup {
         mls_group_config: &MlsGroupConfig,
         signature_key: &SignaturePublicKey,
     ) -> Result<Self, NewGroupError<KeyStore::Error>> {
-        Self::new_with_group_id(
-            backend,
-            mls_group_config,
-            GroupId::random(backend),
-            signature_key,
-        )
+        let group_id = mls_group_config
+            .group_id_strategy
+            .group_id(backend, signature_key)?;
+        Self::new_with_group_id(backend, mls_group_config, group_id, signature_key)
     }
 
     /// Creates a new group with a given group ID with the creator as the only member.
@@ -43,6 +45,12 @@ impl MlsGroup {
         group_id: GroupId,
         signature_key: &SignaturePublicKey,
     ) -> Result<Self, NewGroupError<KeyStore::Error>> {
+        #[cfg(feature = "fips")]
+        if !crate::ciphersuite::fips::is_approved_ciphersuite(
+            mls_group_config.crypto_config.ciphersuite,
+        ) {
+            return Err(NewGroupError::NotFipsApproved);
+        }
         // TODO #751
         let credential_bundle: CredentialBundle =
             backend
@@ -54,9 +62,10 @@ impl MlsGroup {
         let group_config = CoreGroupConfig {
             add_ratchet_tree_extension: mls_group_config.use_ratchet_tree_extension,
         };
-        let group = CoreGroup::builder(group_id, mls_group_config.crypto_config)
+        let mut group = CoreGroup::builder(group_id, mls_group_config.crypto_config)
             .with_config(group_config)
             .with_required_capabilities(mls_group_config.required_capabilities.clone())
+            .with_max_members(mls_group_config.max_members.map(MaxMembersExtension::new))
             .with_max_past_epoch_secrets(mls_group_config.max_past_epochs)
             .with_lifetime(*mls_group_config.lifetime())
             .build(&credential_bundle, backend)
@@ -68,6 +77,7 @@ impl MlsGroup {
                 CoreGroupBuildError::UnsupportedExtensionType => {
                     NewGroupError::UnsupportedExtensionType
                 }
+                CoreGroupBuildError::UnsupportedMlsVersion => NewGroupError::UnsupportedMlsVersion,
                 // We don't support PSKs yet
                 CoreGroupBuildError::PskError(e) => {
                     log::debug!("Unexpected PSK error: {:?}", e);
@@ -75,80 +85,146 @@ impl MlsGroup {
                 }
                 CoreGroupBuildError::KeyStoreError(e) => NewGroupError::KeyStoreError(e),
             })?;
+        group.set_allow_duplicate_identities(
+            mls_group_config
+                .duplicate_identity_policy
+                .allows_duplicate_identities(),
+        );
 
         let resumption_psk_store =
             ResumptionPskStore::new(mls_group_config.number_of_resumption_psks);
 
-        let mls_group = MlsGroup {
+        let mut mls_group = MlsGroup {
             mls_group_config: mls_group_config.clone(),
             group,
             proposal_store: ProposalStore::new(),
             own_leaf_nodes: vec![],
             aad: vec![],
             resumption_psk_store,
+            replay_window: ReplayWindow::new(mls_group_config.replay_window_size),
+            sender_ratchet_overrides: HashMap::new(),
+            duplicate_identity_callback: None,
+            state_change_callback: None,
+            membership_diff: None,
+            group_events: Vec::new(),
+            membership_history: Vec::new(),
+            expired_proposals: Vec::new(),
+            skipped_keys_evictions: Vec::new(),
+            stored_staged_commit: None,
+            merged_commit_digest: None,
             group_state: MlsGroupState::Operational,
+            persisted_components: HashMap::new(),
             state_changed: InnerState::Changed,
         };
+        mls_group.init_membership_history();
 
         Ok(mls_group)
     }
 
-    /// Creates a new group from a [`Welcome`] message. Returns an error
-    /// ([`WelcomeError::NoMatchingKeyPackage`]) if no [`KeyPackage`]
-    /// can be found.
+    /// Creates a new group from a [`Welcome`] message.
+    ///
+    /// The [`KeyPackage`] the client was added with is looked up in the key
+    /// store by the references of the new members in the Welcome, see
+    /// [`MlsGroup::welcome_key_package()`]. Returns an error
+    /// ([`WelcomeError::NoMatchingKeyPackage`]) if none of them refers to a
+    /// key package in the key store.
+    ///
+    /// This runs all stages of [`ProcessedWelcome`] and [`StagedWelcome`] at
+    /// once. Unlike with the stages, the key package is also deleted if
+    /// joining fails.
     pub fn new_from_welcome<KeyStore: OpenMlsKeyStore>(
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         mls_group_config: &MlsGroupConfig,
         welcome: Welcome,
         ratchet_tree: Option<Vec<Option<Node>>>,
     ) -> Result<Self, WelcomeError<KeyStore::Error>> {
-        let resumption_psk_store =
-            ResumptionPskStore::new(mls_group_config.number_of_resumption_psks);
-        let (key_package, _) = welcome
-            .secrets()
-            .iter()
-            .find_map(|egs| {
-                let hash_ref = egs.new_member().as_slice().to_vec();
-                backend
-                    .key_store()
-                    .read(&hash_ref)
-                    .map(|kp: KeyPackage| (kp, hash_ref))
-            })
+        let key_package_bundle = Self::welcome_key_package_bundle(backend, &welcome)
             .ok_or(WelcomeError::NoMatchingKeyPackage)?;
+        let key_package = key_package_bundle.key_package().clone();
 
-        // TODO #751
-        let private_key: Vec<u8> = backend
-            .key_store()
-            .read(key_package.hpke_init_key().as_slice())
-            .ok_or(WelcomeError::NoMatchingKeyPackage)?;
-        let key_package_bundle = KeyPackageBundle {
-            key_package,
-            private_key: private_key.into(),
-        };
+        let result = ProcessedWelcome::new_with_key_package_bundle(
+            backend,
+            mls_group_config,
+            welcome,
+            key_package_bundle,
+        )
+        .and_then(|processed_welcome| processed_welcome.into_staged_welcome(backend, ratchet_tree))
+        .and_then(|staged_welcome| staged_welcome.into_group(backend));
 
-        // Delete the [`KeyPackage`] and the corresponding private key from the
-        // key store
-        key_package_bundle
-            .key_package
-            .delete(backend)
-            .map_err(WelcomeError::KeyStoreError)?;
+        // Delete the [`KeyPackage`] and the corresponding private keys from
+        // the key store if joining failed. On success, this already happened
+        // in `StagedWelcome::into_group()`. The error of joining takes
+        // precedence over an error of the key store.
+        result.map_err(|e| {
+            let _ = Self::consume_key_package(backend, mls_group_config, &key_package);
+            e
+        })
+    }
 
-        let mut group =
-            CoreGroup::new_from_welcome(welcome, ratchet_tree, key_package_bundle, backend)?;
-        group.set_max_past_epochs(mls_group_config.max_past_epochs);
+    /// Deletes the [`KeyPackage`] the client was added with and the
+    /// corresponding private keys from the key store, unless it is kept as
+    /// last resort key package or by configuration. This has to happen after
+    /// the private key was used, since deleting also releases it in the
+    /// crypto provider.
+    pub(super) fn consume_key_package<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        key_package: &KeyPackage,
+    ) -> Result<(), WelcomeError<KeyStore::Error>> {
+        if mls_group_config.keep_consumed_key_packages || key_package.is_last_resort(backend) {
+            return Ok(());
+        }
+        key_package.delete(backend).map_err(|e| match e {
+            KeyPackageDeleteError::LibraryError(e) => WelcomeError::LibraryError(e),
+            KeyPackageDeleteError::KeyStoreError(e) => WelcomeError::KeyStoreError(e),
+        })?;
+        if let Some(replenishment) = &mls_group_config.key_package_replenishment {
+            let ciphersuite = key_package.ciphersuite();
+            replenishment.check(
+                ciphersuite,
+                KeyPackageInventory::load(backend).count(ciphersuite),
+            );
+        }
+        Ok(())
+    }
 
-        let mls_group = MlsGroup {
-            mls_group_config: mls_group_config.clone(),
-            group,
-            proposal_store: ProposalStore::new(),
-            own_leaf_nodes: vec![],
-            aad: vec![],
-            resumption_psk_store,
-            group_state: MlsGroupState::Operational,
-            state_changed: InnerState::Changed,
-        };
+    /// Returns the [`KeyPackage`] in the key store that the client was added
+    /// with in the [`Welcome`], or `None` if there is none.
+    ///
+    /// The Welcome contains the [`KeyPackageRef`](crate::ciphersuite::hash_ref::KeyPackageRef)
+    /// of every new member. The key package is the first one stored under
+    /// one of these references, such that the application doesn't need to
+    /// keep track of which of its key packages was used.
+    pub fn welcome_key_package(
+        backend: &impl OpenMlsCryptoProvider,
+        welcome: &Welcome,
+    ) -> Option<KeyPackage> {
+        Self::welcome_key_package_bundle(backend, welcome)
+            .map(|key_package_bundle| key_package_bundle.key_package)
+    }
 
-        Ok(mls_group)
+    /// Returns the [`KeyPackageBundle`] for [`MlsGroup::welcome_key_package()`].
+    /// A key package is only used if its reference matches the one it is
+    /// stored under and its private init key is in the key store.
+    pub(super) fn welcome_key_package_bundle(
+        backend: &impl OpenMlsCryptoProvider,
+        welcome: &Welcome,
+    ) -> Option<KeyPackageBundle> {
+        welcome.secrets().iter().find_map(|egs| {
+            let key_package_ref = egs.new_member();
+            let key_package: KeyPackage = backend.key_store().read(key_package_ref.as_slice())?;
+            if key_package.hash_ref(backend.crypto()).ok()? != key_package_ref {
+                return None;
+            }
+            // TODO #751
+            let private_key: Vec<u8> = backend
+                .key_store()
+                .read(key_package.hpke_init_key().as_slice())?;
+            Some(KeyPackageBundle {
+                key_package,
+                private_key: private_key.into(),
+            })
+        })
     }
 
     /// Join an existing group through an External Commit.
@@ -170,38 +246,105 @@ impl MlsGroup {
         aad: &[u8],
         credential_bundle: &CredentialBundle,
     ) -> Result<(Self, MlsMessageOut), ExternalCommitError> {
+        Self::join_by_external_commit_with_psks(
+            backend,
+            tree_option,
+            verifiable_group_info,
+            mls_group_config,
+            aad,
+            credential_bundle,
+            &[],
+        )
+    }
+
+    /// Join an existing group through an External Commit that includes a
+    /// PreSharedKey proposal for each of the given `psk_ids`.
+    ///
+    /// The PSKs have to be present in the key store of the joiner as well as
+    /// in the key stores of the existing group members, as they are injected
+    /// into the key schedule. This allows groups to require joiners to prove
+    /// the possession of an out-of-band secret. See
+    /// [`MlsGroupConfigBuilder::require_external_commit_psk()`] for the
+    /// corresponding check on the receiving side.
+    ///
+    /// Apart from that, this function behaves like
+    /// [`MlsGroup::join_by_external_commit()`].
+    pub fn join_by_external_commit_with_psks(
+        backend: &impl OpenMlsCryptoProvider,
+        tree_option: Option<&[Option<Node>]>,
+        verifiable_group_info: VerifiableGroupInfo,
+        mls_group_config: &MlsGroupConfig,
+        aad: &[u8],
+        credential_bundle: &CredentialBundle,
+        psk_ids: &[PreSharedKeyId],
+    ) -> Result<(Self, MlsMessageOut), ExternalCommitError> {
+        if !mls_group_config.is_ciphersuite_allowed(verifiable_group_info.ciphersuite()) {
+            return Err(ExternalCommitError::CiphersuiteNotAllowed);
+        }
+        let group_context = verifiable_group_info.group_context();
+        if mls_group_config.is_downgrade(
+            group_context.ciphersuite(),
+            group_context.protocol_version(),
+        ) {
+            return Err(ExternalCommitError::DowngradeDetected);
+        }
         let resumption_psk_store =
             ResumptionPskStore::new(mls_group_config.number_of_resumption_psks);
 
         // Prepare the commit parameters
         let framing_parameters = FramingParameters::new(aad, WireFormat::PublicMessage);
 
+        let psk_proposals = psk_ids
+            .iter()
+            .map(|psk_id| Proposal::PreSharedKey(PreSharedKeyProposal::new(psk_id.clone())))
+            .collect();
+
         let proposal_store = ProposalStore::new();
         let params = CreateCommitParams::builder()
             .framing_parameters(framing_parameters)
             .credential_bundle(credential_bundle)
             .proposal_store(&proposal_store)
+            .inline_proposals(psk_proposals)
             .build();
         let (mut group, create_commit_result) = CoreGroup::join_by_external_commit(
             backend,
             params,
             tree_option,
             verifiable_group_info,
+            mls_group_config.external_group_info_signers(),
         )?;
         group.set_max_past_epochs(mls_group_config.max_past_epochs);
+        group.set_allow_duplicate_identities(
+            mls_group_config
+                .duplicate_identity_policy
+                .allows_duplicate_identities(),
+        );
 
-        let mls_group = MlsGroup {
+        let mut mls_group = MlsGroup {
             mls_group_config: mls_group_config.clone(),
             group,
             proposal_store: ProposalStore::new(),
             own_leaf_nodes: vec![],
             aad: vec![],
             resumption_psk_store,
+            replay_window: ReplayWindow::new(mls_group_config.replay_window_size),
+            sender_ratchet_overrides: HashMap::new(),
+            duplicate_identity_callback: None,
+            state_change_callback: None,
+            membership_diff: None,
+            group_events: Vec::new(),
+            membership_history: Vec::new(),
+            expired_proposals: Vec::new(),
+            skipped_keys_evictions: Vec::new(),
+            stored_staged_commit: None,
+            merged_commit_digest: None,
             group_state: MlsGroupState::PendingCommit(Box::new(PendingCommitState::External(
                 create_commit_result.staged_commit,
             ))),
+            persisted_components: HashMap::new(),
             state_changed: InnerState::Changed,
         };
+        mls_group.init_membership_history();
 
         let public_message: PublicMessage = create_commit_result.commit.into();
         Ok((mls_group, public_message.into()))
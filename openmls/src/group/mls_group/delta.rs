@@ -0,0 +1,261 @@
+//! Incremental persistence of the group state, see
+//! [`MlsGroup::save_delta()`].
+
+use std::{collections::BTreeMap, fmt};
+
+use openmls_traits::crypto::OpenMlsCrypto;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::*;
+
+/// A component of the persisted state of an [`MlsGroup`] that can be persisted
+/// separately, see [`MlsGroup::save_delta()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum StateComponent {
+    /// The public ratchet tree.
+    PublicTree,
+    /// The own leaf nodes that were created for update proposals.
+    OwnLeafNodes,
+    /// The message secrets of the current and the past epochs.
+    MessageSecrets,
+    /// The pending and the expired proposals.
+    Proposals,
+    /// The remaining state, e.g. the configuration, the group context and the
+    /// epoch secrets.
+    Group,
+}
+
+impl StateComponent {
+    /// All components of the state.
+    const ALL: [StateComponent; 5] = [
+        StateComponent::PublicTree,
+        StateComponent::OwnLeafNodes,
+        StateComponent::MessageSecrets,
+        StateComponent::Proposals,
+        StateComponent::Group,
+    ];
+
+    /// The paths of the fields of the component in the serialized state. The
+    /// [`StateComponent::Group`] contains all fields that are not part of
+    /// another component.
+    fn fields(self) -> &'static [&'static [&'static str]] {
+        match self {
+            StateComponent::PublicTree => &[&["group", "tree"]],
+            StateComponent::OwnLeafNodes => &[&["own_leaf_nodes"]],
+            StateComponent::MessageSecrets => &[&["group", "message_secrets_store"]],
+            StateComponent::Proposals => &[&["proposal_store"], &["expired_proposals"]],
+            StateComponent::Group => &[],
+        }
+    }
+}
+
+/// The components of the state of an [`MlsGroup`] that changed since they were
+/// last persisted, see [`MlsGroup::save_delta()`].
+#[derive(Clone, Default)]
+pub struct StateDelta {
+    components: Vec<(StateComponent, Vec<u8>)>,
+}
+
+impl StateDelta {
+    /// Returns `true` if no component changed.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Returns an iterator over the changed components and their serialized
+    /// state.
+    pub fn components(&self) -> impl Iterator<Item = (StateComponent, &[u8])> {
+        self.components
+            .iter()
+            .map(|(component, state)| (*component, state.as_slice()))
+    }
+
+    /// Returns the changed components and their serialized state.
+    pub fn into_components(self) -> Vec<(StateComponent, Vec<u8>)> {
+        self.components
+    }
+}
+
+impl fmt::Debug for StateDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.components.iter().map(|(component, _)| component))
+            .finish()
+    }
+}
+
+impl MlsGroup {
+    /// Returns the components of the state that changed since they were last
+    /// persisted with [`MlsGroup::save_delta()`] or loaded with
+    /// [`MlsGroup::load_components()`]. All components are considered changed
+    /// if neither happened yet.
+    pub fn changed_components(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Vec<StateComponent>, LibraryError> {
+        Ok(self
+            .component_digests(backend)?
+            .into_iter()
+            .filter(|(component, _, digest)| {
+                self.persisted_components.get(component) != Some(digest)
+            })
+            .map(|(component, _, _)| component)
+            .collect())
+    }
+
+    /// Persists the components of the state that changed since they were last
+    /// persisted with [`MlsGroup::save_delta()`] or loaded with
+    /// [`MlsGroup::load_components()`], such that active groups don't have to
+    /// write their whole state after every message.
+    ///
+    /// The storage layer applies the returned [`StateDelta`] by replacing the
+    /// stored state of each contained component. The group can be loaded from
+    /// the stored components with [`MlsGroup::load_components()`].
+    pub fn save_delta(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<StateDelta, LibraryError> {
+        let mut delta = StateDelta::default();
+        let mut persisted_components = HashMap::new();
+        for (component, state, digest) in self.component_digests(backend)? {
+            if self.persisted_components.get(&component) != Some(&digest) {
+                delta.components.push((component, state));
+            }
+            persisted_components.insert(component, digest);
+        }
+        self.persisted_components = persisted_components;
+        self.state_changed = InnerState::Persisted;
+        Ok(delta)
+    }
+
+    /// Loads the state from all of its components persisted with
+    /// [`MlsGroup::save_delta()`].
+    pub fn load_components(
+        backend: &impl OpenMlsCryptoProvider,
+        components: impl IntoIterator<Item = (StateComponent, Vec<u8>)>,
+    ) -> Result<MlsGroup, DeserializeStateError> {
+        let components: BTreeMap<StateComponent, Vec<u8>> = components.into_iter().collect();
+
+        let mut state = Value::Object(Map::new());
+        for component in StateComponent::ALL {
+            let component_state = components
+                .get(&component)
+                .ok_or(DeserializeStateError::MalformedState)?;
+            let component_state: Value = serde_json::from_slice(component_state)
+                .map_err(|_| DeserializeStateError::MalformedState)?;
+            match component {
+                StateComponent::Group => merge_into(&mut state, component_state),
+                _ => {
+                    let mut fields = match component_state {
+                        Value::Object(fields) => fields,
+                        _ => return Err(DeserializeStateError::MalformedState),
+                    };
+                    for path in component.fields() {
+                        let field = fields
+                            .remove(&path.join("."))
+                            .ok_or(DeserializeStateError::MalformedState)?;
+                        insert_field(&mut state, path, field)
+                            .ok_or(DeserializeStateError::MalformedState)?;
+                    }
+                }
+            }
+        }
+
+        // TODO #245: Remove this once we have a proper serialization format
+        #[allow(deprecated)]
+        let serialized_mls_group = SerializedMlsGroup::from_json(state)
+            .map_err(|_| DeserializeStateError::MalformedState)?;
+        let mut mls_group = serialized_mls_group.into_mls_group();
+
+        let hash_type = mls_group.ciphersuite().hash_algorithm();
+        for (component, state) in components {
+            let digest = backend
+                .crypto()
+                .hash(hash_type, &state)
+                .map_err(LibraryError::unexpected_crypto_error)?;
+            mls_group.persisted_components.insert(component, digest);
+        }
+        Ok(mls_group)
+    }
+
+    /// Serializes the components of the state and returns them together with
+    /// their digest.
+    fn component_digests(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Vec<(StateComponent, Vec<u8>, Vec<u8>)>, LibraryError> {
+        let mut state = serde_json::to_value(self)
+            .map_err(|_| LibraryError::custom("Could not serialize the group state"))?;
+
+        let mut components = Vec::with_capacity(StateComponent::ALL.len());
+        for component in StateComponent::ALL {
+            let component_state = match component {
+                // The remaining fields once all other components were taken.
+                StateComponent::Group => std::mem::take(&mut state),
+                _ => {
+                    let mut fields = Map::new();
+                    for path in component.fields() {
+                        let field = take_field(&mut state, path)
+                            .ok_or_else(|| LibraryError::custom("Missing field of the state"))?;
+                        fields.insert(path.join("."), field);
+                    }
+                    Value::Object(fields)
+                }
+            };
+            let component_state = serde_json::to_vec(&component_state)
+                .map_err(|_| LibraryError::custom("Could not serialize the group state"))?;
+            let digest = backend
+                .crypto()
+                .hash(self.ciphersuite().hash_algorithm(), &component_state)
+                .map_err(LibraryError::unexpected_crypto_error)?;
+            components.push((component, component_state, digest));
+        }
+        Ok(components)
+    }
+}
+
+/// Removes the field at the `path` from the `state` and returns it.
+fn take_field(state: &mut Value, path: &[&str]) -> Option<Value> {
+    let (field, parents) = path.split_last()?;
+    let mut object = state.as_object_mut()?;
+    for parent in parents {
+        object = object.get_mut(*parent)?.as_object_mut()?;
+    }
+    object.remove(*field)
+}
+
+/// Inserts the `value` at the `path` into the `state`, creating the parent
+/// objects if necessary.
+fn insert_field(state: &mut Value, path: &[&str], value: Value) -> Option<()> {
+    let (field, parents) = path.split_last()?;
+    let mut object = state.as_object_mut()?;
+    for parent in parents {
+        object = object
+            .entry(*parent)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()?;
+    }
+    object.insert(field.to_string(), value);
+    Some(())
+}
+
+/// Merges the fields of the `other` object into the `state`, recursing into
+/// objects present in both.
+fn merge_into(state: &mut Value, other: Value) {
+    match (state, other) {
+        (Value::Object(state), Value::Object(other)) => {
+            for (key, value) in other {
+                match state.get_mut(&key) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge_into(existing, value)
+                    }
+                    _ => {
+                        state.insert(key, value);
+                    }
+                }
+            }
+        }
+        (state, other) => *state = other,
+    }
+}
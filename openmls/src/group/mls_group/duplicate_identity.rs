@@ -0,0 +1,175 @@
+//! Detection of new members whose credential has the same identity or
+//! signature key as the credential of an existing member, see
+//! [`DuplicateIdentityPolicy`].
+
+use std::{fmt, sync::Arc};
+
+use super::*;
+
+/// A callback that decides whether a new member is accepted although its
+/// credential (second argument) has the same identity as the credential of an
+/// existing member (first argument).
+#[derive(Clone)]
+pub(crate) struct DuplicateIdentityCallback(
+    Arc<dyn Fn(&Credential, &Credential) -> bool + Send + Sync>,
+);
+
+impl fmt::Debug for DuplicateIdentityCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DuplicateIdentityCallback")
+    }
+}
+
+/// Returns `true` if both credentials have the same signature key.
+fn has_same_signature_key(existing: &Credential, new: &Credential) -> bool {
+    existing.signature_key().as_slice() == new.signature_key().as_slice()
+}
+
+/// Returns `true` if both credentials have the same identity or signature
+/// key.
+fn is_duplicate(existing: &Credential, new: &Credential) -> bool {
+    existing.identity() == new.identity() || has_same_signature_key(existing, new)
+}
+
+impl MlsGroup {
+    /// Sets the callback that decides whether a new member is accepted
+    /// although its credential has the same identity as the credential of an
+    /// existing member. The callback is called with the
+    /// credential of the existing member and the credential of the new member
+    /// and returns `true` to accept the new member.
+    ///
+    /// The callback is only used with [`DuplicateIdentityPolicy::Callback`].
+    /// It is not persisted and has to be set again after the group was
+    /// loaded.
+    pub fn set_duplicate_identity_callback(
+        &mut self,
+        callback: impl Fn(&Credential, &Credential) -> bool + Send + Sync + 'static,
+    ) {
+        self.duplicate_identity_callback = Some(DuplicateIdentityCallback(Arc::new(callback)));
+    }
+
+    /// Returns `true` if the [`DuplicateIdentityPolicy`] of the group accepts
+    /// the `new_credentials` when the members at the `removed` leaves leave
+    /// the group at the same time. New credentials are also checked against
+    /// each other.
+    ///
+    /// New credentials with the signature key of an existing member are only
+    /// accepted by [`DuplicateIdentityPolicy::Allow`], which leaves their
+    /// rejection to the validation of the Add proposals.
+    pub(super) fn accepts_new_credentials<'a>(
+        &self,
+        new_credentials: impl IntoIterator<Item = &'a Credential>,
+        removed: &[LeafNodeIndex],
+    ) -> bool {
+        let policy = self.configuration().duplicate_identity_policy();
+        if policy == DuplicateIdentityPolicy::Allow {
+            return true;
+        }
+        let mut credentials: Vec<&Credential> = self
+            .members()
+            .filter(|member| !removed.contains(&member.index))
+            .filter_map(|member| self.member(member.index))
+            .collect();
+        for new_credential in new_credentials {
+            for &existing_credential in credentials
+                .iter()
+                .filter(|existing_credential| is_duplicate(existing_credential, new_credential))
+            {
+                let accepted = match (policy, &self.duplicate_identity_callback) {
+                    _ if has_same_signature_key(existing_credential, new_credential) => false,
+                    (DuplicateIdentityPolicy::Callback, Some(callback)) => {
+                        (callback.0)(existing_credential, new_credential)
+                    }
+                    _ => false,
+                };
+                if !accepted {
+                    return false;
+                }
+            }
+            credentials.push(new_credential);
+        }
+        true
+    }
+
+    /// Returns `true` if the [`DuplicateIdentityPolicy`] of the group accepts
+    /// the members added by the `proposals` and the `key_packages`.
+    pub(super) fn proposals_accept_key_packages<'a>(
+        &self,
+        proposals: impl Iterator<Item = &'a QueuedProposal>,
+        key_packages: &[KeyPackage],
+    ) -> bool {
+        let mut new_credentials = Vec::new();
+        let mut removed = Vec::new();
+        for queued_proposal in proposals {
+            match queued_proposal.proposal() {
+                Proposal::Add(add_proposal) => {
+                    new_credentials.push(add_proposal.key_package().leaf_node().credential())
+                }
+                Proposal::Remove(remove_proposal) => removed.push(remove_proposal.removed()),
+                _ => (),
+            }
+        }
+        new_credentials.extend(
+            key_packages
+                .iter()
+                .map(|key_package| key_package.leaf_node().credential()),
+        );
+        self.accepts_new_credentials(new_credentials, &removed)
+    }
+
+    /// Returns `true` if the [`DuplicateIdentityPolicy`] of the group accepts
+    /// the members added by the pending proposals and the `key_packages`.
+    pub(super) fn pending_proposals_accept_key_packages(
+        &self,
+        key_packages: &[KeyPackage],
+    ) -> bool {
+        self.proposals_accept_key_packages(self.proposal_store.proposals(), key_packages)
+    }
+
+    /// Returns `true` if the [`DuplicateIdentityPolicy`] of the group accepts
+    /// the members added by the Add proposal or the commit in the processed
+    /// message.
+    pub(super) fn processed_message_accepts_new_members(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> bool {
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => true,
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => self.accepts_new_credentials(
+                        [add_proposal.key_package().leaf_node().credential()],
+                        &[],
+                    ),
+                    _ => true,
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                let mut new_credentials: Vec<&Credential> = staged_commit
+                    .add_proposals()
+                    .map(|queued_add_proposal| {
+                        queued_add_proposal
+                            .add_proposal()
+                            .key_package()
+                            .leaf_node()
+                            .credential()
+                    })
+                    .collect();
+                // The sender of an external commit joins the group.
+                if processed_message.sender() == &Sender::NewMemberCommit {
+                    if let Some(leaf_node) = staged_commit.commit_update_key_package() {
+                        new_credentials.push(leaf_node.credential());
+                    }
+                }
+                let removed: Vec<LeafNodeIndex> = staged_commit
+                    .remove_proposals()
+                    .map(|queued_remove_proposal| {
+                        queued_remove_proposal.remove_proposal().removed()
+                    })
+                    .collect();
+                self.accepts_new_credentials(new_credentials, &removed)
+            }
+        }
+    }
+}
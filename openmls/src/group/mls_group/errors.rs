@@ -6,8 +6,12 @@
 // These errors are exposed through `crate::group::errors`.
 
 use crate::{
-    error::LibraryError,
-    group::errors::{CreateCommitError, MergeCommitError, StageCommitError, ValidationError},
+    error::{error_codes, LibraryError},
+    group::errors::{
+        CreateCommitError, ExternalCommitError, MergeCommitError, StageCommitError, ValidationError,
+    },
+    key_packages::errors::KeyPackageVerifyError,
+    treesync::node::leaf_node::CapabilitiesError,
 };
 use thiserror::Error;
 
@@ -32,8 +36,27 @@ pub enum NewGroupError<KeyStoreError> {
     /// Unsupported extension type in required capabilities.
     #[error("Unsupported extension type in required capabilities.")]
     UnsupportedExtensionType,
+    /// The requested MLS version is not supported.
+    #[error("The requested MLS version is not supported.")]
+    UnsupportedMlsVersion,
+    /// The ciphersuite is not approved in FIPS mode.
+    #[cfg(feature = "fips")]
+    #[error("The ciphersuite is not approved in FIPS mode.")]
+    NotFipsApproved,
 }
 
+error_codes!(NewGroupError<KeyStoreError>: 1600 {
+    LibraryError = 1,
+    NoMatchingKeyPackage = 2,
+    NoMatchingCredentialBundle = 3,
+    KeyStoreError = 4,
+    UnsupportedProposalType = 5,
+    UnsupportedExtensionType = 6,
+    UnsupportedMlsVersion = 7,
+    #[cfg(feature = "fips")]
+    NotFipsApproved = 8,
+});
+
 /// EmptyInput error
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum EmptyInputError {
@@ -45,6 +68,11 @@ pub enum EmptyInputError {
     RemoveMembers,
 }
 
+error_codes!(EmptyInputError: 1700 {
+    AddMembers = 1,
+    RemoveMembers = 2,
+});
+
 /// Group state error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum MlsGroupStateError {
@@ -65,6 +93,14 @@ pub enum MlsGroupStateError {
     NoPendingCommit,
 }
 
+error_codes!(MlsGroupStateError: 1800 {
+    LibraryError = 1,
+    UseAfterEviction = 2,
+    PendingProposal = 3,
+    PendingCommit = 4,
+    NoPendingCommit = 5,
+});
+
 /// Error merging pending commit
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum MergePendingCommitError<KeyStoreError> {
@@ -76,6 +112,27 @@ pub enum MergePendingCommitError<KeyStoreError> {
     MergeCommitError(#[from] MergeCommitError<KeyStoreError>),
 }
 
+error_codes!(MergePendingCommitError<KeyStoreError>: 1900 {
+    MlsGroupStateError = 1,
+    MergeCommitError = 2,
+});
+
+/// Catch up error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CatchUpError<KeyStoreError> {
+    /// See [`ProcessMessageError`] for more details.
+    #[error(transparent)]
+    ProcessMessageError(#[from] ProcessMessageError),
+    /// See [`MergeCommitError`] for more details.
+    #[error(transparent)]
+    MergeCommitError(#[from] MergeCommitError<KeyStoreError>),
+}
+
+error_codes!(CatchUpError<KeyStoreError>: 5500 {
+    ProcessMessageError = 1,
+    MergeCommitError = 2,
+});
+
 /// Process message error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ProcessMessageError {
@@ -100,8 +157,78 @@ pub enum ProcessMessageError {
     /// See [`StageCommitError`] for more details.
     #[error(transparent)]
     InvalidCommit(#[from] StageCommitError),
+    /// The handshake message has already been processed.
+    #[error("The handshake message has already been processed.")]
+    ReplayDetected,
+    /// See [`CompressionError`] for more details.
+    #[error(transparent)]
+    CompressionError(#[from] CompressionError),
+    /// See [`StrictValidationError`] for more details.
+    #[error(transparent)]
+    StrictValidationError(#[from] StrictValidationError),
+    /// The group would exceed the maximum number of members.
+    #[error("The group would exceed the maximum number of members.")]
+    TooManyMembers,
+    /// The identity or signature key of a new member already exists in the group.
+    #[error("The identity or signature key of a new member already exists in the group.")]
+    DuplicateIdentity,
+    /// A verifiable credential of a new or updated member could not be
+    /// verified.
+    #[cfg(feature = "verifiable-credentials")]
+    #[error("A verifiable credential of a new or updated member could not be verified.")]
+    InvalidVerifiableCredential,
+    /// The token binding of a new or updated member could not be verified.
+    #[cfg(feature = "token-bound-credentials")]
+    #[error("The token binding of a new or updated member could not be verified.")]
+    InvalidTokenBinding,
+    /// The publication of the key package of a new member could not be
+    /// verified.
+    #[error("The publication of the key package of a new member could not be verified.")]
+    UntrustedKeyPackage,
+    /// The proposal of a new member or an external sender was rejected by
+    /// the [`ExternalProposalLimiter`](crate::group::ExternalProposalLimiter)
+    /// of the group.
+    #[error("The proposal from outside of the group was rejected by the limiter of the group.")]
+    ExternalProposalRejected,
+    /// The commit was already merged, e.g. because the Delivery Service
+    /// delivered it again. The message can be ignored. For a PrivateMessage,
+    /// this is returned for any commit of the previous epoch, since its
+    /// confirmation tag can't be checked and it can't be merged anymore.
+    #[error("The commit was already merged.")]
+    AlreadyProcessed,
+    /// A ReInit proposal moves the group to a ciphersuite or a protocol
+    /// version that is weaker than the configured minimum.
+    #[error("A ReInit proposal moves the group to a ciphersuite or a protocol version that is weaker than the configured minimum.")]
+    DowngradeDetected,
+    /// The message uses another protocol version than the group.
+    #[error("The message uses another protocol version than the group.")]
+    VersionMismatch,
 }
 
+error_codes!(ProcessMessageError: 2000 {
+    LibraryError = 1,
+    IncompatibleWireFormat = 2,
+    ValidationError = 3,
+    GroupStateError = 4,
+    InvalidSignature = 5,
+    MissingSignatureKey = 6,
+    InvalidCommit = 7,
+    ReplayDetected = 8,
+    CompressionError = 9,
+    StrictValidationError = 10,
+    TooManyMembers = 11,
+    DuplicateIdentity = 12,
+    #[cfg(feature = "verifiable-credentials")]
+    InvalidVerifiableCredential = 13,
+    AlreadyProcessed = 14,
+    #[cfg(feature = "token-bound-credentials")]
+    InvalidTokenBinding = 15,
+    UntrustedKeyPackage = 16,
+    ExternalProposalRejected = 17,
+    DowngradeDetected = 18,
+    VersionMismatch = 19,
+});
+
 /// Create message error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum CreateMessageError {
@@ -114,8 +241,18 @@ pub enum CreateMessageError {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// See [`CompressionError`] for more details.
+    #[error(transparent)]
+    CompressionError(#[from] CompressionError),
 }
 
+error_codes!(CreateMessageError: 2100 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    GroupStateError = 3,
+    CompressionError = 4,
+});
+
 /// Add members error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum AddMembersError<KeyStoreError> {
@@ -134,8 +271,41 @@ pub enum AddMembersError<KeyStoreError> {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// The group would exceed the maximum number of members.
+    #[error("The group would exceed the maximum number of members.")]
+    TooManyMembers,
+    /// The identity or signature key of a new member already exists in the group.
+    #[error("The identity or signature key of a new member already exists in the group.")]
+    DuplicateIdentity,
+    /// The verifiable credential of a new member could not be verified.
+    #[cfg(feature = "verifiable-credentials")]
+    #[error("The verifiable credential of a new member could not be verified.")]
+    InvalidVerifiableCredential,
+    /// The token binding of a new member could not be verified.
+    #[cfg(feature = "token-bound-credentials")]
+    #[error("The token binding of a new member could not be verified.")]
+    InvalidTokenBinding,
+    /// The publication of the key package of a new member could not be
+    /// verified.
+    #[error("The publication of the key package of a new member could not be verified.")]
+    UntrustedKeyPackage,
 }
 
+error_codes!(AddMembersError<KeyStoreError>: 2200 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    EmptyInput = 3,
+    CreateCommitError = 4,
+    GroupStateError = 5,
+    TooManyMembers = 6,
+    DuplicateIdentity = 7,
+    #[cfg(feature = "verifiable-credentials")]
+    InvalidVerifiableCredential = 8,
+    #[cfg(feature = "token-bound-credentials")]
+    InvalidTokenBinding = 9,
+    UntrustedKeyPackage = 10,
+});
+
 /// Propose add members error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ProposeAddMemberError {
@@ -151,8 +321,40 @@ pub enum ProposeAddMemberError {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// The group would exceed the maximum number of members.
+    #[error("The group would exceed the maximum number of members.")]
+    TooManyMembers,
+    /// The identity or signature key of a new member already exists in the group.
+    #[error("The identity or signature key of a new member already exists in the group.")]
+    DuplicateIdentity,
+    /// The verifiable credential of a new member could not be verified.
+    #[cfg(feature = "verifiable-credentials")]
+    #[error("The verifiable credential of a new member could not be verified.")]
+    InvalidVerifiableCredential,
+    /// The token binding of a new member could not be verified.
+    #[cfg(feature = "token-bound-credentials")]
+    #[error("The token binding of a new member could not be verified.")]
+    InvalidTokenBinding,
+    /// The publication of the key package of a new member could not be
+    /// verified.
+    #[error("The publication of the key package of a new member could not be verified.")]
+    UntrustedKeyPackage,
 }
 
+error_codes!(ProposeAddMemberError: 2300 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    UnsupportedExtensions = 3,
+    GroupStateError = 4,
+    TooManyMembers = 5,
+    DuplicateIdentity = 6,
+    #[cfg(feature = "verifiable-credentials")]
+    InvalidVerifiableCredential = 7,
+    #[cfg(feature = "token-bound-credentials")]
+    InvalidTokenBinding = 8,
+    UntrustedKeyPackage = 9,
+});
+
 /// Propose remove members error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ProposeRemoveMemberError {
@@ -170,6 +372,13 @@ pub enum ProposeRemoveMemberError {
     UnknownMember,
 }
 
+error_codes!(ProposeRemoveMemberError: 2400 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    GroupStateError = 3,
+    UnknownMember = 4,
+});
+
 /// Remove members error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum RemoveMembersError<KeyStoreError> {
@@ -191,8 +400,21 @@ pub enum RemoveMembersError<KeyStoreError> {
     /// The member that should be removed can not be found.
     #[error("The member that should be removed can not be found.")]
     UnknownMember,
+    /// Several members match the member that should be removed.
+    #[error("Several members match the member that should be removed.")]
+    AmbiguousMember,
 }
 
+error_codes!(RemoveMembersError<KeyStoreError>: 2500 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    EmptyInput = 3,
+    CreateCommitError = 4,
+    GroupStateError = 5,
+    UnknownMember = 6,
+    AmbiguousMember = 7,
+});
+
 /// Leave group error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum LeaveGroupError {
@@ -207,6 +429,12 @@ pub enum LeaveGroupError {
     GroupStateError(#[from] MlsGroupStateError),
 }
 
+error_codes!(LeaveGroupError: 2600 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    GroupStateError = 3,
+});
+
 /// Self update error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum SelfUpdateError<KeyStoreError> {
@@ -227,6 +455,14 @@ pub enum SelfUpdateError<KeyStoreError> {
     KeyStoreError,
 }
 
+error_codes!(SelfUpdateError<KeyStoreError>: 2700 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    CreateCommitError = 3,
+    GroupStateError = 4,
+    KeyStoreError = 5,
+});
+
 /// Propose self update error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ProposeSelfUpdateError<KeyStoreError> {
@@ -242,8 +478,19 @@ pub enum ProposeSelfUpdateError<KeyStoreError> {
     /// Error accessing the key store.
     #[error("Error accessing the key store.")]
     KeyStoreError(KeyStoreError),
+    /// The identity of the new credential differs from the identity of the own leaf.
+    #[error("The identity of the new credential differs from the identity of the own leaf.")]
+    IdentityMismatch,
 }
 
+error_codes!(ProposeSelfUpdateError<KeyStoreError>: 2800 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    GroupStateError = 3,
+    KeyStoreError = 4,
+    IdentityMismatch = 5,
+});
+
 /// Commit to pending proposals error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum CommitToPendingProposalsError<KeyStoreError> {
@@ -259,8 +506,28 @@ pub enum CommitToPendingProposalsError<KeyStoreError> {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// The group would exceed the maximum number of members.
+    #[error("The group would exceed the maximum number of members.")]
+    TooManyMembers,
+    /// The identity or signature key of a new member already exists in the group.
+    #[error("The identity or signature key of a new member already exists in the group.")]
+    DuplicateIdentity,
+    /// The publication of the key package of a new member could not be
+    /// verified.
+    #[error("The publication of the key package of a new member could not be verified.")]
+    UntrustedKeyPackage,
 }
 
+error_codes!(CommitToPendingProposalsError<KeyStoreError>: 2900 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    CreateCommitError = 3,
+    GroupStateError = 4,
+    TooManyMembers = 5,
+    DuplicateIdentity = 6,
+    UntrustedKeyPackage = 7,
+});
+
 /// Errors that can happen when exporting a group info object.
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ExportGroupInfoError {
@@ -275,6 +542,12 @@ pub enum ExportGroupInfoError {
     GroupStateError(#[from] MlsGroupStateError),
 }
 
+error_codes!(ExportGroupInfoError: 3000 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    GroupStateError = 3,
+});
+
 /// Export secret error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ExportSecretError {
@@ -288,3 +561,420 @@ pub enum ExportSecretError {
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
 }
+
+error_codes!(ExportSecretError: 3100 {
+    LibraryError = 1,
+    KeyLengthTooLong = 2,
+    GroupStateError = 3,
+});
+
+/// Migrate ciphersuite error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum MigrateCiphersuiteError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The own CredentialBundle could not be found in the key store.
+    #[error("The own CredentialBundle could not be found in the key store.")]
+    NoMatchingCredentialBundle,
+    /// A KeyPackage doesn't use the new ciphersuite.
+    #[error("A KeyPackage doesn't use the new ciphersuite.")]
+    CiphersuiteMismatch,
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError<KeyStoreError>),
+    /// See [`MergeCommitError`] for more details.
+    #[error(transparent)]
+    MergeCommitError(#[from] MergeCommitError<KeyStoreError>),
+    /// See [`NewGroupError`] for more details.
+    #[error(transparent)]
+    NewGroupError(#[from] NewGroupError<KeyStoreError>),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The new ciphersuite is weaker than the configured minimum.
+    #[error("The new ciphersuite is weaker than the configured minimum.")]
+    DowngradeDetected,
+}
+
+error_codes!(MigrateCiphersuiteError<KeyStoreError>: 3200 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    CiphersuiteMismatch = 3,
+    CreateCommitError = 4,
+    MergeCommitError = 5,
+    NewGroupError = 6,
+    GroupStateError = 7,
+    DowngradeDetected = 8,
+});
+
+/// Wire format override error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum WireFormatOverrideError {
+    /// The wire format can't be used for handshake messages.
+    #[error("The wire format can't be used for handshake messages.")]
+    UnsupportedWireFormat,
+    /// The wire format is not allowed by the group's wire format policy.
+    #[error("The wire format is not allowed by the group's wire format policy.")]
+    IncompatibleWireFormat,
+}
+
+error_codes!(WireFormatOverrideError: 4900 {
+    UnsupportedWireFormat = 1,
+    IncompatibleWireFormat = 2,
+});
+
+/// Compression error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CompressionError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The group compresses application messages, but compression support is not enabled.
+    #[error("The group compresses application messages, but compression support is not enabled.")]
+    CompressionNotSupported,
+    /// The application message could not be decompressed.
+    #[error("The application message could not be decompressed.")]
+    MalformedPayload,
+    /// The decompressed application message exceeds the maximum size.
+    #[error("The decompressed application message exceeds the maximum size.")]
+    PayloadTooLarge,
+}
+
+error_codes!(CompressionError: 5000 {
+    LibraryError = 1,
+    CompressionNotSupported = 2,
+    MalformedPayload = 3,
+    PayloadTooLarge = 4,
+});
+
+/// Strict validation error
+///
+/// These checks are only performed if the group uses
+/// [`ValidationLevel::Strict`](super::config::ValidationLevel::Strict). Every
+/// error names the semantic validation rule it violates, see also
+/// [`StrictValidationError::rule()`].
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum StrictValidationError {
+    /// ValSem105: The HPKE init key of an Add proposal is the encryption key of an existing member.
+    #[error("ValSem105: The HPKE init key of an Add proposal is the encryption key of an existing member.")]
+    ExistingInitKeyAddProposal,
+    /// ValSem106: The key package of an Add proposal is invalid.
+    #[error("ValSem106: The key package of an Add proposal is invalid: {0}")]
+    InvalidKeyPackage(KeyPackageVerifyError),
+    /// ValSem106: The capabilities of the leaf node of an Update proposal are insufficient for this group.
+    #[error("ValSem106: The capabilities of the leaf node of an Update proposal are insufficient for this group.")]
+    InsufficientUpdateCapabilities,
+    /// ValSem106: The capabilities of the leaf node in the Commit's path are insufficient for this group.
+    #[error("ValSem106: The capabilities of the leaf node in the Commit's path are insufficient for this group.")]
+    InsufficientPathCapabilities,
+    /// ValSem012: The external sender is not listed in the external senders extension of the group.
+    #[error("ValSem012: The external sender is not listed in the external senders extension of the group.")]
+    ExternalSender,
+}
+
+impl StrictValidationError {
+    /// Returns the semantic validation rule that was violated, e.g.
+    /// `"ValSem105"`.
+    pub fn rule(&self) -> &'static str {
+        match self {
+            StrictValidationError::ExistingInitKeyAddProposal => "ValSem105",
+            StrictValidationError::InvalidKeyPackage(_)
+            | StrictValidationError::InsufficientUpdateCapabilities
+            | StrictValidationError::InsufficientPathCapabilities => "ValSem106",
+            StrictValidationError::ExternalSender => "ValSem012",
+        }
+    }
+}
+
+error_codes!(StrictValidationError: 5300 {
+    ExistingInitKeyAddProposal = 1,
+    InvalidKeyPackage = 2,
+    InsufficientUpdateCapabilities = 3,
+    InsufficientPathCapabilities = 4,
+    ExternalSender = 5,
+});
+
+/// Async key store error
+#[cfg(feature = "async")]
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum AsyncKeyStoreError<KeyStoreError> {
+    /// A value could not be serialized for the key store.
+    #[error("A value could not be serialized for the key store.")]
+    SerializationError,
+    /// Error accessing the async key store.
+    #[error(transparent)]
+    KeyStoreError(KeyStoreError),
+}
+
+#[cfg(feature = "async")]
+error_codes!(AsyncKeyStoreError<KeyStoreError>: 4800 {
+    SerializationError = 1,
+    KeyStoreError = 2,
+});
+
+/// Async process message error
+#[cfg(feature = "async")]
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ProcessMessageAsyncError<KeyStoreError> {
+    /// See [`ProcessMessageError`] for more details.
+    #[error(transparent)]
+    ProcessMessageError(#[from] ProcessMessageError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
+#[cfg(feature = "async")]
+error_codes!(ProcessMessageAsyncError<KeyStoreError>: 7400 {
+    ProcessMessageError = 1,
+    KeyStoreError = 2,
+});
+
+/// Rejoin error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RejoinError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The own CredentialBundle could not be found in the key store.
+    #[error("The own CredentialBundle could not be found in the key store.")]
+    NoMatchingCredentialBundle,
+    /// The GroupInfo of the group could not be obtained.
+    #[error("The GroupInfo of the group could not be obtained.")]
+    MissingGroupInfo,
+    /// The GroupInfo belongs to a different group.
+    #[error("The GroupInfo belongs to a different group.")]
+    WrongGroupId,
+    /// See [`ExternalCommitError`] for more details.
+    #[error(transparent)]
+    ExternalCommitError(#[from] ExternalCommitError),
+}
+
+error_codes!(RejoinError: 5400 {
+    LibraryError = 1,
+    GroupStateError = 2,
+    NoMatchingCredentialBundle = 3,
+    MissingGroupInfo = 4,
+    WrongGroupId = 5,
+    ExternalCommitError = 6,
+});
+
+/// Update external senders error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum UpdateExternalSendersError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The own CredentialBundle could not be found in the key store.
+    #[error("The own CredentialBundle could not be found in the key store.")]
+    NoMatchingCredentialBundle,
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError<KeyStoreError>),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The external sender is already listed in the external senders extension.
+    #[error("The external sender is already listed in the external senders extension.")]
+    DuplicateExternalSender,
+    /// The external sender is not listed in the external senders extension.
+    #[error("The external sender is not listed in the external senders extension.")]
+    UnknownExternalSender,
+}
+
+error_codes!(UpdateExternalSendersError<KeyStoreError>: 5800 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    CreateCommitError = 3,
+    GroupStateError = 4,
+    DuplicateExternalSender = 5,
+    UnknownExternalSender = 6,
+});
+
+/// Signature key rotation error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SignatureKeyRotationError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
+error_codes!(SignatureKeyRotationError<KeyStoreError>: 5900 {
+    LibraryError = 1,
+    KeyStoreError = 2,
+});
+
+/// Deserialize state error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum DeserializeStateError {
+    /// The serialized state is malformed.
+    #[error("The serialized state is malformed.")]
+    MalformedState,
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+}
+
+error_codes!(DeserializeStateError: 6100 {
+    MalformedState = 1,
+    LibraryError = 2,
+});
+
+/// Propose and commit error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ProposeAndCommitError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`ProposeAddMemberError`] for more details.
+    #[error(transparent)]
+    ProposeAddMemberError(#[from] ProposeAddMemberError),
+    /// See [`ProposeRemoveMemberError`] for more details.
+    #[error(transparent)]
+    ProposeRemoveMemberError(#[from] ProposeRemoveMemberError),
+    /// See [`CommitToPendingProposalsError`] for more details.
+    #[error(transparent)]
+    CommitToPendingProposalsError(#[from] CommitToPendingProposalsError<KeyStoreError>),
+}
+
+error_codes!(ProposeAndCommitError<KeyStoreError>: 6200 {
+    LibraryError = 1,
+    ProposeAddMemberError = 2,
+    ProposeRemoveMemberError = 3,
+    CommitToPendingProposalsError = 4,
+});
+
+/// Wipe error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum WipeError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
+error_codes!(WipeError<KeyStoreError>: 6400 {
+    LibraryError = 1,
+    KeyStoreError = 2,
+});
+
+/// Update ban list error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum UpdateBanListError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The own CredentialBundle could not be found in the key store.
+    #[error("The own CredentialBundle could not be found in the key store.")]
+    NoMatchingCredentialBundle,
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError<KeyStoreError>),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+}
+
+error_codes!(UpdateBanListError<KeyStoreError>: 6500 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    CreateCommitError = 3,
+    GroupStateError = 4,
+});
+
+/// Pin message secrets error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum PinMessageSecretsError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The message secrets of the epoch are not available.
+    #[error("The message secrets of the epoch are not available.")]
+    UnknownEpoch,
+    /// The sender was not a member of the group in the epoch.
+    #[error("The sender was not a member of the group in the epoch.")]
+    UnknownSender,
+    /// The message secrets of the sender were already deleted.
+    #[error("The message secrets of the sender were already deleted.")]
+    SecretsDeleted,
+}
+
+error_codes!(PinMessageSecretsError: 6700 {
+    LibraryError = 1,
+    UnknownEpoch = 2,
+    UnknownSender = 3,
+    SecretsDeleted = 4,
+});
+
+/// Short authentication string error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ShortAuthenticationStringError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// No member of the group has the given identity.
+    #[error("No member of the group has the given identity.")]
+    UnknownMember,
+}
+
+error_codes!(ShortAuthenticationStringError: 7000 {
+    LibraryError = 1,
+    GroupStateError = 2,
+    UnknownMember = 3,
+});
+
+/// Update capabilities error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum UpdateCapabilitiesError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The own CredentialBundle could not be found in the key store.
+    #[error("The own CredentialBundle could not be found in the key store.")]
+    NoMatchingCredentialBundle,
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError<KeyStoreError>),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+    /// The capabilities don't support the ciphersuite or the protocol version of the group.
+    #[error(
+        "The capabilities don't support the ciphersuite or the protocol version of the group."
+    )]
+    UnsupportedCiphersuiteOrVersion,
+    /// The capabilities don't support the required capabilities of the group.
+    #[error(transparent)]
+    CapabilitiesError(#[from] CapabilitiesError),
+    /// The capabilities don't support an extension of the group context or of the leaf node.
+    #[error(
+        "The capabilities don't support an extension of the group context or of the leaf node."
+    )]
+    UnsupportedExtension,
+}
+
+error_codes!(UpdateCapabilitiesError<KeyStoreError>: 7200 {
+    LibraryError = 1,
+    NoMatchingCredentialBundle = 2,
+    CreateCommitError = 3,
+    GroupStateError = 4,
+    KeyStoreError = 5,
+    UnsupportedCiphersuiteOrVersion = 6,
+    CapabilitiesError = 7,
+    UnsupportedExtension = 8,
+});
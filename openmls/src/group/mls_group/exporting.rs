@@ -1,13 +1,16 @@
 use tls_codec::Serialize;
 
-use crate::{group::errors::ExporterError, schedule::EpochAuthenticator};
+use crate::{
+    ciphersuite::HpkePublicKey, group::errors::ExporterError, schedule::EpochAuthenticator,
+};
 
 use super::*;
 
 impl MlsGroup {
     // === Export secrets ===
 
-    /// Exports a secret from the current epoch.
+    /// Exports a secret from the current epoch. The returned
+    /// [`ExportedSecret`] is overwritten with zeros when it is dropped.
     /// Returns [`ExportSecretError::KeyLengthTooLong`] if the requested
     /// key length is too long.
     /// Returns [`ExportSecretError::GroupStateError(MlsGroupStateError::UseAfterEviction)`](MlsGroupStateError::UseAfterEviction)
@@ -18,7 +21,7 @@ impl MlsGroup {
         label: &str,
         context: &[u8],
         key_length: usize,
-    ) -> Result<Vec<u8>, ExportSecretError> {
+    ) -> Result<ExportedSecret, ExportSecretError> {
         if self.is_active() {
             Ok(self
                 .group
@@ -50,11 +53,46 @@ impl MlsGroup {
         self.resumption_psk_store.get(epoch)
     }
 
+    /// Returns the public key of the external key pair of the current epoch,
+    /// i.e. the content of the external_pub extension of the group infos of
+    /// this epoch. New members encrypt the init secret of their external
+    /// commits to it.
+    ///
+    /// The external key pair is derived from the epoch secrets, so it
+    /// changes with every epoch. Group infos that are served for external
+    /// commits have to be replaced after each merged commit.
+    pub fn external_pub(&self, backend: &impl OpenMlsCryptoProvider) -> HpkePublicKey {
+        self.group.external_pub(backend)
+    }
+
     /// Export a group info object for this group.
+    ///
+    /// The group info contains the external_pub extension, such that it can
+    /// be used to join the group with an external commit.
     pub fn export_group_info(
         &self,
         backend: &impl OpenMlsCryptoProvider,
         with_ratchet_tree: bool,
+    ) -> Result<MlsMessageOut, ExportGroupInfoError> {
+        self.export_group_info_with_external_pub(backend, with_ratchet_tree, true)
+    }
+
+    /// Export a group info object for this group without the external_pub
+    /// extension, e.g. for groups that don't allow external commits. The
+    /// group info can't be used to join the group with an external commit.
+    pub fn export_group_info_without_external_pub(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        with_ratchet_tree: bool,
+    ) -> Result<MlsMessageOut, ExportGroupInfoError> {
+        self.export_group_info_with_external_pub(backend, with_ratchet_tree, false)
+    }
+
+    fn export_group_info_with_external_pub(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        with_ratchet_tree: bool,
+        with_external_pub: bool,
     ) -> Result<MlsMessageOut, ExportGroupInfoError> {
         match self.credential() {
             Ok(credential) => {
@@ -69,7 +107,12 @@ impl MlsGroup {
                     .ok_or(ExportGroupInfoError::NoMatchingCredentialBundle)?;
                 Ok(self
                     .group
-                    .export_group_info(backend, &credential_bundle, with_ratchet_tree)?
+                    .export_group_info_with_external_pub(
+                        backend,
+                        &credential_bundle,
+                        with_ratchet_tree,
+                        with_external_pub,
+                    )?
                     .into())
             }
             Err(e) => Err(e.into()),
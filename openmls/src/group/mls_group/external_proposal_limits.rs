@@ -0,0 +1,122 @@
+//! Rate limiting of proposals from outside of the group with the
+//! [`ExternalProposalLimiter`] of the application.
+
+use std::{fmt, sync::Arc};
+
+use super::*;
+
+/// The source of a proposal from outside of the group, see
+/// [`ExternalProposalLimiter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalProposalSource {
+    sender: Sender,
+    credential: Option<Credential>,
+}
+
+impl ExternalProposalSource {
+    /// Returns the sender of the proposal, i.e. either
+    /// [`Sender::NewMemberProposal`] or [`Sender::External`].
+    pub fn sender(&self) -> &Sender {
+        &self.sender
+    }
+
+    /// Returns the credential in the key package of an Add proposal of a
+    /// [`Sender::NewMemberProposal`].
+    ///
+    /// **The credential is not verified yet**, since the hook is called before
+    /// the signature and the key package of the proposal are validated. It
+    /// must only be used to attribute proposals, e.g. to count them per
+    /// identity, and not to trust them.
+    pub fn credential(&self) -> Option<&Credential> {
+        self.credential.as_ref()
+    }
+}
+
+/// Decides whether proposals from outside of the group are processed, e.g.
+/// to rate-limit or deny-list their sources.
+///
+/// The limiter is called for every message of a [`Sender::NewMemberProposal`]
+/// or a [`Sender::External`] before the message is validated, such that
+/// members don't spend resources on proposals they are spammed with. It
+/// returns `true` if the proposal should be processed.
+///
+/// Closures `Fn(&ExternalProposalSource) -> bool` implement this trait.
+pub trait ExternalProposalLimiter: Send + Sync {
+    /// Returns `true` if the proposal from the `source` should be processed.
+    fn allow(&self, source: &ExternalProposalSource) -> bool;
+}
+
+impl<F> ExternalProposalLimiter for F
+where
+    F: Fn(&ExternalProposalSource) -> bool + Send + Sync,
+{
+    fn allow(&self, source: &ExternalProposalSource) -> bool {
+        self(source)
+    }
+}
+
+/// The [`ExternalProposalLimiter`] in the [`MlsGroupConfig`].
+#[derive(Clone)]
+pub(crate) struct ProposalLimiter(Arc<dyn ExternalProposalLimiter>);
+
+impl ProposalLimiter {
+    pub(crate) fn new(limiter: impl ExternalProposalLimiter + 'static) -> Self {
+        Self(Arc::new(limiter))
+    }
+}
+
+impl fmt::Debug for ProposalLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProposalLimiter")
+    }
+}
+
+impl PartialEq for ProposalLimiter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProposalLimiter {}
+
+impl MlsGroup {
+    /// Sets the [`ExternalProposalLimiter`] of the group, e.g. after the group
+    /// was loaded, since limiters are not persisted. See
+    /// [`MlsGroupConfigBuilder::external_proposal_limiter()`] for more
+    /// information.
+    pub fn set_external_proposal_limiter(
+        &mut self,
+        limiter: impl ExternalProposalLimiter + 'static,
+    ) {
+        self.mls_group_config.external_proposal_limiter = Some(ProposalLimiter::new(limiter));
+    }
+
+    /// Returns `true` if the `message` is not a proposal from outside of the
+    /// group, if the group has no limiter or if the limiter allows the
+    /// proposal.
+    pub(super) fn allows_external_proposal(&self, message: &ProtocolMessage) -> bool {
+        let limiter = match &self.mls_group_config.external_proposal_limiter {
+            Some(limiter) => limiter,
+            None => return true,
+        };
+        let public_message = match message.body() {
+            ProtocolMessageBody::PublicMessage(public_message) => public_message,
+            // Proposals from outside of the group can't be encrypted.
+            ProtocolMessageBody::PrivateMessage(_) => return true,
+        };
+        let sender = public_message.sender();
+        if !matches!(sender, Sender::NewMemberProposal | Sender::External(_)) {
+            return true;
+        }
+        let credential = match public_message.content() {
+            FramedContentBody::Proposal(Proposal::Add(add_proposal)) => {
+                Some(add_proposal.key_package().leaf_node().credential().clone())
+            }
+            _ => None,
+        };
+        limiter.0.allow(&ExternalProposalSource {
+            sender: sender.clone(),
+            credential,
+        })
+    }
+}
@@ -0,0 +1,157 @@
+//! External senders of an [`MlsGroup`].
+//!
+//! This module contains the functions to add and remove entries of the
+//! external senders extension of a group through a GroupContextExtensions
+//! proposal.
+
+use core_group::create_commit_params::CreateCommitParams;
+use tls_codec::Serialize;
+
+use crate::{
+    extensions::{Extension, ExtensionType, ExternalSender, ExternalSendersExtension},
+    messages::GroupInfo,
+};
+
+use super::{errors::UpdateExternalSendersError, *};
+
+impl MlsGroup {
+    /// Returns the external senders of the group, i.e. the entries of the
+    /// external senders extension in the group context. Messages from an
+    /// [`External`](crate::framing::Sender::External) sender are only
+    /// accepted if the sender index points to one of these entries.
+    pub fn external_senders(&self) -> &[ExternalSender] {
+        self.group
+            .group_context_extensions()
+            .external_senders()
+            .map(|external_senders| external_senders.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Adds `external_sender` to the end of the external senders extension of
+    /// the group.
+    ///
+    /// This creates a Commit that covers a GroupContextExtensions proposal
+    /// with the updated extensions. See
+    /// [`update_external_senders()`](Self::update_external_senders()) for
+    /// details.
+    ///
+    /// Returns an error if the external sender is already listed or if there
+    /// is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn add_external_sender<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        external_sender: ExternalSender,
+    ) -> Result<(MlsMessageOut, Option<GroupInfo>), UpdateExternalSendersError<KeyStore::Error>>
+    {
+        if self.external_senders().contains(&external_sender) {
+            return Err(UpdateExternalSendersError::DuplicateExternalSender);
+        }
+        let mut external_senders = self.external_senders().to_vec();
+        external_senders.push(external_sender);
+        self.update_external_senders(backend, external_senders)
+    }
+
+    /// Removes `external_sender` from the external senders extension of the
+    /// group.
+    ///
+    /// External senders are identified by their index in the extension, so
+    /// the indices of all external senders after the removed one decrease by
+    /// one.
+    ///
+    /// This creates a Commit that covers a GroupContextExtensions proposal
+    /// with the updated extensions. See
+    /// [`update_external_senders()`](Self::update_external_senders()) for
+    /// details.
+    ///
+    /// Returns an error if the external sender is not listed or if there is a
+    /// pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn remove_external_sender<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        external_sender: &ExternalSender,
+    ) -> Result<(MlsMessageOut, Option<GroupInfo>), UpdateExternalSendersError<KeyStore::Error>>
+    {
+        let mut external_senders = self.external_senders().to_vec();
+        let position = external_senders
+            .iter()
+            .position(|listed_sender| listed_sender == external_sender)
+            .ok_or(UpdateExternalSendersError::UnknownExternalSender)?;
+        external_senders.remove(position);
+        self.update_external_senders(backend, external_senders)
+    }
+
+    /// Replaces the external senders extension of the group with
+    /// `external_senders`. The extension is removed from the group context if
+    /// `external_senders` is empty.
+    ///
+    /// This creates a Commit that only covers a GroupContextExtensions
+    /// proposal with the updated extensions. Pending proposals are not
+    /// included. Once the Commit is merged with
+    /// [`merge_pending_commit()`](Self::merge_pending_commit()), messages from
+    /// external senders are only accepted if they are listed in the new
+    /// extension.
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`] (containing the
+    /// commit) and the [GroupInfo]. The [GroupInfo] is [Some] if the group
+    /// has the `use_ratchet_tree_extension` flag set.
+    ///
+    /// Returns an error if there is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn update_external_senders<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        external_senders: ExternalSendersExtension,
+    ) -> Result<(MlsMessageOut, Option<GroupInfo>), UpdateExternalSendersError<KeyStore::Error>>
+    {
+        self.is_operational()?;
+
+        let credential = self.credential()?;
+        let credential_bundle: CredentialBundle = backend
+            .key_store()
+            .read(
+                &credential
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+            )
+            .ok_or(UpdateExternalSendersError::NoMatchingCredentialBundle)?;
+
+        let mut extensions = self.group.group_context_extensions().clone();
+        if external_senders.is_empty() {
+            extensions.remove(ExtensionType::ExternalSenders);
+        } else {
+            extensions.add_or_replace(Extension::ExternalSenders(external_senders));
+        }
+        let proposal =
+            Proposal::GroupContextExtensions(GroupContextExtensionProposal::new(extensions));
+
+        // The GroupContextExtensions proposal has to be the only one of its
+        // kind in the Commit, so we don't include pending proposals.
+        let empty_proposal_store = ProposalStore::new();
+        let params = CreateCommitParams::builder()
+            .framing_parameters(self.framing_parameters())
+            .credential_bundle(&credential_bundle)
+            .proposal_store(&empty_proposal_store)
+            .inline_proposals(vec![proposal])
+            .build();
+        let create_commit_result = self.group.create_commit(params, backend)?;
+
+        // Convert PublicMessage messages to MLSMessage and encrypt them if required by
+        // the configuration
+        let mls_message = self.content_to_mls_message(create_commit_result.commit, backend)?;
+
+        // Set the current group state to [`MlsGroupState::PendingCommit`],
+        // storing the current [`StagedCommit`] from the commit results
+        self.set_own_pending_commit(backend, create_commit_result.staged_commit, &mls_message)?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change(StateChangeReason::CommitCreated);
+
+        Ok((mls_message, create_commit_result.group_info))
+    }
+}
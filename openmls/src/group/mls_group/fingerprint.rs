@@ -0,0 +1,157 @@
+//! Fingerprints of members and groups for safety-number style verification,
+//! see [`Member::fingerprint()`] and [`MlsGroup::fingerprint()`].
+
+use std::fmt;
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::HashType};
+use tls_codec::{Serialize as TlsSerializeTrait, TlsSerialize, TlsSize, VLBytes};
+
+use super::*;
+
+/// The label of the fingerprints of members.
+const MEMBER_FINGERPRINT_LABEL: &[u8] = b"OpenMLS member fingerprint";
+
+/// The label of the fingerprints of groups.
+const GROUP_FINGERPRINT_LABEL: &[u8] = b"OpenMLS group fingerprint";
+
+/// The input of the fingerprint of a member.
+///
+/// ```text
+/// struct {
+///     opaque label<V>;
+///     opaque identity<V>;
+///     opaque signature_key<V>;
+/// } MemberFingerprintInput;
+/// ```
+#[derive(TlsSerialize, TlsSize)]
+struct MemberFingerprintInput {
+    label: VLBytes,
+    identity: VLBytes,
+    signature_key: VLBytes,
+}
+
+/// The input of the fingerprint of a group. The fingerprints of the members
+/// are sorted, such that all members compute the same fingerprint.
+///
+/// ```text
+/// struct {
+///     opaque label<V>;
+///     opaque group_id<V>;
+///     opaque member_fingerprints<V><V>;
+/// } GroupFingerprintInput;
+/// ```
+#[derive(TlsSerialize, TlsSize)]
+struct GroupFingerprintInput {
+    label: VLBytes,
+    group_id: VLBytes,
+    member_fingerprints: Vec<VLBytes>,
+}
+
+/// A fingerprint of a member or a group that users compare out of band.
+///
+/// Fingerprints are SHA-256 hashes, independent of the ciphersuite of the
+/// group, such that the fingerprint of a member is the same in all groups the
+/// member is in. They can be displayed as hexadecimal groups, with the
+/// [`fmt::Display`] implementation, or as decimal digits with
+/// [`Fingerprint::digits()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    value: Vec<u8>,
+}
+
+impl Fingerprint {
+    fn new(backend: &impl OpenMlsCryptoProvider, input: &[u8]) -> Result<Self, LibraryError> {
+        let value = backend
+            .crypto()
+            .hash(HashType::Sha2_256, input)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        Ok(Self { value })
+    }
+
+    /// Returns the bytes of the fingerprint.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.value
+    }
+
+    /// Returns the fingerprint as six groups of five decimal digits, separated
+    /// by spaces. Each group is derived from five bytes of the fingerprint.
+    pub fn digits(&self) -> String {
+        self.value
+            .chunks_exact(5)
+            .map(|chunk| {
+                let number = chunk
+                    .iter()
+                    .fold(0u64, |number, byte| number << 8 | u64::from(*byte));
+                format!("{:05}", number % 100_000)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    /// Formats the fingerprint as groups of four upper-case hexadecimal
+    /// digits, separated by spaces.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, chunk) in self.value.chunks(2).enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            for byte in chunk {
+                write!(f, "{byte:02X}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Member {
+    /// Computes the [`Fingerprint`] of the member from its identity and its
+    /// signature key.
+    ///
+    /// The fingerprint doesn't change when the member updates its leaf node,
+    /// unless it rotates its signature key.
+    pub fn fingerprint(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Fingerprint, LibraryError> {
+        let input = MemberFingerprintInput {
+            label: MEMBER_FINGERPRINT_LABEL.into(),
+            identity: self.identity.as_slice().into(),
+            signature_key: self.signature_key.as_slice().into(),
+        }
+        .tls_serialize_detached()
+        .map_err(LibraryError::missing_bound_check)?;
+        Fingerprint::new(backend, &input)
+    }
+}
+
+impl MlsGroup {
+    /// Computes the [`Fingerprint`] of the group from its group ID and the
+    /// fingerprints of all members, see [`Member::fingerprint()`].
+    ///
+    /// All members compute the same fingerprint if they agree on the members
+    /// of the group. The fingerprint changes when a member is added or
+    /// removed, or rotates its signature key.
+    pub fn fingerprint(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Fingerprint, LibraryError> {
+        let mut member_fingerprints = self
+            .members()
+            .map(|member| member.fingerprint(backend))
+            .collect::<Result<Vec<_>, _>>()?;
+        member_fingerprints.sort_unstable_by(|a, b| a.value.cmp(&b.value));
+        let input = GroupFingerprintInput {
+            label: GROUP_FINGERPRINT_LABEL.into(),
+            group_id: self.group_id().as_slice().into(),
+            member_fingerprints: member_fingerprints
+                .iter()
+                .map(|fingerprint| fingerprint.as_slice().into())
+                .collect(),
+        }
+        .tls_serialize_detached()
+        .map_err(LibraryError::missing_bound_check)?;
+        Fingerprint::new(backend, &input)
+    }
+}
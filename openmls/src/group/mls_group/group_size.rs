@@ -0,0 +1,67 @@
+//! Enforcement of the maximum number of members of an [`MlsGroup`], see
+//! [`MaxMembersExtension`].
+
+use super::*;
+
+impl MlsGroup {
+    /// Returns the maximum number of members of the group, as set in the
+    /// [`MaxMembersExtension`] of the group context, if there is one.
+    pub fn max_members(&self) -> Option<u32> {
+        self.group
+            .group_context_extensions()
+            .max_members()
+            .map(MaxMembersExtension::max_members)
+    }
+
+    /// Returns `true` if the group would have more members than allowed by
+    /// the [`MaxMembersExtension`] of the group after `added` members joined
+    /// and `removed` members left.
+    pub(super) fn exceeds_max_members(&self, added: usize, removed: usize) -> bool {
+        match self.max_members() {
+            Some(max_members) => self.members().count() + added > max_members as usize + removed,
+            None => false,
+        }
+    }
+
+    /// Returns `true` if committing the `proposals` together with `added` new
+    /// members would exceed the maximum number of members.
+    pub(super) fn proposals_exceed_max_members<'a>(
+        &self,
+        proposals: impl Iterator<Item = &'a QueuedProposal>,
+        added: usize,
+    ) -> bool {
+        let (proposed_added, proposed_removed) = proposals.fold(
+            (0, 0),
+            |(added, removed), queued_proposal| match queued_proposal.proposal() {
+                Proposal::Add(_) => (added + 1, removed),
+                Proposal::Remove(_) => (added, removed + 1),
+                _ => (added, removed),
+            },
+        );
+        self.exceeds_max_members(added + proposed_added, proposed_removed)
+    }
+
+    /// Returns `true` if committing the pending proposals together with
+    /// `added` new members would exceed the maximum number of members.
+    pub(super) fn pending_proposals_exceed_max_members(&self, added: usize) -> bool {
+        self.proposals_exceed_max_members(self.proposal_store.proposals(), added)
+    }
+
+    /// Returns `true` if the Add proposal in the processed message would
+    /// exceed the maximum number of members. Commits are checked against the
+    /// [`MaxMembersExtension`] when they are staged.
+    pub(super) fn processed_message_exceeds_max_members(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> bool {
+        match processed_message.content() {
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                matches!(queued_proposal.proposal(), Proposal::Add(_))
+                    && self.exceeds_max_members(1, 0)
+            }
+            ProcessedMessageContent::ApplicationMessage(_)
+            | ProcessedMessageContent::StagedCommitMessage(_) => false,
+        }
+    }
+}
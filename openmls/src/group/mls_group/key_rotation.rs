@@ -0,0 +1,264 @@
+//! Rotation of the signature key of a client across all of its groups, see
+//! [`SignatureKeyRotation`].
+
+use tls_codec::Serialize;
+
+use crate::key_packages::{
+    KeyTransparencyLog, KeyTransparencyRecord, SignatureKeyRotationRecord, TransparencyLog,
+};
+
+use super::*;
+
+/// The progress of a [`SignatureKeyRotation`] in a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRotationStatus {
+    /// The own leaf still carries the old credential and there is no pending
+    /// update proposal with the new credential, e.g. because the proposal
+    /// wasn't sent yet or was dropped by a commit that didn't include it.
+    Pending,
+    /// An update proposal with the new credential was sent to the group and
+    /// waits to be committed by another member.
+    Proposed,
+    /// The own leaf carries the new credential.
+    Completed,
+}
+
+/// Rotates the signature key of a client in all of its groups, e.g. after the
+/// key was compromised or because of a key rotation policy.
+///
+/// The rotation holds the [`Credential`] with the new signature key. For every
+/// group, [`SignatureKeyRotation::propose()`] creates an update proposal that
+/// replaces the own leaf with one that carries the new credential. Since the
+/// sender of an update proposal can't commit it, the proposal has to be
+/// committed by another member of the group. Once this commit is merged, the
+/// rotation is completed in the group.
+///
+/// The rotation tracks the [`KeyRotationStatus`] of every group it created a
+/// proposal for. Call [`SignatureKeyRotation::refresh()`] after merging commits
+/// to update it, and [`SignatureKeyRotation::propose()`] again to re-send
+/// proposals that were dropped, until [`SignatureKeyRotation::is_complete()`]
+/// returns `true`. The old [`CredentialBundle`] can then be removed from the
+/// key store.
+///
+/// The new credential should have the same identity as the old one, since
+/// other members may reject an update that changes the identity.
+///
+/// With a [`KeyTransparencyLog`], see
+/// [`SignatureKeyRotation::transparency_log()`], the rotation emits a
+/// [`SignatureKeyRotationRecord`] for every old credential it replaces.
+#[derive(Debug, Clone)]
+pub struct SignatureKeyRotation {
+    credential: Credential,
+    groups: HashMap<GroupId, KeyRotationStatus>,
+    transparency_log: Option<TransparencyLog>,
+    published_credentials: Vec<Credential>,
+}
+
+impl SignatureKeyRotation {
+    /// Create a new [`SignatureKeyRotation`] to the signature key of the
+    /// `credential_bundle`. The bundle is stored in the key store of the
+    /// `backend`, where it is needed to sign messages once the rotation is
+    /// completed in a group.
+    pub fn new<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        credential_bundle: CredentialBundle,
+    ) -> Result<Self, SignatureKeyRotationError<KeyStore::Error>> {
+        let credential = credential_bundle.credential().clone();
+        backend
+            .key_store()
+            .store(
+                &credential
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+                &credential_bundle,
+            )
+            .map_err(SignatureKeyRotationError::KeyStoreError)?;
+        Ok(Self {
+            credential,
+            groups: HashMap::new(),
+            transparency_log: None,
+            published_credentials: Vec::new(),
+        })
+    }
+
+    /// Set the [`KeyTransparencyLog`] that receives a
+    /// [`SignatureKeyRotationRecord`] when the rotation first proposes to
+    /// replace an old credential.
+    pub fn transparency_log(mut self, log: impl KeyTransparencyLog + 'static) -> Self {
+        self.transparency_log = Some(TransparencyLog::new(log));
+        self
+    }
+
+    /// Returns the new credential.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+
+    /// Create an update proposal with the new credential for every group in
+    /// `groups` whose status is [`KeyRotationStatus::Pending`], and start
+    /// tracking these groups.
+    ///
+    /// Returns the group ID together with the [`MlsMessageOut`] containing the
+    /// proposal, or the error that occurred, for every group a proposal was
+    /// created for. Groups for which creating the proposal failed remain
+    /// pending, such that it can be retried.
+    #[allow(clippy::type_complexity)]
+    pub fn propose<'a, KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        groups: impl IntoIterator<Item = &'a mut MlsGroup>,
+    ) -> Vec<(
+        GroupId,
+        Result<MlsMessageOut, ProposeSelfUpdateError<KeyStore::Error>>,
+    )> {
+        let mut proposals = Vec::new();
+        for group in groups {
+            let status = self.refresh_group(group);
+            if status != KeyRotationStatus::Pending {
+                continue;
+            }
+            let old_credential = group.credential().ok().cloned();
+            let result = group.propose_credential_update(backend, &self.credential);
+            if result.is_ok() {
+                self.groups
+                    .insert(group.group_id().clone(), KeyRotationStatus::Proposed);
+                if let Some(old_credential) = old_credential {
+                    self.publish(old_credential);
+                }
+            }
+            proposals.push((group.group_id().clone(), result));
+        }
+        proposals
+    }
+
+    /// Update the status of the tracked groups among `groups`, e.g. after
+    /// merging commits.
+    pub fn refresh<'a>(&mut self, groups: impl IntoIterator<Item = &'a MlsGroup>) {
+        for group in groups {
+            if self.groups.contains_key(group.group_id()) {
+                self.refresh_group(group);
+            }
+        }
+    }
+
+    /// Returns the status of the group with the given `group_id`, or `None`
+    /// if the group isn't tracked.
+    pub fn status(&self, group_id: &GroupId) -> Option<KeyRotationStatus> {
+        self.groups.get(group_id).copied()
+    }
+
+    /// Returns an iterator over the IDs of the tracked groups in which the
+    /// rotation isn't completed yet.
+    pub fn incomplete_groups(&self) -> impl Iterator<Item = &GroupId> {
+        self.groups
+            .iter()
+            .filter(|(_, status)| **status != KeyRotationStatus::Completed)
+            .map(|(group_id, _)| group_id)
+    }
+
+    /// Returns `true` if the rotation is completed in all tracked groups.
+    pub fn is_complete(&self) -> bool {
+        self.incomplete_groups().next().is_none()
+    }
+
+    /// Emit the rotation from the `old_credential` to the transparency log,
+    /// unless it was emitted before.
+    fn publish(&mut self, old_credential: Credential) {
+        if let Some(transparency_log) = &self.transparency_log {
+            if !self.published_credentials.contains(&old_credential) {
+                self.published_credentials.push(old_credential.clone());
+                transparency_log.append(KeyTransparencyRecord::SignatureKeyRotation(
+                    SignatureKeyRotationRecord::new(old_credential, self.credential.clone()),
+                ));
+            }
+        }
+    }
+
+    /// Determine and record the status of the `group`.
+    fn refresh_group(&mut self, group: &MlsGroup) -> KeyRotationStatus {
+        let status = if group.own_leaf().map(|leaf| leaf.credential()) == Some(&self.credential) {
+            KeyRotationStatus::Completed
+        } else if group
+            .own_leaf_nodes
+            .iter()
+            .any(|leaf| leaf.leaf_node().credential() == &self.credential)
+        {
+            KeyRotationStatus::Proposed
+        } else {
+            KeyRotationStatus::Pending
+        };
+        self.groups.insert(group.group_id().clone(), status);
+        status
+    }
+}
+
+impl MlsGroup {
+    /// Creates a proposal to update the own leaf node with a fresh encryption
+    /// key and the given `credential`. The proposal is signed with the current
+    /// credential.
+    fn propose_credential_update<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        credential: &Credential,
+    ) -> Result<MlsMessageOut, ProposeSelfUpdateError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        let read_credential_bundle = |credential: &Credential| {
+            credential
+                .signature_key()
+                .tls_serialize_detached()
+                .map_err(LibraryError::missing_bound_check)
+                .map(|signature_key| backend.key_store().read::<CredentialBundle>(&signature_key))
+        };
+        let credential_bundle = read_credential_bundle(credential)?
+            .ok_or(ProposeSelfUpdateError::NoMatchingCredentialBundle)?;
+        // ValSem109
+        if credential.identity() != self.credential()?.identity() {
+            return Err(ProposeSelfUpdateError::IdentityMismatch);
+        }
+        let old_credential_bundle = read_credential_bundle(self.credential()?)?
+            .ok_or(ProposeSelfUpdateError::NoMatchingCredentialBundle)?;
+
+        // Clone the own leaf to rekey it such that the tree doesn't change
+        // before the proposal is committed.
+        let mut own_leaf = self
+            .group
+            .treesync()
+            .own_leaf_node()
+            .ok_or_else(|| LibraryError::custom("The tree is broken. Couldn't find own leaf."))?
+            .clone();
+        let keypair = own_leaf.rekey(
+            self.group_id(),
+            self.ciphersuite(),
+            self.group.version(),
+            &credential_bundle,
+            backend,
+        )?;
+        keypair
+            .write_to_key_store(backend)
+            .map_err(ProposeSelfUpdateError::KeyStoreError)?;
+
+        let update_proposal = self.group.create_update_proposal(
+            self.framing_parameters(),
+            &old_credential_bundle,
+            own_leaf.leaf_node().clone(),
+            backend,
+        )?;
+
+        self.own_leaf_nodes.push(own_leaf);
+        self.proposal_store
+            .add(QueuedProposal::from_authenticated_content(
+                self.ciphersuite(),
+                backend,
+                update_proposal.clone(),
+            )?);
+
+        let mls_message = self.content_to_mls_message(update_proposal, backend)?;
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change(StateChangeReason::ProposalCreated);
+
+        Ok(mls_message)
+    }
+}
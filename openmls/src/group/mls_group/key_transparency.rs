@@ -0,0 +1,103 @@
+//! Verification of the key packages of new members with the
+//! [`KeyTransparencyVerifier`] of the application.
+
+use std::{fmt, sync::Arc};
+
+use crate::key_packages::{KeyPackagePublication, KeyTransparencyVerifier};
+
+use super::*;
+
+/// The [`KeyTransparencyVerifier`] in the [`MlsGroupConfig`].
+#[derive(Clone)]
+pub(crate) struct TransparencyVerifier(Arc<dyn KeyTransparencyVerifier>);
+
+impl TransparencyVerifier {
+    pub(crate) fn new(verifier: impl KeyTransparencyVerifier + 'static) -> Self {
+        Self(Arc::new(verifier))
+    }
+
+    fn verify(&self, publication: &KeyPackagePublication) -> bool {
+        self.0.verify(publication)
+    }
+}
+
+impl fmt::Debug for TransparencyVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TransparencyVerifier")
+    }
+}
+
+impl PartialEq for TransparencyVerifier {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for TransparencyVerifier {}
+
+impl MlsGroup {
+    /// Sets the [`KeyTransparencyVerifier`] of the group, e.g. after the
+    /// group was loaded, since verifiers are not persisted. A group that was
+    /// created with a verifier rejects the key packages of new members until
+    /// it is set again. See
+    /// [`MlsGroupConfigBuilder::key_transparency_verifier()`] for more
+    /// information.
+    pub fn set_key_transparency_verifier(
+        &mut self,
+        verifier: impl KeyTransparencyVerifier + 'static,
+    ) {
+        self.mls_group_config.key_transparency_verifier = Some(TransparencyVerifier::new(verifier));
+        self.mls_group_config.require_key_transparency = true;
+    }
+
+    /// Returns `true` if the verifier of the group accepts the publications
+    /// of all `key_packages`. Without a verifier, `true` is only returned if
+    /// the group doesn't require one, e.g. since a group that was loaded
+    /// lost its verifier.
+    pub(super) fn verifies_key_packages<'a>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        key_packages: impl IntoIterator<Item = &'a KeyPackage>,
+    ) -> Result<bool, LibraryError> {
+        let verifier = match &self.mls_group_config.key_transparency_verifier {
+            Some(verifier) => verifier,
+            None => return Ok(!self.mls_group_config.require_key_transparency),
+        };
+        for key_package in key_packages {
+            let publication = KeyPackagePublication::new(key_package, backend.crypto())?;
+            if !verifier.verify(&publication) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns `true` if the key transparency verifier of the group accepts
+    /// the key packages of all members added by the proposal or the commit
+    /// in the processed message.
+    pub(super) fn processed_message_verifies_key_packages(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        processed_message: &ProcessedMessage,
+    ) -> Result<bool, LibraryError> {
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => Ok(true),
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => {
+                        self.verifies_key_packages(backend, [add_proposal.key_package()])
+                    }
+                    _ => Ok(true),
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => self
+                .verifies_key_packages(
+                    backend,
+                    staged_commit.add_proposals().map(|queued_add_proposal| {
+                        queued_add_proposal.add_proposal().key_package()
+                    }),
+                ),
+        }
+    }
+}
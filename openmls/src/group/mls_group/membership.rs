@@ -1,6 +1,9 @@
 //! MLS group membership
 //!
-//! This module contains membership-related operations and exposes [`RemoveOperation`].
+//! This module contains membership-related operations and exposes [`RemoveOperation`],
+//! [`RemoveSummary`], [`MembershipDiff`] and [`GroupEvent`].
+
+use std::collections::HashMap;
 
 use core_group::create_commit_params::CreateCommitParams;
 use tls_codec::Serialize;
@@ -37,10 +40,43 @@ impl MlsGroup {
     {
         self.is_operational()?;
 
+        // Drop expired proposals, such that they are not committed
+        self.expire_pending_proposals();
+
         if key_packages.is_empty() {
             return Err(AddMembersError::EmptyInput(EmptyInputError::AddMembers));
         }
 
+        if self.pending_proposals_exceed_max_members(key_packages.len()) {
+            return Err(AddMembersError::TooManyMembers);
+        }
+
+        if !self.pending_proposals_accept_key_packages(key_packages) {
+            return Err(AddMembersError::DuplicateIdentity);
+        }
+
+        #[cfg(feature = "verifiable-credentials")]
+        if !self.verifies_credentials(
+            key_packages
+                .iter()
+                .map(|key_package| key_package.leaf_node().credential()),
+        ) {
+            return Err(AddMembersError::InvalidVerifiableCredential);
+        }
+
+        #[cfg(feature = "token-bound-credentials")]
+        if !self.verifies_token_bindings(
+            key_packages
+                .iter()
+                .map(|key_package| key_package.leaf_node().credential()),
+        ) {
+            return Err(AddMembersError::InvalidTokenBinding);
+        }
+
+        if !self.verifies_key_packages(backend, key_packages)? {
+            return Err(AddMembersError::UntrustedKeyPackage);
+        }
+
         // Create inline add proposals from key packages
         let inline_proposals = key_packages
             .iter()
@@ -85,12 +121,10 @@ impl MlsGroup {
 
         // Set the current group state to [`MlsGroupState::PendingCommit`],
         // storing the current [`StagedCommit`] from the commit results
-        self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
-            create_commit_result.staged_commit,
-        )));
+        self.set_own_pending_commit(backend, create_commit_result.staged_commit, &mls_messages)?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::CommitCreated);
 
         Ok((
             mls_messages,
@@ -129,6 +163,9 @@ impl MlsGroup {
     > {
         self.is_operational()?;
 
+        // Drop expired proposals, such that they are not committed
+        self.expire_pending_proposals();
+
         if members.is_empty() {
             return Err(RemoveMembersError::EmptyInput(
                 EmptyInputError::RemoveMembers,
@@ -168,12 +205,10 @@ impl MlsGroup {
 
         // Set the current group state to [`MlsGroupState::PendingCommit`],
         // storing the current [`StagedCommit`] from the commit results
-        self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
-            create_commit_result.staged_commit,
-        )));
+        self.set_own_pending_commit(backend, create_commit_result.staged_commit, &mls_message)?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::CommitCreated);
 
         Ok((
             mls_message,
@@ -184,6 +219,74 @@ impl MlsGroup {
         ))
     }
 
+    /// Removes the members with the given identities from the group.
+    ///
+    /// This works like [`MlsGroup::remove_members()`], but the leaf indices of
+    /// the members are resolved from the identities of their credentials.
+    ///
+    /// Returns [`RemoveMembersError::UnknownMember`] if no member has one of
+    /// the identities and [`RemoveMembersError::AmbiguousMember`] if several
+    /// members have the same identity. In that case, the members have to be
+    /// removed by their signature key or their leaf index.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn remove_members_by_identity<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        identities: &[&[u8]],
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        RemoveMembersError<KeyStore::Error>,
+    > {
+        let members = self.resolve_members(identities, |member| member.identity.as_slice())?;
+        self.remove_members(backend, &members)
+    }
+
+    /// Removes the members with the given signature keys from the group.
+    ///
+    /// This works like [`MlsGroup::remove_members()`], but the leaf indices of
+    /// the members are resolved from the signature keys of their credentials.
+    ///
+    /// Returns [`RemoveMembersError::UnknownMember`] if no member has one of
+    /// the signature keys.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn remove_members_by_signature_key<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signature_keys: &[&[u8]],
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        RemoveMembersError<KeyStore::Error>,
+    > {
+        let members =
+            self.resolve_members(signature_keys, |member| member.signature_key.as_slice())?;
+        self.remove_members(backend, &members)
+    }
+
+    /// Resolves the leaf index of the member for each of the given `values`,
+    /// where `member_value` returns the value of a member that is compared.
+    fn resolve_members<KeyStoreError>(
+        &self,
+        values: &[&[u8]],
+        member_value: impl Fn(&Member) -> &[u8],
+    ) -> Result<Vec<LeafNodeIndex>, RemoveMembersError<KeyStoreError>> {
+        let members: Vec<Member> = self.members().collect();
+        values
+            .iter()
+            .map(|value| {
+                let mut matching = members
+                    .iter()
+                    .filter(|member| member_value(*member) == *value);
+                match (matching.next(), matching.next()) {
+                    (Some(member), None) => Ok(member.index),
+                    (None, _) => Err(RemoveMembersError::UnknownMember),
+                    (Some(_), Some(_)) => Err(RemoveMembersError::AmbiguousMember),
+                }
+            })
+            .collect()
+    }
+
     /// Creates proposals to add members to the group.
     ///
     /// Returns an error if there is a pending commit.
@@ -194,6 +297,28 @@ impl MlsGroup {
     ) -> Result<MlsMessageOut, ProposeAddMemberError> {
         self.is_operational()?;
 
+        if self.pending_proposals_exceed_max_members(1) {
+            return Err(ProposeAddMemberError::TooManyMembers);
+        }
+
+        if !self.accepts_new_credentials([key_package.leaf_node().credential()], &[]) {
+            return Err(ProposeAddMemberError::DuplicateIdentity);
+        }
+
+        #[cfg(feature = "verifiable-credentials")]
+        if !self.verifies_credentials([key_package.leaf_node().credential()]) {
+            return Err(ProposeAddMemberError::InvalidVerifiableCredential);
+        }
+
+        #[cfg(feature = "token-bound-credentials")]
+        if !self.verifies_token_bindings([key_package.leaf_node().credential()]) {
+            return Err(ProposeAddMemberError::InvalidTokenBinding);
+        }
+
+        if !self.verifies_key_packages(backend, [key_package])? {
+            return Err(ProposeAddMemberError::UntrustedKeyPackage);
+        }
+
         let credential = self.credential()?;
         let credential_bundle: CredentialBundle = backend
             .key_store()
@@ -230,7 +355,7 @@ impl MlsGroup {
         let mls_message = self.content_to_mls_message(add_proposal, backend)?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::ProposalCreated);
 
         Ok(mls_message)
     }
@@ -277,7 +402,7 @@ impl MlsGroup {
         let mls_message = self.content_to_mls_message(remove_proposal, backend)?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::ProposalCreated);
 
         Ok(mls_message)
     }
@@ -334,6 +459,20 @@ impl MlsGroup {
         self.group.treesync().full_leave_members()
     }
 
+    /// Returns the [`MemberDetails`] of all members in the group.
+    pub fn members_details(&self) -> impl Iterator<Item = MemberDetails> + '_ {
+        self.group.treesync().full_leave_member_details()
+    }
+
+    /// Returns the [`MemberDetails`] of the member corresponding to the given
+    /// leaf index. Returns `None` if the member can not be found in this group.
+    pub fn member_details(&self, leaf_index: LeafNodeIndex) -> Option<MemberDetails> {
+        self.group
+            .treesync()
+            .leaf(leaf_index)
+            .map(|leaf| MemberDetails::from_leaf_node(leaf_index, leaf.leaf_node()))
+    }
+
     /// Returns the [`Credential`] of a member corresponding to the given
     /// leaf index. Returns `None` if the member can not be found in this group.
     pub fn member(&self, leaf_index: LeafNodeIndex) -> Option<&Credential> {
@@ -343,6 +482,28 @@ impl MlsGroup {
             .leaf(leaf_index)
             .map(|leaf| leaf.credential())
     }
+
+    /// Returns the [`LeafNode`] of a member corresponding to the given leaf
+    /// index. Returns `None` if the member can not be found in this group.
+    ///
+    /// The leaf nodes in the group's tree were validated when they were added
+    /// to the tree.
+    pub fn member_leaf_node(&self, leaf_index: LeafNodeIndex) -> Option<&LeafNode> {
+        self.group
+            .treesync()
+            .leaf(leaf_index)
+            .map(|leaf| leaf.leaf_node())
+    }
+
+    /// Returns the [`Member`] whose leaf node carries the given application id
+    /// in its [`ApplicationIdExtension`]. Returns `None` if no such member can
+    /// be found in this group.
+    ///
+    /// [`ApplicationIdExtension`]: crate::extensions::ApplicationIdExtension
+    pub fn member_by_application_id(&self, application_id: &[u8]) -> Option<Member> {
+        self.members()
+            .find(|member| member.application_id.as_deref() == Some(application_id))
+    }
 }
 
 /// Helper `enum` that classifies the kind of remove operation. This can be used to
@@ -407,3 +568,287 @@ impl RemoveOperation {
         }
     }
 }
+
+/// The interpretation of all remove proposals covered by a [`StagedCommit`],
+/// see [`RemoveSummary::new()`].
+#[derive(Debug)]
+pub struct RemoveSummary {
+    operations: Vec<RemoveOperation>,
+}
+
+impl RemoveSummary {
+    /// Interprets all remove proposals of the `staged_commit` as
+    /// [`RemoveOperation`]s with respect to the corresponding [`MlsGroup`].
+    pub fn new(staged_commit: &StagedCommit, group: &MlsGroup) -> Result<Self, LibraryError> {
+        let operations = staged_commit
+            .remove_proposals()
+            .map(|remove_proposal| RemoveOperation::new(remove_proposal, group))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { operations })
+    }
+
+    /// Returns the [`RemoveOperation`]s in the order of the remove proposals
+    /// in the commit.
+    pub fn operations(&self) -> &[RemoveOperation] {
+        &self.operations
+    }
+
+    /// Returns `true` if the commit doesn't remove any member.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Returns `true` if we left the group or were removed from it.
+    pub fn self_removed(&self) -> bool {
+        self.we_left() || self.we_were_removed_by().is_some()
+    }
+
+    /// Returns `true` if our own remove proposal was committed.
+    pub fn we_left(&self) -> bool {
+        self.operations
+            .iter()
+            .any(|operation| matches!(operation, RemoveOperation::WeLeft))
+    }
+
+    /// Returns the [`Sender`] that removed us from the group, if any.
+    pub fn we_were_removed_by(&self) -> Option<&Sender> {
+        self.operations
+            .iter()
+            .find_map(|operation| match operation {
+                RemoveOperation::WeWereRemovedBy(sender) => Some(sender),
+                _ => None,
+            })
+    }
+
+    /// Returns the leaf indices of the other members that left or were
+    /// removed, including when we were removed along with them.
+    pub fn others_removed(&self) -> impl Iterator<Item = LeafNodeIndex> + '_ {
+        self.operations
+            .iter()
+            .filter_map(|operation| match operation {
+                RemoveOperation::WeLeft | RemoveOperation::WeWereRemovedBy(_) => None,
+                RemoveOperation::TheyLeft(removed)
+                | RemoveOperation::TheyWereRemovedBy((removed, _))
+                | RemoveOperation::WeRemovedThem(removed) => Some(*removed),
+            })
+    }
+}
+
+/// The changes to the membership of a group by a merged commit, see
+/// [`MlsGroup::membership_diff()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MembershipDiff {
+    /// The members that were added, including the sender of an External
+    /// Commit.
+    pub added: Vec<Member>,
+    /// The members that were removed, as they were before the commit.
+    pub removed: Vec<Member>,
+    /// The members that updated their leaf, e.g. through an Update proposal
+    /// or the path of the commit, as they are after the commit.
+    pub updated: Vec<Member>,
+    /// Indicates whether the extensions of the group context changed.
+    pub gce_changed: bool,
+}
+
+impl MembershipDiff {
+    /// Computes the diff between the members before and after a commit.
+    /// Members at the `removed_leaves` were removed by the commit, even if a
+    /// new member was added at the same leaf.
+    pub(crate) fn new(
+        members_before: Vec<Member>,
+        members_after: impl Iterator<Item = Member>,
+        removed_leaves: &[LeafNodeIndex],
+        gce_changed: bool,
+    ) -> Self {
+        let mut members_before: HashMap<LeafNodeIndex, Member> = members_before
+            .into_iter()
+            .map(|member| (member.index, member))
+            .collect();
+        let mut diff = MembershipDiff {
+            gce_changed,
+            ..Default::default()
+        };
+        for member in members_after {
+            match members_before.remove(&member.index) {
+                Some(member_before) if removed_leaves.contains(&member.index) => {
+                    diff.removed.push(member_before);
+                    diff.added.push(member);
+                }
+                Some(member_before) => {
+                    if member_before.encryption_key != member.encryption_key {
+                        diff.updated.push(member);
+                    }
+                }
+                None => diff.added.push(member),
+            }
+        }
+        // The remaining members are no longer in the tree.
+        diff.removed.extend(members_before.into_values());
+        diff.removed.sort_by_key(|member| member.index);
+        diff
+    }
+}
+
+/// A high-level event of a merged commit, see [`MlsGroup::group_events()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupEvent {
+    /// A member was added by the [`Sender`] of the Add proposal, or joined
+    /// through an External Commit.
+    MemberAdded {
+        /// The added member.
+        member: Member,
+        /// The sender that added the member.
+        by: Sender,
+    },
+    /// Another member was removed by the [`Sender`] of the Remove proposal.
+    MemberRemoved {
+        /// The removed member, as it was before the commit.
+        member: Member,
+        /// The sender that removed the member.
+        by: Sender,
+    },
+    /// A member updated its leaf, e.g. through an Update proposal or the path
+    /// of the commit.
+    MemberUpdated {
+        /// The updated member, as it is after the commit.
+        member: Member,
+    },
+    /// A member was added whose identity or signature key was already in the
+    /// group before, e.g. a contact that rejoined with a new device or a new
+    /// key. It is reported instead of [`GroupEvent::MemberAdded`].
+    MemberRejoined {
+        /// The added member.
+        member: Member,
+        /// The sender that added the member.
+        by: Sender,
+        /// The earlier leaves with the same identity or signature key, as
+        /// they were when they were added, oldest first. See
+        /// [`MlsGroup::membership_history()`].
+        previous_leaves: Vec<Member>,
+    },
+    /// The extensions of the group context changed, e.g. the required
+    /// capabilities or the external senders of the group.
+    PolicyChanged {
+        /// The sender of the GroupContextExtensions proposal.
+        by: Sender,
+    },
+    /// We were removed from the group by the [`Sender`] of the Remove
+    /// proposal, which is ourselves if we left the group.
+    WeWereRemoved {
+        /// The sender that removed us.
+        by: Sender,
+    },
+}
+
+/// The senders of the proposals of a [`StagedCommit`] that are needed to
+/// attribute the [`GroupEvent`]s of the commit once it is merged.
+pub(crate) struct ProposalSenders {
+    added: Vec<(Vec<u8>, Sender)>,
+    removed: HashMap<LeafNodeIndex, Sender>,
+    policy: Option<Sender>,
+}
+
+impl ProposalSenders {
+    pub(crate) fn new(staged_commit: &StagedCommit) -> Self {
+        Self {
+            added: staged_commit
+                .add_proposals()
+                .map(|add_proposal| {
+                    (
+                        add_proposal
+                            .add_proposal()
+                            .key_package()
+                            .leaf_node()
+                            .encryption_key()
+                            .as_slice()
+                            .to_vec(),
+                        add_proposal.sender().clone(),
+                    )
+                })
+                .collect(),
+            removed: staged_commit
+                .remove_proposals()
+                .map(|remove_proposal| {
+                    (
+                        remove_proposal.remove_proposal().removed(),
+                        remove_proposal.sender().clone(),
+                    )
+                })
+                .collect(),
+            policy: staged_commit
+                .queued_proposals()
+                .find(|queued_proposal| {
+                    matches!(
+                        queued_proposal.proposal(),
+                        Proposal::GroupContextExtensions(_)
+                    )
+                })
+                .map(|queued_proposal| queued_proposal.sender().clone()),
+        }
+    }
+
+    /// Returns the [`GroupEvent`]s of the `membership_diff` of the commit.
+    /// Members added without an Add proposal joined through an External
+    /// Commit. Added members that are in the membership `history` before the
+    /// commit rejoined the group.
+    pub(crate) fn group_events(
+        &self,
+        membership_diff: &MembershipDiff,
+        own_index: LeafNodeIndex,
+        history: &[MembershipRecord],
+    ) -> Vec<GroupEvent> {
+        let mut events = Vec::new();
+        for member in &membership_diff.removed {
+            if let Some(by) = self.removed.get(&member.index).cloned() {
+                if member.index == own_index {
+                    events.push(GroupEvent::WeWereRemoved { by });
+                } else {
+                    events.push(GroupEvent::MemberRemoved {
+                        member: member.clone(),
+                        by,
+                    });
+                }
+            }
+        }
+        for member in &membership_diff.added {
+            let by = self
+                .added
+                .iter()
+                .find(|(encryption_key, _)| encryption_key == &member.encryption_key)
+                .map(|(_, sender)| sender.clone())
+                .unwrap_or(Sender::NewMemberCommit);
+            let previous_leaves: Vec<Member> = history
+                .iter()
+                .flat_map(|record| record.added())
+                .filter(|previous| {
+                    previous.identity == member.identity
+                        || previous.signature_key == member.signature_key
+                })
+                .cloned()
+                .collect();
+            if previous_leaves.is_empty() {
+                events.push(GroupEvent::MemberAdded {
+                    member: member.clone(),
+                    by,
+                });
+            } else {
+                events.push(GroupEvent::MemberRejoined {
+                    member: member.clone(),
+                    by,
+                    previous_leaves,
+                });
+            }
+        }
+        for member in &membership_diff.updated {
+            events.push(GroupEvent::MemberUpdated {
+                member: member.clone(),
+            });
+        }
+        if membership_diff.gce_changed {
+            if let Some(by) = self.policy.clone() {
+                events.push(GroupEvent::PolicyChanged { by });
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,70 @@
+//! The history of the membership of a group, see
+//! [`MlsGroup::membership_history()`].
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// The members that joined or left a group in an epoch, see
+/// [`MlsGroup::membership_history()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MembershipRecord {
+    epoch: GroupEpoch,
+    added: Vec<Member>,
+    removed: Vec<Member>,
+}
+
+impl MembershipRecord {
+    /// Returns the epoch in which the membership changed.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the members that were added, or the members of the group when
+    /// the client created or joined it.
+    pub fn added(&self) -> &[Member] {
+        &self.added
+    }
+
+    /// Returns the members that were removed, as they were before they were
+    /// removed.
+    pub fn removed(&self) -> &[Member] {
+        &self.removed
+    }
+}
+
+impl MlsGroup {
+    /// Returns the history of the membership of the group, oldest first.
+    ///
+    /// The first record contains the members of the group when the client
+    /// created or joined it. Afterwards, a record is added for every merged
+    /// commit that added or removed members. Updates of members are not
+    /// recorded. The history is persisted with the group, but groups that were
+    /// persisted before the history was introduced start with an empty one.
+    pub fn membership_history(&self) -> &[MembershipRecord] {
+        &self.membership_history
+    }
+
+    /// Starts the history with the current members of the group.
+    pub(super) fn init_membership_history(&mut self) {
+        self.membership_history = vec![MembershipRecord {
+            epoch: self.epoch(),
+            added: self.members().collect(),
+            removed: Vec::new(),
+        }];
+    }
+
+    /// Records the members that were added and removed by the most recently
+    /// merged commit.
+    pub(super) fn record_membership_diff(&mut self) {
+        if let Some(diff) = &self.membership_diff {
+            if !diff.added.is_empty() || !diff.removed.is_empty() {
+                self.membership_history.push(MembershipRecord {
+                    epoch: self.group.context().epoch(),
+                    added: diff.added.clone(),
+                    removed: diff.removed.clone(),
+                });
+            }
+        }
+    }
+}
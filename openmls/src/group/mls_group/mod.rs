@@ -6,38 +6,97 @@ use super::{
     proposals::{ProposalStore, QueuedProposal},
     staged_commit::StagedCommit,
 };
+#[cfg(feature = "partial-tree")]
+use crate::treesync::{errors::PartialTreeError, PartialTree};
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
-    credentials::{Credential, CredentialBundle},
+    credentials::{Credential, CredentialBundle, Identity},
     error::LibraryError,
+    extensions::{Extensions, MaxMembersExtension, RequiredCapabilitiesExtension},
     framing::{mls_auth_content::AuthenticatedContent, *},
     group::*,
-    key_packages::{KeyPackage, KeyPackageBundle},
+    key_packages::{KeyPackage, KeyPackageBundle, KeyPackageInventory, KeyPackageReplenishment},
     messages::{proposals::*, Welcome},
-    schedule::ResumptionPskSecret,
+    schedule::{ExportedSecret, ResumptionPskSecret},
+    tree::sender_ratchet::SenderRatchetConfiguration,
     treesync::{node::leaf_node::OpenMlsLeafNode, Node},
+    versions::ProtocolVersion,
 };
 use openmls_traits::{key_store::OpenMlsKeyStore, types::Ciphersuite, OpenMlsCryptoProvider};
-use std::io::{Error, Read, Write};
+use std::{
+    collections::HashMap,
+    io::{Error, Read, Write},
+};
 
 // Private
 mod application;
+mod applied_commit;
+#[cfg(feature = "async")]
+pub(crate) mod asynchronous;
+mod ban_list;
+mod capabilities;
+mod catch_up;
+mod compression;
 mod creation;
+mod duplicate_identity;
 mod exporting;
+mod external_senders;
+mod group_size;
+mod key_transparency;
+mod proposal_expiry;
+mod propose_and_commit;
+mod recovery;
+mod reinit;
+mod replay;
 mod resumption;
+mod stored_commit;
+mod strict_validation;
+#[cfg(feature = "token-bound-credentials")]
+mod token_bindings;
 mod updates;
+#[cfg(feature = "verifiable-credentials")]
+mod verifiable_credentials;
 
 use config::*;
+use delta::*;
+use duplicate_identity::*;
 use errors::*;
+use external_proposal_limits::*;
+use key_transparency::*;
+use membership_history::*;
+use replay::*;
 use resumption::*;
 use ser::*;
+use skipped_keys::*;
+use staged_welcome::*;
+use state_change::*;
+#[cfg(feature = "token-bound-credentials")]
+use token_bindings::*;
+#[cfg(feature = "verifiable-credentials")]
+use verifiable_credentials::*;
 
 // Crate
+pub(crate) mod archive;
 pub(crate) mod config;
+pub(crate) mod delta;
 pub(crate) mod errors;
+pub(crate) mod external_proposal_limits;
+pub(crate) mod fingerprint;
+pub(crate) mod key_rotation;
 pub(crate) mod membership;
+pub(crate) mod membership_history;
+pub(crate) mod namespaced_exporter;
+#[cfg(feature = "partial-tree")]
+pub(crate) mod partial_group;
 pub(crate) mod processing;
+pub(crate) mod retention;
+pub(crate) mod sas;
 pub(crate) mod ser;
+pub(crate) mod skipped_keys;
+pub(crate) mod snapshot;
+pub(crate) mod staged_welcome;
+pub(crate) mod state_change;
+pub(crate) mod wipe;
 
 // Tests
 #[cfg(test)]
@@ -87,7 +146,9 @@ impl From<PendingCommitState> for StagedCommit {
 /// when this client creates a commit.
 ///
 /// * [`MlsGroupState::Inactive`]: A group can enter this state from any other
-/// state when it processes a commit that removes this client from the group.
+/// state when it processes a commit that removes this client from the group,
+/// or when it merges a commit that covers a ReInit proposal, since the group
+/// is replaced by its successor group afterwards.
 /// This is a terminal state that the group can not exit from. If the clients
 /// wants to re-join the group, it can either be added by a group member or it
 /// can join via external commit.
@@ -123,7 +184,8 @@ pub enum MlsGroupState {
     PendingCommit(Box<PendingCommitState>),
     /// The group state is in an opertaional state, where new messages and Commits can be created.
     Operational,
-    /// The group is inactive because the member has been removed.
+    /// The group is inactive because the member has been removed or the group
+    /// was re-initialized.
     Inactive,
 }
 
@@ -151,6 +213,9 @@ pub enum MlsGroupState {
 /// An `MlsGroup` has an internal state variable determining if it is active or
 /// inactive, as well as if it has a pending commit. See [`MlsGroupState`] for
 /// more information.
+///
+/// An `MlsGroup` is `Send` and `Sync`, so it can be moved between threads or
+/// async tasks and be shared behind a lock. This is enforced at compile time.
 #[derive(Debug)]
 pub struct MlsGroup {
     // The group configuration. See `MlsGroupCongig` for more information.
@@ -170,15 +235,72 @@ pub struct MlsGroup {
     aad: Vec<u8>,
     // Resumption psk store. This is where the resumption psks are kept in a rollover list.
     resumption_psk_store: ResumptionPskStore,
+    // Replay window. This is where the digests of recently processed handshake
+    // messages are kept to detect replays.
+    replay_window: ReplayWindow,
+    // Sender ratchet configurations for individual senders that override the
+    // sender ratchet configuration of the `MlsGroupConfig`.
+    sender_ratchet_overrides: HashMap<LeafNodeIndex, SenderRatchetConfiguration>,
+    // The callback that decides whether a new member with an existing identity
+    // or signature key is accepted. It is not persisted.
+    duplicate_identity_callback: Option<DuplicateIdentityCallback>,
+    // The callback that is notified whenever the state of the group changes.
+    // It is not persisted.
+    state_change_callback: Option<StateChangeCallback>,
+    // The membership changes of the most recently merged commit. It is not
+    // persisted.
+    membership_diff: Option<MembershipDiff>,
+    // The group events of the most recently merged commit. They are not
+    // persisted.
+    group_events: Vec<GroupEvent>,
+    // The members that joined and left the group since the client created or
+    // joined it.
+    membership_history: Vec<MembershipRecord>,
+    // Pending proposals that were removed from the proposal store without
+    // being committed and that weren't taken by the application yet.
+    expired_proposals: Vec<QueuedProposal>,
+    // Skipped message keys that were deleted to enforce the
+    // `max_skipped_keys` and that weren't taken by the application yet. They
+    // are not persisted.
+    skipped_keys_evictions: Vec<SkippedKeysEviction>,
+    // A commit of another member that was processed and that is stored until
+    // it is merged. It is discarded when any commit is merged.
+    stored_staged_commit: Option<StagedCommit>,
+    // The digest of the PrivateMessage of the commit that led to the current
+    // epoch, if it was recorded to detect the commit when it is delivered
+    // again.
+    merged_commit_digest: Option<Vec<u8>>,
     // A variable that indicates the state of the group. See [`MlsGroupState`]
     // for more information.
     group_state: MlsGroupState,
+    // The digests of the components of the state as they were last persisted
+    // with `save_delta()` or loaded with `load_components()`. It is not
+    // persisted.
+    persisted_components: HashMap<StateComponent, Vec<u8>>,
     // A flag that indicates if the group state has changed and needs to be persisted again. The value
     // is set to `InnerState::Changed` whenever an the internal group state is change and is set to
     // `InnerState::Persisted` once the state has been persisted.
     state_changed: InnerState,
 }
 
+// Make sure that the group and the values that are passed in and out of it
+// can be moved across threads. This fails to compile if a non-`Send` or
+// non-`Sync` type is added to any of them.
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[allow(dead_code)]
+    fn assert_all() {
+        assert_send_sync::<MlsGroup>();
+        assert_send_sync::<MlsGroupConfig>();
+        assert_send_sync::<StagedCommit>();
+        assert_send_sync::<ProcessedMessage>();
+        assert_send_sync::<MlsMessageIn>();
+        assert_send_sync::<MlsMessageOut>();
+        assert_send_sync::<ProcessMessageError>();
+    }
+};
+
 impl MlsGroup {
     // === Configuration ===
 
@@ -190,9 +312,63 @@ impl MlsGroup {
     /// Sets the configuration.
     pub fn set_configuration(&mut self, mls_group_config: &MlsGroupConfig) {
         self.mls_group_config = mls_group_config.clone();
+        self.group.set_allow_duplicate_identities(
+            mls_group_config
+                .duplicate_identity_policy
+                .allows_duplicate_identities(),
+        );
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change(StateChangeReason::ConfigurationChanged);
+    }
+
+    /// Returns the [`SenderRatchetConfiguration`] that is used to decrypt
+    /// messages from the member at `sender`. This is the override set with
+    /// [`MlsGroup::set_sender_ratchet_configuration()`] if there is one and
+    /// the sender ratchet configuration of the [`MlsGroupConfig`] otherwise.
+    pub fn sender_ratchet_configuration(
+        &self,
+        sender: LeafNodeIndex,
+    ) -> &SenderRatchetConfiguration {
+        self.sender_ratchet_overrides
+            .get(&sender)
+            .unwrap_or_else(|| self.mls_group_config.sender_ratchet_configuration())
+    }
+
+    /// Overrides the [`SenderRatchetConfiguration`] of the [`MlsGroupConfig`]
+    /// for messages from the member at `sender`, e.g. to allow a bigger
+    /// out-of-order tolerance for a member whose messages are often
+    /// reordered.
+    ///
+    /// The override applies until it is removed with
+    /// [`MlsGroup::remove_sender_ratchet_configuration()`] or the member is
+    /// removed from the group.
+    pub fn set_sender_ratchet_configuration(
+        &mut self,
+        sender: LeafNodeIndex,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) {
+        self.sender_ratchet_overrides
+            .insert(sender, sender_ratchet_configuration);
+
+        // Since the state of the group might be changed, arm the state flag
+        self.flag_state_change(StateChangeReason::ConfigurationChanged);
+    }
+
+    /// Removes the [`SenderRatchetConfiguration`] override for messages from
+    /// the member at `sender` and returns it, if there was one. Afterwards,
+    /// the sender ratchet configuration of the [`MlsGroupConfig`] applies to
+    /// the member again.
+    pub fn remove_sender_ratchet_configuration(
+        &mut self,
+        sender: LeafNodeIndex,
+    ) -> Option<SenderRatchetConfiguration> {
+        let removed = self.sender_ratchet_overrides.remove(&sender);
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::ConfigurationChanged);
+
+        removed
     }
 
     /// Returns the AAD used in the framing.
@@ -201,11 +377,79 @@ impl MlsGroup {
     }
 
     /// Sets the AAD used in the framing.
+    ///
+    /// The AAD is used for all outgoing messages until it is set again. Use
+    /// [`MlsGroup::create_message_with_aad()`] to set the AAD of a single
+    /// application message and [`MlsGroup::with_aad()`] for proposals and
+    /// commits.
     pub fn set_aad(&mut self, aad: &[u8]) {
         self.aad = aad.to_vec();
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::ConfigurationChanged);
+    }
+
+    /// Calls `f` with `aad` as the AAD used in the framing and restores the
+    /// previous AAD afterwards. This allows attaching authenticated data, e.g.
+    /// routing or policy metadata, to the proposals and commits created by
+    /// `f`:
+    ///
+    /// ```ignore
+    /// let (commit, welcome, group_info) = group.with_aad(b"routing info", |group| {
+    ///     group.add_members(backend, &[key_package])
+    /// })?;
+    /// ```
+    ///
+    /// The AAD is authenticated, but not encrypted. Receivers can read it via
+    /// [`ProcessedMessage::authenticated_data()`].
+    pub fn with_aad<T>(&mut self, aad: &[u8], f: impl FnOnce(&mut Self) -> T) -> T {
+        let previous_aad = std::mem::replace(&mut self.aad, aad.to_vec());
+        let result = f(self);
+        self.aad = previous_aad;
+        result
+    }
+
+    /// Calls `f` with `wire_format` as the wire format of outgoing handshake
+    /// messages and restores the wire format of the group's
+    /// [`WireFormatPolicy`] afterwards. This allows e.g. sending a single
+    /// commit as [`WireFormat::PublicMessage`] for validation by the delivery
+    /// service, while the group otherwise uses
+    /// [`WireFormat::PrivateMessage`]:
+    ///
+    /// ```ignore
+    /// let (commit, welcome, group_info) = group
+    ///     .with_wire_format(WireFormat::PublicMessage, |group| {
+    ///         group.add_members(backend, &[key_package])
+    ///     })??;
+    /// ```
+    ///
+    /// Application messages are always sent as [`WireFormat::PrivateMessage`].
+    ///
+    /// Returns an error if `wire_format` is not a handshake wire format, or if
+    /// the incoming wire format policy of the group doesn't accept it, since
+    /// the other members would reject the message in that case.
+    pub fn with_wire_format<T>(
+        &mut self,
+        wire_format: WireFormat,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, WireFormatOverrideError> {
+        let outgoing = match wire_format {
+            WireFormat::PublicMessage => OutgoingWireFormatPolicy::AlwaysPlaintext,
+            WireFormat::PrivateMessage => OutgoingWireFormatPolicy::AlwaysCiphertext,
+            _ => return Err(WireFormatOverrideError::UnsupportedWireFormat),
+        };
+        let wire_format_policy = self.mls_group_config.wire_format_policy;
+        if !wire_format_policy
+            .incoming()
+            .is_compatible_with(wire_format)
+        {
+            return Err(WireFormatOverrideError::IncompatibleWireFormat);
+        }
+
+        self.mls_group_config.wire_format_policy = wire_format_policy.with_outgoing(outgoing);
+        let result = f(self);
+        self.mls_group_config.wire_format_policy = wire_format_policy;
+        Ok(result)
     }
 
     // === Advanced functions ===
@@ -234,7 +478,7 @@ impl MlsGroup {
     }
 
     /// Get the identity of the client's [`Credential`] owning this group.
-    pub fn own_identity(&self) -> Option<&[u8]> {
+    pub fn own_identity(&self) -> Option<&Identity> {
         self.group.own_identity()
     }
 
@@ -253,6 +497,88 @@ impl MlsGroup {
         self.group.context().epoch()
     }
 
+    /// Returns the [`GroupView`] of the group in the current epoch, which can
+    /// be used to pre-validate incoming messages with
+    /// [`ProtocolMessage::pre_validate()`].
+    pub fn group_view(&self) -> GroupView {
+        GroupView::new(
+            self.group_id().clone(),
+            self.epoch(),
+            self.mls_group_config.max_past_epochs(),
+            self.mls_group_config.wire_format_policy().incoming(),
+        )
+    }
+
+    /// Returns the [`GroupContext`] of the current epoch.
+    pub fn export_group_context(&self) -> &GroupContext {
+        self.group.context()
+    }
+
+    /// Returns the protocol version of the group.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.group.version()
+    }
+
+    /// Returns the tree hash of the current epoch.
+    pub fn tree_hash(&self) -> &[u8] {
+        self.group.context().tree_hash()
+    }
+
+    /// Returns the confirmed transcript hash of the current epoch.
+    pub fn confirmed_transcript_hash(&self) -> &[u8] {
+        self.group.context().confirmed_transcript_hash()
+    }
+
+    /// Returns the interim transcript hash of the current epoch.
+    pub fn interim_transcript_hash(&self) -> &[u8] {
+        self.group.interim_transcript_hash()
+    }
+
+    /// Returns the extensions in the [`GroupContext`] of the current epoch.
+    pub fn group_context_extensions(&self) -> &Extensions {
+        self.group.context().extensions()
+    }
+
+    /// Returns the [`RequiredCapabilitiesExtension`] in the [`GroupContext`]
+    /// of the current epoch, if present. All members of the group support the
+    /// required extension, proposal and credential types.
+    pub fn required_capabilities(&self) -> Option<&RequiredCapabilitiesExtension> {
+        self.group.required_capabilities()
+    }
+
+    /// Returns `true` if application messages in this group are compressed,
+    /// i.e. if [`ExtensionType::DeflateCompression`] is one of the required
+    /// extension types of the group. See the `compression-deflate` feature.
+    pub fn uses_application_compression(&self) -> bool {
+        compression::is_enabled(self.group.required_capabilities())
+    }
+
+    /// Returns an [`ErrorContext`](crate::error::ErrorContext) describing the
+    /// current state of this group, i.e. its group ID, epoch and own leaf
+    /// index. The errors returned by this group's functions don't contain
+    /// this context, but it can be attached to them via
+    /// [`ErrorContext::wrap()`](crate::error::ErrorContext::wrap).
+    pub fn error_context(&self) -> crate::error::ErrorContext {
+        crate::error::ErrorContext::new(self.group_id().clone(), self.epoch())
+            .with_leaf_index(self.own_leaf_index())
+    }
+
+    /// Returns the changes to the membership of the group by the most recently
+    /// merged commit, i.e. the added, removed and updated members and whether
+    /// the group context extensions changed. Returns `None` if no commit was
+    /// merged since the group was created, joined or loaded.
+    pub fn membership_diff(&self) -> Option<&MembershipDiff> {
+        self.membership_diff.as_ref()
+    }
+
+    /// Returns the [`GroupEvent`]s of the most recently merged commit, i.e.
+    /// who added, removed or updated which member and whether the group
+    /// context extensions changed. Returns an empty slice if no commit was
+    /// merged since the group was created, joined or loaded.
+    pub fn group_events(&self) -> &[GroupEvent] {
+        &self.group_events
+    }
+
     /// Returns an `Iterator` over pending proposals.
     pub fn pending_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
         self.proposal_store.proposals()
@@ -296,21 +622,72 @@ impl MlsGroup {
     // === Load & save ===
 
     /// Loads the state from persisted state.
+    ///
+    /// States persisted by earlier versions of OpenMLS, in which the
+    /// extensions were persisted as a map, are migrated. Other serializers
+    /// than JSON don't support this, see [`MlsGroup::load_with()`].
     pub fn load<R: Read>(reader: R) -> Result<MlsGroup, Error> {
+        let state: serde_json::Value = serde_json::from_reader(reader)?;
         // TODO #245: Remove this once we have a proper serialization format
         #[allow(deprecated)]
-        let serialized_mls_group: SerializedMlsGroup = serde_json::from_reader(reader)?;
+        let serialized_mls_group = SerializedMlsGroup::from_json(state)?;
         Ok(serialized_mls_group.into_mls_group())
     }
 
-    /// Persists the state.
+    /// Loads the state from state persisted with [`MlsGroup::save_with()`]
+    /// using the same `serializer`.
+    pub fn load_with<S: StateSerializer, R: Read>(
+        serializer: &S,
+        reader: R,
+    ) -> Result<MlsGroup, S::Error> {
+        // TODO #245: Remove this once we have a proper serialization format
+        #[allow(deprecated)]
+        let serialized_mls_group: SerializedMlsGroup = serializer.deserialize(reader)?;
+        Ok(serialized_mls_group.into_mls_group())
+    }
+
+    /// Persists the state. This includes the pending proposals and the
+    /// pending commit together with the key material needed to merge it, such
+    /// that [`MlsGroup::merge_pending_commit()`] can still be called after the
+    /// group was loaded again.
     pub fn save<W: Write>(&mut self, writer: &mut W) -> Result<(), Error> {
-        let serialized_mls_group = serde_json::to_string_pretty(self)?;
-        writer.write_all(&serialized_mls_group.into_bytes())?;
+        Ok(self.save_with(&JsonSerializer, writer)?)
+    }
+
+    /// Persists the state like [`MlsGroup::save()`], but in the format of the
+    /// given `serializer` instead of JSON.
+    pub fn save_with<S: StateSerializer, W: Write>(
+        &mut self,
+        serializer: &S,
+        writer: W,
+    ) -> Result<(), S::Error> {
+        serializer.serialize(writer, self)?;
         self.state_changed = InnerState::Persisted;
         Ok(())
     }
 
+    /// Returns the persisted state as bytes, e.g. to store it in a database or
+    /// a browser storage where no [`Write`] is available. The bytes are the
+    /// same as written by [`MlsGroup::save()`].
+    pub fn serialize_state(&mut self) -> Result<Vec<u8>, LibraryError> {
+        let mut state = Vec::new();
+        self.save_with(&JsonSerializer, &mut state)
+            .map_err(|_| LibraryError::custom("Could not serialize the group state"))?;
+        Ok(state)
+    }
+
+    /// Restores the group from the bytes returned by
+    /// [`MlsGroup::serialize_state()`] or written by [`MlsGroup::save()`].
+    pub fn deserialize_state(state: &[u8]) -> Result<MlsGroup, DeserializeStateError> {
+        Self::load(state).map_err(|_| DeserializeStateError::MalformedState)
+    }
+
+    /// Marks the state as persisted, e.g. after it was saved as part of an
+    /// [`MlsClient`](crate::client::MlsClient).
+    pub(crate) fn set_persisted(&mut self) {
+        self.state_changed = InnerState::Persisted;
+    }
+
     /// Returns `true` if the internal state has changed and needs to be persisted and
     /// `false` otherwise. Calling [`Self::save()`] resets the value to `false`.
     pub fn state_changed(&self) -> InnerState {
@@ -323,6 +700,19 @@ impl MlsGroup {
     pub fn export_ratchet_tree(&self) -> Vec<Option<Node>> {
         self.group.treesync().export_nodes()
     }
+
+    /// Exports the [`PartialTree`] of the member at `leaf_index`, which can
+    /// be sent to the member instead of the full Ratchet Tree.
+    #[cfg(feature = "partial-tree")]
+    pub fn export_partial_tree(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        leaf_index: LeafNodeIndex,
+    ) -> Result<PartialTree, PartialTreeError> {
+        self.group
+            .treesync()
+            .partial_tree(backend, self.ciphersuite(), leaf_index)
+    }
 }
 
 // Private methods of MlsGroup
@@ -363,11 +753,6 @@ impl MlsGroup {
         Ok(msg)
     }
 
-    /// Arm the state changed flag function
-    fn flag_state_change(&mut self) {
-        self.state_changed = InnerState::Changed;
-    }
-
     /// Group framing parameters
     pub(crate) fn framing_parameters(&self) -> FramingParameters {
         FramingParameters::new(
@@ -390,18 +775,19 @@ impl MlsGroup {
 // Methods used in tests
 impl MlsGroup {
     #[cfg(any(feature = "test-utils", test))]
-    pub fn export_group_context(&self) -> &GroupContext {
-        self.group.context()
-    }
-
-    #[cfg(any(feature = "test-utils", test))]
-    pub fn tree_hash(&self) -> &[u8] {
-        self.group.treesync().tree_hash()
+    pub fn print_tree(&self, message: &str) {
+        self.group.print_tree(message)
     }
 
+    /// Returns the intermediate values of the key schedule the current epoch
+    /// was derived with, e.g. to confirm the derivations against the
+    /// specification.
+    ///
+    /// The values are not persisted, so this returns [`None`] if the epoch was
+    /// derived before the group was loaded.
     #[cfg(any(feature = "test-utils", test))]
-    pub fn print_tree(&self, message: &str) {
-        self.group.print_tree(message)
+    pub fn key_schedule_secrets(&self) -> Option<&crate::schedule::KeyScheduleSecrets> {
+        self.group.group_epoch_secrets().key_schedule_secrets()
     }
 
     /// Returns the underlying [CoreGroup].
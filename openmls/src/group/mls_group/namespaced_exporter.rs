@@ -0,0 +1,99 @@
+//! Namespaced exports of secrets from the group, see [`MlsGroup::exporter()`].
+
+use tls_codec::{Serialize as TlsSerializeTrait, TlsSerialize, TlsSize, VLBytes};
+
+use super::*;
+
+/// The label of the MLS exporter that all namespaced secrets are exported with.
+const NAMESPACED_EXPORTER_LABEL: &str = "OpenMLS namespaced exporter";
+
+/// An exporter that derives secrets from the group within a namespace, such
+/// that different subsystems of an application can derive independent secrets
+/// without coordinating their labels.
+///
+/// The secrets are exported with [`MlsGroup::export_secret()`] under a fixed
+/// label and a context that length-prefixes the namespace, the label and the
+/// context of the export. Secrets of different namespaces are thus
+/// independent, even if the namespaces, labels or contexts are prefixes of
+/// each other.
+///
+/// A `NamespacedExporter` is created with [`MlsGroup::exporter()`] and can be
+/// split further with [`NamespacedExporter::sub_exporter()`].
+#[derive(Debug, Clone)]
+pub struct NamespacedExporter<'a> {
+    group: &'a MlsGroup,
+    namespace: Vec<String>,
+}
+
+/// The context the secrets of a [`NamespacedExporter`] are exported with.
+///
+/// ```text
+/// struct {
+///     opaque namespace<V><V>;
+///     opaque label<V>;
+///     opaque context<V>;
+/// } NamespacedExporterContext;
+/// ```
+#[derive(TlsSerialize, TlsSize)]
+struct NamespacedExporterContext {
+    namespace: Vec<VLBytes>,
+    label: VLBytes,
+    context: VLBytes,
+}
+
+impl<'a> NamespacedExporter<'a> {
+    /// Returns the namespace of the exporter, starting with the outermost
+    /// namespace.
+    pub fn namespace(&self) -> &[String] {
+        &self.namespace
+    }
+
+    /// Returns an exporter for the `namespace` within the namespace of this
+    /// exporter, e.g. for a component of a subsystem.
+    pub fn sub_exporter(&self, namespace: &str) -> NamespacedExporter<'a> {
+        let mut sub_namespace = self.namespace.clone();
+        sub_namespace.push(namespace.to_owned());
+        NamespacedExporter {
+            group: self.group,
+            namespace: sub_namespace,
+        }
+    }
+
+    /// Exports a secret of length `key_length` with the given `label` and
+    /// `context` from the current epoch within the namespace of the exporter.
+    ///
+    /// Returns the same errors as [`MlsGroup::export_secret()`].
+    pub fn export_secret(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<ExportedSecret, ExportSecretError> {
+        let context = NamespacedExporterContext {
+            namespace: self
+                .namespace
+                .iter()
+                .map(|namespace| namespace.as_bytes().into())
+                .collect(),
+            label: label.as_bytes().into(),
+            context: context.into(),
+        }
+        .tls_serialize_detached()
+        .map_err(LibraryError::missing_bound_check)?;
+        self.group
+            .export_secret(backend, NAMESPACED_EXPORTER_LABEL, &context, key_length)
+    }
+}
+
+impl MlsGroup {
+    /// Returns a [`NamespacedExporter`] that exports secrets from the group
+    /// within the given `namespace`, e.g. the name of a subsystem of the
+    /// application.
+    pub fn exporter(&self, namespace: &str) -> NamespacedExporter<'_> {
+        NamespacedExporter {
+            group: self,
+            namespace: vec![namespace.to_owned()],
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! A group that a client joined with a [`PartialTree`] instead of the full
+//! ratchet tree, see [`PartialGroup`].
+
+use crate::schedule::{EpochAuthenticator, GroupEpochSecrets};
+
+use super::*;
+
+/// A group that a client joined from a [`Welcome`] with its [`PartialTree`]
+/// instead of the full ratchet tree, see
+/// [`ProcessedWelcome::into_partial_group()`].
+///
+/// A [`PartialGroup`] holds the [`GroupContext`] and the epoch secrets of the
+/// epoch the client joined in. It can export secrets of that epoch, e.g. to
+/// verify the membership of the client out of band, but it can't process or
+/// create messages, since that requires the full ratchet tree.
+#[derive(Debug)]
+pub struct PartialGroup {
+    group_context: GroupContext,
+    group_epoch_secrets: GroupEpochSecrets,
+    partial_tree: PartialTree,
+    welcome_sender: LeafNodeIndex,
+}
+
+impl PartialGroup {
+    pub(super) fn new(
+        group_context: GroupContext,
+        group_epoch_secrets: GroupEpochSecrets,
+        partial_tree: PartialTree,
+        welcome_sender: LeafNodeIndex,
+    ) -> Self {
+        Self {
+            group_context,
+            group_epoch_secrets,
+            partial_tree,
+            welcome_sender,
+        }
+    }
+
+    /// Returns the [`GroupContext`] of the group.
+    pub fn group_context(&self) -> &GroupContext {
+        &self.group_context
+    }
+
+    /// Returns the ID of the group.
+    pub fn group_id(&self) -> &GroupId {
+        self.group_context.group_id()
+    }
+
+    /// Returns the epoch the client joined in.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.group_context.epoch()
+    }
+
+    /// Returns the leaf index of the client.
+    pub fn own_leaf_index(&self) -> LeafNodeIndex {
+        self.partial_tree.leaf_index()
+    }
+
+    /// Returns the verified [`PartialTree`] of the client.
+    pub fn partial_tree(&self) -> &PartialTree {
+        &self.partial_tree
+    }
+
+    /// Returns the leaf index of the member that sent the Welcome, i.e. the
+    /// signer of the GroupInfo.
+    pub fn welcome_sender_index(&self) -> LeafNodeIndex {
+        self.welcome_sender
+    }
+
+    /// Returns the epoch authenticator of the epoch the client joined in.
+    pub fn epoch_authenticator(&self) -> &EpochAuthenticator {
+        self.group_epoch_secrets.epoch_authenticator()
+    }
+
+    /// Exports a secret of the epoch the client joined in, see
+    /// [`MlsGroup::export_secret()`].
+    pub fn export_secret(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<ExportedSecret, ExportSecretError> {
+        if key_length > u16::MAX.into() {
+            return Err(ExportSecretError::KeyLengthTooLong);
+        }
+        Ok(self
+            .group_epoch_secrets
+            .exporter_secret()
+            .derive_exported_secret(
+                self.group_context.ciphersuite(),
+                backend,
+                label,
+                context,
+                key_length,
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?)
+    }
+}
@@ -7,9 +7,18 @@ use tls_codec::Serialize;
 
 use crate::messages::GroupInfo;
 
-use crate::group::errors::MergeCommitError;
+use crate::{
+    group::errors::{
+        ExternalCommitValidationError, MergeCommitError, StageCommitError, ValidationError,
+    },
+    schedule::{
+        errors::WritePskError,
+        psk::{Psk, ResumptionPsk, ResumptionPskUsage},
+    },
+    tree::sender_ratchet::SenderRatchetConfigurations,
+};
 
-use super::{errors::ProcessMessageError, *};
+use super::{errors::ProcessMessageError, membership::ProposalSenders, *};
 
 impl MlsGroup {
     /// Parses incoming messages from the DS. Checks for syntactic errors and
@@ -20,7 +29,9 @@ impl MlsGroup {
     ///
     /// # Errors:
     /// Returns an [`ProcessMessageError`] when the validation checks fail
-    /// with the exact reason of the failure.
+    /// with the exact reason of the failure, e.g.
+    /// [`ProcessMessageError::VersionMismatch`] if the message was converted
+    /// from an [`MlsMessageIn`] with another protocol version than the group.
     pub fn process_message(
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
@@ -33,6 +44,7 @@ impl MlsGroup {
             ));
         }
         let message = message.into();
+        self.check_version(&message)?;
 
         // Check that handshake messages are compatible with the incoming wire format policy
         if !message.is_external()
@@ -46,19 +58,210 @@ impl MlsGroup {
             return Err(ProcessMessageError::IncompatibleWireFormat);
         }
 
+        // Proposals from outside of the group are rate-limited before they
+        // are validated
+        if !self.allows_external_proposal(&message) {
+            return Err(ProcessMessageError::ExternalProposalRejected);
+        }
+
+        if self.uses_strict_validation() {
+            self.validate_strict_message(&message)?;
+        }
+
+        // Reject handshake messages that have already been processed
+        let replay_digest = self
+            .replay_window
+            .digest(backend, self.ciphersuite(), &message)?;
+        if let Some(digest) = &replay_digest {
+            if self.replay_window.contains(digest) {
+                return Err(ProcessMessageError::ReplayDetected);
+            }
+        }
+
+        // Commits that were already merged are redelivered by at-least-once
+        // transports
+        if self.is_merged_commit(backend, &message)? {
+            return Err(ProcessMessageError::AlreadyProcessed);
+        }
+        let commit_digest = self.private_commit_digest(backend, &message)?;
+
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::MessageProcessed);
 
         // Parse the message
-        let sender_ratchet_configuration =
-            self.configuration().sender_ratchet_configuration().clone();
-        self.group.process_message(
+        let sender_ratchet_configurations = SenderRatchetConfigurations::new(
+            self.configuration().sender_ratchet_configuration().clone(),
+            self.sender_ratchet_overrides.clone(),
+        );
+        let processed_message = self.group.process_message(
             backend,
             message,
-            &sender_ratchet_configuration,
+            &sender_ratchet_configurations,
             &self.proposal_store,
             &self.own_leaf_nodes,
-        )
+        );
+        // Skipped keys are stored even if the message is rejected afterwards
+        self.enforce_skipped_keys_limit()?;
+        let mut processed_message = processed_message?;
+
+        // ValSem247: External Commit must cover an inline external
+        //            PreSharedKey proposal if the group requires it.
+        if self.configuration().require_external_commit_psk()
+            && processed_message.sender() == &Sender::NewMemberCommit
+        {
+            if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+                processed_message.content()
+            {
+                let contains_external_psk = staged_commit.psk_proposals().any(|psk_proposal| {
+                    matches!(psk_proposal.psk_proposal().psk().psk(), Psk::External(_))
+                });
+                if !contains_external_psk {
+                    return Err(ProcessMessageError::InvalidCommit(
+                        StageCommitError::ExternalCommitValidation(
+                            ExternalCommitValidationError::MissingExternalPsk,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        if self.uses_strict_validation() {
+            self.validate_strict_processed_message(backend, &processed_message)?;
+        }
+
+        if self.processed_message_exceeds_max_members(&processed_message) {
+            return Err(ProcessMessageError::TooManyMembers);
+        }
+
+        if self.processed_message_is_downgrade(&processed_message) {
+            return Err(ProcessMessageError::DowngradeDetected);
+        }
+
+        if !self.processed_message_accepts_new_members(&processed_message) {
+            return Err(ProcessMessageError::DuplicateIdentity);
+        }
+
+        #[cfg(feature = "verifiable-credentials")]
+        if !self.processed_message_verifies_credentials(&processed_message) {
+            return Err(ProcessMessageError::InvalidVerifiableCredential);
+        }
+
+        #[cfg(feature = "token-bound-credentials")]
+        if !self.processed_message_verifies_token_bindings(&processed_message) {
+            return Err(ProcessMessageError::InvalidTokenBinding);
+        }
+
+        if !self.processed_message_verifies_key_packages(backend, &processed_message)? {
+            return Err(ProcessMessageError::UntrustedKeyPackage);
+        }
+
+        // Decompress application messages if the group uses compression
+        if self.uses_application_compression() {
+            if let ProcessedMessageContent::ApplicationMessage(application_message) =
+                processed_message.content_mut()
+            {
+                *application_message =
+                    ApplicationMessage::new(compression::decompress(application_message.bytes())?);
+            }
+        }
+
+        if let Some(digest) = replay_digest {
+            self.replay_window.add(digest);
+        }
+
+        if let (Some(commit_digest), ProcessedMessageContent::StagedCommitMessage(staged_commit)) =
+            (commit_digest, processed_message.content_mut())
+        {
+            staged_commit.set_commit_digest(commit_digest);
+        }
+
+        Ok(processed_message)
+    }
+
+    /// Parses an incoming [`MlsMessageIn`] that contains a [`PublicMessage`]
+    /// or a [`PrivateMessage`] like [`MlsGroup::process_message()`].
+    ///
+    /// # Errors:
+    /// Returns [`ValidationError::WrongWireFormat`] if the message is neither
+    /// a [`PublicMessage`] nor a [`PrivateMessage`]. Otherwise, the errors of
+    /// [`MlsGroup::process_message()`] are returned.
+    pub fn process_mls_message(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: MlsMessageIn,
+    ) -> Result<ProcessedMessage, ProcessMessageError> {
+        let message = message
+            .into_protocol_message()
+            .ok_or(ValidationError::WrongWireFormat)?;
+        self.process_message(backend, message)
+    }
+
+    /// Checks that the `message` uses the protocol version of the group, if
+    /// its version is known.
+    fn check_version(&self, message: &ProtocolMessage) -> Result<(), ProcessMessageError> {
+        match message.version() {
+            Some(version) if version != self.protocol_version() => {
+                Err(ProcessMessageError::VersionMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validates an incoming message without decrypting it, e.g. in a relay
+    /// that filters the traffic of the group before it is delivered. The
+    /// state of the group isn't changed, i.e. no sender ratchet is advanced,
+    /// no proposal is stored and the message isn't recorded for replay
+    /// detection, so the message can still be processed with
+    /// [`MlsGroup::process_message()`] afterwards.
+    ///
+    /// For a [`PublicMessage`] the framing, the epoch, the wire format, the
+    /// membership tag and the signature are checked. Since the sender and the
+    /// content of a [`PrivateMessage`] are encrypted, only its group ID and
+    /// epoch are checked, and that the message secrets of the epoch are
+    /// still available. The content of commits and proposals isn't validated.
+    ///
+    /// # Errors:
+    /// Returns a [`ProcessMessageError`] when one of the checks fails.
+    pub fn validate_message(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: impl Into<ProtocolMessage>,
+    ) -> Result<(), ProcessMessageError> {
+        if !self.is_active() {
+            return Err(ProcessMessageError::GroupStateError(
+                MlsGroupStateError::UseAfterEviction,
+            ));
+        }
+        let message = message.into();
+        self.check_version(&message)?;
+
+        if !message.is_external()
+            && message.is_handshake_message()
+            && !self
+                .configuration()
+                .wire_format_policy()
+                .incoming()
+                .is_compatible_with(message.wire_format())
+        {
+            return Err(ProcessMessageError::IncompatibleWireFormat);
+        }
+
+        if self.uses_strict_validation() {
+            self.validate_strict_message(&message)?;
+        }
+
+        if let Some(digest) = self
+            .replay_window
+            .digest(backend, self.ciphersuite(), &message)?
+        {
+            if self.replay_window.contains(&digest) {
+                return Err(ProcessMessageError::ReplayDetected);
+            }
+        }
+
+        self.group
+            .validate_message(backend, message)
+            .map_err(ProcessMessageError::from)
     }
 
     /// Stores a standalone proposal in the internal [ProposalStore]
@@ -67,7 +270,10 @@ impl MlsGroup {
         self.proposal_store.add(proposal);
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::ProposalStored);
+
+        // Drop the proposal again if it was sent in another epoch
+        self.expire_pending_proposals();
     }
 
     /// Creates a Commit message that covers the pending proposals that are
@@ -82,9 +288,49 @@ impl MlsGroup {
     ) -> Result<
         (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
         CommitToPendingProposalsError<KeyStore::Error>,
+    > {
+        self.commit_to_selected_pending_proposals(backend, None)
+    }
+
+    /// Creates a Commit message that covers the pending proposals for which
+    /// `filter` returns `true`, e.g. to leave out a contested Remove proposal.
+    ///
+    /// The other pending proposals stay in the group's [ProposalStore] until
+    /// the commit is merged, such that they are still available if the
+    /// pending commit is cleared. Merging the commit drops them like all
+    /// proposals that a commit doesn't cover, see
+    /// [`MlsGroup::take_expired_proposals()`].
+    ///
+    /// Returns an error if there is a pending commit.
+    #[allow(clippy::type_complexity)]
+    pub fn commit_to_pending_proposals_filtered<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        filter: impl Fn(&QueuedProposal) -> bool,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        CommitToPendingProposalsError<KeyStore::Error>,
+    > {
+        self.commit_to_selected_pending_proposals(backend, Some(&filter))
+    }
+
+    /// Creates a Commit message that covers the pending proposals for which
+    /// `filter` returns `true`, or all pending proposals if there is no
+    /// `filter`.
+    #[allow(clippy::type_complexity)]
+    fn commit_to_selected_pending_proposals<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        filter: Option<&dyn Fn(&QueuedProposal) -> bool>,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        CommitToPendingProposalsError<KeyStore::Error>,
     > {
         self.is_operational()?;
 
+        // Drop expired proposals, such that they are not committed
+        self.expire_pending_proposals();
+
         let credential = self.credential()?;
         let credential_bundle: CredentialBundle = backend
             .key_store()
@@ -96,13 +342,42 @@ impl MlsGroup {
             )
             .ok_or(CommitToPendingProposalsError::NoMatchingCredentialBundle)?;
 
-        // Create Commit over all pending proposals
+        // Create Commit over the selected pending proposals
         // TODO #751
-        let params = CreateCommitParams::builder()
+        let selected = |queued_proposal: &&QueuedProposal| {
+            filter.map_or(true, |filter| filter(queued_proposal))
+        };
+
+        if self.proposals_exceed_max_members(self.proposal_store.proposals().filter(selected), 0) {
+            return Err(CommitToPendingProposalsError::TooManyMembers);
+        }
+
+        if !self
+            .proposals_accept_key_packages(self.proposal_store.proposals().filter(selected), &[])
+        {
+            return Err(CommitToPendingProposalsError::DuplicateIdentity);
+        }
+
+        if !self.verifies_key_packages(
+            backend,
+            self.proposal_store
+                .proposals()
+                .filter(selected)
+                .filter_map(|queued_proposal| match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => Some(add_proposal.key_package()),
+                    _ => None,
+                }),
+        )? {
+            return Err(CommitToPendingProposalsError::UntrustedKeyPackage);
+        }
+        let mut params = CreateCommitParams::builder()
             .framing_parameters(self.framing_parameters())
             .credential_bundle(&credential_bundle)
-            .proposal_store(&self.proposal_store)
-            .build();
+            .proposal_store(&self.proposal_store);
+        if let Some(filter) = filter {
+            params = params.proposal_filter(filter);
+        }
+        let params = params.build();
         let create_commit_result = self.group.create_commit(params, backend)?;
 
         // Convert PublicMessage messages to MLSMessage and encrypt them if required by
@@ -111,12 +386,10 @@ impl MlsGroup {
 
         // Set the current group state to [`MlsGroupState::PendingCommit`],
         // storing the current [`StagedCommit`] from the commit results
-        self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
-            create_commit_result.staged_commit,
-        )));
+        self.set_own_pending_commit(backend, create_commit_result.staged_commit, &mls_message)?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::CommitCreated);
 
         Ok((
             mls_message,
@@ -129,28 +402,98 @@ impl MlsGroup {
 
     /// Merge a [StagedCommit] into the group after inspection. As this advances
     /// the epoch of the group, it also clears any pending commits.
+    ///
+    /// If the Commit covers a ReInit proposal, the resumption PSK of the new
+    /// epoch is written to the key store, such that the successor group can be
+    /// joined through [`MlsGroup::new_from_welcome()`]. The group becomes
+    /// inactive, since it is replaced by the successor group.
     pub fn merge_staged_commit<KeyStore: OpenMlsKeyStore>(
         &mut self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         staged_commit: StagedCommit,
     ) -> Result<(), MergeCommitError<KeyStore::Error>> {
-        // Check if we were removed from the group
-        if staged_commit.self_removed() {
-            self.group_state = MlsGroupState::Inactive;
-        }
+        let self_removed = staged_commit.self_removed();
+        let is_reinit = staged_commit.reinit_proposal().is_some();
+        let commit_digest = staged_commit.commit_digest().map(<[u8]>::to_vec);
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::CommitMerged);
+
+        let removed_leaves: Vec<LeafNodeIndex> = staged_commit
+            .remove_proposals()
+            .map(|remove_proposal| remove_proposal.remove_proposal().removed())
+            .collect();
+
+        // Remember the membership before the merge to compute the membership
+        // diff
+        let members_before: Vec<Member> = self.members().collect();
+        let extensions_before = self.group_context_extensions().clone();
+        let proposal_senders = ProposalSenders::new(&staged_commit);
 
-        // Merge staged commit
+        // Pending proposals that are not committed are dropped with the merge
+        let uncommitted_proposals = self.uncommitted_proposals(&staged_commit);
+
+        // Merge staged commit. The state of the `MlsGroup` is only updated
+        // below, once the merge succeeded.
         self.group
             .merge_staged_commit(backend, staged_commit, &mut self.proposal_store)?;
+        self.merged_commit_digest = commit_digest;
+
+        // Check if we were removed from the group or the group is replaced
+        // by its successor
+        if self_removed || is_reinit {
+            self.group_state = MlsGroupState::Inactive;
+        }
+
+        // Sender ratchet overrides of removed members must not apply to new
+        // members that take their leaves.
+        for removed_leaf in &removed_leaves {
+            self.sender_ratchet_overrides.remove(removed_leaf);
+        }
+
+        self.expired_proposals.extend(uncommitted_proposals);
+
+        // A stored commit of the same epoch can't be merged anymore
+        self.stored_staged_commit = None;
+
+        self.membership_diff = Some(MembershipDiff::new(
+            members_before,
+            self.members(),
+            &removed_leaves,
+            &extensions_before != self.group_context_extensions(),
+        ));
+        self.group_events = self
+            .membership_diff
+            .as_ref()
+            .map(|membership_diff| {
+                proposal_senders.group_events(
+                    membership_diff,
+                    self.own_leaf_index(),
+                    &self.membership_history,
+                )
+            })
+            .unwrap_or_default();
+        self.record_membership_diff();
 
         // Extract and store the resumption psk for the current epoch
         let resumption_psk = self.group.group_epoch_secrets().resumption_psk();
         self.resumption_psk_store
             .add(self.group.context().epoch(), resumption_psk.clone());
 
+        // Make the resumption psk available to the successor group
+        if is_reinit {
+            ResumptionPsk::new(
+                ResumptionPskUsage::Reinit,
+                self.group_id().clone(),
+                self.epoch(),
+            )
+            .write_to_key_store(backend, self.ciphersuite(), resumption_psk.expose())
+            .map_err(|e| match e {
+                WritePskError::LibraryError(e) => MergeCommitError::LibraryError(e),
+                WritePskError::KeyStoreError(e) => MergeCommitError::KeyStoreError(e),
+            })?;
+        }
+
         // Delete own KeyPackageBundles
         self.own_leaf_nodes.clear();
 
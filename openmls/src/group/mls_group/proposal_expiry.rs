@@ -0,0 +1,61 @@
+//! Expiry of pending proposals that were not committed, see
+//! [`MlsGroup::take_expired_proposals()`].
+
+use std::{collections::HashSet, mem};
+
+use crate::ciphersuite::hash_ref::ProposalRef;
+
+use super::*;
+
+impl MlsGroup {
+    /// Removes the pending proposals that were sent in another epoch than the
+    /// current one and, if the [`MlsGroupConfig`] has a maximum age of pending
+    /// proposals, the pending proposals that are older. The removed proposals
+    /// are returned by [`MlsGroup::take_expired_proposals()`].
+    ///
+    /// This is done automatically before the group creates a commit and when
+    /// a proposal is stored with [`MlsGroup::store_pending_proposal()`].
+    pub fn expire_pending_proposals(&mut self) {
+        let expired = self.proposal_store.remove_expired(
+            self.epoch(),
+            self.configuration().max_pending_proposal_age(),
+        );
+        if !expired.is_empty() {
+            self.expired_proposals.extend(expired);
+            self.flag_state_change(StateChangeReason::ProposalsExpired);
+        }
+    }
+
+    /// Returns the pending proposals that were dropped without being
+    /// committed since this function was last called, and forgets them.
+    ///
+    /// Pending proposals are dropped when a commit that doesn't cover them is
+    /// merged, when they were sent in another epoch than the current one or
+    /// when they are older than the maximum age of pending proposals of the
+    /// [`MlsGroupConfig`]. Applications can use this to re-propose changes
+    /// that were lost.
+    pub fn take_expired_proposals(&mut self) -> Vec<QueuedProposal> {
+        if !self.expired_proposals.is_empty() {
+            self.flag_state_change(StateChangeReason::ProposalsExpired);
+        }
+        mem::take(&mut self.expired_proposals)
+    }
+
+    /// Returns the pending proposals that are not covered by the
+    /// `staged_commit`. They have to be recorded as expired once the commit
+    /// is merged, since the proposal store is emptied then.
+    pub(super) fn uncommitted_proposals(
+        &self,
+        staged_commit: &StagedCommit,
+    ) -> Vec<QueuedProposal> {
+        let committed: HashSet<ProposalRef> = staged_commit
+            .queued_proposals()
+            .map(|queued_proposal| queued_proposal.proposal_reference())
+            .collect();
+        self.proposal_store
+            .proposals()
+            .filter(|queued_proposal| !committed.contains(&queued_proposal.proposal_reference()))
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,111 @@
+//! Proposals that are committed right away
+//!
+//! This module contains variants of the proposal operations that create a
+//! proposal and immediately commit to it. The split operations, e.g.
+//! [`MlsGroup::propose_add_member()`] and
+//! [`MlsGroup::commit_to_pending_proposals()`], remain available for flows in
+//! which another member, e.g. one chosen by the DS, commits to the proposals.
+
+use crate::{binary_tree::array_representation::LeafNodeIndex, messages::GroupInfo};
+
+use super::{errors::ProposeAndCommitError, *};
+
+impl MlsGroup {
+    /// Creates a proposal to add a member to the group and immediately commits
+    /// to it, together with all other pending proposals.
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`]s, where the
+    /// first contains the proposal, the second the commit, the third an
+    /// optional [`Welcome`] and the fourth an optional [GroupInfo] that will be
+    /// [Some] if the group has the `use_ratchet_tree_extension` flag set. The
+    /// proposal is sent by reference in the commit, so it has to be sent to
+    /// the other members before the commit.
+    ///
+    /// If the commit can't be created, the proposal is dropped again.
+    ///
+    /// Returns an error if there is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn propose_add_member_and_commit<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        key_package: &KeyPackage,
+    ) -> Result<
+        (
+            MlsMessageOut,
+            MlsMessageOut,
+            Option<MlsMessageOut>,
+            Option<GroupInfo>,
+        ),
+        ProposeAndCommitError<KeyStore::Error>,
+    > {
+        let proposal = self.propose_add_member(backend, key_package)?;
+        self.commit_to_own_proposal(backend, proposal)
+    }
+
+    /// Creates a proposal to remove a member from the group and immediately
+    /// commits to it, together with all other pending proposals. The `member`
+    /// has to be the member's leaf index.
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`]s, where the
+    /// first contains the proposal, the second the commit, the third an
+    /// optional [`Welcome`] and the fourth an optional [GroupInfo] that will be
+    /// [Some] if the group has the `use_ratchet_tree_extension` flag set. The
+    /// proposal is sent by reference in the commit, so it has to be sent to
+    /// the other members before the commit.
+    ///
+    /// If the commit can't be created, the proposal is dropped again.
+    ///
+    /// Returns an error if there is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn propose_remove_member_and_commit<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        member: LeafNodeIndex,
+    ) -> Result<
+        (
+            MlsMessageOut,
+            MlsMessageOut,
+            Option<MlsMessageOut>,
+            Option<GroupInfo>,
+        ),
+        ProposeAndCommitError<KeyStore::Error>,
+    > {
+        let proposal = self.propose_remove_member(backend, member)?;
+        self.commit_to_own_proposal(backend, proposal)
+    }
+
+    /// Commits to the pending proposals, including the `proposal` that was
+    /// just created and stored as the last pending proposal. The proposal is
+    /// removed from the pending proposals if the commit fails.
+    #[allow(clippy::type_complexity)]
+    fn commit_to_own_proposal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        proposal: MlsMessageOut,
+    ) -> Result<
+        (
+            MlsMessageOut,
+            MlsMessageOut,
+            Option<MlsMessageOut>,
+            Option<GroupInfo>,
+        ),
+        ProposeAndCommitError<KeyStore::Error>,
+    > {
+        let proposal_reference = self
+            .proposal_store
+            .proposals()
+            .last()
+            .map(|queued_proposal| queued_proposal.proposal_reference())
+            .ok_or_else(|| LibraryError::custom("The proposal was not stored"))?;
+
+        match self.commit_to_pending_proposals(backend) {
+            Ok((commit, welcome, group_info)) => Ok((proposal, commit, welcome, group_info)),
+            Err(e) => {
+                self.proposal_store.remove(&proposal_reference);
+                Err(e.into())
+            }
+        }
+    }
+}
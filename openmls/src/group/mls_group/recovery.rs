@@ -0,0 +1,82 @@
+//! Recovery of members that missed commits and can't obtain them anymore, see
+//! [`MlsGroup::rejoin_by_external_commit()`].
+
+use tls_codec::Serialize;
+
+use crate::messages::VerifiableGroupInfo;
+
+use super::*;
+
+impl MlsGroup {
+    /// Returns the number of epochs the group is behind the epoch of the
+    /// `message`, i.e. the number of commits the group missed. Returns 0 if
+    /// the message belongs to another group or to the current or a past
+    /// epoch.
+    ///
+    /// If the missed commits can't be obtained anymore, the group can be
+    /// rejoined with [`MlsGroup::rejoin_by_external_commit()`].
+    pub fn missed_epochs(&self, message: &ProtocolMessage) -> u64 {
+        if message.group_id() != self.group_id() {
+            return 0;
+        }
+        message
+            .epoch()
+            .as_u64()
+            .saturating_sub(self.epoch().as_u64())
+    }
+
+    /// Rejoins the group through an External Commit, for example after the
+    /// group missed commits that can't be obtained anymore (see
+    /// [`MlsGroup::missed_epochs()`]).
+    ///
+    /// The current [`VerifiableGroupInfo`] of the group is obtained by calling
+    /// `fetch_group_info` with the group ID. It returns the group info and,
+    /// if the group info doesn't contain the ratchet tree extension, the
+    /// ratchet tree, or `None` if the group info is not available.
+    ///
+    /// The new [`MlsGroup`] uses the configuration, the AAD and the
+    /// duplicate identity callback of this group, and the client rejoins with
+    /// its current credential. The External Commit removes the client's old
+    /// leaf. Like with [`MlsGroup::join_by_external_commit()`], the returned
+    /// group has a pending commit that has to be merged once the External
+    /// Commit was accepted by the Delivery Service, and this group has to be
+    /// discarded.
+    pub fn rejoin_by_external_commit<F>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        fetch_group_info: F,
+    ) -> Result<(MlsGroup, MlsMessageOut), RejoinError>
+    where
+        F: FnOnce(&GroupId) -> Option<(VerifiableGroupInfo, Option<Vec<Option<Node>>>)>,
+    {
+        let credential = self.credential()?;
+        let credential_bundle: CredentialBundle = backend
+            .key_store()
+            .read(
+                &credential
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+            )
+            .ok_or(RejoinError::NoMatchingCredentialBundle)?;
+
+        let (verifiable_group_info, tree_option) =
+            fetch_group_info(self.group_id()).ok_or(RejoinError::MissingGroupInfo)?;
+        if verifiable_group_info.group_context().group_id() != self.group_id() {
+            return Err(RejoinError::WrongGroupId);
+        }
+
+        let (mut mls_group, commit) = MlsGroup::join_by_external_commit(
+            backend,
+            tree_option.as_deref(),
+            verifiable_group_info,
+            &self.mls_group_config,
+            &self.aad,
+            &credential_bundle,
+        )?;
+        mls_group.aad = self.aad.clone();
+        mls_group.duplicate_identity_callback = self.duplicate_identity_callback.clone();
+
+        Ok((mls_group, commit))
+    }
+}
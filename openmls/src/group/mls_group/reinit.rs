@@ -0,0 +1,229 @@
+//! Re-initialization of an [`MlsGroup`].
+//!
+//! This module contains the functions to move a group to a new ciphersuite
+//! using a ReInit proposal.
+
+use core_group::create_commit_params::CreateCommitParams;
+use tls_codec::Serialize;
+
+use crate::{
+    ciphersuite::signature::SignaturePublicKey,
+    group::config::CryptoConfig,
+    schedule::psk::{PreSharedKeyId, Psk, ResumptionPsk, ResumptionPskUsage},
+};
+
+use super::{errors::MigrateCiphersuiteError, *};
+
+impl MlsGroup {
+    /// Migrates the group to a new `ciphersuite`.
+    ///
+    /// This creates a Commit that only covers a ReInit proposal for a new group
+    /// with a random group ID and the given `ciphersuite` and merges it
+    /// directly. Afterwards, the successor group is created and all members
+    /// for which a [`KeyPackage`] is given in `key_packages` are added to it.
+    /// The resulting [`Welcome`] message contains a resumption PSK of the last
+    /// epoch of this group, which proves to the new members that the successor
+    /// group was created by a member of this group.
+    ///
+    /// The `key_packages` have to use the new `ciphersuite` and the own
+    /// credential has to be compatible with it. To migrate to a ciphersuite
+    /// with another signature scheme, e.g. one with a longer hash, use
+    /// [`MlsGroup::migrate_ciphersuite_with_credential()`].
+    ///
+    /// If successful, it returns a triple of the [`MlsMessageOut`] that
+    /// contains the ReInit Commit for this group, the successor [`MlsGroup`]
+    /// and an optional [`MlsMessageOut`] containing the [`Welcome`] for the
+    /// successor group. The Welcome is [Some] if `key_packages` is not empty.
+    ///
+    /// Other members merging the ReInit Commit store the resumption PSK, so
+    /// that they can join the successor group through
+    /// [`MlsGroup::new_from_welcome()`]. Since the ReInit Commit is merged
+    /// immediately, the Commit has to be sent to the group before the Welcome
+    /// message. This group becomes inactive afterwards, like the groups of the
+    /// other members once they merge the ReInit Commit.
+    ///
+    /// Returns an error if there is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn migrate_ciphersuite<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+        key_packages: &[KeyPackage],
+    ) -> Result<
+        (MlsMessageOut, MlsGroup, Option<MlsMessageOut>),
+        MigrateCiphersuiteError<KeyStore::Error>,
+    > {
+        let signature_key = self.credential()?.signature_key().clone();
+        self.migrate_ciphersuite_with_credential(backend, ciphersuite, &signature_key, key_packages)
+    }
+
+    /// Migrates the group to a new `ciphersuite` like
+    /// [`MlsGroup::migrate_ciphersuite()`], but uses the credential with the
+    /// given `signature_key` in the successor group. The ReInit Commit is
+    /// still signed with the own credential of this group.
+    ///
+    /// The credential has to use the signature scheme of the new
+    /// `ciphersuite`, and its [`CredentialBundle`] has to be stored in the
+    /// key store.
+    ///
+    /// Returns an error if there is a pending commit.
+    // FIXME: #1217
+    #[allow(clippy::type_complexity)]
+    pub fn migrate_ciphersuite_with_credential<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+        signature_key: &SignaturePublicKey,
+        key_packages: &[KeyPackage],
+    ) -> Result<
+        (MlsMessageOut, MlsGroup, Option<MlsMessageOut>),
+        MigrateCiphersuiteError<KeyStore::Error>,
+    > {
+        self.is_operational()?;
+
+        if self
+            .configuration()
+            .is_downgrade(ciphersuite, self.group.version())
+        {
+            return Err(MigrateCiphersuiteError::DowngradeDetected);
+        }
+
+        if key_packages
+            .iter()
+            .any(|key_package| key_package.ciphersuite() != ciphersuite)
+        {
+            return Err(MigrateCiphersuiteError::CiphersuiteMismatch);
+        }
+
+        let credential_bundle: CredentialBundle = backend
+            .key_store()
+            .read(
+                &self
+                    .credential()?
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+            )
+            .ok_or(MigrateCiphersuiteError::NoMatchingCredentialBundle)?;
+        let new_credential_bundle: CredentialBundle = backend
+            .key_store()
+            .read(
+                &signature_key
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+            )
+            .ok_or(MigrateCiphersuiteError::NoMatchingCredentialBundle)?;
+
+        // === Commit to the ReInit proposal in this group ===
+
+        let new_group_id = GroupId::random(backend);
+        let reinit_proposal = Proposal::ReInit(ReInitProposal::new(
+            new_group_id.clone(),
+            self.group.version(),
+            ciphersuite,
+            self.group.context().extensions().clone(),
+        ));
+
+        // The ReInit proposal has to be the only proposal in the Commit, so we
+        // don't include pending proposals.
+        let empty_proposal_store = ProposalStore::new();
+        let params = CreateCommitParams::builder()
+            .framing_parameters(self.framing_parameters())
+            .credential_bundle(&credential_bundle)
+            .proposal_store(&empty_proposal_store)
+            .inline_proposals(vec![reinit_proposal])
+            .build();
+        let create_commit_result = self.group.create_commit(params, backend)?;
+
+        let commit = self.content_to_mls_message(create_commit_result.commit, backend)?;
+
+        // Merging the ReInit Commit also stores the resumption PSK.
+        self.merge_staged_commit(backend, create_commit_result.staged_commit)?;
+
+        // === Create the successor group ===
+
+        let mls_group_config = MlsGroupConfig {
+            crypto_config: CryptoConfig {
+                ciphersuite,
+                version: self.group.version(),
+            },
+            ..self.mls_group_config.clone()
+        };
+        let mut new_group =
+            MlsGroup::new_with_group_id(backend, &mls_group_config, new_group_id, signature_key)?;
+
+        if key_packages.is_empty() {
+            return Ok((commit, new_group, None));
+        }
+
+        // === Add the members to the successor group ===
+
+        let resumption_psk_id = PreSharedKeyId::new(
+            ciphersuite,
+            backend.rand(),
+            Psk::Resumption(ResumptionPsk::new(
+                ResumptionPskUsage::Reinit,
+                self.group_id().clone(),
+                self.epoch(),
+            )),
+        )
+        .map_err(LibraryError::unexpected_crypto_error)?;
+
+        let mut inline_proposals = key_packages
+            .iter()
+            .map(|key_package| {
+                Proposal::Add(AddProposal {
+                    key_package: key_package.clone(),
+                })
+            })
+            .collect::<Vec<Proposal>>();
+        inline_proposals.push(Proposal::PreSharedKey(PreSharedKeyProposal::new(
+            resumption_psk_id,
+        )));
+
+        let params = CreateCommitParams::builder()
+            .framing_parameters(new_group.framing_parameters())
+            .credential_bundle(&new_credential_bundle)
+            .proposal_store(&new_group.proposal_store)
+            .inline_proposals(inline_proposals)
+            .build();
+        let create_commit_result = new_group.group.create_commit(params, backend)?;
+
+        // Nobody else is a member of the successor group yet, so we can merge
+        // the Commit right away.
+        new_group.merge_staged_commit(backend, create_commit_result.staged_commit)?;
+
+        let welcome = create_commit_result
+            .welcome_option
+            .map(|welcome| MlsMessageOut::from_welcome(welcome, new_group.group.version()));
+
+        Ok((commit, new_group, welcome))
+    }
+
+    /// Returns `true` if the processed message contains a ReInit proposal,
+    /// or is a commit covering one, that would move the group to a weaker
+    /// ciphersuite or protocol version than the configured minimums.
+    pub(super) fn processed_message_is_downgrade(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> bool {
+        let reinit_proposal = match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => None,
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::ReInit(reinit_proposal) => Some(reinit_proposal),
+                    _ => None,
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                staged_commit.reinit_proposal()
+            }
+        };
+        reinit_proposal.map_or(false, |reinit_proposal| {
+            self.configuration()
+                .is_downgrade(reinit_proposal.ciphersuite(), reinit_proposal.version())
+        })
+    }
+}
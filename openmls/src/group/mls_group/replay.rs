@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+
+use tls_codec::Serialize as TlsSerializeTrait;
+
+use super::*;
+
+/// Replay window for handshake messages. It keeps the digests of the most
+/// recently processed [`PublicMessage`] proposals and commits in a rollover
+/// list, such that a replayed message can be detected.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct ReplayWindow {
+    window_size: usize,
+    digests: VecDeque<Vec<u8>>,
+}
+
+impl ReplayWindow {
+    /// Creates a new window that holds up to `window_size` digests. A window
+    /// size of 0 disables replay detection.
+    pub(crate) fn new(window_size: usize) -> Self {
+        Self {
+            window_size,
+            digests: VecDeque::new(),
+        }
+    }
+
+    /// Returns the digest of the `message` if replay detection applies to it,
+    /// i.e. if it is a [`PublicMessage`] that contains a proposal or a commit
+    /// and the window is enabled.
+    pub(crate) fn digest(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        message: &ProtocolMessage,
+    ) -> Result<Option<Vec<u8>>, LibraryError> {
+        if self.window_size == 0 {
+            return Ok(None);
+        }
+        let public_message = match message.body() {
+            ProtocolMessageBody::PublicMessage(public_message)
+                if public_message.content_type() != ContentType::Application =>
+            {
+                public_message
+            }
+            _ => return Ok(None),
+        };
+        let serialized_message = public_message
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        backend
+            .crypto()
+            .hash(ciphersuite.hash_algorithm(), &serialized_message)
+            .map(Some)
+            .map_err(LibraryError::unexpected_crypto_error)
+    }
+
+    /// Returns `true` if the window contains the `digest`.
+    pub(crate) fn contains(&self, digest: &[u8]) -> bool {
+        self.digests.iter().any(|d| d == digest)
+    }
+
+    /// Adds a `digest` to the window and drops the oldest one if the window
+    /// is full.
+    pub(crate) fn add(&mut self, digest: Vec<u8>) {
+        if self.window_size == 0 {
+            return;
+        }
+        if self.digests.len() == self.window_size {
+            self.digests.pop_front();
+        }
+        self.digests.push_back(digest);
+    }
+}
@@ -36,6 +36,23 @@ impl ResumptionPskStore {
         }
     }
 
+    /// Returns the epochs of the stored resumption psks.
+    pub(crate) fn epochs(&self) -> impl Iterator<Item = GroupEpoch> + '_ {
+        self.resumption_psk.iter().map(|(epoch, _)| *epoch)
+    }
+
+    /// Overwrite all resumption psks with zeros and remove them from the
+    /// store. Returns the number of removed psks.
+    pub(crate) fn zeroize(&mut self) -> usize {
+        for (_, resumption_psk) in self.resumption_psk.iter_mut() {
+            resumption_psk.zeroize();
+        }
+        let removed = self.resumption_psk.len();
+        self.resumption_psk.clear();
+        self.cursor = 0;
+        removed
+    }
+
     /// Searches an entry for a given epoch number and if found, returns the
     /// corresponding resumption psk.
     pub(crate) fn get(&self, epoch: GroupEpoch) -> Option<&ResumptionPskSecret> {
@@ -0,0 +1,80 @@
+//! Retention of the message secrets of selected epochs and senders beyond
+//! [`MlsGroupConfig::max_past_epochs()`], see
+//! [`MlsGroup::pin_message_secrets()`].
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// A pin that keeps the message secrets of an epoch, or of a single sender in
+/// an epoch, after the epoch was evicted from the past epochs of the group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MessageSecretsPin {
+    epoch: GroupEpoch,
+    sender: Option<LeafNodeIndex>,
+}
+
+impl MessageSecretsPin {
+    /// Creates a pin for the message secrets of `sender` in `epoch`, or for
+    /// the message secrets of all senders in `epoch` if `sender` is `None`.
+    pub fn new(epoch: GroupEpoch, sender: Option<LeafNodeIndex>) -> Self {
+        Self { epoch, sender }
+    }
+
+    /// Returns the pinned epoch.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the pinned sender, or `None` if the message secrets of all
+    /// senders are pinned.
+    pub fn sender(&self) -> Option<LeafNodeIndex> {
+        self.sender
+    }
+}
+
+impl MlsGroup {
+    /// Pins the message secrets of an epoch, such that messages of the epoch
+    /// can still be decrypted after the epoch is no longer one of the
+    /// [`MlsGroupConfig::max_past_epochs()`] past epochs the group keeps,
+    /// e.g. to support a legal hold or a delayed delivery of messages.
+    ///
+    /// If `sender` is given, only the message secrets of this sender are
+    /// retained once the epoch is evicted, and the secrets of all other
+    /// senders are deleted on the normal schedule. The pins are persisted with
+    /// the group state and stay in place until they are released with
+    /// [`MlsGroup::unpin_message_secrets()`]. Pinning secrets that were
+    /// already deleted, e.g. of an evicted epoch or of a sender of a pinned
+    /// epoch whose secrets were not retained, fails.
+    ///
+    /// Retaining message secrets weakens the forward secrecy of the pinned
+    /// messages, so pins should be released as soon as possible.
+    pub fn pin_message_secrets(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        epoch: GroupEpoch,
+        sender: Option<LeafNodeIndex>,
+    ) -> Result<MessageSecretsPin, PinMessageSecretsError> {
+        let pin = MessageSecretsPin::new(epoch, sender);
+        self.group.pin_message_secrets(backend, pin)?;
+        self.flag_state_change(StateChangeReason::MessageSecretsPinned);
+        Ok(pin)
+    }
+
+    /// Releases a pin set with [`MlsGroup::pin_message_secrets()`]. If the
+    /// pinned epoch was already evicted, its message secrets are deleted
+    /// immediately, unless they are covered by another pin. Returns `false` if
+    /// the pin didn't exist.
+    pub fn unpin_message_secrets(&mut self, pin: MessageSecretsPin) -> bool {
+        let unpinned = self.group.unpin_message_secrets(pin);
+        if unpinned {
+            self.flag_state_change(StateChangeReason::MessageSecretsPinned);
+        }
+        unpinned
+    }
+
+    /// Returns the pins set with [`MlsGroup::pin_message_secrets()`].
+    pub fn message_secrets_pins(&self) -> &[MessageSecretsPin] {
+        self.group.message_secrets_pins()
+    }
+}
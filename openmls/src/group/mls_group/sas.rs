@@ -0,0 +1,212 @@
+//! Short authentication strings that two members compare out of band to verify
+//! each other, see [`MlsGroup::short_authentication_string()`].
+
+use tls_codec::{Serialize as TlsSerializeTrait, TlsSerialize, TlsSize, VLBytes};
+
+use super::*;
+
+/// The label the bytes of a [`ShortAuthenticationString`] are derived from the
+/// epoch authenticator with.
+const SAS_LABEL: &str = "OpenMLS short authentication string";
+
+/// The number of bytes a [`ShortAuthenticationString`] is derived from.
+const SAS_LENGTH: usize = 6;
+
+/// The context the bytes of a [`ShortAuthenticationString`] are derived with.
+/// The identities of the two members are sorted, such that both members
+/// derive the same string.
+///
+/// ```text
+/// struct {
+///     opaque identities<V><V>;
+/// } SasContext;
+/// ```
+#[derive(TlsSerialize, TlsSize)]
+struct SasContext {
+    identities: Vec<VLBytes>,
+}
+
+/// An emoji of a [`ShortAuthenticationString`], together with its
+/// description, e.g. to show it to users that can't see the symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SasEmoji {
+    symbol: &'static str,
+    description: &'static str,
+}
+
+impl SasEmoji {
+    /// Returns the emoji.
+    pub fn symbol(&self) -> &'static str {
+        self.symbol
+    }
+
+    /// Returns the English description of the emoji.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+}
+
+const fn emoji(symbol: &'static str, description: &'static str) -> SasEmoji {
+    SasEmoji {
+        symbol,
+        description,
+    }
+}
+
+/// The emoji a [`ShortAuthenticationString`] is rendered with. This is the
+/// table of the Matrix specification, such that users are shown familiar
+/// symbols.
+const SAS_EMOJI: [SasEmoji; 64] = [
+    emoji("🐶", "Dog"),
+    emoji("🐱", "Cat"),
+    emoji("🦁", "Lion"),
+    emoji("🐎", "Horse"),
+    emoji("🦄", "Unicorn"),
+    emoji("🐷", "Pig"),
+    emoji("🐘", "Elephant"),
+    emoji("🐰", "Rabbit"),
+    emoji("🐼", "Panda"),
+    emoji("🐓", "Rooster"),
+    emoji("🐧", "Penguin"),
+    emoji("🐢", "Turtle"),
+    emoji("🐟", "Fish"),
+    emoji("🐙", "Octopus"),
+    emoji("🦋", "Butterfly"),
+    emoji("🌷", "Flower"),
+    emoji("🌳", "Tree"),
+    emoji("🌵", "Cactus"),
+    emoji("🍄", "Mushroom"),
+    emoji("🌏", "Globe"),
+    emoji("🌙", "Moon"),
+    emoji("☁️", "Cloud"),
+    emoji("🔥", "Fire"),
+    emoji("🍌", "Banana"),
+    emoji("🍎", "Apple"),
+    emoji("🍓", "Strawberry"),
+    emoji("🌽", "Corn"),
+    emoji("🍕", "Pizza"),
+    emoji("🎂", "Cake"),
+    emoji("❤️", "Heart"),
+    emoji("😀", "Smiley"),
+    emoji("🤖", "Robot"),
+    emoji("🎩", "Hat"),
+    emoji("👓", "Glasses"),
+    emoji("🔧", "Spanner"),
+    emoji("🎅", "Santa"),
+    emoji("👍", "Thumbs Up"),
+    emoji("☂️", "Umbrella"),
+    emoji("⌛", "Hourglass"),
+    emoji("⏰", "Clock"),
+    emoji("🎁", "Gift"),
+    emoji("💡", "Light Bulb"),
+    emoji("📕", "Book"),
+    emoji("✏️", "Pencil"),
+    emoji("📎", "Paperclip"),
+    emoji("✂️", "Scissors"),
+    emoji("🔒", "Lock"),
+    emoji("🔑", "Key"),
+    emoji("🔨", "Hammer"),
+    emoji("☎️", "Telephone"),
+    emoji("🏁", "Flag"),
+    emoji("🚂", "Train"),
+    emoji("🚲", "Bicycle"),
+    emoji("✈️", "Aeroplane"),
+    emoji("🚀", "Rocket"),
+    emoji("🏆", "Trophy"),
+    emoji("⚽", "Ball"),
+    emoji("🎸", "Guitar"),
+    emoji("🎺", "Trumpet"),
+    emoji("🔔", "Bell"),
+    emoji("⚓", "Anchor"),
+    emoji("🎧", "Headphones"),
+    emoji("📁", "Folder"),
+    emoji("📌", "Pin"),
+];
+
+/// A short authentication string (SAS) that two members of a group compare
+/// out of band, e.g. in person or in a call, to verify that they are in the
+/// same group state and that neither of them is impersonated.
+///
+/// The string is derived from the epoch authenticator and the identities of
+/// both members, see [`MlsGroup::short_authentication_string()`]. It can be
+/// rendered as three four-digit numbers with
+/// [`ShortAuthenticationString::decimal()`] or as seven emoji with
+/// [`ShortAuthenticationString::emoji()`]. Both renderings follow the Matrix
+/// specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortAuthenticationString {
+    bytes: [u8; SAS_LENGTH],
+}
+
+impl ShortAuthenticationString {
+    /// Returns the string as three numbers between 1000 and 9191, derived from
+    /// 13 bits each.
+    pub fn decimal(&self) -> [u16; 3] {
+        let b = self.bytes.map(u16::from);
+        [
+            (b[0] << 5 | b[1] >> 3) + 1000,
+            ((b[1] & 0x07) << 10 | b[2] << 2 | b[3] >> 6) + 1000,
+            ((b[3] & 0x3f) << 7 | b[4] >> 1) + 1000,
+        ]
+    }
+
+    /// Returns the string as seven emoji, derived from 6 bits each.
+    pub fn emoji(&self) -> [SasEmoji; 7] {
+        let bits = self
+            .bytes
+            .iter()
+            .fold(0u64, |bits, byte| bits << 8 | u64::from(*byte));
+        let mut emoji = [SAS_EMOJI[0]; 7];
+        for (i, e) in emoji.iter_mut().enumerate() {
+            *e = SAS_EMOJI[((bits >> (42 - 6 * i)) & 0x3f) as usize];
+        }
+        emoji
+    }
+}
+
+impl MlsGroup {
+    /// Derives the [`ShortAuthenticationString`] of the current epoch for the
+    /// own client and the member with the given `identity`.
+    ///
+    /// Both members derive the same string if they are in the same epoch of
+    /// the same group, so they can verify each other by comparing the strings
+    /// out of band. The string changes with every epoch.
+    ///
+    /// Returns [`ShortAuthenticationStringError::UnknownMember`] if no member
+    /// of the group has the given `identity`.
+    pub fn short_authentication_string(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        identity: &[u8],
+    ) -> Result<ShortAuthenticationString, ShortAuthenticationStringError> {
+        let own_identity = self.credential()?.identity().as_slice();
+        if !self
+            .members()
+            .any(|member| member.identity.as_slice() == identity)
+        {
+            return Err(ShortAuthenticationStringError::UnknownMember);
+        }
+
+        let mut identities = [own_identity, identity];
+        identities.sort_unstable();
+        let context = SasContext {
+            identities: identities
+                .iter()
+                .map(|identity| (*identity).into())
+                .collect(),
+        }
+        .tls_serialize_detached()
+        .map_err(LibraryError::missing_bound_check)?;
+        let bytes = self
+            .group
+            .epoch_authenticator()
+            .derive_bytes(backend, SAS_LABEL, &context, SAS_LENGTH)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+
+        let mut sas = ShortAuthenticationString {
+            bytes: [0; SAS_LENGTH],
+        };
+        sas.bytes.copy_from_slice(&bytes);
+        Ok(sas)
+    }
+}
@@ -4,9 +4,54 @@
 use super::*;
 
 use serde::{
+    de::DeserializeOwned,
     ser::{SerializeStruct, Serializer},
     Deserialize, Serialize,
 };
+use serde_json::Value;
+
+use crate::extensions::ExtensionType;
+
+/// A serialization format for the persisted state of an [`MlsGroup`], see
+/// [`MlsGroup::save_with()`] and [`MlsGroup::load_with()`].
+///
+/// This allows applications to use a more compact format than JSON, e.g. CBOR,
+/// bincode or MessagePack, by implementing the trait with the corresponding
+/// serde implementation.
+pub trait StateSerializer {
+    /// The error returned by the serializer.
+    type Error;
+
+    /// Serializes the `state` to the `writer`.
+    fn serialize<W: Write>(&self, writer: W, state: &impl Serialize) -> Result<(), Self::Error>;
+
+    /// Deserializes the state from the `reader`.
+    fn deserialize<R: Read, T: DeserializeOwned>(&self, reader: R) -> Result<T, Self::Error>;
+}
+
+/// The JSON [`StateSerializer`] used by [`MlsGroup::save()`] and
+/// [`MlsGroup::load()`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer;
+
+impl StateSerializer for JsonSerializer {
+    type Error = serde_json::Error;
+
+    fn serialize<W: Write>(
+        &self,
+        mut writer: W,
+        state: &impl Serialize,
+    ) -> Result<(), Self::Error> {
+        let serialized_state = serde_json::to_vec_pretty(state)?;
+        writer
+            .write_all(&serialized_state)
+            .map_err(serde_json::Error::io)
+    }
+
+    fn deserialize<R: Read, T: DeserializeOwned>(&self, reader: R) -> Result<T, Self::Error> {
+        serde_json::from_reader(reader)
+    }
+}
 
 /// Helper struct that contains the serializable values of an `MlsGroup.
 #[deprecated(
@@ -21,37 +66,118 @@ pub struct SerializedMlsGroup {
     own_leaf_nodes: Vec<OpenMlsLeafNode>,
     aad: Vec<u8>,
     resumption_psk_store: ResumptionPskStore,
+    #[serde(default)]
+    replay_window: ReplayWindow,
+    #[serde(default)]
+    sender_ratchet_overrides: HashMap<LeafNodeIndex, SenderRatchetConfiguration>,
+    #[serde(default)]
+    expired_proposals: Vec<QueuedProposal>,
+    #[serde(default)]
+    membership_history: Vec<MembershipRecord>,
+    #[serde(default)]
+    stored_staged_commit: Option<StagedCommit>,
+    #[serde(default)]
+    merged_commit_digest: Option<Vec<u8>>,
     group_state: MlsGroupState,
 }
 
 impl SerializedMlsGroup {
+    /// Deserializes a JSON state written by [`MlsGroup::save()`]. States
+    /// persisted before [`Extensions`] kept the order of the extensions are
+    /// migrated first.
+    pub(crate) fn from_json(mut state: Value) -> Result<Self, serde_json::Error> {
+        migrate_legacy_extensions(&mut state);
+        serde_json::from_value(state)
+    }
+
     /// Helper method that converts the SerializedMlsGroup to MlsGroup.
     pub fn into_mls_group(self) -> MlsGroup {
+        let mut group = self.group;
+        group.set_allow_duplicate_identities(
+            self.mls_group_config
+                .duplicate_identity_policy
+                .allows_duplicate_identities(),
+        );
         MlsGroup {
             mls_group_config: self.mls_group_config,
-            group: self.group,
+            group,
             proposal_store: self.proposal_store,
             own_leaf_nodes: self.own_leaf_nodes,
             aad: self.aad,
             resumption_psk_store: self.resumption_psk_store,
+            replay_window: self.replay_window,
+            sender_ratchet_overrides: self.sender_ratchet_overrides,
+            duplicate_identity_callback: None,
+            state_change_callback: None,
+            membership_diff: None,
+            group_events: Vec::new(),
+            membership_history: self.membership_history,
+            expired_proposals: self.expired_proposals,
+            skipped_keys_evictions: Vec::new(),
+            stored_staged_commit: self.stored_staged_commit,
+            merged_commit_digest: self.merged_commit_digest,
             group_state: self.group_state,
+            persisted_components: HashMap::new(),
             state_changed: InnerState::Persisted,
         }
     }
 }
 
+/// Rewrites all [`Extensions`] in a JSON state that were persisted as a map
+/// from the extension types to the extensions, i.e. as `{"map": {..}}`, to
+/// the current list of extensions. The extensions are ordered by their
+/// extension type, like in the map, such that they are serialized the same
+/// way as before.
+fn migrate_legacy_extensions(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            let is_legacy =
+                fields.len() == 1 && matches!(fields.get("map"), Some(Value::Object(_)));
+            if !is_legacy {
+                fields.values_mut().for_each(migrate_legacy_extensions);
+                return;
+            }
+            if let Some(Value::Object(map)) = fields.remove("map") {
+                let mut extensions: Vec<(Option<u16>, Value)> = map
+                    .into_iter()
+                    .map(|(extension_type, extension)| {
+                        let extension_type =
+                            serde_json::from_value::<ExtensionType>(Value::String(extension_type))
+                                .ok()
+                                .map(u16::from);
+                        (extension_type, extension)
+                    })
+                    .collect();
+                extensions.sort_by_key(|(extension_type, _)| *extension_type);
+                fields.insert(
+                    "extensions".to_owned(),
+                    Value::Array(extensions.into_iter().map(|(_, e)| e).collect()),
+                );
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(migrate_legacy_extensions),
+        _ => {}
+    }
+}
+
 impl Serialize for MlsGroup {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("SerializedMlsGroup", 6)?;
+        let mut state = serializer.serialize_struct("SerializedMlsGroup", 13)?;
         state.serialize_field("mls_group_config", &self.mls_group_config)?;
         state.serialize_field("group", &self.group)?;
         state.serialize_field("proposal_store", &self.proposal_store)?;
         state.serialize_field("own_leaf_nodes", &self.own_leaf_nodes)?;
         state.serialize_field("aad", &self.aad)?;
         state.serialize_field("resumption_psk_store", &self.resumption_psk_store)?;
+        state.serialize_field("replay_window", &self.replay_window)?;
+        state.serialize_field("sender_ratchet_overrides", &self.sender_ratchet_overrides)?;
+        state.serialize_field("expired_proposals", &self.expired_proposals)?;
+        state.serialize_field("membership_history", &self.membership_history)?;
+        state.serialize_field("stored_staged_commit", &self.stored_staged_commit)?;
+        state.serialize_field("merged_commit_digest", &self.merged_commit_digest)?;
         state.serialize_field("group_state", &self.group_state)?;
         state.end()
     }
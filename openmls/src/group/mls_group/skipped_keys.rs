@@ -0,0 +1,121 @@
+//! Group-wide limit of the skipped message keys, see
+//! [`MlsGroupConfigBuilder::max_skipped_keys()`].
+
+use crate::tree::{index::SecretTreeLeafIndex, secret_tree::SecretType};
+
+use super::*;
+
+/// Skipped message keys of a sender that were deleted because the group stored
+/// more than [`MlsGroupConfig::max_skipped_keys()`] skipped keys. Messages of
+/// the sender that were encrypted with one of these keys can't be decrypted
+/// anymore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedKeysEviction {
+    epoch: GroupEpoch,
+    sender: LeafNodeIndex,
+    application: bool,
+    count: usize,
+}
+
+impl SkippedKeysEviction {
+    /// Returns the epoch of the deleted keys.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the leaf index of the sender of the deleted keys.
+    pub fn sender(&self) -> LeafNodeIndex {
+        self.sender
+    }
+
+    /// Returns `true` if the deleted keys were kept to decrypt application
+    /// messages and `false` if they were kept to decrypt handshake messages.
+    pub fn is_application(&self) -> bool {
+        self.application
+    }
+
+    /// Returns the number of deleted keys.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl MlsGroup {
+    /// Returns the skipped message keys that were deleted since the last call,
+    /// because the group stored more than
+    /// [`MlsGroupConfig::max_skipped_keys()`] skipped keys. The evictions are
+    /// not persisted.
+    ///
+    /// Applications can use the evictions to detect senders that skip many
+    /// generations, or to request the retransmission of messages that can't be
+    /// decrypted anymore.
+    pub fn take_skipped_keys_evictions(&mut self) -> Vec<SkippedKeysEviction> {
+        std::mem::take(&mut self.skipped_keys_evictions)
+    }
+
+    /// Deletes skipped message keys until the group stores at most
+    /// [`MlsGroupConfig::max_skipped_keys()`] of them.
+    ///
+    /// The keys of the sender ratchet that stores the most skipped keys are
+    /// deleted first, oldest generation first. If several ratchets store the
+    /// same number of skipped keys, the one of the oldest epoch is chosen.
+    pub(super) fn enforce_skipped_keys_limit(&mut self) -> Result<(), LibraryError> {
+        let max_skipped_keys = match self.configuration().max_skipped_keys() {
+            Some(max_skipped_keys) => max_skipped_keys,
+            None => return Ok(()),
+        };
+
+        let mut message_secrets = self.group.all_message_secrets_mut();
+        // The number of stored and evicted keys per ratchet, identified by the
+        // position of its epoch in `message_secrets`, the sender and the
+        // secret type.
+        let mut ratchets: Vec<(usize, SecretTreeLeafIndex, SecretType, usize, usize)> =
+            message_secrets
+                .iter()
+                .enumerate()
+                .flat_map(|(position, (_, secrets))| {
+                    secrets.secret_tree().skipped_keys().into_iter().map(
+                        move |(sender, secret_type, skipped_keys)| {
+                            (position, sender, secret_type, skipped_keys, 0)
+                        },
+                    )
+                })
+                .collect();
+        let mut total: usize = ratchets.iter().map(|ratchet| ratchet.3).sum();
+        while total > max_skipped_keys {
+            // `max_by_key()` returns the last maximum, i.e. the one of the
+            // oldest epoch.
+            let ratchet = ratchets
+                .iter_mut()
+                .max_by_key(|ratchet| ratchet.3)
+                .ok_or_else(|| LibraryError::custom("No skipped keys to evict"))?;
+            ratchet.3 -= 1;
+            ratchet.4 += 1;
+            total -= 1;
+        }
+
+        for (position, sender, secret_type, _, evicted) in ratchets {
+            if evicted == 0 {
+                continue;
+            }
+            let (epoch, secrets) = &mut message_secrets[position];
+            let count = secrets
+                .secret_tree_mut()
+                .evict_skipped_keys(sender, secret_type, evicted)
+                .map_err(|_| LibraryError::custom("Could not evict skipped keys"))?;
+            log::debug!(
+                "Evicted {} skipped keys of sender {:?} in epoch {:?}",
+                count,
+                sender,
+                epoch
+            );
+            self.skipped_keys_evictions.push(SkippedKeysEviction {
+                epoch: *epoch,
+                sender: LeafNodeIndex::new(sender.as_u32()),
+                application: secret_type == SecretType::ApplicationSecret,
+                count,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! Encrypted snapshots of the persisted group state, see
+//! [`MlsGroup::save_encrypted()`] and [`MlsGroup::load_encrypted()`].
+
+use std::{fmt, io::ErrorKind};
+
+use openmls_traits::{crypto::OpenMlsCrypto, random::OpenMlsRand};
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, TlsDeserialize,
+    TlsSerialize, TlsSize, VLBytes,
+};
+use zeroize::Zeroize;
+
+use super::*;
+use crate::ciphersuite::equal_ct;
+
+/// The exporter label of the [`SnapshotKey`] derived from the group.
+const SNAPSHOT_KEY_LABEL: &str = "OpenMLS group snapshot key";
+
+/// The additional authenticated data of encrypted snapshots.
+const SNAPSHOT_AAD: &[u8] = b"OpenMLS encrypted group snapshot";
+
+/// The key an encrypted snapshot of the group state is encrypted with.
+///
+/// The key is either derived from the group with
+/// [`MlsGroup::export_snapshot_key()`] or provided by the application as
+/// bytes with `SnapshotKey::from()`. Its length has to match the AEAD key length of the
+/// ciphersuite of the group.
+///
+/// Like an [`ExportedSecret`], the key is overwritten with zeros when it is
+/// dropped, compared in constant time and not printed by its [`Debug`]
+/// implementation.
+pub struct SnapshotKey {
+    value: Vec<u8>,
+}
+
+impl SnapshotKey {
+    /// Returns the bytes of the key, e.g. to store it in a secure storage of
+    /// the platform. Copies of the bytes are not overwritten when the key is
+    /// dropped.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+impl From<Vec<u8>> for SnapshotKey {
+    fn from(value: Vec<u8>) -> Self {
+        Self { value }
+    }
+}
+
+impl fmt::Debug for SnapshotKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SnapshotKey { *** }")
+    }
+}
+
+impl PartialEq for SnapshotKey {
+    // Constant time comparison. The length of the keys is public.
+    fn eq(&self, other: &Self) -> bool {
+        self.value.len() == other.value.len() && equal_ct(&self.value, &other.value)
+    }
+}
+
+impl Eq for SnapshotKey {}
+
+impl Drop for SnapshotKey {
+    fn drop(&mut self) {
+        self.value.as_mut_slice().zeroize();
+    }
+}
+
+/// An encrypted snapshot of the group state.
+///
+/// ```text
+/// struct {
+///     CipherSuite cipher_suite;
+///     opaque nonce<V>;
+///     opaque ciphertext<V>;
+/// } EncryptedSnapshot;
+/// ```
+#[derive(TlsSerialize, TlsDeserialize, TlsSize)]
+struct EncryptedSnapshot {
+    ciphersuite: Ciphersuite,
+    nonce: VLBytes,
+    ciphertext: VLBytes,
+}
+
+impl MlsGroup {
+    /// Derives a [`SnapshotKey`] from the current epoch of the group with the
+    /// MLS exporter.
+    ///
+    /// The key changes with every epoch, so the application has to keep the
+    /// key a snapshot was saved with, e.g. in a secure storage of the platform,
+    /// in order to load it again.
+    pub fn export_snapshot_key(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<SnapshotKey, ExportSecretError> {
+        self.export_secret(
+            backend,
+            SNAPSHOT_KEY_LABEL,
+            &[],
+            self.ciphersuite().aead_key_length(),
+        )
+        .map(|secret| SnapshotKey::from(secret.into_bytes()))
+    }
+
+    /// Persists the state like [`MlsGroup::save()`], but encrypts it with the
+    /// AEAD of the ciphersuite of the group under the given `key`, such that
+    /// the group state isn't stored in plaintext.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidInput`] if the length of
+    /// the key doesn't match the AEAD key length of the ciphersuite.
+    pub fn save_encrypted<W: Write>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        writer: &mut W,
+        key: &SnapshotKey,
+    ) -> Result<(), Error> {
+        self.save_encrypted_with(backend, &JsonSerializer, writer, key)
+    }
+
+    /// Persists the state like [`MlsGroup::save_encrypted()`], but serializes
+    /// it in the format of the given `serializer` instead of JSON before it is
+    /// encrypted.
+    ///
+    /// Errors of the serializer are returned with the kind [`ErrorKind::Other`].
+    pub fn save_encrypted_with<S, W: Write>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        serializer: &S,
+        writer: &mut W,
+        key: &SnapshotKey,
+    ) -> Result<(), Error>
+    where
+        S: StateSerializer,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let ciphersuite = self.ciphersuite();
+        if key.as_slice().len() != ciphersuite.aead_key_length() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The length of the snapshot key doesn't match the ciphersuite.",
+            ));
+        }
+
+        let mut plaintext = Vec::new();
+        serializer
+            .serialize(&mut plaintext, self)
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+        let nonce = backend
+            .rand()
+            .random_vec(ciphersuite.aead_nonce_length())
+            .map_err(|_| Error::new(ErrorKind::Other, "Not enough randomness."))?;
+        let ciphertext = backend
+            .crypto()
+            .aead_encrypt(
+                ciphersuite.aead_algorithm(),
+                key.as_slice(),
+                &plaintext,
+                &nonce,
+                SNAPSHOT_AAD,
+            )
+            .map_err(|e| Error::new(ErrorKind::Other, format!("{:?}", e)))?;
+        let snapshot = EncryptedSnapshot {
+            ciphersuite,
+            nonce: nonce.into(),
+            ciphertext: ciphertext.into(),
+        }
+        .tls_serialize_detached()
+        .map_err(|e| Error::new(ErrorKind::Other, format!("{:?}", e)))?;
+
+        writer.write_all(&snapshot)?;
+        self.state_changed = InnerState::Persisted;
+        Ok(())
+    }
+
+    /// Loads the state from a snapshot persisted with
+    /// [`MlsGroup::save_encrypted()`] under the given `key`.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidData`] if the snapshot is
+    /// malformed or can't be decrypted with the key.
+    pub fn load_encrypted<R: Read>(
+        backend: &impl OpenMlsCryptoProvider,
+        reader: R,
+        key: &SnapshotKey,
+    ) -> Result<MlsGroup, Error> {
+        Self::load_encrypted_with(backend, &JsonSerializer, reader, key)
+    }
+
+    /// Loads the state from a snapshot persisted with
+    /// [`MlsGroup::save_encrypted_with()`] under the given `key`, using the
+    /// same `serializer`.
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidData`] if the snapshot is
+    /// malformed, can't be decrypted with the key or can't be deserialized.
+    pub fn load_encrypted_with<S, R: Read>(
+        backend: &impl OpenMlsCryptoProvider,
+        serializer: &S,
+        mut reader: R,
+        key: &SnapshotKey,
+    ) -> Result<MlsGroup, Error>
+    where
+        S: StateSerializer,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let snapshot = EncryptedSnapshot::tls_deserialize(&mut reader)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Malformed snapshot."))?;
+        let plaintext = backend
+            .crypto()
+            .aead_decrypt(
+                snapshot.ciphersuite.aead_algorithm(),
+                key.as_slice(),
+                snapshot.ciphertext.as_slice(),
+                snapshot.nonce.as_slice(),
+                SNAPSHOT_AAD,
+            )
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Could not decrypt snapshot."))?;
+
+        Self::load_with(serializer, plaintext.as_slice())
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+}
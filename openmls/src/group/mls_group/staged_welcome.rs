@@ -0,0 +1,287 @@
+//! Joining a group from a [`Welcome`] in explicit stages, see
+//! [`ProcessedWelcome`] and [`StagedWelcome`].
+
+use crate::{
+    group::core_group::new_from_welcome::DecryptedWelcome, messages::VerifiableGroupInfo,
+    schedule::psk::PreSharedKeyId, treesync::node::encryption_keys::EncryptionKeyPair,
+};
+
+use super::*;
+
+/// A [`Welcome`] of which the group secrets and the GroupInfo were decrypted
+/// with a key package of the client.
+///
+/// Neither the ratchet tree nor the GroupInfo are validated at this stage.
+/// The application can inspect the GroupInfo, e.g. to fetch the ratchet tree
+/// of the group if the Welcome doesn't contain it, and then continue with
+/// [`ProcessedWelcome::into_staged_welcome()`].
+///
+/// The key package is not deleted from the key store until the group is
+/// created with [`StagedWelcome::into_group()`], such that dropping a
+/// [`ProcessedWelcome`] leaves the key store unchanged.
+pub struct ProcessedWelcome {
+    mls_group_config: MlsGroupConfig,
+    decrypted_welcome: DecryptedWelcome,
+}
+
+impl ProcessedWelcome {
+    /// Decrypts the group secrets and the GroupInfo of the `welcome`.
+    ///
+    /// The [`KeyPackage`] the client was added with is looked up in the key
+    /// store, see [`MlsGroup::welcome_key_package()`]. Returns an error
+    /// ([`WelcomeError::NoMatchingKeyPackage`]) if there is none.
+    pub fn new_from_welcome<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+    ) -> Result<Self, WelcomeError<KeyStore::Error>> {
+        let key_package_bundle = MlsGroup::welcome_key_package_bundle(backend, &welcome)
+            .ok_or(WelcomeError::NoMatchingKeyPackage)?;
+        Self::new_with_key_package_bundle(backend, mls_group_config, welcome, key_package_bundle)
+    }
+
+    pub(super) fn new_with_key_package_bundle<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+        key_package_bundle: KeyPackageBundle,
+    ) -> Result<Self, WelcomeError<KeyStore::Error>> {
+        if !mls_group_config.is_ciphersuite_allowed(welcome.ciphersuite()) {
+            return Err(WelcomeError::CiphersuiteNotAllowed);
+        }
+        if mls_group_config.is_downgrade(welcome.ciphersuite(), *welcome.version()) {
+            return Err(WelcomeError::DowngradeDetected);
+        }
+        let decrypted_welcome = CoreGroup::decrypt_welcome(welcome, key_package_bundle, backend)?;
+        // The GroupInfo could claim another version than the Welcome.
+        let group_context = decrypted_welcome.unverified_group_info().group_context();
+        if mls_group_config.is_downgrade(
+            group_context.ciphersuite(),
+            group_context.protocol_version(),
+        ) {
+            return Err(WelcomeError::DowngradeDetected);
+        }
+        Ok(Self {
+            mls_group_config: mls_group_config.clone(),
+            decrypted_welcome,
+        })
+    }
+
+    /// Returns the [`KeyPackage`] the client was added with.
+    pub fn key_package(&self) -> &KeyPackage {
+        self.decrypted_welcome.key_package()
+    }
+
+    /// Returns the GroupInfo of the Welcome. Its signature is only verified
+    /// by [`ProcessedWelcome::into_staged_welcome()`].
+    pub fn unverified_group_info(&self) -> &VerifiableGroupInfo {
+        self.decrypted_welcome.unverified_group_info()
+    }
+
+    /// Returns the ID of the group according to the unverified GroupInfo.
+    pub fn group_id(&self) -> &GroupId {
+        self.unverified_group_info().group_context().group_id()
+    }
+
+    /// Returns the epoch of the group according to the unverified GroupInfo.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.unverified_group_info().group_context().epoch()
+    }
+
+    /// Returns the leaf index of the member that sent the Welcome according
+    /// to the unverified GroupInfo, i.e. the signer of the GroupInfo.
+    pub fn welcome_sender_index(&self) -> LeafNodeIndex {
+        self.unverified_group_info().signer()
+    }
+
+    /// Returns `true` if the GroupInfo contains the ratchet tree of the
+    /// group. Otherwise, the ratchet tree has to be passed to
+    /// [`ProcessedWelcome::into_staged_welcome()`].
+    pub fn has_ratchet_tree(&self) -> bool {
+        self.unverified_group_info()
+            .extensions()
+            .ratchet_tree()
+            .is_some()
+    }
+
+    /// Returns the PSKs that were injected into the key schedule of the
+    /// group.
+    pub fn psks(&self) -> &[PreSharedKeyId] {
+        self.decrypted_welcome.psks()
+    }
+
+    /// Builds the ratchet tree of the group, either from the GroupInfo or
+    /// from the given `ratchet_tree`, and validates it and the GroupInfo.
+    ///
+    /// Returns an error ([`WelcomeError::MissingRatchetTree`]) if the
+    /// GroupInfo doesn't contain the ratchet tree and no `ratchet_tree` is
+    /// given.
+    pub fn into_staged_welcome<KeyStore: OpenMlsKeyStore>(
+        self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ratchet_tree: Option<Vec<Option<Node>>>,
+    ) -> Result<StagedWelcome, WelcomeError<KeyStore::Error>> {
+        let key_package = self.decrypted_welcome.key_package().clone();
+        let validated_welcome = self.decrypted_welcome.validate(ratchet_tree, backend)?;
+        let mut group = validated_welcome.group;
+        group.set_max_past_epochs(self.mls_group_config.max_past_epochs);
+        group.set_allow_duplicate_identities(
+            self.mls_group_config
+                .duplicate_identity_policy
+                .allows_duplicate_identities(),
+        );
+
+        let resumption_psk_store =
+            ResumptionPskStore::new(self.mls_group_config.number_of_resumption_psks);
+        let replay_window = ReplayWindow::new(self.mls_group_config.replay_window_size);
+        let mut mls_group = MlsGroup {
+            mls_group_config: self.mls_group_config,
+            group,
+            proposal_store: ProposalStore::new(),
+            own_leaf_nodes: vec![],
+            aad: vec![],
+            resumption_psk_store,
+            replay_window,
+            sender_ratchet_overrides: HashMap::new(),
+            duplicate_identity_callback: None,
+            state_change_callback: None,
+            membership_diff: None,
+            group_events: Vec::new(),
+            membership_history: Vec::new(),
+            expired_proposals: Vec::new(),
+            skipped_keys_evictions: Vec::new(),
+            stored_staged_commit: None,
+            merged_commit_digest: None,
+            group_state: MlsGroupState::Operational,
+            persisted_components: HashMap::new(),
+            state_changed: InnerState::Changed,
+        };
+        mls_group.init_membership_history();
+
+        if mls_group.exceeds_max_members(0, 0) {
+            return Err(WelcomeError::TooManyMembers);
+        }
+
+        #[cfg(feature = "verifiable-credentials")]
+        if !mls_group.verifies_credentials(
+            mls_group
+                .members()
+                .filter_map(|member| mls_group.member(member.index)),
+        ) {
+            return Err(WelcomeError::InvalidVerifiableCredential);
+        }
+
+        #[cfg(feature = "token-bound-credentials")]
+        if !mls_group.verifies_token_bindings(
+            mls_group
+                .members()
+                .filter_map(|member| mls_group.member(member.index)),
+        ) {
+            return Err(WelcomeError::InvalidTokenBinding);
+        }
+
+        Ok(StagedWelcome {
+            mls_group,
+            group_keypairs: validated_welcome.group_keypairs,
+            welcome_sender: validated_welcome.signer,
+            key_package,
+        })
+    }
+
+    /// Validates the GroupInfo against the [`PartialTree`] of the client and
+    /// the [`PartialTree`] of the member that sent the Welcome, instead of
+    /// the full ratchet tree, and creates a [`PartialGroup`].
+    ///
+    /// Both partial trees are verified against the tree hash of the group.
+    /// The `signer_tree` has to be the one of the signer of the GroupInfo,
+    /// see [`ProcessedWelcome::welcome_sender_index()`].
+    ///
+    /// The key package the client was added with is deleted from the key
+    /// store like with [`StagedWelcome::into_group()`].
+    #[cfg(feature = "partial-tree")]
+    pub fn into_partial_group<KeyStore: OpenMlsKeyStore>(
+        self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        partial_tree: PartialTree,
+        signer_tree: &PartialTree,
+    ) -> Result<PartialGroup, WelcomeError<KeyStore::Error>> {
+        let key_package = self.decrypted_welcome.key_package().clone();
+        let welcome_sender = signer_tree.leaf_index();
+        let (group_context, group_epoch_secrets) =
+            self.decrypted_welcome
+                .validate_partial(&partial_tree, signer_tree, backend)?;
+        MlsGroup::consume_key_package(backend, &self.mls_group_config, &key_package)?;
+
+        Ok(PartialGroup::new(
+            group_context,
+            group_epoch_secrets,
+            partial_tree,
+            welcome_sender,
+        ))
+    }
+}
+
+/// A [`Welcome`] of which the ratchet tree and the GroupInfo were validated.
+///
+/// The application can inspect the group it is about to join, e.g. to ask
+/// the user for consent, and then create the group with
+/// [`StagedWelcome::into_group()`]. Dropping a [`StagedWelcome`] leaves the
+/// key store unchanged.
+#[derive(Debug)]
+pub struct StagedWelcome {
+    mls_group: MlsGroup,
+    group_keypairs: Vec<EncryptionKeyPair>,
+    welcome_sender: LeafNodeIndex,
+    key_package: KeyPackage,
+}
+
+impl StagedWelcome {
+    /// Returns the [`GroupContext`] of the group.
+    pub fn group_context(&self) -> &GroupContext {
+        self.mls_group.export_group_context()
+    }
+
+    /// Returns the [`KeyPackage`] the client was added with.
+    pub fn key_package(&self) -> &KeyPackage {
+        &self.key_package
+    }
+
+    /// Returns the [`Member`]s of the group.
+    pub fn members(&self) -> impl Iterator<Item = Member> + '_ {
+        self.mls_group.members()
+    }
+
+    /// Returns the leaf index of the member that sent the Welcome, i.e. the
+    /// signer of the GroupInfo.
+    pub fn welcome_sender_index(&self) -> LeafNodeIndex {
+        self.welcome_sender
+    }
+
+    /// Returns the [`Credential`] of the member that sent the Welcome.
+    pub fn welcome_sender(&self) -> Option<&Credential> {
+        self.mls_group.member(self.welcome_sender)
+    }
+
+    /// Stores the key pairs of the group in the key store and creates the
+    /// [`MlsGroup`].
+    ///
+    /// The key package the client was added with is deleted from the key
+    /// store, unless it is kept as last resort key package or by
+    /// configuration, see [`MlsGroupConfigBuilder::keep_consumed_key_packages()`].
+    pub fn into_group<KeyStore: OpenMlsKeyStore>(
+        self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<MlsGroup, WelcomeError<KeyStore::Error>> {
+        self.mls_group
+            .group
+            .store_epoch_keypairs(backend, self.group_keypairs.as_slice())
+            .map_err(WelcomeError::KeyStoreError)?;
+        MlsGroup::consume_key_package(
+            backend,
+            &self.mls_group.mls_group_config,
+            &self.key_package,
+        )?;
+
+        Ok(self.mls_group)
+    }
+}
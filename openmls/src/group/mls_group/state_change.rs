@@ -0,0 +1,81 @@
+//! Notification of the application when the state of the group changes, see
+//! [`MlsGroup::set_state_change_callback()`].
+
+use std::{fmt, sync::Arc};
+
+use super::*;
+
+/// The reason why the state of an [`MlsGroup`] changed and needs to be
+/// persisted again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChangeReason {
+    /// A message was processed with [`MlsGroup::process_message()`].
+    MessageProcessed,
+    /// A proposal was stored with [`MlsGroup::store_pending_proposal()`].
+    ProposalStored,
+    /// A staged commit was merged, either a commit of another member or the
+    /// pending commit of the group.
+    CommitMerged,
+    /// A commit was created and is pending.
+    CommitCreated,
+    /// A proposal was created and stored.
+    ProposalCreated,
+    /// An application message was created.
+    MessageCreated,
+    /// Pending proposals expired, or expired proposals were taken with
+    /// [`MlsGroup::take_expired_proposals()`].
+    ProposalsExpired,
+    /// The configuration, the sender ratchet configuration or the AAD of the
+    /// group was changed.
+    ConfigurationChanged,
+    /// A staged commit was stored with [`MlsGroup::store_staged_commit()`] or
+    /// taken with [`MlsGroup::take_stored_staged_commit()`].
+    StagedCommitStored,
+    /// The secrets of the group were destroyed with [`MlsGroup::wipe()`].
+    Wiped,
+    /// Message secrets were pinned with [`MlsGroup::pin_message_secrets()`]
+    /// or a pin was released with [`MlsGroup::unpin_message_secrets()`].
+    MessageSecretsPinned,
+}
+
+/// A callback that is called with the [`StateChangeReason`] whenever the state
+/// of the group changes.
+#[derive(Clone)]
+pub(crate) struct StateChangeCallback(Arc<dyn Fn(StateChangeReason) + Send + Sync>);
+
+impl fmt::Debug for StateChangeCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StateChangeCallback")
+    }
+}
+
+impl MlsGroup {
+    /// Sets a callback that is called with the [`StateChangeReason`] whenever
+    /// the state of the group changes, i.e. whenever [`MlsGroup::state_changed()`]
+    /// would return [`InnerState::Changed`]. This allows applications to
+    /// persist the group when needed instead of polling the flag after every
+    /// call.
+    ///
+    /// The callback is called during the operation that changes the state, so
+    /// the group should be persisted once the operation returned. It is not
+    /// persisted and has to be set again after the group was loaded.
+    pub fn set_state_change_callback(
+        &mut self,
+        callback: impl Fn(StateChangeReason) + Send + Sync + 'static,
+    ) {
+        self.state_change_callback = Some(StateChangeCallback(Arc::new(callback)));
+    }
+
+    /// Removes the callback set with [`MlsGroup::set_state_change_callback()`].
+    pub fn clear_state_change_callback(&mut self) {
+        self.state_change_callback = None;
+    }
+
+    /// Arm the state changed flag and notify the callback about the `reason`.
+    pub(super) fn flag_state_change(&mut self, reason: StateChangeReason) {
+        self.state_changed = InnerState::Changed;
+        if let Some(callback) = &self.state_change_callback {
+            (callback.0)(reason);
+        }
+    }
+}
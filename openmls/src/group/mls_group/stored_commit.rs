@@ -0,0 +1,52 @@
+//! Staged commits of other members that are stored with the group until they
+//! are merged, see [`MlsGroup::store_staged_commit()`].
+
+use super::*;
+use crate::group::errors::MergeCommitError;
+
+impl MlsGroup {
+    /// Stores a [`StagedCommit`] that was returned by
+    /// [`MlsGroup::process_message()`], e.g. while the application waits for
+    /// the user to confirm the changes of the commit. The commit is persisted
+    /// with the group, such that it can be merged with
+    /// [`MlsGroup::merge_stored_staged_commit()`] after the group was loaded
+    /// again.
+    ///
+    /// A previously stored commit is replaced. The stored commit is discarded
+    /// when any commit is merged.
+    pub fn store_staged_commit(&mut self, staged_commit: StagedCommit) {
+        self.stored_staged_commit = Some(staged_commit);
+        self.flag_state_change(StateChangeReason::StagedCommitStored);
+    }
+
+    /// Returns the [`StagedCommit`] stored with
+    /// [`MlsGroup::store_staged_commit()`], if there is one.
+    pub fn stored_staged_commit(&self) -> Option<&StagedCommit> {
+        self.stored_staged_commit.as_ref()
+    }
+
+    /// Removes the [`StagedCommit`] stored with
+    /// [`MlsGroup::store_staged_commit()`] from the group and returns it, e.g.
+    /// if the user rejected the changes of the commit.
+    pub fn take_stored_staged_commit(&mut self) -> Option<StagedCommit> {
+        let staged_commit = self.stored_staged_commit.take();
+        if staged_commit.is_some() {
+            self.flag_state_change(StateChangeReason::StagedCommitStored);
+        }
+        staged_commit
+    }
+
+    /// Merges the [`StagedCommit`] stored with
+    /// [`MlsGroup::store_staged_commit()`] into the group state, see
+    /// [`MlsGroup::merge_staged_commit()`]. Does nothing if no commit is
+    /// stored.
+    pub fn merge_stored_staged_commit<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), MergeCommitError<KeyStore::Error>> {
+        match self.stored_staged_commit.take() {
+            Some(staged_commit) => self.merge_staged_commit(backend, staged_commit),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Additional semantic validation of incoming messages for groups that use
+//! [`ValidationLevel::Strict`].
+
+use crate::treesync::LeafNode;
+
+use super::*;
+
+impl MlsGroup {
+    /// Returns `true` if the group uses [`ValidationLevel::Strict`].
+    pub(super) fn uses_strict_validation(&self) -> bool {
+        self.configuration().validation_level() == ValidationLevel::Strict
+    }
+
+    /// Validates a message before it is parsed. This function implements the
+    /// following checks:
+    ///  - ValSem012: Messages from external senders that are not listed in
+    ///    the external senders extension of the group are rejected
+    pub(super) fn validate_strict_message(
+        &self,
+        message: &ProtocolMessage,
+    ) -> Result<(), StrictValidationError> {
+        // ValSem012
+        if let ProtocolMessageBody::PublicMessage(public_message) = message.body() {
+            if let Sender::External(sender_index) = public_message.sender() {
+                let is_listed = self
+                    .group
+                    .group_context_extensions()
+                    .external_senders()
+                    .map(|external_senders| sender_index.usize() < external_senders.len())
+                    .unwrap_or(false);
+                if !is_listed {
+                    return Err(StrictValidationError::ExternalSender);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the proposals and the path of a processed message. This
+    /// function implements the following checks:
+    ///  - ValSem105
+    ///  - ValSem106
+    pub(super) fn validate_strict_processed_message(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        processed_message: &ProcessedMessage,
+    ) -> Result<(), StrictValidationError> {
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => Ok(()),
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => {
+                        self.validate_strict_add_proposal(backend, add_proposal)
+                    }
+                    Proposal::Update(update_proposal) => {
+                        self.validate_strict_update_proposal(update_proposal)
+                    }
+                    _ => Ok(()),
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                for queued_add_proposal in staged_commit.add_proposals() {
+                    self.validate_strict_add_proposal(backend, queued_add_proposal.add_proposal())?;
+                }
+                for queued_update_proposal in staged_commit.update_proposals() {
+                    self.validate_strict_update_proposal(queued_update_proposal.update_proposal())?;
+                }
+                // ValSem106
+                if let Some(leaf_node) = staged_commit.commit_update_key_package() {
+                    if !self.leaf_node_supports_group(leaf_node) {
+                        return Err(StrictValidationError::InsufficientPathCapabilities);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Validate an Add proposal. This function implements the following checks:
+    ///  - ValSem105
+    ///  - ValSem106: The key package must be valid
+    fn validate_strict_add_proposal(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        add_proposal: &AddProposal,
+    ) -> Result<(), StrictValidationError> {
+        let key_package = add_proposal.key_package();
+
+        // ValSem106
+        // The standard validation only checks the capabilities of the key
+        // package, but not its signature and lifetime.
+        key_package
+            .verify(backend)
+            .map_err(StrictValidationError::InvalidKeyPackage)?;
+
+        // ValSem105
+        let init_key = key_package.hpke_init_key().as_slice();
+        if self
+            .members()
+            .any(|member| member.encryption_key == init_key)
+        {
+            return Err(StrictValidationError::ExistingInitKeyAddProposal);
+        }
+        Ok(())
+    }
+
+    /// Validate an Update proposal. This function implements the following
+    /// checks:
+    ///  - ValSem106: The leaf node must support the group
+    fn validate_strict_update_proposal(
+        &self,
+        update_proposal: &UpdateProposal,
+    ) -> Result<(), StrictValidationError> {
+        if !self.leaf_node_supports_group(update_proposal.leaf_node()) {
+            return Err(StrictValidationError::InsufficientUpdateCapabilities);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the capabilities of the leaf node contain the group's
+    /// ciphersuite and version, as well as the group's required capabilities.
+    fn leaf_node_supports_group(&self, leaf_node: &LeafNode) -> bool {
+        let capabilities = leaf_node.capabilities();
+        capabilities.ciphersuites().contains(&self.ciphersuite())
+            && capabilities.versions().contains(&self.group.version())
+            && self
+                .group
+                .context()
+                .required_capabilities()
+                .map_or(true, |required_capabilities| {
+                    capabilities.supports_required_capabilities(required_capabilities)
+                })
+    }
+}
@@ -0,0 +1,122 @@
+//! Verification of the [`TokenBoundCredential`]s of members with the
+//! [`TokenBindingVerifier`] of the application.
+
+use std::{fmt, sync::Arc};
+
+use crate::credentials::{TokenBindingVerifier, TokenBoundCredential};
+
+use super::*;
+
+/// The [`TokenBindingVerifier`] in the [`MlsGroupConfig`].
+#[derive(Clone)]
+pub(crate) struct TokenVerifier(Arc<dyn TokenBindingVerifier>);
+
+impl TokenVerifier {
+    pub(crate) fn new(verifier: impl TokenBindingVerifier + 'static) -> Self {
+        Self(Arc::new(verifier))
+    }
+
+    fn verify(&self, credential: &TokenBoundCredential) -> bool {
+        self.0.verify(credential)
+    }
+}
+
+impl fmt::Debug for TokenVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenVerifier")
+    }
+}
+
+impl PartialEq for TokenVerifier {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for TokenVerifier {}
+
+/// Returns `true` if the verifier of the `mls_group_config` accepts the
+/// bindings of all token-bound credentials among the `credentials`. Without
+/// a verifier, token-bound credentials are rejected. Other credentials are
+/// accepted.
+pub(super) fn verifies_token_bindings<'a>(
+    mls_group_config: &MlsGroupConfig,
+    credentials: impl IntoIterator<Item = &'a Credential>,
+) -> bool {
+    credentials
+        .into_iter()
+        .all(|credential| match credential.token_bound_credential() {
+            Some(token_bound_credential) => mls_group_config
+                .token_binding_verifier
+                .as_ref()
+                .map_or(false, |verifier| verifier.verify(token_bound_credential)),
+            None => true,
+        })
+}
+
+impl MlsGroup {
+    /// Sets the [`TokenBindingVerifier`] of the group, e.g. after the group
+    /// was loaded, since verifiers are not persisted. See
+    /// [`MlsGroupConfigBuilder::token_binding_verifier()`] for more
+    /// information.
+    pub fn set_token_binding_verifier(&mut self, verifier: impl TokenBindingVerifier + 'static) {
+        self.mls_group_config.token_binding_verifier = Some(TokenVerifier::new(verifier));
+    }
+
+    /// Returns `true` if the verifier of the group accepts the bindings of
+    /// all token-bound credentials among the `credentials`.
+    pub(super) fn verifies_token_bindings<'a>(
+        &self,
+        credentials: impl IntoIterator<Item = &'a Credential>,
+    ) -> bool {
+        verifies_token_bindings(&self.mls_group_config, credentials)
+    }
+
+    /// Returns `true` if the verifier of the group accepts the bindings of
+    /// all token-bound credentials of the members added or updated by the
+    /// proposal or the commit in the processed message.
+    pub(super) fn processed_message_verifies_token_bindings(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> bool {
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => true,
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => self.verifies_token_bindings([add_proposal
+                        .key_package()
+                        .leaf_node()
+                        .credential()]),
+                    Proposal::Update(update_proposal) => {
+                        self.verifies_token_bindings([update_proposal.leaf_node().credential()])
+                    }
+                    _ => true,
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                let added = staged_commit.add_proposals().all(|queued_add_proposal| {
+                    self.verifies_token_bindings([queued_add_proposal
+                        .add_proposal()
+                        .key_package()
+                        .leaf_node()
+                        .credential()])
+                });
+                let updated = staged_commit
+                    .update_proposals()
+                    .all(|queued_update_proposal| {
+                        self.verifies_token_bindings([queued_update_proposal
+                            .update_proposal()
+                            .leaf_node()
+                            .credential()])
+                    });
+                let path = staged_commit
+                    .commit_update_key_package()
+                    .map_or(true, |leaf_node| {
+                        self.verifies_token_bindings([leaf_node.credential()])
+                    });
+                added && updated && path
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use core_group::create_commit_params::CreateCommitParams;
 use tls_codec::Serialize;
 
-use crate::{messages::GroupInfo, treesync::LeafNode, versions::ProtocolVersion};
+use crate::{messages::GroupInfo, treesync::LeafNode};
 
 use super::*;
 
@@ -30,6 +30,9 @@ impl MlsGroup {
     > {
         self.is_operational()?;
 
+        // Drop expired proposals, such that they are not committed
+        self.expire_pending_proposals();
+
         let credential = self.credential()?;
         let credential_bundle: CredentialBundle = backend
             .key_store()
@@ -56,12 +59,10 @@ impl MlsGroup {
 
         // Set the current group state to [`MlsGroupState::PendingCommit`],
         // storing the current [`StagedCommit`] from the commit results
-        self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
-            create_commit_result.staged_commit,
-        )));
+        self.set_own_pending_commit(backend, create_commit_result.staged_commit, &mls_message)?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::CommitCreated);
 
         Ok((
             mls_message,
@@ -108,6 +109,11 @@ impl MlsGroup {
             )
             .ok_or(ProposeSelfUpdateError::NoMatchingCredentialBundle)?;
 
+        // ValSem109
+        if credential.identity() != old_credential.identity() {
+            return Err(ProposeSelfUpdateError::IdentityMismatch);
+        }
+
         let tree = self.group.treesync();
 
         // Here we clone our own leaf to rekey it such that we don't change the
@@ -129,7 +135,7 @@ impl MlsGroup {
             let keypair = own_leaf.rekey(
                 self.group_id(),
                 self.ciphersuite(),
-                ProtocolVersion::default(), // XXX: openmls/openmls#1065
+                self.group.version(),
                 &credential_bundle,
                 backend,
             )?;
@@ -157,7 +163,7 @@ impl MlsGroup {
         let mls_message = self.content_to_mls_message(update_proposal, backend)?;
 
         // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.flag_state_change(StateChangeReason::ProposalCreated);
 
         Ok(mls_message)
     }
@@ -0,0 +1,125 @@
+//! Verification of the [`VerifiableCredential`]s of members with the
+//! [`VerifiableCredentialVerifier`] of the application.
+
+use std::{fmt, sync::Arc};
+
+use crate::credentials::{VerifiableCredential, VerifiableCredentialVerifier};
+
+use super::*;
+
+/// The [`VerifiableCredentialVerifier`] in the [`MlsGroupConfig`].
+#[derive(Clone)]
+pub(crate) struct CredentialVerifier(Arc<dyn VerifiableCredentialVerifier>);
+
+impl CredentialVerifier {
+    pub(crate) fn new(verifier: impl VerifiableCredentialVerifier + 'static) -> Self {
+        Self(Arc::new(verifier))
+    }
+
+    fn verify(&self, credential: &VerifiableCredential) -> bool {
+        self.0.verify(credential)
+    }
+}
+
+impl fmt::Debug for CredentialVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CredentialVerifier")
+    }
+}
+
+impl PartialEq for CredentialVerifier {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CredentialVerifier {}
+
+/// Returns `true` if the verifier of the `mls_group_config` accepts all
+/// verifiable credentials among the `credentials`. Without a verifier,
+/// verifiable credentials are rejected. Other credentials are accepted.
+pub(super) fn verifies_credentials<'a>(
+    mls_group_config: &MlsGroupConfig,
+    credentials: impl IntoIterator<Item = &'a Credential>,
+) -> bool {
+    credentials
+        .into_iter()
+        .all(|credential| match credential.verifiable_credential() {
+            Some(verifiable_credential) => mls_group_config
+                .verifiable_credential_verifier
+                .as_ref()
+                .map_or(false, |verifier| verifier.verify(verifiable_credential)),
+            None => true,
+        })
+}
+
+impl MlsGroup {
+    /// Sets the [`VerifiableCredentialVerifier`] of the group, e.g. after the
+    /// group was loaded, since verifiers are not persisted. See
+    /// [`MlsGroupConfigBuilder::verifiable_credential_verifier()`] for more
+    /// information.
+    pub fn set_verifiable_credential_verifier(
+        &mut self,
+        verifier: impl VerifiableCredentialVerifier + 'static,
+    ) {
+        self.mls_group_config.verifiable_credential_verifier =
+            Some(CredentialVerifier::new(verifier));
+    }
+
+    /// Returns `true` if the verifier of the group accepts all verifiable
+    /// credentials among the `credentials`.
+    pub(super) fn verifies_credentials<'a>(
+        &self,
+        credentials: impl IntoIterator<Item = &'a Credential>,
+    ) -> bool {
+        verifies_credentials(&self.mls_group_config, credentials)
+    }
+
+    /// Returns `true` if the verifier of the group accepts all verifiable
+    /// credentials of the members added or updated by the proposal or the
+    /// commit in the processed message.
+    pub(super) fn processed_message_verifies_credentials(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> bool {
+        match processed_message.content() {
+            ProcessedMessageContent::ApplicationMessage(_) => true,
+            ProcessedMessageContent::ProposalMessage(queued_proposal)
+            | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                match queued_proposal.proposal() {
+                    Proposal::Add(add_proposal) => self.verifies_credentials([add_proposal
+                        .key_package()
+                        .leaf_node()
+                        .credential()]),
+                    Proposal::Update(update_proposal) => {
+                        self.verifies_credentials([update_proposal.leaf_node().credential()])
+                    }
+                    _ => true,
+                }
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                let added = staged_commit.add_proposals().all(|queued_add_proposal| {
+                    self.verifies_credentials([queued_add_proposal
+                        .add_proposal()
+                        .key_package()
+                        .leaf_node()
+                        .credential()])
+                });
+                let updated = staged_commit
+                    .update_proposals()
+                    .all(|queued_update_proposal| {
+                        self.verifies_credentials([queued_update_proposal
+                            .update_proposal()
+                            .leaf_node()
+                            .credential()])
+                    });
+                let path = staged_commit
+                    .commit_update_key_package()
+                    .map_or(true, |leaf_node| {
+                        self.verifies_credentials([leaf_node.credential()])
+                    });
+                added && updated && path
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Permanent deletion of the secrets of a group, see [`MlsGroup::wipe()`].
+
+use crate::{
+    schedule::{
+        errors::WritePskError,
+        psk::{ResumptionPsk, ResumptionPskUsage},
+    },
+    treesync::node::encryption_keys::EncryptionKeyPair,
+};
+
+use super::*;
+
+/// The secrets that were destroyed by [`MlsGroup::wipe()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WipeReport {
+    epoch_keypairs: usize,
+    leaf_keypairs: usize,
+    resumption_psks: usize,
+    zeroized_epochs: usize,
+    zeroized_resumption_psks: usize,
+    pending_commit: bool,
+}
+
+impl WipeReport {
+    /// Returns the number of encryption keypairs of the current epoch that
+    /// were deleted from the key store.
+    pub fn epoch_keypairs(&self) -> usize {
+        self.epoch_keypairs
+    }
+
+    /// Returns the number of encryption keypairs of the own leaf and of
+    /// pending updates that were deleted from the key store.
+    pub fn leaf_keypairs(&self) -> usize {
+        self.leaf_keypairs
+    }
+
+    /// Returns the number of resumption PSKs that were deleted from the key
+    /// store.
+    pub fn resumption_psks(&self) -> usize {
+        self.resumption_psks
+    }
+
+    /// Returns the number of epochs whose message secrets were overwritten in
+    /// memory, i.e. the current and the retained past epochs.
+    pub fn zeroized_epochs(&self) -> usize {
+        self.zeroized_epochs
+    }
+
+    /// Returns the number of resumption PSKs of past epochs that were
+    /// overwritten in memory.
+    pub fn zeroized_resumption_psks(&self) -> usize {
+        self.zeroized_resumption_psks
+    }
+
+    /// Returns `true` if a pending commit was discarded.
+    pub fn pending_commit(&self) -> bool {
+        self.pending_commit
+    }
+}
+
+impl MlsGroup {
+    /// Permanently destroys the secrets of the group, e.g. when the user
+    /// deletes a conversation.
+    ///
+    /// The key store items the group owns are deleted: the encryption
+    /// keypairs of the current epoch, the keypairs of the own leaf and of
+    /// pending updates, and the resumption PSKs of the group. The epoch
+    /// secrets, the message secrets of all retained epochs and the
+    /// resumption PSKs are overwritten with zeros in memory, and pending
+    /// proposals, a pending commit and a stored staged commit are discarded.
+    /// Items shared with other groups, such as the [`CredentialBundle`] and
+    /// key packages, are not deleted.
+    ///
+    /// Afterwards, the group is inactive and can't be used anymore. The
+    /// application should delete the persisted group state as well. If
+    /// access to the key store fails, the in-memory secrets are kept, such
+    /// that the wipe can be retried.
+    pub fn wipe<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<WipeReport, WipeError<KeyStore::Error>> {
+        let mut report = WipeReport {
+            epoch_keypairs: self
+                .group
+                .delete_epoch_keypairs(backend)
+                .map_err(WipeError::KeyStoreError)?,
+            ..Default::default()
+        };
+
+        let leaf_nodes = self
+            .own_leaf()
+            .into_iter()
+            .chain(self.own_leaf_nodes.iter().map(|leaf| leaf.leaf_node()));
+        for leaf_node in leaf_nodes {
+            if let Some(keypair) =
+                EncryptionKeyPair::read_from_key_store(backend, leaf_node.encryption_key())
+            {
+                keypair
+                    .delete_from_key_store(backend)
+                    .map_err(WipeError::KeyStoreError)?;
+                report.leaf_keypairs += 1;
+            }
+        }
+
+        let epochs = self
+            .resumption_psk_store
+            .epochs()
+            .chain([self.epoch()])
+            .collect::<Vec<_>>();
+        for epoch in epochs {
+            for usage in [
+                ResumptionPskUsage::Application,
+                ResumptionPskUsage::Reinit,
+                ResumptionPskUsage::Branch,
+            ] {
+                let deleted = ResumptionPsk::new(usage, self.group_id().clone(), epoch)
+                    .delete_from_key_store(backend)
+                    .map_err(|e| match e {
+                        WritePskError::LibraryError(e) => WipeError::LibraryError(e),
+                        WritePskError::KeyStoreError(e) => WipeError::KeyStoreError(e),
+                    })?;
+                if deleted {
+                    report.resumption_psks += 1;
+                }
+            }
+        }
+
+        if let MlsGroupState::PendingCommit(pending_commit_state) = &mut self.group_state {
+            match pending_commit_state.as_mut() {
+                PendingCommitState::Member(staged_commit)
+                | PendingCommitState::External(staged_commit) => staged_commit.zeroize(),
+            }
+            report.pending_commit = true;
+        }
+        if let Some(mut staged_commit) = self.stored_staged_commit.take() {
+            staged_commit.zeroize();
+        }
+        report.zeroized_epochs = self.group.zeroize_secrets();
+        report.zeroized_resumption_psks = self.resumption_psk_store.zeroize();
+
+        self.proposal_store.empty();
+        self.own_leaf_nodes.clear();
+        self.group_state = MlsGroupState::Inactive;
+        self.flag_state_change(StateChangeReason::Wiped);
+
+        Ok(report)
+    }
+}
@@ -6,17 +6,20 @@ mod group_context;
 
 #[cfg(any(feature = "test-utils", test))]
 use crate::ciphersuite::*;
+use crate::error::LibraryError;
 use crate::extensions::*;
 use crate::utils::*;
+use errors::GroupIdError;
 
 use openmls_traits::OpenMlsCryptoProvider;
 use serde::{Deserialize, Serialize};
-use tls_codec::*;
+use tls_codec::{Serialize as TlsSerializeTrait, *};
 
 // Crate
 pub(crate) mod core_group;
 pub(crate) use core_group::*;
 pub(crate) mod mls_group;
+pub(crate) mod public_group;
 #[cfg(not(any(feature = "test-utils", test)))]
 pub(crate) use group_context::*;
 
@@ -26,10 +29,28 @@ pub mod errors;
 
 pub use core_group::proposals::*;
 pub use core_group::staged_commit::StagedCommit;
+pub use mls_group::archive::ArchivedGroup;
 pub use mls_group::config::*;
+pub use mls_group::delta::{StateComponent, StateDelta};
+pub use mls_group::external_proposal_limits::{ExternalProposalLimiter, ExternalProposalSource};
+pub use mls_group::fingerprint::Fingerprint;
+pub use mls_group::key_rotation::*;
 pub use mls_group::membership::*;
+pub use mls_group::membership_history::MembershipRecord;
+pub use mls_group::namespaced_exporter::NamespacedExporter;
+#[cfg(feature = "partial-tree")]
+pub use mls_group::partial_group::PartialGroup;
 pub use mls_group::processing::*;
+pub use mls_group::retention::MessageSecretsPin;
+pub use mls_group::sas::{SasEmoji, ShortAuthenticationString};
+pub use mls_group::ser::{JsonSerializer, StateSerializer};
+pub use mls_group::skipped_keys::SkippedKeysEviction;
+pub use mls_group::snapshot::SnapshotKey;
+pub use mls_group::staged_welcome::{ProcessedWelcome, StagedWelcome};
+pub use mls_group::state_change::StateChangeReason;
+pub use mls_group::wipe::WipeReport;
 pub use mls_group::*;
+pub use public_group::PublicGroup;
 
 // Tests
 #[cfg(any(feature = "test-utils", test))]
@@ -38,19 +59,48 @@ pub(crate) use create_commit_params::*;
 pub(crate) mod tests;
 #[cfg(any(feature = "test-utils", test))]
 pub use group_context::GroupContext;
-use openmls_traits::random::OpenMlsRand;
+use openmls_traits::{crypto::OpenMlsCrypto, random::OpenMlsRand, types::HashType};
 #[cfg(any(feature = "test-utils", test))]
 pub use proposals::*;
 
+/// The label of the derivation of group IDs, see [`GroupId::derive()`].
+const GROUP_ID_DERIVATION_LABEL: &[u8] = b"OpenMLS group id";
+
+/// The input of the derivation of a group ID.
+///
+/// ```text
+/// struct {
+///     opaque label<V>;
+///     opaque creator<V>;
+///     opaque context<V>;
+/// } GroupIdDerivationInput;
+/// ```
+#[derive(TlsSerialize, TlsSize)]
+struct GroupIdDerivationInput {
+    label: VLBytes,
+    creator: VLBytes,
+    context: VLBytes,
+}
+
 /// A group ID. The group ID is chosen by the creator of the group and should be globally unique.
 #[derive(
     Hash, Eq, Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupId {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     value: VLBytes,
 }
 
 impl GroupId {
+    /// The minimum length of group IDs created with
+    /// [`GroupId::try_from_slice()`].
+    pub const MIN_LENGTH: usize = 16;
+
+    /// The maximum length of group IDs created with
+    /// [`GroupId::try_from_slice()`].
+    pub const MAX_LENGTH: usize = 255;
+
     /// Create a new (random) group ID.
     ///
     /// Group IDs should be random and not be misused as, e.g., a group name.
@@ -64,6 +114,49 @@ impl GroupId {
         }
     }
 
+    /// Create a new (random) group ID in the format of a version 4 UUID.
+    ///
+    /// The group ID has 122 random bits, and the version and variant bits of a
+    /// UUID, such that it can be stored by systems that expect UUIDs.
+    pub fn uuid(backend: &impl OpenMlsCryptoProvider) -> Self {
+        let mut value = backend
+            .rand()
+            .random_vec(16)
+            .expect("Not enough randomness.");
+        value[6] = (value[6] & 0x0f) | 0x40;
+        value[8] = (value[8] & 0x3f) | 0x80;
+        Self {
+            value: value.into(),
+        }
+    }
+
+    /// Derive a group ID from the `creator` of the group, e.g. its signature
+    /// key, and an application-defined `context`.
+    ///
+    /// The group ID is the SHA-256 hash of both values, domain-separated with a
+    /// label. The same creator and context always result in the same group ID,
+    /// so the `context` must be unique among the groups of the creator.
+    pub fn derive(
+        backend: &impl OpenMlsCryptoProvider,
+        creator: &[u8],
+        context: &[u8],
+    ) -> Result<Self, LibraryError> {
+        let input = GroupIdDerivationInput {
+            label: GROUP_ID_DERIVATION_LABEL.into(),
+            creator: creator.into(),
+            context: context.into(),
+        }
+        .tls_serialize_detached()
+        .map_err(LibraryError::missing_bound_check)?;
+        let value = backend
+            .crypto()
+            .hash(HashType::Sha2_256, &input)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        Ok(Self {
+            value: value.into(),
+        })
+    }
+
     /// Create a group ID from a byte slice.
     ///
     /// This should be used only if the group ID is chosen by an entity that ensures uniqueness.
@@ -73,6 +166,21 @@ impl GroupId {
         }
     }
 
+    /// Create a group ID from a byte slice, checking that its length is
+    /// between [`GroupId::MIN_LENGTH`] and [`GroupId::MAX_LENGTH`].
+    ///
+    /// Shorter group IDs are likely to collide with the group IDs of other
+    /// groups. Returns a [`GroupIdError`] if the length is invalid.
+    pub fn try_from_slice(bytes: &[u8]) -> Result<Self, GroupIdError> {
+        if bytes.len() < Self::MIN_LENGTH {
+            return Err(GroupIdError::TooShort);
+        }
+        if bytes.len() > Self::MAX_LENGTH {
+            return Err(GroupIdError::TooLong);
+        }
+        Ok(Self::from_slice(bytes))
+    }
+
     /// Returns the group ID as a byte slice.
     pub fn as_slice(&self) -> &[u8] {
         self.value.as_slice()
@@ -99,6 +207,7 @@ impl GroupId {
     TlsSerialize,
     TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupEpoch(u64);
 
 impl GroupEpoch {
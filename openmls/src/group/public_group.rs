@@ -0,0 +1,495 @@
+//! # Public group
+//!
+//! A [`PublicGroup`] follows the public state of a group, i.e. its members,
+//! its ratchet tree and its epoch, from the handshake messages sent to the
+//! group. It is meant to be used by parties that are not members of the
+//! group, in particular the Delivery Service, which can use it to answer
+//! which clients are in a group and to serve the ratchet tree and the
+//! [`VerifiableGroupInfo`] to new members.
+//!
+//! Since the Delivery Service doesn't know the secrets of the group, a
+//! [`PublicGroup`] can only follow groups whose handshake messages are sent
+//! as [`PublicMessage`]s, and it can't verify membership tags or confirmation
+//! tags. Signatures, parent hashes and the structure of commits are verified.
+
+use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
+use tls_codec::Serialize as TlsSerializeTrait;
+
+#[cfg(feature = "partial-tree")]
+use crate::treesync::{errors::PartialTreeError, PartialTree};
+use crate::{
+    binary_tree::array_representation::LeafNodeIndex,
+    ciphersuite::signable::Verifiable,
+    error::LibraryError,
+    extensions::try_nodes_from_extensions,
+    framing::{
+        mls_auth_content::AuthenticatedContent,
+        mls_content::FramedContentBody,
+        validation::{DecryptedMessage, UnverifiedContextMessage, UnverifiedMessage},
+        ConfirmedTranscriptHashInput, InterimTranscriptHashInput, PublicMessage, Sender,
+    },
+    messages::{
+        proposals::{Proposal, ProposalType},
+        GroupInfo, VerifiableGroupInfo,
+    },
+    treesync::{
+        errors::{PublicTreeError, TreeSyncAddLeaf, TreeSyncFromNodesError},
+        node::{
+            leaf_node::{LeafNodeTbs, OpenMlsLeafNode, TreeInfoTbs, VerifiableLeafNodeTbs},
+            Node,
+        },
+        TreeSync,
+    },
+};
+
+use super::{
+    core_group::{
+        proposals::{ProposalQueue, ProposalStore, QueuedProposal},
+        update_confirmed_transcript_hash, update_interim_transcript_hash, Member,
+    },
+    errors::{
+        FromCommittedProposalsError, ProposalValidationError, PublicGroupError, StageCommitError,
+        ValidationError,
+    },
+    group_context::GroupContext,
+    GroupEpoch, GroupId, MembershipDiff,
+};
+
+/// The public state of a group, as seen by a party that is not a member of
+/// the group. See the [module documentation](self) for more details.
+#[derive(Debug)]
+pub struct PublicGroup {
+    group_context: GroupContext,
+    treesync: TreeSync,
+    interim_transcript_hash: Vec<u8>,
+    proposal_store: ProposalStore,
+    group_info: Option<VerifiableGroupInfo>,
+}
+
+impl PublicGroup {
+    /// Creates a [`PublicGroup`] from the [`VerifiableGroupInfo`] of the
+    /// group and, if the group info doesn't contain the ratchet tree
+    /// extension, the ratchet tree of the group.
+    ///
+    /// The signature of the group info and the tree hash are verified. The
+    /// group info is kept and returned by [`PublicGroup::group_info()`] until
+    /// the next commit is processed.
+    pub fn from_group_info(
+        backend: &impl OpenMlsCryptoProvider,
+        verifiable_group_info: VerifiableGroupInfo,
+        tree_option: Option<&[Option<Node>]>,
+    ) -> Result<Self, PublicGroupError> {
+        let ciphersuite = verifiable_group_info.ciphersuite();
+
+        let nodes = match try_nodes_from_extensions(verifiable_group_info.extensions())
+            .map_err(|_| PublicGroupError::PublicTreeError(PublicTreeError::MalformedTree))?
+        {
+            Some(nodes) => nodes,
+            None => tree_option
+                .ok_or(PublicGroupError::MissingRatchetTree)?
+                .into(),
+        };
+        let treesync = TreeSync::from_nodes_without_leaf(backend, ciphersuite, nodes).map_err(
+            |e| match e {
+                TreeSyncFromNodesError::LibraryError(e) => PublicGroupError::LibraryError(e),
+                TreeSyncFromNodesError::PublicTreeError(e) => PublicGroupError::PublicTreeError(e),
+            },
+        )?;
+
+        let group_info = verify_group_info(backend, &treesync, verifiable_group_info.clone())?;
+        if treesync.tree_hash() != group_info.group_context().tree_hash() {
+            return Err(PublicGroupError::TreeHashMismatch);
+        }
+
+        let interim_transcript_hash = if group_info.group_context().epoch() == GroupEpoch::from(0) {
+            vec![]
+        } else {
+            update_interim_transcript_hash(
+                ciphersuite,
+                backend,
+                &InterimTranscriptHashInput::from(group_info.confirmation_tag()),
+                group_info.group_context().confirmed_transcript_hash(),
+            )?
+        };
+
+        Ok(Self {
+            group_context: group_info.group_context().clone(),
+            treesync,
+            interim_transcript_hash,
+            proposal_store: ProposalStore::new(),
+            group_info: Some(verifiable_group_info),
+        })
+    }
+
+    /// Processes a proposal or a commit sent to the group as a
+    /// [`PublicMessage`].
+    ///
+    /// Proposals are stored until they are committed. Commits are applied to
+    /// the public state of the group, which then moves to the next epoch, and
+    /// the changes of the membership are returned. Returns `None` for
+    /// proposals.
+    ///
+    /// Messages from other epochs than the current one are rejected with
+    /// [`ValidationError::WrongEpoch`].
+    pub fn process_message(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: PublicMessage,
+    ) -> Result<Option<MembershipDiff>, PublicGroupError> {
+        let authenticated_content = self.verify_message(backend, message)?;
+
+        match authenticated_content.content() {
+            FramedContentBody::Proposal(_) => {
+                self.proposal_store
+                    .add(QueuedProposal::from_authenticated_content(
+                        self.ciphersuite(),
+                        backend,
+                        authenticated_content,
+                    )?);
+                Ok(None)
+            }
+            FramedContentBody::Commit(_) => {
+                self.apply_commit(backend, &authenticated_content).map(Some)
+            }
+            // ValSem005: This is rejected when the message is decrypted.
+            FramedContentBody::Application(_) => {
+                Err(ValidationError::UnencryptedApplicationMessage.into())
+            }
+        }
+    }
+
+    /// Validates a proposal or a commit sent to the group as a
+    /// [`PublicMessage`] without applying it, e.g. in a relay that filters the
+    /// traffic of the group. The group ID, the epoch and the signature of the
+    /// message are checked, but neither is the proposal stored nor is the
+    /// content of the commit validated.
+    ///
+    /// Since a [`PublicGroup`] doesn't know the membership key, membership
+    /// tags can't be verified.
+    pub fn validate_message(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: PublicMessage,
+    ) -> Result<(), PublicGroupError> {
+        self.verify_message(backend, message).map(|_| ())
+    }
+
+    /// Sets the [`VerifiableGroupInfo`] that is served to new members, e.g.
+    /// the group info that was sent along with the last commit. The
+    /// signature of the group info is verified, and it has to belong to the
+    /// current epoch of the group.
+    pub fn set_group_info(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        verifiable_group_info: VerifiableGroupInfo,
+    ) -> Result<(), PublicGroupError> {
+        let group_info = verify_group_info(backend, &self.treesync, verifiable_group_info.clone())?;
+        if group_info.group_context() != &self.group_context {
+            return Err(PublicGroupError::GroupInfoMismatch);
+        }
+        self.group_info = Some(verifiable_group_info);
+        Ok(())
+    }
+
+    /// Returns the [`VerifiableGroupInfo`] of the current epoch, if one was
+    /// set. The group info is dropped when a commit is processed.
+    pub fn group_info(&self) -> Option<&VerifiableGroupInfo> {
+        self.group_info.as_ref()
+    }
+
+    /// Returns the group ID.
+    pub fn group_id(&self) -> &GroupId {
+        self.group_context.group_id()
+    }
+
+    /// Returns the epoch.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.group_context.epoch()
+    }
+
+    /// Returns the ciphersuite.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.group_context.ciphersuite()
+    }
+
+    /// Returns a list of [`Member`]s in the group.
+    pub fn members(&self) -> impl Iterator<Item = Member> + '_ {
+        self.treesync.full_leave_members()
+    }
+
+    /// Exports the ratchet tree, e.g. to serve it to new members.
+    pub fn export_ratchet_tree(&self) -> Vec<Option<Node>> {
+        self.treesync.export_nodes()
+    }
+
+    /// Exports the [`PartialTree`] of the member at `leaf_index`, e.g. to
+    /// serve it to a new member instead of the full ratchet tree.
+    #[cfg(feature = "partial-tree")]
+    pub fn export_partial_tree(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        leaf_index: LeafNodeIndex,
+    ) -> Result<PartialTree, PartialTreeError> {
+        self.treesync
+            .partial_tree(backend, self.ciphersuite(), leaf_index)
+    }
+}
+
+// Private methods of PublicGroup
+impl PublicGroup {
+    /// Checks the framing of the `message` and verifies its signature.
+    fn verify_message(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: PublicMessage,
+    ) -> Result<AuthenticatedContent, PublicGroupError> {
+        // ValSem002
+        if message.group_id() != self.group_id() {
+            return Err(ValidationError::WrongGroupId.into());
+        }
+        // ValSem003
+        if message.epoch() != self.epoch() {
+            return Err(ValidationError::WrongEpoch.into());
+        }
+
+        let serialized_context = self
+            .group_context
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        let decrypted_message =
+            DecryptedMessage::from_inbound_public_message_without_membership_tag(
+                message,
+                &serialized_context,
+            )?;
+        let credential = decrypted_message.credential(
+            &self.treesync,
+            &[],
+            self.group_context.extensions().external_senders(),
+        )?;
+        let unverified_message =
+            UnverifiedMessage::from_decrypted_message(decrypted_message, Some(credential));
+
+        // ValSem010
+        let authenticated_content =
+            match UnverifiedContextMessage::from_unverified_message(unverified_message)? {
+                UnverifiedContextMessage::Group(message) => {
+                    message.into_verified(backend)?.take_authenticated_content()
+                }
+                UnverifiedContextMessage::NewMember(message) => {
+                    message.into_verified(backend)?.take_authenticated_content()
+                }
+                UnverifiedContextMessage::External(message) => {
+                    message.into_verified(backend)?.take_authenticated_content()
+                }
+            };
+        Ok(authenticated_content)
+    }
+
+    /// Applies the verified commit in `authenticated_content` to the public
+    /// state of the group.
+    fn apply_commit(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        authenticated_content: &AuthenticatedContent,
+    ) -> Result<MembershipDiff, PublicGroupError> {
+        let ciphersuite = self.ciphersuite();
+        let sender = authenticated_content.sender();
+        let commit = match authenticated_content.content() {
+            FramedContentBody::Commit(commit) => commit,
+            _ => return Err(StageCommitError::WrongPlaintextContentType.into()),
+        };
+        if matches!(sender, Sender::NewMemberProposal) {
+            return Err(StageCommitError::SenderTypeNewMemberProposal.into());
+        }
+
+        let proposal_queue = ProposalQueue::from_committed_proposals(
+            ciphersuite,
+            backend,
+            commit.proposals.as_slice().to_vec(),
+            &self.proposal_store,
+            sender,
+        )
+        .map_err(|e| match e {
+            FromCommittedProposalsError::LibraryError(e) => StageCommitError::LibraryError(e),
+            FromCommittedProposalsError::ProposalNotFound => StageCommitError::MissingProposal,
+            FromCommittedProposalsError::SelfRemoval => StageCommitError::AttemptedSelfRemoval,
+        })?;
+
+        // Apply the proposals to a provisional tree
+        let mut diff = self.treesync.empty_diff();
+        for queued_proposal in proposal_queue.filtered_by_type(ProposalType::Update) {
+            if let (Proposal::Update(update_proposal), Sender::Member(sender_index)) =
+                (queued_proposal.proposal(), queued_proposal.sender())
+            {
+                let leaf_node: OpenMlsLeafNode = update_proposal.leaf_node().clone().into();
+                diff.update_leaf(leaf_node, *sender_index);
+            }
+        }
+        let mut removed_leaves: Vec<LeafNodeIndex> = Vec::new();
+        for queued_proposal in proposal_queue.filtered_by_type(ProposalType::Remove) {
+            if let Proposal::Remove(remove_proposal) = queued_proposal.proposal() {
+                let removed = remove_proposal.removed();
+                // ValSem107
+                if removed_leaves.contains(&removed) {
+                    return Err(ProposalValidationError::DuplicateMemberRemoval.into());
+                }
+                // ValSem108
+                if !self.treesync.is_leaf_in_tree(removed) {
+                    return Err(ProposalValidationError::UnknownMemberRemoval.into());
+                }
+                diff.blank_leaf(removed);
+                removed_leaves.push(removed);
+            }
+        }
+        for queued_proposal in proposal_queue.filtered_by_type(ProposalType::Add) {
+            if let Proposal::Add(add_proposal) = queued_proposal.proposal() {
+                diff.add_leaf(add_proposal.key_package().leaf_node().clone().into())
+                    .map_err(|e| match e {
+                        TreeSyncAddLeaf::LibraryError(e) => e.into(),
+                        TreeSyncAddLeaf::TreeFull => StageCommitError::TooManyNewMembers,
+                    })?;
+            }
+        }
+
+        let extensions = proposal_queue
+            .filtered_by_type(ProposalType::GroupContextExtensions)
+            .find_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::GroupContextExtensions(group_context_extension_proposal) => {
+                    Some(group_context_extension_proposal.extensions().clone())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| self.group_context.extensions().clone());
+
+        let sender_index = match sender {
+            Sender::Member(leaf_index) => *leaf_index,
+            Sender::NewMemberCommit => diff.free_leaf_index(),
+            _ => return Err(StageCommitError::SenderTypeExternal.into()),
+        };
+
+        // Apply the public part of the path
+        match commit.path.clone() {
+            Some(path) => {
+                let leaf_node = path.leaf_node();
+                let tbs = LeafNodeTbs::from(
+                    leaf_node.clone(),
+                    TreeInfoTbs::commit(self.group_id().clone(), sender_index),
+                );
+                let verifiable_leaf_node = VerifiableLeafNodeTbs {
+                    tbs: &tbs,
+                    signature: leaf_node.signature(),
+                };
+                if verifiable_leaf_node
+                    .verify_no_out(
+                        backend,
+                        leaf_node.signature_key(),
+                        leaf_node.credential().signature_scheme(),
+                    )
+                    .is_err()
+                {
+                    return Err(StageCommitError::PathLeafNodeVerificationFailure.into());
+                }
+
+                let (leaf_node, update_path_nodes) = path.into_parts();
+                // The sender of an External Commit is added to the tree first.
+                if sender == &Sender::NewMemberCommit {
+                    let sender_leaf_index =
+                        diff.add_leaf(leaf_node.clone().into())
+                            .map_err(|e| match e {
+                                TreeSyncAddLeaf::LibraryError(e) => e.into(),
+                                TreeSyncAddLeaf::TreeFull => StageCommitError::TooManyNewMembers,
+                            })?;
+                    if sender_leaf_index != sender_index {
+                        return Err(StageCommitError::InconsistentSenderIndex.into());
+                    }
+                }
+                let public_path = diff
+                    .public_path(sender_index, update_path_nodes)
+                    .map_err(StageCommitError::from)?;
+                diff.apply_received_update_path(
+                    backend,
+                    ciphersuite,
+                    sender_index,
+                    leaf_node,
+                    public_path,
+                )
+                .map_err(StageCommitError::from)?;
+            }
+            None => {
+                let path_required = proposal_queue
+                    .queued_proposals()
+                    .any(|queued_proposal| queued_proposal.proposal().is_path_required())
+                    || proposal_queue.is_empty()
+                    || sender == &Sender::NewMemberCommit;
+                // ValSem201
+                if path_required {
+                    return Err(StageCommitError::RequiredPathNotFound.into());
+                }
+            }
+        }
+
+        // Compute the group context of the next epoch
+        let confirmed_transcript_hash = update_confirmed_transcript_hash(
+            ciphersuite,
+            backend,
+            &ConfirmedTranscriptHashInput::try_from(authenticated_content)
+                .map_err(|_| LibraryError::custom("Could not convert commit content"))?,
+            &self.interim_transcript_hash,
+        )?;
+        let confirmation_tag = authenticated_content
+            .confirmation_tag()
+            .ok_or(StageCommitError::ConfirmationTagMissing)?;
+        let interim_transcript_hash = update_interim_transcript_hash(
+            ciphersuite,
+            backend,
+            &InterimTranscriptHashInput::from(confirmation_tag),
+            &confirmed_transcript_hash,
+        )?;
+        let mut epoch = self.epoch();
+        epoch.increment();
+        let staged_diff = diff.into_staged_diff(backend, ciphersuite)?;
+
+        let members_before: Vec<Member> = self.members().collect();
+        self.treesync.merge_diff(staged_diff);
+        self.group_context = GroupContext::new(
+            self.group_context.protocol_version(),
+            ciphersuite,
+            self.group_id().clone(),
+            epoch,
+            self.treesync.tree_hash().to_vec(),
+            confirmed_transcript_hash,
+            extensions,
+        );
+        self.interim_transcript_hash = interim_transcript_hash;
+        self.proposal_store.empty();
+        self.group_info = None;
+
+        Ok(MembershipDiff::new(
+            members_before,
+            self.members(),
+            &removed_leaves,
+            false,
+        ))
+    }
+}
+
+/// Verifies the signature of the `verifiable_group_info` with the signature
+/// key of the signer in the `treesync`.
+fn verify_group_info(
+    backend: &impl OpenMlsCryptoProvider,
+    treesync: &TreeSync,
+    verifiable_group_info: VerifiableGroupInfo,
+) -> Result<GroupInfo, PublicGroupError> {
+    let signer_credential = treesync
+        .leaf(verifiable_group_info.signer())
+        .ok_or(PublicGroupError::UnknownSender)?
+        .credential();
+    let signature_algorithm = verifiable_group_info.ciphersuite().signature_algorithm();
+    verifiable_group_info
+        .verify(
+            backend,
+            signer_credential.signature_key(),
+            signature_algorithm,
+        )
+        .map_err(|_| PublicGroupError::InvalidGroupInfoSignature)
+}
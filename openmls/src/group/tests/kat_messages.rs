@@ -64,7 +64,7 @@ pub fn generate_test_vector(ciphersuite: Ciphersuite) -> MessagesTestVector {
     let crypto = OpenMlsRustCrypto::default();
     let ciphersuite_name = ciphersuite;
     let credential_bundle = CredentialBundle::new(
-        b"OpenMLS rocks".to_vec(),
+        "OpenMLS rocks".into(),
         CredentialType::Basic,
         SignatureScheme::from(ciphersuite_name),
         &crypto,
@@ -88,6 +88,7 @@ pub fn generate_test_vector(ciphersuite: Ciphersuite) -> MessagesTestVector {
     // We can't easily get a "natural" GroupInfo, so we just create one here.
     let group_info_tbs = {
         let group_context = GroupContext::new(
+            ProtocolVersion::default(),
             ciphersuite,
             group.group_id().clone(),
             0,
@@ -144,7 +145,7 @@ pub fn generate_test_vector(ciphersuite: Ciphersuite) -> MessagesTestVector {
 
     // Create proposal to add a user
     let joiner_credential_bundle = CredentialBundle::new(
-        b"MLS rocks".to_vec(),
+        "MLS rocks".into(),
         CredentialType::Basic,
         SignatureScheme::from(ciphersuite_name),
         &crypto,
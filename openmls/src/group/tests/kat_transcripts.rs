@@ -71,13 +71,14 @@ pub fn generate_test_vector(ciphersuite: Ciphersuite) -> TranscriptTestVector {
 
     // Build plaintext commit message.
     let credential_bundle = CredentialBundle::new(
-        b"client".to_vec(),
+        "client".into(),
         CredentialType::Basic,
         SignatureScheme::from(ciphersuite),
         &crypto,
     )
     .expect("An unexpected error occurred.");
     let context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         group_id.clone(),
         epoch,
@@ -201,11 +202,7 @@ pub fn run_test_vector(
         ProtocolVersion::default(),
         ciphersuite,
     ));
-    let confirmation_key = ConfirmationKey::from_secret(Secret::from_slice(
-        &hex_to_bytes(&test_vector.confirmation_key),
-        ProtocolVersion::default(),
-        ciphersuite,
-    ));
+    let confirmation_key = hex_to_bytes(&test_vector.confirmation_key);
     let credential =
         Credential::tls_deserialize(&mut hex_to_bytes(&test_vector.credential).as_slice())
             .expect("An unexpected error occurred.");
@@ -215,6 +212,7 @@ pub fn run_test_vector(
     let commit = PublicMessage::tls_deserialize(&mut commit_bytes.as_slice())
         .expect("Error decoding commit");
     let context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         group_id,
         epoch,
@@ -258,16 +256,19 @@ pub fn run_test_vector(
         )
         .expect("Invalid signature on PublicMessage commit");
 
-    //let my_confirmation_tag = confirmation_key.tag(&confirmed_transcript_hash_before);
     let confirmed_transcript_hash_after =
         hex_to_bytes(&test_vector.confirmed_transcript_hash_after);
 
-    let my_confirmation_tag = confirmation_key
-        .tag(backend, &confirmed_transcript_hash_after)
-        .expect("Could not compute confirmation tag.");
-    if my_confirmation_tag != confirmation_tag {
+    if !verify_confirmation_tag(
+        backend,
+        ciphersuite,
+        &confirmation_key,
+        &confirmed_transcript_hash_after,
+        &confirmation_tag,
+    )
+    .expect("Could not compute confirmation tag.")
+    {
         log::error!("  Confirmation tag mismatch");
-        log::debug!("    Computed: {:x?}", my_confirmation_tag);
         log::debug!("    Expected: {:x?}", confirmation_tag);
         if cfg!(test) {
             panic!("Invalid confirmation tag");
@@ -298,7 +299,7 @@ pub fn run_test_vector(
     let my_interim_transcript_hash_after = update_interim_transcript_hash(
         ciphersuite,
         backend,
-        &InterimTranscriptHashInput::try_from(&my_confirmation_tag)
+        &InterimTranscriptHashInput::try_from(&confirmation_tag)
             .expect("An unexpected error occurred."),
         &my_confirmed_transcript_hash_after,
     )
@@ -8,9 +8,13 @@ pub mod kat_transcripts;
 
 #[cfg(test)]
 mod external_proposal;
+#[cfg(all(test, feature = "async"))]
+mod test_async;
 #[cfg(test)]
 mod test_commit_validation;
 #[cfg(test)]
+mod test_corrupted_messages;
+#[cfg(test)]
 mod test_encoding;
 #[cfg(test)]
 mod test_external_commit_validation;
@@ -25,6 +29,10 @@ mod test_past_secrets;
 #[cfg(test)]
 mod test_proposal_validation;
 #[cfg(test)]
+mod test_public_group;
+#[cfg(test)]
+mod test_remote_kms;
+#[cfg(test)]
 mod test_remove_operation;
 #[cfg(test)]
 mod test_wire_format_policy;
@@ -0,0 +1,342 @@
+//! This module tests the async variants of the [`MlsGroup`] operations.
+
+use std::{
+    future::Future,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::{
+    async_key_store::{AsyncOpenMlsKeyStore, KeyStoreFuture},
+    key_store::OpenMlsKeyStore,
+    types::Ciphersuite,
+    OpenMlsCryptoProvider,
+};
+
+use rstest::*;
+use rstest_reuse::{self, *};
+
+use crate::{
+    credentials::*,
+    framing::*,
+    group::{config::CryptoConfig, *},
+    key_packages::{KeyPackage, KeyPackageInventory},
+};
+
+use super::utils::{generate_credential_bundle, generate_key_package, get_credential_bundle};
+
+/// Exposes a synchronous key store through the [`AsyncOpenMlsKeyStore`] trait.
+struct AsyncKeyStore<'a, KeyStore>(&'a KeyStore);
+
+impl<'a, KeyStore> AsyncOpenMlsKeyStore for AsyncKeyStore<'a, KeyStore>
+where
+    KeyStore: OpenMlsKeyStore,
+    KeyStore::Error: Send + Sync + 'static,
+{
+    type Error = KeyStore::Error;
+
+    fn read_epoch_keys<'b>(
+        &'b self,
+        identity: &'b [u8],
+        group_id: &'b [u8],
+        epoch: u64,
+    ) -> KeyStoreFuture<'b, Vec<Vec<u8>>> {
+        Box::pin(async move { self.0.read_epoch_keys(identity, group_id, epoch) })
+    }
+
+    fn store_epoch_keys<'b>(
+        &'b self,
+        identity: &'b [u8],
+        group_id: &'b [u8],
+        epoch: u64,
+        encryption_keys: Vec<Vec<u8>>,
+    ) -> KeyStoreFuture<'b, Result<(), Self::Error>> {
+        Box::pin(async move {
+            self.0
+                .store_epoch_keys(identity, group_id, epoch, &encryption_keys)
+        })
+    }
+
+    fn delete_epoch_keys<'b>(
+        &'b self,
+        identity: &'b [u8],
+        group_id: &'b [u8],
+        epoch: u64,
+    ) -> KeyStoreFuture<'b, Result<(), Self::Error>> {
+        Box::pin(async move { self.0.delete_epoch_keys(identity, group_id, epoch) })
+    }
+
+    fn store<'b>(&'b self, k: &'b [u8], v: Vec<u8>) -> KeyStoreFuture<'b, Result<(), Self::Error>> {
+        Box::pin(async move { self.0.store(k, &v) })
+    }
+
+    fn read<'b>(&'b self, k: &'b [u8]) -> KeyStoreFuture<'b, Option<Vec<u8>>> {
+        Box::pin(async move { self.0.read(k) })
+    }
+
+    fn delete<'b>(&'b self, k: &'b [u8]) -> KeyStoreFuture<'b, Result<(), Self::Error>> {
+        Box::pin(async move { self.0.delete(k) })
+    }
+}
+
+/// Drive a future to completion. The futures in this test never return
+/// `Poll::Pending`, so a waker that does nothing is sufficient.
+fn block_on<F: Future>(future: F) -> F::Output {
+    fn noop_raw_waker() -> RawWaker {
+        fn no_op(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+
+    // SAFETY: The waker ignores its data pointer.
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut context = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[apply(ciphersuites)]
+fn async_group_operations(ciphersuite: Ciphersuite) {
+    let alice_backend = &OpenMlsRustCrypto::default();
+    let bob_backend = &OpenMlsRustCrypto::default();
+    let alice_key_store = AsyncKeyStore(alice_backend.key_store());
+    let bob_key_store = AsyncKeyStore(bob_backend.key_store());
+
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        alice_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential = generate_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_key_package = generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_key_package_ref = bob_key_package
+        .hash_ref(bob_backend.crypto())
+        .expect("An unexpected error occurred.");
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .use_ratchet_tree_extension(true)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group = MlsGroup::new(
+        alice_backend,
+        &mls_group_config,
+        alice_credential.signature_key(),
+    )
+    .expect("An unexpected error occurred.");
+
+    // === Alice adds Bob ===
+    let (_commit, welcome, _group_info) = block_on(alice_group.add_members_async(
+        alice_backend,
+        &alice_key_store,
+        &[bob_key_package],
+    ))
+    .expect("Could not add member.");
+    block_on(alice_group.merge_pending_commit_async(alice_backend, &alice_key_store))
+        .expect("error merging pending commit");
+
+    let mut bob_group = block_on(MlsGroup::new_from_welcome_async(
+        bob_backend,
+        &bob_key_store,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    ))
+    .expect("error creating bob's group from welcome");
+
+    // Joining consumed the key package, which has been flushed to the key
+    // store.
+    assert!(bob_backend
+        .key_store()
+        .read::<KeyPackage>(bob_key_package_ref.as_slice())
+        .is_none());
+
+    // === Bob updates ===
+    let (commit, _welcome, _group_info) =
+        block_on(bob_group.self_update_async(bob_backend, &bob_key_store))
+            .expect("An unexpected error occurred.");
+
+    let processed_message = block_on(
+        alice_group.process_message_async(
+            alice_backend,
+            &alice_key_store,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        ),
+    )
+    .expect("An unexpected error occurred.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        block_on(alice_group.merge_staged_commit_async(
+            alice_backend,
+            &alice_key_store,
+            *staged_commit,
+        ))
+        .expect("An unexpected error occurred.");
+    } else {
+        panic!("Expected a commit.");
+    }
+    block_on(bob_group.merge_pending_commit_async(bob_backend, &bob_key_store))
+        .expect("error merging pending commit");
+
+    assert_eq!(alice_group.epoch(), bob_group.epoch());
+    assert_eq!(
+        alice_group
+            .export_secret(alice_backend, "test", &[], 32)
+            .expect("An unexpected error occurred."),
+        bob_group
+            .export_secret(bob_backend, "test", &[], 32)
+            .expect("An unexpected error occurred.")
+    );
+}
+
+#[apply(ciphersuites)]
+fn async_welcome_updates_key_package_inventory(ciphersuite: Ciphersuite) {
+    let alice_backend = &OpenMlsRustCrypto::default();
+    let bob_backend = &OpenMlsRustCrypto::default();
+    let bob_key_store = AsyncKeyStore(bob_backend.key_store());
+
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        alice_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential = generate_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_key_package = generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+    generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+    assert_eq!(KeyPackageInventory::load(bob_backend).count(ciphersuite), 2);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .use_ratchet_tree_extension(true)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        alice_backend,
+        &mls_group_config,
+        alice_credential.signature_key(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_backend, &[bob_key_package])
+        .expect("Could not add member.");
+
+    block_on(MlsGroup::new_from_welcome_async(
+        bob_backend,
+        &bob_key_store,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    ))
+    .expect("error creating bob's group from welcome");
+
+    // Only the consumed key package is no longer counted.
+    assert_eq!(KeyPackageInventory::load(bob_backend).count(ciphersuite), 1);
+}
+
+#[apply(ciphersuites)]
+fn async_welcome_keeps_last_resort_key_package(ciphersuite: Ciphersuite) {
+    let alice_backend = &OpenMlsRustCrypto::default();
+    let bob_backend = &OpenMlsRustCrypto::default();
+    let bob_key_store = AsyncKeyStore(bob_backend.key_store());
+
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        alice_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential_bundle = get_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let last_resort_key_package = KeyPackage::builder()
+        .last_resort()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            bob_backend,
+            &bob_credential_bundle,
+        )
+        .expect("Could not build key package.");
+    let last_resort_key_package_ref = last_resort_key_package
+        .hash_ref(bob_backend.crypto())
+        .expect("An unexpected error occurred.");
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .use_ratchet_tree_extension(true)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        alice_backend,
+        &mls_group_config,
+        alice_credential.signature_key(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_backend, &[last_resort_key_package.clone()])
+        .expect("Could not add member.");
+
+    block_on(MlsGroup::new_from_welcome_async(
+        bob_backend,
+        &bob_key_store,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    ))
+    .expect("error creating bob's group from welcome");
+
+    // The last resort key package is still stored and can be used again.
+    assert_eq!(
+        bob_backend
+            .key_store()
+            .read::<KeyPackage>(last_resort_key_package_ref.as_slice()),
+        Some(last_resort_key_package.clone())
+    );
+    assert!(last_resort_key_package.is_last_resort(bob_backend));
+}
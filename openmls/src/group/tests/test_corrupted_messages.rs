@@ -0,0 +1,235 @@
+//! This module makes sure that corrupted messages, Welcome messages and group
+//! infos are rejected without panicking.
+
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
+
+use rstest::*;
+use rstest_reuse::{self, *};
+
+use crate::{
+    credentials::*,
+    extensions::*,
+    framing::*,
+    group::{config::CryptoConfig, *},
+    messages::external_proposals::*,
+    test_utils::corrupted_messages::*,
+};
+
+use super::utils::{generate_credential_bundle, generate_key_package, get_credential_bundle};
+
+#[apply(ciphersuites_and_backends)]
+fn corrupted_messages_dont_panic(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential = generate_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_key_package = generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Handshake messages are sent as plaintext, application messages are
+    // always encrypted.
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(MIXED_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .use_ratchet_tree_extension(true)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group =
+        MlsGroup::new(backend, &mls_group_config, alice_credential.signature_key())
+            .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &[bob_key_package])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("error creating bob's group from welcome");
+
+    // Application message
+    let application_message = bob_group
+        .create_message(backend, b"Hello Alice")
+        .expect("An unexpected error occurred.")
+        .to_bytes()
+        .expect("An unexpected error occurred.");
+    assert!(
+        assert_no_panic_on_corrupted_message(&mut alice_group, backend, &application_message) > 0
+    );
+
+    // Commit with a path
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend)
+        .expect("An unexpected error occurred.");
+    let commit = commit.to_bytes().expect("An unexpected error occurred.");
+    assert!(assert_no_panic_on_corrupted_message(&mut alice_group, backend, &commit) > 0);
+
+    // The original commit is still valid
+    let processed_message = alice_group
+        .process_message(
+            backend,
+            MlsMessageIn::try_from_bytes(&commit).expect("An unexpected error occurred."),
+        )
+        .expect("An unexpected error occurred.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("An unexpected error occurred.");
+    } else {
+        panic!("Expected a commit.");
+    }
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // Proposal
+    let proposal = bob_group
+        .propose_self_update(backend, None)
+        .expect("An unexpected error occurred.")
+        .to_bytes()
+        .expect("An unexpected error occurred.");
+    assert!(assert_no_panic_on_corrupted_message(&mut alice_group, backend, &proposal) > 0);
+
+    // Welcome
+    let charlie_credential = generate_credential_bundle(
+        "Charlie".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let charlie_key_package = generate_key_package(
+        &[ciphersuite],
+        &charlie_credential,
+        Extensions::empty(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &[charlie_key_package])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.to_bytes().expect("An unexpected error occurred.");
+    assert!(assert_no_panic_on_corrupted_welcome(backend, &mls_group_config, &welcome) > 0);
+
+    // Group info for an external commit
+    let dave_credential_bundle = get_credential_bundle(
+        "Dave".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let group_info = alice_group
+        .export_group_info(backend, true)
+        .expect("An unexpected error occurred.");
+    assert!(
+        assert_no_panic_on_corrupted_group_info(
+            backend,
+            &mls_group_config,
+            &dave_credential_bundle,
+            &group_info
+                .to_bytes()
+                .expect("An unexpected error occurred."),
+        ) > 0
+    );
+
+    // External commit
+    let (_dave_group, external_commit) = MlsGroup::join_by_external_commit(
+        backend,
+        None,
+        group_info
+            .into_group_info()
+            .expect("Unexpected message type."),
+        &mls_group_config,
+        &[],
+        &dave_credential_bundle,
+    )
+    .expect("Error joining with an external commit.");
+    let external_commit = external_commit
+        .to_bytes()
+        .expect("An unexpected error occurred.");
+    assert!(assert_no_panic_on_corrupted_message(&mut alice_group, backend, &external_commit) > 0);
+
+    // External proposal from a new member
+    let eve_credential_bundle = get_credential_bundle(
+        "Eve".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let eve_key_package = generate_key_package(
+        &[ciphersuite],
+        eve_credential_bundle.credential(),
+        Extensions::empty(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let join_proposal = JoinProposal::new(
+        eve_key_package,
+        alice_group.group_id().clone(),
+        alice_group.epoch(),
+        &eve_credential_bundle,
+        backend,
+    )
+    .expect("Could not create external proposal.")
+    .to_bytes()
+    .expect("An unexpected error occurred.");
+    assert!(assert_no_panic_on_corrupted_message(&mut alice_group, backend, &join_proposal) > 0);
+
+    // External proposal from an external sender
+    let ds_credential_bundle = get_credential_bundle(
+        "DS".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .add_external_sender(
+            backend,
+            ExternalSender::new(ds_credential_bundle.credential().clone()),
+        )
+        .expect("Could not add external sender.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let remove_proposal = ExternalProposal::new_remove(
+        bob_group.own_leaf_index(),
+        alice_group.group_id().clone(),
+        alice_group.epoch(),
+        SenderExtensionIndex::new(0),
+        &ds_credential_bundle,
+        backend,
+    )
+    .expect("Could not create external proposal.")
+    .to_bytes()
+    .expect("An unexpected error occurred.");
+    assert!(assert_no_panic_on_corrupted_message(&mut alice_group, backend, &remove_proposal) > 0);
+}
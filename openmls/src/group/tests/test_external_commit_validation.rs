@@ -15,6 +15,7 @@ use crate::{
     framing::*,
     group::{config::CryptoConfig, errors::*, tests::utils::resign_external_commit, *},
     messages::proposals::*,
+    schedule::psk::{ExternalPsk, PreSharedKeyId, Psk},
 };
 
 use super::utils::{generate_credential_bundle, generate_key_package};
@@ -795,3 +796,72 @@ fn test_pure_ciphertest(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     // Would fail if handshake message processing did not distinguish external messages
     assert!(alice_group.process_message(backend, mls_message_in).is_ok());
 }
+
+// ValSem247: External Commit must cover an external PreSharedKey proposal if
+//            the group requires it.
+#[apply(ciphersuites_and_backends)]
+fn test_valsem247(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let ECValidationTestSetup {
+        mut alice_group,
+        bob_credential_bundle,
+        plaintext: _,
+        original_plaintext,
+    } = validation_test_setup(PURE_PLAINTEXT_WIRE_FORMAT_POLICY, ciphersuite, backend);
+
+    // Alice now requires external commits to contain an external PSK.
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .require_external_commit_psk(true)
+        .build();
+    alice_group.set_configuration(&mls_group_config);
+
+    // Negative case: the original external commit doesn't contain a PSK.
+    let err = alice_group
+        .process_message(backend, ProtocolMessage::from(original_plaintext))
+        .expect_err("Could process external commit without PSK.");
+    assert_eq!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::ExternalCommitValidation(
+            ExternalCommitValidationError::MissingExternalPsk
+        ))
+    );
+
+    // Positive case: Bob proves that he knows the PSK. Since Alice and Bob
+    // share a backend, the PSK is available to Alice as well.
+    let psk_id = PreSharedKeyId::new(
+        ciphersuite,
+        backend.rand(),
+        Psk::External(ExternalPsk::new(b"invite code".to_vec())),
+    )
+    .expect("An unexpected error occurred.");
+    psk_id
+        .write_to_key_store(backend, ciphersuite, &[7u8; 32])
+        .expect("Error writing PSK to the key store.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, true)
+        .unwrap()
+        .into_group_info()
+        .unwrap();
+
+    let (_bob_group, message) = MlsGroup::join_by_external_commit_with_psks(
+        backend,
+        None,
+        verifiable_group_info,
+        alice_group.configuration(),
+        &[],
+        &bob_credential_bundle,
+        &[psk_id],
+    )
+    .expect("Error initializing group externally.");
+
+    let processed_message = alice_group
+        .process_message(backend, MlsMessageIn::from(message))
+        .expect("Could not process external commit with PSK.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => panic!("Expected a StagedCommit."),
+    };
+    assert_eq!(staged_commit.psk_proposals().count(), 1);
+}
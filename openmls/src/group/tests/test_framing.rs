@@ -119,7 +119,7 @@ fn bad_padding(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
         let calculated_padding_length;
 
         let credential_bundle = CredentialBundle::new(
-            b"Alice".to_vec(),
+            "Alice".into(),
             CredentialType::Basic,
             ciphersuite.signature_algorithm(),
             backend,
@@ -129,6 +129,7 @@ fn bad_padding(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
         let sender = Sender::build_member(LeafNodeIndex::new(654));
 
         let group_context = GroupContext::new(
+            ProtocolVersion::default(),
             ciphersuite,
             GroupId::random(backend),
             1,
@@ -331,7 +332,7 @@ fn bad_padding(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
             backend,
             &mut message_secrets,
             SecretTreeLeafIndex(0),
-            &SenderRatchetConfiguration::default(),
+            &SenderRatchetConfiguration::default().into(),
             sender_data,
         );
 
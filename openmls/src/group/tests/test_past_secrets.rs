@@ -157,7 +157,10 @@ fn test_past_secrets_in_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
             assert_eq!(
                 err,
                 ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
-                    MessageDecryptionError::AeadError
+                    MessageDecryptionError::EpochTooOld {
+                        current_epoch: bob_group.epoch(),
+                        received_epoch: application_message.epoch(),
+                    }
                 ),)
             );
         }
@@ -1735,14 +1735,45 @@ fn test_valsem109(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
     )
     .unwrap();
 
-    // We first go the manual route
-    let update_proposal = bob_group
-        .propose_self_update(backend, Some(update_leaf_node))
-        .expect("error while creating update proposal");
+    // Bob can't propose an update that changes his identity.
+    let err = bob_group
+        .propose_self_update(backend, Some(update_leaf_node.clone()))
+        .expect_err("no error while trying to propose an update with differing identity");
+    assert_eq!(err, ProposeSelfUpdateError::IdentityMismatch);
+
+    // We therefore go the manual route and have Bob sign the proposal with
+    // his current credential.
+    let bob_credential_bundle: CredentialBundle = backend
+        .key_store()
+        .read(
+            &bob_group
+                .credential()
+                .unwrap()
+                .signature_key()
+                .tls_serialize_detached()
+                .unwrap(),
+        )
+        .unwrap();
+    let mut update_proposal: PublicMessage = bob_group
+        .group()
+        .create_update_proposal(
+            bob_group.framing_parameters(),
+            &bob_credential_bundle,
+            update_leaf_node,
+            backend,
+        )
+        .expect("error while creating update proposal")
+        .into();
+    update_proposal
+        .set_membership_tag(
+            backend,
+            bob_group.group().message_secrets().membership_key(),
+        )
+        .unwrap();
 
     // Have Alice process this proposal.
     if let ProcessedMessageContent::ProposalMessage(proposal) = alice_group
-        .process_message(backend, update_proposal.into_protocol_message().unwrap())
+        .process_message(backend, update_proposal.into())
         .expect("error processing proposal")
         .into_content()
     {
@@ -0,0 +1,200 @@
+//! This module tests the [`PublicGroup`] that follows the public state of a
+//! group from its handshake messages.
+
+use super::utils::{generate_credential_bundle, generate_key_package};
+use crate::{
+    credentials::*,
+    framing::*,
+    group::{config::CryptoConfig, errors::*, *},
+    test_utils::*,
+    *,
+};
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+/// Converts a handshake message sent by a member to the [`PublicMessage`]
+/// received by the Delivery Service.
+fn to_public_message(message: MlsMessageOut) -> PublicMessage {
+    MlsMessageIn::from(message)
+        .into_plaintext()
+        .expect("Expected a PublicMessage.")
+}
+
+/// Checks that the public group has the same public state as the group.
+fn assert_same_state(public_group: &PublicGroup, group: &MlsGroup) {
+    assert_eq!(public_group.epoch(), group.epoch());
+    assert_eq!(
+        public_group.members().collect::<Vec<_>>(),
+        group.members().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        public_group.export_ratchet_tree(),
+        group.export_ratchet_tree()
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn public_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    // Generate credential bundles
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    let bob_credential = generate_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Generate KeyPackages
+    let bob_key_package = generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Handshake messages have to be public for the Delivery Service to follow
+    // the group
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .use_ratchet_tree_extension(true)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &mls_group_config,
+        group_id,
+        alice_credential.signature_key(),
+    )
+    .expect("An unexpected error occurred.");
+
+    // === The Delivery Service follows the group from its group info ===
+    let group_info = alice_group
+        .export_group_info(backend, true)
+        .expect("Error exporting group info.")
+        .into_group_info()
+        .expect("Expected a group info.");
+    let mut public_group = PublicGroup::from_group_info(backend, group_info, None)
+        .expect("Error creating the public group.");
+    assert_same_state(&public_group, &alice_group);
+    assert!(public_group.group_info().is_some());
+
+    // === Alice adds Bob ===
+    let (commit, welcome, group_info) = alice_group
+        .add_members(backend, &[bob_key_package])
+        .expect("An unexpected error occurred.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let membership_diff = public_group
+        .process_message(backend, to_public_message(commit.clone()))
+        .expect("Error processing the commit.")
+        .expect("Expected a membership diff.");
+    assert_same_state(&public_group, &alice_group);
+    assert_eq!(membership_diff.added.len(), 1);
+    assert_eq!(membership_diff.added[0].identity, b"Bob".to_vec());
+    assert!(membership_diff.removed.is_empty());
+
+    // The group info of the previous epoch was dropped with the commit
+    assert!(public_group.group_info().is_none());
+    public_group
+        .set_group_info(
+            backend,
+            group_info
+                .expect("Expected a group info.")
+                .into_verifiable_group_info(),
+        )
+        .expect("Error setting the group info.");
+    assert!(public_group.group_info().is_some());
+
+    // An old commit is rejected
+    assert_eq!(
+        public_group.validate_message(backend, to_public_message(commit.clone())),
+        Err(PublicGroupError::ValidationError(
+            ValidationError::WrongEpoch
+        ))
+    );
+    assert_eq!(
+        public_group.process_message(backend, to_public_message(commit)),
+        Err(PublicGroupError::ValidationError(
+            ValidationError::WrongEpoch
+        ))
+    );
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error creating group from Welcome");
+
+    // === Bob proposes an update that Alice commits ===
+    let proposal = bob_group
+        .propose_self_update(backend, None)
+        .expect("Error proposing a self update.");
+    // Validating the proposal doesn't store it
+    assert_eq!(
+        public_group.validate_message(backend, to_public_message(proposal.clone())),
+        Ok(())
+    );
+    assert_eq!(
+        public_group.process_message(backend, to_public_message(proposal.clone())),
+        Ok(None)
+    );
+    let processed_message = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Error processing the proposal.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ProposalMessage(queued_proposal) => {
+            alice_group.store_pending_proposal(*queued_proposal)
+        }
+        _ => panic!("Expected a proposal."),
+    }
+
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend)
+        .expect("Error committing to the pending proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let membership_diff = public_group
+        .process_message(backend, to_public_message(commit))
+        .expect("Error processing the commit.")
+        .expect("Expected a membership diff.");
+    assert_same_state(&public_group, &alice_group);
+    assert!(membership_diff
+        .updated
+        .iter()
+        .any(|member| member.index == bob_group.own_leaf_index()));
+
+    // === Alice removes Bob ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &[bob_group.own_leaf_index()])
+        .expect("Could not remove members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let membership_diff = public_group
+        .process_message(backend, to_public_message(commit))
+        .expect("Error processing the commit.")
+        .expect("Expected a membership diff.");
+    assert_same_state(&public_group, &alice_group);
+    assert_eq!(membership_diff.removed.len(), 1);
+    assert_eq!(membership_diff.removed[0].identity, b"Bob".to_vec());
+    assert_eq!(public_group.members().count(), 1);
+}
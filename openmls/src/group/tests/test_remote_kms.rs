@@ -0,0 +1,360 @@
+//! This module tests that OpenMLS works with a crypto provider that keeps all
+//! private keys in a (simulated) remote KMS and only hands out key handles.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
+
+use openmls_rust_crypto::{MemoryKeyStore, RustCrypto};
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    key_store::OpenMlsKeyStore,
+    types::{
+        AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeCiphertext, HpkeConfig,
+        HpkeKeyPair, KemOutput, SignatureScheme,
+    },
+    OpenMlsCryptoProvider,
+};
+
+use rstest::*;
+use rstest_reuse::{self, *};
+
+use crate::{
+    credentials::*,
+    framing::*,
+    group::{config::CryptoConfig, *},
+};
+
+use super::utils::{generate_credential_bundle, generate_key_package};
+
+const HANDLE_PREFIX: &[u8] = b"kms-key-";
+
+/// A crypto provider that stores all private keys internally and only returns
+/// handles to them.
+#[derive(Default)]
+struct RemoteKms {
+    crypto: RustCrypto,
+    keys: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
+    next_handle: AtomicU64,
+    released: AtomicU64,
+}
+
+impl RemoteKms {
+    fn import(&self, private_key: Vec<u8>) -> Vec<u8> {
+        let mut handle = HANDLE_PREFIX.to_vec();
+        handle.extend_from_slice(
+            &self
+                .next_handle
+                .fetch_add(1, Ordering::SeqCst)
+                .to_be_bytes(),
+        );
+        self.keys
+            .write()
+            .unwrap()
+            .insert(handle.clone(), private_key);
+        handle
+    }
+
+    fn key(&self, handle: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.keys
+            .read()
+            .unwrap()
+            .get(handle)
+            .cloned()
+            .ok_or(CryptoError::CryptoLibraryError)
+    }
+}
+
+impl OpenMlsCrypto for RemoteKms {
+    fn supports(&self, ciphersuite: Ciphersuite) -> Result<(), CryptoError> {
+        self.crypto.supports(ciphersuite)
+    }
+
+    fn supported_ciphersuites(&self) -> Vec<Ciphersuite> {
+        self.crypto.supported_ciphersuites()
+    }
+
+    fn hkdf_extract(
+        &self,
+        hash_type: HashType,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.hkdf_extract(hash_type, salt, ikm)
+    }
+
+    fn hkdf_expand(
+        &self,
+        hash_type: HashType,
+        prk: &[u8],
+        info: &[u8],
+        okm_len: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.hkdf_expand(hash_type, prk, info, okm_len)
+    }
+
+    fn hash(&self, hash_type: HashType, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.hash(hash_type, data)
+    }
+
+    fn aead_encrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        data: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.aead_encrypt(alg, key, data, nonce, aad)
+    }
+
+    fn aead_decrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        ct_tag: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.aead_decrypt(alg, key, ct_tag, nonce, aad)
+    }
+
+    fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let (private_key, public_key) = self.crypto.signature_key_gen(alg)?;
+        Ok((self.import(private_key), public_key))
+    }
+
+    fn verify_signature(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CryptoError> {
+        self.crypto.verify_signature(alg, data, pk, signature)
+    }
+
+    fn sign(&self, alg: SignatureScheme, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.sign(alg, data, &self.key(key)?)
+    }
+
+    fn hpke_seal(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ptxt: &[u8],
+    ) -> HpkeCiphertext {
+        self.crypto.hpke_seal(config, pk_r, info, aad, ptxt)
+    }
+
+    fn hpke_open(
+        &self,
+        config: HpkeConfig,
+        input: &HpkeCiphertext,
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto
+            .hpke_open(config, input, &self.key(sk_r)?, info, aad)
+    }
+
+    fn hpke_setup_sender_and_export(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<(KemOutput, ExporterSecret), CryptoError> {
+        self.crypto.hpke_setup_sender_and_export(
+            config,
+            pk_r,
+            info,
+            exporter_context,
+            exporter_length,
+        )
+    }
+
+    fn hpke_setup_receiver_and_export(
+        &self,
+        config: HpkeConfig,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<ExporterSecret, CryptoError> {
+        self.crypto.hpke_setup_receiver_and_export(
+            config,
+            enc,
+            &self.key(sk_r)?,
+            info,
+            exporter_context,
+            exporter_length,
+        )
+    }
+
+    fn derive_hpke_keypair(&self, config: HpkeConfig, ikm: &[u8]) -> HpkeKeyPair {
+        let keypair = self.crypto.derive_hpke_keypair(config, ikm);
+        HpkeKeyPair {
+            private: self.import(keypair.private),
+            public: keypair.public,
+        }
+    }
+
+    fn release_private_key(&self, private_key: &[u8]) -> Result<(), CryptoError> {
+        self.keys
+            .write()
+            .unwrap()
+            .remove(private_key)
+            .ok_or(CryptoError::CryptoLibraryError)?;
+        self.released.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct RemoteKmsBackend {
+    kms: RemoteKms,
+    rand: RustCrypto,
+    key_store: MemoryKeyStore,
+}
+
+impl OpenMlsCryptoProvider for RemoteKmsBackend {
+    type CryptoProvider = RemoteKms;
+    type RandProvider = RustCrypto;
+    type KeyStoreProvider = MemoryKeyStore;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.kms
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.rand
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+#[apply(ciphersuites)]
+fn remote_kms_key_handles(ciphersuite: Ciphersuite) {
+    let alice_backend = &RemoteKmsBackend::default();
+    let bob_backend = &RemoteKmsBackend::default();
+
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        alice_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential = generate_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_key_package = generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        bob_backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    // The key store only holds a handle for the init key.
+    let init_key: Vec<u8> = bob_backend
+        .key_store()
+        .read(bob_key_package.hpke_init_key().as_slice())
+        .expect("The init key is missing.");
+    assert!(init_key.starts_with(HANDLE_PREFIX));
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .use_ratchet_tree_extension(true)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group = MlsGroup::new(
+        alice_backend,
+        &mls_group_config,
+        alice_credential.signature_key(),
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_backend, &[bob_key_package])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(alice_backend)
+        .expect("error merging pending commit");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        bob_backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("error creating bob's group from welcome");
+
+    // Joining released Bob's init key.
+    assert_eq!(bob_backend.kms.released.load(Ordering::SeqCst), 1);
+    assert!(!bob_backend.kms.keys.read().unwrap().contains_key(&init_key));
+
+    // Bob updates, which replaces his leaf key in both groups.
+    let released_before = alice_backend.kms.released.load(Ordering::SeqCst);
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(bob_backend)
+        .expect("An unexpected error occurred.");
+    let processed_message = alice_group
+        .process_message(
+            alice_backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("An unexpected error occurred.");
+    if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    {
+        alice_group
+            .merge_staged_commit(alice_backend, *staged_commit)
+            .expect("An unexpected error occurred.");
+    } else {
+        panic!("Expected a commit.");
+    }
+    bob_group
+        .merge_pending_commit(bob_backend)
+        .expect("error merging pending commit");
+
+    // Alice no longer needs the path key she used before Bob's update.
+    assert!(alice_backend.kms.released.load(Ordering::SeqCst) > released_before);
+
+    let message = alice_group
+        .create_message(alice_backend, b"Hello Bob")
+        .expect("An unexpected error occurred.");
+    let processed_message = bob_group
+        .process_message(
+            bob_backend,
+            message
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("An unexpected error occurred.");
+    if let ProcessedMessageContent::ApplicationMessage(application_message) =
+        processed_message.into_content()
+    {
+        assert_eq!(application_message.into_bytes(), b"Hello Bob");
+    } else {
+        panic!("Expected an application message.");
+    }
+}
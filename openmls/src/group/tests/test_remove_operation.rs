@@ -2,6 +2,7 @@
 
 use super::utils::{generate_credential_bundle, generate_key_package};
 use crate::{
+    binary_tree::LeafNodeIndex,
     credentials::*,
     framing::*,
     group::{config::CryptoConfig, *},
@@ -282,3 +283,115 @@ fn test_remove_operation_variants(ciphersuite: Ciphersuite, backend: &impl OpenM
         }
     }
 }
+
+// Tests the interpretation of all remove proposals of a commit at once, where
+// we are removed along with another member.
+#[apply(ciphersuites_and_backends)]
+fn test_remove_summary(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // Generate credential bundles
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential = generate_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let charlie_credential = generate_credential_bundle(
+        "Charlie".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Generate KeyPackages
+    let bob_key_package = generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let charlie_key_package = generate_key_package(
+        &[ciphersuite],
+        &charlie_credential,
+        Extensions::empty(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Define the MlsGroup configuration
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group and adds Bob & Charlie ===
+    let mut alice_group =
+        MlsGroup::new(backend, &mls_group_config, alice_credential.signature_key())
+            .expect("An unexpected error occurred.");
+    let (_message, welcome, _group_info) = alice_group
+        .add_members(backend, &[bob_key_package, charlie_key_package])
+        .expect("An unexpected error occurred.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree()),
+    )
+    .expect("Error creating group from Welcome");
+
+    // === Alice removes Bob & Charlie ===
+    let alice_index = alice_group.own_leaf_index();
+    let bob_index = bob_group.own_leaf_index();
+    let charlie_index = alice_group
+        .members()
+        .find(|member| member.identity == b"Charlie")
+        .expect("Charlie is not a member.")
+        .index;
+    let (message, _welcome, _group_info) = alice_group
+        .remove_members(backend, &[bob_index, charlie_index])
+        .expect("Could not remove members.");
+
+    // === Remove summary from Alice's perspective ===
+    let alice_staged_commit = alice_group.pending_commit().expect("No pending commit.");
+    let summary = RemoveSummary::new(alice_staged_commit, &alice_group)
+        .expect("An unexpected Error occurred.");
+    assert_eq!(summary.operations().len(), 2);
+    assert!(!summary.self_removed());
+    let mut others_removed: Vec<LeafNodeIndex> = summary.others_removed().collect();
+    others_removed.sort();
+    assert_eq!(others_removed, vec![bob_index, charlie_index]);
+
+    // === Remove summary from Bob's perspective ===
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+            let summary = RemoveSummary::new(&staged_commit, &bob_group)
+                .expect("An unexpected Error occurred.");
+            assert!(summary.self_removed());
+            assert!(!summary.we_left());
+            assert!(matches!(
+                summary.we_were_removed_by(),
+                Some(Sender::Member(member)) if *member == alice_index
+            ));
+            assert_eq!(
+                summary.others_removed().collect::<Vec<_>>(),
+                vec![charlie_index]
+            );
+        }
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+}
@@ -96,7 +96,7 @@ pub(crate) fn setup(config: TestSetupConfig, backend: &impl OpenMlsCryptoProvide
         for ciphersuite in client.ciphersuites {
             // Create a credential_bundle for the given ciphersuite.
             let credential_bundle = CredentialBundle::new(
-                client.name.as_bytes().to_vec(),
+                client.name.as_bytes().into(),
                 CredentialType::Basic,
                 SignatureScheme::from(ciphersuite),
                 backend,
@@ -329,7 +329,7 @@ pub(super) fn generate_credential_bundle(
     signature_scheme: SignatureScheme,
     backend: &impl OpenMlsCryptoProvider,
 ) -> Result<Credential, CredentialError> {
-    let cb = CredentialBundle::new(identity, credential_type, signature_scheme, backend)?;
+    let cb = CredentialBundle::new(identity.into(), credential_type, signature_scheme, backend)?;
     let credential = cb.credential().clone();
     backend
         .key_store()
@@ -379,7 +379,7 @@ pub(super) fn get_credential_bundle(
     signature_scheme: SignatureScheme,
     backend: &impl OpenMlsCryptoProvider,
 ) -> Result<CredentialBundle, CredentialError> {
-    let cb = CredentialBundle::new(identity, credential_type, signature_scheme, backend)?;
+    let cb = CredentialBundle::new(identity.into(), credential_type, signature_scheme, backend)?;
     let credential = cb.credential().clone();
     backend
         .key_store()
@@ -0,0 +1,728 @@
+//! # JavaScript bindings
+//!
+//! This module provides [`wasm_bindgen`] wrappers around [`MlsGroup`], such
+//! that web applications can use OpenMLS from JavaScript and TypeScript.
+//!
+//! * A [`Client`] holds a basic credential and creates, joins and loads
+//!   [`Group`]s. The crypto provider is [`OpenMlsRustCrypto`]. All keys are
+//!   kept in a key store that is implemented in JavaScript, see [`KeyStore`].
+//! * MLS messages, key packages and Welcomes are passed as `Uint8Array`s
+//!   holding their TLS serialization. Key packages are passed as plain
+//!   `KeyPackage` structures, all other messages as `MLSMessage`s.
+//! * Operations that access the key store return `Promise`s. They use the
+//!   async variants of the [`MlsGroup`] operations, so all key store accesses
+//!   of an operation are batched.
+//! * Errors are thrown as JavaScript `Error`s with the message of the
+//!   corresponding OpenMLS error.
+//!
+//! Received proposals are stored in the group and received commits are merged
+//! right away. Groups are created with the ratchet tree extension, such that
+//! Welcomes can be processed without an out-of-band ratchet tree.
+//!
+//! This module is only available with the `js` feature.
+
+use std::{cell::RefCell, future::Future, rc::Rc};
+
+use js_sys::{Array, Promise, Uint8Array};
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::{
+    async_key_store::{AsyncOpenMlsKeyStore, KeyStoreFuture},
+    key_store::{FromKeyStoreValue, ToKeyStoreValue},
+    types::Ciphersuite,
+};
+use send_wrapper::SendWrapper;
+use thiserror::Error;
+use tls_codec::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+
+use crate::{
+    credentials::{Credential, CredentialBundle, CredentialType},
+    error::error_codes,
+    framing::{MlsMessageIn, MlsMessageOut, ProcessedMessageContent, Sender},
+    group::{
+        config::CryptoConfig, mls_group::asynchronous::BufferedBackend, GroupInfo, MlsGroup,
+        MlsGroupConfig,
+    },
+    key_packages::KeyPackage,
+};
+
+#[wasm_bindgen(typescript_custom_section)]
+const KEY_STORE_INTERFACE: &str = r#"
+/**
+ * A key store whose functions return promises, e.g. one that is backed by
+ * IndexedDB. Keys and values are opaque byte strings.
+ */
+export interface KeyStore {
+    read(key: Uint8Array): Promise<Uint8Array | undefined>;
+    store(key: Uint8Array, value: Uint8Array): Promise<void>;
+    delete(key: Uint8Array): Promise<void>;
+    readEpochKeys(identity: Uint8Array, groupId: Uint8Array, epoch: bigint): Promise<Uint8Array[]>;
+    storeEpochKeys(identity: Uint8Array, groupId: Uint8Array, epoch: bigint, encryptionKeys: Uint8Array[]): Promise<void>;
+    deleteEpochKeys(identity: Uint8Array, groupId: Uint8Array, epoch: bigint): Promise<void>;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A JavaScript object that implements the `KeyStore` interface. Its
+    /// functions correspond to those of [`AsyncOpenMlsKeyStore`], but return
+    /// `Promise`s.
+    #[wasm_bindgen(typescript_type = "KeyStore")]
+    pub type KeyStore;
+
+    #[wasm_bindgen(method, catch)]
+    fn read(this: &KeyStore, key: &Uint8Array) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn store(this: &KeyStore, key: &Uint8Array, value: &Uint8Array) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn delete(this: &KeyStore, key: &Uint8Array) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = readEpochKeys)]
+    fn read_epoch_keys(
+        this: &KeyStore,
+        identity: &Uint8Array,
+        group_id: &Uint8Array,
+        epoch: u64,
+    ) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = storeEpochKeys)]
+    fn store_epoch_keys(
+        this: &KeyStore,
+        identity: &Uint8Array,
+        group_id: &Uint8Array,
+        epoch: u64,
+        encryption_keys: &Array,
+    ) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = deleteEpochKeys)]
+    fn delete_epoch_keys(
+        this: &KeyStore,
+        identity: &Uint8Array,
+        group_id: &Uint8Array,
+        epoch: u64,
+    ) -> Result<Promise, JsValue>;
+}
+
+/// JavaScript key store error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum JsKeyStoreError {
+    /// The key store threw an exception or rejected a promise.
+    #[error("The key store failed: {0}")]
+    Rejected(String),
+}
+
+error_codes!(JsKeyStoreError: 6600 {
+    Rejected = 1,
+});
+
+impl From<JsValue> for JsKeyStoreError {
+    fn from(value: JsValue) -> Self {
+        Self::Rejected(value.as_string().unwrap_or_else(|| format!("{value:?}")))
+    }
+}
+
+/// An [`AsyncOpenMlsKeyStore`] that forwards all calls to a JavaScript
+/// [`KeyStore`]. Keys and values are copied into new `Uint8Array`s, such that
+/// the key store may keep them.
+///
+/// JavaScript values can't be sent to other threads. The key store must
+/// therefore only be used on the thread it was created on, which is always
+/// the case in a browser without web workers sharing memory.
+pub struct JsKeyStore(SendWrapper<KeyStore>);
+
+impl JsKeyStore {
+    /// Wraps the JavaScript `key_store`.
+    pub fn new(key_store: KeyStore) -> Self {
+        Self(SendWrapper::new(key_store))
+    }
+}
+
+/// Returns a future that resolves the `promise` and converts its value.
+fn resolve<'a, T: 'a>(
+    promise: Result<Promise, JsValue>,
+    convert: impl FnOnce(JsValue) -> T + 'a,
+) -> KeyStoreFuture<'a, Result<T, JsKeyStoreError>> {
+    Box::pin(SendWrapper::new(async move {
+        let value = JsFuture::from(promise?).await?;
+        Ok(convert(value))
+    }))
+}
+
+impl AsyncOpenMlsKeyStore for JsKeyStore {
+    type Error = JsKeyStoreError;
+
+    fn read_epoch_keys<'a>(
+        &'a self,
+        identity: &'a [u8],
+        group_id: &'a [u8],
+        epoch: u64,
+    ) -> KeyStoreFuture<'a, Vec<Vec<u8>>> {
+        let encryption_keys = resolve(
+            self.0
+                .read_epoch_keys(&identity.into(), &group_id.into(), epoch),
+            |value| {
+                Array::from(&value)
+                    .iter()
+                    .map(|key| Uint8Array::new(&key).to_vec())
+                    .collect()
+            },
+        );
+        Box::pin(async move { encryption_keys.await.unwrap_or_default() })
+    }
+
+    fn store_epoch_keys<'a>(
+        &'a self,
+        identity: &'a [u8],
+        group_id: &'a [u8],
+        epoch: u64,
+        encryption_keys: Vec<Vec<u8>>,
+    ) -> KeyStoreFuture<'a, Result<(), Self::Error>> {
+        let encryption_keys = encryption_keys
+            .iter()
+            .map(|key| Uint8Array::from(key.as_slice()))
+            .collect::<Array>();
+        resolve(
+            self.0
+                .store_epoch_keys(&identity.into(), &group_id.into(), epoch, &encryption_keys),
+            |_| (),
+        )
+    }
+
+    fn delete_epoch_keys<'a>(
+        &'a self,
+        identity: &'a [u8],
+        group_id: &'a [u8],
+        epoch: u64,
+    ) -> KeyStoreFuture<'a, Result<(), Self::Error>> {
+        resolve(
+            self.0
+                .delete_epoch_keys(&identity.into(), &group_id.into(), epoch),
+            |_| (),
+        )
+    }
+
+    fn store<'a>(&'a self, k: &'a [u8], v: Vec<u8>) -> KeyStoreFuture<'a, Result<(), Self::Error>> {
+        resolve(self.0.store(&k.into(), &v.as_slice().into()), |_| ())
+    }
+
+    fn read<'a>(&'a self, k: &'a [u8]) -> KeyStoreFuture<'a, Option<Vec<u8>>> {
+        let value = resolve(self.0.read(&k.into()), |value| {
+            (!value.is_undefined() && !value.is_null()).then(|| Uint8Array::new(&value).to_vec())
+        });
+        Box::pin(async move { value.await.ok().flatten() })
+    }
+
+    fn delete<'a>(&'a self, k: &'a [u8]) -> KeyStoreFuture<'a, Result<(), Self::Error>> {
+        resolve(self.0.delete(&k.into()), |_| ())
+    }
+}
+
+/// Converts an error into a JavaScript `Error` with the error's message.
+fn js_error(error: impl std::fmt::Display) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+/// Returns the TLS serialization of the MLS message.
+fn message_bytes(message: &MlsMessageOut) -> Result<Vec<u8>, JsError> {
+    message.to_bytes().map_err(js_error)
+}
+
+/// Runs the `future` as a `Promise`.
+fn promise<T: Into<JsValue>>(
+    future: impl Future<Output = Result<T, JsError>> + 'static,
+) -> Promise {
+    future_to_promise(async move { future.await.map(Into::into).map_err(JsValue::from) })
+}
+
+/// The state of a [`Client`] that is shared with its [`Group`]s.
+struct ClientState {
+    backend: OpenMlsRustCrypto,
+    key_store: JsKeyStore,
+    credential_bundle: CredentialBundle,
+    mls_group_config: MlsGroupConfig,
+}
+
+/// A client with a basic credential and a JavaScript [`KeyStore`].
+#[wasm_bindgen]
+pub struct Client {
+    state: Rc<ClientState>,
+}
+
+#[wasm_bindgen]
+impl Client {
+    /// Creates a client with a fresh basic credential for the `identity` and
+    /// the ciphersuite with the given value. The credential bundle is stored
+    /// in the `key_store` under the client's signature key.
+    pub async fn create(
+        identity: Vec<u8>,
+        ciphersuite: u16,
+        key_store: KeyStore,
+    ) -> Result<Client, JsError> {
+        let ciphersuite = Ciphersuite::try_from(ciphersuite).map_err(js_error)?;
+        let backend = OpenMlsRustCrypto::default();
+        let key_store = JsKeyStore::new(key_store);
+        let credential_bundle = CredentialBundle::new(
+            identity.into(),
+            CredentialType::Basic,
+            ciphersuite.signature_algorithm(),
+            &backend,
+        )
+        .map_err(js_error)?;
+        key_store
+            .store(
+                &signature_key_index(credential_bundle.credential())?,
+                credential_bundle.to_key_store_value().map_err(js_error)?,
+            )
+            .await
+            .map_err(js_error)?;
+        Ok(Self::with_credential_bundle(
+            backend,
+            key_store,
+            ciphersuite,
+            credential_bundle,
+        ))
+    }
+
+    /// Loads a client whose credential bundle was stored in the `key_store`
+    /// by [`Client::create()`]. The `signature_key` is the TLS serialized
+    /// signature key of the client, see [`Client::signature_key()`].
+    pub async fn load(
+        signature_key: Vec<u8>,
+        ciphersuite: u16,
+        key_store: KeyStore,
+    ) -> Result<Client, JsError> {
+        let ciphersuite = Ciphersuite::try_from(ciphersuite).map_err(js_error)?;
+        let key_store = JsKeyStore::new(key_store);
+        let value = key_store
+            .read(&signature_key)
+            .await
+            .ok_or_else(|| JsError::new("The credential bundle is not in the key store."))?;
+        let credential_bundle = CredentialBundle::from_key_store_value(&value).map_err(js_error)?;
+        Ok(Self::with_credential_bundle(
+            OpenMlsRustCrypto::default(),
+            key_store,
+            ciphersuite,
+            credential_bundle,
+        ))
+    }
+
+    fn with_credential_bundle(
+        backend: OpenMlsRustCrypto,
+        key_store: JsKeyStore,
+        ciphersuite: Ciphersuite,
+        credential_bundle: CredentialBundle,
+    ) -> Self {
+        let mls_group_config = MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .use_ratchet_tree_extension(true)
+            .build();
+        Self {
+            state: Rc::new(ClientState {
+                backend,
+                key_store,
+                credential_bundle,
+                mls_group_config,
+            }),
+        }
+    }
+
+    /// Returns the identity of the client.
+    pub fn identity(&self) -> Vec<u8> {
+        self.state
+            .credential_bundle
+            .credential()
+            .identity()
+            .as_slice()
+            .to_vec()
+    }
+
+    /// Returns the TLS serialized signature key of the client, which is
+    /// needed to load the client with [`Client::load()`].
+    #[wasm_bindgen(js_name = signatureKey)]
+    pub fn signature_key(&self) -> Result<Vec<u8>, JsError> {
+        signature_key_index(self.state.credential_bundle.credential())
+    }
+
+    /// Creates a key package and stores its private keys in the key store.
+    /// The promise resolves to the TLS serialized `KeyPackage`.
+    #[wasm_bindgen(js_name = createKeyPackage)]
+    pub fn create_key_package(&self) -> Promise {
+        let state = self.state.clone();
+        promise(async move {
+            let buffered_backend = BufferedBackend::new(&state.backend);
+            let key_package = KeyPackage::builder()
+                .build(
+                    *state.mls_group_config.crypto_config(),
+                    &buffered_backend,
+                    &state.credential_bundle,
+                )
+                .map_err(js_error)?;
+            buffered_backend
+                .flush(&state.key_store)
+                .await
+                .map_err(js_error)?;
+            key_package
+                .tls_serialize_detached()
+                .map(|bytes| Uint8Array::from(bytes.as_slice()))
+                .map_err(js_error)
+        })
+    }
+
+    /// Creates a new group with the client as the only member. The promise
+    /// resolves to the [`Group`].
+    #[wasm_bindgen(js_name = createGroup)]
+    pub fn create_group(&self) -> Promise {
+        let state = self.state.clone();
+        promise(async move {
+            let mls_group = MlsGroup::new_async(
+                &state.backend,
+                &state.key_store,
+                &state.mls_group_config,
+                state.credential_bundle.credential().signature_key(),
+            )
+            .await
+            .map_err(js_error)?;
+            Ok(Group::new(state, mls_group))
+        })
+    }
+
+    /// Joins a group from a TLS serialized Welcome message. The promise
+    /// resolves to the [`Group`].
+    #[wasm_bindgen(js_name = joinGroup)]
+    pub fn join_group(&self, welcome: Vec<u8>) -> Promise {
+        let state = self.state.clone();
+        promise(async move {
+            let welcome = MlsMessageIn::try_from_bytes(&welcome)
+                .map_err(js_error)?
+                .into_welcome()
+                .ok_or_else(|| JsError::new("The message is not a Welcome."))?;
+            let mls_group = MlsGroup::new_from_welcome_async(
+                &state.backend,
+                &state.key_store,
+                &state.mls_group_config,
+                welcome,
+                None,
+            )
+            .await
+            .map_err(js_error)?;
+            Ok(Group::new(state, mls_group))
+        })
+    }
+
+    /// Loads a group that was saved with [`Group::save()`].
+    #[wasm_bindgen(js_name = loadGroup)]
+    pub fn load_group(&self, saved: Vec<u8>) -> Result<Group, JsError> {
+        let mls_group = MlsGroup::load(saved.as_slice()).map_err(js_error)?;
+        Ok(Group::new(self.state.clone(), mls_group))
+    }
+}
+
+/// Returns the index of a credential bundle in the key store.
+fn signature_key_index(credential: &Credential) -> Result<Vec<u8>, JsError> {
+    credential
+        .signature_key()
+        .tls_serialize_detached()
+        .map_err(js_error)
+}
+
+/// A group of a [`Client`].
+///
+/// Only one operation can run on a group at a time. Operations that are
+/// started while another one is running fail.
+#[wasm_bindgen]
+pub struct Group {
+    state: Rc<ClientState>,
+    mls_group: Rc<RefCell<MlsGroup>>,
+}
+
+impl Group {
+    fn new(state: Rc<ClientState>, mls_group: MlsGroup) -> Self {
+        Self {
+            state,
+            mls_group: Rc::new(RefCell::new(mls_group)),
+        }
+    }
+
+    /// Runs the `operation` on the group as a `Promise`.
+    fn run<T, F>(
+        &self,
+        operation: impl FnOnce(Rc<ClientState>, Rc<RefCell<MlsGroup>>) -> F,
+    ) -> Promise
+    where
+        T: Into<JsValue>,
+        F: Future<Output = Result<T, JsError>> + 'static,
+    {
+        promise(operation(self.state.clone(), self.mls_group.clone()))
+    }
+}
+
+/// Borrows the group for an operation.
+fn borrow(mls_group: &RefCell<MlsGroup>) -> Result<std::cell::RefMut<'_, MlsGroup>, JsError> {
+    mls_group
+        .try_borrow_mut()
+        .map_err(|_| JsError::new("Another operation is running on the group."))
+}
+
+#[wasm_bindgen]
+impl Group {
+    /// Returns the ID of the group.
+    #[wasm_bindgen(js_name = groupId)]
+    pub fn group_id(&self) -> Result<Vec<u8>, JsError> {
+        Ok(borrow(&self.mls_group)?.group_id().to_vec())
+    }
+
+    /// Returns the current epoch of the group.
+    pub fn epoch(&self) -> Result<u64, JsError> {
+        Ok(borrow(&self.mls_group)?.epoch().as_u64())
+    }
+
+    /// Returns the leaf index of the client in the group.
+    #[wasm_bindgen(js_name = ownIndex)]
+    pub fn own_index(&self) -> Result<u32, JsError> {
+        Ok(borrow(&self.mls_group)?.own_leaf_index().u32())
+    }
+
+    /// Returns the [`GroupMember`]s of the group.
+    pub fn members(&self) -> Result<Array, JsError> {
+        Ok(borrow(&self.mls_group)?
+            .members()
+            .map(|member| {
+                JsValue::from(GroupMember {
+                    index: member.index.u32(),
+                    identity: member.identity.as_slice().to_vec(),
+                    signature_key: member.signature_key,
+                })
+            })
+            .collect())
+    }
+
+    /// Commits to adding the members with the given TLS serialized key
+    /// packages. The promise resolves to the [`CommitOutput`]. The commit has
+    /// to be merged with [`Group::merge_pending_commit()`].
+    #[wasm_bindgen(js_name = addMembers)]
+    pub fn add_members(&self, key_packages: Array) -> Promise {
+        self.run(|state, mls_group| async move {
+            let key_packages = key_packages
+                .iter()
+                .map(|bytes| {
+                    KeyPackage::tls_deserialize(&mut Uint8Array::new(&bytes).to_vec().as_slice())
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(js_error)?;
+            let (commit, welcome, group_info) = borrow(&mls_group)?
+                .add_members_async(&state.backend, &state.key_store, &key_packages)
+                .await
+                .map_err(js_error)?;
+            CommitOutput::new(&commit, Some(&welcome), group_info)
+        })
+    }
+
+    /// Commits to an update of the own leaf. The promise resolves to the
+    /// [`CommitOutput`]. The commit has to be merged with
+    /// [`Group::merge_pending_commit()`].
+    #[wasm_bindgen(js_name = selfUpdate)]
+    pub fn self_update(&self) -> Promise {
+        self.run(|state, mls_group| async move {
+            let (commit, welcome, group_info) = borrow(&mls_group)?
+                .self_update_async(&state.backend, &state.key_store)
+                .await
+                .map_err(js_error)?;
+            CommitOutput::new(&commit, welcome.as_ref(), group_info)
+        })
+    }
+
+    /// Merges the pending commit of the client, e.g. after the delivery
+    /// service accepted it.
+    #[wasm_bindgen(js_name = mergePendingCommit)]
+    pub fn merge_pending_commit(&self) -> Promise {
+        self.run(|state, mls_group| async move {
+            borrow(&mls_group)?
+                .merge_pending_commit_async(&state.backend, &state.key_store)
+                .await
+                .map_err(js_error)?;
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// Encrypts an application message. The promise resolves to the TLS
+    /// serialized MLS message.
+    pub fn encrypt(&self, message: Vec<u8>) -> Promise {
+        self.run(|state, mls_group| async move {
+            let message = borrow(&mls_group)?
+                .create_message_async(&state.backend, &state.key_store, &message)
+                .await
+                .map_err(js_error)?;
+            Ok(Uint8Array::from(message_bytes(&message)?.as_slice()))
+        })
+    }
+
+    /// Processes a TLS serialized MLS message. Proposals are stored and
+    /// commits are merged. The promise resolves to the [`ReceivedMessage`].
+    #[wasm_bindgen(js_name = processMessage)]
+    pub fn process_message(&self, message: Vec<u8>) -> Promise {
+        self.run(|state, mls_group| async move {
+            let message = MlsMessageIn::try_from_bytes(&message)
+                .map_err(js_error)?
+                .into_protocol_message()
+                .ok_or_else(|| JsError::new("The message is not a protocol message."))?;
+            let mut mls_group = borrow(&mls_group)?;
+            let processed_message = mls_group
+                .process_message_async(&state.backend, &state.key_store, message)
+                .await
+                .map_err(js_error)?;
+            let sender = match processed_message.sender() {
+                Sender::Member(index) => Some(index.u32()),
+                _ => None,
+            };
+            let epoch = processed_message.epoch().as_u64();
+            let (kind, application_data) = match processed_message.into_content() {
+                ProcessedMessageContent::ApplicationMessage(application_message) => {
+                    ("application", Some(application_message.into_bytes()))
+                }
+                ProcessedMessageContent::ProposalMessage(queued_proposal)
+                | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal) => {
+                    mls_group.store_pending_proposal(*queued_proposal);
+                    ("proposal", None)
+                }
+                ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                    mls_group
+                        .merge_staged_commit_async(&state.backend, &state.key_store, *staged_commit)
+                        .await
+                        .map_err(js_error)?;
+                    ("commit", None)
+                }
+            };
+            Ok(ReceivedMessage {
+                kind,
+                sender,
+                epoch,
+                application_data,
+            })
+        })
+    }
+
+    /// Returns the JSON serialized state of the group, which can be loaded
+    /// again with [`Client::load_group()`]. The keys of the group stay in the
+    /// key store.
+    pub fn save(&self) -> Result<Vec<u8>, JsError> {
+        let mut saved = Vec::new();
+        borrow(&self.mls_group)?
+            .save(&mut saved)
+            .map_err(js_error)?;
+        Ok(saved)
+    }
+}
+
+/// A member of a [`Group`].
+#[wasm_bindgen]
+pub struct GroupMember {
+    index: u32,
+    identity: Vec<u8>,
+    signature_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl GroupMember {
+    /// Returns the leaf index of the member.
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the identity of the member.
+    #[wasm_bindgen(getter)]
+    pub fn identity(&self) -> Vec<u8> {
+        self.identity.clone()
+    }
+
+    /// Returns the signature key of the member.
+    #[wasm_bindgen(getter, js_name = signatureKey)]
+    pub fn signature_key(&self) -> Vec<u8> {
+        self.signature_key.clone()
+    }
+}
+
+/// The TLS serialized messages that result from a commit.
+#[wasm_bindgen]
+pub struct CommitOutput {
+    commit: Vec<u8>,
+    welcome: Option<Vec<u8>>,
+    group_info: Option<Vec<u8>>,
+}
+
+impl CommitOutput {
+    fn new(
+        commit: &MlsMessageOut,
+        welcome: Option<&MlsMessageOut>,
+        group_info: Option<GroupInfo>,
+    ) -> Result<Self, JsError> {
+        Ok(Self {
+            commit: message_bytes(commit)?,
+            welcome: welcome.map(message_bytes).transpose()?,
+            group_info: group_info
+                .map(|group_info| message_bytes(&group_info.into()))
+                .transpose()?,
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl CommitOutput {
+    /// Returns the commit, which has to be sent to the group.
+    #[wasm_bindgen(getter)]
+    pub fn commit(&self) -> Vec<u8> {
+        self.commit.clone()
+    }
+
+    /// Returns the Welcome for new members, if the commit adds members.
+    #[wasm_bindgen(getter)]
+    pub fn welcome(&self) -> Option<Vec<u8>> {
+        self.welcome.clone()
+    }
+
+    /// Returns the GroupInfo of the new epoch, if the group publishes it.
+    #[wasm_bindgen(getter, js_name = groupInfo)]
+    pub fn group_info(&self) -> Option<Vec<u8>> {
+        self.group_info.clone()
+    }
+}
+
+/// The result of [`Group::process_message()`].
+#[wasm_bindgen]
+pub struct ReceivedMessage {
+    kind: &'static str,
+    sender: Option<u32>,
+    epoch: u64,
+    application_data: Option<Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl ReceivedMessage {
+    /// Returns the kind of the message: `"application"`, `"proposal"` or
+    /// `"commit"`.
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        self.kind.to_owned()
+    }
+
+    /// Returns the leaf index of the sender if it is a member of the group.
+    #[wasm_bindgen(getter)]
+    pub fn sender(&self) -> Option<u32> {
+        self.sender
+    }
+
+    /// Returns the epoch in which the message was sent.
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Returns the decrypted content of an application message.
+    #[wasm_bindgen(getter, js_name = applicationData)]
+    pub fn application_data(&self) -> Option<Vec<u8>> {
+        self.application_data.clone()
+    }
+}
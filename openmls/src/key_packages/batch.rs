@@ -0,0 +1,167 @@
+//! This module contains the [`KeyPackageBatch`], a container that clients and
+//! directory servers use to exchange several key packages at once.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{errors::KeyPackageVerifyError, KeyPackage};
+
+/// How a [`KeyPackage`] in a [`KeyPackageBatch`] may be used.
+///
+/// ```text
+/// enum {
+///     one_time(0),
+///     last_resort(1),
+///     (255)
+/// } KeyPackageUsage;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+#[repr(u8)]
+pub enum KeyPackageUsage {
+    /// The key package must only be used once.
+    OneTime = 0,
+    /// The key package may be used again if no other key package is
+    /// available.
+    LastResort = 1,
+}
+
+/// A [`KeyPackage`] in a [`KeyPackageBatch`] together with its
+/// [`KeyPackageUsage`].
+///
+/// ```text
+/// struct {
+///     KeyPackage key_package;
+///     KeyPackageUsage usage;
+/// } KeyPackageBatchEntry;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct KeyPackageBatchEntry {
+    key_package: KeyPackage,
+    usage: KeyPackageUsage,
+}
+
+impl KeyPackageBatchEntry {
+    /// Returns the key package.
+    pub fn key_package(&self) -> &KeyPackage {
+        &self.key_package
+    }
+
+    /// Returns the ciphersuite of the key package.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.key_package.ciphersuite()
+    }
+
+    /// Returns how the key package may be used.
+    pub fn usage(&self) -> KeyPackageUsage {
+        self.usage
+    }
+
+    /// Returns `true` if the key package is a last resort key package.
+    pub fn is_last_resort(&self) -> bool {
+        self.usage == KeyPackageUsage::LastResort
+    }
+}
+
+/// A batch of key packages, e.g. uploaded by a client to a directory server
+/// in one go.
+///
+/// The batch carries the time it was created at in seconds since the UNIX
+/// epoch, and flags the key packages that may be used as a last resort. It
+/// can be encoded with the TLS codec as well as with serde, e.g. as JSON.
+///
+/// ```text
+/// struct {
+///     uint64 created_at;
+///     KeyPackageBatchEntry entries<V>;
+/// } KeyPackageBatch;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct KeyPackageBatch {
+    created_at: u64,
+    entries: Vec<KeyPackageBatchEntry>,
+}
+
+impl KeyPackageBatch {
+    /// Create a new [`KeyPackageBatch`] created now from one time
+    /// `key_packages`, e.g. the ones returned by
+    /// [`KeyPackageBuilder::build_batch()`](super::KeyPackageBuilder::build_batch()).
+    pub fn new(key_packages: Vec<KeyPackage>) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("SystemTime before UNIX EPOCH!")
+            .as_secs();
+        Self {
+            created_at,
+            entries: key_packages
+                .into_iter()
+                .map(|key_package| KeyPackageBatchEntry {
+                    key_package,
+                    usage: KeyPackageUsage::OneTime,
+                })
+                .collect(),
+        }
+    }
+
+    /// Add a `key_package` with the given `usage` to the batch.
+    pub fn push(&mut self, key_package: KeyPackage, usage: KeyPackageUsage) {
+        self.entries
+            .push(KeyPackageBatchEntry { key_package, usage })
+    }
+
+    /// Returns the time the batch was created at in seconds since the UNIX
+    /// epoch.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the entries of the batch.
+    pub fn entries(&self) -> &[KeyPackageBatchEntry] {
+        &self.entries
+    }
+
+    /// Returns the number of key packages in the batch.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the batch doesn't contain any key packages.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the entries of the batch with the given
+    /// `ciphersuite`.
+    pub fn entries_for(
+        &self,
+        ciphersuite: Ciphersuite,
+    ) -> impl Iterator<Item = &KeyPackageBatchEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.ciphersuite() == ciphersuite)
+    }
+
+    /// Verify all key packages in the batch with [`KeyPackage::verify()`].
+    /// Returns the first error encountered.
+    pub fn verify(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<(), KeyPackageVerifyError> {
+        self.entries
+            .iter()
+            .try_for_each(|entry| entry.key_package.verify(backend))
+    }
+
+    /// Consumes the batch and returns its entries.
+    pub fn into_entries(self) -> Vec<KeyPackageBatchEntry> {
+        self.entries
+    }
+}
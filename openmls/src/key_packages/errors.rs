@@ -4,7 +4,10 @@
 
 use thiserror::Error;
 
-use crate::{ciphersuite::signable::SignatureError, error::LibraryError};
+use crate::{
+    ciphersuite::signable::SignatureError,
+    error::{error_codes, LibraryError},
+};
 
 /// KeyPackage verify error
 #[derive(Error, Debug, PartialEq, Clone)]
@@ -26,6 +29,14 @@ pub enum KeyPackageVerifyError {
     InvalidSignature,
 }
 
+error_codes!(KeyPackageVerifyError: 1200 {
+    LibraryError = 1,
+    InvalidLifetime = 2,
+    MissingLifetime = 3,
+    UnsupportedExtension = 4,
+    InvalidSignature = 5,
+});
+
 /// KeyPackage extension support error
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum KeyPackageExtensionSupportError {
@@ -34,6 +45,10 @@ pub enum KeyPackageExtensionSupportError {
     UnsupportedExtension,
 }
 
+error_codes!(KeyPackageExtensionSupportError: 1300 {
+    UnsupportedExtension = 1,
+});
+
 /// KeyPackage new error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum KeyPackageNewError<KeyStoreError> {
@@ -49,4 +64,33 @@ pub enum KeyPackageNewError<KeyStoreError> {
     /// See [`SignatureError`] for more details.
     #[error(transparent)]
     SignatureError(#[from] SignatureError),
+    /// The ciphersuite is not approved in FIPS mode.
+    #[cfg(feature = "fips")]
+    #[error("The ciphersuite is not approved in FIPS mode.")]
+    NotFipsApproved,
 }
+
+error_codes!(KeyPackageNewError<KeyStoreError>: 1400 {
+    LibraryError = 1,
+    CiphersuiteSignatureSchemeMismatch = 2,
+    KeyStoreError = 3,
+    SignatureError = 4,
+    #[cfg(feature = "fips")]
+    NotFipsApproved = 5,
+});
+
+/// KeyPackage delete error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum KeyPackageDeleteError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// Accessing the key store failed.
+    #[error("Accessing the key store failed.")]
+    KeyStoreError(KeyStoreError),
+}
+
+error_codes!(KeyPackageDeleteError<KeyStoreError>: 7300 {
+    LibraryError = 1,
+    KeyStoreError = 2,
+});
@@ -0,0 +1,135 @@
+//! This module tracks how many unused key packages are in the key store, see
+//! [`KeyPackageInventory`].
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use openmls_traits::{key_store::OpenMlsKeyStore, types::Ciphersuite, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+
+/// The ID of the [`KeyPackageInventory`] in the key store.
+pub(crate) const KEY_PACKAGE_INVENTORY_ID: &[u8] = b"openmls_key_package_inventory";
+
+/// The number of unused key packages in the key store per ciphersuite.
+///
+/// The inventory is kept in the key store. A key package is counted when it
+/// is built with [`KeyPackageBuilder::build()`](super::KeyPackageBuilder::build())
+/// and no longer counted when it is deleted with [`KeyPackage::delete()`](super::KeyPackage::delete()),
+/// which also happens when a Welcome consumes it. Note that the inventory
+/// is read, updated and written back, so that key packages must not be built
+/// or consumed concurrently with the same key store.
+///
+/// See [`KeyPackageReplenishment`] to be notified when a client runs low on
+/// key packages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPackageInventory {
+    counts: BTreeMap<Ciphersuite, usize>,
+}
+
+impl KeyPackageInventory {
+    /// Load the inventory from the key store of the `backend`. If there is no
+    /// inventory in the key store, it is empty.
+    pub fn load<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Self {
+        backend
+            .key_store()
+            .read(KEY_PACKAGE_INVENTORY_ID)
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of unused key packages for the `ciphersuite`.
+    pub fn count(&self, ciphersuite: Ciphersuite) -> usize {
+        self.counts.get(&ciphersuite).copied().unwrap_or_default()
+    }
+
+    /// Returns an iterator over the ciphersuites and the number of unused key
+    /// packages for each of them.
+    pub fn counts(&self) -> impl Iterator<Item = (Ciphersuite, usize)> + '_ {
+        self.counts
+            .iter()
+            .map(|(&ciphersuite, &count)| (ciphersuite, count))
+    }
+
+    /// Count a new key package for the `ciphersuite` in the inventory in the
+    /// key store.
+    pub(crate) fn add<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+    ) -> Result<(), KeyStore::Error> {
+        let mut inventory = Self::load(backend);
+        *inventory.counts.entry(ciphersuite).or_default() += 1;
+        backend
+            .key_store()
+            .store(KEY_PACKAGE_INVENTORY_ID, &inventory)
+    }
+
+    /// Stop counting a key package for the `ciphersuite` in the inventory in
+    /// the key store.
+    pub(crate) fn remove<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+    ) -> Result<(), KeyStore::Error> {
+        let mut inventory = Self::load(backend);
+        if let Some(count) = inventory.counts.get_mut(&ciphersuite) {
+            *count = count.saturating_sub(1);
+        }
+        backend
+            .key_store()
+            .store(KEY_PACKAGE_INVENTORY_ID, &inventory)
+    }
+}
+
+/// Notifies the application when a Welcome consumed a key package and the
+/// number of unused key packages for its ciphersuite in the
+/// [`KeyPackageInventory`] is below a threshold, so that the application can
+/// upload new key packages.
+///
+/// Set it with [`MlsGroupConfigBuilder::key_package_replenishment()`](crate::group::MlsGroupConfigBuilder::key_package_replenishment()).
+#[derive(Clone)]
+pub struct KeyPackageReplenishment {
+    threshold: usize,
+    callback: Arc<dyn Fn(Ciphersuite, usize) + Send + Sync>,
+}
+
+impl KeyPackageReplenishment {
+    /// Create a new [`KeyPackageReplenishment`]. The `callback` is called
+    /// with the ciphersuite and the number of remaining key packages when
+    /// the number drops below `threshold`.
+    pub fn new(
+        threshold: usize,
+        callback: impl Fn(Ciphersuite, usize) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            threshold,
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Returns the threshold below which the callback is called.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Calls the callback if `remaining` is below the threshold.
+    pub(crate) fn check(&self, ciphersuite: Ciphersuite, remaining: usize) {
+        if remaining < self.threshold {
+            (self.callback)(ciphersuite, remaining)
+        }
+    }
+}
+
+impl fmt::Debug for KeyPackageReplenishment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPackageReplenishment")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for KeyPackageReplenishment {
+    fn eq(&self, other: &Self) -> bool {
+        self.threshold == other.threshold && Arc::ptr_eq(&self.callback, &other.callback)
+    }
+}
+
+impl Eq for KeyPackageReplenishment {}
@@ -35,7 +35,7 @@
 //! let backend = OpenMlsRustCrypto::default();
 //!
 //! let credential_bundle = CredentialBundle::new(
-//!     b"Sasha".to_vec(),
+//!     "Sasha".into(),
 //!     CredentialType::Basic,
 //!     SignatureScheme::from(ciphersuite),
 //!     &backend,
@@ -85,8 +85,7 @@ use crate::{
     },
     credentials::*,
     error::LibraryError,
-    extensions::ExtensionType,
-    extensions::Extensions,
+    extensions::{ApplicationIdExtension, Extension, ExtensionType, Extensions},
     group::config::CryptoConfig,
     treesync::{
         node::{
@@ -112,11 +111,22 @@ use tls_codec::{
 use crate::treesync::node::encryption_keys::EncryptionKey;
 
 // Private
+mod batch;
 mod codec;
+mod inventory;
+mod transparency;
 use errors::*;
 
 // Public
 pub mod errors;
+pub use batch::{KeyPackageBatch, KeyPackageBatchEntry, KeyPackageUsage};
+pub use inventory::{KeyPackageInventory, KeyPackageReplenishment};
+pub(crate) use inventory::KEY_PACKAGE_INVENTORY_ID;
+pub(crate) use transparency::TransparencyLog;
+pub use transparency::{
+    KeyPackagePublication, KeyTransparencyLog, KeyTransparencyRecord, KeyTransparencyVerifier,
+    SignatureKeyRotationRecord,
+};
 
 // Tests
 #[cfg(test)]
@@ -138,9 +148,12 @@ mod test_key_packages;
 /// } KeyPackageTBS;
 /// ```
 #[derive(Debug, Clone, PartialEq, TlsSize, TlsSerialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 struct KeyPackageTBS {
     protocol_version: ProtocolVersion,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_ciphersuite))]
     ciphersuite: Ciphersuite,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     init_key: HpkePublicKey,
     leaf_node: LeafNode,
     extensions: Extensions,
@@ -166,6 +179,7 @@ impl From<KeyPackage> for KeyPackageTBS {
 
 /// The key package struct.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct KeyPackage {
     payload: KeyPackageTBS,
     signature: Signature,
@@ -237,6 +251,10 @@ impl KeyPackage {
         if SignatureScheme::from(config.ciphersuite) != credential.credential().signature_scheme() {
             return Err(KeyPackageNewError::CiphersuiteSignatureSchemeMismatch);
         }
+        #[cfg(feature = "fips")]
+        if !crate::ciphersuite::fips::is_approved_ciphersuite(config.ciphersuite) {
+            return Err(KeyPackageNewError::NotFipsApproved);
+        }
 
         // Create a new HPKE key pair
         let ikm = Secret::random(config.ciphersuite, backend, config.version)
@@ -303,15 +321,58 @@ impl KeyPackage {
         Ok((key_package, encryption_key_pair))
     }
 
-    /// Delete this key package and its private key from the key store.
+    /// Delete this key package, its private init key and the private key of
+    /// its leaf node from the key store, as well as the mark of a last resort
+    /// key package. The private init key is also released in the crypto
+    /// provider of the `backend`, see [`OpenMlsCrypto::release_private_key()`].
     pub fn delete<KeyStore: OpenMlsKeyStore>(
         &self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
-    ) -> Result<(), KeyStore::Error> {
-        backend
+    ) -> Result<(), KeyPackageDeleteError<KeyStore::Error>> {
+        let hash_ref = self.hash_ref(backend.crypto())?;
+        let private_key: Option<Vec<u8>> =
+            backend.key_store().read(self.hpke_init_key().as_slice());
+        let stored = backend
             .key_store()
-            .delete(self.hash_ref(backend.crypto()).unwrap().as_slice())?;
-        backend.key_store().delete(self.hpke_init_key().as_slice())
+            .read::<KeyPackage>(hash_ref.as_slice())
+            .is_some();
+        let delete = |k: &[u8]| {
+            backend
+                .key_store()
+                .delete(k)
+                .map_err(KeyPackageDeleteError::KeyStoreError)
+        };
+        delete(hash_ref.as_slice())?;
+        delete(self.hpke_init_key().as_slice())?;
+        delete(&last_resort_id(&hash_ref))?;
+        // The leaf node key pair may be in use by a group that was joined
+        // with this key package, so that it is not released.
+        delete(&self.leaf_node().encryption_key().to_bytes_with_prefix())?;
+        if stored {
+            KeyPackageInventory::remove(backend, self.ciphersuite())
+                .map_err(KeyPackageDeleteError::KeyStoreError)?;
+        }
+        if let Some(private_key) = private_key {
+            // Releasing keys is best effort.
+            if let Err(e) = backend.crypto().release_private_key(&private_key) {
+                log::warn!("Failed to release a private key: {:?}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if this key package was built as a last resort key
+    /// package with [`KeyPackageBuilder::last_resort()`] and is still in the
+    /// key store of the `backend`.
+    pub fn is_last_resort(&self, backend: &impl OpenMlsCryptoProvider) -> bool {
+        self.hash_ref(backend.crypto())
+            .ok()
+            .and_then(|hash_ref| {
+                backend
+                    .key_store()
+                    .read::<KeyPackageUsage>(&last_resort_id(&hash_ref))
+            })
+            .map_or(false, |usage| usage == KeyPackageUsage::LastResort)
     }
 
     /// Verify that this key package is valid:
@@ -572,12 +633,25 @@ impl KeyPackage {
     }
 }
 
+/// The prefix of the key store ID of the mark of a last resort key package.
+pub(crate) const LAST_RESORT_ID_PREFIX: &[u8] = b"openmls_last_resort_key_package";
+
+/// Returns the key store ID of the mark of a last resort key package with the
+/// reference `hash_ref`.
+pub(crate) fn last_resort_id(hash_ref: &KeyPackageRef) -> Vec<u8> {
+    [LAST_RESORT_ID_PREFIX, hash_ref.as_slice()].concat()
+}
+
 /// Builder that helps creating (and configuring) a [`KeyPackage`].
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct KeyPackageBuilder {
     key_package_extensions: Option<Extensions>,
     leaf_node_capabilities: Option<Capabilities>,
     leaf_node_extensions: Option<Extensions>,
+    #[serde(default)]
+    last_resort: bool,
+    #[serde(skip)]
+    transparency_log: Option<TransparencyLog>,
 }
 
 impl KeyPackageBuilder {
@@ -587,6 +661,8 @@ impl KeyPackageBuilder {
             key_package_extensions: None,
             leaf_node_capabilities: None,
             leaf_node_extensions: None,
+            last_resort: false,
+            transparency_log: None,
         }
     }
 
@@ -596,7 +672,8 @@ impl KeyPackageBuilder {
         self
     }
 
-    /// Set the leaf node capabilities.
+    /// Set the leaf node capabilities. See [`Capabilities::builder()`] for a
+    /// convenient way to create them.
     pub fn leaf_node_capabilities(mut self, capabilities: Capabilities) -> Self {
         self.leaf_node_capabilities = Some(capabilities);
         self
@@ -608,6 +685,35 @@ impl KeyPackageBuilder {
         self
     }
 
+    /// Set the application id of the leaf node. This adds an
+    /// [`ApplicationIdExtension`] to the leaf node extensions, replacing an
+    /// existing one.
+    pub fn application_id(mut self, application_id: &[u8]) -> Self {
+        self.leaf_node_extensions
+            .get_or_insert_with(Extensions::default)
+            .add_or_replace(Extension::ApplicationId(ApplicationIdExtension::new(
+                application_id,
+            )));
+        self
+    }
+
+    /// Mark the key package as last resort key package. It may be used by
+    /// more than one Welcome, e.g. when the client has no other key packages
+    /// left, and is therefore kept in the key store when a group is joined
+    /// with it. See [`KeyPackage::is_last_resort()`].
+    pub fn last_resort(mut self) -> Self {
+        self.last_resort = true;
+        self
+    }
+
+    /// Set the [`KeyTransparencyLog`] that receives a
+    /// [`KeyPackagePublication`] for every key package that is built. The
+    /// log isn't serialized with the builder.
+    pub fn transparency_log(mut self, log: impl KeyTransparencyLog + 'static) -> Self {
+        self.transparency_log = Some(TransparencyLog::new(log));
+        self
+    }
+
     pub(crate) fn build_without_key_storage<KeyStore: OpenMlsKeyStore>(
         self,
         config: CryptoConfig,
@@ -666,8 +772,84 @@ impl KeyPackageBuilder {
             .store(key_package.hpke_init_key().as_slice(), &init_private_key)
             .map_err(KeyPackageNewError::KeyStoreError)?;
 
+        // Count the key package in the inventory.
+        KeyPackageInventory::add(backend, key_package.ciphersuite())
+            .map_err(KeyPackageNewError::KeyStoreError)?;
+
+        // Mark last resort key packages, such that they are not deleted when
+        // a group is joined with them.
+        if self.last_resort {
+            backend
+                .key_store()
+                .store(
+                    &last_resort_id(&key_package.hash_ref(backend.crypto())?),
+                    &KeyPackageUsage::LastResort,
+                )
+                .map_err(KeyPackageNewError::KeyStoreError)?;
+        }
+
+        if let Some(transparency_log) = &self.transparency_log {
+            transparency_log.append(KeyTransparencyRecord::KeyPackagePublication(
+                KeyPackagePublication::new(&key_package, backend.crypto())?,
+            ));
+        }
+
         Ok(key_package)
     }
+
+    /// Finalize and build `count` key packages for each of the `ciphersuites`,
+    /// e.g. to upload them to a directory server in one go.
+    ///
+    /// All `ciphersuites` have to use the signature scheme of the
+    /// `credential`. This is checked before any key package is created. As
+    /// with [`build()`](Self::build()), the key packages and their private
+    /// keys are stored in the key store. If this fails, the key packages of
+    /// the batch that were already stored are deleted again.
+    ///
+    /// The key packages are returned in the order of the `ciphersuites`.
+    pub fn build_batch<KeyStore: OpenMlsKeyStore>(
+        self,
+        ciphersuites: &[Ciphersuite],
+        count: usize,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        credential: &CredentialBundle,
+    ) -> Result<Vec<KeyPackage>, KeyPackageNewError<KeyStore::Error>> {
+        if ciphersuites.iter().any(|ciphersuite| {
+            SignatureScheme::from(*ciphersuite) != credential.credential().signature_scheme()
+        }) {
+            return Err(KeyPackageNewError::CiphersuiteSignatureSchemeMismatch);
+        }
+        #[cfg(feature = "fips")]
+        if !ciphersuites
+            .iter()
+            .all(|ciphersuite| crate::ciphersuite::fips::is_approved_ciphersuite(*ciphersuite))
+        {
+            return Err(KeyPackageNewError::NotFipsApproved);
+        }
+
+        let mut key_packages = Vec::with_capacity(ciphersuites.len() * count);
+        for &ciphersuite in ciphersuites {
+            for _ in 0..count {
+                let config = CryptoConfig {
+                    ciphersuite,
+                    version: ProtocolVersion::default(),
+                };
+                match self.clone().build(config, backend, credential) {
+                    Ok(key_package) => key_packages.push(key_package),
+                    Err(e) => {
+                        // Don't leave a partial batch behind in the key store.
+                        for key_package in &key_packages {
+                            if let Err(e) = key_package.delete(backend) {
+                                log::warn!("Failed to delete a key package: {:?}", e);
+                            }
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(key_packages)
+    }
 }
 
 /// A [`KeyPackageBundle`] contains a [`KeyPackage`] and the corresponding private
@@ -2,7 +2,12 @@ use crate::test_utils::*;
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use tls_codec::Deserialize;
 
-use crate::{extensions::*, key_packages::*};
+use crate::{
+    extensions::*,
+    key_packages::*,
+    messages::proposals::ProposalType,
+    treesync::node::leaf_node::{Capabilities, CapabilitiesError},
+};
 
 /// Helper function to generate key packages
 fn key_package(
@@ -10,7 +15,7 @@ fn key_package(
     backend: &impl OpenMlsCryptoProvider,
 ) -> (KeyPackage, CredentialBundle) {
     let credential_bundle = CredentialBundle::new(
-        b"Sasha".to_vec(),
+        "Sasha".into(),
         CredentialType::Basic,
         ciphersuite.into(),
         backend,
@@ -64,7 +69,7 @@ fn serialization(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider)
 fn application_id_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     // This is a leaf node extension but it is set through the key package.
     let credential_bundle = CredentialBundle::new(
-        b"Sasha".to_vec(),
+        "Sasha".into(),
         CredentialType::Basic,
         ciphersuite.into(),
         backend,
@@ -106,6 +111,168 @@ fn application_id_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryp
             .application_id()
             .map(|e| e.as_slice())
     );
+    assert_eq!(Some(id), key_package.leaf_node().application_id());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn capabilities_builder(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let credential_bundle = CredentialBundle::new(
+        "Sasha".into(),
+        CredentialType::Basic,
+        ciphersuite.into(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    let required_capabilities = RequiredCapabilitiesExtension::new(
+        &[ExtensionType::ApplicationId],
+        &[ProposalType::Reinit],
+        &[CredentialType::Basic],
+    );
+
+    // Capabilities that don't support the required proposal type are rejected.
+    let err = Capabilities::builder()
+        .proposals(vec![ProposalType::Add])
+        .build_for(&required_capabilities)
+        .expect_err("Unsupported proposal type was not detected.");
+    assert_eq!(err, CapabilitiesError::UnsupportedProposalType);
+
+    let capabilities = Capabilities::builder()
+        .versions(vec![ProtocolVersion::Mls10])
+        .ciphersuites(vec![ciphersuite])
+        .extensions(vec![ExtensionType::ApplicationId])
+        .proposals(vec![ProposalType::Add, ProposalType::Reinit])
+        .credentials(vec![CredentialType::Basic])
+        .build_for(&required_capabilities)
+        .expect("Consistent capabilities were rejected.");
+
+    let key_package = KeyPackage::builder()
+        .leaf_node_capabilities(capabilities.clone())
+        .build(
+            CryptoConfig {
+                ciphersuite,
+                version: ProtocolVersion::default(),
+            },
+            backend,
+            &credential_bundle,
+        )
+        .expect("An unexpected error occurred.");
+
+    assert_eq!(key_package.leaf_node().capabilities(), &capabilities);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn batch(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let credential_bundle = CredentialBundle::new(
+        "Sasha".into(),
+        CredentialType::Basic,
+        ciphersuite.into(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    // All supported ciphersuites with the same signature scheme
+    let ciphersuites: Vec<Ciphersuite> = backend
+        .crypto()
+        .supported_ciphersuites()
+        .into_iter()
+        .filter(|&other| SignatureScheme::from(other) == SignatureScheme::from(ciphersuite))
+        .collect();
+
+    let key_packages = KeyPackage::builder()
+        .build_batch(&ciphersuites, 3, backend, &credential_bundle)
+        .expect("An unexpected error occurred.");
+    assert_eq!(key_packages.len(), ciphersuites.len() * 3);
+
+    for (key_packages, &ciphersuite) in key_packages.chunks(3).zip(ciphersuites.iter()) {
+        for key_package in key_packages {
+            assert_eq!(key_package.ciphersuite(), ciphersuite);
+            assert!(KeyPackage::verify(key_package, backend).is_ok());
+            // The key package and its private key are in the key store.
+            assert!(backend
+                .key_store()
+                .read::<KeyPackage>(
+                    key_package
+                        .hash_ref(backend.crypto())
+                        .expect("An unexpected error occurred.")
+                        .as_slice()
+                )
+                .is_some());
+            assert!(backend
+                .key_store()
+                .read::<Vec<u8>>(key_package.hpke_init_key().as_slice())
+                .is_some());
+        }
+    }
+
+    // Every key package has its own init key.
+    let init_keys: std::collections::HashSet<_> = key_packages
+        .iter()
+        .map(|key_package| key_package.hpke_init_key().as_slice().to_vec())
+        .collect();
+    assert_eq!(init_keys.len(), key_packages.len());
+
+    // Ciphersuites with a different signature scheme are rejected upfront.
+    let mismatching_ciphersuite = backend
+        .crypto()
+        .supported_ciphersuites()
+        .into_iter()
+        .find(|&other| SignatureScheme::from(other) != SignatureScheme::from(ciphersuite));
+    if let Some(mismatching_ciphersuite) = mismatching_ciphersuite {
+        assert_eq!(
+            KeyPackage::builder().build_batch(
+                &[ciphersuite, mismatching_ciphersuite],
+                3,
+                backend,
+                &credential_bundle,
+            ),
+            Err(KeyPackageNewError::CiphersuiteSignatureSchemeMismatch)
+        );
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn batch_encoding(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let credential_bundle = CredentialBundle::new(
+        "Sasha".into(),
+        CredentialType::Basic,
+        ciphersuite.into(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    let key_packages = KeyPackage::builder()
+        .build_batch(&[ciphersuite], 2, backend, &credential_bundle)
+        .expect("An unexpected error occurred.");
+    let mut batch = KeyPackageBatch::new(key_packages);
+    let (last_resort, _) = key_package(ciphersuite, backend);
+    batch.push(last_resort, KeyPackageUsage::LastResort);
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch.entries_for(ciphersuite).count(), 3);
+    assert_eq!(
+        batch
+            .entries()
+            .iter()
+            .filter(|entry| entry.is_last_resort())
+            .count(),
+        1
+    );
+    assert!(batch.verify(backend).is_ok());
+
+    // TLS
+    let encoded = batch
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let decoded = KeyPackageBatch::tls_deserialize(&mut encoded.as_slice())
+        .expect("An unexpected error occurred.");
+    assert_eq!(decoded, batch);
+
+    // JSON
+    let encoded = serde_json::to_string(&batch).expect("An unexpected error occurred.");
+    let decoded: KeyPackageBatch =
+        serde_json::from_str(&encoded).expect("An unexpected error occurred.");
+    assert_eq!(decoded, batch);
+    assert_eq!(decoded.created_at(), batch.created_at());
 }
 
 #[apply(backends)]
@@ -116,7 +283,7 @@ fn test_mismatch(backend: &impl OpenMlsCryptoProvider) {
     let signature_scheme = SignatureScheme::ECDSA_SECP256R1_SHA256;
 
     let credential_bundle = CredentialBundle::new(
-        vec![1, 2, 3],
+        vec![1, 2, 3].into(),
         CredentialType::Basic,
         signature_scheme,
         backend,
@@ -141,7 +308,7 @@ fn test_mismatch(backend: &impl OpenMlsCryptoProvider) {
     let signature_scheme = SignatureScheme::ED25519;
 
     let credential_bundle = CredentialBundle::new(
-        vec![1, 2, 3],
+        vec![1, 2, 3].into(),
         CredentialType::Basic,
         signature_scheme,
         backend,
@@ -159,3 +326,67 @@ fn test_mismatch(backend: &impl OpenMlsCryptoProvider) {
         )
         .is_ok());
 }
+
+#[apply(ciphersuites_and_backends)]
+fn capabilities_grease(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use crate::treesync::node::leaf_node::is_grease;
+    use tls_codec::{Serialize, Size};
+
+    let credential_bundle = CredentialBundle::new(
+        "Sasha".into(),
+        CredentialType::Basic,
+        ciphersuite.into(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    let capabilities = Capabilities::builder().grease(backend).build();
+    // One GREASE value of two bytes is inserted into each of the four lists.
+    assert_eq!(
+        capabilities
+            .tls_serialize_detached()
+            .expect("An unexpected error occurred.")
+            .len(),
+        Capabilities::default().tls_serialized_len() + 4 * 2
+    );
+
+    let key_package = KeyPackage::builder()
+        .leaf_node_capabilities(capabilities.clone())
+        .build(
+            CryptoConfig {
+                ciphersuite,
+                version: ProtocolVersion::default(),
+            },
+            backend,
+            &credential_bundle,
+        )
+        .expect("An unexpected error occurred.");
+
+    // The GREASE values are ignored by the peer, but preserved such that the
+    // signature can be verified.
+    let encoded = key_package
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let decoded_key_package = KeyPackage::tls_deserialize(&mut encoded.as_slice())
+        .expect("An unexpected error occurred.");
+    assert!(KeyPackage::verify(&decoded_key_package, backend).is_ok());
+    assert_eq!(
+        decoded_key_package.leaf_node().capabilities(),
+        &capabilities
+    );
+    let decoded_capabilities = decoded_key_package.leaf_node().capabilities();
+    assert_eq!(
+        decoded_capabilities.ciphersuites(),
+        Capabilities::default().ciphersuites()
+    );
+    assert!(decoded_capabilities
+        .extensions()
+        .iter()
+        .all(|extension_type| !is_grease(u16::from(*extension_type))));
+    assert_eq!(
+        decoded_key_package
+            .tls_serialize_detached()
+            .expect("An unexpected error occurred."),
+        encoded
+    );
+}
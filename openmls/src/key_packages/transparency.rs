@@ -0,0 +1,247 @@
+//! Records for key-transparency logs, see [`KeyTransparencyRecord`].
+
+use std::{fmt, io::Read, sync::Arc};
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite};
+use serde::{Deserialize, Serialize};
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, Size, TlsDeserialize,
+    TlsSerialize, TlsSize,
+};
+
+use super::KeyPackage;
+use crate::{ciphersuite::hash_ref::KeyPackageRef, credentials::Credential, error::LibraryError};
+
+/// The publication of a [`KeyPackage`], e.g. when it is uploaded to a
+/// directory server.
+///
+/// The record binds the reference of the key package, which commits to its
+/// complete content, to the credential of the client.
+///
+/// ```text
+/// struct {
+///     KeyPackageRef key_package_ref;
+///     CipherSuite cipher_suite;
+///     Credential credential;
+/// } KeyPackagePublication;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct KeyPackagePublication {
+    key_package_ref: KeyPackageRef,
+    ciphersuite: Ciphersuite,
+    credential: Credential,
+}
+
+impl KeyPackagePublication {
+    /// Create the publication record of the `key_package`.
+    pub fn new(
+        key_package: &KeyPackage,
+        crypto: &impl OpenMlsCrypto,
+    ) -> Result<Self, LibraryError> {
+        Ok(Self {
+            key_package_ref: key_package.hash_ref(crypto)?,
+            ciphersuite: key_package.ciphersuite(),
+            credential: key_package.leaf_node().credential().clone(),
+        })
+    }
+
+    /// Returns the reference of the published key package.
+    pub fn key_package_ref(&self) -> &KeyPackageRef {
+        &self.key_package_ref
+    }
+
+    /// Returns the ciphersuite of the published key package.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+
+    /// Returns the credential of the published key package.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+}
+
+/// The rotation of the signature key of a client from the `old_credential`
+/// to the `new_credential`, see
+/// [`SignatureKeyRotation`](crate::group::SignatureKeyRotation).
+///
+/// ```text
+/// struct {
+///     Credential old_credential;
+///     Credential new_credential;
+/// } SignatureKeyRotationRecord;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct SignatureKeyRotationRecord {
+    old_credential: Credential,
+    new_credential: Credential,
+}
+
+impl SignatureKeyRotationRecord {
+    /// Create the record of a rotation from the `old_credential` to the
+    /// `new_credential`.
+    pub fn new(old_credential: Credential, new_credential: Credential) -> Self {
+        Self {
+            old_credential,
+            new_credential,
+        }
+    }
+
+    /// Returns the credential with the old signature key.
+    pub fn old_credential(&self) -> &Credential {
+        &self.old_credential
+    }
+
+    /// Returns the credential with the new signature key.
+    pub fn new_credential(&self) -> &Credential {
+        &self.new_credential
+    }
+}
+
+/// A record for a key-transparency log.
+///
+/// Records are emitted to the [`KeyTransparencyLog`] of the application when
+/// key packages are built and when signature keys are rotated. Their TLS
+/// encoding is stable, such that it can be inserted into the log as is.
+///
+/// ```text
+/// enum {
+///     reserved(0),
+///     key_package_publication(1),
+///     signature_key_rotation(2),
+///     (255)
+/// } KeyTransparencyRecordType;
+///
+/// struct {
+///     KeyTransparencyRecordType record_type;
+///     select (KeyTransparencyRecord.record_type) {
+///         case key_package_publication:
+///             KeyPackagePublication key_package_publication;
+///         case signature_key_rotation:
+///             SignatureKeyRotationRecord signature_key_rotation;
+///     };
+/// } KeyTransparencyRecord;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyTransparencyRecord {
+    /// A key package was published.
+    KeyPackagePublication(KeyPackagePublication),
+    /// A signature key was rotated.
+    SignatureKeyRotation(SignatureKeyRotationRecord),
+}
+
+impl Size for KeyTransparencyRecord {
+    fn tls_serialized_len(&self) -> usize {
+        1 + match self {
+            KeyTransparencyRecord::KeyPackagePublication(record) => record.tls_serialized_len(),
+            KeyTransparencyRecord::SignatureKeyRotation(record) => record.tls_serialized_len(),
+        }
+    }
+}
+
+impl TlsSerializeTrait for KeyTransparencyRecord {
+    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        match self {
+            KeyTransparencyRecord::KeyPackagePublication(record) => {
+                let written = 1u8.tls_serialize(writer)?;
+                record.tls_serialize(writer).map(|l| l + written)
+            }
+            KeyTransparencyRecord::SignatureKeyRotation(record) => {
+                let written = 2u8.tls_serialize(writer)?;
+                record.tls_serialize(writer).map(|l| l + written)
+            }
+        }
+    }
+}
+
+impl TlsDeserializeTrait for KeyTransparencyRecord {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
+        match u8::tls_deserialize(bytes)? {
+            1 => Ok(KeyTransparencyRecord::KeyPackagePublication(
+                KeyPackagePublication::tls_deserialize(bytes)?,
+            )),
+            2 => Ok(KeyTransparencyRecord::SignatureKeyRotation(
+                SignatureKeyRotationRecord::tls_deserialize(bytes)?,
+            )),
+            record_type => Err(tls_codec::Error::DecodingError(format!(
+                "Unknown key transparency record type {}",
+                record_type
+            ))),
+        }
+    }
+}
+
+/// Receives the [`KeyTransparencyRecord`]s of a client, e.g. to insert them
+/// into a key-transparency log.
+///
+/// Set it with [`KeyPackageBuilder::transparency_log()`] and
+/// [`SignatureKeyRotation::transparency_log()`].
+///
+/// Closures `Fn(KeyTransparencyRecord)` implement this trait.
+///
+/// [`KeyPackageBuilder::transparency_log()`]: super::KeyPackageBuilder::transparency_log()
+/// [`SignatureKeyRotation::transparency_log()`]: crate::group::SignatureKeyRotation::transparency_log()
+pub trait KeyTransparencyLog: Send + Sync {
+    /// Append the `record` to the log.
+    fn append(&self, record: KeyTransparencyRecord);
+}
+
+impl<F> KeyTransparencyLog for F
+where
+    F: Fn(KeyTransparencyRecord) + Send + Sync,
+{
+    fn append(&self, record: KeyTransparencyRecord) {
+        self(record)
+    }
+}
+
+/// Verifies that the key packages of peers were published in a
+/// key-transparency log before they are trusted.
+///
+/// The verifier is called with the [`KeyPackagePublication`] of every key
+/// package that is added to a group, both when adding members and when
+/// processing Add proposals and commits. It returns `true` if the
+/// application holds a valid inclusion proof for the publication, e.g. one
+/// that was served together with the key package.
+///
+/// Closures `Fn(&KeyPackagePublication) -> bool` implement this trait.
+pub trait KeyTransparencyVerifier: Send + Sync {
+    /// Returns `true` if the `publication` is proven to be in the log.
+    fn verify(&self, publication: &KeyPackagePublication) -> bool;
+}
+
+impl<F> KeyTransparencyVerifier for F
+where
+    F: Fn(&KeyPackagePublication) -> bool + Send + Sync,
+{
+    fn verify(&self, publication: &KeyPackagePublication) -> bool {
+        self(publication)
+    }
+}
+
+/// The [`KeyTransparencyLog`] of a [`KeyPackageBuilder`] or a
+/// [`SignatureKeyRotation`](crate::group::SignatureKeyRotation).
+///
+/// [`KeyPackageBuilder`]: super::KeyPackageBuilder
+#[derive(Clone)]
+pub(crate) struct TransparencyLog(Arc<dyn KeyTransparencyLog>);
+
+impl TransparencyLog {
+    pub(crate) fn new(log: impl KeyTransparencyLog + 'static) -> Self {
+        Self(Arc::new(log))
+    }
+
+    pub(crate) fn append(&self, record: KeyTransparencyRecord) {
+        self.0.append(record)
+    }
+}
+
+impl fmt::Debug for TransparencyLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TransparencyLog")
+    }
+}
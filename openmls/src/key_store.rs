@@ -1,56 +1,480 @@
-//! Serialization for key store objects.
+//! Serialization for key store objects and enumeration of the entries
+//! OpenMLS keeps in the key store.
+//!
+//! The values OpenMLS writes to the key store start with a header that names
+//! their type and the version of their format, such that values written by an
+//! older version of OpenMLS can be upgraded when they are read.
 
 use crate::{
-    credentials::CredentialBundle, key_packages::KeyPackage, prelude::LibraryError,
-    schedule::psk::PskBundle,
+    credentials::CredentialBundle,
+    error::{error_codes, LibraryError},
+    group::GroupId,
+    key_packages::{
+        KeyPackage, KeyPackageInventory, KeyPackageUsage, KEY_PACKAGE_INVENTORY_ID,
+        LAST_RESORT_ID_PREFIX,
+    },
+    schedule::psk::{PreSharedKeyId, Psk, PskBundle, ResumptionPsk},
+    treesync::node::encryption_keys::{EncryptionKeyPair, ENCRYPTION_KEY_LABEL},
 };
 
-use openmls_traits::key_store::{FromKeyStoreValue, ToKeyStoreValue};
+use openmls_traits::{
+    key_store::{
+        FromKeyStoreValue, KeyStoreId, OpenMlsKeyStore, ToKeyStoreValue, EXTERNAL_PSK_ID_PREFIX,
+    },
+    OpenMlsCryptoProvider,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::cmp::Ordering;
+use thiserror::Error;
+use tls_codec::Deserialize;
+
+// === Enumeration of OpenMLS entries
+
+/// The type of an entry OpenMLS keeps in the key store, see
+/// [`KeyStoreEntry::list()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyStoreEntryType {
+    /// A [`CredentialBundle`], stored under its signature public key.
+    CredentialBundle,
+    /// A [`KeyPackage`], stored under its reference.
+    KeyPackage,
+    /// The bookkeeping of key packages, i.e. the [`KeyPackageInventory`] and
+    /// the marks of last resort key packages.
+    KeyPackageMetadata,
+    /// An HPKE private key, i.e. the init key of a key package or the
+    /// encryption key of a leaf node.
+    HpkeKey,
+    /// The encryption keys of the ratchet tree of a group in an epoch.
+    EpochKeys,
+    /// An external or a resumption PSK.
+    Psk,
+}
+
+/// An entry OpenMLS keeps in the key store, see [`KeyStoreEntry::list()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyStoreEntry {
+    id: KeyStoreId,
+    entry_type: KeyStoreEntryType,
+    group_id: Option<GroupId>,
+}
+
+impl KeyStoreEntry {
+    /// Lists the entries OpenMLS keeps in the key store of the `backend`,
+    /// together with their type and, if the entry belongs to a group, the
+    /// [`GroupId`] of that group. Entries of the application are skipped.
+    ///
+    /// Only key stores that implement [`OpenMlsKeyStore::ids()`] can be
+    /// enumerated. The entries can be backed up and restored with the
+    /// functions of the key store, or deleted if they are not needed anymore.
+    pub fn list(backend: &impl OpenMlsCryptoProvider) -> Vec<Self> {
+        let ids = backend.key_store().ids();
+        let init_keys: Vec<Vec<u8>> = ids
+            .iter()
+            .filter_map(|id| match id {
+                KeyStoreId::Value(k) => backend.key_store().read::<KeyPackage>(k),
+                KeyStoreId::EpochKeys { .. } => None,
+            })
+            .map(|key_package| key_package.hpke_init_key().as_slice().to_vec())
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| {
+                let (entry_type, group_id) = match &id {
+                    KeyStoreId::Value(k) => classify(backend.key_store(), k, &init_keys)?,
+                    KeyStoreId::EpochKeys { group_id, .. } => (
+                        KeyStoreEntryType::EpochKeys,
+                        Some(GroupId::from_slice(group_id)),
+                    ),
+                };
+                Some(Self {
+                    id,
+                    entry_type,
+                    group_id,
+                })
+            })
+            .collect()
+    }
+
+    /// Rewrites the value of the entry in the current format, e.g. after an
+    /// update of OpenMLS, such that it remains readable when support for older
+    /// formats is dropped. Values in older formats are upgraded when they are
+    /// read as well, so this is optional.
+    ///
+    /// Returns `false` if the value is already in the current format, or is
+    /// stored as raw bytes without a format, like the init keys of key
+    /// packages and external PSKs.
+    pub fn upgrade<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<bool, KeyStoreUpgradeError<KeyStore::Error>> {
+        let key_store = backend.key_store();
+        let k = match &self.id {
+            KeyStoreId::Value(k) => k,
+            KeyStoreId::EpochKeys {
+                identity,
+                group_id,
+                epoch,
+            } => {
+                let current = value_header::<EncryptionKeyPair>();
+                if key_store
+                    .read_epoch_keys::<Vec<u8>>(identity, group_id, *epoch)
+                    .iter()
+                    .all(|ksv| ksv.starts_with(&current))
+                {
+                    return Ok(false);
+                }
+                // Reading the keys upgrades them. The key store doesn't return
+                // the keys if one of them can't be read.
+                let encryption_keys =
+                    key_store.read_epoch_keys::<EncryptionKeyPair>(identity, group_id, *epoch);
+                if encryption_keys.is_empty() {
+                    return Err(KeyStoreValueError::Malformed.into());
+                }
+                key_store
+                    .store_epoch_keys(identity, group_id, *epoch, &encryption_keys)
+                    .map_err(KeyStoreUpgradeError::KeyStoreError)?;
+                return Ok(true);
+            }
+        };
+        match self.entry_type {
+            KeyStoreEntryType::CredentialBundle => {
+                upgrade_value::<CredentialBundle, _>(key_store, k)
+            }
+            KeyStoreEntryType::KeyPackage => upgrade_value::<KeyPackage, _>(key_store, k),
+            KeyStoreEntryType::KeyPackageMetadata if k == KEY_PACKAGE_INVENTORY_ID => {
+                upgrade_value::<KeyPackageInventory, _>(key_store, k)
+            }
+            KeyStoreEntryType::KeyPackageMetadata => {
+                upgrade_value::<KeyPackageUsage, _>(key_store, k)
+            }
+            KeyStoreEntryType::HpkeKey if k.starts_with(ENCRYPTION_KEY_LABEL) => {
+                upgrade_value::<EncryptionKeyPair, _>(key_store, k)
+            }
+            KeyStoreEntryType::Psk if !k.starts_with(EXTERNAL_PSK_ID_PREFIX) => {
+                upgrade_value::<PskBundle, _>(key_store, k)
+            }
+            KeyStoreEntryType::HpkeKey | KeyStoreEntryType::Psk | KeyStoreEntryType::EpochKeys => {
+                Ok(false)
+            }
+        }
+    }
+
+    /// Returns the ID of the entry in the key store.
+    pub fn id(&self) -> &KeyStoreId {
+        &self.id
+    }
+
+    /// Returns the type of the entry.
+    pub fn entry_type(&self) -> KeyStoreEntryType {
+        self.entry_type
+    }
+
+    /// Returns the [`GroupId`] of the group the entry belongs to, if any.
+    pub fn group_id(&self) -> Option<&GroupId> {
+        self.group_id.as_ref()
+    }
+}
+
+/// Determine the type and the owning group of the value stored for ID `k`.
+/// Returns `None` if the value wasn't stored by OpenMLS.
+fn classify(
+    key_store: &impl OpenMlsKeyStore,
+    k: &[u8],
+    init_keys: &[Vec<u8>],
+) -> Option<(KeyStoreEntryType, Option<GroupId>)> {
+    if k == KEY_PACKAGE_INVENTORY_ID || k.starts_with(LAST_RESORT_ID_PREFIX) {
+        return Some((KeyStoreEntryType::KeyPackageMetadata, None));
+    }
+    if k.starts_with(EXTERNAL_PSK_ID_PREFIX) {
+        return Some((KeyStoreEntryType::Psk, None));
+    }
+    if k.starts_with(ENCRYPTION_KEY_LABEL) && key_store.read::<EncryptionKeyPair>(k).is_some() {
+        return Some((KeyStoreEntryType::HpkeKey, None));
+    }
+    if key_store.read::<PskBundle>(k).is_some() {
+        // Resumption PSKs are stored under their ID.
+        let group_id = deserialize_exact::<ResumptionPsk>(k)
+            .map(|resumption_psk| resumption_psk.psk_group_id().clone())
+            .or_else(|| match deserialize_exact::<PreSharedKeyId>(k)?.psk {
+                Psk::Resumption(resumption_psk) => Some(resumption_psk.psk_group_id),
+                Psk::External(_) => None,
+            });
+        return Some((KeyStoreEntryType::Psk, group_id));
+    }
+    if key_store.read::<CredentialBundle>(k).is_some() {
+        return Some((KeyStoreEntryType::CredentialBundle, None));
+    }
+    if key_store.read::<KeyPackage>(k).is_some() {
+        return Some((KeyStoreEntryType::KeyPackage, None));
+    }
+    if init_keys.iter().any(|init_key| init_key == k) {
+        return Some((KeyStoreEntryType::HpkeKey, None));
+    }
+    None
+}
+
+/// TLS-deserialize a `T` that spans all of `bytes`.
+fn deserialize_exact<T: Deserialize>(mut bytes: &[u8]) -> Option<T> {
+    let value = T::tls_deserialize(&mut bytes).ok()?;
+    if bytes.is_empty() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+// === Versioned key store values
+
+/// The magic bytes that start the header of the values OpenMLS writes to the
+/// key store. The header continues with the [`KeyStoreValueType`] of the value
+/// (one byte) and the version of its format (two bytes, big-endian), followed
+/// by the serialized value.
+const VALUE_MAGIC: &[u8] = b"OMLS";
+
+/// The length of the header of a versioned value.
+const VALUE_HEADER_LENGTH: usize = VALUE_MAGIC.len() + 3;
+
+/// The types of the values OpenMLS writes to the key store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum KeyStoreValueType {
+    KeyPackage = 1,
+    CredentialBundle = 2,
+    KeyPackageInventory = 3,
+    KeyPackageUsage = 4,
+    PskBundle = 5,
+    EncryptionKeyPair = 6,
+}
+
+/// A value OpenMLS writes to the key store with a versioned header.
+trait VersionedValue: Serialize + DeserializeOwned {
+    /// The type of the value.
+    const VALUE_TYPE: KeyStoreValueType;
+
+    /// The version of the current format of the value. It has to be increased
+    /// whenever the format changes, together with a migration of the older
+    /// formats in [`VersionedValue::upgrade()`].
+    const VERSION: u16 = 1;
+
+    /// Deserialize a value in the format of the older `version`. Version 0 is
+    /// the format of the values written without a header.
+    fn upgrade(version: u16, value: &[u8]) -> Result<Self, KeyStoreValueError> {
+        match version {
+            // The format didn't change since the values are versioned.
+            0 => serde_json::from_slice(value).map_err(|_| KeyStoreValueError::Malformed),
+            _ => Err(KeyStoreValueError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+/// The header of a value of type `T` in the current format.
+fn value_header<T: VersionedValue>() -> Vec<u8> {
+    let mut header = VALUE_MAGIC.to_vec();
+    header.push(T::VALUE_TYPE as u8);
+    header.extend_from_slice(&T::VERSION.to_be_bytes());
+    header
+}
+
+/// Serialize a value with the header of the current format.
+fn to_versioned_value<T: VersionedValue>(value: &T) -> Result<Vec<u8>, KeyStoreValueError> {
+    let mut ksv = value_header::<T>();
+    serde_json::to_writer(&mut ksv, value)
+        .map_err(|_| LibraryError::custom("Error serializing key store value."))?;
+    Ok(ksv)
+}
+
+/// Deserialize a versioned value, or a value written without a header, and
+/// upgrade it to the current format.
+fn from_versioned_value<T: VersionedValue>(ksv: &[u8]) -> Result<T, KeyStoreValueError> {
+    if !ksv.starts_with(VALUE_MAGIC) {
+        return T::upgrade(0, ksv);
+    }
+    if ksv.len() < VALUE_HEADER_LENGTH {
+        return Err(KeyStoreValueError::Malformed);
+    }
+    let (header, value) = ksv.split_at(VALUE_HEADER_LENGTH);
+    if header[VALUE_MAGIC.len()] != T::VALUE_TYPE as u8 {
+        return Err(KeyStoreValueError::UnexpectedType);
+    }
+    let version =
+        u16::from_be_bytes([header[VALUE_MAGIC.len() + 1], header[VALUE_MAGIC.len() + 2]]);
+    match version.cmp(&T::VERSION) {
+        Ordering::Equal => serde_json::from_slice(value).map_err(|_| KeyStoreValueError::Malformed),
+        Ordering::Less => T::upgrade(version, value),
+        Ordering::Greater => Err(KeyStoreValueError::UnsupportedVersion(version)),
+    }
+}
+
+/// Rewrite the value of type `T` stored for ID `k` in the current format.
+/// Returns `false` if the value is already in the current format.
+fn upgrade_value<T: VersionedValue, KeyStore: OpenMlsKeyStore>(
+    key_store: &KeyStore,
+    k: &[u8],
+) -> Result<bool, KeyStoreUpgradeError<KeyStore::Error>> {
+    let ksv = match key_store.read::<Vec<u8>>(k) {
+        Some(ksv) => ksv,
+        None => return Ok(false),
+    };
+    if ksv.starts_with(&value_header::<T>()) {
+        return Ok(false);
+    }
+    let value = from_versioned_value::<T>(&ksv)?;
+    key_store
+        .store(k, &value)
+        .map_err(KeyStoreUpgradeError::KeyStoreError)?;
+    Ok(true)
+}
+
+/// Key store value error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum KeyStoreValueError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The value is malformed.
+    #[error("The value is malformed.")]
+    Malformed,
+    /// The value is of a different type.
+    #[error("The value is of a different type.")]
+    UnexpectedType,
+    /// The format version of the value is not supported, e.g. because it was
+    /// written by a newer version of OpenMLS.
+    #[error("The format version {0} of the value is not supported.")]
+    UnsupportedVersion(u16),
+}
+
+error_codes!(KeyStoreValueError: 6800 {
+    LibraryError = 1,
+    Malformed = 2,
+    UnexpectedType = 3,
+    UnsupportedVersion = 4,
+});
+
+/// Key store upgrade error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum KeyStoreUpgradeError<KeyStoreError> {
+    /// See [`KeyStoreValueError`] for more details.
+    #[error(transparent)]
+    KeyStoreValueError(#[from] KeyStoreValueError),
+    /// Error accessing the key store.
+    #[error("Error accessing the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
+error_codes!(KeyStoreUpgradeError<KeyStoreError>: 6900 {
+    KeyStoreValueError = 1,
+    KeyStoreError = 2,
+});
 
 // === OpenMLS Key Store Types
 
+impl VersionedValue for KeyPackage {
+    const VALUE_TYPE: KeyStoreValueType = KeyStoreValueType::KeyPackage;
+}
+
 impl FromKeyStoreValue for KeyPackage {
-    type Error = LibraryError;
+    type Error = KeyStoreValueError;
     fn from_key_store_value(ksv: &[u8]) -> Result<Self, Self::Error> {
-        serde_json::from_slice(ksv).map_err(|_| LibraryError::custom("Invalid key package bundle."))
+        from_versioned_value(ksv)
     }
 }
 
+impl ToKeyStoreValue for KeyPackage {
+    type Error = KeyStoreValueError;
+    fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error> {
+        to_versioned_value(self)
+    }
+}
+
+impl VersionedValue for CredentialBundle {
+    const VALUE_TYPE: KeyStoreValueType = KeyStoreValueType::CredentialBundle;
+}
+
 impl FromKeyStoreValue for CredentialBundle {
-    type Error = LibraryError;
+    type Error = KeyStoreValueError;
     fn from_key_store_value(ksv: &[u8]) -> Result<Self, Self::Error> {
-        serde_json::from_slice(ksv).map_err(|_| LibraryError::custom("Invalid credential bundle."))
+        from_versioned_value(ksv)
     }
 }
 
-impl ToKeyStoreValue for KeyPackage {
-    type Error = LibraryError;
+impl ToKeyStoreValue for CredentialBundle {
+    type Error = KeyStoreValueError;
     fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error> {
-        serde_json::to_vec(self)
-            .map_err(|_| LibraryError::custom("Error serializing key package bundle."))
+        to_versioned_value(self)
     }
 }
 
-impl ToKeyStoreValue for CredentialBundle {
-    type Error = LibraryError;
+impl VersionedValue for KeyPackageInventory {
+    const VALUE_TYPE: KeyStoreValueType = KeyStoreValueType::KeyPackageInventory;
+}
+
+impl FromKeyStoreValue for KeyPackageInventory {
+    type Error = KeyStoreValueError;
+    fn from_key_store_value(ksv: &[u8]) -> Result<Self, Self::Error> {
+        from_versioned_value(ksv)
+    }
+}
+
+impl ToKeyStoreValue for KeyPackageInventory {
+    type Error = KeyStoreValueError;
+    fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error> {
+        to_versioned_value(self)
+    }
+}
+
+impl VersionedValue for KeyPackageUsage {
+    const VALUE_TYPE: KeyStoreValueType = KeyStoreValueType::KeyPackageUsage;
+}
+
+impl FromKeyStoreValue for KeyPackageUsage {
+    type Error = KeyStoreValueError;
+    fn from_key_store_value(ksv: &[u8]) -> Result<Self, Self::Error> {
+        from_versioned_value(ksv)
+    }
+}
+
+impl ToKeyStoreValue for KeyPackageUsage {
+    type Error = KeyStoreValueError;
     fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error> {
-        serde_json::to_vec(self)
-            .map_err(|_| LibraryError::custom("Error serializing key package bundle."))
+        to_versioned_value(self)
     }
 }
 
 // PSKs
 
+impl VersionedValue for PskBundle {
+    const VALUE_TYPE: KeyStoreValueType = KeyStoreValueType::PskBundle;
+}
+
 impl FromKeyStoreValue for PskBundle {
-    type Error = LibraryError;
+    type Error = KeyStoreValueError;
     fn from_key_store_value(ksv: &[u8]) -> Result<Self, Self::Error> {
-        serde_json::from_slice(ksv).map_err(|_| LibraryError::custom("Invalid PSK bundle."))
+        from_versioned_value(ksv)
     }
 }
 
 impl ToKeyStoreValue for PskBundle {
-    type Error = LibraryError;
+    type Error = KeyStoreValueError;
+    fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error> {
+        to_versioned_value(self)
+    }
+}
+
+// Encryption keys
+
+impl VersionedValue for EncryptionKeyPair {
+    const VALUE_TYPE: KeyStoreValueType = KeyStoreValueType::EncryptionKeyPair;
+}
+
+impl FromKeyStoreValue for EncryptionKeyPair {
+    type Error = KeyStoreValueError;
+    fn from_key_store_value(ksv: &[u8]) -> Result<Self, Self::Error> {
+        from_versioned_value(ksv)
+    }
+}
+
+impl ToKeyStoreValue for EncryptionKeyPair {
+    type Error = KeyStoreValueError;
     fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error> {
-        serde_json::to_vec(self).map_err(|_| LibraryError::custom("Error serializing PSK bundle."))
+        to_versioned_value(self)
     }
 }
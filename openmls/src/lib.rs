@@ -22,7 +22,7 @@
 //!     backend: &impl OpenMlsCryptoProvider,
 //! ) -> Result<Credential, CredentialError> {
 //!     let credential_bundle =
-//!         CredentialBundle::new(identity, credential_type, signature_algorithm, backend)?;
+//!         CredentialBundle::new(identity.into(), credential_type, signature_algorithm, backend)?;
 //!     let credential_id =  credential_bundle.credential()
 //!         .signature_key()
 //!         .tls_serialize_detached()
@@ -179,13 +179,19 @@ pub mod error;
 
 // Public
 pub mod ciphersuite;
+pub mod client;
 pub mod credentials;
+pub mod debug;
 pub mod extensions;
 pub mod framing;
 pub mod group;
+#[cfg(feature = "js")]
+pub mod js;
 pub mod key_packages;
 pub mod messages;
 pub mod schedule;
+#[cfg(feature = "text-encoding")]
+pub mod text_encoding;
 pub mod treesync;
 pub mod versions;
 
@@ -2,14 +2,21 @@
 //!
 //! Contains the types and methods to build external proposal to add/remove a client from a MLS group
 //!
-//! `Add` (from external sender), `Remove` & `ReInit are not yet implemented`
+//! `Add` (from external sender) & `ReInit` are not yet implemented
 
 use crate::{
+    binary_tree::array_representation::LeafNodeIndex,
     credentials::CredentialBundle,
-    framing::{mls_auth_content::AuthenticatedContent, MlsMessageOut, PublicMessage},
-    group::{mls_group::errors::ProposeAddMemberError, GroupEpoch, GroupId},
+    framing::{
+        mls_auth_content::AuthenticatedContent, MlsMessageOut, PublicMessage, Sender,
+        SenderExtensionIndex,
+    },
+    group::{
+        mls_group::errors::{ProposeAddMemberError, ProposeRemoveMemberError},
+        GroupEpoch, GroupId,
+    },
     key_packages::KeyPackage,
-    messages::{AddProposal, Proposal},
+    messages::{AddProposal, Proposal, RemoveProposal},
 };
 use openmls_traits::OpenMlsCryptoProvider;
 
@@ -38,6 +45,7 @@ impl JoinProposal {
     ) -> Result<MlsMessageOut, ProposeAddMemberError> {
         AuthenticatedContent::new_external_proposal(
             Proposal::Add(AddProposal { key_package }),
+            Sender::NewMemberProposal,
             credential,
             group_id,
             epoch,
@@ -48,3 +56,42 @@ impl JoinProposal {
         .map_err(ProposeAddMemberError::from)
     }
 }
+
+/// External Proposal where sender is a [Preconfigured sender](crate::prelude::Sender::External),
+/// i.e. a party that is listed in the
+/// [external senders extension](crate::extensions::ExternalSendersExtension) of the group, for
+/// example the Delivery Service. Group members only accept the proposal if the sender is still
+/// listed at `sender_index` in the group context of the current epoch.
+pub struct ExternalProposal;
+
+impl ExternalProposal {
+    /// Creates an external Remove proposal. For external senders requesting the removal of a
+    /// member from a group. This proposal will have to be committed later by a group member.
+    ///
+    /// # Arguments
+    /// * `removed` - leaf index of the member to remove
+    /// * `group_id` - unique group identifier of the group
+    /// * `epoch` - group's epoch
+    /// * `sender_index` - index of the sender in the external senders extension of the group
+    /// * `credential` - of the sender to sign the message
+    pub fn new_remove(
+        removed: LeafNodeIndex,
+        group_id: GroupId,
+        epoch: GroupEpoch,
+        sender_index: SenderExtensionIndex,
+        credential: &CredentialBundle,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<MlsMessageOut, ProposeRemoveMemberError> {
+        AuthenticatedContent::new_external_proposal(
+            Proposal::Remove(RemoveProposal { removed }),
+            Sender::External(sender_index),
+            credential,
+            group_id,
+            epoch,
+            backend,
+        )
+        .map(PublicMessage::from)
+        .map(MlsMessageOut::from)
+        .map_err(ProposeRemoveMemberError::from)
+    }
+}
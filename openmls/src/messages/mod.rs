@@ -7,6 +7,7 @@ use crate::{
     binary_tree::array_representation::LeafNodeIndex,
     ciphersuite::hash_ref::KeyPackageRef,
     ciphersuite::{signable::*, *},
+    credentials::CredentialBundle,
     error::LibraryError,
     extensions::*,
     group::*,
@@ -48,10 +49,13 @@ use crate::schedule::psk::{ExternalPsk, Psk};
 /// The invited member can use this message to join the group using
 /// [`MlsGroup::new_from_welcome()`](crate::group::mls_group::MlsGroup::new_from_welcome()).
 #[derive(Clone, Debug, Eq, PartialEq, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Welcome {
     version: ProtocolVersion,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_ciphersuite))]
     cipher_suite: Ciphersuite,
     secrets: Vec<EncryptedGroupSecrets>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     encrypted_group_info: VLBytes,
 }
 
@@ -103,10 +107,15 @@ impl Welcome {
 ///
 /// This is part of a [`Welcome`] message. It can be used to correlate the correct secrets with each new member.
 #[derive(Clone, Debug, Eq, PartialEq, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct EncryptedGroupSecrets {
     /// Key package reference of the new member
     new_member: KeyPackageRef,
     /// Ciphertext of the encrypted group secret
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::utils::arbitrary_hpke_ciphertext)
+    )]
     encrypted_group_secrets: HpkeCiphertext,
 }
 
@@ -151,6 +160,7 @@ impl EncryptedGroupSecrets {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct Commit {
     pub(crate) proposals: Vec<ProposalOrRef>,
     pub(crate) path: Option<UpdatePath>,
@@ -174,6 +184,7 @@ impl Commit {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ConfirmationTag(pub(crate) Mac);
 
 /// GroupInfo (To Be Signed)
@@ -189,6 +200,7 @@ pub struct ConfirmationTag(pub(crate) Mac);
 /// } GroupInfoTBS;
 /// ```
 #[derive(Debug, PartialEq, Clone, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct GroupInfoTBS {
     group_context: GroupContext,
     extensions: Extensions,
@@ -266,6 +278,16 @@ impl GroupInfo {
         &self.payload.confirmation_tag
     }
 
+    /// Returns the leaf index of the signer.
+    pub(crate) fn signer(&self) -> LeafNodeIndex {
+        self.payload.signer
+    }
+
+    /// Returns the signature.
+    pub(crate) fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
     #[cfg(any(feature = "test-utils", test))]
     pub(crate) fn into_verifiable_group_info(self) -> VerifiableGroupInfo {
         VerifiableGroupInfo {
@@ -293,6 +315,7 @@ impl SignedStruct<GroupInfoTBS> for GroupInfo {
 /// serialized group info, it can only be deserialized into a [`VerifiableGroupInfo`], which can
 /// then be turned into a group info as described above.
 #[derive(Debug, PartialEq, Clone, TlsDeserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct VerifiableGroupInfo {
     payload: GroupInfoTBS,
     signature: Signature,
@@ -313,6 +336,22 @@ impl VerifiableGroupInfo {
         self.payload.signer
     }
 
+    /// Get (unverified) group context of the verifiable group info.
+    ///
+    /// Note: This method should only be used when necessary to verify the group info signature
+    /// or to inspect the group info for debugging.
+    pub(crate) fn group_context(&self) -> &GroupContext {
+        &self.payload.group_context
+    }
+
+    /// Get (unverified) confirmation tag of the verifiable group info.
+    ///
+    /// Note: This method should only be used when necessary to verify the group info signature
+    /// or to inspect the group info for debugging.
+    pub(crate) fn confirmation_tag(&self) -> &ConfirmationTag {
+        &self.payload.confirmation_tag
+    }
+
     /// Get (unverified) extensions of the verifiable group info.
     ///
     /// Note: This method should only be used when necessary to verify the group info signature.
@@ -320,6 +359,23 @@ impl VerifiableGroupInfo {
         &self.payload.extensions
     }
 
+    /// Re-signs the group info with the signature key of the
+    /// `credential_bundle` of an external signer, such as the Delivery
+    /// Service, that is not a member of the group. The signature of the
+    /// member is replaced without being verified.
+    ///
+    /// New members only accept the re-signed group info for external commits
+    /// if the signature key is configured as external GroupInfo signer, see
+    /// [`MlsGroupConfigBuilder::external_group_info_signer()`].
+    pub fn resign(
+        self,
+        backend: &impl OpenMlsCryptoProvider,
+        credential_bundle: &CredentialBundle,
+    ) -> Result<GroupInfo, SignatureError> {
+        self.payload
+            .sign(backend, credential_bundle.signature_private_key())
+    }
+
     /// Break the signature for testing purposes.
     #[cfg(test)]
     pub(crate) fn break_signature(&mut self) {
@@ -55,6 +55,7 @@ use tls_codec::{
 )]
 #[repr(u16)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ProposalType {
     Add = 1,
     Update = 2,
@@ -123,6 +124,7 @@ impl TryFrom<u16> for ProposalType {
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Proposal {
     Add(AddProposal),
     Update(UpdateProposal),
@@ -171,6 +173,7 @@ impl Proposal {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AddProposal {
     pub(crate) key_package: KeyPackage,
 }
@@ -189,6 +192,7 @@ impl AddProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UpdateProposal {
     pub(crate) leaf_node: LeafNode,
 }
@@ -206,6 +210,7 @@ impl UpdateProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RemoveProposal {
     pub(crate) removed: LeafNodeIndex,
 }
@@ -221,19 +226,19 @@ impl RemoveProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PreSharedKeyProposal {
     psk: PreSharedKeyId,
 }
 
 impl PreSharedKeyProposal {
     /// Create a new PSK proposal
-    #[cfg(any(feature = "test-utils", test))]
     pub(crate) fn new(psk: PreSharedKeyId) -> Self {
         Self { psk }
     }
 
     /// Returns a reference to the [`PreSharedKeyId`] in this proposal.
-    pub(crate) fn _psk(&self) -> &PreSharedKeyId {
+    pub fn psk(&self) -> &PreSharedKeyId {
         &self.psk
     }
 
@@ -249,20 +254,61 @@ impl PreSharedKeyProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ReInitProposal {
     pub(crate) group_id: GroupId,
     pub(crate) version: ProtocolVersion,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_ciphersuite))]
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) extensions: Extensions,
 }
 
+impl ReInitProposal {
+    /// Create a new ReInit proposal
+    pub(crate) fn new(
+        group_id: GroupId,
+        version: ProtocolVersion,
+        ciphersuite: Ciphersuite,
+        extensions: Extensions,
+    ) -> Self {
+        Self {
+            group_id,
+            version,
+            ciphersuite,
+            extensions,
+        }
+    }
+
+    /// Returns the [`GroupId`] of the new group.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the [`ProtocolVersion`] of the new group.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Returns the [`Ciphersuite`] of the new group.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+
+    /// Returns the [`Extensions`] of the new group.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
 /// ExternalInit Proposal.
 ///
 /// This proposal is used for External Commits only.
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalInitProposal {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     kem_output: VLBytes,
 }
 
@@ -293,6 +339,15 @@ pub struct AppAckProposal {
     received_ranges: TlsVecU32<MessageRange>,
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AppAckProposal {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            received_ranges: u.arbitrary::<Vec<MessageRange>>()?.into(),
+        })
+    }
+}
+
 /// ## Group Context Extensions Proposal
 ///
 /// A GroupContextExtensions proposal is used to update the list of extensions
@@ -304,16 +359,21 @@ pub struct AppAckProposal {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GroupContextExtensionProposal {
     extensions: Extensions,
 }
 
 impl GroupContextExtensionProposal {
     /// Create a new [`GroupContextExtensionProposal`].
-    #[cfg(test)]
     pub(crate) fn new(extensions: Extensions) -> Self {
         Self { extensions }
     }
+
+    /// Returns the extensions of the proposal.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
 }
 
 // Crate-only types
@@ -354,6 +414,7 @@ pub(crate) enum ProposalOrRefType {
 #[repr(u8)]
 #[allow(missing_docs)]
 #[allow(clippy::large_enum_variant)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) enum ProposalOrRef {
     #[tls_codec(discriminant = 1)]
     Proposal(Proposal),
@@ -386,6 +447,7 @@ impl ProposalRef {
 #[derive(
     Debug, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct MessageRange {
     sender: KeyPackageRef,
     first_generation: u32,
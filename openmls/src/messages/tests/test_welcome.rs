@@ -34,7 +34,12 @@ fn generate_credential_bundle(
     signature_algorithm: SignatureScheme,
     backend: &impl OpenMlsCryptoProvider,
 ) -> Result<CredentialBundle, CredentialError> {
-    let cb = CredentialBundle::new(identity, credential_type, signature_algorithm, backend)?;
+    let cb = CredentialBundle::new(
+        identity.into(),
+        credential_type,
+        signature_algorithm,
+        backend,
+    )?;
     let credential = cb.credential().clone();
     backend
         .key_store()
@@ -239,6 +244,7 @@ fn test_welcome_message_with_version(
     // We use this dummy group info in all test cases.
     let group_info_tbs = {
         let group_context = GroupContext::new(
+            version,
             ciphersuite,
             GroupId::random(backend),
             123,
@@ -2,7 +2,16 @@
 //! Include this to get access to all the public functions of OpenMLS.
 
 // MlsGroup
-pub use crate::group::{config::CryptoConfig, core_group::Member, errors::*, ser::*, *};
+pub use crate::group::{
+    config::CryptoConfig,
+    core_group::{Member, MemberDetails},
+    errors::*,
+    ser::*,
+    *,
+};
+
+// MlsClient
+pub use crate::client::{errors::*, *};
 
 // Ciphersuite
 pub use crate::ciphersuite::{hash_ref::KeyPackageRef, signable::*, signature::*, *};
@@ -20,7 +29,14 @@ pub use crate::versions::*;
 pub use crate::extensions::{errors::*, *};
 
 // Framing
-pub use crate::framing::{message_in::*, message_out::*, sender::*, validation::*};
+pub use crate::framing::{message_in::*, message_out::*, sender::*, stream::*, validation::*};
+
+// Text encodings
+#[cfg(feature = "text-encoding")]
+pub use crate::text_encoding::*;
+
+// Debug representation
+pub use crate::debug::{DebugTree, Inspect};
 
 // Key packages
 pub use crate::key_packages::{errors::*, *};
@@ -35,18 +51,23 @@ pub use crate::tree::sender_ratchet::SenderRatchetConfiguration;
 pub use crate::binary_tree::LeafNodeIndex;
 
 // TreeSync
+#[cfg(feature = "partial-tree")]
+pub use crate::treesync::{errors::PartialTreeError, PartialTree};
 pub use crate::treesync::{
     errors::{ApplyUpdatePathError, PublicTreeError},
-    node::leaf_node::LeafNode,
+    node::leaf_node::{
+        is_grease, Capabilities, CapabilitiesBuilder, CapabilitiesError, LeafNode, LeafNodeSource,
+        Lifetime, GREASE_VALUES,
+    },
     node::parent_node::ParentNode,
     node::Node,
 };
 
 // PSKs
-// TODO #751
-// pub use crate::schedule::psk::{
-//    BranchPsk, ExternalPsk, PreSharedKeyId, PreSharedKeys, Psk, PskBundle, PskType, ReinitPsk,
-// };
+pub use crate::schedule::{
+    errors::{PskError, WritePskError},
+    psk::{ExternalPsk, PreSharedKeyId, Psk, ResumptionPsk, ResumptionPskUsage},
+};
 
 // TLS codec traits
 pub use tls_codec::{
@@ -58,6 +79,12 @@ pub use crate::error::*;
 
 // OpenMLS traits
 pub use openmls_traits::{
-    crypto::OpenMlsCrypto, key_store::OpenMlsKeyStore, random::OpenMlsRand, types::*,
+    crypto::OpenMlsCrypto,
+    key_store::{KeyStoreId, OpenMlsKeyStore},
+    random::OpenMlsRand,
+    types::*,
     OpenMlsCryptoProvider,
 };
+
+#[cfg(feature = "async")]
+pub use openmls_traits::async_key_store::{AsyncOpenMlsKeyStore, KeyStoreFuture};
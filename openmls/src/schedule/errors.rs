@@ -3,7 +3,7 @@
 use openmls_traits::types::CryptoError;
 use thiserror::Error;
 
-use crate::error::LibraryError;
+use crate::error::{error_codes, LibraryError};
 
 // === Public ===
 
@@ -21,6 +21,28 @@ pub enum PskError {
     KeyNotFound,
 }
 
+error_codes!(PskError: 4100 {
+    LibraryError = 1,
+    TooManyKeys = 2,
+    KeyNotFound = 3,
+});
+
+/// Write PSK error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum WritePskError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// Error writing the PSK to the key store.
+    #[error("Error writing the PSK to the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
+error_codes!(WritePskError<KeyStoreError>: 4200 {
+    LibraryError = 1,
+    KeyStoreError = 2,
+});
+
 // === Crate ===
 
 /// Key schedule state error
@@ -141,6 +141,7 @@ fn generate(
         .expect("An unexpected error occurred.");
 
     let group_context = GroupContext::new(
+        ProtocolVersion::default(),
         ciphersuite,
         GroupId::from_slice(group_id),
         epoch,
@@ -238,11 +239,11 @@ pub fn generate_test_vector(n_epochs: u64, ciphersuite: Ciphersuite) -> KeySched
             sender_data_secret: bytes_to_hex(epoch_secrets.sender_data_secret().as_slice()),
             encryption_secret: bytes_to_hex(epoch_secrets.encryption_secret().as_slice()),
             exporter_secret: bytes_to_hex(epoch_secrets.exporter_secret().as_slice()),
-            epoch_authenticator: bytes_to_hex(epoch_secrets.epoch_authenticator().as_slice()),
+            epoch_authenticator: bytes_to_hex(epoch_secrets.epoch_authenticator().expose()),
             external_secret: bytes_to_hex(epoch_secrets.external_secret().as_slice()),
             confirmation_key: bytes_to_hex(epoch_secrets.confirmation_key().as_slice()),
             membership_key: bytes_to_hex(epoch_secrets.membership_key().as_slice()),
-            resumption_psk: bytes_to_hex(epoch_secrets.resumption_psk().as_slice()),
+            resumption_psk: bytes_to_hex(epoch_secrets.resumption_psk().expose()),
             external_pub: bytes_to_hex(
                 &HpkePublicKey::from(external_key_pair.public)
                     .tls_serialize_detached()
@@ -393,6 +394,7 @@ pub fn run_test_vector(
         let confirmed_transcript_hash = hex_to_bytes(&epoch.confirmed_transcript_hash);
 
         let group_context = GroupContext::new(
+            ProtocolVersion::default(),
             ciphersuite,
             GroupId::from_slice(&group_id),
             i as u64,
@@ -455,8 +457,7 @@ pub fn run_test_vector(
             }
             return Err(KsTestVectorError::ExporterSecretMismatch);
         }
-        if hex_to_bytes(&epoch.epoch_authenticator)
-            != epoch_secrets.epoch_authenticator().as_slice()
+        if hex_to_bytes(&epoch.epoch_authenticator) != epoch_secrets.epoch_authenticator().expose()
         {
             if cfg!(test) {
                 panic!("Epoch authenticator mismatch");
@@ -481,7 +482,7 @@ pub fn run_test_vector(
             }
             return Err(KsTestVectorError::MembershipKeyMismatch);
         }
-        if hex_to_bytes(&epoch.resumption_psk) != epoch_secrets.resumption_psk().as_slice() {
+        if hex_to_bytes(&epoch.resumption_psk) != epoch_secrets.resumption_psk().expose() {
             if cfg!(test) {
                 panic!("Resumption psk mismatch");
             }
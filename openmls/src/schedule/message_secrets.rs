@@ -31,6 +31,14 @@ impl MessageSecrets {
         }
     }
 
+    /// Overwrite the message secrets and the secret tree with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        self.sender_data_secret.secret.zeroize();
+        self.membership_key.secret.zeroize();
+        self.confirmation_key.secret.zeroize();
+        self.secret_tree.zeroize();
+    }
+
     /// Get a reference to the message secrets's sender data secret.
     pub(crate) fn sender_data_secret(&self) -> &SenderDataSecret {
         &self.sender_data_secret
@@ -51,6 +59,11 @@ impl MessageSecrets {
         self.serialized_context.as_ref()
     }
 
+    /// Get a reference to the message secrets's secret tree.
+    pub(crate) fn secret_tree(&self) -> &SecretTree {
+        &self.secret_tree
+    }
+
     /// Get a mutable reference to the message secrets's secret tree.
     pub(crate) fn secret_tree_mut(&mut self) -> &mut SecretTree {
         &mut self.secret_tree
@@ -1,7 +1,7 @@
 //! # Key schedule
 //!
 //! This module contains the types and implementations for key schedule operations.
-//! It exposes the [`EpochAuthenticator`] & [`ResumptionPskSecret`].
+//! It exposes the [`EpochAuthenticator`], [`ResumptionPskSecret`] & [`ExportedSecret`].
 
 // Internal documentation
 //
@@ -123,7 +123,7 @@
 
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
-    ciphersuite::{AeadKey, AeadNonce, HpkePrivateKey, Mac, Secret},
+    ciphersuite::{equal_ct, AeadKey, AeadNonce, HpkePrivateKey, Mac, Secret},
     error::LibraryError,
     framing::{mls_content::AuthenticatedContentTbm, MembershipTag},
     messages::{ConfirmationTag, GroupInfo, PathSecret},
@@ -136,10 +136,10 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 
 // Public
 pub mod errors;
+pub mod psk;
 
 // Crate
 pub(crate) mod message_secrets;
-pub(crate) mod psk;
 
 // Private
 use errors::*;
@@ -172,10 +172,22 @@ impl ResumptionPskSecret {
         Ok(Self { secret })
     }
 
-    /// Returns the secret as a slice.
-    pub fn as_slice(&self) -> &[u8] {
+    /// Returns the bytes of the secret. Copies of the bytes are not
+    /// overwritten when the secret is dropped.
+    pub fn expose(&self) -> &[u8] {
         self.secret.as_slice()
     }
+
+    /// Overwrite the secret with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl Drop for ResumptionPskSecret {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
 }
 
 /// A secret that can be used among members to make sure everyone has the same
@@ -198,10 +210,80 @@ impl EpochAuthenticator {
         Ok(Self { secret })
     }
 
-    /// Returns the secret as a slice.
-    pub fn as_slice(&self) -> &[u8] {
+    /// Returns the bytes of the secret. Copies of the bytes are not
+    /// overwritten when the secret is dropped.
+    pub fn expose(&self) -> &[u8] {
         self.secret.as_slice()
     }
+
+    /// Derive `length` bytes with the given `label` and `context` from the
+    /// authenticator, e.g. for a short authentication string.
+    pub(crate) fn derive_bytes(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        context: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        Ok(self
+            .secret
+            .kdf_expand_label(backend, label, context, length)?
+            .as_slice()
+            .to_vec())
+    }
+}
+
+impl Drop for EpochAuthenticator {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+/// A secret exported from a group, see
+/// [`MlsGroup::export_secret()`](crate::group::MlsGroup::export_secret()). The
+/// secret is overwritten with zeros when it is dropped.
+///
+/// Exported secrets are compared in constant time and their [`Debug`]
+/// implementation doesn't print the secret.
+#[derive(Clone)]
+pub struct ExportedSecret {
+    secret: Secret,
+}
+
+impl std::fmt::Debug for ExportedSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ExportedSecret { *** }")
+    }
+}
+
+impl PartialEq for ExportedSecret {
+    // Constant time comparison. The length of the secrets is public.
+    fn eq(&self, other: &Self) -> bool {
+        self.expose().len() == other.expose().len() && equal_ct(self.expose(), other.expose())
+    }
+}
+
+impl Eq for ExportedSecret {}
+
+impl ExportedSecret {
+    /// Returns the bytes of the secret. Copies of the bytes are not
+    /// overwritten when the secret is dropped.
+    pub fn expose(&self) -> &[u8] {
+        self.secret.as_slice()
+    }
+
+    /// Moves the bytes of the secret out of the exported secret without
+    /// copying them, e.g. into another type that overwrites them when it is
+    /// dropped.
+    pub(crate) fn into_bytes(mut self) -> Vec<u8> {
+        self.secret.take_value()
+    }
+}
+
+impl Drop for ExportedSecret {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
 }
 
 // Crate-only types
@@ -415,6 +497,70 @@ pub(crate) struct KeySchedule {
     intermediate_secret: Option<IntermediateSecret>,
     epoch_secret: Option<EpochSecret>,
     state: State,
+    #[cfg(any(feature = "test-utils", test))]
+    secrets: KeyScheduleSecrets,
+}
+
+/// The intermediate values of the key schedule of an epoch, for protocol
+/// research and KAT tooling. See [`MlsGroup::key_schedule_secrets()`].
+///
+/// ```text
+///                   init_secret_[n-1]
+///                         |
+///                         V
+///    commit_secret -> KDF.Extract
+///                         |
+///                         V
+///                   DeriveSecret(., "joiner")
+///                         |
+///                         V
+///                    joiner_secret
+///                         |
+///                         V
+/// psk_secret (or 0) -> KDF.Extract
+///                         |
+///                         +--> DeriveSecret(., "welcome")
+///                         |    = welcome_secret
+///                         |
+///                         V
+///                   ExpandWithLabel(., "epoch", GroupContext_[n], KDF.Nh)
+///                         |
+///                         V
+///                    epoch_secret
+/// ```
+///
+/// All other secrets are derived from the `epoch_secret`.
+///
+/// [`MlsGroup::key_schedule_secrets()`]: crate::group::MlsGroup::key_schedule_secrets()
+#[cfg(any(feature = "test-utils", test))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyScheduleSecrets {
+    /// The `joiner_secret`.
+    pub joiner_secret: Vec<u8>,
+    /// The `psk_secret`, or [`None`] if no PSK was injected.
+    pub psk_secret: Option<Vec<u8>>,
+    /// The `welcome_secret`.
+    pub welcome_secret: Vec<u8>,
+    /// The `epoch_secret`.
+    pub epoch_secret: Vec<u8>,
+    /// The `sender_data_secret` derived from the `epoch_secret`.
+    pub sender_data_secret: Vec<u8>,
+    /// The `encryption_secret` derived from the `epoch_secret`.
+    pub encryption_secret: Vec<u8>,
+    /// The `exporter_secret` derived from the `epoch_secret`.
+    pub exporter_secret: Vec<u8>,
+    /// The `epoch_authenticator` derived from the `epoch_secret`.
+    pub epoch_authenticator: Vec<u8>,
+    /// The `external_secret` derived from the `epoch_secret`.
+    pub external_secret: Vec<u8>,
+    /// The `confirmation_key` derived from the `epoch_secret`.
+    pub confirmation_key: Vec<u8>,
+    /// The `membership_key` derived from the `epoch_secret`.
+    pub membership_key: Vec<u8>,
+    /// The `resumption_psk` derived from the `epoch_secret`.
+    pub resumption_psk: Vec<u8>,
+    /// The `init_secret` of the next epoch derived from the `epoch_secret`.
+    pub init_secret: Vec<u8>,
 }
 
 impl KeySchedule {
@@ -433,13 +579,27 @@ impl KeySchedule {
         );
         let psk = psk.into();
         log_crypto!(trace, "  {}", if psk.is_some() { "with PSK" } else { "" });
+        #[cfg(any(feature = "test-utils", test))]
+        let psk_secret = psk.as_ref().map(|psk| psk.secret().as_slice().to_vec());
         let intermediate_secret = IntermediateSecret::new(backend, &joiner_secret, psk)
             .map_err(LibraryError::unexpected_crypto_error)?;
+        #[cfg(any(feature = "test-utils", test))]
+        let secrets = KeyScheduleSecrets {
+            joiner_secret: joiner_secret.as_slice().to_vec(),
+            psk_secret,
+            welcome_secret: WelcomeSecret::new(backend, &intermediate_secret)
+                .map_err(LibraryError::unexpected_crypto_error)?
+                .as_slice()
+                .to_vec(),
+            ..Default::default()
+        };
         Ok(Self {
             ciphersuite,
             intermediate_secret: Some(intermediate_secret),
             epoch_secret: None,
             state: State::Initial,
+            #[cfg(any(feature = "test-utils", test))]
+            secrets,
         })
     }
 
@@ -522,7 +682,27 @@ impl KeySchedule {
             None => return Err(LibraryError::custom("state machine error").into()),
         };
 
-        Ok(EpochSecrets::new(backend, epoch_secret)?)
+        #[cfg(any(feature = "test-utils", test))]
+        let epoch_secret_bytes = epoch_secret.secret.as_slice().to_vec();
+        #[allow(unused_mut)]
+        let mut epoch_secrets = EpochSecrets::new(backend, epoch_secret)?;
+        #[cfg(any(feature = "test-utils", test))]
+        {
+            epoch_secrets.key_schedule_secrets = Some(KeyScheduleSecrets {
+                epoch_secret: epoch_secret_bytes,
+                sender_data_secret: epoch_secrets.sender_data_secret.as_slice().to_vec(),
+                encryption_secret: epoch_secrets.encryption_secret.as_slice().to_vec(),
+                exporter_secret: epoch_secrets.exporter_secret.as_slice().to_vec(),
+                epoch_authenticator: epoch_secrets.epoch_authenticator.expose().to_vec(),
+                external_secret: epoch_secrets.external_secret.as_slice().to_vec(),
+                confirmation_key: epoch_secrets.confirmation_key.as_slice().to_vec(),
+                membership_key: epoch_secrets.membership_key.as_slice().to_vec(),
+                resumption_psk: epoch_secrets.resumption_psk.expose().to_vec(),
+                init_secret: epoch_secrets.init_secret.as_slice().to_vec(),
+                ..self.secrets.clone()
+            });
+        }
+        Ok(epoch_secrets)
     }
 }
 
@@ -694,7 +874,7 @@ impl EncryptionSecret {
 }
 
 /// A secret that we can derive secrets from, that are used outside of OpenMLS.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct ExporterSecret {
     secret: Secret,
@@ -715,9 +895,9 @@ impl ExporterSecret {
         self.secret.as_slice()
     }
 
-    /// Derive a `Secret` from the exporter secret. We return `Vec<u8>` here, so
-    /// it can be used outside of OpenMLS. This function is made available for
-    /// use from the outside through [`crate::group::core_group::export_secret`].
+    /// Derive an [`ExportedSecret`] from the exporter secret, which can be used
+    /// outside of OpenMLS. This function is made available for use from the
+    /// outside through [`MlsGroup::export_secret()`](crate::group::MlsGroup::export_secret()).
     pub(crate) fn derive_exported_secret(
         &self,
         ciphersuite: Ciphersuite,
@@ -725,16 +905,15 @@ impl ExporterSecret {
         label: &str,
         context: &[u8],
         key_length: usize,
-    ) -> Result<Vec<u8>, CryptoError> {
+    ) -> Result<ExportedSecret, CryptoError> {
         let context_hash = &backend
             .crypto()
             .hash(ciphersuite.hash_algorithm(), context)?;
-        Ok(self
+        let secret = self
             .secret
             .derive_secret(backend, label)?
-            .kdf_expand_label(backend, label, context_hash, key_length)?
-            .as_slice()
-            .to_vec())
+            .kdf_expand_label(backend, label, context_hash, key_length)?;
+        Ok(ExportedSecret { secret })
     }
 }
 
@@ -835,6 +1014,33 @@ impl ConfirmationKey {
     }
 }
 
+/// Verify the `confirmation_tag` of a commit given the `confirmation_key` and
+/// the `confirmed_transcript_hash` of the epoch the commit leads to, without
+/// constructing a group.
+///
+/// >  11.2. Commit
+///
+/// ```text
+/// PublicMessage.confirmation_tag =
+///     MAC(confirmation_key, GroupContext.confirmed_transcript_hash)
+/// ```
+///
+/// Returns `Ok(false)` if the tag doesn't match and an error if the tag can't
+/// be computed.
+pub fn verify_confirmation_tag(
+    backend: &impl OpenMlsCryptoProvider,
+    ciphersuite: Ciphersuite,
+    confirmation_key: &[u8],
+    confirmed_transcript_hash: &[u8],
+    confirmation_tag: &ConfirmationTag,
+) -> Result<bool, CryptoError> {
+    let confirmation_key = ConfirmationKey {
+        secret: Secret::from_slice(confirmation_key, ProtocolVersion::default(), ciphersuite),
+    };
+    let my_confirmation_tag = confirmation_key.tag(backend, confirmed_transcript_hash)?;
+    Ok(&my_confirmation_tag == confirmation_tag)
+}
+
 /// The membership key is used to calculate the `MembershipTag`.
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -1016,6 +1222,8 @@ pub(crate) struct EpochSecrets {
     confirmation_key: ConfirmationKey,
     membership_key: MembershipKey,
     resumption_psk: ResumptionPskSecret,
+    #[cfg(any(feature = "test-utils", test))]
+    key_schedule_secrets: Option<KeyScheduleSecrets>,
 }
 
 impl std::fmt::Debug for EpochSecrets {
@@ -1136,6 +1344,8 @@ impl EpochSecrets {
             confirmation_key,
             membership_key,
             resumption_psk,
+            #[cfg(any(feature = "test-utils", test))]
+            key_schedule_secrets: None,
         })
     }
 
@@ -1178,6 +1388,8 @@ impl EpochSecrets {
                 epoch_authenticator: self.epoch_authenticator,
                 external_secret: self.external_secret,
                 resumption_psk: self.resumption_psk,
+                #[cfg(any(feature = "test-utils", test))]
+                key_schedule_secrets: self.key_schedule_secrets,
             },
             MessageSecrets::new(
                 self.sender_data_secret,
@@ -1197,6 +1409,10 @@ pub(crate) struct GroupEpochSecrets {
     epoch_authenticator: EpochAuthenticator,
     external_secret: ExternalSecret,
     resumption_psk: ResumptionPskSecret,
+    // The key schedule secrets are not persisted.
+    #[cfg(any(feature = "test-utils", test))]
+    #[serde(skip)]
+    key_schedule_secrets: Option<KeyScheduleSecrets>,
 }
 
 impl std::fmt::Debug for GroupEpochSecrets {
@@ -1224,6 +1440,15 @@ impl PartialEq for GroupEpochSecrets {
 }
 
 impl GroupEpochSecrets {
+    /// Overwrite all epoch secrets with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        self.init_secret.secret.zeroize();
+        self.exporter_secret.secret.zeroize();
+        self.epoch_authenticator.secret.zeroize();
+        self.external_secret.secret.zeroize();
+        self.resumption_psk.zeroize();
+    }
+
     /// Init secret
     pub(crate) fn init_secret(&self) -> &InitSecret {
         &self.init_secret
@@ -1248,4 +1473,11 @@ impl GroupEpochSecrets {
     pub(crate) fn resumption_psk(&self) -> &ResumptionPskSecret {
         &self.resumption_psk
     }
+
+    /// The intermediate values of the key schedule this epoch was derived
+    /// with, if the epoch was derived since the group was loaded.
+    #[cfg(any(feature = "test-utils", test))]
+    pub(crate) fn key_schedule_secrets(&self) -> Option<&KeyScheduleSecrets> {
+        self.key_schedule_secrets.as_ref()
+    }
 }
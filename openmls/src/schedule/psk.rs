@@ -33,6 +33,7 @@ use tls_codec::{Serialize as TlsSerializeTrait, VLBytes};
 )]
 #[repr(u8)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ResumptionPskUsage {
     Application = 1,
     Reinit = 2,
@@ -43,7 +44,9 @@ pub enum ResumptionPskUsage {
 #[derive(
     Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExternalPsk {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     psk_id: VLBytes,
 }
 
@@ -59,6 +62,19 @@ impl ExternalPsk {
     pub fn psk_id(&self) -> &[u8] {
         self.psk_id.as_slice()
     }
+
+    /// Write the secret `psk` for this `ExternalPsk` to the key store with
+    /// [`OpenMlsKeyStore::store_psk()`]. In contrast to
+    /// [`PreSharedKeyId::write_to_key_store()`], the PSK is indexed by the PSK
+    /// ID only and can thus be injected into the key schedule for any
+    /// `psk_nonce`, e.g. when joining a group from a Welcome message.
+    pub fn write_to_key_store<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        psk: &[u8],
+    ) -> Result<(), KeyStore::Error> {
+        backend.key_store().store_psk(self.psk_id(), psk)
+    }
 }
 
 /// Contains the secret part of the PSK as well as the
@@ -85,6 +101,7 @@ impl PskBundle {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ResumptionPsk {
     pub(crate) usage: ResumptionPskUsage,
     pub(crate) psk_group_id: GroupId,
@@ -115,6 +132,51 @@ impl ResumptionPsk {
     pub fn psk_epoch(&self) -> GroupEpoch {
         self.psk_epoch
     }
+
+    /// Write the resumption `secret` to the key store.
+    ///
+    /// In contrast to external PSKs, resumption PSKs are indexed by the
+    /// `ResumptionPsk` only, i.e. without the `psk_nonce`. This way they can be
+    /// written to the key store before the [`PreSharedKeyId`] that is used in a
+    /// Commit or Welcome message is known.
+    pub(crate) fn write_to_key_store<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+        secret: &[u8],
+    ) -> Result<(), WritePskError<KeyStore::Error>> {
+        let psk_bundle = PskBundle {
+            secret: Secret::from_slice(secret, ProtocolVersion::default(), ciphersuite),
+        };
+        backend
+            .key_store()
+            .store(
+                &self
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+                &psk_bundle,
+            )
+            .map_err(WritePskError::KeyStoreError)
+    }
+
+    /// Delete the resumption secret from the key store. Returns `true` if
+    /// there was a secret to delete.
+    pub(crate) fn delete_from_key_store<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<bool, WritePskError<KeyStore::Error>> {
+        let id = self
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        if backend.key_store().read::<PskBundle>(&id).is_none() {
+            return Ok(false);
+        }
+        backend
+            .key_store()
+            .delete(&id)
+            .map_err(WritePskError::KeyStoreError)?;
+        Ok(true)
+    }
 }
 
 /// PSK enum that can contain the different PSK types
@@ -123,6 +185,7 @@ impl ResumptionPsk {
 )]
 #[allow(missing_docs)]
 #[repr(u8)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Psk {
     #[tls_codec(discriminant = 1)]
     External(ExternalPsk),
@@ -149,8 +212,10 @@ pub enum Psk {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PreSharedKeyId {
     pub(crate) psk: Psk,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     pub(crate) psk_nonce: VLBytes,
 }
 
@@ -179,6 +244,29 @@ impl PreSharedKeyId {
     pub fn psk_nonce(&self) -> &[u8] {
         self.psk_nonce.as_slice()
     }
+
+    /// Write the secret `psk` for this `PreSharedKeyId` to the key store, so
+    /// that it can be injected into the key schedule of a group with the given
+    /// `ciphersuite`.
+    pub fn write_to_key_store<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+        psk: &[u8],
+    ) -> Result<(), WritePskError<KeyStore::Error>> {
+        let psk_bundle = PskBundle {
+            secret: Secret::from_slice(psk, ProtocolVersion::default(), ciphersuite),
+        };
+        backend
+            .key_store()
+            .store(
+                &self
+                    .tls_serialize_detached()
+                    .map_err(LibraryError::missing_bound_check)?,
+                &psk_bundle,
+            )
+            .map_err(WritePskError::KeyStoreError)
+    }
 }
 
 /// `PskLabel` is used in the final concatentation of PSKs before they are
@@ -240,7 +328,36 @@ impl PskSecret {
             ) {
                 psk_bundles.push(psk_bundle);
             } else {
-                return Err(PskError::KeyNotFound);
+                match psk_id.psk() {
+                    // External PSKs can also be stored by their PSK ID only.
+                    // See `OpenMlsKeyStore::store_psk()`.
+                    Psk::External(external_psk) => {
+                        let psk = backend
+                            .key_store()
+                            .read_psk(external_psk.psk_id())
+                            .ok_or(PskError::KeyNotFound)?;
+                        psk_bundles.push(PskBundle {
+                            secret: Secret::from_slice(
+                                &psk,
+                                ProtocolVersion::default(),
+                                ciphersuite,
+                            ),
+                        });
+                    }
+                    // Resumption PSKs can also be stored without a nonce. See
+                    // `ResumptionPsk::write_to_key_store()`.
+                    Psk::Resumption(resumption_psk) => {
+                        let psk_bundle = backend
+                            .key_store()
+                            .read(
+                                &resumption_psk
+                                    .tls_serialize_detached()
+                                    .map_err(LibraryError::missing_bound_check)?,
+                            )
+                            .ok_or(PskError::KeyNotFound)?;
+                        psk_bundles.push(psk_bundle);
+                    }
+                }
             }
         }
 
@@ -248,8 +365,11 @@ impl PskSecret {
         let mut psk_secret = Secret::zero(ciphersuite, mls_version);
         for ((index, psk_bundle), psk_id) in psk_bundles.iter().enumerate().zip(psk_ids) {
             let zero_secret = Secret::zero(ciphersuite, mls_version);
+            // The PSK might stem from a group with a different ciphersuite,
+            // e.g. in case of a resumption PSK after a ReInit.
+            let psk = Secret::from_slice(psk_bundle.secret().as_slice(), mls_version, ciphersuite);
             let psk_extracted = zero_secret
-                .hkdf_extract(backend, psk_bundle.secret())
+                .hkdf_extract(backend, &psk)
                 .map_err(LibraryError::unexpected_crypto_error)?;
             let psk_label = PskLabel::new(psk_id, index as u16, num_psks)
                 .tls_serialize_detached()
@@ -10,7 +10,7 @@ use crate::{
     ciphersuite::Secret, schedule::psk::PskBundle, schedule::psk::*, versions::ProtocolVersion,
 };
 
-use super::PskSecret;
+use super::{verify_confirmation_tag, ConfirmationKey, PskSecret};
 
 #[apply(ciphersuites_and_backends)]
 fn test_psks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
@@ -54,3 +54,34 @@ fn test_psks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     let _psk_secret =
         PskSecret::new(ciphersuite, backend, &psk_ids).expect("Could not calculate PSK secret.");
 }
+
+#[apply(ciphersuites_and_backends)]
+fn test_verify_confirmation_tag(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let confirmation_key = ConfirmationKey::random(ciphersuite, backend);
+    let confirmed_transcript_hash = backend
+        .rand()
+        .random_vec(ciphersuite.hash_length())
+        .expect("Not enough randomness.");
+    let confirmation_tag = confirmation_key
+        .tag(backend, &confirmed_transcript_hash)
+        .expect("Could not compute confirmation tag.");
+
+    assert!(verify_confirmation_tag(
+        backend,
+        ciphersuite,
+        confirmation_key.as_slice(),
+        &confirmed_transcript_hash,
+        &confirmation_tag,
+    )
+    .expect("Could not verify confirmation tag."));
+
+    // A different transcript hash results in a different tag.
+    assert!(!verify_confirmation_tag(
+        backend,
+        ciphersuite,
+        confirmation_key.as_slice(),
+        &[0u8; 32],
+        &confirmation_tag,
+    )
+    .expect("Could not verify confirmation tag."));
+}
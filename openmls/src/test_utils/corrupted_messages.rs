@@ -0,0 +1,146 @@
+//! Utilities to make sure that corrupted messages from untrusted peers are
+//! rejected with an error instead of causing a panic.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::{
+    credentials::CredentialBundle,
+    framing::{MlsMessageIn, MlsMessageInBody, ProtocolMessage},
+    group::{MlsGroup, MlsGroupConfig},
+};
+
+/// Returns corrupted variants of the given serialized message: every
+/// truncation of the message as well as one variant per byte in which that
+/// byte is flipped.
+pub fn corrupted_variants(serialized_message: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    let truncations = (0..serialized_message.len()).map(|len| serialized_message[..len].to_vec());
+    let bit_flips = (0..serialized_message.len()).map(|position| {
+        let mut corrupted = serialized_message.to_vec();
+        corrupted[position] ^= 0xff;
+        corrupted
+    });
+    truncations.chain(bit_flips)
+}
+
+/// Feeds all [`corrupted_variants()`] of the serialized message to
+/// [`MlsGroup::process_message()`] and asserts that none of them causes a
+/// panic. Each corrupted message is processed by a fresh copy of `group`, so
+/// that the state of `group` is not changed.
+///
+/// Variants that can't be decoded are skipped, as are variants that decode to
+/// something other than a [`ProtocolMessage`]. Returns the number of
+/// corrupted messages that were processed.
+pub fn assert_no_panic_on_corrupted_message(
+    group: &mut MlsGroup,
+    backend: &impl OpenMlsCryptoProvider,
+    serialized_message: &[u8],
+) -> usize {
+    let mut snapshot = vec![];
+    group
+        .save(&mut snapshot)
+        .expect("Could not create snapshot of the group.");
+
+    assert_no_panic_on_corrupted_variants(serialized_message, |message| {
+        let protocol_message: ProtocolMessage = match message {
+            MlsMessageInBody::PublicMessage(m) => m.into(),
+            MlsMessageInBody::PrivateMessage(m) => m.into(),
+            _ => return false,
+        };
+        let mut group_copy =
+            MlsGroup::load(snapshot.as_slice()).expect("Could not load group snapshot.");
+        // The result doesn't matter, processing must just not panic.
+        let _ = group_copy.process_message(backend, protocol_message);
+        true
+    })
+}
+
+/// Feeds all [`corrupted_variants()`] of the serialized [`Welcome`] to
+/// [`MlsGroup::new_from_welcome()`] and asserts that none of them causes a
+/// panic. The key package the client was added with must be in the key store
+/// of `backend`. It is kept there for all variants, regardless of
+/// `mls_group_config`.
+///
+/// Variants that don't decode to a [`Welcome`] are skipped. Returns the number
+/// of corrupted Welcome messages that were processed.
+///
+/// [`Welcome`]: crate::messages::Welcome
+pub fn assert_no_panic_on_corrupted_welcome(
+    backend: &impl OpenMlsCryptoProvider,
+    mls_group_config: &MlsGroupConfig,
+    serialized_welcome: &[u8],
+) -> usize {
+    let mut mls_group_config = mls_group_config.clone();
+    mls_group_config.keep_consumed_key_packages = true;
+
+    assert_no_panic_on_corrupted_variants(serialized_welcome, |message| {
+        let welcome = match message {
+            MlsMessageInBody::Welcome(welcome) => welcome,
+            _ => return false,
+        };
+        // The result doesn't matter, joining must just not panic.
+        let _ = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, None);
+        true
+    })
+}
+
+/// Feeds all [`corrupted_variants()`] of the serialized [`GroupInfo`] to
+/// [`MlsGroup::join_by_external_commit()`] and asserts that none of them
+/// causes a panic. The group info has to contain the ratchet tree.
+///
+/// Variants that don't decode to a [`GroupInfo`] are skipped. Returns the
+/// number of corrupted group infos that were processed.
+///
+/// [`GroupInfo`]: crate::messages::GroupInfo
+pub fn assert_no_panic_on_corrupted_group_info(
+    backend: &impl OpenMlsCryptoProvider,
+    mls_group_config: &MlsGroupConfig,
+    credential_bundle: &CredentialBundle,
+    serialized_group_info: &[u8],
+) -> usize {
+    assert_no_panic_on_corrupted_variants(serialized_group_info, |message| {
+        let verifiable_group_info = match message {
+            MlsMessageInBody::GroupInfo(group_info) => group_info,
+            _ => return false,
+        };
+        // The result doesn't matter, joining must just not panic.
+        let _ = MlsGroup::join_by_external_commit(
+            backend,
+            None,
+            verifiable_group_info,
+            mls_group_config,
+            &[],
+            credential_bundle,
+        );
+        true
+    })
+}
+
+/// Decodes all [`corrupted_variants()`] of the serialized message and passes
+/// the ones that can be decoded to `process`, which returns whether it
+/// processed the message. Panics if `process` panics and returns the number of
+/// processed messages.
+fn assert_no_panic_on_corrupted_variants(
+    serialized_message: &[u8],
+    mut process: impl FnMut(MlsMessageInBody) -> bool,
+) -> usize {
+    let mut processed = 0;
+    for corrupted in corrupted_variants(serialized_message) {
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            match MlsMessageIn::try_from_bytes(&corrupted).map(|m| m.extract()) {
+                Ok(message) => process(message),
+                Err(_) => false,
+            }
+        }));
+        match result {
+            Ok(true) => processed += 1,
+            Ok(false) => {}
+            Err(_) => panic!(
+                "Processing a corrupted message panicked: {}",
+                super::bytes_to_hex(&corrupted)
+            ),
+        }
+    }
+    processed
+}
@@ -0,0 +1,365 @@
+//! Generators for groups that went through random churn, see
+//! [`GroupFixtureBuilder`].
+
+use ::rand::{rngs::StdRng, Rng, SeedableRng};
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::{key_store::OpenMlsKeyStore, types::Ciphersuite, OpenMlsCryptoProvider};
+use tls_codec::Serialize;
+
+use crate::{
+    credentials::{Credential, CredentialBundle, CredentialType},
+    framing::{MlsMessageOut, ProcessedMessageContent},
+    group::{config::CryptoConfig, GroupEpoch, MlsGroup, MlsGroupConfig},
+    key_packages::KeyPackage,
+    treesync::node::leaf_node::Capabilities,
+};
+
+/// The relative weights of the operations that are committed in every epoch
+/// of the churn. An operation with weight `0` is never chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Churn {
+    /// The weight of adding a new member.
+    pub adds: u32,
+    /// The weight of removing a random member.
+    pub removes: u32,
+    /// The weight of a self update of the committer.
+    pub updates: u32,
+}
+
+impl Default for Churn {
+    fn default() -> Self {
+        Self {
+            adds: 1,
+            removes: 1,
+            updates: 1,
+        }
+    }
+}
+
+/// A member of a [`GroupFixture`] with its own backend.
+#[derive(Debug)]
+pub struct FixtureMember {
+    /// The identity of the member.
+    pub identity: Vec<u8>,
+    /// The credential of the member.
+    pub credential: Credential,
+    /// The backend of the member that holds its keys.
+    pub backend: OpenMlsRustCrypto,
+    /// The group state of the member.
+    pub group: MlsGroup,
+}
+
+/// A builder for a [`GroupFixture`].
+///
+/// The group is created by a single member, who adds the other initial
+/// members with one commit. Then, in each of the `epochs`, a random member
+/// commits a random operation, which is chosen according to the [`Churn`].
+/// All members process and merge every commit. The random choices are drawn
+/// from a random number generator seeded with `seed`, so that a fixture can
+/// be reproduced.
+#[derive(Debug, Clone)]
+pub struct GroupFixtureBuilder {
+    ciphersuite: Ciphersuite,
+    members: usize,
+    epochs: usize,
+    churn: Churn,
+    seed: u64,
+    mls_group_config: MlsGroupConfig,
+}
+
+impl GroupFixtureBuilder {
+    /// Creates a builder for a group with two members and no churn.
+    pub fn new(ciphersuite: Ciphersuite) -> Self {
+        Self {
+            ciphersuite,
+            members: 2,
+            epochs: 0,
+            churn: Churn::default(),
+            seed: 0,
+            mls_group_config: MlsGroupConfig::default(),
+        }
+    }
+
+    /// Sets the number of initial members. The default is `2`.
+    pub fn members(mut self, members: usize) -> Self {
+        self.members = members.max(1);
+        self
+    }
+
+    /// Sets the number of epochs of churn after the initial members were
+    /// added. The default is `0`.
+    pub fn epochs(mut self, epochs: usize) -> Self {
+        self.epochs = epochs;
+        self
+    }
+
+    /// Sets the weights of the operations of the churn.
+    pub fn churn(mut self, churn: Churn) -> Self {
+        self.churn = churn;
+        self
+    }
+
+    /// Sets the seed of the random number generator. The default is `0`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the configuration of the groups of all members. Its crypto
+    /// config is replaced by one for the ciphersuite of the fixture. The key
+    /// packages of new members support its required capabilities.
+    pub fn mls_group_config(mut self, mls_group_config: MlsGroupConfig) -> Self {
+        self.mls_group_config = mls_group_config;
+        self
+    }
+
+    /// Creates the group and runs the churn.
+    ///
+    /// Panics if an MLS operation fails.
+    pub fn build(self) -> GroupFixture {
+        let mut mls_group_config = self.mls_group_config;
+        mls_group_config.crypto_config = CryptoConfig::with_default_version(self.ciphersuite);
+        let mut fixture = GroupFixture {
+            ciphersuite: self.ciphersuite,
+            mls_group_config,
+            members: Vec::new(),
+            next_identity: 0,
+            rng: StdRng::seed_from_u64(self.seed),
+        };
+
+        let (identity, credential_bundle, backend) = fixture.new_client();
+        let credential = credential_bundle.credential().clone();
+        let group = MlsGroup::new(
+            &backend,
+            &fixture.mls_group_config,
+            credential.signature_key(),
+        )
+        .expect("Could not create the group.");
+        fixture.members.push(FixtureMember {
+            identity,
+            credential,
+            backend,
+            group,
+        });
+        if self.members > 1 {
+            fixture.add(0, self.members - 1);
+        }
+
+        for _ in 0..self.epochs {
+            fixture.churn_epoch(self.churn);
+        }
+
+        fixture
+    }
+}
+
+/// A group of which the states of all members are available. See
+/// [`GroupFixtureBuilder`] for how it is created.
+#[derive(Debug)]
+pub struct GroupFixture {
+    ciphersuite: Ciphersuite,
+    mls_group_config: MlsGroupConfig,
+    members: Vec<FixtureMember>,
+    next_identity: usize,
+    rng: StdRng,
+}
+
+impl GroupFixture {
+    /// Returns a [`GroupFixtureBuilder`] for the `ciphersuite`.
+    pub fn builder(ciphersuite: Ciphersuite) -> GroupFixtureBuilder {
+        GroupFixtureBuilder::new(ciphersuite)
+    }
+
+    /// Returns the current members in the order in which they joined.
+    pub fn members(&self) -> &[FixtureMember] {
+        &self.members
+    }
+
+    /// Returns the current members in the order in which they joined.
+    pub fn members_mut(&mut self) -> &mut [FixtureMember] {
+        &mut self.members
+    }
+
+    /// Returns the current members in the order in which they joined.
+    pub fn into_members(self) -> Vec<FixtureMember> {
+        self.members
+    }
+
+    /// Returns the current epoch of the group.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.members[0].group.epoch()
+    }
+
+    /// Runs one more epoch of churn with the given weights.
+    pub fn churn_epoch(&mut self, churn: Churn) {
+        let committer = self.rng.gen_range(0..self.members.len());
+        let removes = if self.members.len() > 1 {
+            churn.removes
+        } else {
+            0
+        };
+        let total = churn.adds + removes + churn.updates;
+        let choice = if total == 0 {
+            // Without any weights, the committer updates.
+            churn.adds + removes
+        } else {
+            self.rng.gen_range(0..total)
+        };
+
+        if choice < churn.adds {
+            self.add(committer, 1);
+        } else if choice < churn.adds + removes {
+            let mut removed = self.rng.gen_range(0..self.members.len() - 1);
+            if removed >= committer {
+                removed += 1;
+            }
+            let removed_index = self.members[removed].group.own_leaf_index();
+            let member = &mut self.members[committer];
+            let (commit, _welcome, _group_info) = member
+                .group
+                .remove_members(&member.backend, &[removed_index])
+                .expect("Could not remove a member.");
+            self.distribute_commit(committer, commit);
+        } else {
+            let member = &mut self.members[committer];
+            let (commit, _welcome, _group_info) = member
+                .group
+                .self_update(&member.backend)
+                .expect("Could not update.");
+            self.distribute_commit(committer, commit);
+        }
+    }
+
+    /// Panics if the members don't agree on the state of the group.
+    pub fn assert_consistent(&self) {
+        let first = &self.members[0].group;
+        for member in &self.members[1..] {
+            assert_eq!(member.group.epoch(), first.epoch());
+            assert_eq!(
+                member.group.epoch_authenticator().expose(),
+                first.epoch_authenticator().expose()
+            );
+            assert!(member.group.members().eq(first.members()));
+        }
+    }
+
+    // Creates a client with a fresh identity and a credential bundle for the
+    // ciphersuite of the fixture.
+    fn new_client(&mut self) -> (Vec<u8>, CredentialBundle, OpenMlsRustCrypto) {
+        let identity = format!("Member {}", self.next_identity).into_bytes();
+        self.next_identity += 1;
+        let backend = OpenMlsRustCrypto::default();
+        let credential_bundle = CredentialBundle::new(
+            identity.clone().into(),
+            CredentialType::Basic,
+            self.ciphersuite.signature_algorithm(),
+            &backend,
+        )
+        .expect("Could not create a credential.");
+        backend
+            .key_store()
+            .store(
+                &credential_bundle
+                    .credential()
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .expect("Error serializing signature key."),
+                &credential_bundle,
+            )
+            .expect("Could not store the credential bundle.");
+        (identity, credential_bundle, backend)
+    }
+
+    // Returns the default capabilities together with the required
+    // capabilities of the configuration, such that new clients can join.
+    fn leaf_node_capabilities(&self) -> Capabilities {
+        let required_capabilities = &self.mls_group_config.required_capabilities;
+        let mut extensions = Capabilities::default().extensions().to_vec();
+        for extension_type in required_capabilities.extension_types() {
+            if !extensions.contains(extension_type) {
+                extensions.push(*extension_type);
+            }
+        }
+        Capabilities::builder()
+            .ciphersuites(vec![self.ciphersuite])
+            .extensions(extensions)
+            .build_for(required_capabilities)
+            .expect("The required capabilities are not supported.")
+    }
+
+    // Has the `committer` add `count` new clients to the group.
+    fn add(&mut self, committer: usize, count: usize) {
+        let clients: Vec<_> = (0..count).map(|_| self.new_client()).collect();
+        let key_packages: Vec<KeyPackage> = clients
+            .iter()
+            .map(|(_, credential_bundle, backend)| {
+                KeyPackage::builder()
+                    .leaf_node_capabilities(self.leaf_node_capabilities())
+                    .build(
+                        CryptoConfig::with_default_version(self.ciphersuite),
+                        backend,
+                        credential_bundle,
+                    )
+                    .expect("Could not create a key package.")
+            })
+            .collect();
+        let member = &mut self.members[committer];
+        let (commit, welcome, _group_info) = member
+            .group
+            .add_members(&member.backend, &key_packages)
+            .expect("Could not add members.");
+        self.distribute_commit(committer, commit);
+
+        let ratchet_tree = self.members[committer].group.export_ratchet_tree();
+        for (identity, credential_bundle, backend) in clients {
+            let group = MlsGroup::new_from_welcome(
+                &backend,
+                &self.mls_group_config,
+                welcome
+                    .clone()
+                    .into_welcome()
+                    .expect("Unexpected message type."),
+                Some(ratchet_tree.clone()),
+            )
+            .expect("Could not join the group.");
+            self.members.push(FixtureMember {
+                identity,
+                credential: credential_bundle.credential().clone(),
+                backend,
+                group,
+            });
+        }
+    }
+
+    // Merges the commit of the `committer` and has all other members process
+    // and merge it. Members that were removed are dropped.
+    fn distribute_commit(&mut self, committer: usize, commit: MlsMessageOut) {
+        for (index, member) in self.members.iter_mut().enumerate() {
+            if index == committer {
+                member
+                    .group
+                    .merge_pending_commit(&member.backend)
+                    .expect("Could not merge the commit.");
+                continue;
+            }
+            let processed_message = member
+                .group
+                .process_message(
+                    &member.backend,
+                    commit
+                        .clone()
+                        .into_protocol_message()
+                        .expect("Unexpected message type."),
+                )
+                .expect("Could not process the commit.");
+            match processed_message.into_content() {
+                ProcessedMessageContent::StagedCommitMessage(staged_commit) => member
+                    .group
+                    .merge_staged_commit(&member.backend, *staged_commit)
+                    .expect("Could not merge the commit."),
+                _ => panic!("Expected a commit."),
+            }
+        }
+        self.members.retain(|member| member.group.is_active());
+    }
+}
@@ -0,0 +1,406 @@
+//! A deterministic in-memory delivery service for integration tests of MLS
+//! flows, see [`MockDeliveryService`].
+
+use std::{collections::BTreeMap, sync::Mutex};
+
+use ::rand::{rngs::StdRng, Rng, SeedableRng};
+use openmls_traits::OpenMlsCryptoProvider;
+use thiserror::Error;
+
+use crate::{
+    error::LibraryError,
+    framing::{errors::MlsMessageError, mls_content::ContentType, MlsMessageIn, MlsMessageOut},
+    group::{GroupEpoch, GroupId},
+    key_packages::KeyPackage,
+};
+
+/// The identifier of a client of the [`MockDeliveryService`].
+pub type ClientId = Vec<u8>;
+
+/// Faults that the [`MockDeliveryService`] injects when fanning out messages.
+///
+/// The faults are drawn from a random number generator that is seeded with
+/// `seed`, so that a test run can be reproduced.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeliveryFaults {
+    /// The seed of the random number generator.
+    pub seed: u64,
+    /// The probability that a message is not delivered to a recipient.
+    pub drop_probability: f64,
+    /// The probability that a message is delivered to a recipient before
+    /// messages that are already waiting in its inbox.
+    pub reorder_probability: f64,
+}
+
+impl Default for DeliveryFaults {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            drop_probability: 0.0,
+            reorder_probability: 0.0,
+        }
+    }
+}
+
+/// Errors of the [`MockDeliveryService`].
+#[derive(Error, Debug, Clone)]
+pub enum MockDeliveryServiceError {
+    /// The client is not registered.
+    #[error("The client is not registered.")]
+    UnknownClient,
+    /// The group is not known to the delivery service.
+    #[error("The group is not known to the delivery service.")]
+    UnknownGroup,
+    /// The group already exists.
+    #[error("The group already exists.")]
+    GroupAlreadyExists,
+    /// The sender is not subscribed to the group.
+    #[error("The sender is not subscribed to the group.")]
+    NotSubscribed,
+    /// The message is not a protocol message.
+    #[error("The message is not a protocol message.")]
+    NotAProtocolMessage,
+    /// The message is not a Welcome.
+    #[error("The message is not a Welcome.")]
+    NotAWelcome,
+    /// A key package in the Welcome wasn't handed out by the delivery service.
+    #[error("A key package in the Welcome wasn't handed out by the delivery service.")]
+    UnknownKeyPackage,
+    /// Another commit for the epoch of the commit was already accepted.
+    #[error("Another commit for the epoch of the commit was already accepted.")]
+    StaleCommit {
+        /// The epoch of the rejected commit.
+        epoch: GroupEpoch,
+        /// The epoch for which the delivery service accepts commits.
+        expected: GroupEpoch,
+    },
+    /// See [`MlsMessageError`] for more details.
+    #[error(transparent)]
+    MlsMessageError(#[from] MlsMessageError),
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+}
+
+#[derive(Debug)]
+struct MockGroup {
+    // The subscribers in the order in which they joined.
+    subscribers: Vec<ClientId>,
+    // The epoch for which the next commit is accepted. It is learned from the
+    // first commit if the group was created without an epoch.
+    epoch: Option<GroupEpoch>,
+}
+
+#[derive(Debug)]
+struct MockDeliveryServiceState {
+    inboxes: BTreeMap<ClientId, Vec<Vec<u8>>>,
+    key_packages: BTreeMap<ClientId, Vec<KeyPackage>>,
+    key_package_owners: BTreeMap<Vec<u8>, ClientId>,
+    groups: BTreeMap<Vec<u8>, MockGroup>,
+    faults: DeliveryFaults,
+    rng: StdRng,
+    dropped_messages: usize,
+}
+
+/// A deterministic in-memory delivery service.
+///
+/// The delivery service
+/// * keeps a directory of the key packages of its clients, of which each is
+///   handed out only once,
+/// * routes Welcome messages to the clients that published the key packages
+///   they were created with and subscribes them to the group,
+/// * fans out protocol messages to all other subscribers of a group in the
+///   order in which they were sent, and
+/// * accepts only the first commit for every epoch, like a delivery service
+///   that enforces an order on the commits of a group.
+///
+/// Messages are serialized when they are sent and deserialized when they are
+/// received, so that the clients see the same messages they would see over a
+/// network. Optionally, messages are dropped or reordered, see
+/// [`DeliveryFaults`].
+#[derive(Debug)]
+pub struct MockDeliveryService {
+    state: Mutex<MockDeliveryServiceState>,
+}
+
+impl Default for MockDeliveryService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockDeliveryService {
+    /// Creates a delivery service that delivers every message.
+    pub fn new() -> Self {
+        Self::with_faults(DeliveryFaults::default())
+    }
+
+    /// Creates a delivery service that injects the given `faults`.
+    pub fn with_faults(faults: DeliveryFaults) -> Self {
+        Self {
+            state: Mutex::new(MockDeliveryServiceState {
+                inboxes: BTreeMap::new(),
+                key_packages: BTreeMap::new(),
+                key_package_owners: BTreeMap::new(),
+                groups: BTreeMap::new(),
+                faults,
+                rng: StdRng::seed_from_u64(faults.seed),
+                dropped_messages: 0,
+            }),
+        }
+    }
+
+    /// Registers a client with an empty inbox. Registering a client again
+    /// has no effect.
+    pub fn register_client(&self, client_id: &[u8]) {
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        state.inboxes.entry(client_id.to_vec()).or_default();
+    }
+
+    /// Publishes a key package of the client in the key package directory.
+    pub fn publish_key_package(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        client_id: &[u8],
+        key_package: KeyPackage,
+    ) -> Result<(), MockDeliveryServiceError> {
+        let key_package_ref = key_package.hash_ref(backend.crypto())?;
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        if !state.inboxes.contains_key(client_id) {
+            return Err(MockDeliveryServiceError::UnknownClient);
+        }
+        state
+            .key_package_owners
+            .insert(key_package_ref.as_slice().to_vec(), client_id.to_vec());
+        state
+            .key_packages
+            .entry(client_id.to_vec())
+            .or_default()
+            .push(key_package);
+        Ok(())
+    }
+
+    /// Hands out the oldest key package the client published, or `None` if
+    /// there is none left.
+    pub fn fetch_key_package(&self, client_id: &[u8]) -> Option<KeyPackage> {
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        let key_packages = state.key_packages.get_mut(client_id)?;
+        if key_packages.is_empty() {
+            None
+        } else {
+            Some(key_packages.remove(0))
+        }
+    }
+
+    /// Returns the number of key packages the client published that weren't
+    /// handed out yet.
+    pub fn key_package_count(&self, client_id: &[u8]) -> usize {
+        let state = self.state.lock().expect("The lock is poisoned.");
+        state.key_packages.get(client_id).map_or(0, Vec::len)
+    }
+
+    /// Creates a group with the `creator` as the only subscriber. The
+    /// delivery service accepts commits for `epoch`, or for the epoch of the
+    /// first commit if `epoch` is `None`.
+    pub fn create_group(
+        &self,
+        group_id: &GroupId,
+        creator: &[u8],
+        epoch: Option<GroupEpoch>,
+    ) -> Result<(), MockDeliveryServiceError> {
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        if !state.inboxes.contains_key(creator) {
+            return Err(MockDeliveryServiceError::UnknownClient);
+        }
+        if state.groups.contains_key(group_id.as_slice()) {
+            return Err(MockDeliveryServiceError::GroupAlreadyExists);
+        }
+        state.groups.insert(
+            group_id.as_slice().to_vec(),
+            MockGroup {
+                subscribers: vec![creator.to_vec()],
+                epoch,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the subscribers of the group in the order in which they
+    /// joined.
+    pub fn subscribers(
+        &self,
+        group_id: &GroupId,
+    ) -> Result<Vec<ClientId>, MockDeliveryServiceError> {
+        let state = self.state.lock().expect("The lock is poisoned.");
+        state
+            .groups
+            .get(group_id.as_slice())
+            .map(|group| group.subscribers.clone())
+            .ok_or(MockDeliveryServiceError::UnknownGroup)
+    }
+
+    /// Subscribes the client to the group, e.g. after it joined through an
+    /// external commit.
+    pub fn subscribe(
+        &self,
+        group_id: &GroupId,
+        client_id: &[u8],
+    ) -> Result<(), MockDeliveryServiceError> {
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        if !state.inboxes.contains_key(client_id) {
+            return Err(MockDeliveryServiceError::UnknownClient);
+        }
+        let group = state
+            .groups
+            .get_mut(group_id.as_slice())
+            .ok_or(MockDeliveryServiceError::UnknownGroup)?;
+        if !group.subscribers.iter().any(|s| s == client_id) {
+            group.subscribers.push(client_id.to_vec());
+        }
+        Ok(())
+    }
+
+    /// Unsubscribes the client from the group, e.g. after it was removed.
+    pub fn unsubscribe(
+        &self,
+        group_id: &GroupId,
+        client_id: &[u8],
+    ) -> Result<(), MockDeliveryServiceError> {
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        let group = state
+            .groups
+            .get_mut(group_id.as_slice())
+            .ok_or(MockDeliveryServiceError::UnknownGroup)?;
+        group.subscribers.retain(|s| s != client_id);
+        Ok(())
+    }
+
+    /// Sends a protocol message of the `sender` to all other subscribers of
+    /// its group.
+    ///
+    /// Returns an error ([`MockDeliveryServiceError::StaleCommit`]) if the
+    /// message is a commit and another commit was already accepted for its
+    /// epoch.
+    pub fn send_message(
+        &self,
+        sender: &[u8],
+        message: &MlsMessageOut,
+    ) -> Result<(), MockDeliveryServiceError> {
+        let bytes = message.to_bytes()?;
+        let protocol_message = MlsMessageIn::try_from_bytes(&bytes)?
+            .into_protocol_message()
+            .ok_or(MockDeliveryServiceError::NotAProtocolMessage)?;
+
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        let group = state
+            .groups
+            .get_mut(protocol_message.group_id().as_slice())
+            .ok_or(MockDeliveryServiceError::UnknownGroup)?;
+        if !group.subscribers.iter().any(|s| s == sender) {
+            return Err(MockDeliveryServiceError::NotSubscribed);
+        }
+        if protocol_message.content_type() == ContentType::Commit {
+            let epoch = protocol_message.epoch();
+            match group.epoch {
+                Some(expected) if expected != epoch => {
+                    return Err(MockDeliveryServiceError::StaleCommit { epoch, expected });
+                }
+                _ => group.epoch = Some(GroupEpoch::from(epoch.as_u64() + 1)),
+            }
+        }
+        let recipients: Vec<ClientId> = group
+            .subscribers
+            .iter()
+            .filter(|s| s.as_slice() != sender)
+            .cloned()
+            .collect();
+        for recipient in recipients {
+            state.deliver(&recipient, bytes.clone());
+        }
+        Ok(())
+    }
+
+    /// Sends a Welcome to the clients that published the key packages it was
+    /// created with, and subscribes them to the group.
+    pub fn send_welcome(
+        &self,
+        group_id: &GroupId,
+        welcome: &MlsMessageOut,
+    ) -> Result<(), MockDeliveryServiceError> {
+        let bytes = welcome.to_bytes()?;
+        let welcome = MlsMessageIn::try_from_bytes(&bytes)?
+            .into_welcome()
+            .ok_or(MockDeliveryServiceError::NotAWelcome)?;
+
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        let recipients = welcome
+            .secrets()
+            .iter()
+            .map(|egs| {
+                state
+                    .key_package_owners
+                    .get(egs.new_member().as_slice())
+                    .cloned()
+                    .ok_or(MockDeliveryServiceError::UnknownKeyPackage)
+            })
+            .collect::<Result<Vec<ClientId>, _>>()?;
+        let group = state
+            .groups
+            .get_mut(group_id.as_slice())
+            .ok_or(MockDeliveryServiceError::UnknownGroup)?;
+        for recipient in &recipients {
+            if !group.subscribers.contains(recipient) {
+                group.subscribers.push(recipient.clone());
+            }
+        }
+        // Welcomes are never dropped or reordered, since they can't be
+        // recovered from.
+        for recipient in recipients {
+            state
+                .inboxes
+                .entry(recipient)
+                .or_default()
+                .push(bytes.clone());
+        }
+        Ok(())
+    }
+
+    /// Takes all messages in the inbox of the client in the order in which
+    /// they are delivered.
+    pub fn receive(&self, client_id: &[u8]) -> Result<Vec<MlsMessageIn>, MockDeliveryServiceError> {
+        let mut state = self.state.lock().expect("The lock is poisoned.");
+        let inbox = state
+            .inboxes
+            .get_mut(client_id)
+            .ok_or(MockDeliveryServiceError::UnknownClient)?;
+        std::mem::take(inbox)
+            .into_iter()
+            .map(|bytes| MlsMessageIn::try_from_bytes(&bytes).map_err(|e| e.into()))
+            .collect()
+    }
+
+    /// Returns the number of messages that were dropped so far.
+    pub fn dropped_messages(&self) -> usize {
+        let state = self.state.lock().expect("The lock is poisoned.");
+        state.dropped_messages
+    }
+}
+
+impl MockDeliveryServiceState {
+    // Puts a message into the inbox of the recipient, unless it is dropped.
+    fn deliver(&mut self, recipient: &[u8], bytes: Vec<u8>) {
+        if self.faults.drop_probability > 0.0 && self.rng.gen_bool(self.faults.drop_probability) {
+            self.dropped_messages += 1;
+            return;
+        }
+        let reorder = self.faults.reorder_probability > 0.0
+            && self.rng.gen_bool(self.faults.reorder_probability);
+        let inbox = self.inboxes.entry(recipient.to_vec()).or_default();
+        if reorder && !inbox.is_empty() {
+            let position = self.rng.gen_range(0..inbox.len());
+            inbox.insert(position, bytes);
+        } else {
+            inbox.push(bytes);
+        }
+    }
+}
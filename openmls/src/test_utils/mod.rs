@@ -15,6 +15,9 @@ use std::{
     io::{BufReader, Write},
 };
 
+pub mod corrupted_messages;
+pub mod group_fixtures;
+pub mod mock_delivery_service;
 pub mod test_framework;
 
 pub(crate) fn write(file_name: &str, obj: impl Serialize) {
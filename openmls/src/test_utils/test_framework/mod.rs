@@ -29,6 +29,7 @@ use crate::{
     group::*,
     key_packages::*,
     messages::*,
+    schedule::ExportedSecret,
     treesync::{node::Node, LeafNode},
 };
 use ::rand::{rngs::OsRng, RngCore};
@@ -62,7 +63,7 @@ pub struct Group {
     pub ciphersuite: Ciphersuite,
     pub group_config: MlsGroupConfig,
     pub public_tree: Vec<Option<Node>>,
-    pub exporter_secret: Vec<u8>,
+    pub exporter_secret: ExportedSecret,
 }
 
 impl Group {
@@ -135,7 +136,7 @@ impl MlsGroupTestSetup {
             let mut credentials = HashMap::new();
             for ciphersuite in crypto.crypto().supported_ciphersuites().iter() {
                 let cb = CredentialBundle::new(
-                    identity.clone(),
+                    identity.clone().into(),
                     CredentialType::Basic,
                     SignatureScheme::from(*ciphersuite),
                     &crypto,
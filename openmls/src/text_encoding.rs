@@ -0,0 +1,358 @@
+//! # Text encodings
+//!
+//! This module provides text encodings of [`KeyPackage`]s, MLS messages
+//! ([`MlsMessageOut`] and [`MlsMessageIn`]) and [`Welcome`]s, such that they can
+//! be pasted into REST APIs, logs and bug reports without additional codecs.
+//!
+//! * `to_base64()` and `to_hex()` encode the TLS serialization of a structure.
+//!   `from_base64()` and `from_hex()` decode it again. Since outgoing messages
+//!   are decoded as [`MlsMessageIn`], [`MlsMessageOut`] can only be encoded.
+//! * `to_debug_json()` returns a JSON representation of a structure for
+//!   debugging. It only contains public information, e.g. the group ID and
+//!   epoch of a message, but not its ciphertext. The representation is not
+//!   meant to be parsed and its format may change.
+//! * `to_qr_text()` returns a compact encoding of a [`KeyPackage`] or a
+//!   [`Welcome`] for QR codes, and `from_qr_text()` decodes it again. The
+//!   encoding consists of a prefix with the type and the version of the
+//!   encoding, i.e. `MLSKP1:` for key packages and `MLSINV1:` for Welcomes,
+//!   followed by the base45 encoding ([RFC 9285]) of the TLS serialization
+//!   and a CRC-32 checksum of it. It only uses characters of the alphanumeric
+//!   mode of QR codes, which stores them more densely than the byte mode.
+//!
+//! This module is only available with the `text-encoding` feature.
+//!
+//! [RFC 9285]: https://www.rfc-editor.org/rfc/rfc9285
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tls_codec::{Deserialize, Serialize};
+
+use crate::{
+    error::error_codes,
+    framing::{MlsMessageIn, MlsMessageInBody, MlsMessageOut},
+    key_packages::KeyPackage,
+    messages::Welcome,
+};
+
+/// Text encoding error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum TextEncodingError {
+    /// The structure could not be serialized.
+    #[error("The structure could not be serialized.")]
+    EncodingError,
+    /// The text is not valid base64.
+    #[error("The text is not valid base64.")]
+    InvalidBase64,
+    /// The text is not valid hex.
+    #[error("The text is not valid hex.")]
+    InvalidHex,
+    /// The structure could not be deserialized.
+    #[error("The structure could not be deserialized.")]
+    DecodingError,
+    /// The text doesn't start with the prefix of the expected structure.
+    #[error("The text doesn't start with the prefix of the expected structure.")]
+    InvalidQrPrefix,
+    /// The version of the QR encoding is not supported.
+    #[error("The version of the QR encoding is not supported.")]
+    UnsupportedQrVersion,
+    /// The text is not valid base45.
+    #[error("The text is not valid base45.")]
+    InvalidBase45,
+    /// The checksum doesn't match, i.e. the text is corrupted.
+    #[error("The checksum doesn't match, i.e. the text is corrupted.")]
+    ChecksumMismatch,
+}
+
+error_codes!(TextEncodingError: 5100 {
+    EncodingError = 1,
+    InvalidBase64 = 2,
+    InvalidHex = 3,
+    DecodingError = 4,
+    InvalidQrPrefix = 5,
+    UnsupportedQrVersion = 6,
+    InvalidBase45 = 7,
+    ChecksumMismatch = 8,
+});
+
+/// The version of the QR encoding.
+const QR_VERSION: u8 = 1;
+
+/// The alphabet of base45, see RFC 9285.
+const BASE45_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Encodes `bytes` with base45.
+fn base45_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity((bytes.len() + 1) / 2 * 3);
+    for chunk in bytes.chunks(2) {
+        let mut n = chunk
+            .iter()
+            .fold(0usize, |n, byte| n << 8 | usize::from(*byte));
+        // Two bytes are encoded with three characters, a single byte with two.
+        for _ in 0..=chunk.len() {
+            text.push(char::from(BASE45_ALPHABET[n % 45]));
+            n /= 45;
+        }
+    }
+    text
+}
+
+/// Decodes a base45 encoded `text`.
+fn base45_decode(text: &str) -> Result<Vec<u8>, TextEncodingError> {
+    let digits = text
+        .bytes()
+        .map(|c| {
+            BASE45_ALPHABET
+                .iter()
+                .position(|a| *a == c)
+                .ok_or(TextEncodingError::InvalidBase45)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut bytes = Vec::with_capacity(digits.len() / 3 * 2 + 1);
+    for chunk in digits.chunks(3) {
+        let n = chunk.iter().rev().fold(0usize, |n, digit| n * 45 + digit);
+        match chunk.len() {
+            3 if n <= 0xffff => bytes.extend_from_slice(&(n as u16).to_be_bytes()),
+            2 if n <= 0xff => bytes.push(n as u8),
+            _ => return Err(TextEncodingError::InvalidBase45),
+        }
+    }
+    Ok(bytes)
+}
+
+/// Computes the CRC-32 (IEEE 802.3) checksum of `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & 0u32.wrapping_sub(crc & 1))
+        })
+    })
+}
+
+/// Encodes `bytes` for QR codes, with the given type `prefix`.
+fn qr_encode(prefix: &str, bytes: &[u8]) -> String {
+    let mut payload = bytes.to_vec();
+    payload.extend_from_slice(&crc32(bytes).to_be_bytes());
+    format!("{prefix}{QR_VERSION}:{}", base45_encode(&payload))
+}
+
+/// Decodes a `text` encoded for QR codes with the given type `prefix`.
+fn qr_decode(prefix: &str, text: &str) -> Result<Vec<u8>, TextEncodingError> {
+    let (tag, data) = text
+        .trim()
+        .split_once(':')
+        .ok_or(TextEncodingError::InvalidQrPrefix)?;
+    let version: u8 = tag
+        .strip_prefix(prefix)
+        .and_then(|version| version.parse().ok())
+        .ok_or(TextEncodingError::InvalidQrPrefix)?;
+    if version != QR_VERSION {
+        return Err(TextEncodingError::UnsupportedQrVersion);
+    }
+    let mut bytes = base45_decode(data)?;
+    if bytes.len() < 4 {
+        return Err(TextEncodingError::ChecksumMismatch);
+    }
+    let checksum = bytes.split_off(bytes.len() - 4);
+    if checksum != crc32(&bytes).to_be_bytes() {
+        return Err(TextEncodingError::ChecksumMismatch);
+    }
+    Ok(bytes)
+}
+
+/// Conversion of a structure from and to the bytes that are text encoded.
+trait TextBytes: Sized {
+    /// Returns the TLS serialization of the structure.
+    fn to_text_bytes(&self) -> Result<Vec<u8>, TextEncodingError>;
+
+    /// Returns the JSON debug representation of the structure.
+    fn to_debug_value(&self) -> Result<Value, TextEncodingError>;
+}
+
+/// Implements `to_base64()`, `to_hex()` and `to_debug_json()` for a type that
+/// implements [`TextBytes`].
+macro_rules! text_encoding {
+    ($type:ty, $name:literal) => {
+        impl $type {
+            #[doc = concat!("Returns the base64 encoding of the TLS serialized ", $name, ".")]
+            pub fn to_base64(&self) -> Result<String, TextEncodingError> {
+                Ok(STANDARD.encode(self.to_text_bytes()?))
+            }
+
+            #[doc = concat!("Returns the hex encoding of the TLS serialized ", $name, ".")]
+            pub fn to_hex(&self) -> Result<String, TextEncodingError> {
+                Ok(hex::encode(self.to_text_bytes()?))
+            }
+
+            #[doc = concat!("Returns a JSON representation of the ", $name, " for debugging.")]
+            #[doc = ""]
+            #[doc = "The representation is not meant to be parsed and its format may change."]
+            pub fn to_debug_json(&self) -> Result<String, TextEncodingError> {
+                serde_json::to_string_pretty(&self.to_debug_value()?)
+                    .map_err(|_| TextEncodingError::EncodingError)
+            }
+        }
+    };
+}
+
+/// Implements `to_qr_text()` and `from_qr_text()` for a type that implements
+/// [`TextBytes`], given the prefix of the type and a function that
+/// deserializes it from bytes.
+macro_rules! qr_encoding {
+    ($type:ty, $name:literal, $prefix:literal, $from_bytes:expr) => {
+        impl $type {
+            #[doc = concat!("Returns a compact encoding of the TLS serialized ", $name, " for QR codes.")]
+            #[doc = ""]
+            #[doc = concat!("The encoding starts with `", $prefix, "1:`, see the [`text_encoding`](crate::text_encoding) module.")]
+            pub fn to_qr_text(&self) -> Result<String, TextEncodingError> {
+                Ok(qr_encode($prefix, &self.to_text_bytes()?))
+            }
+
+            #[doc = concat!("Decodes a ", $name, " encoded with `to_qr_text()`.")]
+            pub fn from_qr_text(text: &str) -> Result<Self, TextEncodingError> {
+                $from_bytes(&qr_decode($prefix, text)?)
+            }
+        }
+    };
+}
+
+/// Implements `from_base64()` and `from_hex()` for a type, given a function
+/// that deserializes it from bytes.
+macro_rules! text_decoding {
+    ($type:ty, $name:literal, $from_bytes:expr) => {
+        impl $type {
+            #[doc = concat!("Decodes a base64 encoded, TLS serialized ", $name, ".")]
+            pub fn from_base64(text: &str) -> Result<Self, TextEncodingError> {
+                let bytes = STANDARD
+                    .decode(text.trim())
+                    .map_err(|_| TextEncodingError::InvalidBase64)?;
+                $from_bytes(&bytes)
+            }
+
+            #[doc = concat!("Decodes a hex encoded, TLS serialized ", $name, ".")]
+            pub fn from_hex(text: &str) -> Result<Self, TextEncodingError> {
+                let bytes = hex::decode(text.trim()).map_err(|_| TextEncodingError::InvalidHex)?;
+                $from_bytes(&bytes)
+            }
+        }
+    };
+}
+
+impl TextBytes for KeyPackage {
+    fn to_text_bytes(&self) -> Result<Vec<u8>, TextEncodingError> {
+        self.tls_serialize_detached()
+            .map_err(|_| TextEncodingError::EncodingError)
+    }
+
+    fn to_debug_value(&self) -> Result<Value, TextEncodingError> {
+        Ok(json!({
+            "ciphersuite": self.ciphersuite(),
+            "protocol_version": self.protocol_version(),
+            "init_key": hex::encode(self.hpke_init_key().as_slice()),
+            "identity": hex::encode(self.leaf_node().credential().identity()),
+            "signature_key": hex::encode(self.leaf_node().signature_key().as_slice()),
+            "extensions": self
+                .extensions()
+                .iter()
+                .map(|extension| extension.extension_type())
+                .collect::<Vec<_>>(),
+        }))
+    }
+}
+
+impl TextBytes for Welcome {
+    fn to_text_bytes(&self) -> Result<Vec<u8>, TextEncodingError> {
+        self.tls_serialize_detached()
+            .map_err(|_| TextEncodingError::EncodingError)
+    }
+
+    fn to_debug_value(&self) -> Result<Value, TextEncodingError> {
+        Ok(json!({
+            "protocol_version": self.version(),
+            "ciphersuite": self.ciphersuite(),
+            "new_members": self
+                .secrets()
+                .iter()
+                .map(|secrets| hex::encode(secrets.new_member().as_slice()))
+                .collect::<Vec<_>>(),
+        }))
+    }
+}
+
+impl TextBytes for MlsMessageIn {
+    fn to_text_bytes(&self) -> Result<Vec<u8>, TextEncodingError> {
+        let mut bytes = self
+            .version
+            .tls_serialize_detached()
+            .map_err(|_| TextEncodingError::EncodingError)?;
+        self.body
+            .tls_serialize(&mut bytes)
+            .map_err(|_| TextEncodingError::EncodingError)?;
+        Ok(bytes)
+    }
+
+    fn to_debug_value(&self) -> Result<Value, TextEncodingError> {
+        let body = match &self.body {
+            MlsMessageInBody::PublicMessage(message) => json!({
+                "group_id": hex::encode(message.group_id().as_slice()),
+                "epoch": message.epoch().as_u64(),
+                "sender": message.sender(),
+                "content_type": message.content_type(),
+            }),
+            MlsMessageInBody::PrivateMessage(message) => json!({
+                "group_id": hex::encode(message.group_id().as_slice()),
+                "epoch": message.epoch().as_u64(),
+                "content_type": message.content_type(),
+            }),
+            MlsMessageInBody::Welcome(welcome) => welcome.to_debug_value()?,
+            MlsMessageInBody::GroupInfo(group_info) => json!({
+                "ciphersuite": group_info.ciphersuite(),
+                "signer": group_info.signer().u32(),
+            }),
+            MlsMessageInBody::KeyPackage(key_package) => key_package.to_debug_value()?,
+        };
+        Ok(json!({
+            "protocol_version": self.version,
+            "wire_format": self.wire_format(),
+            "body": body,
+        }))
+    }
+}
+
+impl TextBytes for MlsMessageOut {
+    fn to_text_bytes(&self) -> Result<Vec<u8>, TextEncodingError> {
+        self.tls_serialize_detached()
+            .map_err(|_| TextEncodingError::EncodingError)
+    }
+
+    fn to_debug_value(&self) -> Result<Value, TextEncodingError> {
+        // Outgoing messages are represented like the message the receivers
+        // decode.
+        let bytes = self.to_text_bytes()?;
+        MlsMessageIn::tls_deserialize(&mut bytes.as_slice())
+            .map_err(|_| TextEncodingError::EncodingError)?
+            .to_debug_value()
+    }
+}
+
+text_encoding!(KeyPackage, "key package");
+text_encoding!(Welcome, "welcome");
+text_encoding!(MlsMessageIn, "message");
+text_encoding!(MlsMessageOut, "message");
+
+text_decoding!(KeyPackage, "key package", |mut bytes: &[u8]| {
+    KeyPackage::tls_deserialize(&mut bytes).map_err(|_| TextEncodingError::DecodingError)
+});
+text_decoding!(Welcome, "welcome", |mut bytes: &[u8]| {
+    Welcome::tls_deserialize(&mut bytes).map_err(|_| TextEncodingError::DecodingError)
+});
+text_decoding!(MlsMessageIn, "message", |bytes: &[u8]| {
+    MlsMessageIn::try_from_bytes(bytes).map_err(|_| TextEncodingError::DecodingError)
+});
+
+qr_encoding!(KeyPackage, "key package", "MLSKP", |mut bytes: &[u8]| {
+    KeyPackage::tls_deserialize(&mut bytes).map_err(|_| TextEncodingError::DecodingError)
+});
+qr_encoding!(Welcome, "welcome", "MLSINV", |mut bytes: &[u8]| {
+    Welcome::tls_deserialize(&mut bytes).map_err(|_| TextEncodingError::DecodingError)
+});
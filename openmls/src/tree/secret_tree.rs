@@ -1,5 +1,6 @@
 use super::*;
 use crate::{
+    error::error_codes,
     framing::{mls_content::ContentType, *},
     schedule::*,
     tree::{index::*, sender_ratchet::*, treemath::*},
@@ -40,7 +41,19 @@ pub enum SecretTreeError {
     CryptoError(#[from] CryptoError),
 }
 
-#[derive(Debug, Copy, Clone)]
+error_codes!(SecretTreeError: 1500 {
+    TooDistantInThePast = 1,
+    TooDistantInTheFuture = 2,
+    IndexOutOfBounds = 3,
+    SecretReuseError = 4,
+    RatchetTypeError = 5,
+    RatchetTooLong = 6,
+    LibraryError = 7,
+    CodecError = 8,
+    CryptoError = 9,
+});
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) enum SecretType {
     HandshakeSecret,
     ApplicationSecret,
@@ -142,8 +155,22 @@ impl SecretTree {
         }
     }
 
+    /// Overwrite the secrets of all nodes and sender ratchets with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        for node in self.nodes.iter_mut().flatten() {
+            node.secret.zeroize();
+        }
+        for sender_ratchet in self
+            .handshake_sender_ratchets
+            .iter_mut()
+            .chain(self.application_sender_ratchets.iter_mut())
+            .flatten()
+        {
+            sender_ratchet.zeroize();
+        }
+    }
+
     /// Get current generation for a specific SenderRatchet
-    #[cfg(test)]
     pub(crate) fn generation(&self, index: SecretTreeLeafIndex, secret_type: SecretType) -> u32 {
         match self
             .ratchet_opt(index, secret_type)
@@ -154,9 +181,33 @@ impl SecretTree {
         }
     }
 
+    /// Overwrite the secrets of all nodes and of the sender ratchets of all
+    /// senders except `senders` with zeros and delete them. Afterwards, only
+    /// messages of `senders` can be decrypted with the tree.
+    pub(crate) fn retain_senders(&mut self, senders: &[SecretTreeLeafIndex]) {
+        for node in self.nodes.iter_mut() {
+            if let Some(mut node) = node.take() {
+                node.secret.zeroize();
+            }
+        }
+        for (index, sender_ratchet) in self
+            .handshake_sender_ratchets
+            .iter_mut()
+            .enumerate()
+            .chain(self.application_sender_ratchets.iter_mut().enumerate())
+        {
+            if senders.contains(&SecretTreeLeafIndex::from(index)) {
+                continue;
+            }
+            if let Some(mut sender_ratchet) = sender_ratchet.take() {
+                sender_ratchet.zeroize();
+            }
+        }
+    }
+
     /// Initializes a specific SenderRatchet pair for a given index by
     /// calculating and deleting the appropriate values in the SecretTree
-    fn initialize_sender_ratchets(
+    pub(crate) fn initialize_sender_ratchets(
         &mut self,
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
@@ -173,12 +224,10 @@ impl SecretTree {
         }
         // Check if SenderRatchets are already initialized
         if self
-            .ratchet_opt(index, SecretType::HandshakeSecret)
-            .expect("Index out of bounds.")
+            .ratchet_opt(index, SecretType::HandshakeSecret)?
             .is_some()
             && self
-                .ratchet_opt(index, SecretType::ApplicationSecret)
-                .expect("Index out of bounds.")
+                .ratchet_opt(index, SecretType::ApplicationSecret)?
                 .is_some()
         {
             log::trace!("The sender ratchets are initialized already.");
@@ -187,10 +236,8 @@ impl SecretTree {
         // Calculate direct path
         let index_in_tree = SecretTreeNodeIndex::from(index);
         let mut dir_path = vec![index_in_tree];
-        dir_path.extend(
-            leaf_direct_path(index, self.size)
-                .expect("initialize_sender_ratchets: Error while computing direct path."),
-        );
+        dir_path
+            .extend(leaf_direct_path(index, self.size).map_err(|_| SecretTreeError::LibraryError)?);
         log::trace!("Direct path for leaf {:?}: {:?}", index, dir_path);
         let mut empty_nodes: Vec<SecretTreeNodeIndex> = vec![];
         for n in dir_path {
@@ -199,6 +246,13 @@ impl SecretTree {
                 break;
             }
         }
+        // The secrets the ratchets would be derived from were deleted, e.g.
+        // because only the ratchets of other senders were retained.
+        if let Some(n) = empty_nodes.last() {
+            if self.nodes[n.as_usize()].is_none() {
+                return Err(SecretTreeError::SecretReuseError);
+            }
+        }
         // Remove leaf and invert direct path
         empty_nodes.remove(0);
         empty_nodes.reverse();
@@ -273,7 +327,7 @@ impl SecretTree {
         if self.ratchet_opt(index, secret_type)?.is_none() {
             self.initialize_sender_ratchets(ciphersuite, backend, index)?;
         }
-        match self.ratchet_mut(index, secret_type) {
+        match self.ratchet_mut(index, secret_type)? {
             SenderRatchet::EncryptionRatchet(_) => Err(SecretTreeError::RatchetTypeError),
             SenderRatchet::DecryptionRatchet(dec_ratchet) => {
                 dec_ratchet.secret_for_decryption(ciphersuite, backend, generation, configuration)
@@ -291,10 +345,9 @@ impl SecretTree {
         secret_type: SecretType,
     ) -> Result<(u32, RatchetKeyMaterial), SecretTreeError> {
         if self.ratchet_opt(index, secret_type)?.is_none() {
-            self.initialize_sender_ratchets(ciphersuite, backend, index)
-                .expect("Index out of bounds");
+            self.initialize_sender_ratchets(ciphersuite, backend, index)?;
         }
-        match self.ratchet_mut(index, secret_type) {
+        match self.ratchet_mut(index, secret_type)? {
             SenderRatchet::DecryptionRatchet(_) => Err(SecretTreeError::RatchetTypeError),
             SenderRatchet::EncryptionRatchet(enc_ratchet) => {
                 enc_ratchet.ratchet_forward(backend, ciphersuite)
@@ -304,20 +357,65 @@ impl SecretTree {
 
     /// Returns a mutable reference to a specific SenderRatchet. The
     /// SenderRatchet needs to be initialized.
+    /// Returns the number of skipped keys stored in the decryption ratchets,
+    /// per sender and secret type. Ratchets without skipped keys are omitted.
+    pub(crate) fn skipped_keys(&self) -> Vec<(SecretTreeLeafIndex, SecretType, usize)> {
+        let handshake_ratchets = self
+            .handshake_sender_ratchets
+            .iter()
+            .enumerate()
+            .map(|(index, ratchet)| (index, SecretType::HandshakeSecret, ratchet));
+        let application_ratchets = self
+            .application_sender_ratchets
+            .iter()
+            .enumerate()
+            .map(|(index, ratchet)| (index, SecretType::ApplicationSecret, ratchet));
+        handshake_ratchets
+            .chain(application_ratchets)
+            .filter_map(|(index, secret_type, ratchet)| match ratchet {
+                Some(SenderRatchet::DecryptionRatchet(dec_ratchet)) => Some((
+                    SecretTreeLeafIndex::from(index),
+                    secret_type,
+                    dec_ratchet.skipped_keys(),
+                )),
+                _ => None,
+            })
+            .filter(|(_, _, skipped_keys)| *skipped_keys > 0)
+            .collect()
+    }
+
+    /// Deletes up to `count` of the oldest skipped keys of the decryption
+    /// ratchet of the given sender and secret type. Returns the number of
+    /// deleted keys.
+    pub(crate) fn evict_skipped_keys(
+        &mut self,
+        index: SecretTreeLeafIndex,
+        secret_type: SecretType,
+        count: usize,
+    ) -> Result<usize, SecretTreeError> {
+        match self.ratchet_mut(index, secret_type)? {
+            SenderRatchet::EncryptionRatchet(_) => Err(SecretTreeError::RatchetTypeError),
+            SenderRatchet::DecryptionRatchet(dec_ratchet) => {
+                Ok(dec_ratchet.evict_skipped_keys(count))
+            }
+        }
+    }
+
     fn ratchet_mut(
         &mut self,
         index: SecretTreeLeafIndex,
         secret_type: SecretType,
-    ) -> &mut SenderRatchet {
+    ) -> Result<&mut SenderRatchet, SecretTreeError> {
         let sender_ratchets = match secret_type {
             SecretType::HandshakeSecret => &mut self.handshake_sender_ratchets,
             SecretType::ApplicationSecret => &mut self.application_sender_ratchets,
         };
         sender_ratchets
             .get_mut(index.as_usize())
-            .unwrap_or_else(|| panic!("SenderRatchets not initialized: {}", index.as_usize()))
+            .ok_or(SecretTreeError::IndexOutOfBounds)?
             .as_mut()
-            .expect("SecretTree not initialized")
+            // The sender ratchets are initialized before they are accessed
+            .ok_or(SecretTreeError::LibraryError)
     }
 
     /// Returns an optional reference to a specific SenderRatchet
@@ -356,10 +454,9 @@ impl SecretTree {
             None => return Err(SecretTreeError::LibraryError),
         };
         log_crypto!(trace, "Node secret: {:x?}", node_secret.as_slice());
-        let left_index =
-            left(index_in_tree).expect("derive_down: Error while computing left child.");
-        let right_index = right(index_in_tree, self.size)
-            .expect("derive_down: Error while computing right child.");
+        let left_index = left(index_in_tree).map_err(|_| SecretTreeError::LibraryError)?;
+        let right_index =
+            right(index_in_tree, self.size).map_err(|_| SecretTreeError::LibraryError)?;
         let left_secret = node_secret.kdf_expand_label(backend, "tree", b"left", hash_len)?;
         let right_secret = node_secret.kdf_expand_label(backend, "tree", b"right", hash_len)?;
         log_crypto!(
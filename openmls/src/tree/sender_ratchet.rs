@@ -5,10 +5,11 @@
 //! This means that some functions that are not expected to fail and throw an
 //! error, will still return a `Result` since they may throw a `LibraryError`.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 use openmls_traits::types::Ciphersuite;
 
+use crate::binary_tree::LeafNodeIndex;
 use crate::ciphersuite::{AeadNonce, *};
 use crate::tree::secret_tree::*;
 
@@ -59,6 +60,37 @@ impl Default for SenderRatchetConfiguration {
     }
 }
 
+/// The [`SenderRatchetConfiguration`]s used to decrypt the messages of a
+/// group: a configuration for all senders and overrides for individual
+/// senders.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SenderRatchetConfigurations {
+    default: SenderRatchetConfiguration,
+    overrides: HashMap<LeafNodeIndex, SenderRatchetConfiguration>,
+}
+
+impl SenderRatchetConfigurations {
+    /// Create a new set of configurations from the configuration for all
+    /// senders and the overrides for individual senders.
+    pub(crate) fn new(
+        default: SenderRatchetConfiguration,
+        overrides: HashMap<LeafNodeIndex, SenderRatchetConfiguration>,
+    ) -> Self {
+        Self { default, overrides }
+    }
+
+    /// Get the configuration for the decryption ratchets of the given sender.
+    pub(crate) fn for_sender(&self, sender: LeafNodeIndex) -> &SenderRatchetConfiguration {
+        self.overrides.get(&sender).unwrap_or(&self.default)
+    }
+}
+
+impl From<SenderRatchetConfiguration> for SenderRatchetConfigurations {
+    fn from(default: SenderRatchetConfiguration) -> Self {
+        Self::new(default, HashMap::new())
+    }
+}
+
 /// The key material derived from a [`RatchetSecret`] meant for use with a
 /// nonce-based symmetric encryption scheme.
 pub(crate) type RatchetKeyMaterial = (AeadKey, AeadNonce);
@@ -77,6 +109,14 @@ pub(crate) enum SenderRatchet {
 }
 
 impl SenderRatchet {
+    /// Overwrite the secrets of the ratchet with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        match self {
+            SenderRatchet::EncryptionRatchet(enc_ratchet) => enc_ratchet.zeroize(),
+            SenderRatchet::DecryptionRatchet(dec_ratchet) => dec_ratchet.zeroize(),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn generation(&self) -> Generation {
         match self {
@@ -107,6 +147,11 @@ impl RatchetSecret {
         }
     }
 
+    /// Overwrite the head of the ratchet chain with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+
     /// Return the generation of this [`RatchetSecret`].
     pub(crate) fn generation(&self) -> Generation {
         self.generation
@@ -178,6 +223,16 @@ impl DecryptionRatchet {
         }
     }
 
+    /// Overwrite the head of the ratchet chain and the key material of past
+    /// generations with zeros.
+    pub(crate) fn zeroize(&mut self) {
+        for (key, nonce) in self.past_secrets.iter_mut().flatten() {
+            key.zeroize();
+            nonce.zeroize();
+        }
+        self.ratchet_head.zeroize();
+    }
+
     /// Remove elements from the `past_secrets` queue until it is within the
     /// bounds determined by the [`SenderRatchetConfiguration`].
     fn prune_past_secrets(&mut self, configuration: &SenderRatchetConfiguration) {
@@ -190,6 +245,30 @@ impl DecryptionRatchet {
         self.ratchet_head.generation()
     }
 
+    /// Get the number of skipped generations whose key material is kept to
+    /// decrypt messages that arrive out of order.
+    pub(crate) fn skipped_keys(&self) -> usize {
+        self.past_secrets
+            .iter()
+            .filter(|secret| secret.is_some())
+            .count()
+    }
+
+    /// Delete the key material of up to `count` skipped generations, starting
+    /// with the oldest one. Returns the number of deleted keys.
+    pub(crate) fn evict_skipped_keys(&mut self, count: usize) -> usize {
+        let mut evicted = 0;
+        for secret in self.past_secrets.iter_mut().rev() {
+            if evicted == count {
+                break;
+            }
+            if secret.take().is_some() {
+                evicted += 1;
+            }
+        }
+        evicted
+    }
+
     #[cfg(test)]
     pub(crate) fn ratchet_secret_mut(&mut self) -> &mut RatchetSecret {
         &mut self.ratchet_head
@@ -599,7 +599,7 @@ pub fn run_test_vector(
                     backend,
                     group.message_secrets_test_mut(),
                     leaf_index.into(),
-                    &SenderRatchetConfiguration::default(),
+                    &SenderRatchetConfiguration::default().into(),
                     sender_data,
                 )
                 .expect("Error decrypting PrivateMessage");
@@ -671,7 +671,7 @@ pub fn run_test_vector(
                     backend,
                     group.message_secrets_test_mut(),
                     leaf_index.into(),
-                    &SenderRatchetConfiguration::default(),
+                    &SenderRatchetConfiguration::default().into(),
                     sender_data,
                 )
                 .expect("Error decrypting PrivateMessage");
@@ -739,7 +739,7 @@ pub fn run_test_vector(
                     backend,
                     group.message_secrets_test_mut(),
                     leaf_index.into(),
-                    &SenderRatchetConfiguration::default(),
+                    &SenderRatchetConfiguration::default().into(),
                     sender_data,
                 )
                 .expect("Error decrypting PrivateMessage");
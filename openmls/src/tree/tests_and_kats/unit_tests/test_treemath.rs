@@ -44,7 +44,7 @@ fn test_tree_hash(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
     ) -> KeyPackageBundle {
         let signature_scheme = SignatureScheme::from(ciphersuite);
         let credential_bundle = CredentialBundle::new(
-            id.to_vec(),
+            id.to_vec().into(),
             CredentialType::Basic,
             signature_scheme,
             backend,
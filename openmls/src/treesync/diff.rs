@@ -34,6 +34,9 @@ use super::{
     TreeSync, TreeSyncParentHashError, TreeSyncSetPathError,
 };
 
+#[cfg(feature = "partial-tree")]
+use super::partial_tree::PartialTree;
+
 use crate::{
     binary_tree::{
         array_representation::{LeafNodeIndex, ParentNodeIndex, TreeNodeIndex, MIN_TREE_SIZE},
@@ -778,6 +781,46 @@ impl<'a> TreeSyncDiff<'a> {
         self.compute_tree_hash(backend, ciphersuite, self.diff.root(), &HashSet::new())
     }
 
+    /// Returns the [`PartialTree`] of the leaf at `leaf_index`, i.e. the
+    /// leaf, its direct path and the tree hashes of its copath.
+    #[cfg(feature = "partial-tree")]
+    pub(super) fn partial_tree(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        leaf_index: LeafNodeIndex,
+    ) -> Result<PartialTree, LibraryError> {
+        let leaf_node = self
+            .diff
+            .leaf(leaf_index)
+            .node()
+            .as_ref()
+            .map(|leaf| leaf.leaf_node().clone());
+        let direct_path_indices = self.diff.direct_path(leaf_index);
+        // A tree with a single leaf doesn't have a copath.
+        let copath = if direct_path_indices.is_empty() {
+            vec![]
+        } else {
+            self.diff.copath(leaf_index)
+        };
+        let direct_path = direct_path_indices
+            .into_iter()
+            .map(|parent_index| self.diff.parent(parent_index).node().clone())
+            .collect();
+        let copath_tree_hashes = copath
+            .into_iter()
+            .map(|node_index| {
+                self.compute_tree_hash(backend, ciphersuite, node_index, &HashSet::new())
+            })
+            .collect::<Result<Vec<Vec<u8>>, LibraryError>>()?;
+        Ok(PartialTree::new(
+            leaf_index,
+            leaf_node,
+            direct_path,
+            copath_tree_hashes,
+        ))
+    }
+
     /// Returns the position of the subtree root shared by both given indices in
     /// the direct path of `leaf_index_1`.
     ///
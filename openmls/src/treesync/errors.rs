@@ -1,11 +1,15 @@
 //! TreeSync errors
 //!
-//! This module exposes [`ApplyUpdatePathError`] and [`PublicTreeError`].
+//! This module exposes [`ApplyUpdatePathError`] and [`PublicTreeError`], as well as
+//! `PartialTreeError` with the `partial-tree` feature.
 
 use thiserror::Error;
 
 use super::*;
-use crate::{binary_tree::MlsBinaryTreeDiffError, error::LibraryError};
+use crate::{
+    binary_tree::MlsBinaryTreeDiffError,
+    error::{error_codes, LibraryError},
+};
 
 // === Public errors ===
 
@@ -29,6 +33,14 @@ pub enum PublicTreeError {
     InvalidParentHash,
 }
 
+error_codes!(PublicTreeError: 4600 {
+    PublicKeyMismatch = 1,
+    DuplicateKeyPackage = 2,
+    MissingKeyPackage = 3,
+    MalformedTree = 4,
+    InvalidParentHash = 5,
+});
+
 /// Apply update path error
 #[derive(Error, Debug, PartialEq, Clone)]
 pub enum ApplyUpdatePathError {
@@ -57,6 +69,44 @@ pub enum ApplyUpdatePathError {
     MissingSender,
 }
 
+error_codes!(ApplyUpdatePathError: 4700 {
+    LibraryError = 1,
+    PathLengthMismatch = 2,
+    PathMismatch = 3,
+    ParentHashMismatch = 4,
+    MissingParentHash = 5,
+    UnableToDecrypt = 6,
+    MissingSender = 7,
+});
+
+/// Partial tree error
+#[cfg(feature = "partial-tree")]
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum PartialTreeError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The leaf is outside of the tree.
+    #[error("The leaf is outside of the tree.")]
+    LeafOutsideOfTree,
+    /// The direct path and the copath of the partial tree don't match the position of the leaf.
+    #[error(
+        "The direct path and the copath of the partial tree don't match the position of the leaf."
+    )]
+    MalformedPartialTree,
+    /// The tree hash computed from the partial tree doesn't match the expected one.
+    #[error("The tree hash computed from the partial tree doesn't match the expected one.")]
+    TreeHashMismatch,
+}
+
+#[cfg(feature = "partial-tree")]
+error_codes!(PartialTreeError: 5700 {
+    LibraryError = 1,
+    LeafOutsideOfTree = 2,
+    MalformedPartialTree = 3,
+    TreeHashMismatch = 4,
+});
+
 // === Crate errors ===
 
 // TODO: This will go away in #819 again.
@@ -34,7 +34,7 @@ use crate::{
     error::LibraryError,
     extensions::Extensions,
     framing::SenderError,
-    group::{config::CryptoConfig, Member},
+    group::{config::CryptoConfig, Member, MemberDetails},
     messages::{PathSecret, PathSecretError},
     schedule::CommitSecret,
 };
@@ -55,6 +55,8 @@ use errors::*;
 // Crate
 pub(crate) mod diff;
 pub(crate) mod node;
+#[cfg(feature = "partial-tree")]
+pub(crate) mod partial_tree;
 pub(crate) mod treekem;
 pub(crate) mod treesync_node;
 
@@ -63,6 +65,8 @@ pub mod errors;
 
 // Public re-exports
 pub use node::{leaf_node::LeafNode, parent_node::ParentNode, Node};
+#[cfg(feature = "partial-tree")]
+pub use partial_tree::PartialTree;
 
 // Tests
 #[cfg(any(feature = "test-utils", test))]
@@ -153,26 +157,33 @@ impl TreeSync {
     }
 
     /// A helper function that generates a [`TreeSync`] instance from the given
-    /// slice of nodes. It verifies that the provided encryption key is present
-    /// in the tree and that the invariants documented in [`TreeSync`] hold.
-    pub(crate) fn from_nodes(
+    /// nodes. It verifies that the provided encryption key is present in the
+    /// tree and that the invariants documented in [`TreeSync`] hold.
+    ///
+    /// The nodes are consumed one at a time, such that they can be
+    /// deserialized on demand, e.g. from a [`RatchetTreeExtension`]. An error
+    /// returned by `node_options` is reported as a malformed tree.
+    ///
+    /// [`RatchetTreeExtension`]: crate::extensions::RatchetTreeExtension
+    pub(crate) fn from_nodes<E>(
         backend: &impl OpenMlsCryptoProvider,
         ciphersuite: Ciphersuite,
-        node_options: &[Option<Node>],
+        node_options: impl IntoIterator<Item = Result<Option<Node>, E>>,
         encryption_key: &EncryptionKey,
     ) -> Result<Self, TreeSyncFromNodesError> {
         // TODO #800: Unmerged leaves should be checked
         // Before we can instantiate the TreeSync instance, we have to figure
         // out what our leaf index is.
+        let node_options = node_options.into_iter();
         let mut ts_nodes: Vec<TreeNode<TreeSyncLeafNode, TreeSyncParentNode>> =
-            Vec::with_capacity(node_options.len());
+            Vec::with_capacity(node_options.size_hint().0);
         let mut own_index_option = None;
 
         // Check that our own encryption key is in the tree.
-        for (node_index, node_option) in node_options.iter().enumerate() {
+        for (node_index, node_option) in node_options.enumerate() {
+            let node_option = node_option.map_err(|_| PublicTreeError::MalformedTree)?;
             let ts_node_option: TreeNode<TreeSyncLeafNode, TreeSyncParentNode> = match node_option {
-                Some(node) => {
-                    let mut node = node.clone();
+                Some(mut node) => {
                     if let Node::LeafNode(ref mut leaf_node) = node {
                         let leaf_index = LeafNodeIndex::new((node_index / 2) as u32);
                         if leaf_node.encryption_key() == encryption_key {
@@ -358,11 +369,21 @@ impl TreeSync {
                         .signature_key()
                         .as_slice()
                         .to_vec(),
-                    leaf_node.leaf_node.credential().identity().to_vec(),
+                    leaf_node.leaf_node.credential().identity().clone(),
+                    leaf_node.leaf_node.application_id().map(|id| id.to_vec()),
                 )
             })
     }
 
+    /// Returns the [`MemberDetails`] of all members in the tree.
+    pub(crate) fn full_leave_member_details(&self) -> impl Iterator<Item = MemberDetails> + '_ {
+        self.tree
+            .leaves()
+            // Filter out blank nodes
+            .filter_map(|(index, tsn)| tsn.node().as_ref().map(|node| (index, node)))
+            .map(|(index, leaf_node)| MemberDetails::from_leaf_node(index, &leaf_node.leaf_node))
+    }
+
     /// Returns a [`TreeSyncError::UnsupportedExtension`] if an [`ExtensionType`]
     /// in `extensions` is not supported by a leaf in this tree.
     #[cfg(test)]
@@ -14,6 +14,7 @@ pub(crate) mod parent_node;
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 #[allow(clippy::large_enum_variant)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Node {
     LeafNode(OpenMlsLeafNode),
     ParentNode(ParentNode),
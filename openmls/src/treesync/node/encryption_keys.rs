@@ -1,5 +1,5 @@
 use openmls_traits::crypto::OpenMlsCrypto;
-use openmls_traits::key_store::{FromKeyStoreValue, OpenMlsKeyStore, ToKeyStoreValue};
+use openmls_traits::key_store::OpenMlsKeyStore;
 use openmls_traits::types::{Ciphersuite, CryptoError, HpkeCiphertext, HpkeKeyPair};
 use openmls_traits::OpenMlsCryptoProvider;
 use serde::{Deserialize, Serialize};
@@ -15,7 +15,9 @@ use crate::versions::ProtocolVersion;
 #[derive(
     Debug, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize, PartialEq, Eq,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub(crate) struct EncryptionKey {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     key: HpkePublicKey,
 }
 
@@ -34,7 +36,7 @@ impl EncryptionKey {
     /// the `ENCRYPTION_KEY_LABEL`.
     ///
     /// Returns the resulting bytes.
-    fn to_bytes_with_prefix(&self) -> Vec<u8> {
+    pub(crate) fn to_bytes_with_prefix(&self) -> Vec<u8> {
         let mut key_store_index = ENCRYPTION_KEY_LABEL.to_vec();
         key_store_index.extend_from_slice(self.as_slice());
         key_store_index
@@ -90,7 +92,7 @@ pub(crate) struct EncryptionKeyPair {
     private_key: EncryptionPrivateKey,
 }
 
-const ENCRYPTION_KEY_LABEL: &[u8; 19] = b"leaf_encryption_key";
+pub(crate) const ENCRYPTION_KEY_LABEL: &[u8; 19] = b"leaf_encryption_key";
 
 impl EncryptionKeyPair {
     /// Write the [`EncryptionKeyPair`] to the key store of the `backend`. This
@@ -135,6 +137,20 @@ impl EncryptionKeyPair {
             .delete(&self.public_key().to_bytes_with_prefix())
     }
 
+    /// Release the private key of this [`EncryptionKeyPair`] in the crypto
+    /// provider of the `backend`. This function is meant for keypairs that
+    /// are not used by any group anymore.
+    ///
+    /// Returns an error if the crypto provider fails to release the key.
+    pub(crate) fn release_private_key(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<(), CryptoError> {
+        backend
+            .crypto()
+            .release_private_key(self.private_key.key.as_slice())
+    }
+
     pub(crate) fn public_key(&self) -> &EncryptionKey {
         &self.public_key
     }
@@ -184,20 +200,3 @@ impl From<(EncryptionKey, EncryptionPrivateKey)> for EncryptionKeyPair {
         }
     }
 }
-
-impl ToKeyStoreValue for EncryptionKeyPair {
-    type Error = LibraryError;
-
-    fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error> {
-        serde_json::to_vec(self)
-            .map_err(|_| LibraryError::custom("Error serializing encryption key."))
-    }
-}
-
-impl FromKeyStoreValue for EncryptionKeyPair {
-    type Error = LibraryError;
-
-    fn from_key_store_value(ksv: &[u8]) -> Result<Self, Self::Error> {
-        serde_json::from_slice(ksv).map_err(|_| LibraryError::custom("Invalid encryption key."))
-    }
-}
@@ -2,7 +2,7 @@
 use openmls_traits::{key_store::OpenMlsKeyStore, types::Ciphersuite, OpenMlsCryptoProvider};
 use serde::{Deserialize, Serialize};
 use tls_codec::{
-    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, TlsDeserialize,
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, Size, TlsDeserialize,
     TlsSerialize, TlsSize, VLBytes,
 };
 
@@ -15,9 +15,9 @@ use crate::{
         HpkePublicKey, Signature, SignaturePublicKey,
     },
     credentials::{Credential, CredentialBundle, CredentialType},
-    error::LibraryError,
+    error::{error_codes, LibraryError},
     extensions::Extensions,
-    extensions::{ExtensionType, RequiredCapabilitiesExtension},
+    extensions::{CustomExtension, ExtensionType, RequiredCapabilitiesExtension},
     group::{config::CryptoConfig, GroupId},
     key_packages::KeyPackage,
     messages::proposals::ProposalType,
@@ -25,7 +25,10 @@ use crate::{
     versions::ProtocolVersion,
 };
 
+mod grease;
 mod lifetime;
+use self::grease::CapabilitiesGrease;
+pub use self::grease::{is_grease, GREASE_VALUES};
 pub use self::lifetime::Lifetime;
 
 use super::encryption_keys::{EncryptionKey, EncryptionKeyPair};
@@ -40,6 +43,31 @@ pub enum LeafNodeGenerationError<KeyStoreError> {
     KeyStoreError(KeyStoreError),
 }
 
+error_codes!(LeafNodeGenerationError<KeyStoreError>: 4400 {
+    LibraryError = 1,
+    KeyStoreError = 2,
+});
+
+/// Capabilities error
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum CapabilitiesError {
+    /// A required extension type is not supported.
+    #[error("A required extension type is not supported.")]
+    UnsupportedExtensionType,
+    /// A required proposal type is not supported.
+    #[error("A required proposal type is not supported.")]
+    UnsupportedProposalType,
+    /// A required credential type is not supported.
+    #[error("A required credential type is not supported.")]
+    UnsupportedCredentialType,
+}
+
+error_codes!(CapabilitiesError: 4500 {
+    UnsupportedExtensionType = 1,
+    UnsupportedProposalType = 2,
+    UnsupportedCredentialType = 3,
+});
+
 /// Capabilities of [`LeafNode`]s.
 ///
 /// ```text
@@ -51,15 +79,87 @@ pub enum LeafNodeGenerationError<KeyStoreError> {
 ///     CredentialType credentials<V>;
 /// } Capabilities;
 /// ```
-#[derive(
-    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
-)]
+///
+/// [`GREASE_VALUES`] in the lists of ciphersuites, extension types, proposal
+/// types and credential types are ignored, but preserved such that the
+/// capabilities are re-serialized byte-exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Capabilities {
     versions: Vec<ProtocolVersion>,
     ciphersuites: Vec<Ciphersuite>,
     extensions: Vec<ExtensionType>,
     proposals: Vec<ProposalType>,
     credentials: Vec<CredentialType>,
+    #[serde(default)]
+    grease: CapabilitiesGrease,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Capabilities {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let versions = u.arbitrary()?;
+        let ciphersuites = (0..u.arbitrary_len::<u16>()?)
+            .map(|_| crate::utils::arbitrary_ciphersuite(u))
+            .collect::<arbitrary::Result<_>>()?;
+        // GREASE values would be deserialized into the GREASE lists, so they
+        // are left out.
+        let extensions = u
+            .arbitrary::<Vec<ExtensionType>>()?
+            .into_iter()
+            .filter(|extension_type| !grease::is_grease(u16::from(*extension_type)))
+            .collect();
+        Ok(Self {
+            versions,
+            ciphersuites,
+            extensions,
+            proposals: u.arbitrary()?,
+            credentials: u.arbitrary()?,
+            grease: CapabilitiesGrease::default(),
+        })
+    }
+}
+
+impl Size for Capabilities {
+    fn tls_serialized_len(&self) -> usize {
+        self.versions.tls_serialized_len()
+            + grease::list_serialized_len(&self.ciphersuites, &self.grease.ciphersuites)
+            + grease::list_serialized_len(&self.extensions, &self.grease.extensions)
+            + grease::list_serialized_len(&self.proposals, &self.grease.proposals)
+            + grease::list_serialized_len(&self.credentials, &self.grease.credentials)
+    }
+}
+
+impl TlsSerializeTrait for Capabilities {
+    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        Ok(self.versions.tls_serialize(writer)?
+            + grease::write_list(&self.ciphersuites, &self.grease.ciphersuites, writer)?
+            + grease::write_list(&self.extensions, &self.grease.extensions, writer)?
+            + grease::write_list(&self.proposals, &self.grease.proposals, writer)?
+            + grease::write_list(&self.credentials, &self.grease.credentials, writer)?)
+    }
+}
+
+impl TlsDeserializeTrait for Capabilities {
+    fn tls_deserialize<R: std::io::Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
+        let versions = Vec::tls_deserialize(bytes)?;
+        let (ciphersuites, ciphersuites_grease) = grease::read_list(bytes)?;
+        let (extensions, extensions_grease) = grease::read_list(bytes)?;
+        let (proposals, proposals_grease) = grease::read_list(bytes)?;
+        let (credentials, credentials_grease) = grease::read_list(bytes)?;
+        Ok(Self {
+            versions,
+            ciphersuites,
+            extensions,
+            proposals,
+            credentials,
+            grease: CapabilitiesGrease {
+                ciphersuites: ciphersuites_grease,
+                extensions: extensions_grease,
+                proposals: proposals_grease,
+                credentials: credentials_grease,
+            },
+        })
+    }
 }
 
 // FIXME: deduplicate with CapabilitiesExtension.
@@ -98,6 +198,12 @@ fn default_credentials() -> Vec<CredentialType> {
 }
 
 impl Capabilities {
+    /// Create a [`CapabilitiesBuilder`] that is initialized with the default
+    /// capabilities.
+    pub fn builder() -> CapabilitiesBuilder {
+        CapabilitiesBuilder::new()
+    }
+
     /// Create new empty [`Capabilities`].
     pub fn empty() -> Self {
         Self {
@@ -106,6 +212,7 @@ impl Capabilities {
             extensions: Vec::new(),
             proposals: Vec::new(),
             credentials: Vec::new(),
+            grease: CapabilitiesGrease::default(),
         }
     }
 
@@ -140,6 +247,7 @@ impl Capabilities {
                 Some(c) => c.into(),
                 None => default_credentials(),
             },
+            grease: CapabilitiesGrease::default(),
         }
     }
 
@@ -180,6 +288,13 @@ impl Capabilities {
         &self.credentials
     }
 
+    /// Returns `true` if the [`ExtensionType`] is supported by these
+    /// [`Capabilities`], either explicitly or as a default extension.
+    pub(crate) fn supports_extension(&self, extension_type: &ExtensionType) -> bool {
+        self.extensions.contains(extension_type)
+            || default_extensions().iter().any(|et| et == extension_type)
+    }
+
     /// Check if these [`Capabilities`] support all the capabilities
     /// required by the given [`RequiredCapabilities`] extension. Returns
     /// `true` if that is the case and `false` otherwise.
@@ -187,13 +302,27 @@ impl Capabilities {
         &self,
         required_capabilities: &RequiredCapabilitiesExtension,
     ) -> bool {
+        self.check_required_capabilities(required_capabilities)
+            .is_ok()
+    }
+
+    /// Check that these [`Capabilities`] are consistent with the capabilities
+    /// required by a group, i.e. that all extension, proposal and credential
+    /// types of the given [`RequiredCapabilitiesExtension`] are supported.
+    ///
+    /// Returns a [`CapabilitiesError`] describing the first unsupported
+    /// capability otherwise.
+    pub fn check_required_capabilities(
+        &self,
+        required_capabilities: &RequiredCapabilitiesExtension,
+    ) -> Result<(), CapabilitiesError> {
         // Check if all required extensions are supported.
         if required_capabilities
             .extension_types()
             .iter()
             .any(|e| !self.extensions().contains(e))
         {
-            return false;
+            return Err(CapabilitiesError::UnsupportedExtensionType);
         }
         // Check if all required proposals are supported.
         if required_capabilities
@@ -201,9 +330,120 @@ impl Capabilities {
             .iter()
             .any(|p| !self.proposals().contains(p))
         {
-            return false;
+            return Err(CapabilitiesError::UnsupportedProposalType);
         }
-        true
+        // Check if all required credentials are supported.
+        if required_capabilities
+            .credential_types()
+            .iter()
+            .any(|c| !self.credentials().contains(c))
+        {
+            return Err(CapabilitiesError::UnsupportedCredentialType);
+        }
+        Ok(())
+    }
+}
+
+/// Builder for [`Capabilities`].
+///
+/// The builder starts out with the default capabilities. Every setter replaces
+/// the respective list. Use [`CapabilitiesBuilder::build_for()`] to make sure
+/// the resulting capabilities are consistent with the required capabilities
+/// of the group they are meant for.
+///
+/// ```
+/// use openmls::prelude::*;
+///
+/// let capabilities = Capabilities::builder()
+///     .versions(vec![ProtocolVersion::Mls10])
+///     .ciphersuites(vec![Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519])
+///     .credentials(vec![CredentialType::Basic])
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CapabilitiesBuilder {
+    capabilities: Capabilities,
+}
+
+impl CapabilitiesBuilder {
+    /// Create a new [`CapabilitiesBuilder`] initialized with the default
+    /// capabilities.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the supported protocol versions.
+    pub fn versions(mut self, versions: Vec<ProtocolVersion>) -> Self {
+        self.capabilities.versions = versions;
+        self
+    }
+
+    /// Set the supported ciphersuites.
+    pub fn ciphersuites(mut self, ciphersuites: Vec<Ciphersuite>) -> Self {
+        self.capabilities.ciphersuites = ciphersuites;
+        self
+    }
+
+    /// Set the supported extension types.
+    pub fn extensions(mut self, extensions: Vec<ExtensionType>) -> Self {
+        self.capabilities.extensions = extensions;
+        self
+    }
+
+    /// Add the extension type of the [`CustomExtension`] `T` to the supported
+    /// extension types.
+    pub fn custom_extension<T: CustomExtension>(mut self) -> Self {
+        let extension_type = ExtensionType::custom::<T>();
+        if !self.capabilities.extensions.contains(&extension_type) {
+            self.capabilities.extensions.push(extension_type);
+        }
+        self
+    }
+
+    /// Set the supported proposal types.
+    pub fn proposals(mut self, proposals: Vec<ProposalType>) -> Self {
+        self.capabilities.proposals = proposals;
+        self
+    }
+
+    /// Set the supported credential types.
+    pub fn credentials(mut self, credentials: Vec<CredentialType>) -> Self {
+        self.capabilities.credentials = credentials;
+        self
+    }
+
+    /// Insert a random GREASE value at a random position in each of the lists of
+    /// ciphersuites, extension types, proposal types and credential types,
+    /// replacing previously inserted GREASE values. The GREASE values are only
+    /// visible in the serialized capabilities.
+    ///
+    /// Advertising GREASE values makes sure that peers ignore values they don't
+    /// know, such that new values can be deployed later on.
+    pub fn grease(mut self, backend: &impl OpenMlsCryptoProvider) -> Self {
+        self.capabilities.grease = CapabilitiesGrease::random(
+            backend,
+            self.capabilities.ciphersuites.len(),
+            self.capabilities.extensions.len(),
+            self.capabilities.proposals.len(),
+            self.capabilities.credentials.len(),
+        );
+        self
+    }
+
+    /// Build the [`Capabilities`].
+    pub fn build(self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Build the [`Capabilities`] and check that they support everything
+    /// required by the given [`RequiredCapabilitiesExtension`].
+    pub fn build_for(
+        self,
+        required_capabilities: &RequiredCapabilitiesExtension,
+    ) -> Result<Capabilities, CapabilitiesError> {
+        self.capabilities
+            .check_required_capabilities(required_capabilities)?;
+        Ok(self.capabilities)
     }
 }
 
@@ -215,21 +455,31 @@ impl Default for Capabilities {
             extensions: default_extensions(),
             proposals: default_proposals(),
             credentials: default_credentials(),
+            grease: CapabilitiesGrease::default(),
         }
     }
 }
 
 pub type ParentHash = VLBytes;
 
+/// The source of a [`LeafNode`], i.e. how it was added to the tree.
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum LeafNodeSource {
+    /// The leaf node is part of a key package with the given [`Lifetime`].
     #[tls_codec(discriminant = 1)]
     KeyPackage(Lifetime),
+    /// The leaf node was sent in an update proposal.
     Update,
-    Commit(ParentHash),
+    /// The leaf node was sent in the path of a commit with the given parent
+    /// hash.
+    Commit(
+        #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
+        ParentHash,
+    ),
 }
 
 #[derive(Debug, TlsSerialize, TlsDeserialize, TlsSize)]
@@ -303,6 +553,7 @@ impl TreeInfoTbs {
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 struct LeafNodePayload {
     encryption_key: EncryptionKey,
     signature_key: SignaturePublicKey,
@@ -412,6 +663,7 @@ impl TlsDeserializeTrait for LeafNodeTbs {
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct LeafNode {
     payload: LeafNodePayload,
     signature: Signature,
@@ -517,6 +769,11 @@ impl LeafNode {
         &self.payload.encryption_key
     }
 
+    /// Returns the public HPKE encryption key as byte slice.
+    pub fn public_encryption_key(&self) -> &[u8] {
+        self.payload.encryption_key.as_slice()
+    }
+
     /// Returns the `signature_key` as byte slice.
     pub fn signature_key(&self) -> &SignaturePublicKey {
         &self.payload.signature_key
@@ -545,13 +802,28 @@ impl LeafNode {
         }
     }
 
+    /// Returns a reference to the [`LeafNodeSource`] of this leaf.
+    pub fn leaf_node_source(&self) -> &LeafNodeSource {
+        &self.payload.leaf_node_source
+    }
+
     /// Returns a reference to the [`Signature`] of this leaf.
     pub fn signature(&self) -> &Signature {
         &self.signature
     }
 
+    /// Return the application id from the [`ApplicationIdExtension`] of this
+    /// leaf node, if present.
+    ///
+    /// [`ApplicationIdExtension`]: crate::extensions::ApplicationIdExtension
+    pub fn application_id(&self) -> Option<&[u8]> {
+        self.extensions()
+            .application_id()
+            .map(|application_id| application_id.as_slice())
+    }
+
     /// Return a reference to [`Capabilities`].
-    pub(crate) fn capabilities(&self) -> &Capabilities {
+    pub fn capabilities(&self) -> &Capabilities {
         &self.payload.capabilities
     }
 
@@ -585,12 +857,7 @@ impl LeafNode {
 
     /// Returns `true` if the [`ExtensionType`] is supported by this leaf node.
     pub(crate) fn supports_extension(&self, extension_type: &ExtensionType) -> bool {
-        self.payload
-            .capabilities
-            .extensions
-            .iter()
-            .any(|et| et == extension_type)
-            || default_extensions().iter().any(|et| et == extension_type)
+        self.payload.capabilities.supports_extension(extension_type)
     }
 
     /// Returns `true` if the [`ProposalType`] is supported by this leaf node.
@@ -753,6 +1020,13 @@ impl From<LeafNode> for OpenMlsLeafNode {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for OpenMlsLeafNode {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(u.arbitrary::<LeafNode>()?.into())
+    }
+}
+
 impl From<KeyPackage> for OpenMlsLeafNode {
     fn from(key_package: KeyPackage) -> Self {
         Self {
@@ -796,6 +1070,10 @@ impl OpenMlsLeafNode {
     }
 
     /// Update the `encryption_key` in this leaf node and re-signs it.
+    ///
+    /// Returns a [`LibraryError`] if the identity of the new credential differs
+    /// from the identity in this leaf (ValSem109). Callers have to check this
+    /// beforehand.
     pub(crate) fn update_and_re_sign(
         &mut self,
         new_encryption_key: &EncryptionKey,
@@ -809,7 +1087,12 @@ impl OpenMlsLeafNode {
         leaf_node_tbs.payload.encryption_key = new_encryption_key.clone();
 
         // Update credential
-        // TODO: #133 ValSem109 check that the identity is the same.
+        // ValSem109
+        if credential_bundle.credential().identity() != self.leaf_node.credential().identity() {
+            return Err(LibraryError::custom(
+                "The identity of the new credential differs from the identity of the leaf.",
+            ));
+        }
         leaf_node_tbs.payload.credential = credential_bundle.credential().clone();
 
         // Set the new signed leaf node with the new encryption key
@@ -819,6 +1102,20 @@ impl OpenMlsLeafNode {
         Ok(())
     }
 
+    /// Replace the capabilities and extensions of this leaf.
+    ///
+    /// This doesn't re-sign the leaf node, which has to be done with
+    /// [`OpenMlsLeafNode::rekey()`] or [`OpenMlsLeafNode::update_and_re_sign()`]
+    /// afterwards.
+    pub(crate) fn set_capabilities_and_extensions(
+        &mut self,
+        capabilities: Capabilities,
+        extensions: Extensions,
+    ) {
+        self.leaf_node.payload.capabilities = capabilities;
+        self.leaf_node.payload.extensions = extensions;
+    }
+
     /// Replace the encryption key in this leaf with a random one.
     ///
     /// This signs the new leaf node as well.
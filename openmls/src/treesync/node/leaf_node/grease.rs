@@ -0,0 +1,136 @@
+use std::io::{Read, Write};
+
+use openmls_traits::OpenMlsCryptoProvider;
+use serde::{Deserialize, Serialize};
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, Size, VLBytes,
+};
+
+/// The GREASE values (Generate Random Extensions And Sustain Extensibility)
+/// for ciphersuites, extension types, proposal types and credential types.
+///
+/// Clients can advertise GREASE values in their [`Capabilities`] to make sure
+/// that their peers ignore values they don't know, such that new values can
+/// be deployed later on.
+///
+/// [`Capabilities`]: super::Capabilities
+pub const GREASE_VALUES: [u16; 15] = [
+    0x0A0A, 0x1A1A, 0x2A2A, 0x3A3A, 0x4A4A, 0x5A5A, 0x6A6A, 0x7A7A, 0x8A8A, 0x9A9A, 0xAAAA, 0xBABA,
+    0xCACA, 0xDADA, 0xEAEA,
+];
+
+/// Returns `true` if the `value` is one of the [`GREASE_VALUES`].
+pub fn is_grease(value: u16) -> bool {
+    GREASE_VALUES.contains(&value)
+}
+
+/// The GREASE values in the lists of [`Capabilities`], together with their
+/// positions in the serialized lists, such that the capabilities are
+/// re-serialized byte-exactly.
+///
+/// [`Capabilities`]: super::Capabilities
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CapabilitiesGrease {
+    pub(super) ciphersuites: Vec<(usize, u16)>,
+    pub(super) extensions: Vec<(usize, u16)>,
+    pub(super) proposals: Vec<(usize, u16)>,
+    pub(super) credentials: Vec<(usize, u16)>,
+}
+
+impl CapabilitiesGrease {
+    /// Returns GREASE values for all lists, each at a random position in a list
+    /// of the given length.
+    pub(super) fn random(
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuites: usize,
+        extensions: usize,
+        proposals: usize,
+        credentials: usize,
+    ) -> Self {
+        let random: [u8; 8] = backend
+            .rand()
+            .random_array()
+            .expect("Not enough randomness.");
+        let grease = |i: usize, len: usize| {
+            vec![(
+                random[2 * i] as usize % (len + 1),
+                GREASE_VALUES[random[2 * i + 1] as usize % GREASE_VALUES.len()],
+            )]
+        };
+        Self {
+            ciphersuites: grease(0, ciphersuites),
+            extensions: grease(1, extensions),
+            proposals: grease(2, proposals),
+            credentials: grease(3, credentials),
+        }
+    }
+}
+
+/// Serializes the list of `values` with the `grease` values inserted at their
+/// positions. All values have to be serialized as `u16`.
+fn serialize_list<T: TlsSerializeTrait>(
+    values: &[T],
+    grease: &[(usize, u16)],
+) -> Result<VLBytes, tls_codec::Error> {
+    let mut content = Vec::with_capacity(2 * (values.len() + grease.len()));
+    let mut values = values.iter();
+    let mut grease = grease.iter().peekable();
+    let mut position = 0;
+    loop {
+        // GREASE values with positions beyond the end of the list are
+        // appended.
+        if let Some((_, value)) =
+            grease.next_if(|(grease_position, _)| *grease_position <= position)
+        {
+            value.tls_serialize(&mut content)?;
+        } else if let Some(value) = values.next() {
+            value.tls_serialize(&mut content)?;
+        } else if let Some((_, value)) = grease.next() {
+            value.tls_serialize(&mut content)?;
+        } else {
+            break;
+        }
+        position += 1;
+    }
+    Ok(content.into())
+}
+
+/// Returns the serialized length of the list of `values` with the `grease`
+/// values.
+pub(super) fn list_serialized_len<T>(values: &[T], grease: &[(usize, u16)]) -> usize {
+    VLBytes::new(vec![0; 2 * (values.len() + grease.len())]).tls_serialized_len()
+}
+
+/// Writes the list of `values` with the `grease` values inserted at their
+/// positions.
+pub(super) fn write_list<T: TlsSerializeTrait, W: Write>(
+    values: &[T],
+    grease: &[(usize, u16)],
+    writer: &mut W,
+) -> Result<usize, tls_codec::Error> {
+    serialize_list(values, grease)?.tls_serialize(writer)
+}
+
+/// Reads a list of values and GREASE values, which are returned separately
+/// with their positions. All values have to be serialized as `u16`.
+pub(super) fn read_list<T: TlsDeserializeTrait, R: Read>(
+    bytes: &mut R,
+) -> Result<(Vec<T>, Vec<(usize, u16)>), tls_codec::Error> {
+    let content = VLBytes::tls_deserialize(bytes)?;
+    let mut values = Vec::new();
+    let mut grease = Vec::new();
+    for (position, mut value) in content.as_slice().chunks(2).enumerate() {
+        if value.len() != 2 {
+            return Err(tls_codec::Error::DecodingError(
+                "Invalid length of a capabilities list".into(),
+            ));
+        }
+        let raw = u16::from_be_bytes([value[0], value[1]]);
+        if is_grease(raw) {
+            grease.push((position, raw));
+        } else {
+            values.push(T::tls_deserialize(&mut value)?);
+        }
+    }
+    Ok((values, grease))
+}
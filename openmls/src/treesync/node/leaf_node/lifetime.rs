@@ -32,6 +32,7 @@ const DEFAULT_KEY_PACKAGE_LIFETIME_MARGIN_SECONDS: u64 = 60 * 60;
 #[derive(
     PartialEq, Eq, Copy, Clone, Debug, TlsSerialize, TlsSize, TlsDeserialize, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Lifetime {
     not_before: u64,
     not_after: u64,
@@ -55,6 +56,18 @@ impl Lifetime {
         }
     }
 
+    /// Returns the time (in seconds since the UNIX epoch) from which on the
+    /// lifetime is valid.
+    pub fn not_before(&self) -> u64 {
+        self.not_before
+    }
+
+    /// Returns the time (in seconds since the UNIX epoch) until which the
+    /// lifetime is valid.
+    pub fn not_after(&self) -> u64 {
+        self.not_after
+    }
+
     /// Returns true if this lifetime is valid.
     pub(crate) fn is_valid(&self) -> bool {
         let now = SystemTime::now()
@@ -27,8 +27,10 @@ use super::encryption_keys::{EncryptionKey, EncryptionKeyPair};
 #[derive(
     Debug, Eq, PartialEq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ParentNode {
     pub(super) encryption_key: EncryptionKey,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::utils::arbitrary_vl_bytes))]
     pub(super) parent_hash: VLBytes,
     pub(super) unmerged_leaves: UnmergedLeaves,
 }
@@ -206,6 +208,17 @@ pub(in crate::treesync) struct UnmergedLeaves {
     list: Vec<LeafNodeIndex>,
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for UnmergedLeaves {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // The list of unmerged leaves must be sorted and free of duplicates.
+        let mut list: Vec<LeafNodeIndex> = u.arbitrary()?;
+        list.sort();
+        list.dedup();
+        Ok(Self { list })
+    }
+}
+
 impl UnmergedLeaves {
     pub(in crate::treesync) fn new() -> Self {
         Self { list: Vec::new() }
@@ -0,0 +1,143 @@
+//! This module provides the [`PartialTree`], a slice of the ratchet tree for
+//! clients that can't afford to receive the full ratchet tree of a large
+//! group.
+
+use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize, VLByteSlice, VLBytes};
+
+use crate::binary_tree::array_representation::LeafNodeIndex;
+
+use super::{errors::PartialTreeError, hashes::TreeHashInput, LeafNode, ParentNode, TreeSync};
+
+/// The part of a ratchet tree that is relevant for a single leaf: the leaf
+/// itself, the nodes in its direct path and the tree hashes of the subtrees
+/// in its copath.
+///
+/// A [`PartialTree`] is much smaller than the full ratchet tree of a large
+/// group, since it only grows logarithmically with the number of members. It
+/// can be verified against the tree hash of the group with
+/// [`PartialTree::verify()`], which ensures that the leaf and the direct path
+/// are the ones of the group.
+///
+/// ```text
+/// struct {
+///     uint32 leaf_index;
+///     optional<LeafNode> leaf_node;
+///     optional<ParentNode> direct_path<V>;
+///     opaque copath_tree_hashes<V><V>;
+/// } PartialTree;
+/// ```
+#[derive(Debug, Clone, PartialEq, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct PartialTree {
+    leaf_index: LeafNodeIndex,
+    leaf_node: Option<LeafNode>,
+    direct_path: Vec<Option<ParentNode>>,
+    copath_tree_hashes: Vec<VLBytes>,
+}
+
+impl PartialTree {
+    /// Create a new [`PartialTree`]. The `direct_path` is ordered from the
+    /// parent of the leaf to the root and the `copath_tree_hashes` from the
+    /// sibling of the leaf to the child of the root.
+    pub(super) fn new(
+        leaf_index: LeafNodeIndex,
+        leaf_node: Option<LeafNode>,
+        direct_path: Vec<Option<ParentNode>>,
+        copath_tree_hashes: Vec<Vec<u8>>,
+    ) -> Self {
+        Self {
+            leaf_index,
+            leaf_node,
+            direct_path,
+            copath_tree_hashes: copath_tree_hashes.into_iter().map(VLBytes::from).collect(),
+        }
+    }
+
+    /// Returns the index of the leaf.
+    pub fn leaf_index(&self) -> LeafNodeIndex {
+        self.leaf_index
+    }
+
+    /// Returns the leaf node, or `None` if the leaf is blank.
+    pub fn leaf_node(&self) -> Option<&LeafNode> {
+        self.leaf_node.as_ref()
+    }
+
+    /// Returns the nodes in the direct path of the leaf, ordered from the
+    /// parent of the leaf to the root. Blank nodes are `None`.
+    pub fn direct_path(&self) -> &[Option<ParentNode>] {
+        &self.direct_path
+    }
+
+    /// Verifies that this [`PartialTree`] is a part of the ratchet tree with
+    /// the given `tree_hash`, e.g. the tree hash in the group context of a
+    /// verified GroupInfo.
+    ///
+    /// The tree hash is computed from the leaf, the direct path and the tree
+    /// hashes of the copath.
+    pub fn verify(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        tree_hash: &[u8],
+    ) -> Result<(), PartialTreeError> {
+        if self.direct_path.len() != self.copath_tree_hashes.len() {
+            return Err(PartialTreeError::MalformedPartialTree);
+        }
+        // The tree has 2^n leaves if the direct path has n nodes.
+        match self
+            .leaf_index
+            .u32()
+            .checked_shr(self.direct_path.len() as u32)
+        {
+            Some(0) => (),
+            Some(_) => return Err(PartialTreeError::LeafOutsideOfTree),
+            None => return Err(PartialTreeError::MalformedPartialTree),
+        }
+
+        let mut hash = TreeHashInput::new_leaf(&self.leaf_index, self.leaf_node.as_ref())
+            .hash(backend, ciphersuite)?;
+        for (level, (parent_node, copath_tree_hash)) in self
+            .direct_path
+            .iter()
+            .zip(self.copath_tree_hashes.iter())
+            .enumerate()
+        {
+            // The bit of the leaf index at this level tells whether the
+            // subtree we come from is the left or the right child.
+            let (left_hash, right_hash) = if (self.leaf_index.u32() >> level) & 1 == 0 {
+                (hash.as_slice(), copath_tree_hash.as_slice())
+            } else {
+                (copath_tree_hash.as_slice(), hash.as_slice())
+            };
+            hash = TreeHashInput::new_parent(
+                parent_node.as_ref(),
+                VLByteSlice(left_hash),
+                VLByteSlice(right_hash),
+            )
+            .hash(backend, ciphersuite)?;
+        }
+
+        if hash != tree_hash {
+            return Err(PartialTreeError::TreeHashMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl TreeSync {
+    /// Returns the [`PartialTree`] of the leaf at `leaf_index`.
+    pub(crate) fn partial_tree(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        leaf_index: LeafNodeIndex,
+    ) -> Result<PartialTree, PartialTreeError> {
+        if leaf_index.u32() >= self.leaf_count() {
+            return Err(PartialTreeError::LeafOutsideOfTree);
+        }
+        Ok(self
+            .empty_diff()
+            .partial_tree(backend, ciphersuite, leaf_index)?)
+    }
+}
@@ -1,3 +1,5 @@
+use std::convert::Infallible;
+
 use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
 use rstest::*;
 use rstest_reuse::apply;
@@ -17,7 +19,7 @@ use openmls_rust_crypto::OpenMlsRustCrypto;
 #[apply(ciphersuites_and_backends)]
 fn test_free_leaf_computation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     let cb_0 = CredentialBundle::new(
-        "leaf0".as_bytes().to_vec(),
+        "leaf0".into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
@@ -27,7 +29,7 @@ fn test_free_leaf_computation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
     let kpb_0 = KeyPackageBundle::new(backend, ciphersuite, &cb_0);
 
     let cb_3 = CredentialBundle::new(
-        "leaf3".as_bytes().to_vec(),
+        "leaf3".into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
@@ -60,7 +62,7 @@ fn test_free_leaf_computation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
     let tree = TreeSync::from_nodes(
         backend,
         ciphersuite,
-        &nodes,
+        nodes.into_iter().map(Ok::<_, Infallible>),
         encryption_key_pair.public_key(),
     )
     .expect("error generating tree");
@@ -68,7 +70,7 @@ fn test_free_leaf_computation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
     // Create and add a new leaf. It should go to leaf index 1
 
     let cb_2 = CredentialBundle::new(
-        "leaf2".as_bytes().to_vec(),
+        "leaf2".into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
@@ -178,6 +178,27 @@ impl<'a> TreeSyncDiff<'a> {
 
         Ok((path, keypairs, commit_secret))
     }
+
+    /// Returns the public part of the `update_path` of the member at
+    /// `sender_leaf_index` without decrypting any path secret. This allows
+    /// parties that are not members of the group to follow the public state
+    /// of the tree.
+    ///
+    /// Returns an error if the path doesn't have the right length.
+    pub(crate) fn public_path(
+        &self,
+        sender_leaf_index: LeafNodeIndex,
+        update_path: Vec<UpdatePathNode>,
+    ) -> Result<Vec<ParentNode>, ApplyUpdatePathError> {
+        // ValSem202: Path must be the right length
+        if self.filtered_direct_path(sender_leaf_index).len() != update_path.len() {
+            return Err(ApplyUpdatePathError::PathLengthMismatch);
+        }
+        Ok(update_path
+            .into_iter()
+            .map(|update_path_node| update_path_node.public_key.into())
+            .collect())
+    }
 }
 
 pub(crate) struct DecryptPathParams<'a> {
@@ -199,19 +220,38 @@ pub(crate) struct DecryptPathParams<'a> {
 #[derive(
     Debug, Eq, PartialEq, Clone, Serialize, Deserialize, TlsDeserialize, TlsSerialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UpdatePathNode {
     pub(super) public_key: EncryptionKey,
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = arbitrary_encrypted_path_secrets)
+    )]
     pub(super) encrypted_path_secrets: Vec<HpkeCiphertext>,
 }
 
+#[cfg(feature = "arbitrary")]
+fn arbitrary_encrypted_path_secrets(
+    u: &mut arbitrary::Unstructured<'_>,
+) -> arbitrary::Result<Vec<HpkeCiphertext>> {
+    (0..u.arbitrary_len::<(Vec<u8>, Vec<u8>)>()?)
+        .map(|_| crate::utils::arbitrary_hpke_ciphertext(u))
+        .collect()
+}
+
 impl UpdatePathNode {
     /// Return the `encrypted_path_secrets`.
     fn encrypted_path_secrets(&self, ciphertext_index: usize) -> Option<&HpkeCiphertext> {
         self.encrypted_path_secrets.get(ciphertext_index)
     }
 
+    /// Return all `encrypted_path_secrets`.
+    pub(crate) fn ciphertexts(&self) -> &[HpkeCiphertext] {
+        &self.encrypted_path_secrets
+    }
+
     /// Return the `public_key`.
-    fn public_key(&self) -> &HpkePublicKey {
+    pub(crate) fn public_key(&self) -> &HpkePublicKey {
         self.public_key.key()
     }
 
@@ -344,6 +384,7 @@ impl PlaintextSecret {
 #[derive(
     Debug, PartialEq, Eq, Clone, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct UpdatePath {
     leaf_node: LeafNode,
     nodes: Vec<UpdatePathNode>,
@@ -355,6 +396,11 @@ impl UpdatePath {
         Self { leaf_node, nodes }
     }
 
+    /// Return the `nodes` of this [`UpdatePath`].
+    pub(crate) fn nodes(&self) -> &[UpdatePathNode] {
+        &self.nodes
+    }
+
     /// Return the `leaf_node` of this [`UpdatePath`].
     pub(crate) fn leaf_node(&self) -> &LeafNode {
         &self.leaf_node
@@ -24,6 +24,64 @@ pub(crate) fn zero(length: usize) -> Vec<u8> {
     vec![0u8; length]
 }
 
+// === Helpers for the `Arbitrary` implementations of protocol types.
+
+/// Returns arbitrary bytes as [`VLBytes`](tls_codec::VLBytes).
+#[cfg(feature = "arbitrary")]
+pub(crate) fn arbitrary_vl_bytes(
+    u: &mut arbitrary::Unstructured<'_>,
+) -> arbitrary::Result<tls_codec::VLBytes> {
+    Ok(<Vec<u8> as arbitrary::Arbitrary>::arbitrary(u)?.into())
+}
+
+/// Returns an [`HpkeCiphertext`](openmls_traits::types::HpkeCiphertext) with
+/// arbitrary bytes.
+#[cfg(feature = "arbitrary")]
+pub(crate) fn arbitrary_hpke_ciphertext(
+    u: &mut arbitrary::Unstructured<'_>,
+) -> arbitrary::Result<openmls_traits::types::HpkeCiphertext> {
+    Ok(openmls_traits::types::HpkeCiphertext {
+        kem_output: arbitrary_vl_bytes(u)?,
+        ciphertext: arbitrary_vl_bytes(u)?,
+    })
+}
+
+/// Returns one of the ciphersuites defined by MLS.
+#[cfg(feature = "arbitrary")]
+pub(crate) fn arbitrary_ciphersuite(
+    u: &mut arbitrary::Unstructured<'_>,
+) -> arbitrary::Result<openmls_traits::types::Ciphersuite> {
+    use openmls_traits::types::Ciphersuite;
+
+    u.choose(&[
+        Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+        Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+        Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448,
+        Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+        Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448,
+        Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+    ])
+    .copied()
+}
+
+/// Returns one of the signature schemes of the ciphersuites defined by MLS.
+#[cfg(feature = "arbitrary")]
+pub(crate) fn arbitrary_signature_scheme(
+    u: &mut arbitrary::Unstructured<'_>,
+) -> arbitrary::Result<openmls_traits::types::SignatureScheme> {
+    use openmls_traits::types::SignatureScheme;
+
+    u.choose(&[
+        SignatureScheme::ECDSA_SECP256R1_SHA256,
+        SignatureScheme::ECDSA_SECP384R1_SHA384,
+        SignatureScheme::ECDSA_SECP521R1_SHA512,
+        SignatureScheme::ED25519,
+        SignatureScheme::ED448,
+    ])
+    .copied()
+}
+
 // With the crypto-debug feature enabled sensitive crypto parts can be logged.
 #[cfg(feature = "crypto-debug")]
 macro_rules! log_crypto {
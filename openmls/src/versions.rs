@@ -1,12 +1,21 @@
 //! # MLS versions
 //!
-//! Only MLS 1.0 is currently supported.
+//! OpenMLS can host groups on any of the [`SUPPORTED_PROTOCOL_VERSIONS`] at
+//! the same time. The version of a group is fixed when the group is created
+//! (see [`CryptoConfig`](crate::group::config::CryptoConfig)) and all
+//! version-dependent values of the group, such as the `GroupContext`, the
+//! `Welcome` messages and the key schedule labels, are derived from it.
+//!
+//! The pre RFC version [`ProtocolVersion::Mls10Draft11`] can be decoded, but
+//! is not supported.
 
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, fmt};
 use thiserror::Error;
 use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 
+use crate::error::error_codes;
+
 // Public types
 
 /// # Protocol Version
@@ -36,12 +45,40 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 )]
 #[repr(u8)]
 #[allow(missing_docs)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ProtocolVersion {
     Mls10 = 1,
     Mls10Draft11 = 200, // pre RFC version
 }
 
-/// There's only one version right now, which is the default.
+/// The protocol versions supported by this implementation, in order of
+/// preference.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[ProtocolVersion] = &[ProtocolVersion::Mls10];
+
+impl ProtocolVersion {
+    /// Returns `true` if groups on this protocol version can be created,
+    /// joined and processed by this implementation and `false` otherwise.
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED_PROTOCOL_VERSIONS.contains(self)
+    }
+
+    /// Returns `true` if this protocol version is the `minimum` version or a
+    /// newer one. Note that the pre RFC version is older than MLS 1.0, even
+    /// though its value is larger.
+    pub fn is_at_least(&self, minimum: ProtocolVersion) -> bool {
+        self.release() >= minimum.release()
+    }
+
+    /// The position of the protocol version in the order of releases.
+    fn release(&self) -> u8 {
+        match self {
+            ProtocolVersion::Mls10Draft11 => 0,
+            ProtocolVersion::Mls10 => 1,
+        }
+    }
+}
+
+/// MLS 1.0 is the default version.
 impl Default for ProtocolVersion {
     fn default() -> Self {
         ProtocolVersion::Mls10
@@ -79,3 +116,7 @@ pub enum VersionError {
     #[error("Unsupported MLS version.")]
     UnsupportedMlsVersion,
 }
+
+error_codes!(VersionError: 4300 {
+    UnsupportedMlsVersion = 1,
+});
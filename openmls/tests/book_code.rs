@@ -46,8 +46,12 @@ fn generate_credential_bundle(
     backend: &impl OpenMlsCryptoProvider,
 ) -> Result<Credential, CredentialError> {
     // ANCHOR: create_credential_bundle
-    let credential_bundle =
-        CredentialBundle::new(identity, credential_type, signature_algorithm, backend)?;
+    let credential_bundle = CredentialBundle::new(
+        identity.into(),
+        credential_type,
+        signature_algorithm,
+        backend,
+    )?;
     // ANCHOR_END: create_credential_bundle
     // ANCHOR: store_credential_bundle
     let credential = credential_bundle.credential().clone();
@@ -71,8 +75,12 @@ fn get_credential_bundle(
     signature_algorithm: SignatureScheme,
     backend: &impl OpenMlsCryptoProvider,
 ) -> Result<CredentialBundle, CredentialError> {
-    let credential_bundle =
-        CredentialBundle::new(identity, credential_type, signature_algorithm, backend)?;
+    let credential_bundle = CredentialBundle::new(
+        identity.into(),
+        credential_type,
+        signature_algorithm,
+        backend,
+    )?;
     let credential = credential_bundle.credential().clone();
     backend
         .key_store()
@@ -288,8 +296,8 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
 
     // Make sure that both groups have the same epoch authenticator
     assert_eq!(
-        alice_group.epoch_authenticator().as_slice(),
-        bob_group.epoch_authenticator().as_slice()
+        alice_group.epoch_authenticator().expose(),
+        bob_group.epoch_authenticator().expose()
     );
 
     // === Alice sends a message to Bob ===
@@ -311,9 +319,8 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
     // ANCHOR_END: mls_message_in_from_bytes
 
     // ANCHOR: process_message
-    let protocol_message: ProtocolMessage = mls_message.into();
     let processed_message = bob_group
-        .process_message(backend, protocol_message)
+        .process_mls_message(backend, mls_message)
         .expect("Could not process message.");
     // ANCHOR_END: process_message
 
@@ -639,7 +646,7 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
 
     // Make sure that this is Bob's actual KP reference.
     assert_eq!(
-        bob_member.identity,
+        &bob_member.identity,
         bob_group
             .own_identity()
             .expect("An unexpected error occurred.")
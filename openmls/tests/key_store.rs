@@ -7,7 +7,7 @@ fn test_store_key_package(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
     // ANCHOR: key_store_store
     // First we generate a credential and key package for our user.
     let credential_bundle = CredentialBundle::new(
-        b"User ID".to_vec(),
+        "User ID".into(),
         CredentialType::Basic,
         SignatureScheme::from(ciphersuite),
         backend,
@@ -38,7 +38,7 @@ fn test_store_key_package(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
 fn test_read_credential_bundle(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     // First we generate a credential bundle
     let credential_bundle_to_store = CredentialBundle::new(
-        b"User ID".to_vec(),
+        "User ID".into(),
         CredentialType::Basic,
         SignatureScheme::from(ciphersuite),
         backend,
@@ -74,3 +74,115 @@ fn test_read_credential_bundle(ciphersuite: Ciphersuite, backend: &impl OpenMlsC
 
     assert_eq!(credential_bundle, credential_bundle_to_store);
 }
+
+#[apply(ciphersuites_and_backends)]
+fn test_list_entries(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let credential_bundle = CredentialBundle::new(
+        "User ID".into(),
+        CredentialType::Basic,
+        SignatureScheme::from(ciphersuite),
+        backend,
+    )
+    .unwrap();
+    let signature_key = credential_bundle.credential().signature_key().clone();
+    backend
+        .key_store()
+        .store(
+            &signature_key.tls_serialize_detached().unwrap(),
+            &credential_bundle,
+        )
+        .expect("Failed to store credential in keystore.");
+
+    // The key package, its init key and the key of its leaf node are stored.
+    let _key_package = KeyPackage::builder()
+        .build(
+            config::CryptoConfig {
+                ciphersuite,
+                version: ProtocolVersion::default(),
+            },
+            backend,
+            &credential_bundle,
+        )
+        .unwrap();
+
+    // The encryption keys of the first epoch are stored.
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(config::CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let group = MlsGroup::new(backend, &mls_group_config, &signature_key).unwrap();
+
+    backend
+        .key_store()
+        .store_psk(b"external psk", b"secret")
+        .unwrap();
+    // Entries of the application are not listed.
+    backend
+        .key_store()
+        .store(b"application entry", &b"value".to_vec())
+        .unwrap();
+
+    // ANCHOR: key_store_list
+    let entries = KeyStoreEntry::list(backend);
+    // ANCHOR_END: key_store_list
+    let count = |entry_type: KeyStoreEntryType| {
+        entries
+            .iter()
+            .filter(|entry| entry.entry_type() == entry_type)
+            .count()
+    };
+    assert_eq!(count(KeyStoreEntryType::CredentialBundle), 1);
+    assert_eq!(count(KeyStoreEntryType::KeyPackage), 1);
+    assert_eq!(count(KeyStoreEntryType::KeyPackageMetadata), 1);
+    assert_eq!(count(KeyStoreEntryType::Psk), 1);
+    assert!(count(KeyStoreEntryType::HpkeKey) >= 2);
+    let epoch_keys = entries
+        .iter()
+        .find(|entry| entry.entry_type() == KeyStoreEntryType::EpochKeys)
+        .expect("Missing epoch keys.");
+    assert_eq!(epoch_keys.group_id(), Some(group.group_id()));
+    assert!(!entries
+        .iter()
+        .any(|entry| entry.id() == &KeyStoreId::Value(b"application entry".to_vec())));
+}
+
+#[apply(ciphersuites_and_backends)]
+fn test_upgrade_values(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let credential_bundle = CredentialBundle::new(
+        "User ID".into(),
+        CredentialType::Basic,
+        SignatureScheme::from(ciphersuite),
+        backend,
+    )
+    .unwrap();
+    let id = credential_bundle
+        .credential()
+        .signature_key()
+        .tls_serialize_detached()
+        .unwrap();
+
+    // Store the credential bundle the way OpenMLS did before the values were
+    // versioned, i.e. without a header.
+    let legacy_value = serde_json::to_vec(&credential_bundle).unwrap();
+    backend.key_store().store(&id, &legacy_value).unwrap();
+    let read: CredentialBundle = backend.key_store().read(&id).unwrap();
+    assert_eq!(read.credential(), credential_bundle.credential());
+
+    // Rewrite the value in the current format.
+    let entry = KeyStoreEntry::list(backend)
+        .into_iter()
+        .find(|entry| entry.id() == &KeyStoreId::Value(id.clone()))
+        .expect("Missing credential bundle.");
+    assert_eq!(entry.entry_type(), KeyStoreEntryType::CredentialBundle);
+    assert!(entry.upgrade(backend).unwrap());
+    assert!(!entry.upgrade(backend).unwrap());
+    let value: Vec<u8> = backend.key_store().read(&id).unwrap();
+    assert!(value.starts_with(b"OMLS"));
+    let read: CredentialBundle = backend.key_store().read(&id).unwrap();
+    assert_eq!(read.credential(), credential_bundle.credential());
+
+    // Values of a newer format version can't be read.
+    let mut newer_value = value;
+    newer_value[5..7].copy_from_slice(&u16::MAX.to_be_bytes());
+    backend.key_store().store(&id, &newer_value).unwrap();
+    assert!(backend.key_store().read::<CredentialBundle>(&id).is_none());
+}
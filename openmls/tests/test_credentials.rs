@@ -19,7 +19,7 @@ fn credential_with_signature_keypair(
     let signature_keypair = SignatureKeypair::from_parts(public_key, private_key);
 
     // Create a CredentialBundle
-    let credential_bundle = CredentialBundle::from_parts(vec![1, 2, 3], signature_keypair);
+    let credential_bundle = CredentialBundle::from_parts(vec![1, 2, 3].into(), signature_keypair);
     let (credential, signature_private_key) = credential_bundle.into_parts();
 
     // Make sure the credential's keys are the original keys
@@ -12,7 +12,7 @@ fn create_alice_group(
 
     let alice_cb = {
         let alice_cb = CredentialBundle::new(
-            b"Alice".to_vec(),
+            "Alice".into(),
             CredentialType::Basic,
             ciphersuite.signature_algorithm(),
             backend,
@@ -82,7 +82,7 @@ fn test_external_commit(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     // Now, Bob wants to join Alice' group by an external commit. (Positive case.)
     {
         let bob_cb = CredentialBundle::new(
-            b"Bob".to_vec(),
+            "Bob".into(),
             CredentialType::Basic,
             ciphersuite.signature_algorithm(),
             backend,
@@ -105,7 +105,7 @@ fn test_external_commit(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     // Now, Bob wants to join Alice' group by an external commit. (Negative case, broken signature.)
     {
         let bob_cb = CredentialBundle::new(
-            b"Bob".to_vec(),
+            "Bob".into(),
             CredentialType::Basic,
             ciphersuite.signature_algorithm(),
             backend,
@@ -139,7 +139,7 @@ fn test_group_info(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
 
     // Bob wants to join
     let bob_cb = CredentialBundle::new(
-        b"Bob".to_vec(),
+        "Bob".into(),
         CredentialType::Basic,
         ciphersuite.signature_algorithm(),
         backend,
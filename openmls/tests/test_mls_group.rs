@@ -19,7 +19,12 @@ fn generate_credential_bundle(
     signature_algorithm: SignatureScheme,
     backend: &impl OpenMlsCryptoProvider,
 ) -> Result<Credential, CredentialError> {
-    let cb = CredentialBundle::new(identity, credential_type, signature_algorithm, backend)?;
+    let cb = CredentialBundle::new(
+        identity.into(),
+        credential_type,
+        signature_algorithm,
+        backend,
+    )?;
     let credential = cb.credential().clone();
     backend
         .key_store()
@@ -180,8 +185,8 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
 
         // Make sure that both groups have the same epoch authenticator
         assert_eq!(
-            alice_group.epoch_authenticator().as_slice(),
-            bob_group.epoch_authenticator().as_slice()
+            alice_group.epoch_authenticator().expose(),
+            bob_group.epoch_authenticator().expose()
         );
 
         // === Alice sends a message to Bob ===
@@ -1150,3 +1155,190 @@ fn mls_group_ratchet_tree_extension(
         assert_eq!(error, WelcomeError::MissingRatchetTree);
     }
 }
+
+#[apply(ciphersuites_and_backends)]
+fn mock_delivery_service(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use openmls::test_utils::mock_delivery_service::{
+        MockDeliveryService, MockDeliveryServiceError,
+    };
+
+    let alice_credential = generate_credential_bundle(
+        "Alice".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+    let bob_credential = generate_credential_bundle(
+        "Bob".into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("An unexpected error occurred.");
+
+    let ds = MockDeliveryService::new();
+    ds.register_client(b"Alice");
+    ds.register_client(b"Bob");
+
+    // === Bob publishes a key package ===
+    let bob_key_package = generate_key_package(
+        &[ciphersuite],
+        &bob_credential,
+        Extensions::empty(),
+        backend,
+    );
+    ds.publish_key_package(backend, b"Bob", bob_key_package)
+        .expect("Could not publish the key package.");
+    assert_eq!(ds.key_package_count(b"Bob"), 1);
+
+    // === Alice creates a group and adds Bob ===
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let mut alice_group =
+        MlsGroup::new(backend, &mls_group_config, alice_credential.signature_key())
+            .expect("An unexpected error occurred.");
+    ds.create_group(alice_group.group_id(), b"Alice", Some(alice_group.epoch()))
+        .expect("Could not create the group.");
+
+    let bob_key_package = ds
+        .fetch_key_package(b"Bob")
+        .expect("Bob has no key package.");
+    assert!(ds.fetch_key_package(b"Bob").is_none());
+    let (commit, welcome, _group_info) = alice_group
+        .add_members(backend, &[bob_key_package])
+        .expect("Could not add members.");
+    ds.send_message(b"Alice", &commit)
+        .expect("Could not send the commit.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    ds.send_welcome(alice_group.group_id(), &welcome)
+        .expect("Could not send the Welcome.");
+    assert_eq!(
+        ds.subscribers(alice_group.group_id())
+            .expect("Unknown group."),
+        vec![b"Alice".to_vec(), b"Bob".to_vec()]
+    );
+
+    // === Bob joins the group ===
+    let mut bob_messages = ds.receive(b"Bob").expect("Unknown client.");
+    assert_eq!(bob_messages.len(), 1);
+    let welcome = bob_messages
+        .remove(0)
+        .into_welcome()
+        .expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, None)
+        .expect("Error creating group from Welcome");
+
+    // === Alice and Bob commit concurrently ===
+    let (bob_commit, _, _) = bob_group.self_update(backend).expect("Could not update.");
+    let (alice_commit, _, _) = alice_group.self_update(backend).expect("Could not update.");
+    ds.send_message(b"Bob", &bob_commit)
+        .expect("Could not send the commit.");
+    let error = ds
+        .send_message(b"Alice", &alice_commit)
+        .expect_err("Accepted two commits for the same epoch.");
+    assert!(matches!(
+        error,
+        MockDeliveryServiceError::StaleCommit { .. }
+    ));
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("Could not merge commit.");
+    alice_group.clear_pending_commit();
+
+    // === Alice processes Bob's commit and sends a message ===
+    for message in ds.receive(b"Alice").expect("Unknown client.") {
+        let processed_message = alice_group
+            .process_message(
+                backend,
+                message
+                    .into_protocol_message()
+                    .expect("Unexpected message type."),
+            )
+            .expect("Could not process message.");
+        if let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+            processed_message.into_content()
+        {
+            alice_group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Could not merge commit.");
+        } else {
+            unreachable!("Expected a StagedCommit.");
+        }
+    }
+    let message = alice_group
+        .create_message(backend, b"Hi, Bob!")
+        .expect("Error creating application message");
+    ds.send_message(b"Alice", &message)
+        .expect("Could not send the message.");
+
+    let messages = ds.receive(b"Bob").expect("Unknown client.");
+    assert_eq!(messages.len(), 1);
+    let processed_message = bob_group
+        .process_message(
+            backend,
+            messages[0]
+                .clone()
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    if let ProcessedMessageContent::ApplicationMessage(application_message) =
+        processed_message.into_content()
+    {
+        assert_eq!(application_message.into_bytes(), b"Hi, Bob!");
+    } else {
+        unreachable!("Expected an ApplicationMessage.");
+    }
+    assert_eq!(ds.dropped_messages(), 0);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn group_fixture(ciphersuite: Ciphersuite, _backend: &impl OpenMlsCryptoProvider) {
+    use openmls::test_utils::group_fixtures::{Churn, GroupFixture};
+
+    let fixture = GroupFixture::builder(ciphersuite)
+        .members(4)
+        .epochs(10)
+        .churn(Churn {
+            adds: 2,
+            removes: 1,
+            updates: 1,
+        })
+        .seed(7)
+        .build();
+    fixture.assert_consistent();
+    // The group was created in epoch 0, the initial members were added in
+    // epoch 1 and every epoch of churn adds one more.
+    assert_eq!(fixture.epoch(), GroupEpoch::from(11));
+
+    // The same seed results in the same members.
+    let identities = |fixture: &GroupFixture| {
+        fixture
+            .members()
+            .iter()
+            .map(|member| member.identity.clone())
+            .collect::<Vec<_>>()
+    };
+    let same_fixture = GroupFixture::builder(ciphersuite)
+        .members(4)
+        .epochs(10)
+        .churn(Churn {
+            adds: 2,
+            removes: 1,
+            updates: 1,
+        })
+        .seed(7)
+        .build();
+    assert_eq!(identities(&fixture), identities(&same_fixture));
+
+    // Without churn, all initial members are still there.
+    let fixture = GroupFixture::builder(ciphersuite).members(5).build();
+    fixture.assert_consistent();
+    assert_eq!(fixture.members().len(), 5);
+    assert_eq!(fixture.members()[0].group.members().count(), 5);
+}
@@ -0,0 +1,376 @@
+//! # HPKE
+//!
+//! The base mode of HPKE ([RFC 9180]) with the DHKEMs, KDFs and AEADs of the
+//! MLS ciphersuites, on top of the AWS-LC primitives of this crate. Only the
+//! single-shot operations that OpenMLS needs are implemented, i.e. a context
+//! is used for at most one encryption.
+//!
+//! [RFC 9180]: https://www.rfc-editor.org/rfc/rfc9180
+
+use std::convert::TryFrom;
+
+use aws_lc_rs::{
+    agreement::{self, PrivateKey, UnparsedPublicKey},
+    rand::{SecureRandom, SystemRandom},
+};
+use openmls_traits::types::{
+    AeadType, CryptoError, HashType, HpkeAeadType, HpkeConfig, HpkeKdfType, HpkeKemType,
+};
+
+use crate::provider::{aead_open, aead_seal, hkdf_expand, hkdf_extract};
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+
+/// An HPKE suite.
+pub(crate) struct Hpke {
+    kem: HpkeKemType,
+    kdf: HpkeKdfType,
+    aead: HpkeAeadType,
+}
+
+/// An HPKE context after the key schedule, see [`Hpke::setup_sender()`] and
+/// [`Hpke::setup_receiver()`].
+pub(crate) struct Context {
+    aead: Option<AeadType>,
+    key: Vec<u8>,
+    base_nonce: Vec<u8>,
+    exporter_secret: Vec<u8>,
+    kdf_hash: HashType,
+    suite_id: Vec<u8>,
+}
+
+impl Context {
+    /// Encrypts the `plaintext` with the first nonce of the context.
+    fn seal(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let aead = self.aead.ok_or(CryptoError::UnsupportedAeadAlgorithm)?;
+        aead_seal(aead, &self.key, plaintext, &self.base_nonce, aad)
+    }
+
+    /// Decrypts the `ciphertext` with the first nonce of the context.
+    fn open(&self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let aead = self.aead.ok_or(CryptoError::UnsupportedAeadAlgorithm)?;
+        aead_open(aead, &self.key, ciphertext, &self.base_nonce, aad)
+    }
+
+    /// Exports a secret of `length` bytes for the `exporter_context`.
+    pub(crate) fn export(
+        &self,
+        exporter_context: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        labeled_expand(
+            self.kdf_hash,
+            &self.suite_id,
+            &self.exporter_secret,
+            b"sec",
+            exporter_context,
+            length,
+        )
+    }
+}
+
+fn labeled_extract(
+    hash_type: HashType,
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let labeled_ikm = [VERSION_LABEL, suite_id, label, ikm].concat();
+    Ok(hkdf_extract(hash_type, salt, &labeled_ikm))
+}
+
+fn labeled_expand(
+    hash_type: HashType,
+    suite_id: &[u8],
+    prk: &[u8],
+    label: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    let length_bytes = u16::try_from(length)
+        .map_err(|_| CryptoError::InvalidLength)?
+        .to_be_bytes();
+    let labeled_info = [&length_bytes[..], VERSION_LABEL, suite_id, label, info].concat();
+    hkdf_expand(hash_type, prk, &labeled_info, length)
+}
+
+impl Hpke {
+    pub(crate) fn new(config: HpkeConfig) -> Self {
+        Self {
+            kem: config.0,
+            kdf: config.1,
+            aead: config.2,
+        }
+    }
+
+    /// The hash of the KDF of the KEM.
+    fn kem_hash(&self) -> HashType {
+        match self.kem {
+            HpkeKemType::DhKemP256 | HpkeKemType::DhKem25519 => HashType::Sha2_256,
+            HpkeKemType::DhKemP384 => HashType::Sha2_384,
+            HpkeKemType::DhKemP521 | HpkeKemType::DhKem448 => HashType::Sha2_512,
+        }
+    }
+
+    /// The length of the shared secret of the KEM.
+    fn kem_secret_len(&self) -> usize {
+        match self.kem_hash() {
+            HashType::Sha2_256 => 32,
+            HashType::Sha2_384 => 48,
+            HashType::Sha2_512 => 64,
+        }
+    }
+
+    /// The length of a private key of the KEM.
+    fn private_key_len(&self) -> usize {
+        match self.kem {
+            HpkeKemType::DhKemP256 | HpkeKemType::DhKem25519 => 32,
+            HpkeKemType::DhKemP384 => 48,
+            HpkeKemType::DhKemP521 => 66,
+            HpkeKemType::DhKem448 => 56,
+        }
+    }
+
+    /// The key agreement algorithm of the KEM. X448 is not supported.
+    fn agreement_algorithm(&self) -> Result<&'static agreement::Algorithm, CryptoError> {
+        match self.kem {
+            HpkeKemType::DhKemP256 => Ok(&agreement::ECDH_P256),
+            HpkeKemType::DhKemP384 => Ok(&agreement::ECDH_P384),
+            HpkeKemType::DhKemP521 => Ok(&agreement::ECDH_P521),
+            HpkeKemType::DhKem25519 => Ok(&agreement::X25519),
+            HpkeKemType::DhKem448 => Err(CryptoError::UnsupportedCiphersuite),
+        }
+    }
+
+    fn kdf_hash(&self) -> HashType {
+        match self.kdf {
+            HpkeKdfType::HkdfSha256 => HashType::Sha2_256,
+            HpkeKdfType::HkdfSha384 => HashType::Sha2_384,
+            HpkeKdfType::HkdfSha512 => HashType::Sha2_512,
+        }
+    }
+
+    fn aead_type(&self) -> Option<AeadType> {
+        match self.aead {
+            HpkeAeadType::AesGcm128 => Some(AeadType::Aes128Gcm),
+            HpkeAeadType::AesGcm256 => Some(AeadType::Aes256Gcm),
+            HpkeAeadType::ChaCha20Poly1305 => Some(AeadType::ChaCha20Poly1305),
+            HpkeAeadType::Export => None,
+        }
+    }
+
+    fn kem_suite_id(&self) -> Vec<u8> {
+        [&b"KEM"[..], &(self.kem as u16).to_be_bytes()[..]].concat()
+    }
+
+    fn suite_id(&self) -> Vec<u8> {
+        [
+            &b"HPKE"[..],
+            &(self.kem as u16).to_be_bytes()[..],
+            &(self.kdf as u16).to_be_bytes()[..],
+            &(self.aead as u16).to_be_bytes()[..],
+        ]
+        .concat()
+    }
+
+    /// Derives a key pair from the `ikm` (`DeriveKeyPair`). Returns the
+    /// private and the serialized public key.
+    pub(crate) fn derive_key_pair(&self, ikm: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let hash = self.kem_hash();
+        let suite_id = self.kem_suite_id();
+        let dkp_prk = labeled_extract(hash, &suite_id, b"", b"dkp_prk", ikm)?;
+        let algorithm = self.agreement_algorithm()?;
+        if self.kem == HpkeKemType::DhKem25519 {
+            let private_key = labeled_expand(
+                hash,
+                &suite_id,
+                &dkp_prk,
+                b"sk",
+                b"",
+                self.private_key_len(),
+            )?;
+            let public_key = self.public_key(&private_key)?;
+            return Ok((private_key, public_key));
+        }
+        let bitmask = match self.kem {
+            HpkeKemType::DhKemP521 => 0x01,
+            _ => 0xff,
+        };
+        for counter in 0..=255u8 {
+            let mut candidate = labeled_expand(
+                hash,
+                &suite_id,
+                &dkp_prk,
+                b"candidate",
+                &[counter],
+                self.private_key_len(),
+            )?;
+            candidate[0] &= bitmask;
+            // AWS-LC rejects scalars that are zero or not smaller than the
+            // order of the curve.
+            if PrivateKey::from_private_key(algorithm, &candidate).is_ok() {
+                let public_key = self.public_key(&candidate)?;
+                return Ok((candidate, public_key));
+            }
+        }
+        Err(CryptoError::CryptoLibraryError)
+    }
+
+    /// Generates a random key pair.
+    pub(crate) fn generate_key_pair(&self) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let mut ikm = vec![0u8; self.private_key_len()];
+        SystemRandom::new()
+            .fill(&mut ikm)
+            .map_err(|_| CryptoError::InsufficientRandomness)?;
+        self.derive_key_pair(&ikm)
+    }
+
+    /// Returns the serialized public key of the `private_key`.
+    fn public_key(&self, private_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        PrivateKey::from_private_key(self.agreement_algorithm()?, private_key)
+            .map_err(|_| CryptoError::CryptoLibraryError)?
+            .compute_public_key()
+            .map(|public_key| public_key.as_ref().to_vec())
+            .map_err(|_| CryptoError::CryptoLibraryError)
+    }
+
+    /// The Diffie-Hellman shared secret of the `private_key` and the
+    /// serialized `public_key`.
+    fn dh(&self, private_key: &[u8], public_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let algorithm = self.agreement_algorithm()?;
+        let private_key = PrivateKey::from_private_key(algorithm, private_key)
+            .map_err(|_| CryptoError::CryptoLibraryError)?;
+        agreement::agree(
+            &private_key,
+            &UnparsedPublicKey::new(algorithm, public_key),
+            CryptoError::CryptoLibraryError,
+            |shared_secret| Ok(shared_secret.to_vec()),
+        )
+    }
+
+    /// `ExtractAndExpand` of the DHKEM.
+    fn extract_and_expand(&self, dh: &[u8], kem_context: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let hash = self.kem_hash();
+        let suite_id = self.kem_suite_id();
+        let eae_prk = labeled_extract(hash, &suite_id, b"", b"eae_prk", dh)?;
+        labeled_expand(
+            hash,
+            &suite_id,
+            &eae_prk,
+            b"shared_secret",
+            kem_context,
+            self.kem_secret_len(),
+        )
+    }
+
+    /// `Encap` of the DHKEM. Returns the shared secret and the encapsulation.
+    fn encap(&self, pk_r: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let (sk_e, enc) = self.generate_key_pair()?;
+        let dh = self.dh(&sk_e, pk_r)?;
+        let kem_context = [enc.as_slice(), pk_r].concat();
+        Ok((self.extract_and_expand(&dh, &kem_context)?, enc))
+    }
+
+    /// `Decap` of the DHKEM. Returns the shared secret.
+    fn decap(&self, enc: &[u8], sk_r: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let dh = self.dh(sk_r, enc)?;
+        let kem_context = [enc, self.public_key(sk_r)?.as_slice()].concat();
+        self.extract_and_expand(&dh, &kem_context)
+    }
+
+    /// The key schedule of the base mode.
+    fn key_schedule(&self, shared_secret: &[u8], info: &[u8]) -> Result<Context, CryptoError> {
+        let hash = self.kdf_hash();
+        let suite_id = self.suite_id();
+        let psk_id_hash = labeled_extract(hash, &suite_id, b"", b"psk_id_hash", b"")?;
+        let info_hash = labeled_extract(hash, &suite_id, b"", b"info_hash", info)?;
+        // The mode is 0 (base).
+        let key_schedule_context = [&[0u8][..], &psk_id_hash, &info_hash].concat();
+        let secret = labeled_extract(hash, &suite_id, shared_secret, b"secret", b"")?;
+
+        let aead = self.aead_type();
+        let (key, base_nonce) = match aead {
+            Some(aead) => (
+                labeled_expand(
+                    hash,
+                    &suite_id,
+                    &secret,
+                    b"key",
+                    &key_schedule_context,
+                    aead.key_size(),
+                )?,
+                labeled_expand(
+                    hash,
+                    &suite_id,
+                    &secret,
+                    b"base_nonce",
+                    &key_schedule_context,
+                    aead.nonce_size(),
+                )?,
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+        let exporter_secret = labeled_expand(
+            hash,
+            &suite_id,
+            &secret,
+            b"exp",
+            &key_schedule_context,
+            secret.len(),
+        )?;
+        Ok(Context {
+            aead,
+            key,
+            base_nonce,
+            exporter_secret,
+            kdf_hash: hash,
+            suite_id,
+        })
+    }
+
+    /// `SetupBaseS`. Returns the encapsulation and the context.
+    pub(crate) fn setup_sender(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+    ) -> Result<(Vec<u8>, Context), CryptoError> {
+        let (shared_secret, enc) = self.encap(pk_r)?;
+        Ok((enc, self.key_schedule(&shared_secret, info)?))
+    }
+
+    /// `SetupBaseR`.
+    pub(crate) fn setup_receiver(
+        &self,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+    ) -> Result<Context, CryptoError> {
+        let shared_secret = self.decap(enc, sk_r)?;
+        self.key_schedule(&shared_secret, info)
+    }
+
+    /// `SealBase`. Returns the encapsulation and the ciphertext.
+    pub(crate) fn seal(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let (enc, context) = self.setup_sender(pk_r, info)?;
+        Ok((enc, context.seal(aad, plaintext)?))
+    }
+
+    /// `OpenBase`.
+    pub(crate) fn open(
+        &self,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.setup_receiver(enc, sk_r, info)?.open(aad, ciphertext)
+    }
+}
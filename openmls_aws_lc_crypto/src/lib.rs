@@ -0,0 +1,36 @@
+//! # AWS-LC Crypto Provider
+//!
+//! This is an implementation of the [`OpenMlsCryptoProvider`] trait that uses
+//! AWS-LC for all cryptographic operations and randomness. With the `fips`
+//! feature, the FIPS validated AWS-LC module is used.
+
+pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError};
+use openmls_traits::OpenMlsCryptoProvider;
+
+mod hpke;
+mod provider;
+pub use provider::*;
+
+#[derive(Default, Debug)]
+pub struct OpenMlsAwsLc {
+    crypto: AwsLcCrypto,
+    key_store: MemoryKeyStore,
+}
+
+impl OpenMlsCryptoProvider for OpenMlsAwsLc {
+    type CryptoProvider = AwsLcCrypto;
+    type RandProvider = AwsLcCrypto;
+    type KeyStoreProvider = MemoryKeyStore;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
@@ -0,0 +1,184 @@
+//! Migrations of groups between ciphersuites with different hash lengths,
+//! which the other backends don't support.
+
+use openmls::prelude::*;
+use openmls_aws_lc_crypto::OpenMlsAwsLc;
+
+fn credential_bundle(
+    backend: &OpenMlsAwsLc,
+    identity: &str,
+    ciphersuite: Ciphersuite,
+) -> CredentialBundle {
+    let credential_bundle = CredentialBundle::new(
+        identity.into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+    .expect("Could not create credential bundle.");
+    backend
+        .key_store()
+        .store(
+            &credential_bundle
+                .credential()
+                .signature_key()
+                .tls_serialize_detached()
+                .expect("Could not serialize signature key."),
+            &credential_bundle,
+        )
+        .expect("Could not store credential bundle.");
+    credential_bundle
+}
+
+fn key_package(
+    backend: &OpenMlsAwsLc,
+    ciphersuite: Ciphersuite,
+    credential_bundle: &CredentialBundle,
+) -> KeyPackage {
+    KeyPackage::builder()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            credential_bundle,
+        )
+        .expect("Could not create key package.")
+}
+
+fn migrate(ciphersuite: Ciphersuite, new_ciphersuite: Ciphersuite) {
+    assert_ne!(ciphersuite.hash_length(), new_ciphersuite.hash_length());
+    let alice_backend = OpenMlsAwsLc::default();
+    let bob_backend = OpenMlsAwsLc::default();
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let alice_credential_bundle = credential_bundle(&alice_backend, "Alice", ciphersuite);
+    let bob_credential_bundle = credential_bundle(&bob_backend, "Bob", ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        &alice_backend,
+        &mls_group_config,
+        alice_credential_bundle.credential().signature_key(),
+    )
+    .expect("Could not create group.");
+    let bob_key_package = key_package(&bob_backend, ciphersuite, &bob_credential_bundle);
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(&alice_backend, &[bob_key_package])
+        .expect("Could not add Bob.");
+    alice_group
+        .merge_pending_commit(&alice_backend)
+        .expect("Could not merge commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        &bob_backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Could not join group.");
+
+    // === Alice migrates the group ===
+    // Both need credentials with the signature scheme of the new ciphersuite.
+    let new_alice_credential_bundle = credential_bundle(&alice_backend, "Alice", new_ciphersuite);
+    let new_bob_credential_bundle = credential_bundle(&bob_backend, "Bob", new_ciphersuite);
+    let bob_key_package = key_package(&bob_backend, new_ciphersuite, &new_bob_credential_bundle);
+    let (commit, mut new_alice_group, welcome) = alice_group
+        .migrate_ciphersuite_with_credential(
+            &alice_backend,
+            new_ciphersuite,
+            new_alice_credential_bundle.credential().signature_key(),
+            &[bob_key_package],
+        )
+        .expect("Could not migrate the group.");
+    assert_eq!(new_alice_group.ciphersuite(), new_ciphersuite);
+
+    // Bob processes the ReInit Commit, which stores the resumption PSK of
+    // the old group, ...
+    let processed_message = bob_group
+        .process_message(
+            &bob_backend,
+            commit
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process ReInit Commit.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => panic!("Expected a StagedCommit."),
+    };
+    assert_eq!(
+        staged_commit
+            .reinit_proposal()
+            .expect("Expected a ReInit proposal.")
+            .ciphersuite(),
+        new_ciphersuite
+    );
+    bob_group
+        .merge_staged_commit(&bob_backend, *staged_commit)
+        .expect("Could not merge ReInit Commit.");
+
+    // ... and joins the successor group with it, although the PSK has the
+    // hash length of the old ciphersuite.
+    let mut new_bob_group = MlsGroup::new_from_welcome(
+        &bob_backend,
+        &MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(new_ciphersuite))
+            .use_ratchet_tree_extension(true)
+            .build(),
+        welcome
+            .expect("Expected a Welcome.")
+            .into_welcome()
+            .expect("Unexpected message type."),
+        None,
+    )
+    .expect("Could not join the successor group.");
+    assert_eq!(new_bob_group.group_id(), new_alice_group.group_id());
+    assert_eq!(
+        new_bob_group.epoch_authenticator().expose().len(),
+        new_ciphersuite.hash_length()
+    );
+    assert_eq!(
+        new_alice_group.export_secret(&alice_backend, "migration", &[], 32),
+        new_bob_group.export_secret(&bob_backend, "migration", &[], 32)
+    );
+
+    // Alice and Bob can talk in the successor group.
+    let message = new_alice_group
+        .create_message(&alice_backend, b"Hello Bob")
+        .expect("Could not create message.");
+    let processed_message = new_bob_group
+        .process_message(
+            &bob_backend,
+            message
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(message) => {
+            assert_eq!(message.into_bytes(), b"Hello Bob")
+        }
+        _ => panic!("Expected an ApplicationMessage."),
+    }
+}
+
+#[test]
+fn migrate_to_longer_hash() {
+    migrate(
+        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+        Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+    );
+    migrate(
+        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+        Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+    );
+}
+
+#[test]
+fn migrate_to_shorter_hash() {
+    migrate(
+        Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+    );
+}
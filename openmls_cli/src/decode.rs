@@ -0,0 +1,41 @@
+//! Decoding and pretty-printing of protocol objects.
+
+use std::path::Path;
+
+use clap_derive::ArgEnum;
+use openmls::prelude::*;
+
+use crate::{
+    encoding::{self, Encoding},
+    Result,
+};
+
+/// The kind of a protocol object.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// An `MLSMessage`, i.e. a PublicMessage, PrivateMessage, Welcome,
+    /// GroupInfo or KeyPackage with a message header.
+    Message,
+    /// A `KeyPackage` without a message header.
+    KeyPackage,
+    /// A `Welcome` without a message header.
+    Welcome,
+    /// A ratchet tree, as contained in the ratchet tree extension.
+    RatchetTree,
+}
+
+/// Decodes the object in `input` and prints its JSON representation.
+pub fn decode(input: &Path, kind: Kind, encoding: Encoding) -> Result<()> {
+    let bytes = encoding::read(input, encoding)?;
+    let json = match kind {
+        Kind::Message => MlsMessageIn::try_from_bytes(&bytes)?.to_debug_json()?,
+        Kind::KeyPackage => KeyPackage::tls_deserialize(&mut bytes.as_slice())?.to_debug_json()?,
+        Kind::Welcome => Welcome::tls_deserialize(&mut bytes.as_slice())?.to_debug_json()?,
+        Kind::RatchetTree => {
+            let ratchet_tree = RatchetTreeExtension::tls_deserialize(&mut bytes.as_slice())?;
+            serde_json::to_string_pretty(&ratchet_tree)?
+        }
+    };
+    println!("{}", json);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! Reading and writing protocol objects in different encodings.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap_derive::ArgEnum;
+
+use crate::Result;
+
+/// The encoding of a file.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// The raw TLS serialization.
+    Binary,
+    /// The base64 encoded TLS serialization.
+    Base64,
+    /// The hex encoded TLS serialization.
+    Hex,
+}
+
+impl Encoding {
+    /// The file extension for the encoding.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Encoding::Binary => "bin",
+            Encoding::Base64 => "b64",
+            Encoding::Hex => "hex",
+        }
+    }
+}
+
+/// Reads the TLS serialized bytes from `path`, or from stdin if `path` is `-`.
+pub fn read(path: &Path, encoding: Encoding) -> Result<Vec<u8>> {
+    let mut input = Vec::new();
+    if path == Path::new("-") {
+        io::stdin().read_to_end(&mut input)?;
+    } else {
+        input = fs::read(path)?;
+    }
+
+    Ok(match encoding {
+        Encoding::Binary => input,
+        Encoding::Base64 => STANDARD.decode(String::from_utf8(input)?.trim())?,
+        Encoding::Hex => hex::decode(String::from_utf8(input)?.trim())?,
+    })
+}
+
+/// Writes the TLS serialized `bytes` to `path`.
+pub fn write(path: &Path, bytes: &[u8], encoding: Encoding) -> Result<()> {
+    match encoding {
+        Encoding::Binary => fs::write(path, bytes)?,
+        Encoding::Base64 => fs::write(path, STANDARD.encode(bytes))?,
+        Encoding::Hex => fs::write(path, hex::encode(bytes))?,
+    }
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//! A local group with multiple members that exchange messages.
+
+use std::{fs, path::Path};
+
+use openmls::prelude::*;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+use crate::Result;
+
+/// Label used to export a secret from every member to check that they agree
+/// on the group state.
+const EXPORTER_LABEL: &str = "openmls-cli";
+
+/// A member of the local group with its own backend.
+struct Client {
+    name: String,
+    backend: OpenMlsRustCrypto,
+    credential_bundle: CredentialBundle,
+    group: Option<MlsGroup>,
+}
+
+impl Client {
+    fn new(name: String, ciphersuite: Ciphersuite) -> Result<Self> {
+        let backend = OpenMlsRustCrypto::default();
+        let credential_bundle = CredentialBundle::new(
+            name.as_bytes().into(),
+            CredentialType::Basic,
+            ciphersuite.signature_algorithm(),
+            &backend,
+        )?;
+        backend.key_store().store(
+            &credential_bundle
+                .credential()
+                .signature_key()
+                .tls_serialize_detached()?,
+            &credential_bundle,
+        )?;
+        Ok(Self {
+            name,
+            backend,
+            credential_bundle,
+            group: None,
+        })
+    }
+
+    fn group(&mut self) -> Result<&mut MlsGroup> {
+        self.group
+            .as_mut()
+            .ok_or_else(|| format!("{} is not a member of the group", self.name).into())
+    }
+
+    fn key_package(&self, ciphersuite: Ciphersuite) -> Result<KeyPackage> {
+        Ok(KeyPackage::builder().build(
+            CryptoConfig::with_default_version(ciphersuite),
+            &self.backend,
+            &self.credential_bundle,
+        )?)
+    }
+
+    fn join(&mut self, config: &MlsGroupConfig, welcome: &[u8]) -> Result<()> {
+        let welcome = match MlsMessageIn::try_from_bytes(welcome)?.extract() {
+            MlsMessageInBody::Welcome(welcome) => welcome,
+            _ => return Err("Expected a Welcome message".into()),
+        };
+        self.group = Some(MlsGroup::new_from_welcome(
+            &self.backend,
+            config,
+            welcome,
+            None,
+        )?);
+        Ok(())
+    }
+
+    /// Processes a serialized message. Commits are merged right away. Returns
+    /// the content of application messages.
+    fn process(&mut self, message: &[u8]) -> Result<Option<Vec<u8>>> {
+        let message: ProtocolMessage = match MlsMessageIn::try_from_bytes(message)?.extract() {
+            MlsMessageInBody::PublicMessage(message) => message.into(),
+            MlsMessageInBody::PrivateMessage(message) => message.into(),
+            _ => return Err("Expected a PublicMessage or PrivateMessage".into()),
+        };
+        let backend = &self.backend;
+        let group = self
+            .group
+            .as_mut()
+            .ok_or_else(|| format!("{} is not a member of the group", self.name))?;
+        match group.process_message(backend, message)?.into_content() {
+            ProcessedMessageContent::ApplicationMessage(application_message) => {
+                Ok(Some(application_message.into_bytes()))
+            }
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                group.merge_staged_commit(backend, *staged_commit)?;
+                Ok(None)
+            }
+            _ => Err("Unexpected proposal".into()),
+        }
+    }
+}
+
+/// Writes the exchanged messages to a directory, if one is given.
+struct Recorder<'a> {
+    out_dir: Option<&'a Path>,
+    count: usize,
+}
+
+impl<'a> Recorder<'a> {
+    fn new(out_dir: Option<&'a Path>) -> Result<Self> {
+        if let Some(out_dir) = out_dir {
+            fs::create_dir_all(out_dir)?;
+        }
+        Ok(Self { out_dir, count: 0 })
+    }
+
+    fn record(&mut self, label: &str, bytes: &[u8]) -> Result<()> {
+        if let Some(out_dir) = self.out_dir {
+            fs::write(
+                out_dir.join(format!("{:03}-{}.bin", self.count, label)),
+                bytes,
+            )?;
+            self.count += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Creates a group with `members` members, lets every member update and send
+/// `messages` application messages, and checks that all members end up in
+/// the same state.
+pub fn run(
+    members: usize,
+    messages: usize,
+    ciphersuite: Ciphersuite,
+    plaintext: bool,
+    out_dir: Option<&Path>,
+) -> Result<()> {
+    if members == 0 {
+        return Err("The group needs at least one member".into());
+    }
+    let config = MlsGroupConfig::builder()
+        .wire_format_policy(if plaintext {
+            PURE_PLAINTEXT_WIRE_FORMAT_POLICY
+        } else {
+            PURE_CIPHERTEXT_WIRE_FORMAT_POLICY
+        })
+        .use_ratchet_tree_extension(true)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let mut recorder = Recorder::new(out_dir)?;
+    let mut clients = (0..members)
+        .map(|i| Client::new(format!("member-{}", i), ciphersuite))
+        .collect::<Result<Vec<_>>>()?;
+
+    // The first member creates the group and adds everybody else.
+    let creator = &mut clients[0];
+    creator.group = Some(MlsGroup::new(
+        &creator.backend,
+        &config,
+        creator.credential_bundle.credential().signature_key(),
+    )?);
+    println!("{} created the group", creator.name);
+
+    for new_member in 1..members {
+        let key_package = clients[new_member].key_package(ciphersuite)?;
+        let creator = &mut clients[0];
+        let backend = &creator.backend;
+        let group = creator
+            .group
+            .as_mut()
+            .ok_or("The creator is not a member of the group")?;
+        let (commit, welcome, _group_info) = group.add_members(backend, &[key_package])?;
+        group.merge_pending_commit(backend)?;
+
+        let commit = commit.to_bytes()?;
+        let welcome = welcome.to_bytes()?;
+        recorder.record("commit", &commit)?;
+        recorder.record("welcome", &welcome)?;
+        for member in clients[1..new_member].iter_mut() {
+            member.process(&commit)?;
+        }
+        clients[new_member].join(&config, &welcome)?;
+        println!("{} added {}", clients[0].name, clients[new_member].name);
+    }
+
+    // Every member updates its leaf.
+    for sender in 0..members {
+        let client = &mut clients[sender];
+        let backend = &client.backend;
+        let group = client
+            .group
+            .as_mut()
+            .ok_or("The sender is not a member of the group")?;
+        let (commit, _welcome, _group_info) = group.self_update(backend)?;
+        group.merge_pending_commit(backend)?;
+
+        let commit = commit.to_bytes()?;
+        recorder.record("commit", &commit)?;
+        for (_, member) in clients
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| *index != sender)
+        {
+            member.process(&commit)?;
+        }
+        println!("{} updated", clients[sender].name);
+    }
+
+    // Every member sends application messages.
+    for sender in 0..members {
+        for i in 0..messages {
+            let content = format!("Message {} from {}", i, clients[sender].name);
+            let client = &mut clients[sender];
+            let backend = &client.backend;
+            let group = client
+                .group
+                .as_mut()
+                .ok_or("The sender is not a member of the group")?;
+            let message = group
+                .create_message(backend, content.as_bytes())?
+                .to_bytes()?;
+            recorder.record("application", &message)?;
+            for (_, member) in clients
+                .iter_mut()
+                .enumerate()
+                .filter(|(index, _)| *index != sender)
+            {
+                if member.process(&message)?.as_deref() != Some(content.as_bytes()) {
+                    return Err(format!("{} received a wrong message", member.name).into());
+                }
+            }
+        }
+    }
+
+    // All members must agree on the group state.
+    let mut exported_secrets = Vec::with_capacity(members);
+    for client in clients.iter_mut() {
+        let backend = &client.backend;
+        let group = client
+            .group
+            .as_mut()
+            .ok_or("A client is not a member of the group")?;
+        exported_secrets.push((
+            group.epoch(),
+            group.export_secret(backend, EXPORTER_LABEL, &[], 32)?,
+        ));
+    }
+    if exported_secrets.windows(2).any(|pair| pair[0] != pair[1]) {
+        return Err("The members disagree on the group state".into());
+    }
+
+    let group = clients[0].group()?;
+    let ratchet_tree =
+        RatchetTreeExtension::new(group.export_ratchet_tree()).tls_serialize_detached()?;
+    recorder.record("ratchet_tree", &ratchet_tree)?;
+    println!(
+        "All {} members agree on epoch {} with {} application message(s) each",
+        members,
+        group.epoch().as_u64(),
+        messages
+    );
+    Ok(())
+}
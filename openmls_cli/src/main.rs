@@ -0,0 +1,158 @@
+//! # OpenMLS CLI
+//!
+//! A command line tool for debugging and provisioning. It can
+//! * generate credentials and key packages,
+//! * decode and pretty-print MLS messages, key packages, Welcome messages and
+//!   ratchet trees, e.g. from interop captures, and
+//! * drive a local group with multiple members and record the messages they
+//!   exchange.
+//!
+//! Run `openmls-cli help` for the usage.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use clap_derive::*;
+use openmls::prelude::*;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+mod decode;
+mod encoding;
+mod group;
+mod provision;
+
+use encoding::Encoding;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[clap(name = "openmls-cli", version, about)]
+struct Opts {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a basic credential and its signature key pair.
+    ///
+    /// The credential bundle, including the private signature key, is written
+    /// as JSON.
+    Credential {
+        /// The identity of the credential.
+        #[clap(short, long)]
+        identity: String,
+        /// The ciphersuite, which determines the signature scheme.
+        #[clap(short, long, default_value = "1")]
+        ciphersuite: u16,
+        /// The file the credential bundle is written to.
+        #[clap(short, long)]
+        out: PathBuf,
+    },
+    /// Generate key packages for a credential created with `credential`.
+    ///
+    /// Only the public key packages are written. The command line tool has
+    /// no persistent key store, so the private keys are discarded, i.e. the
+    /// key packages can be used to test adding members, but the members
+    /// can't join.
+    KeyPackage {
+        /// The credential bundle file.
+        #[clap(long)]
+        credential: PathBuf,
+        /// The ciphersuite of the key packages.
+        #[clap(short, long, default_value = "1")]
+        ciphersuite: u16,
+        /// The number of key packages.
+        #[clap(short = 'n', long, default_value = "1")]
+        count: usize,
+        /// The directory the key packages are written to.
+        #[clap(short, long)]
+        out_dir: PathBuf,
+        /// The encoding of the written key packages.
+        #[clap(short, long, arg_enum, default_value = "binary")]
+        encoding: Encoding,
+    },
+    /// Decode and pretty-print a protocol object.
+    Decode {
+        /// The file to decode. Use `-` to read from stdin.
+        input: PathBuf,
+        /// The kind of the object.
+        #[clap(short, long, arg_enum, default_value = "message")]
+        kind: decode::Kind,
+        /// The encoding of the input.
+        #[clap(short, long, arg_enum, default_value = "binary")]
+        encoding: Encoding,
+    },
+    /// Drive a local group with multiple members.
+    ///
+    /// The first member creates the group and adds all other members. Then
+    /// every member updates its leaf and sends application messages. All
+    /// messages are processed by all other members and the group state is
+    /// checked for consistency.
+    Group {
+        /// The number of members.
+        #[clap(short, long, default_value = "3")]
+        members: usize,
+        /// The number of application messages every member sends.
+        #[clap(long, default_value = "1")]
+        messages: usize,
+        /// The ciphersuite of the group.
+        #[clap(short, long, default_value = "1")]
+        ciphersuite: u16,
+        /// Send handshake messages as PublicMessage instead of PrivateMessage.
+        #[clap(long)]
+        plaintext: bool,
+        /// A directory to which all exchanged messages are written.
+        #[clap(short, long)]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+fn ciphersuite(value: u16) -> Result<Ciphersuite> {
+    Ciphersuite::try_from(value).map_err(|_| format!("Unknown ciphersuite {}", value).into())
+}
+
+fn main() -> Result<()> {
+    pretty_env_logger::init();
+
+    match Opts::parse().command {
+        Command::Credential {
+            identity,
+            ciphersuite: value,
+            out,
+        } => provision::credential(&identity, ciphersuite(value)?, &out),
+        Command::KeyPackage {
+            credential,
+            ciphersuite: value,
+            count,
+            out_dir,
+            encoding,
+        } => provision::key_packages(
+            &OpenMlsRustCrypto::default(),
+            &credential,
+            ciphersuite(value)?,
+            count,
+            &out_dir,
+            encoding,
+        )
+        .map(|_| ()),
+        Command::Decode {
+            input,
+            kind,
+            encoding,
+        } => decode::decode(&input, kind, encoding),
+        Command::Group {
+            members,
+            messages,
+            ciphersuite: value,
+            plaintext,
+            out_dir,
+        } => group::run(
+            members,
+            messages,
+            ciphersuite(value)?,
+            plaintext,
+            out_dir.as_deref(),
+        ),
+    }
+}
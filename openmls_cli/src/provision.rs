@@ -0,0 +1,142 @@
+//! Generation of credentials and key packages.
+
+use std::{fs, path::Path};
+
+use openmls::prelude::*;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+use crate::{
+    encoding::{self, Encoding},
+    Result,
+};
+
+/// Generates a basic credential for `identity` and writes the credential
+/// bundle to `out`.
+pub fn credential(identity: &str, ciphersuite: Ciphersuite, out: &Path) -> Result<()> {
+    let backend = OpenMlsRustCrypto::default();
+    let credential_bundle = CredentialBundle::new(
+        identity.into(),
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+        &backend,
+    )?;
+    fs::write(out, serde_json::to_vec_pretty(&credential_bundle)?)?;
+
+    println!(
+        "Wrote credential for {:?} with signature key {}",
+        identity,
+        hex::encode(credential_bundle.credential().signature_key().as_slice())
+    );
+    Ok(())
+}
+
+/// Generates `count` key packages for the credential bundle in `credential`
+/// and writes them to `out_dir`.
+///
+/// The private keys of the key packages are stored in the key store of the
+/// `backend`. The key packages can only be used to join groups if its key
+/// store is kept.
+pub fn key_packages(
+    backend: &impl OpenMlsCryptoProvider,
+    credential: &Path,
+    ciphersuite: Ciphersuite,
+    count: usize,
+    out_dir: &Path,
+    encoding: Encoding,
+) -> Result<Vec<KeyPackage>> {
+    let credential_bundle: CredentialBundle = serde_json::from_slice(&fs::read(credential)?)?;
+    fs::create_dir_all(out_dir)?;
+
+    let mut key_packages = Vec::with_capacity(count);
+    for i in 0..count {
+        let key_package = KeyPackage::builder().build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &credential_bundle,
+        )?;
+        let path = out_dir.join(format!("key_package_{}.{}", i, encoding.extension()));
+        encoding::write(&path, &key_package.tls_serialize_detached()?, encoding)?;
+
+        println!(
+            "Wrote key package {} to {}",
+            hex::encode(key_package.hash_ref(backend.crypto())?.as_slice()),
+            path.display()
+        );
+        key_packages.push(key_package);
+    }
+    Ok(key_packages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A provisioned key package can be used to join a group with the backend
+    // that provisioned it.
+    #[test]
+    fn join_with_provisioned_key_package() {
+        let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+        let out_dir = tempfile::tempdir().expect("Could not create directory.");
+        let credential_path = out_dir.path().join("bob.json");
+        credential("Bob", ciphersuite, &credential_path).expect("Could not write credential.");
+
+        let bob_backend = OpenMlsRustCrypto::default();
+        key_packages(
+            &bob_backend,
+            &credential_path,
+            ciphersuite,
+            1,
+            out_dir.path(),
+            Encoding::Hex,
+        )
+        .expect("Could not write key packages.");
+        let bytes = encoding::read(&out_dir.path().join("key_package_0.hex"), Encoding::Hex)
+            .expect("Could not read key package.");
+        let bob_key_package = KeyPackage::tls_deserialize(&mut bytes.as_slice())
+            .expect("Could not decode key package.");
+
+        let alice_backend = OpenMlsRustCrypto::default();
+        let alice_credential_bundle = CredentialBundle::new(
+            "Alice".into(),
+            CredentialType::Basic,
+            ciphersuite.signature_algorithm(),
+            &alice_backend,
+        )
+        .expect("Could not create credential.");
+        alice_backend
+            .key_store()
+            .store(
+                &alice_credential_bundle
+                    .credential()
+                    .signature_key()
+                    .tls_serialize_detached()
+                    .expect("Could not encode signature key."),
+                &alice_credential_bundle,
+            )
+            .expect("Could not store credential.");
+        let config = MlsGroupConfig::builder()
+            .use_ratchet_tree_extension(true)
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .build();
+        let mut alice_group = MlsGroup::new(
+            &alice_backend,
+            &config,
+            alice_credential_bundle.credential().signature_key(),
+        )
+        .expect("Could not create group.");
+        let (_commit, welcome, _group_info) = alice_group
+            .add_members(&alice_backend, &[bob_key_package])
+            .expect("Could not add member.");
+        alice_group
+            .merge_pending_commit(&alice_backend)
+            .expect("Could not merge commit.");
+
+        let welcome = match MlsMessageIn::from(welcome).extract() {
+            MlsMessageInBody::Welcome(welcome) => welcome,
+            _ => panic!("Expected a Welcome message"),
+        };
+        let bob_group = MlsGroup::new_from_welcome(&bob_backend, &config, welcome, None)
+            .expect("Could not join group.");
+        assert_eq!(bob_group.members().count(), 2);
+    }
+}
@@ -0,0 +1,414 @@
+//! # HPKE
+//!
+//! The base mode of HPKE ([RFC 9180]) with the DHKEMs, KDFs and AEADs of the
+//! MLS ciphersuites, on top of the OpenSSL primitives of this crate. Only the
+//! single-shot operations that OpenMLS needs are implemented, i.e. a context
+//! is used for at most one encryption.
+//!
+//! [RFC 9180]: https://www.rfc-editor.org/rfc/rfc9180
+
+use std::convert::TryFrom;
+
+use openmls_traits::types::{
+    AeadType, CryptoError, HashType, HpkeAeadType, HpkeConfig, HpkeKdfType, HpkeKemType,
+};
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    derive::Deriver,
+    ec::{EcGroup, EcKey, EcPoint},
+    nid::Nid,
+    pkey::{Id, PKey},
+};
+
+use crate::provider::{
+    aead_open, aead_seal, ec_key_from_private, ec_public_key, hkdf_expand, hkdf_extract,
+};
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+
+/// An HPKE suite.
+pub(crate) struct Hpke {
+    kem: HpkeKemType,
+    kdf: HpkeKdfType,
+    aead: HpkeAeadType,
+}
+
+/// An HPKE context after the key schedule, see [`Hpke::setup_sender()`] and
+/// [`Hpke::setup_receiver()`].
+pub(crate) struct Context {
+    aead: Option<AeadType>,
+    key: Vec<u8>,
+    base_nonce: Vec<u8>,
+    exporter_secret: Vec<u8>,
+    kdf_hash: HashType,
+    suite_id: Vec<u8>,
+}
+
+impl Context {
+    /// Encrypts the `plaintext` with the first nonce of the context.
+    fn seal(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let aead = self.aead.ok_or(CryptoError::UnsupportedAeadAlgorithm)?;
+        aead_seal(aead, &self.key, plaintext, &self.base_nonce, aad)
+    }
+
+    /// Decrypts the `ciphertext` with the first nonce of the context.
+    fn open(&self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let aead = self.aead.ok_or(CryptoError::UnsupportedAeadAlgorithm)?;
+        aead_open(aead, &self.key, ciphertext, &self.base_nonce, aad)
+    }
+
+    /// Exports a secret of `length` bytes for the `exporter_context`.
+    pub(crate) fn export(
+        &self,
+        exporter_context: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        labeled_expand(
+            self.kdf_hash,
+            &self.suite_id,
+            &self.exporter_secret,
+            b"sec",
+            exporter_context,
+            length,
+        )
+    }
+}
+
+fn labeled_extract(
+    hash_type: HashType,
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let labeled_ikm = [VERSION_LABEL, suite_id, label, ikm].concat();
+    hkdf_extract(hash_type, salt, &labeled_ikm)
+}
+
+fn labeled_expand(
+    hash_type: HashType,
+    suite_id: &[u8],
+    prk: &[u8],
+    label: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    let length_bytes = u16::try_from(length)
+        .map_err(|_| CryptoError::InvalidLength)?
+        .to_be_bytes();
+    let labeled_info = [&length_bytes[..], VERSION_LABEL, suite_id, label, info].concat();
+    hkdf_expand(hash_type, prk, &labeled_info, length)
+}
+
+impl Hpke {
+    pub(crate) fn new(config: HpkeConfig) -> Self {
+        Self {
+            kem: config.0,
+            kdf: config.1,
+            aead: config.2,
+        }
+    }
+
+    /// The hash of the KDF of the KEM.
+    fn kem_hash(&self) -> HashType {
+        match self.kem {
+            HpkeKemType::DhKemP256 | HpkeKemType::DhKem25519 => HashType::Sha2_256,
+            HpkeKemType::DhKemP384 => HashType::Sha2_384,
+            HpkeKemType::DhKemP521 | HpkeKemType::DhKem448 => HashType::Sha2_512,
+        }
+    }
+
+    /// The length of the shared secret of the KEM.
+    fn kem_secret_len(&self) -> usize {
+        match self.kem_hash() {
+            HashType::Sha2_256 => 32,
+            HashType::Sha2_384 => 48,
+            HashType::Sha2_512 => 64,
+        }
+    }
+
+    /// The length of a private key of the KEM.
+    fn private_key_len(&self) -> usize {
+        match self.kem {
+            HpkeKemType::DhKemP256 | HpkeKemType::DhKem25519 => 32,
+            HpkeKemType::DhKemP384 => 48,
+            HpkeKemType::DhKemP521 => 66,
+            HpkeKemType::DhKem448 => 56,
+        }
+    }
+
+    /// The curve of a NIST KEM, or the key type of an X25519 or X448 KEM.
+    fn curve(&self) -> Result<Curve, CryptoError> {
+        let nid = match self.kem {
+            HpkeKemType::DhKemP256 => Nid::X9_62_PRIME256V1,
+            HpkeKemType::DhKemP384 => Nid::SECP384R1,
+            HpkeKemType::DhKemP521 => Nid::SECP521R1,
+            HpkeKemType::DhKem25519 => return Ok(Curve::Montgomery(Id::X25519)),
+            HpkeKemType::DhKem448 => return Ok(Curve::Montgomery(Id::X448)),
+        };
+        EcGroup::from_curve_name(nid)
+            .map(Curve::Nist)
+            .map_err(|_| CryptoError::UnsupportedCiphersuite)
+    }
+
+    fn kdf_hash(&self) -> HashType {
+        match self.kdf {
+            HpkeKdfType::HkdfSha256 => HashType::Sha2_256,
+            HpkeKdfType::HkdfSha384 => HashType::Sha2_384,
+            HpkeKdfType::HkdfSha512 => HashType::Sha2_512,
+        }
+    }
+
+    fn aead_type(&self) -> Option<AeadType> {
+        match self.aead {
+            HpkeAeadType::AesGcm128 => Some(AeadType::Aes128Gcm),
+            HpkeAeadType::AesGcm256 => Some(AeadType::Aes256Gcm),
+            HpkeAeadType::ChaCha20Poly1305 => Some(AeadType::ChaCha20Poly1305),
+            HpkeAeadType::Export => None,
+        }
+    }
+
+    fn kem_suite_id(&self) -> Vec<u8> {
+        [&b"KEM"[..], &(self.kem as u16).to_be_bytes()[..]].concat()
+    }
+
+    fn suite_id(&self) -> Vec<u8> {
+        [
+            &b"HPKE"[..],
+            &(self.kem as u16).to_be_bytes()[..],
+            &(self.kdf as u16).to_be_bytes()[..],
+            &(self.aead as u16).to_be_bytes()[..],
+        ]
+        .concat()
+    }
+
+    /// Derives a key pair from the `ikm` (`DeriveKeyPair`). Returns the
+    /// private and the serialized public key.
+    pub(crate) fn derive_key_pair(&self, ikm: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let hash = self.kem_hash();
+        let suite_id = self.kem_suite_id();
+        let dkp_prk = labeled_extract(hash, &suite_id, b"", b"dkp_prk", ikm)?;
+        match self.curve()? {
+            Curve::Montgomery(_) => {
+                let private_key = labeled_expand(
+                    hash,
+                    &suite_id,
+                    &dkp_prk,
+                    b"sk",
+                    b"",
+                    self.private_key_len(),
+                )?;
+                let public_key = self.public_key(&private_key)?;
+                Ok((private_key, public_key))
+            }
+            Curve::Nist(group) => {
+                let bitmask = match self.kem {
+                    HpkeKemType::DhKemP521 => 0x01,
+                    _ => 0xff,
+                };
+                let mut ctx = BigNumContext::new().map_err(|_| CryptoError::CryptoLibraryError)?;
+                let mut order = BigNum::new().map_err(|_| CryptoError::CryptoLibraryError)?;
+                group
+                    .order(&mut order, &mut ctx)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                for counter in 0..=255u8 {
+                    let mut candidate = labeled_expand(
+                        hash,
+                        &suite_id,
+                        &dkp_prk,
+                        b"candidate",
+                        &[counter],
+                        self.private_key_len(),
+                    )?;
+                    candidate[0] &= bitmask;
+                    let scalar = BigNum::from_slice(&candidate)
+                        .map_err(|_| CryptoError::CryptoLibraryError)?;
+                    if scalar.num_bits() > 0 && scalar < order {
+                        let key = ec_key_from_private(&group, &candidate)?;
+                        return Ok((candidate, ec_public_key(&key)?));
+                    }
+                }
+                Err(CryptoError::CryptoLibraryError)
+            }
+        }
+    }
+
+    /// Generates a random key pair.
+    pub(crate) fn generate_key_pair(&self) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let mut ikm = vec![0u8; self.private_key_len()];
+        openssl::rand::rand_bytes(&mut ikm).map_err(|_| CryptoError::InsufficientRandomness)?;
+        self.derive_key_pair(&ikm)
+    }
+
+    /// Returns the serialized public key of the `private_key`.
+    fn public_key(&self, private_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match self.curve()? {
+            Curve::Montgomery(id) => PKey::private_key_from_raw_bytes(private_key, id)
+                .and_then(|key| key.raw_public_key())
+                .map_err(|_| CryptoError::CryptoLibraryError),
+            Curve::Nist(group) => ec_public_key(&ec_key_from_private(&group, private_key)?),
+        }
+    }
+
+    /// The Diffie-Hellman shared secret of the `private_key` and the
+    /// serialized `public_key`.
+    fn dh(&self, private_key: &[u8], public_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let (private_key, public_key) = match self.curve()? {
+            Curve::Montgomery(id) => (
+                PKey::private_key_from_raw_bytes(private_key, id)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?,
+                PKey::public_key_from_raw_bytes(public_key, id)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?,
+            ),
+            Curve::Nist(group) => {
+                let mut ctx = BigNumContext::new().map_err(|_| CryptoError::CryptoLibraryError)?;
+                let point = EcPoint::from_bytes(&group, public_key, &mut ctx)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let public_key = EcKey::from_public_key(&group, &point)
+                    .and_then(PKey::from_ec_key)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let private_key = PKey::from_ec_key(ec_key_from_private(&group, private_key)?)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                (private_key, public_key)
+            }
+        };
+        let mut deriver =
+            Deriver::new(&private_key).map_err(|_| CryptoError::CryptoLibraryError)?;
+        deriver
+            .set_peer(&public_key)
+            .map_err(|_| CryptoError::CryptoLibraryError)?;
+        deriver
+            .derive_to_vec()
+            .map_err(|_| CryptoError::CryptoLibraryError)
+    }
+
+    /// `ExtractAndExpand` of the DHKEM.
+    fn extract_and_expand(&self, dh: &[u8], kem_context: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let hash = self.kem_hash();
+        let suite_id = self.kem_suite_id();
+        let eae_prk = labeled_extract(hash, &suite_id, b"", b"eae_prk", dh)?;
+        labeled_expand(
+            hash,
+            &suite_id,
+            &eae_prk,
+            b"shared_secret",
+            kem_context,
+            self.kem_secret_len(),
+        )
+    }
+
+    /// `Encap` of the DHKEM. Returns the shared secret and the encapsulation.
+    fn encap(&self, pk_r: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let (sk_e, enc) = self.generate_key_pair()?;
+        let dh = self.dh(&sk_e, pk_r)?;
+        let kem_context = [enc.as_slice(), pk_r].concat();
+        Ok((self.extract_and_expand(&dh, &kem_context)?, enc))
+    }
+
+    /// `Decap` of the DHKEM. Returns the shared secret.
+    fn decap(&self, enc: &[u8], sk_r: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let dh = self.dh(sk_r, enc)?;
+        let kem_context = [enc, self.public_key(sk_r)?.as_slice()].concat();
+        self.extract_and_expand(&dh, &kem_context)
+    }
+
+    /// The key schedule of the base mode.
+    fn key_schedule(&self, shared_secret: &[u8], info: &[u8]) -> Result<Context, CryptoError> {
+        let hash = self.kdf_hash();
+        let suite_id = self.suite_id();
+        let psk_id_hash = labeled_extract(hash, &suite_id, b"", b"psk_id_hash", b"")?;
+        let info_hash = labeled_extract(hash, &suite_id, b"", b"info_hash", info)?;
+        // The mode is 0 (base).
+        let key_schedule_context = [&[0u8][..], &psk_id_hash, &info_hash].concat();
+        let secret = labeled_extract(hash, &suite_id, shared_secret, b"secret", b"")?;
+
+        let aead = self.aead_type();
+        let (key, base_nonce) = match aead {
+            Some(aead) => (
+                labeled_expand(
+                    hash,
+                    &suite_id,
+                    &secret,
+                    b"key",
+                    &key_schedule_context,
+                    aead.key_size(),
+                )?,
+                labeled_expand(
+                    hash,
+                    &suite_id,
+                    &secret,
+                    b"base_nonce",
+                    &key_schedule_context,
+                    aead.nonce_size(),
+                )?,
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+        let exporter_secret = labeled_expand(
+            hash,
+            &suite_id,
+            &secret,
+            b"exp",
+            &key_schedule_context,
+            secret.len(),
+        )?;
+        Ok(Context {
+            aead,
+            key,
+            base_nonce,
+            exporter_secret,
+            kdf_hash: hash,
+            suite_id,
+        })
+    }
+
+    /// `SetupBaseS`. Returns the encapsulation and the context.
+    pub(crate) fn setup_sender(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+    ) -> Result<(Vec<u8>, Context), CryptoError> {
+        let (shared_secret, enc) = self.encap(pk_r)?;
+        Ok((enc, self.key_schedule(&shared_secret, info)?))
+    }
+
+    /// `SetupBaseR`.
+    pub(crate) fn setup_receiver(
+        &self,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+    ) -> Result<Context, CryptoError> {
+        let shared_secret = self.decap(enc, sk_r)?;
+        self.key_schedule(&shared_secret, info)
+    }
+
+    /// `SealBase`. Returns the encapsulation and the ciphertext.
+    pub(crate) fn seal(
+        &self,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        let (enc, context) = self.setup_sender(pk_r, info)?;
+        Ok((enc, context.seal(aad, plaintext)?))
+    }
+
+    /// `OpenBase`.
+    pub(crate) fn open(
+        &self,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.setup_receiver(enc, sk_r, info)?.open(aad, ciphertext)
+    }
+}
+
+enum Curve {
+    Nist(EcGroup),
+    Montgomery(Id),
+}
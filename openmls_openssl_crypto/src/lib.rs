@@ -0,0 +1,35 @@
+//! # OpenSSL Crypto Provider
+//!
+//! This is an implementation of the [`OpenMlsCryptoProvider`] trait that uses
+//! OpenSSL 3 for all cryptographic operations and randomness.
+
+pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError};
+use openmls_traits::OpenMlsCryptoProvider;
+
+mod hpke;
+mod provider;
+pub use provider::*;
+
+#[derive(Default, Debug)]
+pub struct OpenMlsOpenSsl {
+    crypto: OpenSslCrypto,
+    key_store: MemoryKeyStore,
+}
+
+impl OpenMlsCryptoProvider for OpenMlsOpenSsl {
+    type CryptoProvider = OpenSslCrypto;
+    type RandProvider = OpenSslCrypto;
+    type KeyStoreProvider = MemoryKeyStore;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
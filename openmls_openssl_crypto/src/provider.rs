@@ -0,0 +1,513 @@
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    random::OpenMlsRand,
+    types::{
+        AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeCiphertext, HpkeConfig,
+        HpkeKeyPair, KemOutput, SignatureScheme,
+    },
+};
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    ec::{EcGroup, EcGroupRef, EcKey, EcPoint, PointConversionForm},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{Id, PKey, Private},
+    sign::{Signer, Verifier},
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+
+use crate::hpke::Hpke;
+
+const ALL_CIPHERSUITES: [Ciphersuite; 7] = [
+    Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+    Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+    Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+    Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448,
+    Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+    Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448,
+    Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+];
+
+/// The OpenSSL crypto provider.
+///
+/// The supported ciphersuites are determined when the provider is created,
+/// by running the algorithms of every ciphersuite once. Ciphersuites with
+/// algorithms that are not available in the OpenSSL configuration, e.g.
+/// because only the FIPS provider is loaded, are not supported.
+#[derive(Debug)]
+pub struct OpenSslCrypto {
+    supported_ciphersuites: Vec<Ciphersuite>,
+}
+
+impl Default for OpenSslCrypto {
+    fn default() -> Self {
+        let mut crypto = Self {
+            supported_ciphersuites: Vec::new(),
+        };
+        crypto.supported_ciphersuites = ALL_CIPHERSUITES
+            .iter()
+            .copied()
+            .filter(|ciphersuite| crypto.probe(*ciphersuite))
+            .collect();
+        crypto
+    }
+}
+
+impl OpenSslCrypto {
+    /// Returns `true` if all algorithms of the `ciphersuite` work.
+    fn probe(&self, ciphersuite: Ciphersuite) -> bool {
+        let aead = ciphersuite.aead_algorithm();
+        self.hash(ciphersuite.hash_algorithm(), &[]).is_ok()
+            && self
+                .aead_encrypt(
+                    aead,
+                    &vec![0u8; aead.key_size()],
+                    &[],
+                    &vec![0u8; aead.nonce_size()],
+                    &[],
+                )
+                .is_ok()
+            && self
+                .signature_key_gen(ciphersuite.signature_algorithm())
+                .is_ok()
+            && Hpke::new(ciphersuite.hpke_config())
+                .generate_key_pair()
+                .is_ok()
+    }
+}
+
+/// Returns the OpenSSL digest of the hash type.
+pub(crate) fn message_digest(hash_type: HashType) -> MessageDigest {
+    match hash_type {
+        HashType::Sha2_256 => MessageDigest::sha256(),
+        HashType::Sha2_384 => MessageDigest::sha384(),
+        HashType::Sha2_512 => MessageDigest::sha512(),
+    }
+}
+
+fn hmac(digest: MessageDigest, key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let key = PKey::hmac(key).map_err(|_| CryptoError::CryptoLibraryError)?;
+    let mut signer = Signer::new(digest, &key).map_err(|_| CryptoError::CryptoLibraryError)?;
+    signer
+        .update(data)
+        .map_err(|_| CryptoError::CryptoLibraryError)?;
+    signer
+        .sign_to_vec()
+        .map_err(|_| CryptoError::CryptoLibraryError)
+}
+
+/// HKDF extract (RFC 5869) with HMAC.
+pub(crate) fn hkdf_extract(
+    hash_type: HashType,
+    salt: &[u8],
+    ikm: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let digest = message_digest(hash_type);
+    // An empty salt is the same as a salt of zeros, but not all OpenSSL
+    // versions accept empty HMAC keys.
+    let zeros;
+    let salt = if salt.is_empty() {
+        zeros = vec![0u8; digest.size()];
+        &zeros
+    } else {
+        salt
+    };
+    hmac(digest, salt, ikm)
+}
+
+/// HKDF expand (RFC 5869) with HMAC.
+pub(crate) fn hkdf_expand(
+    hash_type: HashType,
+    prk: &[u8],
+    info: &[u8],
+    okm_len: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    let digest = message_digest(hash_type);
+    if prk.len() < digest.size() || okm_len > 255 * digest.size() {
+        return Err(CryptoError::HkdfOutputLengthInvalid);
+    }
+    let mut okm = Vec::with_capacity(okm_len);
+    let mut block = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < okm_len {
+        block.extend_from_slice(info);
+        block.push(counter);
+        block = hmac(digest, prk, &block)?;
+        okm.extend_from_slice(&block);
+        counter = counter.wrapping_add(1);
+    }
+    okm.truncate(okm_len);
+    Ok(okm)
+}
+
+fn cipher(alg: AeadType) -> Cipher {
+    match alg {
+        AeadType::Aes128Gcm => Cipher::aes_128_gcm(),
+        AeadType::Aes256Gcm => Cipher::aes_256_gcm(),
+        AeadType::ChaCha20Poly1305 => Cipher::chacha20_poly1305(),
+    }
+}
+
+/// AEAD encryption. The tag is appended to the ciphertext.
+pub(crate) fn aead_seal(
+    alg: AeadType,
+    key: &[u8],
+    data: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    if key.len() != alg.key_size() || nonce.len() != alg.nonce_size() {
+        return Err(CryptoError::InvalidLength);
+    }
+    let mut tag = vec![0u8; alg.tag_size()];
+    let mut ct_tag = encrypt_aead(cipher(alg), key, Some(nonce), aad, data, &mut tag)
+        .map_err(|_| CryptoError::CryptoLibraryError)?;
+    ct_tag.extend_from_slice(&tag);
+    Ok(ct_tag)
+}
+
+/// AEAD decryption of a ciphertext with appended tag.
+pub(crate) fn aead_open(
+    alg: AeadType,
+    key: &[u8],
+    ct_tag: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    if key.len() != alg.key_size() || nonce.len() != alg.nonce_size() {
+        return Err(CryptoError::InvalidLength);
+    }
+    if ct_tag.len() < alg.tag_size() {
+        return Err(CryptoError::AeadDecryptionError);
+    }
+    let (ciphertext, tag) = ct_tag.split_at(ct_tag.len() - alg.tag_size());
+    decrypt_aead(cipher(alg), key, Some(nonce), aad, ciphertext, tag)
+        .map_err(|_| CryptoError::AeadDecryptionError)
+}
+
+/// The curve, digest and scalar length of an ECDSA signature scheme.
+fn ecdsa_parameters(alg: SignatureScheme) -> Option<(Nid, MessageDigest, usize)> {
+    match alg {
+        SignatureScheme::ECDSA_SECP256R1_SHA256 => {
+            Some((Nid::X9_62_PRIME256V1, MessageDigest::sha256(), 32))
+        }
+        SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+            Some((Nid::SECP384R1, MessageDigest::sha384(), 48))
+        }
+        SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+            Some((Nid::SECP521R1, MessageDigest::sha512(), 66))
+        }
+        SignatureScheme::ED25519 | SignatureScheme::ED448 => None,
+    }
+}
+
+/// The OpenSSL key type of an EdDSA signature scheme.
+fn eddsa_id(alg: SignatureScheme) -> Option<Id> {
+    match alg {
+        SignatureScheme::ED25519 => Some(Id::ED25519),
+        SignatureScheme::ED448 => Some(Id::ED448),
+        _ => None,
+    }
+}
+
+/// Returns the EC key with the private scalar `private_key`, which is
+/// checked to be in range.
+pub(crate) fn ec_key_from_private(
+    group: &EcGroupRef,
+    private_key: &[u8],
+) -> Result<EcKey<Private>, CryptoError> {
+    let ctx = BigNumContext::new().map_err(|_| CryptoError::CryptoLibraryError)?;
+    let d = BigNum::from_slice(private_key).map_err(|_| CryptoError::CryptoLibraryError)?;
+    let mut public = EcPoint::new(group).map_err(|_| CryptoError::CryptoLibraryError)?;
+    public
+        .mul_generator(group, &d, &ctx)
+        .map_err(|_| CryptoError::CryptoLibraryError)?;
+    let key = EcKey::from_private_components(group, &d, &public)
+        .map_err(|_| CryptoError::CryptoLibraryError)?;
+    key.check_key().map_err(|_| CryptoError::InvalidLength)?;
+    Ok(key)
+}
+
+/// Returns the uncompressed encoding of the public key of the EC key.
+pub(crate) fn ec_public_key<T: openssl::pkey::HasPublic>(
+    key: &EcKey<T>,
+) -> Result<Vec<u8>, CryptoError> {
+    let mut ctx = BigNumContext::new().map_err(|_| CryptoError::CryptoLibraryError)?;
+    key.public_key()
+        .to_bytes(key.group(), PointConversionForm::UNCOMPRESSED, &mut ctx)
+        .map_err(|_| CryptoError::CryptoLibraryError)
+}
+
+impl OpenMlsCrypto for OpenSslCrypto {
+    fn supports(&self, ciphersuite: Ciphersuite) -> Result<(), CryptoError> {
+        if self.supported_ciphersuites.contains(&ciphersuite) {
+            Ok(())
+        } else {
+            Err(CryptoError::UnsupportedCiphersuite)
+        }
+    }
+
+    fn supported_ciphersuites(&self) -> Vec<Ciphersuite> {
+        self.supported_ciphersuites.clone()
+    }
+
+    fn hkdf_extract(
+        &self,
+        hash_type: HashType,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        hkdf_extract(hash_type, salt, ikm)
+    }
+
+    fn hkdf_expand(
+        &self,
+        hash_type: HashType,
+        prk: &[u8],
+        info: &[u8],
+        okm_len: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        hkdf_expand(hash_type, prk, info, okm_len)
+    }
+
+    fn hash(&self, hash_type: HashType, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        openssl::hash::hash(message_digest(hash_type), data)
+            .map(|digest| digest.to_vec())
+            .map_err(|_| CryptoError::CryptoLibraryError)
+    }
+
+    fn aead_encrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        data: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        aead_seal(alg, key, data, nonce, aad)
+    }
+
+    fn aead_decrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        ct_tag: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        aead_open(alg, key, ct_tag, nonce, aad)
+    }
+
+    fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        if let Some((nid, _, scalar_len)) = ecdsa_parameters(alg) {
+            let group = EcGroup::from_curve_name(nid)
+                .map_err(|_| CryptoError::UnsupportedSignatureScheme)?;
+            let key = EcKey::generate(&group).map_err(|_| CryptoError::CryptoLibraryError)?;
+            let private_key = key
+                .private_key()
+                .to_vec_padded(scalar_len as i32)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+            return Ok((private_key, ec_public_key(&key)?));
+        }
+        let key = match alg {
+            SignatureScheme::ED25519 => PKey::generate_ed25519(),
+            _ => PKey::generate_ed448(),
+        }
+        .map_err(|_| CryptoError::UnsupportedSignatureScheme)?;
+        let private_key = key
+            .raw_private_key()
+            .map_err(|_| CryptoError::CryptoLibraryError)?;
+        let public_key = key
+            .raw_public_key()
+            .map_err(|_| CryptoError::CryptoLibraryError)?;
+        Ok((private_key, public_key))
+    }
+
+    fn signature_key_import(
+        &self,
+        alg: SignatureScheme,
+        private_key: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        if let Some((nid, _, scalar_len)) = ecdsa_parameters(alg) {
+            if private_key.len() != scalar_len {
+                return Err(CryptoError::InvalidLength);
+            }
+            let group = EcGroup::from_curve_name(nid)
+                .map_err(|_| CryptoError::UnsupportedSignatureScheme)?;
+            let key = ec_key_from_private(&group, private_key)?;
+            return Ok((private_key.to_vec(), ec_public_key(&key)?));
+        }
+        let id = eddsa_id(alg).ok_or(CryptoError::UnsupportedSignatureScheme)?;
+        let key = PKey::private_key_from_raw_bytes(private_key, id)
+            .map_err(|_| CryptoError::InvalidLength)?;
+        let public_key = key
+            .raw_public_key()
+            .map_err(|_| CryptoError::CryptoLibraryError)?;
+        Ok((private_key.to_vec(), public_key))
+    }
+
+    fn verify_signature(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CryptoError> {
+        let valid = if let Some((nid, digest, _)) = ecdsa_parameters(alg) {
+            let group = EcGroup::from_curve_name(nid)
+                .map_err(|_| CryptoError::UnsupportedSignatureScheme)?;
+            let mut ctx = BigNumContext::new().map_err(|_| CryptoError::CryptoLibraryError)?;
+            let point = EcPoint::from_bytes(&group, pk, &mut ctx)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+            let key = EcKey::from_public_key(&group, &point)
+                .and_then(PKey::from_ec_key)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+            let mut verifier =
+                Verifier::new(digest, &key).map_err(|_| CryptoError::CryptoLibraryError)?;
+            verifier
+                .update(data)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+            verifier
+                .verify(signature)
+                .map_err(|_| CryptoError::InvalidSignature)?
+        } else {
+            let id = eddsa_id(alg).ok_or(CryptoError::UnsupportedSignatureScheme)?;
+            let key = PKey::public_key_from_raw_bytes(pk, id)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+            let mut verifier =
+                Verifier::new_without_digest(&key).map_err(|_| CryptoError::CryptoLibraryError)?;
+            verifier
+                .verify_oneshot(signature, data)
+                .map_err(|_| CryptoError::InvalidSignature)?
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+
+    fn sign(&self, alg: SignatureScheme, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if let Some((nid, digest, _)) = ecdsa_parameters(alg) {
+            let group = EcGroup::from_curve_name(nid)
+                .map_err(|_| CryptoError::UnsupportedSignatureScheme)?;
+            let key = PKey::from_ec_key(ec_key_from_private(&group, key)?)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+            let mut signer =
+                Signer::new(digest, &key).map_err(|_| CryptoError::CryptoLibraryError)?;
+            signer
+                .update(data)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+            return signer
+                .sign_to_vec()
+                .map_err(|_| CryptoError::CryptoLibraryError);
+        }
+        let id = eddsa_id(alg).ok_or(CryptoError::UnsupportedSignatureScheme)?;
+        let key = PKey::private_key_from_raw_bytes(key, id)
+            .map_err(|_| CryptoError::CryptoLibraryError)?;
+        let mut signer =
+            Signer::new_without_digest(&key).map_err(|_| CryptoError::CryptoLibraryError)?;
+        signer
+            .sign_oneshot_to_vec(data)
+            .map_err(|_| CryptoError::CryptoLibraryError)
+    }
+
+    fn hpke_seal(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ptxt: &[u8],
+    ) -> HpkeCiphertext {
+        let (kem_output, ciphertext) = Hpke::new(config)
+            .seal(pk_r, info, aad, ptxt)
+            .expect("HPKE encryption failed.");
+        HpkeCiphertext {
+            kem_output: kem_output.into(),
+            ciphertext: ciphertext.into(),
+        }
+    }
+
+    fn hpke_open(
+        &self,
+        config: HpkeConfig,
+        input: &HpkeCiphertext,
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        Hpke::new(config)
+            .open(
+                input.kem_output.as_slice(),
+                sk_r,
+                info,
+                aad,
+                input.ciphertext.as_slice(),
+            )
+            .map_err(|_| CryptoError::HpkeDecryptionError)
+    }
+
+    fn hpke_setup_sender_and_export(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<(KemOutput, ExporterSecret), CryptoError> {
+        let (kem_output, context) = Hpke::new(config)
+            .setup_sender(pk_r, info)
+            .map_err(|_| CryptoError::SenderSetupError)?;
+        let exported_secret = context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)?;
+        Ok((kem_output, exported_secret))
+    }
+
+    fn hpke_setup_receiver_and_export(
+        &self,
+        config: HpkeConfig,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<ExporterSecret, CryptoError> {
+        let context = Hpke::new(config)
+            .setup_receiver(enc, sk_r, info)
+            .map_err(|_| CryptoError::ReceiverSetupError)?;
+        context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)
+    }
+
+    fn derive_hpke_keypair(&self, config: HpkeConfig, ikm: &[u8]) -> HpkeKeyPair {
+        let (private, public) = Hpke::new(config)
+            .derive_key_pair(ikm)
+            .expect("HPKE key derivation failed.");
+        HpkeKeyPair { private, public }
+    }
+}
+
+impl OpenMlsRand for OpenSslCrypto {
+    type Error = RandError;
+
+    fn random_array<const N: usize>(&self) -> Result<[u8; N], Self::Error> {
+        let mut out = [0u8; N];
+        openssl::rand::rand_bytes(&mut out).map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+
+    fn random_vec(&self, len: usize) -> Result<Vec<u8>, Self::Error> {
+        let mut out = vec![0u8; len];
+        openssl::rand::rand_bytes(&mut out).map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RandError {
+    #[error("Unable to collect enough randomness.")]
+    NotEnoughRandomness,
+}
@@ -0,0 +1,34 @@
+//! # ring Crypto Provider
+//!
+//! This is an implementation of the [`OpenMlsCryptoProvider`] trait that uses
+//! ring for all cryptographic operations except HPKE.
+
+pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError};
+use openmls_traits::OpenMlsCryptoProvider;
+
+mod provider;
+pub use provider::*;
+
+#[derive(Default, Debug)]
+pub struct OpenMlsRing {
+    crypto: RingCrypto,
+    key_store: MemoryKeyStore,
+}
+
+impl OpenMlsCryptoProvider for OpenMlsRing {
+    type CryptoProvider = RingCrypto;
+    type RandProvider = RingCrypto;
+    type KeyStoreProvider = MemoryKeyStore;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
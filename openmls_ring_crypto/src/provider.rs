@@ -0,0 +1,402 @@
+//! # ring Crypto Provider
+//!
+//! Use ring for all crypto operations except HPKE, which uses hpke-rs with
+//! the RustCrypto backend.
+
+use hpke::Hpke;
+use hpke_rs_crypto::types as hpke_types;
+use hpke_rs_rust_crypto::HpkeRustCrypto;
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    random::OpenMlsRand,
+    types::{
+        AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeAeadType, HpkeCiphertext,
+        HpkeConfig, HpkeKdfType, HpkeKemType, HpkeKeyPair, KemOutput, SignatureScheme,
+    },
+};
+use ring::{
+    aead::{self, Aad, LessSafeKey, Nonce, UnboundKey},
+    digest, hmac,
+    rand::{SecureRandom, SystemRandom},
+    signature::{self, EcdsaKeyPair, Ed25519KeyPair, KeyPair, UnparsedPublicKey},
+};
+
+/// The ring crypto provider.
+#[derive(Debug)]
+pub struct RingCrypto {
+    rng: SystemRandom,
+}
+
+impl Default for RingCrypto {
+    fn default() -> Self {
+        Self {
+            rng: SystemRandom::new(),
+        }
+    }
+}
+
+fn hmac_algorithm(hash_type: HashType) -> hmac::Algorithm {
+    match hash_type {
+        HashType::Sha2_256 => hmac::HMAC_SHA256,
+        HashType::Sha2_384 => hmac::HMAC_SHA384,
+        HashType::Sha2_512 => hmac::HMAC_SHA512,
+    }
+}
+
+fn aead_algorithm(alg: AeadType) -> &'static aead::Algorithm {
+    match alg {
+        AeadType::Aes128Gcm => &aead::AES_128_GCM,
+        AeadType::Aes256Gcm => &aead::AES_256_GCM,
+        AeadType::ChaCha20Poly1305 => &aead::CHACHA20_POLY1305,
+    }
+}
+
+fn aead_key(alg: AeadType, key: &[u8]) -> Result<LessSafeKey, CryptoError> {
+    UnboundKey::new(aead_algorithm(alg), key)
+        .map(LessSafeKey::new)
+        .map_err(|_| CryptoError::CryptoLibraryError)
+}
+
+fn ecdsa_signing_algorithm(
+    alg: SignatureScheme,
+) -> Result<&'static signature::EcdsaSigningAlgorithm, CryptoError> {
+    match alg {
+        SignatureScheme::ECDSA_SECP256R1_SHA256 => Ok(&signature::ECDSA_P256_SHA256_ASN1_SIGNING),
+        SignatureScheme::ECDSA_SECP384R1_SHA384 => Ok(&signature::ECDSA_P384_SHA384_ASN1_SIGNING),
+        _ => Err(CryptoError::UnsupportedSignatureScheme),
+    }
+}
+
+fn verification_algorithm(
+    alg: SignatureScheme,
+) -> Result<&'static dyn signature::VerificationAlgorithm, CryptoError> {
+    match alg {
+        SignatureScheme::ED25519 => Ok(&signature::ED25519),
+        SignatureScheme::ECDSA_SECP256R1_SHA256 => Ok(&signature::ECDSA_P256_SHA256_ASN1),
+        SignatureScheme::ECDSA_SECP384R1_SHA384 => Ok(&signature::ECDSA_P384_SHA384_ASN1),
+        SignatureScheme::ED448 | SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+            Err(CryptoError::UnsupportedSignatureScheme)
+        }
+    }
+}
+
+#[inline(always)]
+fn kem_mode(kem: HpkeKemType) -> hpke_types::KemAlgorithm {
+    match kem {
+        HpkeKemType::DhKemP256 => hpke_types::KemAlgorithm::DhKemP256,
+        HpkeKemType::DhKemP384 => hpke_types::KemAlgorithm::DhKemP384,
+        HpkeKemType::DhKemP521 => hpke_types::KemAlgorithm::DhKemP521,
+        HpkeKemType::DhKem25519 => hpke_types::KemAlgorithm::DhKem25519,
+        HpkeKemType::DhKem448 => hpke_types::KemAlgorithm::DhKem448,
+    }
+}
+
+#[inline(always)]
+fn kdf_mode(kdf: HpkeKdfType) -> hpke_types::KdfAlgorithm {
+    match kdf {
+        HpkeKdfType::HkdfSha256 => hpke_types::KdfAlgorithm::HkdfSha256,
+        HpkeKdfType::HkdfSha384 => hpke_types::KdfAlgorithm::HkdfSha384,
+        HpkeKdfType::HkdfSha512 => hpke_types::KdfAlgorithm::HkdfSha512,
+    }
+}
+
+#[inline(always)]
+fn aead_mode(aead: HpkeAeadType) -> hpke_types::AeadAlgorithm {
+    match aead {
+        HpkeAeadType::AesGcm128 => hpke_types::AeadAlgorithm::Aes128Gcm,
+        HpkeAeadType::AesGcm256 => hpke_types::AeadAlgorithm::Aes256Gcm,
+        HpkeAeadType::ChaCha20Poly1305 => hpke_types::AeadAlgorithm::ChaCha20Poly1305,
+        HpkeAeadType::Export => hpke_types::AeadAlgorithm::HpkeExport,
+    }
+}
+
+fn hpke_from_config(config: HpkeConfig) -> Hpke<HpkeRustCrypto> {
+    Hpke::<HpkeRustCrypto>::new(
+        hpke::Mode::Base,
+        kem_mode(config.0),
+        kdf_mode(config.1),
+        aead_mode(config.2),
+    )
+}
+
+impl OpenMlsCrypto for RingCrypto {
+    fn supports(&self, ciphersuite: Ciphersuite) -> Result<(), CryptoError> {
+        match ciphersuite {
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519
+            | Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519
+            | Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 => Ok(()),
+            _ => Err(CryptoError::UnsupportedCiphersuite),
+        }
+    }
+
+    fn supported_ciphersuites(&self) -> Vec<Ciphersuite> {
+        vec![
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+            Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+        ]
+    }
+
+    fn hkdf_extract(
+        &self,
+        hash_type: HashType,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        // ring's HKDF doesn't expose the pseudorandom key, so HKDF is
+        // implemented with HMAC.
+        let key = hmac::Key::new(hmac_algorithm(hash_type), salt);
+        Ok(hmac::sign(&key, ikm).as_ref().to_vec())
+    }
+
+    fn hkdf_expand(
+        &self,
+        hash_type: HashType,
+        prk: &[u8],
+        info: &[u8],
+        okm_len: usize,
+    ) -> Result<Vec<u8>, CryptoError> {
+        let algorithm = hmac_algorithm(hash_type);
+        let hash_len = algorithm.digest_algorithm().output_len;
+        if prk.len() < hash_len || okm_len > 255 * hash_len {
+            return Err(CryptoError::HkdfOutputLengthInvalid);
+        }
+        let key = hmac::Key::new(algorithm, prk);
+        let mut okm = Vec::with_capacity(okm_len);
+        let mut block = Vec::new();
+        let mut counter = 1u8;
+        while okm.len() < okm_len {
+            block.extend_from_slice(info);
+            block.push(counter);
+            block = hmac::sign(&key, &block).as_ref().to_vec();
+            okm.extend_from_slice(&block);
+            counter = counter.wrapping_add(1);
+        }
+        okm.truncate(okm_len);
+        Ok(okm)
+    }
+
+    fn hash(&self, hash_type: HashType, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let algorithm = match hash_type {
+            HashType::Sha2_256 => &digest::SHA256,
+            HashType::Sha2_384 => &digest::SHA384,
+            HashType::Sha2_512 => &digest::SHA512,
+        };
+        Ok(digest::digest(algorithm, data).as_ref().to_vec())
+    }
+
+    fn aead_encrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        data: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| CryptoError::CryptoLibraryError)?;
+        let mut in_out = data.to_vec();
+        aead_key(alg, key)?
+            .seal_in_place_append_tag(nonce, Aad::from(aad), &mut in_out)
+            .map_err(|_| CryptoError::CryptoLibraryError)?;
+        Ok(in_out)
+    }
+
+    fn aead_decrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        ct_tag: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| CryptoError::CryptoLibraryError)?;
+        let mut in_out = ct_tag.to_vec();
+        let plaintext = aead_key(alg, key)?
+            .open_in_place(nonce, Aad::from(aad), &mut in_out)
+            .map_err(|_| CryptoError::AeadDecryptionError)?;
+        Ok(plaintext.to_vec())
+    }
+
+    fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        match alg {
+            SignatureScheme::ED25519 => {
+                let mut seed = [0u8; 32];
+                self.rng
+                    .fill(&mut seed)
+                    .map_err(|_| CryptoError::InsufficientRandomness)?;
+                self.signature_key_import(alg, &seed)
+            }
+            _ => {
+                let algorithm = ecdsa_signing_algorithm(alg)?;
+                let pkcs8 = EcdsaKeyPair::generate_pkcs8(algorithm, &self.rng)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let key_pair = EcdsaKeyPair::from_pkcs8(algorithm, pkcs8.as_ref())
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                Ok((
+                    pkcs8.as_ref().to_vec(),
+                    key_pair.public_key().as_ref().to_vec(),
+                ))
+            }
+        }
+    }
+
+    fn signature_key_import(
+        &self,
+        alg: SignatureScheme,
+        private_key: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        match alg {
+            SignatureScheme::ED25519 => {
+                let key_pair = Ed25519KeyPair::from_seed_unchecked(private_key)
+                    .map_err(|_| CryptoError::InvalidLength)?;
+                Ok((
+                    private_key.to_vec(),
+                    key_pair.public_key().as_ref().to_vec(),
+                ))
+            }
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn verify_signature(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CryptoError> {
+        UnparsedPublicKey::new(verification_algorithm(alg)?, pk)
+            .verify(data, signature)
+            .map_err(|_| CryptoError::InvalidSignature)
+    }
+
+    fn sign(&self, alg: SignatureScheme, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match alg {
+            SignatureScheme::ED25519 => {
+                let key_pair = Ed25519KeyPair::from_seed_unchecked(key)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                Ok(key_pair.sign(data).as_ref().to_vec())
+            }
+            _ => {
+                let key_pair = EcdsaKeyPair::from_pkcs8(ecdsa_signing_algorithm(alg)?, key)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                key_pair
+                    .sign(&self.rng, data)
+                    .map(|signature| signature.as_ref().to_vec())
+                    .map_err(|_| CryptoError::CryptoLibraryError)
+            }
+        }
+    }
+
+    fn hpke_seal(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ptxt: &[u8],
+    ) -> HpkeCiphertext {
+        let (kem_output, ciphertext) = hpke_from_config(config)
+            .seal(&pk_r.into(), info, aad, ptxt, None, None, None)
+            .unwrap();
+        HpkeCiphertext {
+            kem_output: kem_output.into(),
+            ciphertext: ciphertext.into(),
+        }
+    }
+
+    fn hpke_open(
+        &self,
+        config: HpkeConfig,
+        input: &HpkeCiphertext,
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        hpke_from_config(config)
+            .open(
+                input.kem_output.as_slice(),
+                &sk_r.into(),
+                info,
+                aad,
+                input.ciphertext.as_slice(),
+                None,
+                None,
+                None,
+            )
+            .map_err(|_| CryptoError::HpkeDecryptionError)
+    }
+
+    fn hpke_setup_sender_and_export(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<(KemOutput, ExporterSecret), CryptoError> {
+        let (kem_output, context) = hpke_from_config(config)
+            .setup_sender(&pk_r.into(), info, None, None, None)
+            .map_err(|_| CryptoError::SenderSetupError)?;
+        let exported_secret = context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)?;
+        Ok((kem_output, exported_secret))
+    }
+
+    fn hpke_setup_receiver_and_export(
+        &self,
+        config: HpkeConfig,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<ExporterSecret, CryptoError> {
+        let context = hpke_from_config(config)
+            .setup_receiver(enc, &sk_r.into(), info, None, None, None)
+            .map_err(|_| CryptoError::ReceiverSetupError)?;
+        context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)
+    }
+
+    fn derive_hpke_keypair(&self, config: HpkeConfig, ikm: &[u8]) -> HpkeKeyPair {
+        let kp = hpke_from_config(config)
+            .derive_key_pair(ikm)
+            .unwrap()
+            .into_keys();
+        HpkeKeyPair {
+            private: kp.0.as_slice().into(),
+            public: kp.1.as_slice().into(),
+        }
+    }
+}
+
+impl OpenMlsRand for RingCrypto {
+    type Error = RandError;
+
+    fn random_array<const N: usize>(&self) -> Result<[u8; N], Self::Error> {
+        let mut out = [0u8; N];
+        self.rng
+            .fill(&mut out)
+            .map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+
+    fn random_vec(&self, len: usize) -> Result<Vec<u8>, Self::Error> {
+        let mut out = vec![0u8; len];
+        self.rng
+            .fill(&mut out)
+            .map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RandError {
+    #[error("Unable to collect enough randomness.")]
+    NotEnoughRandomness,
+}
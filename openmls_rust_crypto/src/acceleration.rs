@@ -0,0 +1,90 @@
+//! # Hardware acceleration for AEAD
+//!
+//! The [aes-gcm] and [chacha20poly1305] crates select their implementation
+//! at runtime:
+//!
+//! * On x86 and x86_64, AES-GCM uses AES-NI and CLMUL, and ChaCha20 uses AVX2
+//!   if the CPU supports them.
+//! * On aarch64, AES-GCM uses the ARMv8 cryptography extensions if the
+//!   `armv8` feature of this crate is enabled (this requires a nightly
+//!   compiler). Otherwise the portable implementation is used.
+//!
+//! [`AeadAcceleration::detect()`] reports which of these paths are available
+//! on the current CPU, such that applications can log it or pick a
+//! ciphersuite accordingly. The report is informational only: it doesn't
+//! change the implementation that is used by the [`RustCrypto`](crate::RustCrypto)
+//! provider, which is always selected by the crates themselves.
+//!
+//! [aes-gcm]: https://docs.rs/aes-gcm
+//! [chacha20poly1305]: https://docs.rs/chacha20poly1305
+
+/// The AEAD hardware acceleration available on the current CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AeadAcceleration {
+    /// AES-GCM uses hardware AES and carry-less multiplication instructions.
+    pub aes_gcm: bool,
+    /// ChaCha20-Poly1305 uses SIMD instructions.
+    pub chacha20_poly1305: bool,
+}
+
+impl AeadAcceleration {
+    /// Detect the AEAD hardware acceleration of the current CPU.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn detect() -> Self {
+        Self {
+            aes_gcm: std::is_x86_feature_detected!("aes")
+                && std::is_x86_feature_detected!("pclmulqdq"),
+            chacha20_poly1305: std::is_x86_feature_detected!("avx2"),
+        }
+    }
+
+    /// Detect the AEAD hardware acceleration of the current CPU.
+    #[cfg(target_arch = "aarch64")]
+    pub fn detect() -> Self {
+        Self {
+            aes_gcm: cfg!(feature = "armv8")
+                && std::arch::is_aarch64_feature_detected!("aes")
+                && std::arch::is_aarch64_feature_detected!("pmull"),
+            // The NEON backend of ChaCha20 is not enabled.
+            chacha20_poly1305: false,
+        }
+    }
+
+    /// Detect the AEAD hardware acceleration of the current CPU.
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    pub fn detect() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_reports_cpu_features() {
+        let acceleration = AeadAcceleration::detect();
+        assert_eq!(acceleration, AeadAcceleration::detect());
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            assert_eq!(
+                acceleration.aes_gcm,
+                std::is_x86_feature_detected!("aes") && std::is_x86_feature_detected!("pclmulqdq")
+            );
+            assert_eq!(
+                acceleration.chacha20_poly1305,
+                std::is_x86_feature_detected!("avx2")
+            );
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            assert!(cfg!(feature = "armv8") || !acceleration.aes_gcm);
+            assert!(!acceleration.chacha20_poly1305);
+        }
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        assert_eq!(acceleration, AeadAcceleration::default());
+    }
+}
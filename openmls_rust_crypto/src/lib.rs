@@ -6,7 +6,9 @@
 pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError};
 use openmls_traits::OpenMlsCryptoProvider;
 
+mod acceleration;
 mod provider;
+pub use acceleration::*;
 pub use provider::*;
 
 #[derive(Default, Debug)]
@@ -27,19 +27,31 @@ use p256::{
 use rand::{RngCore, SeedableRng};
 use sha2::{Digest, Sha256, Sha384, Sha512};
 
+use crate::AeadAcceleration;
+
 #[derive(Debug)]
 pub struct RustCrypto {
     rng: RwLock<rand_chacha::ChaCha20Rng>,
+    aead_acceleration: AeadAcceleration,
 }
 
 impl Default for RustCrypto {
     fn default() -> Self {
         Self {
             rng: RwLock::new(rand_chacha::ChaCha20Rng::from_entropy()),
+            aead_acceleration: AeadAcceleration::detect(),
         }
     }
 }
 
+impl RustCrypto {
+    /// Returns the AEAD hardware acceleration that is used by this provider.
+    /// See [`AeadAcceleration`] for details.
+    pub fn aead_acceleration(&self) -> AeadAcceleration {
+        self.aead_acceleration
+    }
+}
+
 #[inline(always)]
 fn kem_mode(kem: HpkeKemType) -> hpke_types::KemAlgorithm {
     match kem {
@@ -242,6 +254,31 @@ impl OpenMlsCrypto for RustCrypto {
         }
     }
 
+    fn signature_key_import(
+        &self,
+        alg: openmls_traits::types::SignatureScheme,
+        private_key: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), openmls_traits::types::CryptoError> {
+        match alg {
+            SignatureScheme::ECDSA_SECP256R1_SHA256 => {
+                let k =
+                    SigningKey::from_bytes(private_key).map_err(|_| CryptoError::InvalidLength)?;
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                Ok((k.to_bytes().as_slice().into(), pk))
+            }
+            SignatureScheme::ED25519 => {
+                let secret = ed25519_dalek::SecretKey::from_bytes(private_key)
+                    .map_err(|_| CryptoError::InvalidLength)?;
+                let public = ed25519_dalek::PublicKey::from(&secret);
+                let pk = public.to_bytes().to_vec();
+                // full key here because we need it to sign...
+                let sk_pk = ed25519_dalek::Keypair { secret, public }.to_bytes().into();
+                Ok((sk_pk, pk))
+            }
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
     fn verify_signature(
         &self,
         alg: openmls_traits::types::SignatureScheme,
@@ -0,0 +1,64 @@
+//! # OpenMLS Async Key Store Trait
+//!
+//! The [`AsyncOpenMlsKeyStore`] trait is the asynchronous counterpart of the
+//! [`OpenMlsKeyStore`](crate::key_store::OpenMlsKeyStore) trait. It is meant
+//! for key stores that are backed by remote services (e.g. a KMS) or by
+//! asynchronous platform APIs (e.g. IndexedDB in the browser).
+//!
+//! Values are passed in their serialized form, i.e. the bytes produced by
+//! [`ToKeyStoreValue`](crate::key_store::ToKeyStoreValue). OpenMLS takes care
+//! of the (de)serialization.
+
+use std::{fmt::Debug, future::Future, pin::Pin};
+
+/// A boxed future returned by the [`AsyncOpenMlsKeyStore`] functions.
+pub type KeyStoreFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The Async Key Store trait
+pub trait AsyncOpenMlsKeyStore: Send + Sync {
+    /// The error type returned by the [`AsyncOpenMlsKeyStore`].
+    type Error: std::error::Error + Debug + PartialEq + Send + Sync + 'static;
+
+    /// Load all serialized encryption keys associated with the given client
+    /// identity, epoch and group ID from the key store.
+    fn read_epoch_keys<'a>(
+        &'a self,
+        identity: &'a [u8],
+        group_id: &'a [u8],
+        epoch: u64,
+    ) -> KeyStoreFuture<'a, Vec<Vec<u8>>>;
+
+    /// Store all serialized encryption keys associated with the given client
+    /// identity, epoch and group ID in the key store.
+    fn store_epoch_keys<'a>(
+        &'a self,
+        identity: &'a [u8],
+        group_id: &'a [u8],
+        epoch: u64,
+        encryption_keys: Vec<Vec<u8>>,
+    ) -> KeyStoreFuture<'a, Result<(), Self::Error>>;
+
+    /// Delete all encryption keys associated with the given client identity,
+    /// epoch and group ID from the key store.
+    fn delete_epoch_keys<'a>(
+        &'a self,
+        identity: &'a [u8],
+        group_id: &'a [u8],
+        epoch: u64,
+    ) -> KeyStoreFuture<'a, Result<(), Self::Error>>;
+
+    /// Store the serialized value `v` for ID `k`.
+    ///
+    /// Returns an error if storing fails.
+    fn store<'a>(&'a self, k: &'a [u8], v: Vec<u8>) -> KeyStoreFuture<'a, Result<(), Self::Error>>;
+
+    /// Read and return the serialized value stored for ID `k`.
+    ///
+    /// Returns [`None`] if no value is stored for `k` or reading fails.
+    fn read<'a>(&'a self, k: &'a [u8]) -> KeyStoreFuture<'a, Option<Vec<u8>>>;
+
+    /// Delete a value stored for ID `k`.
+    ///
+    /// Returns an error if deleting fails.
+    fn delete<'a>(&'a self, k: &'a [u8]) -> KeyStoreFuture<'a, Result<(), Self::Error>>;
+}
@@ -1,6 +1,20 @@
 //! # The OpenMLS Crypto Trait
 //!
 //! This trait defines all cryptographic functions used by OpenMLS.
+//!
+//! ## Private keys and key handles
+//!
+//! OpenMLS never inspects private keys. It only passes the bytes returned by
+//! [`OpenMlsCrypto::signature_key_gen()`] and
+//! [`OpenMlsCrypto::derive_hpke_keypair()`] back into
+//! [`OpenMlsCrypto::sign()`], [`OpenMlsCrypto::hpke_open()`] and
+//! [`OpenMlsCrypto::hpke_setup_receiver_and_export()`], and it stores them in
+//! the key store. A provider that keeps private keys in a remote KMS can
+//! therefore return opaque key handles (e.g. key IDs) instead of raw private
+//! keys. The key store then only ever holds the handles.
+//!
+//! OpenMLS calls [`OpenMlsCrypto::release_private_key()`] when it no longer
+//! needs a private key, such that the KMS can delete it.
 
 use crate::types::{
     AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeCiphertext, HpkeConfig,
@@ -69,12 +83,31 @@ pub trait OpenMlsCrypto {
         aad: &[u8],
     ) -> Result<Vec<u8>, CryptoError>;
 
-    /// Generate a signature key.
+    /// Generate a signature key. The private key comes first and can be a key
+    /// handle.
     ///
     /// Returns an error if the [`SignatureScheme`] is not supported or the key
     /// generation fails.
     fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError>;
 
+    /// Import an existing signature private key, e.g. one provisioned by a
+    /// PKI. The `private_key` is the raw private key of the
+    /// [`SignatureScheme`], i.e. the 32 byte seed for Ed25519 and the 32 byte
+    /// scalar for ECDSA P-256. The private key comes first and can be a key
+    /// handle, the public key is returned in the same format as by
+    /// [`OpenMlsCrypto::signature_key_gen()`].
+    ///
+    /// The default implementation returns a
+    /// [`CryptoError::UnsupportedSignatureScheme`] error for all signature
+    /// schemes.
+    fn signature_key_import(
+        &self,
+        _alg: SignatureScheme,
+        _private_key: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        Err(CryptoError::UnsupportedSignatureScheme)
+    }
+
     /// Verify the signature
     ///
     /// Returns an error if the [`SignatureScheme`] is not supported or the
@@ -87,7 +120,8 @@ pub trait OpenMlsCrypto {
         signature: &[u8],
     ) -> Result<(), CryptoError>;
 
-    /// Sign with the given parameters.
+    /// Sign with the given parameters. The `key` is the private key (or key
+    /// handle) returned by [`OpenMlsCrypto::signature_key_gen()`].
     ///
     /// Returns an error if the [`SignatureScheme`] is not supported or an error
     /// occurs during signature generation.
@@ -142,5 +176,18 @@ pub trait OpenMlsCrypto {
     ) -> Result<ExporterSecret, CryptoError>;
 
     /// Derive a new HPKE keypair from a given input key material.
+    ///
+    /// The private key of the returned keypair can be a key handle, e.g. after
+    /// importing the derived key into a KMS.
     fn derive_hpke_keypair(&self, config: HpkeConfig, ikm: &[u8]) -> HpkeKeyPair;
+
+    /// Release a private key (or key handle) that is no longer used by
+    /// OpenMLS.
+    ///
+    /// The default implementation does nothing, since raw private keys are
+    /// simply dropped. Providers that hand out key handles can use this to
+    /// delete the key in their KMS.
+    fn release_private_key(&self, _private_key: &[u8]) -> Result<(), CryptoError> {
+        Ok(())
+    }
 }
@@ -12,6 +12,20 @@ pub trait ToKeyStoreValue {
     fn to_key_store_value(&self) -> Result<Vec<u8>, Self::Error>;
 }
 
+/// The ID of a value in an [`OpenMlsKeyStore`], see [`OpenMlsKeyStore::ids()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KeyStoreId {
+    /// A value stored with [`OpenMlsKeyStore::store()`] for this ID.
+    Value(Vec<u8>),
+    /// The encryption keys stored with [`OpenMlsKeyStore::store_epoch_keys()`]
+    /// for this client identity, group ID and epoch.
+    EpochKeys {
+        identity: Vec<u8>,
+        group_id: Vec<u8>,
+        epoch: u64,
+    },
+}
+
 /// The Key Store trait
 pub trait OpenMlsKeyStore: Send + Sync {
     /// The error type returned by the [`OpenMlsKeyStore`].
@@ -68,6 +82,63 @@ pub trait OpenMlsKeyStore: Send + Sync {
     ///
     /// Returns an error if storing fails.
     fn delete(&self, k: &[u8]) -> Result<(), Self::Error>;
+
+    /// Return the IDs of all values in the key store, such that the entries
+    /// can be enumerated, e.g. by backup tools.
+    ///
+    /// Enumeration is optional. By default, no IDs are returned. Key stores
+    /// that keep external PSKs in a dedicated storage don't have to return
+    /// their IDs.
+    fn ids(&self) -> Vec<KeyStoreId> {
+        Vec::new()
+    }
+
+    /// Store the secret `psk` of the external PSK with the given `psk_id`, such
+    /// that it can be injected into the key schedule when processing a commit
+    /// or joining a group from a Welcome message.
+    ///
+    /// By default, the PSK is stored with [`OpenMlsKeyStore::store()`] under
+    /// an ID derived from the `psk_id`. Key stores that keep PSKs in a
+    /// dedicated storage can override this function together with
+    /// [`OpenMlsKeyStore::read_psk()`] and [`OpenMlsKeyStore::delete_psk()`].
+    ///
+    /// Returns an error if storing fails.
+    fn store_psk(&self, psk_id: &[u8], psk: &[u8]) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+    {
+        self.store(&psk_store_id(psk_id), &psk.to_vec())
+    }
+
+    /// Read and return the secret of the external PSK with the given `psk_id`.
+    ///
+    /// Returns [`None`] if no PSK is stored for `psk_id` or reading fails.
+    fn read_psk(&self, psk_id: &[u8]) -> Option<Vec<u8>>
+    where
+        Self: Sized,
+    {
+        self.read(&psk_store_id(psk_id))
+    }
+
+    /// Delete the secret of the external PSK with the given `psk_id`.
+    ///
+    /// Returns an error if deleting fails.
+    fn delete_psk(&self, psk_id: &[u8]) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+    {
+        self.delete(&psk_store_id(psk_id))
+    }
+}
+
+/// The prefix of the IDs external PSKs are stored under by default, see
+/// [`OpenMlsKeyStore::store_psk()`].
+pub const EXTERNAL_PSK_ID_PREFIX: &[u8] = b"openmls external psk";
+
+/// The ID external PSKs are stored under by default, see
+/// [`OpenMlsKeyStore::store_psk()`].
+fn psk_store_id(psk_id: &[u8]) -> Vec<u8> {
+    [EXTERNAL_PSK_ID_PREFIX, psk_id].concat()
 }
 
 impl ToKeyStoreValue for Vec<u8> {
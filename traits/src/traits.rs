@@ -3,6 +3,7 @@
 //! This module defines a number of traits that are used by the public
 //! API of OpenMLS.
 
+pub mod async_key_store;
 pub mod crypto;
 pub mod key_store;
 pub mod random;
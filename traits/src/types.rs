@@ -520,4 +520,19 @@ impl Ciphersuite {
     pub const fn aead_nonce_length(&self) -> usize {
         self.aead_algorithm().nonce_size()
     }
+
+    /// Returns the security level of the ciphersuite in bits, i.e. 128 or
+    /// 256.
+    #[inline]
+    pub const fn security_level(&self) -> u16 {
+        match self {
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519
+            | Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256
+            | Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519 => 128,
+            Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448
+            | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+            | Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448
+            | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => 256,
+        }
+    }
 }